@@ -388,11 +388,12 @@ fn run<P: Preset>(
                 .filter(|block| block.message().slot() <= end_slot);
 
             for block in epoch_blocks {
-                block_verification_pool.push(block.clone_arc());
+                block_verification_pool.push(block.clone_arc(), None);
 
                 if mode == Mode::Synchronous {
                     let state = controller.head_state().value;
                     block_verification_pool.verify_and_process_blocks(&state);
+                    block_verification_pool.wait_for_verification();
                     controller.wait_for_tasks();
                 }
             }
@@ -413,6 +414,7 @@ fn run<P: Preset>(
                 }
             }
 
+            block_verification_pool.wait_for_verification();
             controller.wait_for_tasks();
         }
     } else {
@@ -2,6 +2,10 @@
 // See <https://github.com/rust-lang/rust/issues/57274>.
 #![allow(unused_crate_dependencies)]
 
+use core::{
+    hint::black_box,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use std::sync::Arc;
 
 use allocator as _;
@@ -19,6 +23,10 @@ use types::{
     traits::SignedBeaconBlock as _,
 };
 
+// Enough to keep the mutator busy publishing snapshots, but not so many that the query threads
+// compete with block processing for CPU time on typical machines.
+const QUERY_THREADS: usize = 4;
+
 // Criterion macros only add confusion.
 #[allow(clippy::too_many_lines)]
 fn main() -> Result<()> {
@@ -180,6 +188,21 @@ impl Criterion {
                     BatchSize::SmallInput,
                 );
             })
+            .bench_function("in their own slots with concurrent queries", |bencher| {
+                let expected_head_block_root = *expected_head_block_root;
+
+                bencher.iter_batched(
+                    controller_with_blocks,
+                    |(controller, _mutator_handle, blocks)| {
+                        process_blocks_in_their_slots_with_concurrent_queries(
+                            &controller,
+                            blocks,
+                            expected_head_block_root,
+                        );
+                    },
+                    BatchSize::SmallInput,
+                );
+            })
             .bench_function("in a future slot synchronously", |bencher| {
                 let expected_head_block_root = *expected_head_block_root;
 
@@ -273,6 +296,34 @@ fn process_blocks_in_their_slots<P: Preset>(
     assert_eq!(controller.head_block_root().value, expected_head_block_root);
 }
 
+// This simulates heavy HTTP API load. The results should be close to those for
+// `process_blocks_in_their_slots`. Queries operate on snapshots and should never delay processing.
+fn process_blocks_in_their_slots_with_concurrent_queries<P: Preset>(
+    controller: &BenchController<P>,
+    blocks: Vec<Arc<SignedBeaconBlock<P>>>,
+    expected_head_block_root: H256,
+) {
+    let done = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        for _ in 0..QUERY_THREADS {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    let snapshot = controller.snapshot();
+
+                    black_box(snapshot.head_state());
+                    black_box(snapshot.finalized_root());
+                    black_box(controller.head_block_root());
+                }
+            });
+        }
+
+        process_blocks_in_their_slots(controller, blocks, expected_head_block_root);
+
+        done.store(true, Ordering::Relaxed);
+    });
+}
+
 fn process_blocks_in_future_slot_synchronously<P: Preset>(
     controller: &BenchController<P>,
     blocks: Vec<Arc<SignedBeaconBlock<P>>>,
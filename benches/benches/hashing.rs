@@ -5,6 +5,7 @@
 use allocator as _;
 use bls::{PublicKeyBytes, SignatureBytes};
 use criterion::{Bencher, Criterion, Throughput};
+use hashing::Backend;
 use openssl::sha::Sha256 as OpenSslSha256;
 use sha2::{Digest as _, Sha256 as Sha2Sha256};
 use tap::Conv as _;
@@ -86,6 +87,29 @@ fn main() {
         .bench_function("sha2::Sha256", hash_a(sha2_hash_768))
         .bench_function("openssl::sha::sha256", hash_a(openssl_hash_768));
 
+    let mut group = criterion.benchmark_group("256 + 256 = 512 bits by backend");
+
+    group.throughput(Throughput::Elements(1));
+
+    // `sha2` falls back to scalar instructions on CPUs without the SHA extensions,
+    // so the `sha-ni` backend is only benchmarked on CPUs that have them.
+    for backend in Backend::ALL
+        .into_iter()
+        .filter(|backend| backend.is_supported())
+    {
+        group.bench_function(backend.name(), |bencher| {
+            bencher.iter(|| {
+                hashing::hash_256_256_with_backend(
+                    backend,
+                    core::hint::black_box(H256::default()),
+                    core::hint::black_box(H256::default()),
+                )
+            })
+        });
+    }
+
+    group.finish();
+
     criterion.final_summary();
 }
 
@@ -0,0 +1,326 @@
+use core::time::Duration;
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+    time::Instant,
+};
+
+use log::{Level, Log, Metadata, Record};
+use parking_lot::Mutex;
+use prometheus_metrics::Metrics;
+
+// Warnings tend to be repeated for as long as the condition causing them persists
+// (an unreachable execution engine, for example). Logging every occurrence buries other messages.
+const LOGGED_OCCURRENCES_PER_INTERVAL: u64 = 3;
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(60);
+
+static METRICS: OnceLock<Arc<Metrics>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct CallSite {
+    module_path: &'static str,
+    file: Option<&'static str>,
+    line: Option<u32>,
+    level: Level,
+}
+
+struct Occurrences {
+    interval_start: Instant,
+    first_message: String,
+    logged: u64,
+    left_out: u64,
+}
+
+/// Count warnings and errors in metrics. Only the first call has any effect.
+pub fn track_log_metrics(metrics: Arc<Metrics>) {
+    METRICS.set(metrics).ok();
+}
+
+/// A [`Log`] wrapper that collapses repeated warnings into periodic summaries.
+///
+/// Warnings are grouped by the location they were logged at and their level.
+/// Messages that only differ in their arguments (slots, roots, peer IDs) are counted together.
+/// Errors are never left out.
+pub struct DeduplicatingLogger<L> {
+    inner: L,
+    occurrences: Mutex<HashMap<CallSite, Occurrences>>,
+}
+
+impl<L: Log> Log for DeduplicatingLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.log_at(record, Instant::now());
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl<L: Log> DeduplicatingLogger<L> {
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            occurrences: Mutex::default(),
+        }
+    }
+
+    fn log_at(&self, record: &Record, now: Instant) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Summaries are written when any message is logged after the interval ends.
+        // The node logs messages every slot, so there is no need for a separate timer.
+        for (call_site, occurrences) in self.take_expired(now) {
+            self.log_summary(call_site, occurrences, now);
+        }
+
+        let Some(module_path) = record.module_path_static() else {
+            self.inner.log(record);
+            return;
+        };
+
+        if record.level() > Level::Warn {
+            self.inner.log(record);
+            return;
+        }
+
+        if let Some(metrics) = METRICS.get() {
+            let location = match record.line() {
+                Some(line) => format!("{module_path}:{line}"),
+                None => module_path.to_owned(),
+            };
+
+            metrics.register_log_warning(record.level().as_str(), &location);
+        }
+
+        if record.level() == Level::Error {
+            self.inner.log(record);
+            return;
+        }
+
+        let call_site = CallSite {
+            module_path,
+            file: record.file_static(),
+            line: record.line(),
+            level: record.level(),
+        };
+
+        let should_log = {
+            let mut occurrences_by_call_site = self.occurrences.lock();
+
+            // Only the first message in an interval is formatted. It is quoted in the summary.
+            let occurrences = occurrences_by_call_site
+                .entry(call_site)
+                .or_insert_with(|| Occurrences {
+                    interval_start: now,
+                    first_message: record.args().to_string(),
+                    logged: 0,
+                    left_out: 0,
+                });
+
+            if occurrences.logged < LOGGED_OCCURRENCES_PER_INTERVAL {
+                occurrences.logged += 1;
+                true
+            } else {
+                occurrences.left_out += 1;
+                false
+            }
+        };
+
+        if should_log {
+            self.inner.log(record);
+        }
+    }
+
+    fn take_expired(&self, now: Instant) -> Vec<(CallSite, Occurrences)> {
+        let mut occurrences_by_call_site = self.occurrences.lock();
+
+        let expired_call_sites = occurrences_by_call_site
+            .iter()
+            .filter(|(_, occurrences)| {
+                now.duration_since(occurrences.interval_start) >= SUMMARY_INTERVAL
+            })
+            .map(|(call_site, _)| *call_site)
+            .collect::<Vec<_>>();
+
+        let mut expired = expired_call_sites
+            .into_iter()
+            .filter_map(|call_site| {
+                let occurrences = occurrences_by_call_site.remove(&call_site)?;
+                (occurrences.left_out > 0).then_some((call_site, occurrences))
+            })
+            .collect::<Vec<_>>();
+
+        // Write summaries in a stable order regardless of `HashMap` iteration order.
+        expired
+            .sort_by_key(|(call_site, occurrences)| (occurrences.interval_start, call_site.line));
+
+        expired
+    }
+
+    fn log_summary(&self, call_site: CallSite, occurrences: Occurrences, now: Instant) {
+        let CallSite {
+            module_path,
+            file,
+            line,
+            level,
+        } = call_site;
+
+        let Occurrences {
+            interval_start,
+            first_message,
+            left_out,
+            ..
+        } = occurrences;
+
+        let elapsed = now.duration_since(interval_start).as_secs();
+
+        self.inner.log(
+            &Record::builder()
+                .args(format_args!(
+                    "{left_out} more occurrences of \"{first_message}\" and similar messages \
+                     were left out of logs in the last {elapsed}s",
+                ))
+                .level(level)
+                .target(module_path)
+                .module_path_static(Some(module_path))
+                .file_static(file)
+                .line(line)
+                .build(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Arguments;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingLogger {
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl Log for RecordingLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.messages.lock().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn repeated_warnings_are_left_out_until_interval_ends() {
+        let logger = DeduplicatingLogger::new(RecordingLogger::default());
+        let start = Instant::now();
+
+        for index in 0..5 {
+            logger.log_at(&record(Level::Warn, 1, format_args!("warning")), start);
+            logger.log_at(&record(Level::Info, 2, format_args!("info {index}")), start);
+        }
+
+        logger.log_at(
+            &record(Level::Info, 2, format_args!("info after interval")),
+            start + SUMMARY_INTERVAL,
+        );
+
+        logger.log_at(
+            &record(Level::Warn, 1, format_args!("warning")),
+            start + SUMMARY_INTERVAL,
+        );
+
+        assert_eq!(
+            *logger.inner.messages.lock(),
+            [
+                "warning",
+                "info 0",
+                "warning",
+                "info 1",
+                "warning",
+                "info 2",
+                "info 3",
+                "info 4",
+                "2 more occurrences of \"warning\" and similar messages \
+                 were left out of logs in the last 60s",
+                "info after interval",
+                "warning",
+            ],
+        );
+    }
+
+    #[test]
+    fn warnings_with_varying_arguments_are_grouped_by_call_site() {
+        let logger = DeduplicatingLogger::new(RecordingLogger::default());
+        let start = Instant::now();
+
+        for index in 0..5 {
+            logger.log_at(
+                &record(Level::Warn, 1, format_args!("warning {index}")),
+                start,
+            );
+            logger.log_at(
+                &record(Level::Warn, 2, format_args!("other warning {index}")),
+                start,
+            );
+        }
+
+        logger.log_at(
+            &record(Level::Warn, 1, format_args!("warning 5")),
+            start + SUMMARY_INTERVAL,
+        );
+
+        assert_eq!(
+            *logger.inner.messages.lock(),
+            [
+                "warning 0",
+                "other warning 0",
+                "warning 1",
+                "other warning 1",
+                "warning 2",
+                "other warning 2",
+                "2 more occurrences of \"warning 0\" and similar messages \
+                 were left out of logs in the last 60s",
+                "2 more occurrences of \"other warning 0\" and similar messages \
+                 were left out of logs in the last 60s",
+                "warning 5",
+            ],
+        );
+    }
+
+    #[test]
+    fn errors_are_never_left_out() {
+        let logger = DeduplicatingLogger::new(RecordingLogger::default());
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            logger.log_at(&record(Level::Error, 1, format_args!("error")), start);
+        }
+
+        logger.log_at(
+            &record(Level::Error, 1, format_args!("error")),
+            start + SUMMARY_INTERVAL,
+        );
+
+        assert_eq!(*logger.inner.messages.lock(), ["error"; 6]);
+    }
+
+    fn record(level: Level, line: u32, args: Arguments) -> Record {
+        Record::builder()
+            .args(args)
+            .level(level)
+            .target("tests")
+            .module_path_static(Some("tests"))
+            .line(Some(line))
+            .build()
+    }
+}
@@ -6,6 +6,12 @@ use env_logger::{Builder, Env, Target, WriteStyle};
 use log::LevelFilter;
 use rayon::ThreadPoolBuilder;
 
+pub use crate::deduplicating_logger::track_log_metrics;
+
+use crate::deduplicating_logger::DeduplicatingLogger;
+
+mod deduplicating_logger;
+
 pub fn initialize_logger(
     module_path: &str,
     always_write_style: bool,
@@ -70,7 +76,10 @@ pub fn initialize_logger(
         builder.parse_env(env);
     }
 
-    builder.try_init().map_err(Into::into)
+    let logger = builder.build();
+
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(DeduplicatingLogger::new(logger))).map_err(Into::into)
 }
 
 pub fn initialize_rayon() -> Result<()> {
@@ -101,9 +101,76 @@ impl CachedPublicKey {
         self.bytes
     }
 
+    #[inline]
+    #[must_use]
+    pub fn is_decompressed(&self) -> bool {
+        self.decompressed.get().is_some()
+    }
+
     #[inline]
     pub fn decompress(&self) -> Result<&PublicKey, Error> {
         self.decompressed
             .get_or_try_init(|| self.bytes.try_into().map(Box::new))
     }
+
+    /// Fills the cache from the output of [`PublicKey::to_uncompressed`].
+    ///
+    /// This is much faster than [`Self::decompress`] because it skips both the square root and the
+    /// subgroup check. The key is only accepted if it compresses to the same bytes as `self`.
+    /// Compression is injective, so the result is the same point [`Self::decompress`] would return.
+    /// The subgroup check may only be skipped for keys that are known to have passed it before,
+    /// such as ones in a validator registry.
+    pub fn decompress_from_uncompressed(&self, uncompressed: &[u8]) -> Result<&PublicKey, Error> {
+        self.decompressed.get_or_try_init(|| {
+            let public_key = PublicKey::from_uncompressed_unchecked(uncompressed)?;
+
+            if PublicKeyBytes::from(public_key) != self.bytes {
+                return Err(Error::UncompressedPublicKeyMismatch);
+            }
+
+            Ok(Box::new(public_key))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std_ext::CopyExt as _;
+    use tap::{Conv as _, TryConv as _};
+
+    use crate::{SecretKey, SecretKeyBytes};
+
+    use super::*;
+
+    #[test]
+    fn decompress_from_uncompressed_accepts_only_matching_keys() -> Result<(), Error> {
+        let public_key = public_key(b"????????????????????????????????");
+        let other_public_key = public_key(b"!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!");
+        let cached_public_key = CachedPublicKey::from(PublicKeyBytes::from(public_key));
+
+        assert!(matches!(
+            cached_public_key.decompress_from_uncompressed(&other_public_key.to_uncompressed()),
+            Err(Error::UncompressedPublicKeyMismatch),
+        ));
+
+        assert!(cached_public_key
+            .decompress_from_uncompressed(&[0; PublicKey::UNCOMPRESSED_SIZE])
+            .is_err());
+
+        assert_eq!(
+            cached_public_key.decompress_from_uncompressed(&public_key.to_uncompressed())?,
+            &public_key,
+        );
+
+        Ok(())
+    }
+
+    fn public_key(secret_key_bytes: &[u8; 32]) -> PublicKey {
+        secret_key_bytes
+            .copy()
+            .conv::<SecretKeyBytes>()
+            .try_conv::<SecretKey>()
+            .expect("bytes encode a valid secret key")
+            .to_public_key()
+    }
 }
@@ -9,6 +9,8 @@ pub enum Error {
     DecompressionFailed(BLST_ERROR),
     #[error("no public keys to aggregate")]
     NoPublicKeysToAggregate,
+    #[error("uncompressed public key does not match compressed one")]
+    UncompressedPublicKeyMismatch,
 }
 
 assert_eq_size!(Error, u32);
@@ -31,6 +31,8 @@ impl TryFrom<PublicKeyBytes> for PublicKey {
 }
 
 impl PublicKey {
+    pub const UNCOMPRESSED_SIZE: usize = 96;
+
     /// [`eth_aggregate_pubkeys`](https://github.com/ethereum/consensus-specs/blob/86fb82b221474cc89387fa6436806507b3849d88/specs/altair/bls.md#eth_aggregate_pubkeys)
     pub fn aggregate_nonempty(public_keys: impl IntoIterator<Item = Self>) -> Result<Self, Error> {
         public_keys
@@ -54,6 +56,23 @@ impl PublicKey {
         self.0 = self_aggregate.to_public_key();
     }
 
+    /// Serializes the public key without compression.
+    ///
+    /// Uncompressed keys take twice as much space but can be read back without computing a square
+    /// root. See [`CachedPublicKey::decompress_from_uncompressed`].
+    ///
+    /// [`CachedPublicKey::decompress_from_uncompressed`]: crate::CachedPublicKey::decompress_from_uncompressed
+    #[inline]
+    #[must_use]
+    pub fn to_uncompressed(&self) -> [u8; Self::UNCOMPRESSED_SIZE] {
+        self.as_raw().serialize()
+    }
+
+    // This does not perform the subgroup check done in `TryFrom<PublicKeyBytes>`.
+    pub(crate) fn from_uncompressed_unchecked(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(RawPublicKey::deserialize(bytes)?))
+    }
+
     pub(crate) const fn as_raw(&self) -> &RawPublicKey {
         &self.0
     }
@@ -1,14 +1,17 @@
 use core::time::Duration;
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc, time::Instant};
 
 use anyhow::{bail, ensure, Result};
 use bls::PublicKeyBytes;
-use derive_more::Constructor;
+use clock::Tick;
+use database::Database;
+use futures::future::{join_all, select_ok};
 use helper_functions::signing::SignForAllForks;
 use itertools::Itertools as _;
-use log::{debug, info};
+use log::{debug, info, warn};
+use parking_lot::Mutex;
 use prometheus_metrics::Metrics;
-use reqwest::{Client, Response, StatusCode, Url};
+use reqwest::{Client, Error as ReqwestError, Response, StatusCode, Url};
 use ssz::SszHash as _;
 use thiserror::Error;
 use typenum::Unsigned as _;
@@ -27,11 +30,26 @@ use types::{
 use crate::{
     combined::{ExecutionPayloadAndBlobsBundle, SignedBuilderBid},
     consts::BUILDER_PROPOSAL_DELAY_TOLERANCE,
+    relay_statistics::{HeaderOutcome, RelayReport, RelayScores},
     unphased::containers::SignedValidatorRegistrationV1,
     BuilderConfig,
 };
 
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(BUILDER_PROPOSAL_DELAY_TOLERANCE);
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(BUILDER_PROPOSAL_DELAY_TOLERANCE);
+
+// Bids are only needed until the corresponding blinded block is published.
+const BID_SOURCE_SLOTS_TO_KEEP: u64 = 2;
+
+// Some relays reject requests whose bodies are too long.
+// We have 50000 validators in Holesky. Their registrations add up to over 20 MiB.
+//
+// We originally set `MAX_VALIDATORS_PER_REGISTRATION` to 1000.
+// That didn't work because processing registrations for 1000 validators takes around 3 seconds,
+// which happens to be the default timeout for validator registration requests in `mev-boost`.
+const MAX_VALIDATORS_PER_REGISTRATION: usize = 500;
+
+const REGISTRATION_ATTEMPTS: usize = 3;
+const REGISTRATION_RETRY_DELAY: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Error)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
@@ -44,6 +62,8 @@ pub enum BuilderApiError {
     ConsecutiveMissingBlocks { missing_blocks: u64 },
     #[error("{missing_blocks} missing blocks in the last rolling epoch")]
     RollingEpochMissingBlocks { missing_blocks: u64 },
+    #[error("no builder relays configured")]
+    NoRelays,
     #[error(
         "execution payload root ({payload_root:?}) does not match header root ({header_root:?})"
     )]
@@ -58,14 +78,48 @@ pub enum BuilderApiError {
     VersionMismatch { computed: Phase, in_response: Phase },
 }
 
-#[derive(Constructor)]
 pub struct Api {
     config: BuilderConfig,
     client: Client,
     metrics: Option<Arc<Metrics>>,
+    relay_scores: RelayScores,
+    // Relays that returned the bids that were selected, keyed by execution payload block hash.
+    // Blinded blocks have to be sent to the relay that provided the header.
+    bid_sources: Mutex<BTreeMap<ExecutionBlockHash, (Slot, Url)>>,
 }
 
 impl Api {
+    pub fn new(
+        config: BuilderConfig,
+        client: Client,
+        metrics: Option<Arc<Metrics>>,
+        database: Database,
+    ) -> Result<Self> {
+        ensure!(
+            !config.builder_api_urls.is_empty(),
+            BuilderApiError::NoRelays
+        );
+
+        let relay_scores = RelayScores::load(&config.builder_api_urls, Arc::new(database))?;
+
+        let api = Self {
+            config,
+            client,
+            metrics,
+            relay_scores,
+            bid_sources: Mutex::default(),
+        };
+
+        api.track_relay_metrics();
+
+        Ok(api)
+    }
+
+    #[must_use]
+    pub fn relay_reports(&self) -> Vec<RelayReport> {
+        self.relay_scores.reports()
+    }
+
     pub fn can_use_builder_api<P: Preset>(
         &self,
         slot: Slot,
@@ -117,17 +171,66 @@ impl Api {
 
         debug!("registering validators: {validator_registrations:?}");
 
-        let url = self.url("/eth/v1/builder/validators")?;
-        let response = self
-            .client
-            .post(url)
-            .json(validator_registrations)
-            .send()
-            .await?;
+        // Registrations are signed once and submitted to all relays in parallel.
+        // Each relay makes progress and retries failed batches independently of the others.
+        let requests = self
+            .config
+            .builder_api_urls
+            .iter()
+            .map(|relay| self.register_validators_with_relay(relay, validator_registrations));
 
-        let response = handle_error(response).await?;
+        let results = join_all(requests).await;
+        let mut registered = false;
 
-        debug!("register_validators response: {response:?}");
+        for (relay, result) in self.config.builder_api_urls.iter().zip(results) {
+            match result {
+                Ok(()) => registered = true,
+                Err(error) => warn!("failed to register validators with relay {relay}: {error}"),
+            }
+        }
+
+        ensure!(registered, "failed to register validators with any relay");
+
+        Ok(())
+    }
+
+    async fn register_validators_with_relay(
+        &self,
+        relay: &Url,
+        validator_registrations: &[SignedValidatorRegistrationV1],
+    ) -> Result<()> {
+        let url = relay.join("/eth/v1/builder/validators")?;
+
+        // Do not submit batches to the same relay in parallel.
+        // Doing so causes all of them to be timed out.
+        for batch in validator_registrations.chunks(MAX_VALIDATORS_PER_REGISTRATION) {
+            let mut attempt = 1;
+
+            loop {
+                let result = async {
+                    let response = self.client.post(url.clone()).json(batch).send().await?;
+                    handle_error(response).await
+                }
+                .await;
+
+                match result {
+                    Ok(response) => {
+                        debug!("register_validators response from {relay}: {response:?}");
+                        break;
+                    }
+                    Err(error) if attempt < REGISTRATION_ATTEMPTS && is_retryable(&error) => {
+                        debug!(
+                            "retrying validator registration with relay {relay} \
+                             after failed attempt {attempt}: {error}",
+                        );
+
+                        attempt += 1;
+                        tokio::time::sleep(REGISTRATION_RETRY_DELAY).await;
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        }
 
         Ok(())
     }
@@ -145,9 +248,93 @@ impl Api {
                 .start_timer()
         });
 
-        let url = self.url(&format!(
-            "/eth/v1/builder/header/{slot}/{parent_hash:?}/{pubkey:?}"
-        ))?;
+        let path = &format!("/eth/v1/builder/header/{slot}/{parent_hash:?}/{pubkey:?}");
+
+        let requests = self.config.builder_api_urls.iter().map(|relay| async move {
+            let start = Instant::now();
+            let result = self
+                .get_execution_payload_header_from_relay::<P>(chain_config, relay, path, slot)
+                .await;
+
+            (relay, start.elapsed(), result)
+        });
+
+        let mut best_bid = None;
+        let mut last_error = None;
+        let mut failed_relay_count = 0;
+
+        for (relay, latency, result) in join_all(requests).await {
+            let outcome = match &result {
+                Ok(Some(_)) => HeaderOutcome::ValidBid,
+                Ok(None) => HeaderOutcome::NoBid,
+                Err(error) if is_request_failure(error) => HeaderOutcome::RequestFailed,
+                Err(_) => HeaderOutcome::InvalidBid,
+            };
+
+            let score = self.relay_scores.record_header(relay, outcome, latency);
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_builder_relay_header(relay.as_str(), outcome.as_str(), latency);
+                metrics.set_builder_relay_score(relay.as_str(), score);
+            }
+
+            match result {
+                Ok(Some(builder_bid)) => {
+                    let key = (builder_bid.mev(), score);
+
+                    // Prefer the highest bid. Break ties in favor of more reliable relays.
+                    if best_bid
+                        .as_ref()
+                        .map_or(true, |(best_key, _, _)| key > *best_key)
+                    {
+                        best_bid = Some((key, relay, builder_bid));
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    warn!("failed to get execution payload header from relay {relay}: {error}");
+                    last_error = Some(error);
+                    failed_relay_count += 1;
+                }
+            }
+        }
+
+        // Writing to the database blocks, so it must not delay the response to the proposer.
+        self.relay_scores.persist_in_background();
+
+        let Some((_, relay, builder_bid)) = best_bid else {
+            // A missing bid is only reported as such if at least one relay responded without one.
+            // Otherwise the failure would be indistinguishable from relays having no bids.
+            return match last_error {
+                Some(error) if failed_relay_count == self.config.builder_api_urls.len() => {
+                    Err(error)
+                }
+                _ => {
+                    info!("no relay has an execution payload header available for slot {slot}");
+                    Ok(None)
+                }
+            };
+        };
+
+        info!("selected execution payload header from relay {relay} for slot {slot}");
+
+        {
+            let mut bid_sources = self.bid_sources.lock();
+            bid_sources.retain(|_, (bid_slot, _)| *bid_slot + BID_SOURCE_SLOTS_TO_KEEP > slot);
+            bid_sources.insert(builder_bid.block_hash(), (slot, relay.clone()));
+        }
+
+        Ok(Some(builder_bid))
+    }
+
+    async fn get_execution_payload_header_from_relay<P: Preset>(
+        &self,
+        chain_config: &ChainConfig,
+        relay: &Url,
+        path: &str,
+        slot: Slot,
+    ) -> Result<Option<SignedBuilderBid<P>>> {
+        let url = relay.join(path)?;
 
         debug!("getting execution payload header from {url}");
 
@@ -155,13 +342,13 @@ impl Api {
         let response = handle_error(response).await?;
 
         if response.status() == StatusCode::NO_CONTENT {
-            info!("builder has no execution payload header available for slot {slot}");
+            info!("relay {relay} has no execution payload header available for slot {slot}");
             return Ok(None);
         }
 
         let builder_bid = response.json::<SignedBuilderBid<P>>().await?;
 
-        debug!("get_execution_payload_header response: {builder_bid:?}");
+        debug!("get_execution_payload_header response from {relay}: {builder_bid:?}");
 
         validate_phase(chain_config.phase_at_slot::<P>(slot), builder_bid.phase())?;
 
@@ -186,7 +373,7 @@ impl Api {
             }
         }
 
-        info!("received execution payload header from builder for slot {slot}");
+        info!("received execution payload header from relay {relay} for slot {slot}");
 
         Ok(Some(builder_bid))
     }
@@ -202,11 +389,62 @@ impl Api {
             .as_ref()
             .map(|metrics| metrics.builder_post_blinded_block_times.start_timer());
 
-        let url = self.url("/eth/v1/builder/blinded_blocks")?;
-
         let (next_interval, remaining_time) =
             clock::next_interval_with_remaining_time(chain_config, genesis_time)?;
 
+        let block_hash = block.execution_payload_header().block_hash();
+        let bid_source = self.bid_sources.lock().remove(&block_hash);
+
+        // If the header was not requested through this node, the relay that produced it is
+        // unknown. Post the block to all relays and use the first valid response.
+        // Relays that did not produce the header cannot deliver the payload,
+        // so failures are only recorded when the relay is known.
+        let (relays, relay_is_known) = match &bid_source {
+            Some((_, relay)) => (vec![relay], true),
+            None => (self.config.builder_api_urls.iter().collect(), false),
+        };
+
+        let requests = relays.into_iter().map(|relay| {
+            Box::pin(async move {
+                let result = self
+                    .post_blinded_block_to_relay(relay, block, next_interval, remaining_time)
+                    .await;
+
+                if relay_is_known {
+                    let score = self
+                        .relay_scores
+                        .record_payload_delivery(relay, result.is_ok());
+
+                    if let Some(metrics) = self.metrics.as_ref() {
+                        metrics.set_builder_relay_score(relay.as_str(), score);
+                    }
+                }
+
+                result.map_err(|error| {
+                    warn!("failed to get execution payload from relay {relay}: {error}");
+                    error
+                })
+            })
+        });
+
+        let result = select_ok(requests).await;
+
+        self.relay_scores.persist_in_background();
+
+        let (response, _) = result?;
+
+        Ok(response)
+    }
+
+    async fn post_blinded_block_to_relay<P: Preset>(
+        &self,
+        relay: &Url,
+        block: &SignedBlindedBeaconBlock<P>,
+        next_interval: Tick,
+        remaining_time: Duration,
+    ) -> Result<WithBlobsAndMev<ExecutionPayload<P>, P>> {
+        let url = relay.join("/eth/v1/builder/blinded_blocks")?;
+
         debug!(
             "posting blinded block to {url} with timeout of {remaining_time:?} \
              before next interval {next_interval:?}",
@@ -231,7 +469,7 @@ impl Api {
 
         let execution_payload = &response.value;
 
-        debug!("post_blinded_block response: {execution_payload:?}");
+        debug!("post_blinded_block response from {relay}: {execution_payload:?}");
 
         validate_phase(block.phase(), execution_payload.phase())?;
 
@@ -246,13 +484,20 @@ impl Api {
             },
         );
 
-        info!("received execution payload from builder for block {block_root:?} at slot {slot}");
+        info!(
+            "received execution payload from relay {relay} \
+             for block {block_root:?} at slot {slot}",
+        );
 
         Ok(response)
     }
 
-    fn url(&self, path: &str) -> Result<Url> {
-        self.config.builder_api_url.join(path).map_err(Into::into)
+    fn track_relay_metrics(&self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            for report in self.relay_scores.reports() {
+                metrics.set_builder_relay_score(&report.url, report.score);
+            }
+        }
     }
 }
 
@@ -270,6 +515,26 @@ async fn handle_error(response: Response) -> Result<Response> {
     Ok(response)
 }
 
+// Distinguishes relays that could not be reached from relays that returned invalid bids.
+fn is_request_failure(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<ReqwestError>().is_some() {
+        return true;
+    }
+
+    matches!(
+        error.downcast_ref(),
+        Some(BuilderApiError::BadRequest { .. } | BuilderApiError::BuilderNodeInternalError { .. }),
+    )
+}
+
+// Requests rejected by the relay would be rejected again.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    !matches!(
+        error.downcast_ref(),
+        Some(BuilderApiError::BadRequest { .. })
+    )
+}
+
 fn validate_phase(computed: Phase, in_response: Phase) -> Result<()> {
     ensure!(
         computed == in_response,
@@ -326,19 +591,50 @@ mod tests {
     ) -> Result<(), BuilderApiError> {
         let api = BuilderApi::new(
             BuilderConfig {
-                builder_api_url: Url::parse("http://localhost")
-                    .expect("http://localhost should be a valid URL"),
+                builder_api_urls: vec![
+                    Url::parse("http://localhost").expect("http://localhost should be a valid URL")
+                ],
                 builder_disable_checks: false,
                 builder_max_skipped_slots_per_epoch: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
                 builder_max_skipped_slots: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
             },
             Client::new(),
             None,
-        );
+            Database::in_memory(),
+        )
+        .expect("in-memory database should be readable");
 
         api.can_use_builder_api::<Mainnet>(slot, nonempty_slots)
     }
 
+    #[tokio::test]
+    async fn header_request_fails_if_every_relay_fails() {
+        let api = BuilderApi::new(
+            BuilderConfig {
+                builder_api_urls: ["http://127.0.0.1:1", "http://127.0.0.1:2"]
+                    .into_iter()
+                    .map(|url| Url::parse(url).expect("relay URLs should be valid"))
+                    .collect(),
+                builder_disable_checks: false,
+                builder_max_skipped_slots_per_epoch: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
+                builder_max_skipped_slots: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
+            },
+            Client::new(),
+            None,
+            Database::in_memory(),
+        )
+        .expect("in-memory database should be readable");
+
+        api.get_execution_payload_header::<Mainnet>(
+            &ChainConfig::mainnet(),
+            1,
+            ExecutionBlockHash::zero(),
+            PublicKeyBytes::default(),
+        )
+        .await
+        .expect_err("header request should fail if no relay can be reached");
+    }
+
     fn nonempty_slots_in_mainnet() -> impl Iterator<Item = Slot> {
         mainnet::BEACON_BLOCKS_UP_TO_SLOT_128
             .force()
@@ -7,7 +7,7 @@ use types::{
     combined::{ExecutionPayload, ExecutionPayloadHeader},
     deneb::primitives::KzgCommitment,
     nonstandard::{Phase, WithBlobsAndMev},
-    phase0::primitives::Uint256,
+    phase0::primitives::{ExecutionBlockHash, Uint256},
     preset::Preset,
 };
 
@@ -82,6 +82,15 @@ impl<P: Preset> SignedBuilderBid<P> {
         }
     }
 
+    #[must_use]
+    pub(crate) const fn block_hash(&self) -> ExecutionBlockHash {
+        match self {
+            Self::Bellatrix(response) => response.message.header.block_hash,
+            Self::Capella(response) => response.message.header.block_hash,
+            Self::Deneb(response) => response.message.header.block_hash,
+        }
+    }
+
     #[must_use]
     pub const fn mev(&self) -> Uint256 {
         match self {
@@ -4,6 +4,7 @@ pub use crate::{
         Config as BuilderConfig, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
         DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
     },
+    relay_statistics::{RelayReport, RelayStatistics},
 };
 
 pub mod combined;
@@ -27,4 +28,5 @@ mod deneb {
 
 mod api;
 mod config;
+mod relay_statistics;
 mod signing;
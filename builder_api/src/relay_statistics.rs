@@ -0,0 +1,417 @@
+use core::time::Duration;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::Builder,
+};
+
+use anyhow::Result;
+use database::Database;
+use derive_more::Display;
+use itertools::Itertools as _;
+use log::{error, warn};
+use parking_lot::Mutex;
+use reqwest::Url;
+use serde::Serialize;
+use ssz::{Ssz, SszReadDefault as _, SszWrite as _};
+
+use crate::api::REQUEST_TIMEOUT;
+
+// Scores are expressed in basis points to avoid floating point arithmetic.
+const MAX_SCORE: u64 = 10_000;
+
+// Weights (in percent) of the components of a relay score. They add up to 100.
+const PAYLOAD_DELIVERY_WEIGHT: u64 = 40;
+const BID_VALIDITY_WEIGHT: u64 = 30;
+const AVAILABILITY_WEIGHT: u64 = 20;
+const LATENCY_WEIGHT: u64 = 10;
+
+// Weight of the latest sample in the moving average of relay latencies.
+const LATENCY_SMOOTHING_DENOMINATOR: u64 = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub enum HeaderOutcome {
+    ValidBid,
+    InvalidBid,
+    NoBid,
+    RequestFailed,
+}
+
+impl HeaderOutcome {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ValidBid => "valid_bid",
+            Self::InvalidBid => "invalid_bid",
+            Self::NoBid => "no_bid",
+            Self::RequestFailed => "request_failed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct RelayStatistics {
+    pub header_requests: u64,
+    pub valid_bids: u64,
+    pub invalid_bids: u64,
+    pub failed_requests: u64,
+    pub payloads_delivered: u64,
+    pub payload_delivery_failures: u64,
+    pub average_latency_millis: u64,
+}
+
+impl RelayStatistics {
+    /// Computes a score in basis points. Higher scores mean more reliable relays.
+    ///
+    /// Ratios are smoothed so that relays with no history start with a neutral score.
+    #[must_use]
+    pub fn score(&self) -> u64 {
+        let payload_delivery = smoothed_ratio(
+            self.payloads_delivered,
+            self.payloads_delivered + self.payload_delivery_failures,
+        );
+
+        let bid_validity = smoothed_ratio(self.valid_bids, self.valid_bids + self.invalid_bids);
+
+        let availability = smoothed_ratio(
+            self.header_requests.saturating_sub(self.failed_requests),
+            self.header_requests,
+        );
+
+        let timeout_millis = u64::try_from(REQUEST_TIMEOUT.as_millis()).unwrap_or(u64::MAX);
+        let latency_millis = self.average_latency_millis.min(timeout_millis);
+        let latency = MAX_SCORE - latency_millis * MAX_SCORE / timeout_millis;
+
+        (PAYLOAD_DELIVERY_WEIGHT * payload_delivery
+            + BID_VALIDITY_WEIGHT * bid_validity
+            + AVAILABILITY_WEIGHT * availability
+            + LATENCY_WEIGHT * latency)
+            / 100
+    }
+
+    fn record_header(&mut self, outcome: HeaderOutcome, latency: Duration) {
+        self.header_requests += 1;
+
+        match outcome {
+            HeaderOutcome::ValidBid => self.valid_bids += 1,
+            HeaderOutcome::InvalidBid => self.invalid_bids += 1,
+            HeaderOutcome::NoBid => {}
+            HeaderOutcome::RequestFailed => self.failed_requests += 1,
+        }
+
+        let latency_millis = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+
+        self.average_latency_millis = if self.header_requests == 1 {
+            latency_millis
+        } else {
+            (self.average_latency_millis * (LATENCY_SMOOTHING_DENOMINATOR - 1) + latency_millis)
+                / LATENCY_SMOOTHING_DENOMINATOR
+        };
+    }
+
+    fn record_payload_delivery(&mut self, delivered: bool) {
+        if delivered {
+            self.payloads_delivered += 1;
+        } else {
+            self.payload_delivery_failures += 1;
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RelayReport {
+    pub url: String,
+    pub score: u64,
+    #[serde(flatten)]
+    pub statistics: RelayStatistics,
+}
+
+/// Per-relay statistics backed by a database so that scores survive restarts.
+///
+/// Statistics are updated in memory while requests to relays are in flight.
+/// Writing them to the database is left to [`RelayScores::persist_in_background`].
+pub struct RelayScores {
+    statistics: Mutex<BTreeMap<Url, RelayStatistics>>,
+    unsaved_relays: Mutex<BTreeSet<Url>>,
+    writer_tx: Sender<WriteMessage>,
+}
+
+enum WriteMessage {
+    Save(Vec<(Url, RelayStatistics)>),
+    Flush(Sender<()>),
+}
+
+impl RelayScores {
+    pub fn load(relays: &[Url], database: Arc<Database>) -> Result<Self> {
+        let statistics = relays
+            .iter()
+            .map(|relay| {
+                let statistics = database
+                    .get(RelayStatisticsByUrl(relay).to_string())?
+                    .map(RelayStatistics::from_ssz_default)
+                    .transpose()?
+                    .unwrap_or_default();
+
+                Ok((relay.clone(), statistics))
+            })
+            .collect::<Result<_>>()?;
+
+        let (writer_tx, writer_rx) = mpsc::channel();
+
+        Builder::new()
+            .name("relay-statistics-writer".to_owned())
+            .spawn(move || run_writer(&database, writer_rx))?;
+
+        Ok(Self {
+            statistics: Mutex::new(statistics),
+            unsaved_relays: Mutex::default(),
+            writer_tx,
+        })
+    }
+
+    #[must_use]
+    pub fn reports(&self) -> Vec<RelayReport> {
+        self.statistics
+            .lock()
+            .iter()
+            .map(|(url, statistics)| RelayReport {
+                url: url.to_string(),
+                score: statistics.score(),
+                statistics: *statistics,
+            })
+            .collect()
+    }
+
+    pub fn record_header(&self, relay: &Url, outcome: HeaderOutcome, latency: Duration) -> u64 {
+        self.update(relay, |statistics| {
+            statistics.record_header(outcome, latency);
+        })
+    }
+
+    pub fn record_payload_delivery(&self, relay: &Url, delivered: bool) -> u64 {
+        self.update(relay, |statistics| {
+            statistics.record_payload_delivery(delivered);
+        })
+    }
+
+    /// Queues statistics updated since the last call to be written by a dedicated thread.
+    ///
+    /// Writes are performed one at a time in the order they were queued,
+    /// so statistics from an earlier call can never overwrite newer ones.
+    pub fn persist_in_background(&self) {
+        // Keep the lock until the statistics are queued.
+        // Otherwise concurrent calls could queue their statistics out of order.
+        let mut unsaved_relays = self.unsaved_relays.lock();
+
+        if unsaved_relays.is_empty() {
+            return;
+        }
+
+        let unsaved_statistics = {
+            let all_statistics = self.statistics.lock();
+
+            core::mem::take(&mut *unsaved_relays)
+                .into_iter()
+                .filter_map(|relay| {
+                    let statistics = *all_statistics.get(&relay)?;
+                    Some((relay, statistics))
+                })
+                .collect_vec()
+        };
+
+        if self
+            .writer_tx
+            .send(WriteMessage::Save(unsaved_statistics))
+            .is_err()
+        {
+            error!("relay statistics not saved because the writer thread stopped");
+        }
+    }
+
+    /// Waits until all previously queued writes are completed.
+    pub fn flush(&self) {
+        let (reply_tx, reply_rx) = mpsc::channel();
+
+        if self.writer_tx.send(WriteMessage::Flush(reply_tx)).is_ok() {
+            reply_rx.recv().ok();
+        }
+    }
+
+    fn update(&self, relay: &Url, function: impl FnOnce(&mut RelayStatistics)) -> u64 {
+        let statistics = {
+            let mut all_statistics = self.statistics.lock();
+            let statistics = all_statistics.entry(relay.clone()).or_default();
+            function(statistics);
+            *statistics
+        };
+
+        self.unsaved_relays.lock().insert(relay.clone());
+
+        statistics.score()
+    }
+}
+
+// The thread exits once the `RelayScores` is dropped and all queued writes are completed.
+fn run_writer(database: &Database, rx: Receiver<WriteMessage>) {
+    for message in rx {
+        match message {
+            WriteMessage::Save(statistics) => {
+                if let Err(error) = save(database, &statistics) {
+                    warn!("failed to persist relay statistics: {error:?}");
+                }
+            }
+            WriteMessage::Flush(reply_tx) => {
+                reply_tx.send(()).ok();
+            }
+        }
+    }
+}
+
+fn save(database: &Database, statistics: &[(Url, RelayStatistics)]) -> Result<()> {
+    let pairs = statistics
+        .iter()
+        .map(|(relay, statistics)| {
+            Ok((
+                RelayStatisticsByUrl(relay).to_string(),
+                statistics.to_ssz()?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    database.put_batch(pairs)
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0}", Self::PREFIX)]
+struct RelayStatisticsByUrl<'url>(&'url Url);
+
+impl RelayStatisticsByUrl<'_> {
+    const PREFIX: &'static str = "r";
+}
+
+// Laplace smoothing makes `0 / 0` evaluate to `1 / 2`.
+const fn smoothed_ratio(successes: u64, total: u64) -> u64 {
+    (successes + 1) * MAX_SCORE / (total + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std_ext::ArcExt as _;
+
+    use super::*;
+
+    fn relay(url: &str) -> Url {
+        Url::parse(url).expect("URL in test should be valid")
+    }
+
+    fn load_statistics(database: &Database, relay: &Url) -> Result<RelayStatistics> {
+        let bytes = database
+            .get(RelayStatisticsByUrl(relay).to_string())?
+            .expect("statistics should be persisted");
+
+        RelayStatistics::from_ssz_default(bytes).map_err(Into::into)
+    }
+
+    #[test]
+    fn relays_without_history_have_neutral_scores() {
+        let statistics = RelayStatistics::default();
+
+        assert_eq!(statistics.score(), 5500);
+    }
+
+    #[test]
+    fn failed_payload_deliveries_lower_the_score() {
+        let mut reliable = RelayStatistics::default();
+        let mut unreliable = RelayStatistics::default();
+
+        for _ in 0..10 {
+            reliable.record_header(HeaderOutcome::ValidBid, Duration::from_millis(100));
+            reliable.record_payload_delivery(true);
+            unreliable.record_header(HeaderOutcome::ValidBid, Duration::from_millis(100));
+            unreliable.record_payload_delivery(false);
+        }
+
+        assert!(reliable.score() > unreliable.score());
+    }
+
+    #[test]
+    fn latency_is_a_moving_average() {
+        let mut statistics = RelayStatistics::default();
+
+        statistics.record_header(HeaderOutcome::NoBid, Duration::from_millis(800));
+        assert_eq!(statistics.average_latency_millis, 800);
+
+        statistics.record_header(HeaderOutcome::NoBid, Duration::from_millis(0));
+        assert_eq!(statistics.average_latency_millis, 700);
+    }
+
+    #[test]
+    fn statistics_are_persisted() -> Result<()> {
+        let database = Arc::new(Database::in_memory());
+        let relays = [relay("http://relay-a"), relay("http://relay-b")];
+        let scores = RelayScores::load(&relays, database.clone_arc())?;
+
+        scores.record_header(&relays[0], HeaderOutcome::InvalidBid, Duration::ZERO);
+        scores.record_payload_delivery(&relays[1], true);
+        scores.persist_in_background();
+        scores.flush();
+
+        let expected = scores.reports().into_iter().map(|report| report.statistics);
+        let actual = relays
+            .iter()
+            .map(|relay| load_statistics(&database, relay))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert!(expected.eq(actual));
+
+        Ok(())
+    }
+
+    #[test]
+    fn later_statistics_are_never_overwritten_by_earlier_ones() -> Result<()> {
+        let database = Arc::new(Database::in_memory());
+        let relays = [relay("http://relay-a")];
+        let scores = RelayScores::load(&relays, database.clone_arc())?;
+
+        for _ in 0..100 {
+            scores.record_payload_delivery(&relays[0], true);
+            scores.persist_in_background();
+        }
+
+        scores.flush();
+
+        assert_eq!(
+            load_statistics(&database, &relays[0])?.payloads_delivered,
+            100
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_are_not_written_while_recording() -> Result<()> {
+        let database = Arc::new(Database::in_memory());
+        let relays = [relay("http://relay-a")];
+        let scores = RelayScores::load(&relays, database.clone_arc())?;
+
+        scores.record_header(&relays[0], HeaderOutcome::ValidBid, Duration::ZERO);
+
+        assert_eq!(
+            database.get(RelayStatisticsByUrl(&relays[0]).to_string())?,
+            None,
+        );
+
+        scores.persist_in_background();
+        scores.flush();
+
+        assert!(database
+            .get(RelayStatisticsByUrl(&relays[0]).to_string())?
+            .is_some());
+
+        Ok(())
+    }
+}
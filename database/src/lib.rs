@@ -1,33 +1,207 @@
 // TODO(feature/in-memory-db): Minimize changes from `develop`.
 
+#[cfg(not(feature = "rocksdb"))]
+use core::convert::Infallible;
 use core::ops::{Range, RangeFrom, RangeToInclusive};
-use std::{borrow::Cow, path::Path, sync::Mutex};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use bytes::Bytes;
 use bytesize::ByteSize;
 use im::OrdMap;
 use itertools::Either;
-use libmdbx::{DatabaseFlags, Environment, Geometry, WriteFlags};
-use log::info;
+use libmdbx::{DatabaseFlags, Environment, EnvironmentFlags, Geometry, Mode, SyncMode, WriteFlags};
+use log::{info, warn};
+#[cfg(feature = "rocksdb")]
+use rocksdb::{
+    checkpoint::Checkpoint, DBCompressionType, Direction, IteratorMode, Options, WriteBatch,
+    WriteOptions, DB,
+};
 use snap::raw::{Decoder, Encoder};
+use strum::{Display, EnumString, IntoStaticStr};
 use tap::Pipe as _;
 use thiserror::Error;
 use unwrap_none::UnwrapNone as _;
 
+// RocksDB is only available with the `rocksdb` feature. Its C++ library adds a lot to build times.
+// `DB` is uninhabited without it, so `DatabaseKind::RocksDb` cannot be constructed.
+// The compiler checks that match arms for it are unreachable.
+#[cfg(not(feature = "rocksdb"))]
+type DB = Infallible;
+
 const GROWTH_STEP: ByteSize = ByteSize::mib(256);
 const MAX_NAMED_DATABASES: usize = 10;
+// Number of entries copied in each write transaction when backing up MDBX databases.
+const BACKUP_BATCH_SIZE: usize = 10_000;
+
+// Files that only exist in directories of the respective backends.
+const MDBX_DATA_FILE: &str = "mdbx.dat";
+const ROCKSDB_CURRENT_FILE: &str = "CURRENT";
+
+// Every Zstandard frame starts with this magic number. A raw Snappy block never does, because the
+// first element after its length prefix must be a literal and `0xb5` is the tag of a copy.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+// Higher levels barely improve the ratio for states but make writes considerably slower.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Storage engine used by persistent databases.
+///
+/// RocksDB compacts large databases more gracefully, which matters for archival nodes.
+/// It is only available with the `rocksdb` feature.
+/// Databases are not converted between backends.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Display, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum DatabaseBackend {
+    #[default]
+    Mdbx,
+    RocksDb,
+}
+
+impl DatabaseBackend {
+    /// The durability level used when none is configured.
+    ///
+    /// RocksDB databases were written without syncing before durability became configurable.
+    /// Syncing every write would slow them down considerably.
+    #[must_use]
+    pub const fn default_durability(self) -> DurabilityLevel {
+        match self {
+            Self::Mdbx => DurabilityLevel::Always,
+            Self::RocksDb => DurabilityLevel::Async,
+        }
+    }
+
+    fn detect(directory: &Path) -> Option<Self> {
+        if directory.join(MDBX_DATA_FILE).exists() {
+            Some(Self::Mdbx)
+        } else if directory.join(ROCKSDB_CURRENT_FILE).exists() {
+            Some(Self::RocksDb)
+        } else {
+            None
+        }
+    }
+}
 
-pub struct Database(DatabaseKind);
+/// Compression applied to values before they are written.
+///
+/// Values are decoded based on their contents, so the codec can be changed at any time.
+/// Values written with either codec remain readable.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Display, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum Codec {
+    /// Raw Snappy. Fast, but leaves much of the redundancy in SSZ-encoded states in place.
+    #[default]
+    Snappy,
+    /// Compresses states several times better at the cost of more CPU time per write.
+    Zstd,
+}
+
+impl Codec {
+    fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Snappy => Encoder::new().compress_vec(data).map_err(Into::into),
+            Self::Zstd => zstd::bulk::compress(data, ZSTD_LEVEL).map_err(Into::into),
+        }
+    }
+}
+
+/// How long writes may stay in memory before they are flushed to disk.
+///
+/// Weaker levels make writes faster at the cost of losing the most recent ones if the system
+/// crashes or loses power. Databases stay consistent at every level.
+/// Writes that must survive crashes can request a stronger level with
+/// [`Database::put_batch_with_durability`].
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug, Display, EnumString, IntoStaticStr,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DurabilityLevel {
+    /// Leave flushing to the operating system.
+    Async,
+    /// Flush data on every write but let MDBX flush metadata lazily.
+    /// A crash may roll back the last write. Same as `Async` for RocksDB.
+    EveryBatch,
+    /// Flush every write to disk before it returns.
+    #[default]
+    Always,
+}
+
+impl DurabilityLevel {
+    const fn mdbx_sync_mode(self) -> SyncMode {
+        match self {
+            Self::Async => SyncMode::SafeNoSync,
+            Self::EveryBatch => SyncMode::NoMetaSync,
+            Self::Always => SyncMode::Durable,
+        }
+    }
+}
+
+pub struct Database {
+    kind: DatabaseKind,
+    durability: DurabilityLevel,
+    codec: Codec,
+}
 
 impl Database {
     pub fn persistent(name: &str, directory: impl AsRef<Path>, size: ByteSize) -> Result<Self> {
+        Self::persistent_with_backend(
+            DatabaseBackend::Mdbx,
+            DurabilityLevel::default(),
+            name,
+            directory,
+            size,
+        )
+    }
+
+    /// Opens or creates a database in `directory` using `backend`.
+    /// Writes are flushed to disk as often as `durability` requires.
+    ///
+    /// `size` only limits MDBX databases. RocksDB has no upper limit.
+    pub fn persistent_with_backend(
+        backend: DatabaseBackend,
+        durability: DurabilityLevel,
+        name: &str,
+        directory: impl AsRef<Path>,
+        size: ByteSize,
+    ) -> Result<Self> {
+        let directory = directory.as_ref();
+
+        recover_interrupted_compaction(directory)?;
+
+        if let Some(existing) = DatabaseBackend::detect(directory) {
+            ensure!(
+                existing == backend,
+                Error::BackendMismatch {
+                    directory: directory.to_owned(),
+                    existing,
+                    requested: backend,
+                },
+            );
+        }
+
+        match backend {
+            DatabaseBackend::Mdbx => Self::open_mdbx(name, directory, size, durability, false),
+            DatabaseBackend::RocksDb => Self::open_rocksdb(directory, durability),
+        }
+    }
+
+    fn open_mdbx(
+        name: &str,
+        directory: &Path,
+        size: ByteSize,
+        durability: DurabilityLevel,
+        exclusive: bool,
+    ) -> Result<Self> {
         // If a database with the legacy name exists, keep using it.
         // Otherwise, create a new database with the specified name.
         // This check will not force existing users to resync.
-        let legacy_name = directory.as_ref().to_str().ok_or(Error)?;
+        let legacy_name = directory.to_str().ok_or(Error::NonUnicodePath)?;
 
-        fs_err::create_dir_all(&directory)?;
+        fs_err::create_dir_all(directory)?;
 
         // TODO(Grandine Team): The call to `set_max_dbs` and `MAX_NAMED_DATABASES` should be
         //                      unnecessary if the default database is used.
@@ -39,7 +213,14 @@ impl Database {
                 shrink_threshold: None,
                 page_size: None,
             })
-            .open_with_permissions(directory.as_ref(), 0o600)?;
+            .set_flags(EnvironmentFlags {
+                exclusive,
+                mode: Mode::ReadWrite {
+                    sync_mode: durability.mdbx_sync_mode(),
+                },
+                ..EnvironmentFlags::default()
+            })
+            .open_with_permissions(directory, 0o600)?;
 
         let transaction = environment.begin_rw_txn()?;
         let existing_db = transaction.open_db(Some(legacy_name));
@@ -56,17 +237,127 @@ impl Database {
 
         transaction.commit()?;
 
-        Ok(Self(DatabaseKind::Persistent {
-            database_name,
-            environment,
-        }))
+        Ok(Self {
+            kind: DatabaseKind::Persistent {
+                database_name,
+                environment,
+            },
+            durability,
+            codec: Codec::default(),
+        })
+    }
+
+    #[cfg(feature = "rocksdb")]
+    fn open_rocksdb(directory: &Path, durability: DurabilityLevel) -> Result<Self> {
+        fs_err::create_dir_all(directory)?;
+
+        let mut options = Options::default();
+
+        options.create_if_missing(true);
+
+        // Values are already compressed by `Database` itself.
+        options.set_compression_type(DBCompressionType::None);
+
+        let database = DB::open(&options, directory)?;
+
+        info!("RocksDB database: {directory:?}");
+
+        Ok(Self {
+            kind: DatabaseKind::RocksDb { database },
+            durability,
+            codec: Codec::default(),
+        })
+    }
+
+    #[cfg(not(feature = "rocksdb"))]
+    fn open_rocksdb(_directory: &Path, _durability: DurabilityLevel) -> Result<Self> {
+        bail!(Error::RocksDbDisabled)
+    }
+
+    /// Opens an existing database in `directory` without allowing any modifications.
+    ///
+    /// The backend is detected from the files in `directory`.
+    pub fn persistent_read_only(name: &str, directory: impl AsRef<Path>) -> Result<Self> {
+        let directory = directory.as_ref();
+
+        let backend = DatabaseBackend::detect(directory).ok_or_else(|| Error::NotFound {
+            directory: directory.to_owned(),
+        })?;
+
+        match backend {
+            DatabaseBackend::Mdbx => Self::open_mdbx_read_only(name, directory),
+            DatabaseBackend::RocksDb => Self::open_rocksdb_read_only(directory),
+        }
+    }
+
+    fn open_mdbx_read_only(name: &str, directory: &Path) -> Result<Self> {
+        let legacy_name = directory.to_str().ok_or(Error::NonUnicodePath)?;
+
+        let environment = Environment::builder()
+            .set_max_dbs(MAX_NAMED_DATABASES)
+            .set_flags(EnvironmentFlags {
+                mode: Mode::ReadOnly,
+                ..EnvironmentFlags::default()
+            })
+            .open(directory)?;
+
+        let transaction = environment.begin_ro_txn()?;
+
+        let database_name = if transaction.open_db(Some(legacy_name)).is_ok() {
+            legacy_name
+        } else {
+            transaction.open_db(Some(name))?;
+            name
+        }
+        .to_owned();
+
+        drop(transaction);
+
+        info!("database (read-only): {directory:?} with name {database_name}");
+
+        Ok(Self {
+            kind: DatabaseKind::Persistent {
+                database_name,
+                environment,
+            },
+            durability: DurabilityLevel::default(),
+            codec: Codec::default(),
+        })
+    }
+
+    #[cfg(feature = "rocksdb")]
+    fn open_rocksdb_read_only(directory: &Path) -> Result<Self> {
+        let database = DB::open_for_read_only(&Options::default(), directory, false)?;
+
+        info!("RocksDB database (read-only): {directory:?}");
+
+        Ok(Self {
+            kind: DatabaseKind::RocksDb { database },
+            durability: DurabilityLevel::default(),
+            codec: Codec::default(),
+        })
+    }
+
+    #[cfg(not(feature = "rocksdb"))]
+    fn open_rocksdb_read_only(_directory: &Path) -> Result<Self> {
+        bail!(Error::RocksDbDisabled)
     }
 
     #[must_use]
     pub fn in_memory() -> Self {
-        Self(DatabaseKind::InMemory {
-            map: Mutex::default(),
-        })
+        Self {
+            kind: DatabaseKind::InMemory {
+                map: Mutex::default(),
+            },
+            durability: DurabilityLevel::default(),
+            codec: Codec::default(),
+        }
+    }
+
+    /// Makes values be compressed with `codec` before they are written.
+    #[must_use]
+    pub const fn with_codec(self, codec: Codec) -> Self {
+        Self { codec, ..self }
     }
 
     pub fn delete(&self, key: impl AsRef<[u8]>) -> Result<()> {
@@ -90,6 +381,10 @@ impl Database {
                     .expect("in-memory database mutex is poisoned")
                     .remove(key.as_ref());
             }
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database.delete(key.as_ref())?,
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
 
         Ok(())
@@ -144,6 +439,14 @@ impl Database {
 
                 *map = new_map;
             }
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => {
+                let mut batch = WriteBatch::default();
+                batch.delete_range(start, end);
+                database.write(batch)?;
+            }
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
 
         Ok(())
@@ -165,6 +468,10 @@ impl Database {
                 .lock()
                 .expect("in-memory database mutex is poisoned")
                 .contains_key(key.as_ref()),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database.get_pinned(key.as_ref())?.is_some(),
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         };
 
         Ok(contains_key)
@@ -188,6 +495,12 @@ impl Database {
                 .expect("in-memory database mutex is poisoned")
                 .get(key.as_ref())
                 .map(|compressed| decompress(compressed)),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .get_pinned(key.as_ref())?
+                .map(|compressed| decompress(&compressed)),
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
         .transpose()
     }
@@ -231,8 +544,74 @@ impl Database {
                 above
                     .into_iter()
                     .map(|(key, value)| Ok((Cow::Owned(key.to_vec()), decompress(value.as_ref())?)))
+                    .pipe(Either::Left)
                     .pipe(Either::Right)
             }
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .iterator(IteratorMode::From(start, Direction::Forward))
+                .map(decompress_rocksdb_pair)
+                .pipe(Either::Right)
+                .pipe(Either::Right),
+            // `Database::open_rocksdb` fails without the `rocksdb` feature.
+            // The empty iterator only exists to fill in the type of `Either`.
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { .. } => {
+                core::iter::empty().pipe(Either::Right).pipe(Either::Right)
+            }
+        }
+        .pipe(Ok)
+    }
+
+    /// Iterates over all entries without decompressing them.
+    ///
+    /// Yields keys along with the number of bytes their values take up in storage.
+    pub fn stored_sizes(&self) -> Result<impl Iterator<Item = Result<(Cow<[u8]>, usize)>>> {
+        match self.kind() {
+            DatabaseKind::Persistent {
+                database_name,
+                environment,
+            } => {
+                let transaction = environment.begin_ro_txn()?;
+                let database = transaction.open_db(Some(database_name))?;
+
+                let mut cursor = transaction.cursor(&database)?;
+
+                let mut iterator = cursor
+                    .first()
+                    .transpose()
+                    .into_iter()
+                    .chain(core::iter::from_fn(move || cursor.next().transpose()))
+                    .map(|result| {
+                        let (key, value): (Cow<[u8]>, Cow<[u8]>) = result?;
+                        Ok((key, value.len()))
+                    });
+
+                Either::Left(core::iter::from_fn(move || iterator.next()))
+            }
+            DatabaseKind::InMemory { map } => map
+                .lock()
+                .expect("in-memory database mutex is poisoned")
+                .clone()
+                .into_iter()
+                .map(|(key, value)| Ok((Cow::Owned(key.to_vec()), value.len())))
+                .pipe(Either::Left)
+                .pipe(Either::Right),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .iterator(IteratorMode::Start)
+                .map(|result| {
+                    let (key, value) = result?;
+                    Ok((Cow::Owned(key.into_vec()), value.len()))
+                })
+                .pipe(Either::Right)
+                .pipe(Either::Right),
+            // `Database::open_rocksdb` fails without the `rocksdb` feature.
+            // The empty iterator only exists to fill in the type of `Either`.
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { .. } => {
+                core::iter::empty().pipe(Either::Right).pipe(Either::Right)
+            }
         }
         .pipe(Ok)
     }
@@ -277,8 +656,21 @@ impl Database {
                     .into_iter()
                     .rev()
                     .map(|(key, value)| Ok((Cow::Owned(key.to_vec()), decompress(value.as_ref())?)))
+                    .pipe(Either::Left)
                     .pipe(Either::Right)
             }
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .iterator(IteratorMode::From(end, Direction::Reverse))
+                .map(decompress_rocksdb_pair)
+                .pipe(Either::Right)
+                .pipe(Either::Right),
+            // `Database::open_rocksdb` fails without the `rocksdb` feature.
+            // The empty iterator only exists to fill in the type of `Either`.
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { .. } => {
+                core::iter::empty().pipe(Either::Right).pipe(Either::Right)
+            }
         }
         .pipe(Ok)
     }
@@ -290,6 +682,52 @@ impl Database {
     pub fn put_batch(
         &self,
         pairs: impl IntoIterator<Item = (impl AsRef<[u8]>, impl AsRef<[u8]>)>,
+    ) -> Result<()> {
+        self.put_batch_with_durability(pairs, self.durability)
+    }
+
+    /// Writes `pairs` in a single transaction that is flushed to disk at least as reliably as
+    /// `durability` requires, even if the database was opened with a weaker level.
+    pub fn put_batch_with_durability(
+        &self,
+        pairs: impl IntoIterator<Item = (impl AsRef<[u8]>, impl AsRef<[u8]>)>,
+        durability: DurabilityLevel,
+    ) -> Result<()> {
+        self.write_batch_with_durability(pairs, core::iter::empty::<&[u8]>(), durability)
+    }
+
+    /// Like [`Database::put_batch`], but also deletes `deleted_keys` in the same transaction.
+    /// Keys are deleted after `pairs` are written.
+    pub fn write_batch(
+        &self,
+        pairs: impl IntoIterator<Item = (impl AsRef<[u8]>, impl AsRef<[u8]>)>,
+        deleted_keys: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    ) -> Result<()> {
+        self.write_batch_with_durability(pairs, deleted_keys, self.durability)
+    }
+
+    /// Like [`Database::put_batch_with_durability`], but also deletes `deleted_keys` in the same
+    /// transaction. Keys are deleted after `pairs` are written.
+    pub fn write_batch_with_durability(
+        &self,
+        pairs: impl IntoIterator<Item = (impl AsRef<[u8]>, impl AsRef<[u8]>)>,
+        deleted_keys: impl IntoIterator<Item = impl AsRef<[u8]>>,
+        durability: DurabilityLevel,
+    ) -> Result<()> {
+        self.write_compressed_batch(
+            pairs
+                .into_iter()
+                .map(|(key, value)| Ok((key, self.codec.compress(value.as_ref())?))),
+            deleted_keys,
+            durability,
+        )
+    }
+
+    fn write_compressed_batch(
+        &self,
+        pairs: impl IntoIterator<Item = Result<(impl AsRef<[u8]>, impl AsRef<[u8]>)>>,
+        deleted_keys: impl IntoIterator<Item = impl AsRef<[u8]>>,
+        durability: DurabilityLevel,
     ) -> Result<()> {
         match self.kind() {
             DatabaseKind::Persistent {
@@ -299,31 +737,241 @@ impl Database {
                 let transaction = environment.begin_rw_txn()?;
                 let database = transaction.open_db(Some(database_name))?;
 
-                for (key, value) in pairs {
-                    let key = key.as_ref();
-                    let compressed = compress(value.as_ref())?;
-                    transaction.put(database.dbi(), key, compressed, WriteFlags::default())?;
+                for result in pairs {
+                    let (key, compressed) = result?;
+
+                    transaction.put(
+                        database.dbi(),
+                        key.as_ref(),
+                        compressed.as_ref(),
+                        WriteFlags::default(),
+                    )?;
+                }
+
+                for key in deleted_keys {
+                    transaction.del(database.dbi(), key.as_ref(), None)?;
                 }
 
                 transaction.commit()?;
+
+                // The sync mode of an MDBX environment cannot be changed per transaction.
+                if durability > self.durability {
+                    environment.sync(true)?;
+                }
             }
             DatabaseKind::InMemory { map } => {
                 let mut map = map.lock().expect("in-memory database mutex is poisoned");
                 let mut new_map = map.clone();
 
-                for (key, value) in pairs {
+                for result in pairs {
+                    let (key, compressed) = result?;
                     let key = Bytes::copy_from_slice(key.as_ref());
-                    let compressed = compress(value.as_ref())?.into();
+                    let compressed = Bytes::copy_from_slice(compressed.as_ref());
                     new_map.insert(key, compressed);
                 }
 
+                for key in deleted_keys {
+                    new_map.remove(key.as_ref());
+                }
+
                 *map = new_map;
             }
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => {
+                let mut batch = WriteBatch::default();
+
+                for result in pairs {
+                    let (key, compressed) = result?;
+                    batch.put(key.as_ref(), compressed.as_ref());
+                }
+
+                for key in deleted_keys {
+                    batch.delete(key.as_ref());
+                }
+
+                let mut write_options = WriteOptions::default();
+                write_options.set_sync(durability.max(self.durability) == DurabilityLevel::Always);
+
+                database.write_opt(batch, &write_options)?;
+            }
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
+        }
+
+        Ok(())
+    }
+
+    /// Copies the database to a new database in `directory`.
+    ///
+    /// The backup is a consistent snapshot of the database at the time `on_snapshot` is called.
+    /// Callers can let writes resume from it. Later writes do not end up in the backup.
+    /// Values are copied without decompressing them. `size` only limits MDBX backups.
+    pub fn backup_to(
+        &self,
+        directory: impl AsRef<Path>,
+        size: ByteSize,
+        on_snapshot: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let directory = directory.as_ref();
+
+        ensure!(
+            DatabaseBackend::detect(directory).is_none(),
+            Error::BackupTargetNotEmpty {
+                directory: directory.to_owned(),
+            },
+        );
+
+        match self.kind() {
+            DatabaseKind::Persistent {
+                database_name,
+                environment,
+            } => {
+                // The backup uses the same name so that it can replace the original directory.
+                let backup = Self::open_mdbx(
+                    database_name,
+                    directory,
+                    size,
+                    DurabilityLevel::Always,
+                    false,
+                )?;
+
+                // The whole copy is read in a single transaction, which sees the database as it
+                // was when the transaction started regardless of later writes. MDBX cannot reuse
+                // pages freed after that, so the database may grow for as long as the copy takes.
+                let transaction = environment.begin_ro_txn()?;
+                let database = transaction.open_db(Some(database_name))?;
+
+                on_snapshot()?;
+
+                let mut cursor = transaction.cursor(&database)?;
+
+                let mut pairs = cursor
+                    .first()
+                    .transpose()
+                    .into_iter()
+                    .chain(core::iter::from_fn(move || cursor.next().transpose()));
+
+                loop {
+                    let chunk = pairs
+                        .by_ref()
+                        .take(BACKUP_BATCH_SIZE)
+                        .collect::<Result<Vec<(Cow<[u8]>, Cow<[u8]>)>, _>>()?;
+
+                    if chunk.is_empty() {
+                        break;
+                    }
+
+                    backup.write_compressed_batch(
+                        chunk.into_iter().map(Ok),
+                        core::iter::empty::<&[u8]>(),
+                        backup.durability,
+                    )?;
+                }
+            }
+            DatabaseKind::InMemory { .. } => bail!(Error::InMemoryBackup),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => {
+                // RocksDB fails to create a checkpoint in a directory that already exists,
+                // even an empty one. Empty directories are accepted like they are for MDBX.
+                if directory.is_dir() && fs_err::read_dir(directory)?.next().is_none() {
+                    fs_err::remove_dir(directory)?;
+                }
+
+                // Checkpoints are consistent and mostly consist of hard links to existing files.
+                Checkpoint::new(database)?.create_checkpoint(directory)?;
+
+                on_snapshot()?;
+            }
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
 
+        info!("database backed up to {directory:?}");
+
         Ok(())
     }
 
+    /// Compacts the database while it is open and returns the number of bytes reclaimed on disk.
+    ///
+    /// MDBX databases can only be compacted with [`Database::compact_offline`].
+    pub fn compact(&self) -> Result<u64> {
+        match self.kind() {
+            DatabaseKind::Persistent { .. } => bail!(Error::OnlineCompactionUnsupported),
+            DatabaseKind::InMemory { .. } => Ok(0),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => {
+                let size_before = directory_size(database.path())?;
+
+                database.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+                let size_after = directory_size(database.path())?;
+
+                info!("RocksDB database compacted: {:?}", database.path());
+
+                Ok(size_before.saturating_sub(size_after))
+            }
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
+        }
+    }
+
+    #[must_use]
+    pub const fn supports_online_compaction(&self) -> bool {
+        !matches!(self.kind(), DatabaseKind::Persistent { .. })
+    }
+
+    /// Compacts the database in `directory` and returns the number of bytes reclaimed on disk.
+    ///
+    /// The database must not be open in any process. MDBX never shrinks its data file,
+    /// so MDBX databases are copied to a new directory that then replaces the original one.
+    /// The MDBX database is opened in exclusive mode, which fails if any other process has it open.
+    pub fn compact_offline(name: &str, directory: impl AsRef<Path>, size: ByteSize) -> Result<u64> {
+        let directory = directory.as_ref();
+
+        recover_interrupted_compaction(directory)?;
+
+        let backend = DatabaseBackend::detect(directory).ok_or_else(|| Error::NotFound {
+            directory: directory.to_owned(),
+        })?;
+
+        let size_before = directory_size(directory)?;
+
+        match backend {
+            DatabaseBackend::Mdbx => {
+                let compacted_directory = directory.with_extension("compacted");
+                let original_directory = directory.with_extension("original");
+
+                // A previous run may have been interrupted before replacing the original.
+                if compacted_directory.exists() {
+                    fs_err::remove_dir_all(&compacted_directory)?;
+                }
+
+                // Keep the database open until it is replaced to prevent other processes from
+                // opening it in the meantime.
+                let database =
+                    Self::open_mdbx(name, directory, size, DurabilityLevel::default(), true)?;
+
+                database.backup_to(&compacted_directory, size, || Ok(()))?;
+
+                fs_err::rename(directory, &original_directory)?;
+                fs_err::rename(&compacted_directory, directory)?;
+
+                drop(database);
+
+                fs_err::remove_dir_all(original_directory)?;
+
+                info!("MDBX database compacted: {directory:?}");
+            }
+            DatabaseBackend::RocksDb => {
+                Self::open_rocksdb(directory, DurabilityLevel::default())?.compact()?;
+            }
+        }
+
+        let size_after = directory_size(directory)?;
+
+        Ok(size_before.saturating_sub(size_after))
+    }
+
     /// Returns the first key-value pair whose key is less than or equal to `key`.
     ///
     /// Behaves like [`im::OrdMap::get_prev`].
@@ -352,6 +1000,15 @@ impl Database {
                 .expect("in-memory database mutex is poisoned")
                 .get_prev(key.as_ref())
                 .map(|(key, value)| Ok((key.to_vec(), decompress(value)?))),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .iterator(IteratorMode::From(key.as_ref(), Direction::Reverse))
+                .next()
+                .map(|result| {
+                    decompress_rocksdb_pair(result).map(|(key, value)| (key.into_owned(), value))
+                }),
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
         .transpose()
     }
@@ -379,12 +1036,21 @@ impl Database {
                 .expect("in-memory database mutex is poisoned")
                 .get_next(key.as_ref())
                 .map(|(key, value)| Ok((key.to_vec(), decompress(value)?))),
+            #[cfg(feature = "rocksdb")]
+            DatabaseKind::RocksDb { database } => database
+                .iterator(IteratorMode::From(key.as_ref(), Direction::Forward))
+                .next()
+                .map(|result| {
+                    decompress_rocksdb_pair(result).map(|(key, value)| (key.into_owned(), value))
+                }),
+            #[cfg(not(feature = "rocksdb"))]
+            DatabaseKind::RocksDb { database } => match *database {},
         }
         .transpose()
     }
 
     const fn kind(&self) -> &DatabaseKind {
-        &self.0
+        &self.kind
     }
 }
 
@@ -404,17 +1070,71 @@ enum DatabaseKind {
         //                             Alternatively, return `Bytes` instead of `Cow` and `Vec`.
         map: Mutex<OrdMap<Bytes, Bytes>>,
     },
+    #[cfg_attr(not(feature = "rocksdb"), allow(dead_code))]
+    RocksDb { database: DB },
 }
 
 #[derive(Debug, Error)]
-#[error("database directory path should be a valid Unicode string")]
-struct Error;
+enum Error {
+    #[error("database directory path should be a valid Unicode string")]
+    NonUnicodePath,
+    #[error(
+        "database in {directory:?} was created with the {existing} backend \
+         and cannot be opened with the {requested} backend"
+    )]
+    BackendMismatch {
+        directory: PathBuf,
+        existing: DatabaseBackend,
+        requested: DatabaseBackend,
+    },
+    #[error("no database found in {directory:?}")]
+    NotFound { directory: PathBuf },
+    #[error("backup target {directory:?} already contains a database")]
+    BackupTargetNotEmpty { directory: PathBuf },
+    #[error("in-memory databases cannot be backed up")]
+    InMemoryBackup,
+    #[error("MDBX databases cannot be compacted while open")]
+    OnlineCompactionUnsupported,
+    #[cfg(not(feature = "rocksdb"))]
+    #[error("RocksDB databases are only supported when built with the rocksdb feature")]
+    RocksDbDisabled,
+}
+
+// `Database::compact_offline` replaces MDBX databases by renaming directories.
+// If it was interrupted after moving the original database aside, either put the original back
+// or finish replacing it with the compacted copy, depending on how far it got.
+fn recover_interrupted_compaction(directory: &Path) -> Result<()> {
+    let original_directory = directory.with_extension("original");
+
+    if !original_directory.exists() {
+        return Ok(());
+    }
+
+    if directory.exists() {
+        info!("removing database left over by interrupted compaction: {original_directory:?}");
+        fs_err::remove_dir_all(original_directory)?;
+    } else {
+        warn!("restoring database moved aside by interrupted compaction: {directory:?}");
+        fs_err::rename(original_directory, directory)?;
+    }
 
-fn compress(data: &[u8]) -> Result<Vec<u8>> {
-    Encoder::new().compress_vec(data).map_err(Into::into)
+    Ok(())
+}
+
+// Both backends keep all of their files directly in the database directory.
+fn directory_size(directory: &Path) -> Result<u64> {
+    fs_err::read_dir(directory)?
+        .map(|entry| -> Result<u64> { Ok(entry?.metadata()?.len()) })
+        .sum()
 }
 
 fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    if data.starts_with(ZSTD_MAGIC) {
+        let mut decompressed = vec![];
+        zstd::stream::copy_decode(data, &mut decompressed)?;
+        return Ok(decompressed);
+    }
+
     Decoder::new().decompress_vec(data).map_err(Into::into)
 }
 
@@ -423,6 +1143,15 @@ fn decompress_pair<K>((key, compressed_value): (K, Cow<[u8]>)) -> Result<(K, Vec
     Ok((key, value))
 }
 
+#[cfg(feature = "rocksdb")]
+fn decompress_rocksdb_pair(
+    result: Result<(Box<[u8]>, Box<[u8]>), rocksdb::Error>,
+) -> Result<(Cow<'static, [u8]>, Vec<u8>)> {
+    let (key, compressed_value) = result?;
+    let value = decompress(&compressed_value)?;
+    Ok((Cow::Owned(key.into_vec()), value))
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -434,6 +1163,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_delete(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -450,6 +1180,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_delete_range_inclusive_exclusive(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -465,6 +1196,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_delete_range_between(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -480,6 +1212,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_contains_key(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -495,6 +1228,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_iterator_ascending(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -527,6 +1261,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_iterator_descending(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -564,6 +1299,7 @@ mod tests {
     // This covers a bug we introduced and fixed while implementing in-memory mode.
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_iterators_do_not_modify_the_database(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -584,6 +1320,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_multiple_of_the_same_key(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -601,6 +1338,7 @@ mod tests {
     // ```
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_prev(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -624,6 +1362,7 @@ mod tests {
     // ```
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_next(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
 
@@ -642,6 +1381,7 @@ mod tests {
 
     #[test_case(build_persistent_database)]
     #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
     fn test_isolation(constructor: Constructor) -> Result<()> {
         let database = constructor()?;
         let iterator = database.iterator_ascending("A"..)?;
@@ -653,12 +1393,366 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(build_persistent_database)]
+    #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
+    fn test_values_are_readable_after_changing_codec(constructor: Constructor) -> Result<()> {
+        let database = constructor()?.with_codec(Codec::Zstd);
+        let state_like = [1, 2, 3].repeat(1000);
+
+        database.put("Z", &state_like)?;
+
+        assert_eq!(database.get("A")?, Some(b"1".to_vec()));
+        assert_eq!(database.get("Z")?, Some(state_like.clone()));
+
+        let database = database.with_codec(Codec::Snappy);
+
+        database.put("S", &state_like)?;
+
+        assert_eq!(database.get("S")?, Some(state_like.clone()));
+        assert_eq!(database.get("Z")?, Some(state_like));
+
+        Ok(())
+    }
+
+    #[test_case(build_persistent_database)]
+    #[test_case(build_in_memory_database)]
+    #[cfg_attr(feature = "rocksdb", test_case(build_rocksdb_database))]
+    fn test_stored_sizes(constructor: Constructor) -> Result<()> {
+        let database = constructor()?;
+        let compressed_size = Codec::default().compress(b"1")?.len();
+
+        let sizes = database
+            .stored_sizes()?
+            .map(|result| {
+                let (key, size) = result?;
+                Ok((key.into_owned(), size))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            sizes,
+            [b"A", b"B", b"C", b"E"].map(|key| (key.to_vec(), compressed_size)),
+        );
+
+        Ok(())
+    }
+
+    #[test_case(DatabaseBackend::Mdbx)]
+    #[cfg_attr(feature = "rocksdb", test_case(DatabaseBackend::RocksDb))]
+    fn test_read_only(backend: DatabaseBackend) -> Result<()> {
+        let directory = TempDir::new()?;
+
+        Database::persistent_read_only("test_db", directory.path())
+            .err()
+            .expect("opening a missing database in read-only mode should fail");
+
+        let database = Database::persistent_with_backend(
+            backend,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path(),
+            ByteSize::mib(1),
+        )?;
+
+        populate_database(&database)?;
+        drop(database);
+
+        let database = Database::persistent_read_only("test_db", directory.path())?;
+
+        assert_pairs_eq(
+            database.iterator_ascending("0"..)?,
+            [("A", "1"), ("B", "2"), ("C", "3"), ("E", "5")],
+        )?;
+
+        Ok(())
+    }
+
+    #[test_case(DatabaseBackend::Mdbx, DurabilityLevel::Async)]
+    #[test_case(DatabaseBackend::Mdbx, DurabilityLevel::EveryBatch)]
+    #[cfg_attr(
+        feature = "rocksdb",
+        test_case(DatabaseBackend::RocksDb, DurabilityLevel::Async)
+    )]
+    fn test_durability_levels(backend: DatabaseBackend, durability: DurabilityLevel) -> Result<()> {
+        let directory = TempDir::new()?;
+
+        let database = Database::persistent_with_backend(
+            backend,
+            durability,
+            "test_db",
+            directory.path(),
+            ByteSize::mib(1),
+        )?;
+
+        database.put_batch([("A", "1"), ("B", "2")])?;
+        database.put_batch_with_durability([("C", "3")], DurabilityLevel::Always)?;
+        drop(database);
+
+        let database = Database::persistent_read_only("test_db", directory.path())?;
+
+        assert_pairs_eq(
+            database.iterator_ascending("0"..)?,
+            [("A", "1"), ("B", "2"), ("C", "3")],
+        )?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rocksdb")]
+    #[test_case(DatabaseBackend::Mdbx, DatabaseBackend::RocksDb)]
+    #[test_case(DatabaseBackend::RocksDb, DatabaseBackend::Mdbx)]
+    fn test_backend_mismatch(existing: DatabaseBackend, requested: DatabaseBackend) -> Result<()> {
+        let directory = TempDir::new()?;
+
+        drop(Database::persistent_with_backend(
+            existing,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path(),
+            ByteSize::mib(1),
+        )?);
+
+        Database::persistent_with_backend(
+            requested,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path(),
+            ByteSize::mib(1),
+        )
+        .err()
+        .expect("opening a database with a different backend should fail");
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "rocksdb"))]
+    #[test]
+    fn test_rocksdb_requires_feature() -> Result<()> {
+        let directory = TempDir::new()?;
+
+        Database::persistent_with_backend(
+            DatabaseBackend::RocksDb,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path(),
+            ByteSize::mib(1),
+        )
+        .err()
+        .expect("RocksDB databases should not be opened without the rocksdb feature");
+
+        Ok(())
+    }
+
+    #[test_case(DatabaseBackend::Mdbx)]
+    #[cfg_attr(feature = "rocksdb", test_case(DatabaseBackend::RocksDb))]
+    fn test_compact_offline(backend: DatabaseBackend) -> Result<()> {
+        let directory = TempDir::new()?;
+        let database_directory = directory.path().join("test_db");
+
+        let database = Database::persistent_with_backend(
+            backend,
+            DurabilityLevel::default(),
+            "test_db",
+            &database_directory,
+            ByteSize::mib(1),
+        )?;
+
+        populate_database(&database)?;
+        database.delete_range("C".."F")?;
+        drop(database);
+
+        Database::compact_offline("test_db", &database_directory, ByteSize::mib(1))?;
+
+        let database = Database::persistent_read_only("test_db", &database_directory)?;
+
+        assert_pairs_eq(
+            database.iterator_ascending("0"..)?,
+            [("A", "1"), ("B", "2")],
+        )?;
+
+        // Only the compacted database should be left.
+        assert_eq!(fs_err::read_dir(directory.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupted_compaction_is_recovered() -> Result<()> {
+        let directory = TempDir::new()?;
+        let database_directory = directory.path().join("test_db");
+
+        let database = Database::persistent("test_db", &database_directory, ByteSize::mib(1))?;
+
+        populate_database(&database)?;
+        drop(database);
+
+        // Simulate compaction interrupted right after moving the original database aside.
+        fs_err::rename(
+            &database_directory,
+            database_directory.with_extension("original"),
+        )?;
+
+        let database = Database::persistent("test_db", &database_directory, ByteSize::mib(1))?;
+
+        assert_eq!(database.get("A")?, Some(b"1".to_vec()));
+        assert!(!database_directory.with_extension("original").exists());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rocksdb")]
+    #[test]
+    fn test_compact_rocksdb_while_open() -> Result<()> {
+        let database = build_rocksdb_database()?;
+
+        database.delete_range("C".."F")?;
+        database.compact()?;
+
+        assert_pairs_eq(
+            database.iterator_ascending("0"..)?,
+            [("A", "1"), ("B", "2")],
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_mdbx_while_open() -> Result<()> {
+        let database = build_persistent_database()?;
+
+        assert!(!database.supports_online_compaction());
+
+        database
+            .compact()
+            .expect_err("MDBX databases should not be compacted while open");
+
+        Ok(())
+    }
+
+    #[test_case(DatabaseBackend::Mdbx)]
+    #[cfg_attr(feature = "rocksdb", test_case(DatabaseBackend::RocksDb))]
+    fn test_backup_to(backend: DatabaseBackend) -> Result<()> {
+        let directory = TempDir::new()?;
+        let backup_directory = directory.path().join("backup");
+
+        let database = Database::persistent_with_backend(
+            backend,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path().join("database"),
+            ByteSize::mib(1),
+        )?;
+
+        populate_database(&database)?;
+
+        database.backup_to(&backup_directory, ByteSize::mib(1), || {
+            // MDBX does not allow read and write transactions to overlap in the same thread.
+            std::thread::scope(|scope| {
+                scope
+                    .spawn(|| database.put("F", "6"))
+                    .join()
+                    .expect("writer thread should not panic")
+            })
+        })?;
+
+        let backup = Database::persistent_read_only("test_db", &backup_directory)?;
+
+        // Writes made after the snapshot must not end up in the backup.
+        let expected_pairs = [("A", "1"), ("B", "2"), ("C", "3"), ("E", "5")];
+
+        assert_pairs_eq(backup.iterator_ascending("0"..)?, expected_pairs)?;
+
+        database
+            .backup_to(&backup_directory, ByteSize::mib(1), || Ok(()))
+            .expect_err("backing up to a directory with a database should fail");
+
+        Ok(())
+    }
+
+    #[test_case(DatabaseBackend::Mdbx)]
+    #[cfg_attr(feature = "rocksdb", test_case(DatabaseBackend::RocksDb))]
+    fn test_backup_to_existing_empty_directory(backend: DatabaseBackend) -> Result<()> {
+        let directory = TempDir::new()?;
+        let backup_directory = directory.path().join("backup");
+
+        let database = Database::persistent_with_backend(
+            backend,
+            DurabilityLevel::default(),
+            "test_db",
+            directory.path().join("database"),
+            ByteSize::mib(1),
+        )?;
+
+        populate_database(&database)?;
+
+        fs_err::create_dir(&backup_directory)?;
+
+        database.backup_to(&backup_directory, ByteSize::mib(1), || Ok(()))?;
+
+        let backup = Database::persistent_read_only("test_db", &backup_directory)?;
+
+        assert_pairs_eq(
+            backup.iterator_ascending("0"..)?,
+            [("A", "1"), ("B", "2"), ("C", "3"), ("E", "5")],
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_to_copies_mdbx_database_in_chunks() -> Result<()> {
+        let directory = TempDir::new()?;
+        let backup_directory = directory.path().join("backup");
+        let pair_count = BACKUP_BATCH_SIZE * 2 + 1;
+
+        let database = Database::persistent(
+            "test_db",
+            directory.path().join("database"),
+            ByteSize::mib(16),
+        )?;
+
+        database.put_batch((0..pair_count).map(|index| (format!("{index:08}"), "value")))?;
+        database.backup_to(&backup_directory, ByteSize::mib(16), || Ok(()))?;
+
+        let backup = Database::persistent_read_only("test_db", &backup_directory)?;
+
+        assert_eq!(backup.iterator_ascending("0"..)?.count(), pair_count);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_to_fails_for_in_memory_database() -> Result<()> {
+        let directory = TempDir::new()?;
+
+        build_in_memory_database()?
+            .backup_to(directory.path(), ByteSize::mib(1), || Ok(()))
+            .expect_err("in-memory databases cannot be backed up");
+
+        Ok(())
+    }
+
     fn build_persistent_database() -> Result<Database> {
         let database = Database::persistent("test_db", TempDir::new()?, ByteSize::mib(1))?;
         populate_database(&database)?;
         Ok(database)
     }
 
+    #[cfg(feature = "rocksdb")]
+    fn build_rocksdb_database() -> Result<Database> {
+        let database = Database::persistent_with_backend(
+            DatabaseBackend::RocksDb,
+            DurabilityLevel::default(),
+            "test_db",
+            TempDir::new()?,
+            ByteSize::mib(1),
+        )?;
+
+        populate_database(&database)?;
+        Ok(database)
+    }
+
     fn build_in_memory_database() -> Result<Database> {
         let database = Database::in_memory();
         populate_database(&database)?;
@@ -0,0 +1,371 @@
+use core::{cmp::Ordering, ops::Range};
+
+use anyhow::{ensure, Result};
+use hashing::ZERO_HASHES;
+use ssz::{ProofWithLength, SszHash as _, H256};
+use typenum::Unsigned as _;
+use types::phase0::{
+    consts::DepositContractTreeDepth, containers::DepositData, primitives::DepositIndex,
+};
+
+use crate::{DepositTree, Error};
+
+const DEPTH: usize = DepositContractTreeDepth::USIZE;
+
+/// A deposit tree that can construct proofs for unfinalized deposits without recomputing them.
+///
+/// [`DepositTree`] only stores the hashes needed to add more deposits, so constructing proofs with
+/// it requires adding all unfinalized deposits again. This stores [`DepositTree`] for finalized
+/// deposits along with hashes of all complete nodes that contain unfinalized deposits.
+/// Adding a deposit, computing the root of the tree at any unfinalized deposit count and
+/// constructing a proof for a deposit each take `O(log n)` time.
+///
+/// Only the finalized part is meant to be persisted.
+/// The rest can be rebuilt from Eth1 blocks containing unfinalized deposits.
+#[derive(Clone)]
+pub struct IncrementalDepositTree {
+    finalized: DepositTree,
+    // `levels[height]` contains hashes of complete nodes at `height` that contain unfinalized
+    // deposits, starting with the node at index `self.finalized.deposit_count >> height`.
+    // Complete nodes containing only finalized deposits are never needed except for the last left
+    // node at each height. Those are stored in `self.finalized.merkle_tree`.
+    levels: Vec<Vec<H256>>,
+    deposit_count: DepositIndex,
+}
+
+impl IncrementalDepositTree {
+    #[must_use]
+    pub fn new(finalized: DepositTree) -> Self {
+        Self {
+            finalized,
+            levels: vec![vec![]; DEPTH + 1],
+            deposit_count: finalized.deposit_count,
+        }
+    }
+
+    #[must_use]
+    pub const fn finalized(&self) -> &DepositTree {
+        &self.finalized
+    }
+
+    #[must_use]
+    pub const fn deposit_count(&self) -> DepositIndex {
+        self.deposit_count
+    }
+
+    pub fn push(&mut self, index: DepositIndex, data: DepositData) -> Result<()> {
+        DepositTree::validate_index_fits(index)?;
+
+        ensure!(
+            index == self.deposit_count,
+            Error::UnexpectedIndex {
+                expected: self.deposit_count,
+                actual: index,
+            },
+        );
+
+        let mut hash = data.hash_tree_root();
+        let mut node_index = index;
+
+        self.levels[0].push(hash);
+
+        // Adding a right node completes its parent.
+        for height in 1..=DEPTH {
+            if node_index % 2 == 0 {
+                break;
+            }
+
+            hash = hashing::hash_256_256(self.complete_node(height - 1, node_index - 1)?, hash);
+            node_index /= 2;
+
+            self.levels[height].push(hash);
+        }
+
+        self.deposit_count += 1;
+
+        Ok(())
+    }
+
+    /// Computes the root of the tree as it was when it contained `deposit_count` deposits.
+    ///
+    /// The result can be compared with `Eth1Data.deposit_root`.
+    pub fn root(&self, deposit_count: DepositIndex) -> Result<H256> {
+        self.validate_deposit_count(deposit_count)?;
+
+        let boundary_nodes = self.boundary_nodes(deposit_count)?;
+        let root = self.node(DEPTH, 0, deposit_count, &boundary_nodes)?;
+
+        Ok(ssz::mix_in_length(root, deposit_count.try_into()?))
+    }
+
+    /// Constructs proofs for deposits in `proof_indices` against the root
+    /// of the tree as it was when it contained `deposit_count` deposits.
+    pub fn construct_proofs(
+        &self,
+        proof_indices: Range<DepositIndex>,
+        deposit_count: DepositIndex,
+    ) -> Result<Vec<ProofWithLength<DepositContractTreeDepth>>> {
+        self.validate_deposit_count(deposit_count)?;
+
+        let unfinalized_indices = self.finalized.deposit_count..deposit_count;
+
+        ensure!(
+            unfinalized_indices.start <= proof_indices.start
+                && proof_indices.end <= unfinalized_indices.end,
+            Error::ProofIndicesOutOfRange {
+                proof_indices,
+                unfinalized_indices,
+            },
+        );
+
+        let boundary_nodes = self.boundary_nodes(deposit_count)?;
+
+        // The last element of the proof corresponds to the node added by `mix_in_length`.
+        // SSZ lengths are little-endian.
+        let mut length_hash = H256::zero();
+        length_hash[..core::mem::size_of::<DepositIndex>()]
+            .copy_from_slice(&deposit_count.to_le_bytes());
+
+        proof_indices
+            .map(|proof_index| {
+                let mut proof = ProofWithLength::<DepositContractTreeDepth>::default();
+
+                for height in 0..DEPTH {
+                    let sibling_index = (proof_index >> height) ^ 1;
+                    proof[height] =
+                        self.node(height, sibling_index, deposit_count, &boundary_nodes)?;
+                }
+
+                proof[DEPTH] = length_hash;
+
+                Ok(proof)
+            })
+            .collect()
+    }
+
+    /// Replaces the finalized part of the tree with `finalized`.
+    ///
+    /// `finalized` must contain a prefix of the deposits in this tree.
+    /// If it contains all of them or more, unfinalized deposits are discarded.
+    pub fn finalize(&mut self, finalized: DepositTree) -> Result<()> {
+        let old_count = self.finalized.deposit_count;
+        let new_count = finalized.deposit_count;
+
+        if !(old_count..self.deposit_count).contains(&new_count) {
+            *self = Self::new(finalized);
+            return Ok(());
+        }
+
+        for (height, level) in self.levels.iter_mut().enumerate() {
+            let finalized_nodes = usize::try_from((new_count >> height) - (old_count >> height))?;
+            level.drain(..finalized_nodes.min(level.len()));
+        }
+
+        self.finalized = finalized;
+
+        Ok(())
+    }
+
+    fn validate_deposit_count(&self, deposit_count: DepositIndex) -> Result<()> {
+        ensure!(
+            (self.finalized.deposit_count..=self.deposit_count).contains(&deposit_count),
+            Error::DepositCountOutOfRange {
+                deposit_count,
+                finalized_count: self.finalized.deposit_count,
+                available_count: self.deposit_count,
+            },
+        );
+
+        Ok(())
+    }
+
+    // Returns the hash of a node that only contains existing deposits.
+    //
+    // Nodes containing only finalized deposits can be accessed only if they are the last complete
+    // left node at their height. All callers satisfy this because they only access nodes adjacent
+    // to the paths from the root to unfinalized deposits.
+    fn complete_node(&self, height: usize, index: DepositIndex) -> Result<H256> {
+        let first_stored_index = self.finalized.deposit_count >> height;
+
+        if index < first_stored_index {
+            return Ok(self.finalized.merkle_tree.sibling_hash(height));
+        }
+
+        let position = usize::try_from(index - first_stored_index)?;
+
+        Ok(self.levels[height][position])
+    }
+
+    // Returns hashes of nodes at index `deposit_count >> height` for every height, computed as if
+    // the tree contained only the first `deposit_count` deposits. Every other node in such a tree
+    // is either complete or empty.
+    fn boundary_nodes(&self, deposit_count: DepositIndex) -> Result<Vec<H256>> {
+        let mut boundary_nodes = Vec::with_capacity(DEPTH + 1);
+        let mut hash = ZERO_HASHES[0];
+
+        boundary_nodes.push(hash);
+
+        for height in 1..=DEPTH {
+            let child_index = deposit_count >> (height - 1);
+
+            hash = if child_index % 2 == 1 {
+                hashing::hash_256_256(self.complete_node(height - 1, child_index - 1)?, hash)
+            } else {
+                hashing::hash_256_256(hash, ZERO_HASHES[height - 1])
+            };
+
+            boundary_nodes.push(hash);
+        }
+
+        Ok(boundary_nodes)
+    }
+
+    fn node(
+        &self,
+        height: usize,
+        index: DepositIndex,
+        deposit_count: DepositIndex,
+        boundary_nodes: &[H256],
+    ) -> Result<H256> {
+        match index.cmp(&(deposit_count >> height)) {
+            Ordering::Less => self.complete_node(height, index),
+            Ordering::Equal => Ok(boundary_nodes[height]),
+            Ordering::Greater => Ok(ZERO_HASHES[height]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+
+    const DEPOSIT_COUNT: DepositIndex = 23;
+
+    fn deposit_data(index: DepositIndex) -> DepositData {
+        DepositData {
+            amount: index,
+            ..DepositData::default()
+        }
+    }
+
+    fn finalized_tree(deposit_count: DepositIndex) -> Result<DepositTree> {
+        let mut deposit_tree = DepositTree::default();
+
+        for index in 0..deposit_count {
+            deposit_tree.push(index, deposit_data(index))?;
+        }
+
+        Ok(deposit_tree)
+    }
+
+    fn incremental_tree(finalized_count: DepositIndex) -> Result<IncrementalDepositTree> {
+        let mut incremental_tree = IncrementalDepositTree::new(finalized_tree(finalized_count)?);
+
+        for index in finalized_count..DEPOSIT_COUNT {
+            incremental_tree.push(index, deposit_data(index))?;
+        }
+
+        Ok(incremental_tree)
+    }
+
+    fn assert_matches_deposit_tree(incremental_tree: &IncrementalDepositTree) -> Result<()> {
+        let finalized_count = incremental_tree.finalized().deposit_count;
+
+        for deposit_count in finalized_count..=DEPOSIT_COUNT {
+            let mut deposit_tree = finalized_tree(finalized_count)?;
+            let mut expected_root = None;
+
+            for index in finalized_count..deposit_count {
+                expected_root =
+                    Some(deposit_tree.push_and_compute_root(index, deposit_data(index))?);
+            }
+
+            if let Some(expected_root) = expected_root {
+                assert_eq!(incremental_tree.root(deposit_count)?, expected_root);
+            }
+
+            if finalized_count < deposit_count {
+                let data = (finalized_count..deposit_count)
+                    .map(deposit_data)
+                    .collect_vec();
+                let data = data.iter().collect_vec();
+                let indices = finalized_count..deposit_count;
+
+                let expected_proofs = finalized_tree(finalized_count)?
+                    .extend_and_construct_proofs(&data, indices.clone(), indices.clone())?
+                    .into_iter()
+                    .map(|deposit| deposit.proof)
+                    .collect_vec();
+
+                assert_eq!(
+                    incremental_tree.construct_proofs(indices, deposit_count)?,
+                    expected_proofs,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn roots_and_proofs_match_deposit_tree() -> Result<()> {
+        for finalized_count in [0, 1, 2, 7, 8, 16, 22, DEPOSIT_COUNT] {
+            assert_matches_deposit_tree(&incremental_tree(finalized_count)?)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn roots_and_proofs_match_deposit_tree_after_finalization() -> Result<()> {
+        for (old_count, new_count) in [(0, 5), (3, 8), (7, 16), (9, 22), (0, DEPOSIT_COUNT)] {
+            let mut incremental_tree = incremental_tree(old_count)?;
+
+            incremental_tree.finalize(finalized_tree(new_count)?)?;
+
+            assert_eq!(incremental_tree.deposit_count(), DEPOSIT_COUNT);
+            assert_matches_deposit_tree(&incremental_tree)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn root_fails_outside_unfinalized_range() -> Result<()> {
+        let incremental_tree = incremental_tree(8)?;
+
+        incremental_tree
+            .root(7)
+            .expect_err("root should not be available for finalized deposit counts");
+
+        incremental_tree
+            .root(DEPOSIT_COUNT + 1)
+            .expect_err("root should not be available for missing deposits");
+
+        Ok(())
+    }
+
+    #[test]
+    fn construct_proofs_fails_for_finalized_deposits() -> Result<()> {
+        let incremental_tree = incremental_tree(8)?;
+
+        incremental_tree
+            .construct_proofs(7..9, DEPOSIT_COUNT)
+            .expect_err("proofs should not be available for finalized deposits");
+
+        Ok(())
+    }
+
+    #[test]
+    fn push_fails_on_unexpected_index() -> Result<()> {
+        let mut incremental_tree = incremental_tree(8)?;
+
+        incremental_tree
+            .push(DEPOSIT_COUNT + 1, DepositData::default())
+            .expect_err("pushing with incorrect index should fail");
+
+        Ok(())
+    }
+}
@@ -11,6 +11,10 @@ use types::phase0::{
     primitives::{DepositIndex, ExecutionBlockNumber, H256},
 };
 
+pub use crate::incremental::IncrementalDepositTree;
+
+mod incremental;
+
 const MAX_DEPOSITS: DepositIndex = 1 << DepositContractTreeDepth::USIZE;
 
 // We do not store the whole deposit tree, only hashes that are needed to construct proofs.
@@ -173,6 +177,23 @@ enum Error {
         data_count: usize,
         index_count: usize,
     },
+    #[error(
+        "deposit count {deposit_count} is outside the range covered by deposit tree \
+         (finalized deposits: {finalized_count}, all deposits: {available_count})"
+    )]
+    DepositCountOutOfRange {
+        deposit_count: DepositIndex,
+        finalized_count: DepositIndex,
+        available_count: DepositIndex,
+    },
+    #[error(
+        "cannot construct proofs for deposits {proof_indices:?} \
+         in tree with unfinalized deposits {unfinalized_indices:?}"
+    )]
+    ProofIndicesOutOfRange {
+        proof_indices: Range<DepositIndex>,
+        unfinalized_indices: Range<DepositIndex>,
+    },
 }
 
 // False positive. See <https://github.com/rust-lang/rust-clippy/issues/3307>.
@@ -0,0 +1,451 @@
+use core::time::Duration;
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use axum::{extract::State, routing::post, Json, Router, Server};
+use ethereum_types::H64;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use std_ext::ArcExt as _;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use types::phase0::primitives::{ExecutionAddress, ExecutionBlockHash, H256};
+use url::Url;
+
+use crate::{
+    replay::{RecordedOutcome, Recording},
+    script::{MethodScript, ScriptedBlobsBundle, ScriptedStatus},
+};
+
+const SUPPORTED_METHODS: &[&str] = &[
+    "engine_newPayloadV1",
+    "engine_newPayloadV2",
+    "engine_newPayloadV3",
+    "engine_forkchoiceUpdatedV1",
+    "engine_forkchoiceUpdatedV2",
+    "engine_forkchoiceUpdatedV3",
+    "engine_getPayloadV1",
+    "engine_getPayloadV2",
+    "engine_getPayloadV3",
+];
+
+// Arbitrary values that look like ones a real execution client would use.
+const GAS_LIMIT: &str = "0x1c9c380";
+const BASE_FEE_PER_GAS: &str = "0x7";
+
+/// A request received by [`MockEngine`], recorded so that tests can check what was sent.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EngineRequest {
+    pub method: String,
+    pub params: Vec<Value>,
+}
+
+/// An Engine API server running in the background.
+///
+/// The server is stopped when the `MockEngine` is dropped.
+pub struct MockEngine {
+    url: Url,
+    state: Arc<Mutex<EngineState>>,
+    server: JoinHandle<()>,
+}
+
+impl Drop for MockEngine {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+impl MockEngine {
+    /// Starts a server on a random port on the loopback interface.
+    ///
+    /// Must be called in the context of a Tokio runtime.
+    pub fn start() -> Result<Self> {
+        Self::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+    }
+
+    /// Starts a server on `address`.
+    ///
+    /// Must be called in the context of a Tokio runtime.
+    pub fn bind(address: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(address)?;
+
+        listener.set_nonblocking(true)?;
+
+        let address = listener.local_addr()?;
+        let url = format!("http://{address}").parse()?;
+        let state = Arc::new(Mutex::new(EngineState::default()));
+
+        let router = Router::new()
+            .route("/", post(handle_request))
+            .with_state(state.clone_arc());
+
+        let serve = Server::from_tcp(listener)?.serve(router.into_make_service());
+
+        let server = tokio::spawn(async move {
+            if let Err(error) = serve.await {
+                warn!("mock execution engine stopped with an error: {error}");
+            }
+        });
+
+        info!("mock execution engine is listening on {address}");
+
+        Ok(Self { url, state, server })
+    }
+
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn set_new_payload_status(&self, scripted: ScriptedStatus) {
+        self.state.lock().new_payload.default = scripted;
+    }
+
+    pub fn push_new_payload_status(&self, scripted: ScriptedStatus) {
+        self.state.lock().new_payload.queued.push_back(scripted);
+    }
+
+    pub fn set_new_payload_status_for(
+        &self,
+        block_hash: ExecutionBlockHash,
+        scripted: ScriptedStatus,
+    ) {
+        self.state
+            .lock()
+            .new_payload
+            .by_block_hash
+            .insert(block_hash, scripted);
+    }
+
+    pub fn set_forkchoice_updated_status(&self, scripted: ScriptedStatus) {
+        self.state.lock().forkchoice_updated.default = scripted;
+    }
+
+    pub fn push_forkchoice_updated_status(&self, scripted: ScriptedStatus) {
+        self.state
+            .lock()
+            .forkchoice_updated
+            .queued
+            .push_back(scripted);
+    }
+
+    pub fn set_forkchoice_updated_status_for(
+        &self,
+        head_block_hash: ExecutionBlockHash,
+        scripted: ScriptedStatus,
+    ) {
+        self.state
+            .lock()
+            .forkchoice_updated
+            .by_block_hash
+            .insert(head_block_hash, scripted);
+    }
+
+    pub fn set_blobs_bundle(&self, blobs_bundle: ScriptedBlobsBundle) {
+        self.state.lock().blobs_bundle = Some(blobs_bundle);
+    }
+
+    /// Answers requests with exchanges from `recording` before falling back to scripted responses.
+    pub fn replay(&self, recording: Recording) {
+        self.state.lock().replay = recording;
+    }
+
+    #[must_use]
+    pub fn unreplayed_exchanges(&self) -> usize {
+        self.state.lock().replay.len()
+    }
+
+    #[must_use]
+    pub fn requests(&self) -> Vec<EngineRequest> {
+        self.state.lock().requests.clone()
+    }
+}
+
+#[derive(Default)]
+struct EngineState {
+    new_payload: MethodScript,
+    forkchoice_updated: MethodScript,
+    blobs_bundle: Option<ScriptedBlobsBundle>,
+    payload_jobs: HashMap<H64, PayloadJob>,
+    last_payload_id: u64,
+    requests: Vec<EngineRequest>,
+    replay: Recording,
+}
+
+impl EngineState {
+    fn respond(&mut self, method: &str, params: &[Value]) -> Result<(Value, Duration), Error> {
+        self.requests.push(EngineRequest {
+            method: method.to_owned(),
+            params: params.to_vec(),
+        });
+
+        if let Some(exchange) = self.replay.take(method, params) {
+            let delay = exchange.delay();
+
+            return match exchange.into_outcome() {
+                RecordedOutcome::Result(result) => Ok((result, delay)),
+                RecordedOutcome::Error { code, message } => Err(Error::Recorded { code, message }),
+            };
+        }
+
+        match method {
+            "engine_newPayloadV1" | "engine_newPayloadV2" | "engine_newPayloadV3" => {
+                self.new_payload(params)
+            }
+            "engine_forkchoiceUpdatedV1"
+            | "engine_forkchoiceUpdatedV2"
+            | "engine_forkchoiceUpdatedV3" => self.forkchoice_updated(params),
+            "engine_getPayloadV1" => self.get_payload(params, 1),
+            "engine_getPayloadV2" => self.get_payload(params, 2),
+            "engine_getPayloadV3" => self.get_payload(params, 3),
+            "engine_exchangeCapabilities" => Ok((json!(SUPPORTED_METHODS), Duration::ZERO)),
+            _ => Err(Error::MethodNotFound {
+                method: method.to_owned(),
+            }),
+        }
+    }
+
+    fn new_payload(&mut self, params: &[Value]) -> Result<(Value, Duration), Error> {
+        let PayloadHeader { block_hash } = param(params, 0)?;
+        let scripted = self.new_payload.next(block_hash);
+        let payload_status = scripted.payload_status(block_hash);
+
+        Ok((serde_json::to_value(payload_status)?, scripted.delay))
+    }
+
+    fn forkchoice_updated(&mut self, params: &[Value]) -> Result<(Value, Duration), Error> {
+        let ForkChoiceState { head_block_hash } = param(params, 0)?;
+        let payload_attributes = optional_param::<PayloadAttributes>(params, 1)?;
+        let scripted = self.forkchoice_updated.next(head_block_hash);
+        let payload_status = scripted.payload_status(head_block_hash);
+
+        // Execution clients only start building payloads on top of heads they consider valid.
+        let payload_id = match payload_attributes {
+            Some(attributes) if payload_status.status.is_valid() => {
+                self.last_payload_id += 1;
+
+                let payload_id = H64::from_low_u64_be(self.last_payload_id);
+
+                let job = PayloadJob {
+                    parent_hash: head_block_hash,
+                    attributes,
+                };
+
+                self.payload_jobs.insert(payload_id, job);
+
+                Some(payload_id)
+            }
+            _ => None,
+        };
+
+        let response = json!({
+            "payloadStatus": payload_status,
+            "payloadId": payload_id,
+        });
+
+        Ok((response, scripted.delay))
+    }
+
+    fn get_payload(&self, params: &[Value], version: u8) -> Result<(Value, Duration), Error> {
+        let payload_id = param(params, 0)?;
+
+        let job = self
+            .payload_jobs
+            .get(&payload_id)
+            .ok_or(Error::UnknownPayload { payload_id })?;
+
+        let response = job.response(payload_id, version, self.blobs_bundle.as_ref());
+
+        Ok((response, Duration::ZERO))
+    }
+}
+
+struct PayloadJob {
+    parent_hash: ExecutionBlockHash,
+    attributes: PayloadAttributes,
+}
+
+impl PayloadJob {
+    fn response(
+        &self,
+        payload_id: H64,
+        version: u8,
+        blobs_bundle: Option<&ScriptedBlobsBundle>,
+    ) -> Value {
+        let PayloadAttributes {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals,
+        } = &self.attributes;
+
+        let block_hash = hashing::hash_256_64(self.parent_hash, payload_id.to_low_u64_be());
+
+        let mut payload = json!({
+            "parentHash": self.parent_hash,
+            "feeRecipient": suggested_fee_recipient,
+            "stateRoot": H256::zero(),
+            "receiptsRoot": H256::zero(),
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "prevRandao": prev_randao,
+            "blockNumber": "0x1",
+            "gasLimit": GAS_LIMIT,
+            "gasUsed": "0x0",
+            "timestamp": timestamp,
+            "extraData": "0x",
+            "baseFeePerGas": BASE_FEE_PER_GAS,
+            "blockHash": block_hash,
+            "transactions": [],
+        });
+
+        if version == 1 {
+            return payload;
+        }
+
+        payload["withdrawals"] = json!(withdrawals.clone().unwrap_or_default());
+
+        if version == 2 {
+            return json!({
+                "executionPayload": payload,
+                "blockValue": "0x0",
+            });
+        }
+
+        let (blobs_bundle, blob_gas_used) = match blobs_bundle {
+            Some(scripted) => (scripted.blobs_bundle(), scripted.blob_gas_used()),
+            None => (
+                json!({
+                    "commitments": [],
+                    "proofs": [],
+                    "blobs": [],
+                }),
+                "0x0".to_owned(),
+            ),
+        };
+
+        payload["blobGasUsed"] = json!(blob_gas_used);
+        payload["excessBlobGas"] = json!("0x0");
+
+        json!({
+            "executionPayload": payload,
+            "blockValue": "0x0",
+            "blobsBundle": blobs_bundle,
+            "shouldOverrideBuilder": false,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonRpcRequest {
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PayloadHeader {
+    block_hash: ExecutionBlockHash,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForkChoiceState {
+    head_block_hash: ExecutionBlockHash,
+}
+
+// `timestamp` and `withdrawals` are passed through to payloads unchanged.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PayloadAttributes {
+    timestamp: Value,
+    prev_randao: H256,
+    suggested_fee_recipient: ExecutionAddress,
+    withdrawals: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("invalid parameters: {0}")]
+    InvalidParams(#[from] serde_json::Error),
+    #[error("method not found: {method}")]
+    MethodNotFound { method: String },
+    #[error("missing parameter at index {index}")]
+    MissingParam { index: usize },
+    #[error("recorded error: {message}")]
+    Recorded { code: Option<i64>, message: String },
+    #[error("unknown payload: {payload_id:?}")]
+    UnknownPayload { payload_id: H64 },
+}
+
+impl Error {
+    // See <https://github.com/ethereum/execution-apis/blob/b7c5d3420e00648f456744d121ffbd929862924d/src/engine/common.md#errors>.
+    const fn code(&self) -> i64 {
+        match self {
+            Self::InvalidParams(_) | Self::MissingParam { .. } => -32602,
+            Self::MethodNotFound { .. } => -32601,
+            // Errors recorded without a code did not come from the execution engine.
+            // Report them as internal errors.
+            Self::Recorded { code, .. } => match code {
+                Some(code) => *code,
+                None => -32603,
+            },
+            Self::UnknownPayload { .. } => -38001,
+        }
+    }
+}
+
+async fn handle_request(
+    State(state): State<Arc<Mutex<EngineState>>>,
+    Json(request): Json<JsonRpcRequest>,
+) -> Json<Value> {
+    let JsonRpcRequest { id, method, params } = request;
+
+    // Bind the result first so that the lock is not held while sleeping.
+    let result = state.lock().respond(&method, &params);
+
+    let response = match result {
+        Ok((result, delay)) => {
+            tokio::time::sleep(delay).await;
+
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": result,
+            })
+        }
+        Err(error) => {
+            warn!("mock execution engine failed to handle {method}: {error}");
+
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": error.code(),
+                    "message": error.to_string(),
+                },
+            })
+        }
+    };
+
+    Json(response)
+}
+
+fn param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<T, Error> {
+    let value = params.get(index).ok_or(Error::MissingParam { index })?;
+    T::deserialize(value).map_err(Into::into)
+}
+
+fn optional_param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<Option<T>, Error> {
+    match params.get(index) {
+        Some(value) => Option::deserialize(value).map_err(Into::into),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,24 @@
+//! A mock execution engine that speaks the Engine API over JSON-RPC.
+//!
+//! Responses to `engine_newPayload*` and `engine_forkchoiceUpdated*` are scripted per method.
+//! Payloads returned by `engine_getPayload*` are built from the payload attributes passed to
+//! `engine_forkchoiceUpdated*`. They are consistent enough to be deserialized and included in
+//! blocks, but no transactions are ever executed. Blobs bundles are empty unless scripted.
+//! JWT authentication is not checked.
+//!
+//! Sessions recorded by `eth1_api::Recorder` can be replayed with [`MockEngine::replay`].
+
+// `binary_utils` and `clap` are only used in `main.rs`.
+// The `unused_crate_dependencies` lint checks every crate in a package separately.
+// See <https://github.com/rust-lang/rust/issues/57274>.
+#![allow(unused_crate_dependencies)]
+
+pub use crate::{
+    engine::{EngineRequest, MockEngine},
+    replay::Recording,
+    script::{MethodScript, ScriptedBlobsBundle, ScriptedStatus},
+};
+
+mod engine;
+mod replay;
+mod script;
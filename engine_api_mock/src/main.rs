@@ -0,0 +1,78 @@
+// The `unused_crate_dependencies` lint checks every crate in a package separately.
+// See <https://github.com/rust-lang/rust/issues/57274>.
+#![allow(unused_crate_dependencies)]
+
+use core::time::Duration;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use engine_api_mock::{MockEngine, Recording, ScriptedStatus};
+use execution_engine::PayloadValidationStatus;
+use log::info;
+use serde_json::Value;
+
+/// Runs a mock execution engine for local debugging.
+#[derive(Parser)]
+struct Options {
+    #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    address: IpAddr,
+    #[clap(long, default_value_t = 8551)]
+    port: u16,
+    /// Status returned from `engine_newPayload*` (for example, `SYNCING`)
+    #[clap(long, default_value = "VALID", value_parser = parse_status)]
+    new_payload_status: PayloadValidationStatus,
+    /// Status returned from `engine_forkchoiceUpdated*`
+    #[clap(long, default_value = "VALID", value_parser = parse_status)]
+    forkchoice_updated_status: PayloadValidationStatus,
+    /// Delay in milliseconds before responding to scripted methods
+    #[clap(long, default_value_t = 0)]
+    delay: u64,
+    /// File recorded with `--eth1-api-recording-file` to answer requests from
+    #[clap(long)]
+    replay: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    binary_utils::initialize_logger(module_path!(), false, true)?;
+
+    let Options {
+        address,
+        port,
+        new_payload_status,
+        forkchoice_updated_status,
+        delay,
+        replay,
+    } = Options::parse();
+
+    let delay = Duration::from_millis(delay);
+    let engine = MockEngine::bind(SocketAddr::new(address, port))?;
+
+    engine.set_new_payload_status(ScriptedStatus::new(new_payload_status).with_delay(delay));
+
+    engine.set_forkchoice_updated_status(
+        ScriptedStatus::new(forkchoice_updated_status).with_delay(delay),
+    );
+
+    if let Some(path) = replay {
+        let recording = Recording::load(&path)?;
+
+        info!("replaying {} exchanges from {path:?}", recording.len());
+
+        engine.replay(recording);
+    }
+
+    tokio::signal::ctrl_c().await?;
+
+    info!("received Ctrl+C; stopping mock execution engine");
+
+    Ok(())
+}
+
+fn parse_status(string: &str) -> Result<PayloadValidationStatus> {
+    serde_json::from_value(Value::String(string.to_uppercase())).map_err(Into::into)
+}
@@ -0,0 +1,98 @@
+use core::time::Duration;
+use std::{
+    collections::VecDeque,
+    io::{BufRead as _, BufReader},
+    path::Path,
+};
+
+use anyhow::{Context as _, Result};
+use fs_err::File;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Engine API exchanges recorded by `eth1_api::Recorder`.
+///
+/// Each request is answered with the first unreplayed exchange that has the same method and
+/// parameters. If there is none, the first unreplayed exchange with the same method is used
+/// instead, which makes it possible to replay sessions against nodes whose state differs slightly
+/// from the one that was recorded. Requests that match no exchange at all fall back to scripted
+/// responses.
+#[derive(Default, Debug)]
+pub struct Recording {
+    exchanges: VecDeque<RecordedExchange>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let mut exchanges = VecDeque::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let exchange = serde_json::from_str(&line)
+                .with_context(|| format!("invalid exchange on line {} of {path:?}", index + 1))?;
+
+            exchanges.push_back(exchange);
+        }
+
+        Ok(Self { exchanges })
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.exchanges.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.exchanges.is_empty()
+    }
+
+    pub(crate) fn take(&mut self, method: &str, params: &[Value]) -> Option<RecordedExchange> {
+        let index = self
+            .exchanges
+            .iter()
+            .position(|exchange| exchange.method == method && exchange.params == params)
+            .or_else(|| {
+                self.exchanges
+                    .iter()
+                    .position(|exchange| exchange.method == method)
+            })?;
+
+        self.exchanges.remove(index)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordedExchange {
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(flatten)]
+    outcome: RecordedOutcome,
+}
+
+impl RecordedExchange {
+    pub(crate) const fn delay(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    pub(crate) fn into_outcome(self) -> RecordedOutcome {
+        self.outcome
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedOutcome {
+    Result(Value),
+    Error { code: Option<i64>, message: String },
+}
@@ -0,0 +1,173 @@
+use core::time::Duration;
+use std::collections::{HashMap, VecDeque};
+
+use execution_engine::{PayloadStatusV1, PayloadValidationStatus};
+use serde_json::{json, Value};
+use types::{bellatrix::primitives::Gas, phase0::primitives::ExecutionBlockHash};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScriptedStatus {
+    pub status: PayloadValidationStatus,
+    // `None` is replaced with the hash of the block being validated if `status` is `VALID`.
+    pub latest_valid_hash: Option<ExecutionBlockHash>,
+    pub validation_error: Option<String>,
+    pub delay: Duration,
+}
+
+impl Default for ScriptedStatus {
+    fn default() -> Self {
+        Self::new(PayloadValidationStatus::Valid)
+    }
+}
+
+impl ScriptedStatus {
+    #[must_use]
+    pub const fn new(status: PayloadValidationStatus) -> Self {
+        Self {
+            status,
+            latest_valid_hash: None,
+            validation_error: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_latest_valid_hash(mut self, latest_valid_hash: ExecutionBlockHash) -> Self {
+        self.latest_valid_hash = Some(latest_valid_hash);
+        self
+    }
+
+    #[must_use]
+    pub fn with_validation_error(mut self, validation_error: impl Into<String>) -> Self {
+        self.validation_error = Some(validation_error.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub(crate) fn payload_status(&self, block_hash: ExecutionBlockHash) -> PayloadStatusV1 {
+        let latest_valid_hash = self
+            .latest_valid_hash
+            .or_else(|| self.status.is_valid().then_some(block_hash));
+
+        PayloadStatusV1 {
+            status: self.status,
+            latest_valid_hash,
+            validation_error: self.validation_error.clone(),
+        }
+    }
+}
+
+/// A blobs bundle returned by `engine_getPayloadV3` in place of the default empty one.
+///
+/// The bundle is not checked in any way. `blob_gas_used` is passed through to payloads unchanged.
+/// This allows tests to make the mock engine return bundles that are inconsistent with payloads.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScriptedBlobsBundle {
+    pub commitments: Vec<Value>,
+    pub proofs: Vec<Value>,
+    pub blobs: Vec<Value>,
+    pub blob_gas_used: Gas,
+}
+
+impl ScriptedBlobsBundle {
+    pub(crate) fn blobs_bundle(&self) -> Value {
+        json!({
+            "commitments": self.commitments,
+            "proofs": self.proofs,
+            "blobs": self.blobs,
+        })
+    }
+
+    pub(crate) fn blob_gas_used(&self) -> String {
+        format!("{:#x}", self.blob_gas_used)
+    }
+}
+
+/// Responses to a single Engine API method.
+///
+/// Statuses set for specific block hashes take priority over queued ones.
+/// Queued statuses are used once each in the order they were pushed.
+/// The default status is used when there is nothing else to use.
+#[derive(Default, Debug)]
+pub struct MethodScript {
+    pub default: ScriptedStatus,
+    pub queued: VecDeque<ScriptedStatus>,
+    pub by_block_hash: HashMap<ExecutionBlockHash, ScriptedStatus>,
+}
+
+impl MethodScript {
+    pub(crate) fn next(&mut self, block_hash: ExecutionBlockHash) -> ScriptedStatus {
+        if let Some(scripted) = self.by_block_hash.get(&block_hash) {
+            return scripted.clone();
+        }
+
+        self.queued
+            .pop_front()
+            .unwrap_or_else(|| self.default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_script_prefers_block_hashes_then_queue_then_default() {
+        let specific_hash = ExecutionBlockHash::repeat_byte(1);
+        let other_hash = ExecutionBlockHash::repeat_byte(2);
+
+        let mut script = MethodScript {
+            default: ScriptedStatus::new(PayloadValidationStatus::Syncing),
+            queued: [ScriptedStatus::new(PayloadValidationStatus::Accepted)].into(),
+            by_block_hash: [(
+                specific_hash,
+                ScriptedStatus::new(PayloadValidationStatus::Invalid),
+            )]
+            .into(),
+        };
+
+        assert_eq!(
+            script.next(specific_hash).status,
+            PayloadValidationStatus::Invalid,
+        );
+
+        assert_eq!(
+            script.next(other_hash).status,
+            PayloadValidationStatus::Accepted,
+        );
+
+        assert_eq!(
+            script.next(other_hash).status,
+            PayloadValidationStatus::Syncing,
+        );
+
+        assert_eq!(
+            script.next(specific_hash).status,
+            PayloadValidationStatus::Invalid,
+        );
+    }
+
+    #[test]
+    fn latest_valid_hash_defaults_to_block_hash_only_for_valid_payloads() {
+        let block_hash = ExecutionBlockHash::repeat_byte(1);
+
+        assert_eq!(
+            ScriptedStatus::default()
+                .payload_status(block_hash)
+                .latest_valid_hash,
+            Some(block_hash),
+        );
+
+        assert_eq!(
+            ScriptedStatus::new(PayloadValidationStatus::Syncing)
+                .payload_status(block_hash)
+                .latest_valid_hash,
+            None,
+        );
+    }
+}
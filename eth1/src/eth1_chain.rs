@@ -1,8 +1,11 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
 use anyhow::{Context as _, Error as AnyhowError, Result};
 use database::Database;
-use deposit_tree::DepositTree;
+use deposit_tree::{DepositTree, IncrementalDepositTree};
 use eth1_api::{Auth, DepositEvent, Eth1ApiToMetrics, Eth1Block};
 use futures::{
     channel::mpsc::UnboundedSender,
@@ -33,11 +36,15 @@ pub struct Eth1Config {
     pub eth1_rpc_urls: Vec<Url>,
     pub deposit_contract_starting_block: Option<ExecutionBlockNumber>,
     pub default_deposit_tree: Option<DepositTree>,
+    pub eth1_api_recording_file: Option<PathBuf>,
 }
 
 pub struct Eth1Chain {
     cache: Arc<Eth1Cache>,
     unfinalized_blocks: Arc<RwLock<Vec<Eth1Block>>>,
+    // Contains deposits from `unfinalized_blocks` on top of the finalized deposit tree.
+    // Only the finalized part is persisted. The rest is rebuilt from cached blocks on startup.
+    deposit_tree: Arc<RwLock<IncrementalDepositTree>>,
 }
 
 impl Eth1Chain {
@@ -53,9 +60,12 @@ impl Eth1Chain {
             .map(Arc::new)
             .context("failed to create Eth1 cache database environment")?;
 
+        let deposit_tree = cache.get_deposit_tree()?.ok_or(Error)?;
+
         let chain = Self {
             cache,
             unfinalized_blocks: Arc::new(RwLock::new(vec![])),
+            deposit_tree: Arc::new(RwLock::new(IncrementalDepositTree::new(deposit_tree))),
         };
 
         if !eth1_config.eth1_rpc_urls.is_empty() {
@@ -76,12 +86,24 @@ impl Eth1Chain {
         &self.unfinalized_blocks
     }
 
+    #[must_use]
+    pub fn deposit_tree(&self) -> &RwLock<IncrementalDepositTree> {
+        &self.deposit_tree
+    }
+
     pub fn add_deposits(
         &self,
         deposit_events: Vec<&DepositEvent>,
         block_number: ExecutionBlockNumber,
     ) -> Result<()> {
-        self.cache.add_deposits(deposit_events, block_number)
+        self.cache.add_deposits(deposit_events, block_number)?;
+
+        let finalized_deposit_tree = self.load_deposit_tree()?;
+
+        self.deposit_tree
+            .write()
+            .expect("deposit tree lock is poisoned")
+            .finalize(finalized_deposit_tree)
     }
 
     pub fn load_deposit_tree(&self) -> Result<DepositTree> {
@@ -123,12 +145,18 @@ impl Eth1Chain {
 
     pub fn spawn_unfinalized_blocks_tracker_task(&self) -> Result<()> {
         let unfinalized_blocks = self.unfinalized_blocks.clone_arc();
+        let incremental_deposit_tree = self.deposit_tree.clone_arc();
         let cache = self.cache.clone_arc();
         let deposit_tree = self.load_deposit_tree()?;
 
         tokio::spawn(async move {
-            if let Err(error) =
-                run_unfinalized_blocks_tracker(unfinalized_blocks, cache, deposit_tree).await
+            if let Err(error) = run_unfinalized_blocks_tracker(
+                unfinalized_blocks,
+                incremental_deposit_tree,
+                cache,
+                deposit_tree,
+            )
+            .await
             {
                 panic!("failed to update unfinalized Eth1 block list: {error}");
             }
@@ -245,6 +273,7 @@ fn handle_error(error: &AnyhowError) {
 
 async fn run_unfinalized_blocks_tracker(
     unfinalized_blocks: Arc<RwLock<Vec<Eth1Block>>>,
+    incremental_deposit_tree: Arc<RwLock<IncrementalDepositTree>>,
     cache: Arc<Eth1Cache>,
     deposit_tree: DepositTree,
 ) -> Result<()> {
@@ -266,10 +295,24 @@ async fn run_unfinalized_blocks_tracker(
 
             features::log!(DebugEth1, "read Eth1 block from cache: {}", block.number);
 
-            unfinalized_blocks
+            // Lock both at once so that readers never see deposits missing from one of them.
+            let mut unfinalized_blocks = unfinalized_blocks
                 .write()
-                .expect("unfinalized blocks lock is poisoned")
-                .push(block);
+                .expect("unfinalized blocks lock is poisoned");
+
+            let mut incremental_deposit_tree = incremental_deposit_tree
+                .write()
+                .expect("deposit tree lock is poisoned");
+
+            // Deposits may already be in the finalized deposit tree if they were
+            // added to it by `DownloadManager` before the application was restarted.
+            for DepositEvent { data, index } in block.deposit_events.iter().copied() {
+                if incremental_deposit_tree.deposit_count() <= index {
+                    incremental_deposit_tree.push(index, data)?;
+                }
+            }
+
+            unfinalized_blocks.push(block);
         }
 
         tokio::time::sleep(Duration::from_secs(1)).await;
@@ -0,0 +1,67 @@
+use anyhow::{ensure, Result};
+use execution_engine::EngineGetPayloadV3Response;
+use thiserror::Error;
+use types::{bellatrix::primitives::Gas, preset::Preset};
+
+// See <https://eips.ethereum.org/EIPS/eip-4844#parameters>.
+const GAS_PER_BLOB: Gas = 1 << 17;
+
+// Commitments from the bundle are included in the block body as is and the blobs are published in
+// sidecars. A block built from an inconsistent bundle would be rejected by the rest of the network,
+// so bundles are checked before they reach the validator.
+pub fn validate<P: Preset>(response: &EngineGetPayloadV3Response<P>) -> Result<()> {
+    let blob_gas_used = response.execution_payload.blob_gas_used;
+    let commitments = &response.blobs_bundle.commitments;
+    let proofs = &response.blobs_bundle.proofs;
+    let blobs = &response.blobs_bundle.blobs;
+
+    ensure!(
+        commitments.len() == proofs.len() && commitments.len() == blobs.len(),
+        Error::LengthMismatch {
+            commitments: commitments.len(),
+            proofs: proofs.len(),
+            blobs: blobs.len(),
+        },
+    );
+
+    let expected_blob_gas_used = Gas::try_from(commitments.len())? * GAS_PER_BLOB;
+
+    ensure!(
+        blob_gas_used == expected_blob_gas_used,
+        Error::BlobGasUsedMismatch {
+            expected: expected_blob_gas_used,
+            actual: blob_gas_used,
+        },
+    );
+
+    if blobs.is_empty() {
+        return Ok(());
+    }
+
+    let proofs_valid = kzg_utils::eip_4844::verify_blob_kzg_proof_batch::<P>(
+        blobs.iter(),
+        commitments.iter().copied(),
+        proofs.iter().copied(),
+    )?;
+
+    ensure!(proofs_valid, Error::InvalidProofs);
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error(
+        "blobs bundle contains {commitments} commitments, {proofs} proofs and {blobs} blobs \
+         (all counts should be equal)"
+    )]
+    LengthMismatch {
+        commitments: usize,
+        proofs: usize,
+        blobs: usize,
+    },
+    #[error("execution payload uses {actual} blob gas but blobs bundle requires {expected}")]
+    BlobGasUsedMismatch { expected: Gas, actual: Gas },
+    #[error("KZG proofs in blobs bundle do not match its blobs and commitments")]
+    InvalidProofs,
+}
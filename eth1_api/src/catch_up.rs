@@ -0,0 +1,198 @@
+// A dedicated path for importing blocks while the execution engine is far behind.
+//
+// An execution engine that is syncing answers `engine_newPayload` with `SYNCING` without doing any
+// useful work. Sending it every payload only delays the point at which it catches up, and each of
+// those payloads has to be validated again once it does. After enough consecutive `SYNCING`
+// responses, payloads of imported blocks are no longer sent. Instead, the engine is periodically
+// pointed at the fork choice head with `engine_forkchoiceUpdated`, which lets it sync directly
+// from its peers. Blocks remain optimistic in fork choice until the engine reports a `VALID` head.
+// Once it does, payloads of withheld blocks that are still optimistic are sent again so that
+// `INVALID` statuses are attributed to the blocks that contain the payloads.
+
+use core::time::Duration;
+use std::time::Instant;
+
+use execution_engine::PayloadValidationStatus;
+use types::phase0::primitives::H256;
+
+// A handful of `SYNCING` responses is normal right after an execution engine restarts.
+const SYNCING_RESPONSES_BEFORE_CATCH_UP: u64 = 32;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Transition {
+    Entered,
+    Exited { deferred_block_roots: Vec<H256> },
+}
+
+#[derive(Default)]
+pub struct CatchUp {
+    consecutive_syncing_responses: u64,
+    catching_up: Option<CatchingUp>,
+}
+
+#[derive(Default)]
+struct CatchingUp {
+    last_checkpoint_sent_at: Option<Instant>,
+    // Roots of blocks whose payloads were not sent.
+    // Payloads are looked up again when catch-up ends to avoid keeping them in memory.
+    deferred_block_roots: Vec<H256>,
+}
+
+impl CatchUp {
+    #[must_use]
+    pub const fn is_active(&self) -> bool {
+        self.catching_up.is_some()
+    }
+
+    pub fn on_new_payload_status(&mut self, status: PayloadValidationStatus) -> Option<Transition> {
+        if status.is_syncing() {
+            self.consecutive_syncing_responses += 1;
+
+            if self.catching_up.is_none()
+                && self.consecutive_syncing_responses >= SYNCING_RESPONSES_BEFORE_CATCH_UP
+            {
+                self.catching_up = Some(CatchingUp::default());
+                return Some(Transition::Entered);
+            }
+
+            return None;
+        }
+
+        self.consecutive_syncing_responses = 0;
+
+        // `ACCEPTED` means the payload is on a side branch. It says nothing about sync progress.
+        if status == PayloadValidationStatus::Accepted {
+            return None;
+        }
+
+        self.exit()
+    }
+
+    pub fn on_forkchoice_updated_status(
+        &mut self,
+        status: PayloadValidationStatus,
+    ) -> Option<Transition> {
+        if status.is_syncing() || status == PayloadValidationStatus::Accepted {
+            return None;
+        }
+
+        self.consecutive_syncing_responses = 0;
+        self.exit()
+    }
+
+    /// Records that the payload of the block with root `beacon_block_root` was not sent to the
+    /// execution engine.
+    ///
+    /// Returns `true` if `interval` has passed since the last checkpoint was sent.
+    pub fn defer_payload(
+        &mut self,
+        beacon_block_root: H256,
+        now: Instant,
+        interval: Duration,
+    ) -> bool {
+        let Some(catching_up) = self.catching_up.as_mut() else {
+            return false;
+        };
+
+        catching_up.deferred_block_roots.push(beacon_block_root);
+
+        let due = catching_up.last_checkpoint_sent_at.map_or(true, |sent_at| {
+            now.saturating_duration_since(sent_at) >= interval
+        });
+
+        if due {
+            catching_up.last_checkpoint_sent_at = Some(now);
+        }
+
+        due
+    }
+
+    fn exit(&mut self) -> Option<Transition> {
+        let CatchingUp {
+            deferred_block_roots,
+            ..
+        } = self.catching_up.take()?;
+
+        Some(Transition::Exited {
+            deferred_block_roots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(12);
+
+    fn catching_up() -> CatchUp {
+        let mut catch_up = CatchUp::default();
+
+        for _ in 1..SYNCING_RESPONSES_BEFORE_CATCH_UP {
+            assert_eq!(
+                catch_up.on_new_payload_status(PayloadValidationStatus::Syncing),
+                None,
+            );
+        }
+
+        assert_eq!(
+            catch_up.on_new_payload_status(PayloadValidationStatus::Syncing),
+            Some(Transition::Entered),
+        );
+
+        catch_up
+    }
+
+    #[test]
+    fn valid_payload_resets_count_of_syncing_responses() {
+        let mut catch_up = CatchUp::default();
+
+        for _ in 1..SYNCING_RESPONSES_BEFORE_CATCH_UP {
+            catch_up.on_new_payload_status(PayloadValidationStatus::Syncing);
+        }
+
+        assert_eq!(
+            catch_up.on_new_payload_status(PayloadValidationStatus::Valid),
+            None,
+        );
+        assert_eq!(
+            catch_up.on_new_payload_status(PayloadValidationStatus::Syncing),
+            None,
+        );
+        assert!(!catch_up.is_active());
+    }
+
+    #[test]
+    fn checkpoints_are_due_at_intervals() {
+        let mut catch_up = catching_up();
+        let start = Instant::now();
+        let root = H256::repeat_byte;
+
+        assert!(catch_up.defer_payload(root(1), start, INTERVAL));
+        assert!(!catch_up.defer_payload(root(2), start, INTERVAL));
+        assert!(catch_up.defer_payload(root(3), start + INTERVAL, INTERVAL));
+    }
+
+    #[test]
+    fn valid_forkchoice_update_ends_catch_up_and_returns_deferred_blocks() {
+        let mut catch_up = catching_up();
+        let now = Instant::now();
+        let root = H256::repeat_byte;
+
+        catch_up.defer_payload(root(1), now, INTERVAL);
+        catch_up.defer_payload(root(2), now, INTERVAL);
+
+        assert_eq!(
+            catch_up.on_forkchoice_updated_status(PayloadValidationStatus::Syncing),
+            None,
+        );
+        assert_eq!(
+            catch_up.on_forkchoice_updated_status(PayloadValidationStatus::Valid),
+            Some(Transition::Exited {
+                deferred_block_roots: vec![root(1), root(2)],
+            }),
+        );
+        assert!(!catch_up.is_active());
+        assert!(!catch_up.defer_payload(root(3), now, INTERVAL));
+    }
+}
@@ -0,0 +1,411 @@
+// These tests run `Eth1Api` against `engine_api_mock::MockEngine` over HTTP.
+// Unlike the tests in `eth1_api`, they cover the requests sent as well as the responses.
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use anyhow::Result;
+use either::Either;
+use engine_api_mock::{MockEngine, Recording, ScriptedBlobsBundle, ScriptedStatus};
+use ethereum_types::H64;
+use execution_engine::{
+    PayloadAttributes, PayloadAttributesV1, PayloadAttributesV2, PayloadAttributesV3, PayloadId,
+    PayloadValidationStatus,
+};
+use reqwest::Client;
+use serde_json::json;
+use ssz::ContiguousList;
+use tempfile::NamedTempFile;
+use test_case::test_case;
+use typenum::Unsigned as _;
+use types::{
+    bellatrix::containers::ExecutionPayload as BellatrixExecutionPayload,
+    combined::ExecutionPayload,
+    config::Config,
+    deneb::primitives::Blob,
+    nonstandard::WithBlobsAndMev,
+    phase0::primitives::{ExecutionAddress, ExecutionBlockHash, H256},
+    preset::{Mainnet, Preset},
+};
+
+use crate::{Auth, AuthOptions, Eth1Api, Recorder};
+
+#[tokio::test]
+async fn new_payload_returns_scripted_statuses_in_order() -> Result<()> {
+    let engine = MockEngine::start()?;
+    let eth1_api = eth1_api(&engine, Client::new());
+    let latest_valid_hash = ExecutionBlockHash::repeat_byte(1);
+
+    engine.push_new_payload_status(ScriptedStatus::new(PayloadValidationStatus::Valid));
+    engine.push_new_payload_status(ScriptedStatus::new(PayloadValidationStatus::Syncing));
+
+    engine.push_new_payload_status(
+        ScriptedStatus::new(PayloadValidationStatus::Invalid)
+            .with_latest_valid_hash(latest_valid_hash)
+            .with_validation_error("invalid state root"),
+    );
+
+    let valid = eth1_api.new_payload(default_payload(), None).await?;
+    let syncing = eth1_api.new_payload(default_payload(), None).await?;
+    let invalid = eth1_api.new_payload(default_payload(), None).await?;
+
+    assert_eq!(valid.status, PayloadValidationStatus::Valid);
+    assert_eq!(valid.latest_valid_hash, Some(ExecutionBlockHash::zero()));
+    assert_eq!(syncing.status, PayloadValidationStatus::Syncing);
+    assert_eq!(syncing.latest_valid_hash, None);
+    assert_eq!(invalid.status, PayloadValidationStatus::Invalid);
+    assert_eq!(invalid.latest_valid_hash, Some(latest_valid_hash));
+
+    assert_eq!(
+        invalid.validation_error.as_deref(),
+        Some("invalid state root"),
+    );
+
+    assert!(engine
+        .requests()
+        .iter()
+        .all(|request| request.method == "engine_newPayloadV1"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn forkchoice_updated_and_get_payload_round_trip_in_every_phase() -> Result<()> {
+    let engine = MockEngine::start()?;
+    let eth1_api = eth1_api(&engine, Client::new());
+    let head_block_hash = ExecutionBlockHash::repeat_byte(1);
+    let finalized_block_hash = ExecutionBlockHash::repeat_byte(2);
+    let prev_randao = H256::repeat_byte(3);
+    let suggested_fee_recipient = ExecutionAddress::repeat_byte(4);
+    let timestamp = 1_700_000_000;
+
+    let attributes = [
+        PayloadAttributes::Bellatrix(PayloadAttributesV1 {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+        }),
+        PayloadAttributes::Capella(PayloadAttributesV2 {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: ContiguousList::default(),
+        }),
+        PayloadAttributes::Deneb(PayloadAttributesV3 {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: ContiguousList::default(),
+            parent_beacon_block_root: H256::repeat_byte(5),
+        }),
+    ];
+
+    for payload_attributes in attributes {
+        let phase = payload_attributes.phase();
+
+        let response = eth1_api
+            .forkchoice_updated::<Mainnet>(
+                head_block_hash,
+                finalized_block_hash,
+                finalized_block_hash,
+                Either::Right(payload_attributes),
+            )
+            .await?;
+
+        assert_eq!(
+            response.payload_status.status,
+            PayloadValidationStatus::Valid,
+        );
+
+        let payload_id = response
+            .payload_id
+            .expect("mock engine should start building a payload on a valid head");
+
+        let payload = eth1_api.get_payload::<Mainnet>(payload_id).await?.value;
+
+        assert_eq!(payload.phase(), phase);
+
+        match payload {
+            ExecutionPayload::Bellatrix(payload) => {
+                assert_eq!(payload.parent_hash, head_block_hash);
+                assert_eq!(payload.fee_recipient, suggested_fee_recipient);
+                assert_eq!(payload.prev_randao, prev_randao);
+                assert_eq!(payload.timestamp, timestamp);
+            }
+            ExecutionPayload::Capella(payload) => {
+                assert_eq!(payload.parent_hash, head_block_hash);
+                assert_eq!(payload.fee_recipient, suggested_fee_recipient);
+                assert_eq!(payload.prev_randao, prev_randao);
+                assert_eq!(payload.timestamp, timestamp);
+            }
+            ExecutionPayload::Deneb(payload) => {
+                assert_eq!(payload.parent_hash, head_block_hash);
+                assert_eq!(payload.fee_recipient, suggested_fee_recipient);
+                assert_eq!(payload.prev_randao, prev_randao);
+                assert_eq!(payload.timestamp, timestamp);
+            }
+        }
+    }
+
+    let methods = engine
+        .requests()
+        .into_iter()
+        .map(|request| request.method)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        methods,
+        [
+            "engine_forkchoiceUpdatedV1",
+            "engine_getPayloadV1",
+            "engine_forkchoiceUpdatedV2",
+            "engine_getPayloadV2",
+            "engine_forkchoiceUpdatedV3",
+            "engine_getPayloadV3",
+        ],
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn forkchoice_updated_to_syncing_head_does_not_start_payload() -> Result<()> {
+    let engine = MockEngine::start()?;
+    let eth1_api = eth1_api(&engine, Client::new());
+    let head_block_hash = ExecutionBlockHash::repeat_byte(1);
+
+    engine.set_forkchoice_updated_status_for(
+        head_block_hash,
+        ScriptedStatus::new(PayloadValidationStatus::Syncing),
+    );
+
+    let payload_attributes = PayloadAttributes::Bellatrix(PayloadAttributesV1 {
+        timestamp: 1_700_000_000,
+        prev_randao: H256::zero(),
+        suggested_fee_recipient: ExecutionAddress::zero(),
+    });
+
+    let response = eth1_api
+        .forkchoice_updated::<Mainnet>(
+            head_block_hash,
+            ExecutionBlockHash::zero(),
+            ExecutionBlockHash::zero(),
+            Either::Right(payload_attributes),
+        )
+        .await?;
+
+    assert_eq!(
+        response.payload_status.status,
+        PayloadValidationStatus::Syncing,
+    );
+
+    assert!(response.payload_id.is_none());
+
+    // Payload IDs that the engine never returned are rejected.
+    let unknown_payload_id = PayloadId::Bellatrix(H64::from_low_u64_be(1));
+
+    assert!(eth1_api
+        .get_payload::<Mainnet>(unknown_payload_id)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delayed_responses_exceed_client_timeout() -> Result<()> {
+    let engine = MockEngine::start()?;
+    let client = Client::builder()
+        .timeout(Duration::from_millis(100))
+        .build()?;
+    let eth1_api = eth1_api(&engine, client);
+
+    engine.push_new_payload_status(ScriptedStatus::default().with_delay(Duration::from_secs(1)));
+
+    assert!(eth1_api
+        .new_payload::<Mainnet>(default_payload(), None)
+        .await
+        .is_err());
+
+    // The delay only applied to the first response.
+    let status = eth1_api
+        .new_payload::<Mainnet>(default_payload(), None)
+        .await?;
+
+    assert_eq!(status.status, PayloadValidationStatus::Valid);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn recorded_session_can_be_replayed_with_jwt_redacted() -> Result<()> {
+    let secret_file = NamedTempFile::new()?;
+    let recording_file = NamedTempFile::new()?;
+
+    fs_err::write(
+        secret_file.path(),
+        "a8ecf8012460d00d11a5bd65165c192f705d1ef759afdda5e9db0f2cd29bbf11",
+    )?;
+
+    let auth = Auth::new(AuthOptions {
+        secrets_path: Some(secret_file.path().to_owned()),
+        ..AuthOptions::default()
+    })?;
+
+    let recording_engine = MockEngine::start()?;
+    let recorder = Recorder::create(recording_file.path())?;
+
+    let recording_eth1_api = Eth1Api::new(
+        Arc::new(Config::mainnet()),
+        Client::new(),
+        Arc::new(auth),
+        vec![recording_engine.url().clone()],
+        None,
+        None,
+    )
+    .with_recorder(Some(Arc::new(recorder)));
+
+    recording_engine.push_new_payload_status(ScriptedStatus::new(PayloadValidationStatus::Syncing));
+
+    recording_eth1_api
+        .new_payload::<Mainnet>(default_payload(), None)
+        .await?;
+
+    let recorded = fs_err::read_to_string(recording_file.path())?;
+
+    assert!(recorded.contains(r#""authorization":"[REDACTED]""#));
+    assert!(!recorded.contains("Bearer"));
+
+    // The replaying engine would respond with `VALID` if it used its script.
+    let replaying_engine = MockEngine::start()?;
+
+    replaying_engine.replay(Recording::load(recording_file.path())?);
+
+    let status = eth1_api(&replaying_engine, Client::new())
+        .new_payload::<Mainnet>(default_payload(), None)
+        .await?;
+
+    assert_eq!(status.status, PayloadValidationStatus::Syncing);
+    assert_eq!(replaying_engine.unreplayed_exchanges(), 0);
+
+    Ok(())
+}
+
+#[test_case(0)]
+#[test_case(1)]
+#[test_case(3)]
+#[tokio::test]
+async fn get_payload_accepts_consistent_blobs_bundle(blob_count: u8) -> Result<()> {
+    let engine = MockEngine::start()?;
+    let eth1_api = eth1_api(&engine, Client::new());
+
+    engine.set_blobs_bundle(consistent_blobs_bundle(blob_count)?);
+
+    let payload = deneb_payload(&eth1_api).await?;
+
+    assert_eq!(
+        payload.commitments.map(|commitments| commitments.len()),
+        Some(blob_count.into()),
+    );
+
+    Ok(())
+}
+
+#[test_case(|bundle| drop(bundle.commitments.pop()); "missing commitment")]
+#[test_case(|bundle| drop(bundle.proofs.pop()); "missing proof")]
+#[test_case(|bundle| drop(bundle.blobs.pop()); "missing blob")]
+#[test_case(|bundle| bundle.blobs.push(bundle.blobs[0].clone()); "extra blob")]
+#[test_case(|bundle| bundle.commitments.swap(0, 1); "commitments in wrong order")]
+#[test_case(|bundle| bundle.proofs.swap(0, 1); "proofs in wrong order")]
+#[test_case(|bundle| bundle.proofs[1] = bundle.proofs[0].clone(); "proof for another blob")]
+#[test_case(|bundle| bundle.blob_gas_used /= 2; "blob gas used for fewer blobs")]
+#[test_case(|bundle| bundle.blob_gas_used *= 2; "blob gas used for more blobs")]
+#[test_case(|bundle| bundle.blob_gas_used += 1; "blob gas used not a multiple of blob gas")]
+#[tokio::test]
+async fn get_payload_rejects_malformed_blobs_bundle(
+    malform: fn(&mut ScriptedBlobsBundle),
+) -> Result<()> {
+    let engine = MockEngine::start()?;
+    let eth1_api = eth1_api(&engine, Client::new());
+    let mut blobs_bundle = consistent_blobs_bundle(2)?;
+
+    malform(&mut blobs_bundle);
+
+    engine.set_blobs_bundle(blobs_bundle);
+
+    assert!(deneb_payload(&eth1_api).await.is_err());
+
+    Ok(())
+}
+
+fn eth1_api(engine: &MockEngine, client: Client) -> Eth1Api {
+    Eth1Api::new(
+        Arc::new(Config::mainnet()),
+        client,
+        Arc::default(),
+        vec![engine.url().clone()],
+        None,
+        None,
+    )
+}
+
+fn default_payload() -> ExecutionPayload<Mainnet> {
+    BellatrixExecutionPayload::default().into()
+}
+
+async fn deneb_payload(
+    eth1_api: &Eth1Api,
+) -> Result<WithBlobsAndMev<ExecutionPayload<Mainnet>, Mainnet>> {
+    let payload_attributes = PayloadAttributes::Deneb(PayloadAttributesV3 {
+        timestamp: 1_700_000_000,
+        prev_randao: H256::zero(),
+        suggested_fee_recipient: ExecutionAddress::zero(),
+        withdrawals: ContiguousList::default(),
+        parent_beacon_block_root: H256::zero(),
+    });
+
+    let payload_id = eth1_api
+        .forkchoice_updated::<Mainnet>(
+            ExecutionBlockHash::repeat_byte(1),
+            ExecutionBlockHash::zero(),
+            ExecutionBlockHash::zero(),
+            Either::Right(payload_attributes),
+        )
+        .await?
+        .payload_id
+        .expect("mock engine should start building a payload on a valid head");
+
+    eth1_api.get_payload(payload_id).await
+}
+
+fn consistent_blobs_bundle(blob_count: u8) -> Result<ScriptedBlobsBundle> {
+    let mut commitments = vec![];
+    let mut proofs = vec![];
+    let mut blobs = vec![];
+
+    for index in 0..blob_count {
+        // Every 32 byte chunk of a blob must be a canonical field element.
+        // Setting only the last byte of each chunk is a simple way to satisfy that.
+        let field_element = format!("{}{index:02x}", "00".repeat(31));
+        let blob_hex = format!(
+            "0x{}",
+            field_element.repeat(<Mainnet as Preset>::FieldElementsPerBlob::USIZE),
+        );
+
+        let blob = serde_json::from_value::<Blob<Mainnet>>(json!(blob_hex))?;
+        let commitment = kzg_utils::eip_4844::blob_to_kzg_commitment::<Mainnet>(&blob)?;
+        let proof = kzg_utils::eip_4844::compute_blob_kzg_proof::<Mainnet>(&blob, commitment)?;
+
+        commitments.push(json!(commitment));
+        proofs.push(json!(proof));
+        blobs.push(json!(blob_hex));
+    }
+
+    Ok(ScriptedBlobsBundle {
+        commitments,
+        proofs,
+        blobs,
+        // `GAS_PER_BLOB` from EIP-4844.
+        blob_gas_used: u64::from(blob_count) << 17,
+    })
+}
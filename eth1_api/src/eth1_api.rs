@@ -6,9 +6,9 @@ use either::Either;
 use enum_iterator::Sequence as _;
 use ethereum_types::H64;
 use execution_engine::{
-    EngineGetPayloadV1Response, EngineGetPayloadV2Response, EngineGetPayloadV3Response,
-    ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, ForkChoiceStateV1,
-    ForkChoiceUpdatedResponse, PayloadAttributes, PayloadId, PayloadStatusV1,
+    BlobAndProofV1, EngineGetPayloadV1Response, EngineGetPayloadV2Response,
+    EngineGetPayloadV3Response, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3,
+    ForkChoiceStateV1, ForkChoiceUpdatedResponse, PayloadAttributes, PayloadId, PayloadStatusV1,
 };
 use futures::{channel::mpsc::UnboundedSender, lock::Mutex, Future};
 use log::warn;
@@ -22,6 +22,7 @@ use thiserror::Error;
 use types::{
     combined::{ExecutionPayload, ExecutionPayloadParams},
     config::Config,
+    deneb::primitives::VersionedHash,
     nonstandard::{Phase, WithBlobsAndMev},
     phase0::primitives::{ExecutionBlockHash, ExecutionBlockNumber},
     preset::Preset,
@@ -35,8 +36,8 @@ use web3::{
 };
 
 use crate::{
-    auth::Auth, deposit_event::DepositEvent, eth1_block::Eth1Block, Eth1ApiToMetrics,
-    Eth1ConnectionData,
+    auth::Auth, blobs_bundle, deposit_event::DepositEvent, eth1_block::Eth1Block,
+    recorder::Recorder, Eth1ApiToMetrics, Eth1ConnectionData,
 };
 
 #[allow(clippy::struct_field_names)]
@@ -46,8 +47,10 @@ pub struct Eth1Api {
     auth: Arc<Auth>,
     original: Vec<Url>,
     endpoints: Mutex<IntoIter<Url>>,
+    last_error: Mutex<Option<String>>,
     eth1_api_to_metrics_tx: Option<UnboundedSender<Eth1ApiToMetrics>>,
     metrics: Option<Arc<Metrics>>,
+    recorder: Option<Arc<Recorder>>,
 }
 
 impl Eth1Api {
@@ -66,11 +69,20 @@ impl Eth1Api {
             auth,
             original: eth1_rpc_urls.clone(),
             endpoints: Mutex::new(eth1_rpc_urls.into_iter()),
+            last_error: Mutex::default(),
             eth1_api_to_metrics_tx,
             metrics,
+            recorder: None,
         }
     }
 
+    /// Records requests made through [`Eth1Api::execute`] (that is, Engine API requests).
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Option<Arc<Recorder>>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
     pub async fn current_head_number(&self) -> Result<ExecutionBlockNumber> {
         Ok(self
             .request_with_fallback(|(api, headers)| Ok(api.block_number(headers)))
@@ -328,13 +340,45 @@ impl Eth1Api {
             PayloadId::Deneb(payload_id) => {
                 let params = vec![serde_json::to_value(payload_id)?];
 
-                self.execute::<EngineGetPayloadV3Response<P>>("engine_getPayloadV3", params)
-                    .await
-                    .map(Into::into)
+                let response = self
+                    .execute::<EngineGetPayloadV3Response<P>>("engine_getPayloadV3", params)
+                    .await?;
+
+                blobs_bundle::validate(&response)?;
+
+                Ok(response.into())
             }
         }
     }
 
+    /// Calls [`engine_getBlobsV1`].
+    ///
+    /// The response contains an entry for every versioned hash in `versioned_hashes`.
+    /// Entries are `None` for blobs missing from the transaction pool of the execution client.
+    ///
+    /// [`engine_getBlobsV1`]: https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getblobsv1
+    pub async fn get_blobs<P: Preset>(
+        &self,
+        versioned_hashes: Vec<VersionedHash>,
+    ) -> Result<Vec<Option<BlobAndProofV1<P>>>> {
+        let expected_length = versioned_hashes.len();
+        let params = vec![serde_json::to_value(versioned_hashes)?];
+
+        let blobs_and_proofs = self
+            .execute::<Vec<Option<BlobAndProofV1<P>>>>("engine_getBlobsV1", params)
+            .await?;
+
+        ensure!(
+            blobs_and_proofs.len() == expected_length,
+            Error::WrongNumberOfBlobs {
+                expected: expected_length,
+                actual: blobs_and_proofs.len(),
+            },
+        );
+
+        Ok(blobs_and_proofs)
+    }
+
     async fn execute<T: DeserializeOwned + Send>(
         &self,
         method: &str,
@@ -344,14 +388,28 @@ impl Eth1Api {
             prometheus_metrics::start_timer_vec(&metrics.eth1_api_request_times, method)
         });
 
-        self.request_with_fallback(|(api, headers)| {
-            Ok(CallFuture::new(api.transport().execute_with_headers(
-                method,
-                params.clone(),
-                headers,
-            )))
-        })
-        .await
+        let request = self.request_with_fallback(|(api, headers)| {
+            let pending = self.recorder.as_deref().map(|recorder| {
+                let pending = recorder.start(method, &params, headers.as_ref());
+                (recorder, pending)
+            });
+
+            let response = api
+                .transport()
+                .execute_with_headers(method, params.clone(), headers);
+
+            Ok(CallFuture::new(async move {
+                let result = response.await;
+
+                if let Some((recorder, pending)) = pending {
+                    recorder.finish(pending, &result).await;
+                }
+
+                result
+            }))
+        });
+
+        request_tracing::in_span(method.to_owned(), request).await
     }
 
     async fn request_with_fallback<R, O, F>(&self, request_from_api: R) -> Result<O>
@@ -383,6 +441,8 @@ impl Eth1Api {
                         metrics.eth1_api_errors_count.inc();
                     }
 
+                    *self.last_error.lock().await = Some(format!("{url}: {error}"));
+
                     match self.peek_next_endpoint().await {
                         Some(next_eth) => warn!(
                             "Eth1 RPC endpoint {url} returned an error: {error}; \
@@ -417,10 +477,21 @@ impl Eth1Api {
         bail!(Error::EndpointsExhausted)
     }
 
-    async fn current_endpoint(&self) -> Option<Url> {
+    pub async fn current_endpoint(&self) -> Option<Url> {
         self.endpoints.lock().await.as_slice().first().cloned()
     }
 
+    pub async fn last_error(&self) -> Option<String> {
+        self.last_error.lock().await.clone()
+    }
+
+    /// Switches back to the first endpoint and forgets the last error.
+    /// Requests made after this will not be affected by failures of earlier ones.
+    pub async fn reconnect(&self) {
+        self.reset_endpoints().await;
+        *self.last_error.lock().await = None;
+    }
+
     async fn next_endpoint(&self) -> Option<Url> {
         self.endpoints.lock().await.next()
     }
@@ -451,6 +522,8 @@ enum Error {
     NoEndpointsProvided,
     #[error("pre-Bellatrix phase passed to Eth1Api::forkchoice_updated")]
     PhasePreBellatrix,
+    #[error("engine_getBlobsV1 returned {actual} entries instead of {expected}")]
+    WrongNumberOfBlobs { expected: usize, actual: usize },
 }
 
 #[cfg(test)]
@@ -700,6 +773,67 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_blobs_preserves_missing_entries() -> Result<()> {
+        let blob = format!("0x{}", "00".repeat(131_072));
+        let proof = format!("0x{}", "c0".repeat(48));
+
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": [
+                null,
+                {
+                    "blob": blob,
+                    "proof": proof,
+                },
+            ],
+        });
+
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(Method::POST).path("/");
+            then.status(200).body(body.to_string());
+        });
+
+        let config = Arc::new(Config::mainnet());
+        let auth = Arc::default();
+        let server_url = server.url("/").parse()?;
+
+        let eth1_api = Arc::new(Eth1Api::new(
+            config,
+            Client::new(),
+            auth,
+            vec![server_url],
+            None,
+            None,
+        ));
+
+        let blobs_and_proofs = eth1_api
+            .get_blobs::<Mainnet>(vec![H256::repeat_byte(1), H256::repeat_byte(2)])
+            .await?;
+
+        assert_eq!(blobs_and_proofs.len(), 2);
+        assert!(blobs_and_proofs[0].is_none());
+        assert!(blobs_and_proofs[1].is_some());
+
+        let error = eth1_api
+            .get_blobs::<Mainnet>(vec![H256::repeat_byte(1)])
+            .await
+            .expect_err("response with a different number of entries should be rejected");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::WrongNumberOfBlobs {
+                expected: 1,
+                actual: 2,
+            }),
+        ));
+
+        Ok(())
+    }
+
     fn default_payload<P: Preset>() -> ExecutionPayload<P> {
         BellatrixExecutionPayload::default().into()
     }
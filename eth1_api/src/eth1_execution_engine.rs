@@ -8,8 +8,9 @@ use futures::channel::{mpsc::UnboundedSender, oneshot::Sender};
 use log::{info, warn};
 use tokio::runtime::{Builder, Handle};
 use types::{
-    combined::{ExecutionPayload, ExecutionPayloadParams},
+    combined::{ExecutionPayload, ExecutionPayloadParams, SignedBeaconBlock},
     config::Config,
+    deneb::primitives::BlobIndex,
     nonstandard::{Phase, TimedPowBlock, WithBlobsAndMev},
     phase0::primitives::{ExecutionBlockHash, H256},
     preset::Preset,
@@ -46,6 +47,7 @@ impl<P: Preset> ExecutionEngine<P> for Eth1ExecutionEngine<P> {
             finalized_eth1_block_hash,
             payload_attributes,
             sender,
+            trace: request_tracing::current(),
         }
         .send(&self.execution_service_tx);
     }
@@ -62,12 +64,21 @@ impl<P: Preset> ExecutionEngine<P> for Eth1ExecutionEngine<P> {
             payload,
             params,
             sender,
+            trace: request_tracing::current(),
         }
         .send(&self.execution_service_tx);
 
         Ok(())
     }
 
+    fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>) {
+        ExecutionServiceMessage::GetBlobs {
+            block,
+            blob_indices,
+        }
+        .send(&self.execution_service_tx);
+    }
+
     fn pow_block(&self, block_hash: ExecutionBlockHash) -> Option<TimedPowBlock> {
         // `ExecutionEngine::pow_block` is not `async` because it is called from non-`async` code.
         // We need some way to run the future returned by `Eth1Api::get_block_by_hash`.
@@ -1,29 +1,64 @@
-use std::sync::Arc;
+use core::time::Duration;
+use std::{sync::Arc, time::Instant};
 
 use anyhow::Result;
-use derive_more::Constructor;
 use either::Either;
-use execution_engine::{ForkChoiceUpdatedResponse, PayloadAttributes, PayloadStatusV1};
+use execution_engine::{
+    BlobAndProofV1, ForkChoiceUpdatedResponse, PayloadAttributes, PayloadStatusV1,
+};
 use fork_choice_control::Wait;
 use futures::{channel::mpsc::UnboundedReceiver, StreamExt as _};
-use log::warn;
+use helper_functions::misc;
+use log::{info, warn};
+use prometheus_metrics::Metrics;
 use types::{
-    combined::{ExecutionPayload, ExecutionPayloadParams},
+    combined::{ExecutionPayload, ExecutionPayloadParams, SignedBeaconBlock},
+    deneb::primitives::BlobIndex,
     nonstandard::Phase,
     phase0::primitives::{ExecutionBlockHash, H256},
     preset::Preset,
+    traits::SignedBeaconBlock as _,
 };
 
-use crate::{eth1_api::Eth1Api, messages::ExecutionServiceMessage, misc::ApiController};
+use crate::{
+    catch_up::{CatchUp, Transition},
+    eth1_api::Eth1Api,
+    messages::ExecutionServiceMessage,
+    misc::ApiController,
+};
 
-#[derive(Constructor)]
 pub struct ExecutionService<P: Preset, W: Wait> {
     api: Arc<Eth1Api>,
     controller: ApiController<P, W>,
+    metrics: Option<Arc<Metrics>>,
     rx: UnboundedReceiver<ExecutionServiceMessage<P>>,
+    catch_up: CatchUp,
+    // Catch-up checkpoints reuse these from the latest `engine_forkchoiceUpdated` call.
+    head_eth1_block_hash: ExecutionBlockHash,
+    safe_eth1_block_hash: ExecutionBlockHash,
+    finalized_eth1_block_hash: ExecutionBlockHash,
 }
 
 impl<P: Preset, W: Wait> ExecutionService<P, W> {
+    #[must_use]
+    pub fn new(
+        api: Arc<Eth1Api>,
+        controller: ApiController<P, W>,
+        metrics: Option<Arc<Metrics>>,
+        rx: UnboundedReceiver<ExecutionServiceMessage<P>>,
+    ) -> Self {
+        Self {
+            api,
+            controller,
+            metrics,
+            rx,
+            catch_up: CatchUp::default(),
+            head_eth1_block_hash: ExecutionBlockHash::zero(),
+            safe_eth1_block_hash: ExecutionBlockHash::zero(),
+            finalized_eth1_block_hash: ExecutionBlockHash::zero(),
+        }
+    }
+
     pub async fn run(mut self) -> Result<()> {
         while let Some(message) = self.rx.next().await {
             match message {
@@ -33,15 +68,21 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
                     finalized_eth1_block_hash,
                     payload_attributes,
                     sender,
+                    trace,
                 } => {
-                    let Some(response) = self
-                        .notify_forkchoice_updated(
-                            head_eth1_block_hash,
-                            safe_eth1_block_hash,
-                            finalized_eth1_block_hash,
-                            payload_attributes,
-                        )
-                        .await
+                    self.head_eth1_block_hash = head_eth1_block_hash;
+                    self.safe_eth1_block_hash = safe_eth1_block_hash;
+                    self.finalized_eth1_block_hash = finalized_eth1_block_hash;
+
+                    let notify_forkchoice_updated = self.notify_forkchoice_updated(
+                        head_eth1_block_hash,
+                        safe_eth1_block_hash,
+                        finalized_eth1_block_hash,
+                        payload_attributes,
+                    );
+
+                    let Some(response) =
+                        request_tracing::resume(trace, notify_forkchoice_updated).await
                     else {
                         continue;
                     };
@@ -51,9 +92,15 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
                         payload_id,
                     } = response;
 
+                    let transition = self
+                        .catch_up
+                        .on_forkchoice_updated_status(payload_status.status);
+
                     self.controller
                         .on_notified_fork_choice_update(payload_status);
 
+                    self.handle_catch_up_transition(transition).await;
+
                     if let Some(sender) = sender {
                         if let Err(message) = sender.send(payload_id) {
                             warn!(
@@ -68,13 +115,26 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
                     payload,
                     params,
                     sender,
+                    trace,
                 } => {
-                    let response = self
-                        .notify_new_payload(beacon_block_root, payload.clone(), params)
-                        .await;
+                    // Nothing waits for the status of payloads sent without a `sender`.
+                    // Those are the ones the catch-up path may skip.
+                    if sender.is_none() && self.catch_up.is_active() {
+                        self.defer_payload(beacon_block_root, payload.phase()).await;
+                        continue;
+                    }
+
+                    let notify_new_payload =
+                        self.notify_new_payload(beacon_block_root, payload.clone(), params);
+
+                    let response = request_tracing::resume(trace, notify_new_payload).await;
+
+                    let mut transition = None;
 
                     match &response {
                         Ok(payload_status) => {
+                            transition = self.catch_up.on_new_payload_status(payload_status.status);
+
                             self.controller.on_notified_new_payload(
                                 payload.block_hash(),
                                 payload_status.clone(),
@@ -100,6 +160,14 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
                             );
                         }
                     }
+
+                    self.handle_catch_up_transition(transition).await;
+                }
+                ExecutionServiceMessage::GetBlobs {
+                    block,
+                    blob_indices,
+                } => {
+                    self.get_blobs(block, blob_indices).await;
                 }
             }
         }
@@ -155,6 +223,191 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
         }
     }
 
+    fn slot_duration(&self) -> Duration {
+        Duration::from_secs(self.controller.chain_config().seconds_per_slot.get())
+    }
+
+    async fn defer_payload(&mut self, beacon_block_root: H256, phase: Phase) {
+        let due =
+            self.catch_up
+                .defer_payload(beacon_block_root, Instant::now(), self.slot_duration());
+
+        // Blocks may be imported out of order or from multiple branches.
+        // The fork choice head is the one the execution engine should sync to.
+        let head_eth1_block_hash = self.head_eth1_block_hash;
+
+        if !due || head_eth1_block_hash.is_zero() {
+            return;
+        }
+
+        features::log!(
+            DebugEth1,
+            "pointing syncing execution engine at catch-up checkpoint \
+             (head_eth1_block_hash: {head_eth1_block_hash:?})",
+        );
+
+        let Some(response) = self
+            .notify_forkchoice_updated(
+                head_eth1_block_hash,
+                self.safe_eth1_block_hash,
+                self.finalized_eth1_block_hash,
+                Either::Left(phase),
+            )
+            .await
+        else {
+            return;
+        };
+
+        let payload_status = response.payload_status;
+        let transition = self
+            .catch_up
+            .on_forkchoice_updated_status(payload_status.status);
+
+        self.controller
+            .on_notified_fork_choice_update(payload_status);
+
+        self.handle_catch_up_transition(transition).await;
+    }
+
+    async fn handle_catch_up_transition(&mut self, transition: Option<Transition>) {
+        let Some(transition) = transition else {
+            return;
+        };
+
+        self.track_catch_up_transition(&transition);
+
+        if let Transition::Exited {
+            deferred_block_roots,
+        } = transition
+        {
+            self.resend_deferred_payloads(deferred_block_roots).await;
+        }
+    }
+
+    // `engine_forkchoiceUpdated` only reports the latest valid ancestor of the head.
+    // Fork choice cannot tell which block made a branch invalid without statuses of individual
+    // payloads, so every payload that is still optimistic is sent again.
+    async fn resend_deferred_payloads(&mut self, deferred_block_roots: Vec<H256>) {
+        for beacon_block_root in deferred_block_roots {
+            // The execution engine may fall behind again while payloads are being sent.
+            if self.catch_up.is_active() {
+                self.catch_up.defer_payload(
+                    beacon_block_root,
+                    Instant::now(),
+                    self.slot_duration(),
+                );
+
+                continue;
+            }
+
+            let block = match self.controller.block_by_root(beacon_block_root) {
+                Ok(Some(block)) => block,
+                Ok(None) => continue,
+                Err(error) => {
+                    warn!("failed to look up block with withheld payload: {error:?}");
+                    continue;
+                }
+            };
+
+            // The block may have become valid as a descendant of a valid head or been pruned.
+            if !block.optimistic {
+                continue;
+            }
+
+            let block = block.value;
+            let params = execution_payload_params(&block);
+
+            let Some(payload) = block.as_ref().clone().execution_payload() else {
+                continue;
+            };
+
+            let block_hash = payload.block_hash();
+
+            match self
+                .notify_new_payload(beacon_block_root, payload, params)
+                .await
+            {
+                Ok(payload_status) => {
+                    let transition = self.catch_up.on_new_payload_status(payload_status.status);
+
+                    if let Some(transition) = transition {
+                        self.track_catch_up_transition(&transition);
+                    }
+
+                    self.controller
+                        .on_notified_new_payload(block_hash, payload_status);
+                }
+                Err(error) => warn!("engine_newPayload call failed: {error}"),
+            }
+        }
+    }
+
+    fn track_catch_up_transition(&self, transition: &Transition) {
+        match transition {
+            Transition::Entered => warn!(
+                "execution engine keeps responding with SYNCING; \
+                 withholding payloads and sending periodic checkpoints until it catches up",
+            ),
+            Transition::Exited {
+                deferred_block_roots,
+            } => info!(
+                "execution engine caught up; sending all payloads again \
+                 (payloads withheld: {})",
+                deferred_block_roots.len(),
+            ),
+        }
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            let (label, active) = match transition {
+                Transition::Entered => ("entered", true),
+                Transition::Exited { .. } => ("exited", false),
+            };
+
+            metrics.register_eth1_api_catch_up_transition(label, active);
+        }
+    }
+
+    async fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>) {
+        let Some(body) = block.message().body().post_deneb() else {
+            return;
+        };
+
+        let (blob_indices, versioned_hashes): (Vec<_>, Vec<_>) = blob_indices
+            .into_iter()
+            .filter_map(|index| {
+                let commitment = body
+                    .blob_kzg_commitments()
+                    .get(usize::try_from(index).ok()?)?;
+
+                Some((index, misc::kzg_commitment_to_versioned_hash(*commitment)))
+            })
+            .unzip();
+
+        let blobs_and_proofs = match self.api.get_blobs::<P>(versioned_hashes).await {
+            Ok(blobs_and_proofs) => blobs_and_proofs,
+            Err(error) => {
+                warn!("engine_getBlobsV1 call failed: {error}");
+                return;
+            }
+        };
+
+        for (index, blob_and_proof) in blob_indices.into_iter().zip(blobs_and_proofs) {
+            let Some(BlobAndProofV1 { blob, proof }) = blob_and_proof else {
+                continue;
+            };
+
+            match misc::construct_blob_sidecar(&block, index, blob, proof) {
+                Ok(blob_sidecar) => self
+                    .controller
+                    .on_execution_layer_blob_sidecar(Arc::new(blob_sidecar)),
+                Err(error) => warn!(
+                    "failed to construct blob sidecar from engine_getBlobsV1 response \
+                     (index: {index}, error: {error})",
+                ),
+            }
+        }
+    }
+
     async fn notify_new_payload(
         &self,
         beacon_block_root: H256,
@@ -188,3 +441,21 @@ impl<P: Preset, W: Wait> ExecutionService<P, W> {
         Ok(response)
     }
 }
+
+fn execution_payload_params<P: Preset>(
+    block: &SignedBeaconBlock<P>,
+) -> Option<ExecutionPayloadParams> {
+    let body = block.message().body().post_deneb()?;
+
+    let versioned_hashes = body
+        .blob_kzg_commitments()
+        .iter()
+        .copied()
+        .map(misc::kzg_commitment_to_versioned_hash)
+        .collect();
+
+    Some(ExecutionPayloadParams::Deneb {
+        versioned_hashes,
+        parent_beacon_block_root: block.message().parent_root(),
+    })
+}
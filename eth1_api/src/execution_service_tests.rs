@@ -0,0 +1,390 @@
+// These tests run fork choice with `ExecutionService` against `engine_api_mock::MockEngine`.
+// Unlike the tests in `fork_choice_control`, payload statuses come from Engine API responses.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use clock::{Tick, TickKind};
+use crossbeam_utils::sync::WaitGroup;
+use either::Either;
+use engine_api_mock::{MockEngine, ScriptedStatus};
+use execution_engine::{
+    ExecutionEngine as _, PayloadAttributes, PayloadAttributesV1, PayloadValidationStatus,
+};
+use fork_choice_control::{Controller, MutatorHandle};
+use futures::channel::{mpsc::UnboundedReceiver, oneshot};
+use helper_functions::{accessors, misc};
+use reqwest::Client;
+use serde_json::json;
+use ssz::SszHash as _;
+use std_ext::ArcExt as _;
+use tokio::task::JoinHandle;
+use types::{
+    bellatrix::containers::{
+        ExecutionPayload as BellatrixExecutionPayload,
+        ExecutionPayloadHeader as BellatrixExecutionPayloadHeader,
+    },
+    combined::{BeaconState, ExecutionPayloadHeader, SignedBeaconBlock},
+    config::Config,
+    nonstandard::Phase,
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{ExecutionAddress, ExecutionBlockHash, Slot, H256},
+    },
+    preset::Minimal,
+    traits::SignedBeaconBlock as _,
+};
+
+use crate::{
+    eth1_api::Eth1Api, eth1_execution_engine::Eth1ExecutionEngine,
+    execution_service::ExecutionService, messages::ExecutionServiceMessage,
+};
+
+const GENESIS_BLOCK_HASH: ExecutionBlockHash = ExecutionBlockHash::repeat_byte(0x10);
+const UNRELATED_BLOCK_HASH: ExecutionBlockHash = ExecutionBlockHash::repeat_byte(0xff);
+
+type EngineController = Controller<Minimal, Arc<Eth1ExecutionEngine<Minimal>>, WaitGroup>;
+
+struct Context {
+    config: Arc<Config>,
+    engine: MockEngine,
+    execution_engine: Arc<Eth1ExecutionEngine<Minimal>>,
+    controller: Arc<EngineController>,
+    // Keep the `MutatorHandle` around to avoid joining the mutator thread prematurely.
+    #[allow(dead_code)]
+    mutator_handle: MutatorHandle<Minimal, WaitGroup>,
+    service: JoinHandle<Result<()>>,
+    genesis_state: Arc<BeaconState<Minimal>>,
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        self.service.abort();
+    }
+}
+
+impl Context {
+    fn new() -> Result<Self> {
+        let config = Arc::new(Config::minimal().start_and_stay_in(Phase::Bellatrix));
+        let engine = MockEngine::start()?;
+
+        // Start after the Merge to keep terminal PoW block lookups out of the tests.
+        let header = BellatrixExecutionPayloadHeader {
+            block_hash: GENESIS_BLOCK_HASH,
+            ..BellatrixExecutionPayloadHeader::default()
+        };
+
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let genesis_state = genesis_state
+            .as_ref()
+            .clone()
+            .with_execution_payload_header(Some(ExecutionPayloadHeader::Bellatrix(header)))
+            .map(Arc::new)?;
+
+        let genesis_block = Arc::new(genesis::beacon_block(&genesis_state));
+
+        let eth1_api = Arc::new(Eth1Api::new(
+            config.clone_arc(),
+            Client::new(),
+            Arc::default(),
+            vec![engine.url().clone()],
+            None,
+            None,
+        ));
+
+        let (execution_service_tx, execution_service_rx) = futures::channel::mpsc::unbounded();
+
+        let execution_engine = Arc::new(Eth1ExecutionEngine::new(
+            config.clone_arc(),
+            eth1_api.clone_arc(),
+            execution_service_tx,
+        ));
+
+        let (controller, mutator_handle) = EngineController::with_execution_engine(
+            config.clone_arc(),
+            genesis_block,
+            genesis_state.clone_arc(),
+            execution_engine.clone_arc(),
+        );
+
+        let service = tokio::spawn(run_service(
+            eth1_api,
+            controller.clone_arc(),
+            execution_service_rx,
+        ));
+
+        Ok(Self {
+            config,
+            engine,
+            execution_engine,
+            controller,
+            mutator_handle,
+            service,
+            genesis_state,
+        })
+    }
+
+    fn block_with_payload(
+        &self,
+        pre_state: &Arc<BeaconState<Minimal>>,
+        slot: Slot,
+        execution_block_hash: ExecutionBlockHash,
+    ) -> Result<(Arc<SignedBeaconBlock<Minimal>>, Arc<BeaconState<Minimal>>)> {
+        let execution_payload =
+            factory::execution_payload(&self.config, pre_state, slot, execution_block_hash)?;
+
+        factory::block_with_payload(
+            &self.config,
+            pre_state.clone_arc(),
+            slot,
+            H256::zero(),
+            execution_payload,
+        )
+    }
+
+    fn import_blocks(&self, blocks: &[&Arc<SignedBeaconBlock<Minimal>>]) {
+        for block in blocks {
+            self.controller.on_requested_block(block.clone_arc(), None);
+        }
+
+        tokio::task::block_in_place(|| self.controller.wait_for_tasks());
+    }
+
+    // `ExecutionService` handles messages in order. Once it responds to this payload,
+    // it has passed the responses to all earlier messages on to fork choice.
+    async fn process_pending_messages(&self) -> Result<()> {
+        let (sender, receiver) = oneshot::channel();
+
+        let payload = BellatrixExecutionPayload::<Minimal> {
+            block_hash: UNRELATED_BLOCK_HASH,
+            ..BellatrixExecutionPayload::default()
+        };
+
+        self.execution_engine.notify_new_payload(
+            H256::zero(),
+            payload.into(),
+            None,
+            Some(sender),
+        )?;
+
+        receiver.await??;
+
+        tokio::task::block_in_place(|| self.controller.wait_for_tasks());
+
+        Ok(())
+    }
+
+    fn assert_optimistic(&self, block: &SignedBeaconBlock<Minimal>, expected_optimistic: bool) {
+        assert_eq!(
+            self.controller
+                .check_block_root(block.message().hash_tree_root())
+                .expect("no storage errors should occur")
+                .expect("block should be present in the store")
+                .optimistic,
+            expected_optimistic,
+        );
+    }
+
+    fn assert_head(&self, block: &SignedBeaconBlock<Minimal>) {
+        assert_eq!(
+            self.controller.head_block_root().value,
+            block.message().hash_tree_root(),
+        );
+    }
+
+    fn new_payload_block_hashes(&self) -> Vec<ExecutionBlockHash> {
+        self.engine
+            .requests()
+            .into_iter()
+            .filter(|request| request.method.starts_with("engine_newPayload"))
+            .filter_map(|request| {
+                serde_json::from_value(request.params[0]["blockHash"].clone()).ok()
+            })
+            .filter(|block_hash| *block_hash != UNRELATED_BLOCK_HASH)
+            .collect()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn blocks_remain_optimistic_while_engine_is_syncing() -> Result<()> {
+    let context = Context::new()?;
+    let block_hash_1 = ExecutionBlockHash::repeat_byte(1);
+    let block_hash_2 = ExecutionBlockHash::repeat_byte(2);
+
+    // Fork choice would treat a `VALID` response to `engine_forkchoiceUpdated` as confirmation.
+    context
+        .engine
+        .set_forkchoice_updated_status(ScriptedStatus::new(PayloadValidationStatus::Syncing));
+
+    context
+        .engine
+        .set_new_payload_status(ScriptedStatus::new(PayloadValidationStatus::Syncing));
+
+    let (block_1, state_1) = context.block_with_payload(&context.genesis_state, 1, block_hash_1)?;
+    let (block_2, _) = context.block_with_payload(&state_1, 2, block_hash_2)?;
+
+    context.controller.on_slot(block_2.message().slot());
+    context.import_blocks(&[&block_1, &block_2]);
+    context.process_pending_messages().await?;
+
+    context.assert_head(&block_2);
+    context.assert_optimistic(&block_1, true);
+    context.assert_optimistic(&block_2, true);
+
+    assert_eq!(
+        context.new_payload_block_hashes(),
+        [block_hash_1, block_hash_2],
+    );
+
+    // Fork choice asks about optimistic heads again 1 second before the next interval.
+    // A `VALID` status for the head confirms its ancestors as well.
+    context
+        .engine
+        .set_new_payload_status(ScriptedStatus::new(PayloadValidationStatus::Valid));
+
+    context.controller.on_tick(Tick {
+        slot: block_2.message().slot(),
+        kind: TickKind::ProposeFourth,
+    });
+
+    tokio::task::block_in_place(|| context.controller.wait_for_tasks());
+    context.process_pending_messages().await?;
+
+    context.assert_optimistic(&block_1, false);
+    context.assert_optimistic(&block_2, false);
+
+    assert_eq!(
+        context.new_payload_block_hashes(),
+        [block_hash_1, block_hash_2, block_hash_2],
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_payload_status_removes_block_from_fork_choice() -> Result<()> {
+    let context = Context::new()?;
+    let block_hash_1 = ExecutionBlockHash::repeat_byte(1);
+    let block_hash_2 = ExecutionBlockHash::repeat_byte(2);
+
+    context
+        .engine
+        .set_forkchoice_updated_status(ScriptedStatus::new(PayloadValidationStatus::Syncing));
+
+    context.engine.set_new_payload_status_for(
+        block_hash_2,
+        ScriptedStatus::new(PayloadValidationStatus::Invalid)
+            .with_latest_valid_hash(block_hash_1)
+            .with_validation_error("invalid state root"),
+    );
+
+    let (block_1, state_1) = context.block_with_payload(&context.genesis_state, 1, block_hash_1)?;
+    let (block_2, _) = context.block_with_payload(&state_1, 2, block_hash_2)?;
+
+    context.controller.on_slot(block_2.message().slot());
+    context.import_blocks(&[&block_1, &block_2]);
+    context.process_pending_messages().await?;
+
+    context.assert_head(&block_1);
+    context.assert_optimistic(&block_1, false);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proposed_block_contains_payload_built_by_engine() -> Result<()> {
+    let context = Context::new()?;
+    let slot = 1;
+    let suggested_fee_recipient = ExecutionAddress::repeat_byte(1);
+
+    let payload_attributes = PayloadAttributes::Bellatrix(PayloadAttributesV1 {
+        timestamp: misc::compute_timestamp_at_slot(&context.config, &context.genesis_state, slot),
+        prev_randao: accessors::get_randao_mix(&context.genesis_state, GENESIS_EPOCH),
+        suggested_fee_recipient,
+    });
+
+    let (sender, receiver) = oneshot::channel();
+
+    context.execution_engine.notify_forkchoice_updated(
+        GENESIS_BLOCK_HASH,
+        GENESIS_BLOCK_HASH,
+        GENESIS_BLOCK_HASH,
+        Either::Right(payload_attributes),
+        Some(sender),
+    );
+
+    let payload_id = receiver
+        .await?
+        .expect("mock engine should start building a payload on a valid head");
+
+    let payload = context
+        .execution_engine
+        .get_execution_payload(payload_id)
+        .await?
+        .value;
+
+    let block_hash = payload.block_hash();
+
+    // Block processing also checks that the payload is built on top of the genesis payload.
+    let (block, _) = factory::block_with_payload(
+        &context.config,
+        context.genesis_state.clone_arc(),
+        slot,
+        H256::zero(),
+        payload,
+    )?;
+
+    context.controller.on_slot(slot);
+
+    tokio::task::block_in_place(|| {
+        context
+            .controller
+            .on_own_block(WaitGroup::new(), block.clone_arc());
+
+        context.controller.wait_for_tasks();
+    });
+
+    context.process_pending_messages().await?;
+
+    context.assert_head(&block);
+    context.assert_optimistic(&block, false);
+
+    let methods = context
+        .engine
+        .requests()
+        .into_iter()
+        .map(|request| request.method)
+        .take(2)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        methods,
+        ["engine_forkchoiceUpdatedV1", "engine_getPayloadV1"]
+    );
+    assert_eq!(context.new_payload_block_hashes(), [block_hash]);
+
+    let forkchoice_updated = &context.engine.requests()[0];
+
+    assert_eq!(
+        forkchoice_updated.params[0]["headBlockHash"],
+        json!(GENESIS_BLOCK_HASH),
+    );
+
+    assert_eq!(
+        forkchoice_updated.params[1]["suggestedFeeRecipient"],
+        json!(suggested_fee_recipient),
+    );
+
+    Ok(())
+}
+
+async fn run_service(
+    eth1_api: Arc<Eth1Api>,
+    controller: Arc<EngineController>,
+    rx: UnboundedReceiver<ExecutionServiceMessage<Minimal>>,
+) -> Result<()> {
+    ExecutionService::new(eth1_api, controller, None, rx)
+        .run()
+        .await
+}
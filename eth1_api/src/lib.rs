@@ -7,9 +7,12 @@ pub use crate::{
     execution_service::ExecutionService,
     messages::{Eth1ApiToMetrics, Eth1ConnectionData, Eth1Metrics, ExecutionServiceMessage},
     misc::{ApiController, RealController},
+    recorder::Recorder,
 };
 
 mod auth;
+mod blobs_bundle;
+mod catch_up;
 mod deposit_event;
 mod eth1_api;
 mod eth1_block;
@@ -17,3 +20,9 @@ mod eth1_execution_engine;
 mod execution_service;
 mod messages;
 mod misc;
+mod recorder;
+
+#[cfg(test)]
+mod engine_api_tests;
+#[cfg(test)]
+mod execution_service_tests;
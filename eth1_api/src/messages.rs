@@ -1,10 +1,14 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use either::Either;
 use execution_engine::{PayloadAttributes, PayloadId, PayloadStatusV1};
 use futures::channel::{mpsc::UnboundedSender, oneshot::Sender};
 use log::debug;
+use request_tracing::TraceContext;
 use types::{
-    combined::{ExecutionPayload, ExecutionPayloadParams},
+    combined::{ExecutionPayload, ExecutionPayloadParams, SignedBeaconBlock},
+    deneb::primitives::BlobIndex,
     nonstandard::Phase,
     phase0::primitives::{ExecutionBlockHash, H256},
     preset::Preset,
@@ -17,12 +21,18 @@ pub enum ExecutionServiceMessage<P: Preset> {
         finalized_eth1_block_hash: ExecutionBlockHash,
         payload_attributes: Either<Phase, PayloadAttributes<P>>,
         sender: Option<Sender<Option<PayloadId>>>,
+        trace: Option<TraceContext>,
     },
     NotifyNewPayload {
         beacon_block_root: H256,
         payload: ExecutionPayload<P>,
         params: Option<ExecutionPayloadParams>,
         sender: Option<Sender<Result<PayloadStatusV1>>>,
+        trace: Option<TraceContext>,
+    },
+    GetBlobs {
+        block: Arc<SignedBeaconBlock<P>>,
+        blob_indices: Vec<BlobIndex>,
     },
 }
 
@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeMap,
+    io::Write as _,
+    path::Path,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use fs_err::{File, OpenOptions};
+use log::warn;
+use parking_lot::Mutex;
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+use serde::Serialize;
+use serde_json::Value;
+use std_ext::ArcExt as _;
+use web3::Error as Web3Error;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Appends Engine API requests and responses to a file for replay debugging.
+///
+/// Every exchange is written as a single line of JSON with the following members:
+/// - `timestamp`: milliseconds since the Unix epoch when the request was sent.
+/// - `duration_ms`: time it took to receive the response.
+/// - `method` and `params`: the JSON-RPC request.
+/// - `headers`: HTTP headers sent along with the request. JWTs and other sensitive values are
+///   replaced with `[REDACTED]`.
+/// - Either `result` or `error`. `error.code` is missing if the request failed before a JSON-RPC
+///   response was received.
+///
+/// Recordings can be replayed with `engine_api_mock::Recording`.
+/// Failing to write an exchange is logged but does not affect the request.
+pub struct Recorder {
+    file: Arc<Mutex<File>>,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    pub(crate) fn start(
+        &self,
+        method: &str,
+        params: &[Value],
+        headers: Option<&HeaderMap>,
+    ) -> PendingExchange {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        PendingExchange {
+            started: Instant::now(),
+            timestamp,
+            method: method.to_owned(),
+            params: params.to_vec(),
+            headers: headers.map(redact).unwrap_or_default(),
+        }
+    }
+
+    pub(crate) async fn finish(&self, pending: PendingExchange, result: &Result<Value, Web3Error>) {
+        let PendingExchange {
+            started,
+            timestamp,
+            method,
+            params,
+            headers,
+        } = pending;
+
+        let outcome = match result {
+            Ok(value) => Outcome::Result(value.clone()),
+            Err(Web3Error::Rpc(error)) => Outcome::Error {
+                code: Some(error.code.code()),
+                message: error.message.clone(),
+            },
+            Err(error) => Outcome::Error {
+                code: None,
+                message: error.to_string(),
+            },
+        };
+
+        let exchange = RecordedExchange {
+            timestamp,
+            duration_ms: started.elapsed().as_millis(),
+            method: &method,
+            params: &params,
+            headers,
+            outcome,
+        };
+
+        if let Err(error) = self.write(&exchange).await {
+            warn!("failed to record Engine API exchange for {method}: {error}");
+        }
+    }
+
+    async fn write(&self, exchange: &RecordedExchange<'_>) -> Result<()> {
+        let mut line = serde_json::to_vec(exchange)?;
+        line.push(b'\n');
+
+        let file = self.file.clone_arc();
+
+        // Write the whole line at once so that concurrent exchanges are not interleaved.
+        // Writing to the file may block, so it is done outside of the async executor.
+        tokio::task::spawn_blocking(move || file.lock().write_all(&line)).await??;
+
+        Ok(())
+    }
+}
+
+pub struct PendingExchange {
+    started: Instant,
+    timestamp: u128,
+    method: String,
+    params: Vec<Value>,
+    headers: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct RecordedExchange<'exchange> {
+    timestamp: u128,
+    duration_ms: u128,
+    method: &'exchange str,
+    params: &'exchange [Value],
+    headers: BTreeMap<String, String>,
+    #[serde(flatten)]
+    outcome: Outcome,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Result(Value),
+    Error { code: Option<i64>, message: String },
+}
+
+fn redact(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == AUTHORIZATION || value.is_sensitive() {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or(REDACTED)
+            };
+
+            (name.as_str().to_owned(), value.to_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderValue, CONTENT_TYPE};
+
+    use super::*;
+
+    #[test]
+    fn redact_replaces_authorization_and_sensitive_values() {
+        let mut sensitive = HeaderValue::from_static("secret");
+        sensitive.set_sensitive(true);
+
+        let headers = HeaderMap::from_iter([
+            (AUTHORIZATION, HeaderValue::from_static("Bearer token")),
+            (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            ("x-api-key".parse().expect("name is valid"), sensitive),
+        ]);
+
+        assert_eq!(
+            redact(&headers),
+            BTreeMap::from([
+                ("authorization".to_owned(), REDACTED.to_owned()),
+                ("content-type".to_owned(), "application/json".to_owned()),
+                ("x-api-key".to_owned(), REDACTED.to_owned()),
+            ]),
+        );
+    }
+}
@@ -10,7 +10,8 @@ use either::Either;
 use futures::channel::oneshot::Sender;
 use thiserror::Error;
 use types::{
-    combined::{ExecutionPayload, ExecutionPayloadParams},
+    combined::{ExecutionPayload, ExecutionPayloadParams, SignedBeaconBlock},
+    deneb::primitives::BlobIndex,
     nonstandard::{Phase, TimedPowBlock},
     phase0::primitives::{ExecutionBlockHash, H256},
     preset::Preset,
@@ -44,6 +45,11 @@ pub trait ExecutionEngine<P: Preset> {
 
     /// [`get_pow_block`](https://github.com/ethereum/consensus-specs/blob/1bfefe301da592375e2e02f65849a96aadec1936/specs/bellatrix/fork-choice.md#get_pow_block)
     fn pow_block(&self, block_hash: ExecutionBlockHash) -> Option<TimedPowBlock>;
+
+    /// Requests blobs committed to in `block` from the transaction pool of the execution layer.
+    ///
+    /// Blob sidecars constructed from the returned blobs are submitted to the fork choice store.
+    fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>);
 }
 
 impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for &E {
@@ -83,6 +89,10 @@ impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for &E {
     fn pow_block(&self, block_hash: ExecutionBlockHash) -> Option<TimedPowBlock> {
         (*self).pow_block(block_hash)
     }
+
+    fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>) {
+        (*self).get_blobs(block, blob_indices)
+    }
 }
 
 impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for Arc<E> {
@@ -123,6 +133,10 @@ impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for Arc<E> {
     fn pow_block(&self, block_hash: ExecutionBlockHash) -> Option<TimedPowBlock> {
         self.as_ref().pow_block(block_hash)
     }
+
+    fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>) {
+        self.as_ref().get_blobs(block, blob_indices)
+    }
 }
 
 impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for Mutex<E> {
@@ -170,6 +184,12 @@ impl<P: Preset, E: ExecutionEngine<P>> ExecutionEngine<P> for Mutex<E> {
             .expect("execution engine mutex is poisoned")
             .pow_block(block_hash)
     }
+
+    fn get_blobs(&self, block: Arc<SignedBeaconBlock<P>>, blob_indices: Vec<BlobIndex>) {
+        self.lock()
+            .expect("execution engine mutex is poisoned")
+            .get_blobs(block, blob_indices)
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -205,6 +225,8 @@ impl<P: Preset> ExecutionEngine<P> for NullExecutionEngine {
     fn pow_block(&self, _block_hash: ExecutionBlockHash) -> Option<TimedPowBlock> {
         None
     }
+
+    fn get_blobs(&self, _block: Arc<SignedBeaconBlock<P>>, _blob_indices: Vec<BlobIndex>) {}
 }
 
 pub struct MockExecutionEngine {
@@ -244,6 +266,8 @@ impl<P: Preset> ExecutionEngine<P> for MockExecutionEngine {
     fn pow_block(&self, block_hash: ExecutionBlockHash) -> Option<TimedPowBlock> {
         self.pow_blocks.get(&block_hash).copied()
     }
+
+    fn get_blobs(&self, _block: Arc<SignedBeaconBlock<P>>, _blob_indices: Vec<BlobIndex>) {}
 }
 
 impl MockExecutionEngine {
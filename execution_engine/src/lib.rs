@@ -1,10 +1,11 @@
 pub use crate::{
     execution_engine::{ExecutionEngine, MockExecutionEngine, NullExecutionEngine},
     types::{
-        EngineGetPayloadV1Response, EngineGetPayloadV2Response, EngineGetPayloadV3Response,
-        ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, ForkChoiceStateV1,
-        ForkChoiceUpdatedResponse, PayloadAttributes, PayloadAttributesV1, PayloadAttributesV2,
-        PayloadAttributesV3, PayloadId, PayloadStatusV1, PayloadValidationStatus,
+        BlobAndProofV1, EngineGetPayloadV1Response, EngineGetPayloadV2Response,
+        EngineGetPayloadV3Response, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3,
+        ForkChoiceStateV1, ForkChoiceUpdatedResponse, PayloadAttributes, PayloadAttributesV1,
+        PayloadAttributesV2, PayloadAttributesV3, PayloadId, PayloadStatusV1,
+        PayloadValidationStatus,
     },
 };
 
@@ -368,6 +368,14 @@ pub struct BlobsBundleV1<P: Preset> {
     pub blobs: ContiguousList<Blob<P>, P::MaxBlobsPerBlock>,
 }
 
+/// [`BlobAndProofV1`](https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#blobandproofv1)
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub struct BlobAndProofV1<P: Preset> {
+    pub blob: Blob<P>,
+    pub proof: KzgProof,
+}
+
 /// [`ForkChoiceStateV1`](https://github.com/ethereum/execution-apis/blob/b7c5d3420e00648f456744d121ffbd929862924d/src/engine/paris.md#forkchoicestatev1)
 // clippy warning about all fields ending with same postfixes disabled
 #[allow(clippy::struct_field_names)]
@@ -510,9 +518,8 @@ impl<P: Preset> PayloadAttributes<P> {
 }
 
 /// [`PayloadStatusV1`](https://github.com/ethereum/execution-apis/blob/b7c5d3420e00648f456744d121ffbd929862924d/src/engine/paris.md#payloadstatusv1)
-#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-#[cfg_attr(test, derive(Serialize))]
 pub struct PayloadStatusV1 {
     pub status: PayloadValidationStatus,
     pub latest_valid_hash: Option<ExecutionBlockHash>,
@@ -568,9 +575,8 @@ impl From<WithdrawalV1> for Withdrawal {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-#[cfg_attr(test, derive(Serialize))]
 pub enum PayloadValidationStatus {
     Valid,
     Invalid,
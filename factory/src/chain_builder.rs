@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+use helper_functions::misc;
+use ssz::{ByteVector, ContiguousList, ContiguousVector};
+use std_ext::ArcExt as _;
+use try_from_iterator::TryFromIterator as _;
+use typenum::Unsigned as _;
+use types::{
+    combined::{BeaconState, SignedBeaconBlock},
+    config::Config,
+    deneb::{
+        containers::BlobSidecar,
+        primitives::{Blob, KzgCommitment, KzgProof},
+    },
+    nonstandard::Phase,
+    phase0::primitives::{Slot, H256},
+    preset::Preset,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
+};
+
+use crate::FULL_PARTICIPATION;
+
+type BlobsWithProofs<P> = (
+    Vec<Blob<P>>,
+    ContiguousList<KzgCommitment, <P as Preset>::MaxBlobCommitmentsPerBlock>,
+    Vec<KzgProof>,
+);
+
+/// A block along with the state after it and sidecars for the blobs committed to in it.
+#[derive(Clone)]
+pub struct BuiltBlock<P: Preset> {
+    pub block: Arc<SignedBeaconBlock<P>>,
+    pub state: Arc<BeaconState<P>>,
+    pub blob_sidecars: Vec<Arc<BlobSidecar<P>>>,
+}
+
+impl<P: Preset> BuiltBlock<P> {
+    #[must_use]
+    pub fn root(&self) -> H256 {
+        self.block.message().hash_tree_root()
+    }
+
+    #[must_use]
+    pub fn slot(&self) -> Slot {
+        self.block.message().slot()
+    }
+}
+
+/// Builds chains of valid blocks for tests without relying on data captured from real networks.
+///
+/// Building the same chain twice produces identical blocks. Forks can be built by extending the
+/// same block more than once. Blocks in forks must differ in slots or graffiti to have different
+/// roots.
+#[derive(Clone)]
+pub struct ChainBuilder {
+    config: Arc<Config>,
+    graffiti: H256,
+    participation_percentage: usize,
+    blobs_per_block: usize,
+}
+
+impl ChainBuilder {
+    #[must_use]
+    pub const fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            graffiti: H256::zero(),
+            participation_percentage: FULL_PARTICIPATION,
+            blobs_per_block: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_graffiti(mut self, graffiti: H256) -> Self {
+        self.graffiti = graffiti;
+        self
+    }
+
+    /// Sets the percentage of validators in each committee that attest and sign sync aggregates.
+    ///
+    /// Each block includes attestations for all slots since its parent that are still includable.
+    #[must_use]
+    pub const fn with_participation(mut self, participation_percentage: usize) -> Self {
+        assert!(
+            participation_percentage <= FULL_PARTICIPATION,
+            "participation should be a percentage",
+        );
+        self.participation_percentage = participation_percentage;
+        self
+    }
+
+    /// Blobs are only added to blocks in phases that support them.
+    #[must_use]
+    pub const fn with_blobs_per_block(mut self, blobs_per_block: usize) -> Self {
+        self.blobs_per_block = blobs_per_block;
+        self
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &Arc<Config> {
+        &self.config
+    }
+
+    pub fn genesis<P: Preset>(&self) -> Result<BuiltBlock<P>> {
+        let (state, _) = crate::min_genesis_state(&self.config)?;
+        let block = Arc::new(genesis::beacon_block(&state));
+
+        Ok(BuiltBlock {
+            block,
+            state,
+            blob_sidecars: vec![],
+        })
+    }
+
+    /// Builds a chain descending from `parent` with a block in each slot in `slots`.
+    ///
+    /// Slots left out of `slots` are skipped.
+    pub fn extend<P: Preset>(
+        &self,
+        parent: &BuiltBlock<P>,
+        slots: impl IntoIterator<Item = Slot>,
+    ) -> Result<Vec<BuiltBlock<P>>> {
+        let mut parent = parent.clone();
+        let mut blocks = vec![];
+
+        for slot in slots {
+            ensure!(
+                parent.slot() < slot,
+                "blocks should be built in increasing slots \
+                 (parent slot: {}, slot: {slot})",
+                parent.slot(),
+            );
+
+            let built = self.block(&parent, slot)?;
+
+            parent = built.clone();
+            blocks.push(built);
+        }
+
+        Ok(blocks)
+    }
+
+    fn block<P: Preset>(&self, parent: &BuiltBlock<P>, slot: Slot) -> Result<BuiltBlock<P>> {
+        let config = &self.config;
+        let advanced_state = crate::advance_state(config, parent.state.clone_arc(), slot)?;
+        let eth1_data = advanced_state.eth1_data();
+
+        // Attestations from before `earliest_includable_slot` would make the block invalid.
+        let earliest_includable_slot = slot.saturating_sub(P::SlotsPerEpoch::U64);
+        let attestation_slots = parent.slot().max(earliest_includable_slot)..slot;
+
+        let attestations = crate::aggregate_attestations(
+            config,
+            &advanced_state,
+            attestation_slots,
+            self.participation_percentage,
+        )?;
+
+        let sync_aggregate =
+            crate::sync_committee_aggregate(config, &advanced_state, self.participation_percentage);
+
+        let (blobs, blob_kzg_commitments, proofs) = if advanced_state.phase() >= Phase::Deneb {
+            self.blobs_with_proofs::<P>(slot)?
+        } else {
+            BlobsWithProofs::<P>::default()
+        };
+
+        let (block, state) = crate::block(
+            config,
+            advanced_state,
+            eth1_data,
+            self.graffiti,
+            attestations,
+            ContiguousList::default(),
+            sync_aggregate,
+            None,
+            blob_kzg_commitments,
+        )?;
+
+        let blob_sidecars =
+            misc::construct_blob_sidecars(&block, blobs.into_iter(), proofs.into_iter())?
+                .into_iter()
+                .map(Arc::new)
+                .collect();
+
+        Ok(BuiltBlock {
+            block,
+            state,
+            blob_sidecars,
+        })
+    }
+
+    fn blobs_with_proofs<P: Preset>(&self, slot: Slot) -> Result<BlobsWithProofs<P>> {
+        let mut blobs = vec![];
+        let mut commitments = vec![];
+        let mut proofs = vec![];
+
+        for index in 0..self.blobs_per_block.try_into()? {
+            // Field elements must be less than the BLS modulus.
+            // Only the low 8 bytes of each are set to keep them well below it.
+            let seed = slot * P::MaxBlobsPerBlock::U64 + index + 1;
+
+            let field_element = {
+                let mut bytes = [0; 32];
+                bytes[24..].copy_from_slice(&seed.to_be_bytes());
+                bytes
+            };
+
+            let bytes = core::iter::repeat(field_element)
+                .take(P::FieldElementsPerBlob::USIZE)
+                .flatten();
+
+            let blob = Box::new(ByteVector::from(ContiguousVector::try_from_iter(bytes)?));
+            let commitment = kzg_utils::eip_4844::blob_to_kzg_commitment::<P>(&blob)?;
+            let proof = kzg_utils::eip_4844::compute_blob_kzg_proof::<P>(&blob, commitment)?;
+
+            blobs.push(blob);
+            commitments.push(commitment);
+            proofs.push(proof);
+        }
+
+        Ok((blobs, ContiguousList::try_from_iter(commitments)?, proofs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn chains_are_deterministic_and_forks_differ() -> Result<()> {
+        let builder = ChainBuilder::new(Arc::new(Config::minimal())).with_participation(50);
+        let genesis = builder.genesis::<Minimal>()?;
+        let trunk = builder.extend(&genesis, [1, 2, 4])?;
+        let fork_base = trunk.last().expect("trunk should contain blocks");
+
+        let fork_a = builder
+            .clone()
+            .with_graffiti(H256::repeat_byte(1))
+            .extend(fork_base, 5..=8)?;
+
+        let fork_b = builder
+            .clone()
+            .with_graffiti(H256::repeat_byte(2))
+            .extend(fork_base, [6, 8])?;
+
+        let roots =
+            |blocks: &[BuiltBlock<Minimal>]| blocks.iter().map(BuiltBlock::root).collect_vec();
+        let slots =
+            |blocks: &[BuiltBlock<Minimal>]| blocks.iter().map(BuiltBlock::slot).collect_vec();
+
+        assert_eq!(roots(&trunk), roots(&builder.extend(&genesis, [1, 2, 4])?));
+        assert_eq!(slots(&fork_a), [5, 6, 7, 8]);
+        assert_eq!(slots(&fork_b), [6, 8]);
+        assert_ne!(fork_a[1].root(), fork_b[0].root());
+        assert_eq!(fork_b[0].block.message().parent_root(), fork_base.root());
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_in_deneb_commit_to_blobs() -> Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Deneb);
+        let builder = ChainBuilder::new(Arc::new(config)).with_blobs_per_block(2);
+        let genesis = builder.genesis::<Minimal>()?;
+        let blocks = builder.extend(&genesis, 1..=2)?;
+
+        for built in blocks {
+            assert_eq!(built.blob_sidecars.len(), 2);
+
+            for blob_sidecar in &built.blob_sidecars {
+                assert_eq!(blob_sidecar.signed_block_header.message.slot, built.slot());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_must_be_built_in_increasing_slots() -> Result<()> {
+        let builder = ChainBuilder::new(Arc::new(Config::minimal()));
+        let genesis = builder.genesis::<Minimal>()?;
+
+        assert!(builder.extend(&genesis, [2, 1]).is_err());
+
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@
 //! Currently only suitable for use in tests.
 //! All containers are signed with keys generated by [`interop::secret_key`].
 
+pub use crate::chain_builder::{BuiltBlock, ChainBuilder};
+
 use core::ops::Range;
 use std::sync::Arc;
 
@@ -32,9 +34,12 @@ use types::{
     },
     combined::{BeaconBlock, BeaconState, ExecutionPayload, SignedBeaconBlock},
     config::Config,
-    deneb::containers::{
-        BeaconBlock as DenebBeaconBlock, BeaconBlockBody as DenebBeaconBlockBody,
-        ExecutionPayload as DenebExecutionPayload,
+    deneb::{
+        containers::{
+            BeaconBlock as DenebBeaconBlock, BeaconBlockBody as DenebBeaconBlockBody,
+            ExecutionPayload as DenebExecutionPayload,
+        },
+        primitives::KzgCommitment,
     },
     nonstandard::{AttestationEpoch, Phase, RelativeEpoch},
     phase0::{
@@ -49,6 +54,11 @@ use types::{
     traits::BeaconState as _,
 };
 
+mod chain_builder;
+
+// Participation is expressed as a percentage of each committee.
+const FULL_PARTICIPATION: usize = 100;
+
 type BlockWithState<P> = (Arc<SignedBeaconBlock<P>>, Arc<BeaconState<P>>);
 
 pub fn min_genesis_state<P: Preset>(config: &Config) -> Result<(Arc<BeaconState<P>>, DepositTree)> {
@@ -83,6 +93,7 @@ pub fn empty_block<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -96,7 +107,12 @@ pub fn block_justifying_previous_epoch<P: Preset>(
     let advanced_state = advance_state(config, pre_state, block_slot)?;
     let eth1_data = advanced_state.eth1_data();
     let attestation_slots = misc::slots_in_epoch::<P>(epoch - 1);
-    let attestations = full_aggregate_attestations(config, &advanced_state, attestation_slots)?;
+    let attestations = aggregate_attestations(
+        config,
+        &advanced_state,
+        attestation_slots,
+        FULL_PARTICIPATION,
+    )?;
     let deposits = ContiguousList::default();
     let sync_aggregate = SyncAggregate::empty();
     let execution_payload = None;
@@ -110,6 +126,7 @@ pub fn block_justifying_previous_epoch<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -124,7 +141,12 @@ pub fn block_justifying_current_epoch<P: Preset>(
     let advanced_state = advance_state(config, pre_state, block_slot)?;
     let eth1_data = advanced_state.eth1_data();
     let attestation_slots = misc::compute_start_slot_at_epoch::<P>(epoch)..block_slot;
-    let attestations = full_aggregate_attestations(config, &advanced_state, attestation_slots)?;
+    let attestations = aggregate_attestations(
+        config,
+        &advanced_state,
+        attestation_slots,
+        FULL_PARTICIPATION,
+    )?;
     let deposits = ContiguousList::default();
     let sync_aggregate = SyncAggregate::empty();
 
@@ -137,6 +159,7 @@ pub fn block_justifying_current_epoch<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -162,6 +185,7 @@ pub fn block_with_deposits<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -187,6 +211,7 @@ pub fn block_with_eth1_vote_and_deposits<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -213,6 +238,7 @@ pub fn block_with_payload<P: Preset>(
         deposits,
         sync_aggregate,
         execution_payload,
+        ContiguousList::default(),
     )
 }
 
@@ -232,9 +258,14 @@ pub fn full_blocks_up_to_epoch<P: Preset>(
         let advanced_state = advance_state(config, pre_state, slot)?;
         let eth1_data = advanced_state.eth1_data();
         let graffiti = H256::zero();
-        let attestations = full_aggregate_attestations(config, &advanced_state, (slot - 1)..slot)?;
+        let attestations = aggregate_attestations(
+            config,
+            &advanced_state,
+            (slot - 1)..slot,
+            FULL_PARTICIPATION,
+        )?;
         let deposits = ContiguousList::default();
-        let sync_aggregate = full_sync_aggregate(config, &advanced_state);
+        let sync_aggregate = sync_committee_aggregate(config, &advanced_state, FULL_PARTICIPATION);
         let execution_payload = None;
 
         let (block, post_state) = block(
@@ -246,6 +277,7 @@ pub fn full_blocks_up_to_epoch<P: Preset>(
             deposits,
             sync_aggregate,
             execution_payload,
+            ContiguousList::default(),
         )?;
 
         pre_state = post_state;
@@ -375,6 +407,7 @@ fn block<P: Preset>(
     deposits: ContiguousList<Deposit, P::MaxDeposits>,
     sync_aggregate: SyncAggregate<P>,
     mut execution_payload: Option<ExecutionPayload<P>>,
+    blob_kzg_commitments: ContiguousList<KzgCommitment, P::MaxBlobCommitmentsPerBlock>,
 ) -> Result<BlockWithState<P>> {
     let slot = advanced_state.slot();
     let proposer_index = accessors::get_beacon_proposer_index(&advanced_state)?;
@@ -465,6 +498,7 @@ fn block<P: Preset>(
                 graffiti,
                 attestations,
                 sync_aggregate,
+                blob_kzg_commitments,
                 ..DenebBeaconBlockBody::default()
             },
         }),
@@ -484,10 +518,14 @@ fn block<P: Preset>(
 
 // `advanced_state` is the one for the block being constructed,
 // not the one that the attestations would be constructed with.
-fn full_aggregate_attestations<P: Preset>(
+//
+// Validators at the start of each committee attest. Committees where no validators would attest
+// are left out entirely because attestations must have at least one participant.
+fn aggregate_attestations<P: Preset>(
     config: &Config,
     advanced_state: &BeaconState<P>,
     slots: Range<Slot>,
+    participation_percentage: usize,
 ) -> Result<ContiguousList<Attestation<P>, P::MaxAttestations>> {
     let attestations = slots
         .into_iter()
@@ -514,7 +552,13 @@ fn full_aggregate_attestations<P: Preset>(
 
             let committees = accessors::beacon_committees(advanced_state, slot)?;
 
-            let attestations = committees.zip(0..).map(move |(committee, index)| {
+            let attestations = committees.zip(0..).filter_map(move |(committee, index)| {
+                let participant_count = committee.len() * participation_percentage / 100;
+
+                if participant_count == 0 {
+                    return None;
+                }
+
                 let data = AttestationData {
                     slot,
                     index,
@@ -527,16 +571,23 @@ fn full_aggregate_attestations<P: Preset>(
 
                 let signature = committee
                     .into_iter()
+                    .take(participant_count)
                     .map(|validator_index| interop::secret_key(validator_index).sign(signing_root))
                     .reduce(AggregateSignature::aggregate)
                     .unwrap_or_default()
                     .into();
 
-                Attestation {
-                    aggregation_bits: BitList::new(true, committee.len()),
+                let mut aggregation_bits = BitList::with_length(committee.len());
+
+                for position in 0..participant_count {
+                    aggregation_bits.set(position, true);
+                }
+
+                Some(Attestation {
+                    aggregation_bits,
                     data,
                     signature,
-                }
+                })
             });
 
             Ok(attestations)
@@ -549,21 +600,29 @@ fn full_aggregate_attestations<P: Preset>(
     .map_err(Into::into)
 }
 
-fn full_sync_aggregate<P: Preset>(
+// Like in `aggregate_attestations`, members at the start of the sync committee participate.
+fn sync_committee_aggregate<P: Preset>(
     config: &Config,
     advanced_state: &BeaconState<P>,
+    participation_percentage: usize,
 ) -> SyncAggregate<P> {
     let Some(advanced_state) = advanced_state.post_altair() else {
         return SyncAggregate::empty();
     };
 
+    let pubkeys = &advanced_state.current_sync_committee().pubkeys;
+    let participant_count = pubkeys.len() * participation_percentage / 100;
+
+    if participant_count == 0 {
+        return SyncAggregate::empty();
+    }
+
     let parent_root = accessors::latest_block_root(advanced_state);
     let signing_root = parent_root.signing_root(config, advanced_state, advanced_state.slot() - 1);
 
-    let sync_committee_signature = advanced_state
-        .current_sync_committee()
-        .pubkeys
+    let sync_committee_signature = pubkeys
         .iter()
+        .take(participant_count)
         .map(|pubkey| {
             accessors::index_of_public_key(advanced_state, pubkey.to_bytes()).expect(
                 "public keys in advanced_state.current_sync_committee \
@@ -575,8 +634,14 @@ fn full_sync_aggregate<P: Preset>(
         .unwrap_or_default()
         .into();
 
+    let mut sync_committee_bits = BitVector::default();
+
+    for position in 0..participant_count {
+        sync_committee_bits.set(position, true);
+    }
+
     SyncAggregate {
-        sync_committee_bits: BitVector::new(true),
+        sync_committee_bits,
         sync_committee_signature,
     }
 }
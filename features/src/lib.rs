@@ -40,6 +40,7 @@ static FEATURES: [AtomicBool; Feature::CARDINALITY] = [FALSE; Feature::CARDINALI
 pub enum Feature {
     AlwaysPrepackAttestations,
     CacheTargetStates,
+    DebugAttestationPool,
     DebugEth1,
     DebugP2p,
     DisableBlockVerificationPool,
@@ -0,0 +1,106 @@
+use helper_functions::misc;
+use types::{
+    config::Config,
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{Epoch, Slot},
+    },
+    preset::Preset,
+};
+
+/// Decides when blob sidecars can be deleted.
+///
+/// Nodes are required to serve blob sidecars from the last `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS`
+/// epochs. Older ones are only deleted once the blocks they belong to are finalized, so pruning
+/// is only worth doing after the finalized epoch advances.
+pub struct BlobRetention {
+    retention_epochs: u64,
+    last_finalized_epoch: Option<Epoch>,
+}
+
+impl BlobRetention {
+    /// `retention_epochs` overrides the retention period if it is longer than the one in `config`.
+    #[must_use]
+    pub fn new(config: &Config, retention_epochs: Option<u64>) -> Self {
+        let minimum = config.min_epochs_for_blob_sidecars_requests;
+
+        Self {
+            retention_epochs: retention_epochs.unwrap_or(minimum).max(minimum),
+            last_finalized_epoch: None,
+        }
+    }
+
+    /// Returns the slot before which blob sidecars should be deleted
+    /// if `finalized_epoch` has advanced since the last call.
+    pub fn on_epoch<P: Preset>(
+        &mut self,
+        current_epoch: Epoch,
+        finalized_epoch: Epoch,
+    ) -> Option<Slot> {
+        if self
+            .last_finalized_epoch
+            .is_some_and(|last_finalized_epoch| last_finalized_epoch >= finalized_epoch)
+        {
+            return None;
+        }
+
+        self.last_finalized_epoch = Some(finalized_epoch);
+
+        let up_to_epoch = current_epoch
+            .saturating_sub(self.retention_epochs)
+            .min(finalized_epoch);
+
+        (up_to_epoch > GENESIS_EPOCH).then(|| misc::compute_start_slot_at_epoch::<P>(up_to_epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn blob_sidecars_are_pruned_once_per_finalized_epoch() {
+        let config = Config::minimal();
+        let retention_epochs = config.min_epochs_for_blob_sidecars_requests;
+        let mut blob_retention = BlobRetention::new(&config, None);
+
+        assert_eq!(blob_retention.on_epoch::<Minimal>(10, 8), None);
+        assert_eq!(
+            blob_retention.on_epoch::<Minimal>(retention_epochs + 10, retention_epochs + 8),
+            Some(80),
+        );
+        assert_eq!(
+            blob_retention.on_epoch::<Minimal>(retention_epochs + 11, retention_epochs + 8),
+            None,
+        );
+    }
+
+    #[test]
+    fn blob_sidecars_of_unfinalized_blocks_are_retained() {
+        let config = Config::minimal();
+        let retention_epochs = config.min_epochs_for_blob_sidecars_requests;
+        let mut blob_retention = BlobRetention::new(&config, None);
+
+        assert_eq!(
+            blob_retention.on_epoch::<Minimal>(retention_epochs + 10, 4),
+            Some(32),
+        );
+    }
+
+    #[test]
+    fn retention_cannot_be_shorter_than_required() {
+        let config = Config::minimal();
+        let minimum = config.min_epochs_for_blob_sidecars_requests;
+
+        assert_eq!(
+            BlobRetention::new(&config, Some(1)).retention_epochs,
+            minimum,
+        );
+        assert_eq!(
+            BlobRetention::new(&config, Some(minimum * 2)).retention_epochs,
+            minimum * 2,
+        );
+    }
+}
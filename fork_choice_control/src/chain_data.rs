@@ -0,0 +1,472 @@
+// Export of canonical chain data for analysis in external tools.
+//
+// Rows are written as they are read from storage. Parquet output is buffered in row groups of
+// `ROWS_PER_BATCH` rows, so memory usage does not depend on the length of the exported range.
+//
+// Parquet output pulls in Arrow, which is large, so it is only available with the `parquet` feature.
+
+use std::path::Path;
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+#[cfg(feature = "parquet")]
+use arrow_array::{
+    builder::{StringBuilder, UInt64Builder},
+    ArrayRef, RecordBatch,
+};
+#[cfg(feature = "parquet")]
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use fs_err::File;
+use itertools::Itertools as _;
+#[cfg(feature = "parquet")]
+use parquet::arrow::ArrowWriter;
+#[cfg(feature = "parquet")]
+use std_ext::ArcExt as _;
+use strum::{Display, EnumString};
+use thiserror::Error;
+use types::{
+    combined::SignedBeaconBlock,
+    phase0::primitives::H256,
+    preset::Preset,
+    traits::{BeaconBlock as _, BeaconBlockBody as _, SignedBeaconBlock as _},
+};
+
+#[cfg(feature = "parquet")]
+const ROWS_PER_BATCH: usize = 8192;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ChainDataFormat {
+    Csv,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Display, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ChainDataField {
+    Slot,
+    BlockRoot,
+    ParentRoot,
+    Proposer,
+    AttestationCount,
+    DepositCount,
+    BlobCount,
+    Graffiti,
+}
+
+impl ChainDataField {
+    #[cfg(feature = "parquet")]
+    const fn data_type(self) -> DataType {
+        match self {
+            Self::BlockRoot | Self::ParentRoot | Self::Graffiti => DataType::Utf8,
+            Self::Slot
+            | Self::Proposer
+            | Self::AttestationCount
+            | Self::DepositCount
+            | Self::BlobCount => DataType::UInt64,
+        }
+    }
+
+    fn value<P: Preset>(self, block: &SignedBeaconBlock<P>, block_root: H256) -> Value {
+        let message = block.message();
+        let body = message.body();
+
+        match self {
+            Self::Slot => Value::Number(message.slot()),
+            Self::BlockRoot => Value::Text(format!("{block_root:?}")),
+            Self::ParentRoot => Value::Text(format!("{:?}", message.parent_root())),
+            Self::Proposer => Value::Number(message.proposer_index()),
+            Self::AttestationCount => Value::Number(body.attestations().len() as u64),
+            Self::DepositCount => Value::Number(body.deposits().len() as u64),
+            Self::BlobCount => Value::Number(
+                body.post_deneb()
+                    .map(|body| body.blob_kzg_commitments().len())
+                    .unwrap_or_default() as u64,
+            ),
+            Self::Graffiti => Value::Text(graffiti_text(body.graffiti())),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("no fields to export")]
+    NoFields,
+    #[error("field {field} is requested more than once")]
+    DuplicateField { field: ChainDataField },
+}
+
+enum Value {
+    Number(u64),
+    Text(String),
+}
+
+#[cfg(feature = "parquet")]
+enum ColumnBuilder {
+    Number(UInt64Builder),
+    Text(StringBuilder),
+}
+
+#[cfg(feature = "parquet")]
+impl ColumnBuilder {
+    fn new(data_type: DataType) -> Self {
+        if data_type == DataType::Utf8 {
+            Self::Text(StringBuilder::new())
+        } else {
+            Self::Number(UInt64Builder::new())
+        }
+    }
+
+    fn append(&mut self, value: Value) {
+        match (self, value) {
+            (Self::Number(builder), Value::Number(number)) => builder.append_value(number),
+            (Self::Text(builder), Value::Text(text)) => builder.append_value(text),
+            _ => unreachable!("values of a field are always of the same type"),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Number(builder) => Arc::new(builder.finish()),
+            Self::Text(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+pub struct ChainDataWriter {
+    fields: Vec<ChainDataField>,
+    sink: Sink,
+}
+
+enum Sink {
+    Csv(csv::Writer<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(ParquetSink),
+}
+
+#[cfg(feature = "parquet")]
+struct ParquetSink {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    columns: Vec<ColumnBuilder>,
+    buffered_rows: usize,
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetSink {
+    fn new(file: File, fields: &[ChainDataField]) -> Result<Self> {
+        let schema = Arc::new(Schema::new(
+            fields
+                .iter()
+                .map(|field| Field::new(field.to_string(), field.data_type(), false))
+                .collect_vec(),
+        ));
+
+        let columns = fields
+            .iter()
+            .map(|field| ColumnBuilder::new(field.data_type()))
+            .collect();
+
+        Ok(Self {
+            writer: ArrowWriter::try_new(file, schema.clone_arc(), None)?,
+            schema,
+            columns,
+            buffered_rows: 0,
+        })
+    }
+
+    fn append(&mut self, values: impl IntoIterator<Item = Value>) -> Result<()> {
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            column.append(value);
+        }
+
+        self.buffered_rows += 1;
+
+        if self.buffered_rows >= ROWS_PER_BATCH {
+            self.flush_batch()?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.flush_batch()?;
+        self.writer.close()?;
+        Ok(())
+    }
+
+    fn flush_batch(&mut self) -> Result<()> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+
+        let arrays = self
+            .columns
+            .iter_mut()
+            .map(ColumnBuilder::finish)
+            .collect_vec();
+
+        self.writer
+            .write(&RecordBatch::try_new(self.schema.clone_arc(), arrays)?)?;
+
+        self.buffered_rows = 0;
+
+        Ok(())
+    }
+}
+
+impl ChainDataWriter {
+    pub fn create(
+        path: &Path,
+        format: ChainDataFormat,
+        fields: Vec<ChainDataField>,
+    ) -> Result<Self> {
+        ensure!(!fields.is_empty(), Error::NoFields);
+
+        if let Some(field) = fields.iter().duplicates().next() {
+            return Err(Error::DuplicateField { field: *field }.into());
+        }
+
+        let file = File::create(path)?;
+
+        let sink = match format {
+            ChainDataFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(fields.iter().map(ToString::to_string))?;
+                Sink::Csv(writer)
+            }
+            #[cfg(feature = "parquet")]
+            ChainDataFormat::Parquet => Sink::Parquet(ParquetSink::new(file, &fields)?),
+        };
+
+        Ok(Self { fields, sink })
+    }
+
+    pub fn write_block<P: Preset>(
+        &mut self,
+        block: &SignedBeaconBlock<P>,
+        block_root: H256,
+    ) -> Result<()> {
+        let values = self
+            .fields
+            .iter()
+            .map(|field| field.value(block, block_root));
+
+        match &mut self.sink {
+            Sink::Csv(writer) => {
+                writer.write_record(values.map(|value| match value {
+                    Value::Number(number) => number.to_string(),
+                    Value::Text(text) => text,
+                }))?;
+            }
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(sink) => sink.append(values)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self.sink {
+            Sink::Csv(mut writer) => writer.flush()?,
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(sink) => sink.finish()?,
+        }
+
+        Ok(())
+    }
+}
+
+// Graffiti is usually text padded with zeros, but any bytes are valid.
+fn graffiti_text(graffiti: H256) -> String {
+    let bytes = graffiti.as_bytes();
+    let length = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |index| index + 1);
+
+    String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use tempfile::TempDir;
+    use types::{
+        phase0::containers::{
+            BeaconBlock as Phase0BeaconBlock, BeaconBlockBody as Phase0BeaconBlockBody,
+            SignedBeaconBlock as Phase0SignedBeaconBlock,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    const FIELDS: [ChainDataField; 4] = [
+        ChainDataField::Slot,
+        ChainDataField::BlockRoot,
+        ChainDataField::Proposer,
+        ChainDataField::Graffiti,
+    ];
+
+    fn block(slot: u64, graffiti: &[u8]) -> SignedBeaconBlock<Minimal> {
+        let mut graffiti_bytes = H256::zero();
+        graffiti_bytes[..graffiti.len()].copy_from_slice(graffiti);
+
+        Phase0SignedBeaconBlock {
+            message: Phase0BeaconBlock {
+                slot,
+                proposer_index: slot * 2,
+                body: Phase0BeaconBlockBody {
+                    graffiti: graffiti_bytes,
+                    ..Phase0BeaconBlockBody::default()
+                },
+                ..Phase0BeaconBlock::default()
+            },
+            ..Phase0SignedBeaconBlock::default()
+        }
+        .into()
+    }
+
+    fn write_blocks(path: &Path, format: ChainDataFormat) -> Result<()> {
+        let mut writer = ChainDataWriter::create(path, format, FIELDS.to_vec())?;
+
+        writer.write_block(&block(1, b"grandine"), H256::repeat_byte(1))?;
+        writer.write_block(&block(3, b""), H256::repeat_byte(3))?;
+        writer.finish()
+    }
+
+    fn expected_rows() -> Vec<Vec<String>> {
+        vec![
+            vec![
+                "1".to_owned(),
+                format!("{:?}", H256::repeat_byte(1)),
+                "2".to_owned(),
+                "grandine".to_owned(),
+            ],
+            vec![
+                "3".to_owned(),
+                format!("{:?}", H256::repeat_byte(3)),
+                "6".to_owned(),
+                String::new(),
+            ],
+        ]
+    }
+
+    #[test]
+    fn csv_output_can_be_read_back() -> Result<()> {
+        let directory = TempDir::new()?;
+        let path = directory.path().join("chain.csv");
+
+        write_blocks(&path, ChainDataFormat::Csv)?;
+
+        let mut reader = csv::Reader::from_reader(File::open(&path)?);
+
+        assert_eq!(
+            reader.headers()?.iter().collect_vec(),
+            ["slot", "block_root", "proposer", "graffiti"],
+        );
+
+        let rows = reader
+            .records()
+            .map_ok(|record| record.iter().map(ToOwned::to_owned).collect_vec())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(rows, expected_rows());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_output_can_be_read_back() -> Result<()> {
+        use arrow_array::{cast::AsArray as _, types::UInt64Type};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let directory = TempDir::new()?;
+        let path = directory.path().join("chain.parquet");
+
+        write_blocks(&path, ChainDataFormat::Parquet)?;
+
+        let (file, _) = File::open(&path)?.into_parts();
+        let batches = ParquetRecordBatchReaderBuilder::try_new(file)?
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+
+        assert_eq!(
+            batch.column(0).as_primitive::<UInt64Type>().values(),
+            &[1, 3],
+        );
+        assert_eq!(
+            batch.column(2).as_primitive::<UInt64Type>().values(),
+            &[2, 6],
+        );
+
+        let text_columns = [1, 3].map(|index| {
+            batch
+                .column(index)
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.map(ToOwned::to_owned).unwrap_or_default())
+                .collect_vec()
+        });
+
+        assert_eq!(
+            text_columns,
+            [
+                vec![
+                    format!("{:?}", H256::repeat_byte(1)),
+                    format!("{:?}", H256::repeat_byte(3)),
+                ],
+                vec!["grandine".to_owned(), String::new()],
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fields_are_parsed_from_snake_case_names() {
+        assert_eq!(
+            [
+                "slot",
+                "proposer",
+                "attestation_count",
+                "blob_count",
+                "graffiti"
+            ]
+            .into_iter()
+            .map(ChainDataField::from_str)
+            .collect::<Result<Vec<_>, _>>(),
+            Ok(vec![
+                ChainDataField::Slot,
+                ChainDataField::Proposer,
+                ChainDataField::AttestationCount,
+                ChainDataField::BlobCount,
+                ChainDataField::Graffiti,
+            ]),
+        );
+
+        assert!(ChainDataField::from_str("attestations").is_err());
+    }
+
+    #[test]
+    fn graffiti_is_trimmed_and_decoded_lossily() {
+        let mut graffiti = H256::zero();
+        graffiti[..8].copy_from_slice(b"grandine");
+
+        assert_eq!(graffiti_text(graffiti), "grandine");
+        assert_eq!(graffiti_text(H256::zero()), "");
+        assert_eq!(
+            graffiti_text(H256::repeat_byte(0xff)),
+            "\u{fffd}".repeat(32)
+        );
+    }
+}
@@ -1,20 +1,26 @@
 use core::time::Duration;
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use helper_functions::misc;
 use http_api_utils::BlockId;
 use log::info;
 use mime::APPLICATION_OCTET_STREAM;
 use reqwest::{header::ACCEPT, Client, StatusCode, Url};
-use ssz::SszRead;
+use ssz::{SszHash as _, SszRead};
 use thiserror::Error;
 use types::{
     combined::{BeaconState, SignedBeaconBlock},
     config::Config,
-    phase0::{consts::GENESIS_EPOCH, primitives::H256},
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{Slot, H256},
+    },
     preset::Preset,
-    traits::SignedBeaconBlock as _,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
 pub struct FinalizedCheckpoint<P: Preset> {
@@ -22,6 +28,12 @@ pub struct FinalizedCheckpoint<P: Preset> {
     pub state: Arc<BeaconState<P>>,
 }
 
+/// Paths of SSZ files to load a [`FinalizedCheckpoint`] from.
+pub struct CheckpointFiles {
+    pub block_file: PathBuf,
+    pub state_file: PathBuf,
+}
+
 pub async fn load_finalized_from_remote<P: Preset>(
     config: &Config,
     client: &Client,
@@ -65,6 +77,57 @@ pub async fn load_finalized_from_remote<P: Preset>(
     Ok(FinalizedCheckpoint { block, state })
 }
 
+pub fn load_finalized_from_files<P: Preset>(
+    config: &Config,
+    block_file: &Path,
+    state_file: &Path,
+) -> Result<FinalizedCheckpoint<P>> {
+    info!("loading checkpoint block from {block_file:?} and state from {state_file:?}…");
+
+    let block = Arc::from_ssz(config, fs_err::read(block_file)?)?;
+    let state = Arc::from_ssz(config, fs_err::read(state_file)?)?;
+
+    validate_anchor(&block, &state)?;
+
+    info!("loaded state at slot {}", block.message().slot());
+
+    Ok(FinalizedCheckpoint { block, state })
+}
+
+// Blocks and states loaded from remote beacon nodes are consistent by construction.
+// Ones loaded from files could have been saved separately.
+fn validate_anchor<P: Preset>(block: &SignedBeaconBlock<P>, state: &BeaconState<P>) -> Result<()> {
+    let block_slot = block.message().slot();
+    let state_slot = state.slot();
+
+    ensure!(
+        block_slot == state_slot,
+        Error::SlotMismatch {
+            block_slot,
+            state_slot,
+        },
+    );
+
+    // The anchor has to be usable as a checkpoint, which requires it to be at an epoch boundary.
+    ensure!(
+        misc::is_epoch_start::<P>(block_slot),
+        Error::AnchorNotAtEpochStart { slot: block_slot },
+    );
+
+    let block_state_root = block.message().state_root();
+    let state_root = state.hash_tree_root();
+
+    ensure!(
+        block_state_root == state_root,
+        Error::StateRootMismatch {
+            block_state_root,
+            state_root,
+        },
+    );
+
+    Ok(())
+}
+
 async fn fetch_block<P: Preset>(
     config: &Config,
     client: &Client,
@@ -111,10 +174,97 @@ async fn fetch<T: SszRead<Config>>(
 
 #[derive(Debug, Error)]
 enum Error {
+    #[error("checkpoint block at slot {slot} is not at the start of an epoch")]
+    AnchorNotAtEpochStart { slot: Slot },
     #[error("remote beacon node does not have post-state of block {block_root:?}")]
     MissingPostState { block_root: H256 },
     #[error("remote beacon node has no block usable as anchor")]
     NoBlockUsableAsAnchor,
     #[error("remote beacon node has no finalized block")]
     NoFinalizedBlock,
+    #[error(
+        "checkpoint block is at slot {block_slot} \
+         but checkpoint state is at slot {state_slot}"
+    )]
+    SlotMismatch { block_slot: Slot, state_slot: Slot },
+    #[error(
+        "checkpoint state root {state_root:?} \
+         does not match state root in checkpoint block {block_state_root:?}"
+    )]
+    StateRootMismatch {
+        block_state_root: H256,
+        state_root: H256,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std_ext::ArcExt as _;
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn validate_anchor_accepts_block_at_epoch_start_with_its_post_state() -> Result<()> {
+        let config = Config::minimal();
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let (block, state) = factory::empty_block(&config, genesis_state, 8, H256::zero())?;
+
+        validate_anchor(&block, &state)
+    }
+
+    #[test]
+    fn validate_anchor_rejects_block_in_middle_of_epoch() -> Result<()> {
+        let config = Config::minimal();
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let (block, state) = factory::empty_block(&config, genesis_state, 9, H256::zero())?;
+
+        let error = validate_anchor(&block, &state).expect_err("slot 9 is not an epoch start");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::AnchorNotAtEpochStart { slot: 9 }),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_anchor_rejects_state_at_different_slot() -> Result<()> {
+        let config = Config::minimal();
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let (block, _) = factory::empty_block(&config, genesis_state.clone_arc(), 8, H256::zero())?;
+
+        let error = validate_anchor(&block, &genesis_state)
+            .expect_err("state is not the post-state of the block");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::SlotMismatch {
+                block_slot: 8,
+                state_slot: 0,
+            }),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_anchor_rejects_post_state_of_other_block() -> Result<()> {
+        let config = Config::minimal();
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let (block, _) = factory::empty_block(&config, genesis_state.clone_arc(), 8, H256::zero())?;
+        let (_, other_state) =
+            factory::empty_block(&config, genesis_state, 8, H256::repeat_byte(1))?;
+
+        let error = validate_anchor(&block, &other_state)
+            .expect_err("state is the post-state of a block with different graffiti");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::StateRootMismatch { .. }),
+        ));
+
+        Ok(())
+    }
 }
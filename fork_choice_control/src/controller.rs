@@ -10,6 +10,7 @@
 
 use core::panic::AssertUnwindSafe;
 use std::{
+    path::Path,
     sync::{mpsc::Sender, Arc},
     thread::{Builder, JoinHandle},
     time::Instant,
@@ -17,6 +18,7 @@ use std::{
 
 use anyhow::{Context as _, Result};
 use arc_swap::{ArcSwap, Guard};
+use bytesize::ByteSize;
 use clock::Tick;
 use eth2_libp2p::{GossipId, PeerId};
 use execution_engine::{ExecutionEngine, PayloadStatusV1};
@@ -44,13 +46,18 @@ use types::{
 };
 
 use crate::{
+    blob_retention::BlobRetention,
     messages::{
         ApiMessage, MutatorMessage, P2pMessage, SubnetMessage, SyncMessage, ValidatorMessage,
     },
     misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
     mutator::Mutator,
+    proposer_duties::ProposerDutiesCache,
+    pruning_progress::PruningProgress,
     state_cache::StateCache,
-    storage::Storage,
+    storage::{ArchivePruningReport, Storage},
+    storage_back_sync::BackSyncStatus,
+    storage_writer::StorageWriter,
     tasks::{
         AggregateAndProofTask, AttestationTask, AttesterSlashingTask, BlobSidecarTask, BlockTask,
     },
@@ -64,6 +71,7 @@ pub struct Controller<P: Preset, E, W: Wait> {
     store_snapshot: Arc<ArcSwap<Store<P>>>,
     execution_engine: E,
     state_cache: Arc<StateCache<P, W>>,
+    proposer_duties_cache: Arc<ProposerDutiesCache>,
     storage: Arc<Storage<P>>,
     thread_pool: ThreadPool<P, E, W>,
     wait_group: W::Swappable,
@@ -121,11 +129,17 @@ where
             mutator_tx.clone(),
         ));
 
+        let proposer_duties_cache = Arc::new(ProposerDutiesCache::default());
+
+        let storage_writer = StorageWriter::new(storage.clone_arc(), sync_tx.clone())?;
+
         let mut mutator = Mutator::new(
             store_snapshot.clone_arc(),
             state_cache.clone_arc(),
+            proposer_duties_cache.clone_arc(),
             execution_engine.clone(),
             storage.clone_arc(),
+            storage_writer,
             thread_pool.clone(),
             metrics.clone(),
             mutator_tx.clone(),
@@ -145,6 +159,7 @@ where
             store_snapshot,
             execution_engine,
             state_cache,
+            proposer_duties_cache,
             storage,
             thread_pool,
             wait_group: wait_group.clone(),
@@ -225,6 +240,10 @@ where
         self.spawn_blob_sidecar_task(blob_sidecar, true, BlobSidecarOrigin::Api)
     }
 
+    pub fn on_execution_layer_blob_sidecar(&self, blob_sidecar: Arc<BlobSidecar<P>>) {
+        self.spawn_blob_sidecar_task(blob_sidecar, true, BlobSidecarOrigin::ExecutionLayer)
+    }
+
     pub fn on_api_block(
         &self,
         block: Arc<SignedBeaconBlock<P>>,
@@ -300,6 +319,22 @@ where
         })
     }
 
+    pub fn on_trusted_api_singular_attestation(
+        &self,
+        attestation: Arc<Attestation<P>>,
+        subnet_id: SubnetId,
+        sender: OneshotSender<Result<ValidationOutcome>>,
+    ) {
+        self.spawn(AttestationTask {
+            store_snapshot: self.owned_store_snapshot(),
+            mutator_tx: self.owned_mutator_tx(),
+            wait_group: self.owned_wait_group(),
+            attestation,
+            origin: AttestationOrigin::TrustedApi(subnet_id, sender),
+            metrics: self.metrics.clone(),
+        })
+    }
+
     pub fn on_gossip_singular_attestation(
         &self,
         attestation: Arc<Attestation<P>>,
@@ -416,8 +451,13 @@ where
     pub fn store_back_sync_blocks(
         &self,
         blocks: impl IntoIterator<Item = Arc<SignedBeaconBlock<P>>>,
+        status: BackSyncStatus,
     ) -> Result<()> {
-        self.storage.store_back_sync_blocks(blocks)
+        self.storage.store_back_sync_blocks(blocks, status)
+    }
+
+    pub fn back_sync_status(&self) -> Result<Option<BackSyncStatus>> {
+        self.storage.back_sync_status()
     }
 
     pub fn archive_back_sync_states(
@@ -430,6 +470,83 @@ where
             .archive_back_sync_states(start_slot, end_slot, genesis_provider)
     }
 
+    pub fn backup_storage(&self, directory: &Path, size: ByteSize) -> Result<()> {
+        self.storage.backup(directory, size)
+    }
+
+    pub fn compact_storage(&self) -> Result<u64> {
+        self.storage.compact()
+    }
+
+    #[must_use]
+    pub fn storage_supports_online_compaction(&self) -> bool {
+        self.storage.supports_online_compaction()
+    }
+
+    /// Deletes blob sidecars outside the retention period without waiting for the next epoch.
+    pub fn prune_blob_sidecars(&self, progress: &PruningProgress) -> Result<()> {
+        let _pruning = self.storage.lock_pruning()?;
+        let store = self.store_snapshot();
+
+        let up_to_slot =
+            BlobRetention::new(store.chain_config(), self.storage.blob_retention_epochs())
+                .on_epoch::<P>(store.current_epoch(), store.finalized_epoch());
+
+        match up_to_slot {
+            Some(up_to_slot) => self.storage.prune_old_blob_sidecars(up_to_slot, progress),
+            None => Ok(()),
+        }
+    }
+
+    pub fn prune_orphaned_blocks(&self, progress: &PruningProgress) -> Result<()> {
+        let _pruning = self.storage.lock_pruning()?;
+        self.storage.prune_orphaned_blocks(progress)
+    }
+
+    /// Deletes states and blocks in epochs more than `retain_epochs` epochs before the finalized
+    /// epoch like `--prune-history-epochs` does.
+    pub fn prune_history(
+        &self,
+        retain_epochs: u64,
+        progress: &PruningProgress,
+    ) -> Result<ArchivePruningReport> {
+        let _pruning = self.storage.lock_pruning()?;
+        let finalized_epoch = self.store_snapshot().finalized_epoch();
+
+        self.storage
+            .prune_history(finalized_epoch, retain_epochs, progress)
+    }
+
+    /// Deletes execution payloads stored separately from blocks in epochs more than
+    /// `retain_epochs` epochs before the finalized epoch.
+    pub fn prune_execution_payloads(
+        &self,
+        retain_epochs: u64,
+        progress: &PruningProgress,
+    ) -> Result<usize> {
+        let _pruning = self.storage.lock_pruning()?;
+        let finalized_epoch = self.store_snapshot().finalized_epoch();
+
+        self.storage
+            .prune_execution_payloads(finalized_epoch, retain_epochs, progress)
+    }
+
+    /// Returns `true` if data is being pruned, either automatically or by an earlier request.
+    #[must_use]
+    pub fn is_pruning(&self) -> bool {
+        self.storage.is_pruning()
+    }
+
+    #[must_use]
+    pub fn prune_history_epochs(&self) -> Option<u64> {
+        self.storage.prune_history_epochs()
+    }
+
+    #[must_use]
+    pub fn execution_payload_retention_epochs(&self) -> Option<u64> {
+        self.storage.execution_payload_retention_epochs()
+    }
+
     fn spawn_blob_sidecar_task(
         &self,
         blob_sidecar: Arc<BlobSidecar<P>>,
@@ -493,6 +610,10 @@ where
         &self.state_cache
     }
 
+    pub(crate) const fn proposer_duties_cache(&self) -> &Arc<ProposerDutiesCache> {
+        &self.proposer_duties_cache
+    }
+
     pub(crate) fn store_snapshot(&self) -> Guard<Arc<Store<P>>> {
         self.store_snapshot.load()
     }
@@ -0,0 +1,707 @@
+//! Reading and writing finalized blocks and states in era files.
+//!
+//! Era files are [e2store] archives containing the blocks of one period of
+//! `SLOTS_PER_HISTORICAL_ROOT` slots followed by the state at the end of the period.
+//! See the [era format specification] for details.
+//!
+//! `.era1` files contain pre-merge execution layer history and are not read here.
+//!
+//! Era files come from outside the node, so blocks are only served from them after the era is
+//! verified against the historical roots or summaries of a state the node trusts. The block and
+//! state roots of an era are read from the state at its end, and every block read is checked
+//! against the block roots.
+//!
+//! [e2store]:                    https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md
+//! [era format specification]:   https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era.md
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{ensure, Result};
+use fs_err::File;
+use log::{info, warn};
+use parking_lot::{Mutex, RwLock};
+use snap::{read::FrameDecoder, write::FrameEncoder};
+use ssz::{SszHash as _, SszRead as _, SszReadDefault as _, SszWrite as _};
+use thiserror::Error;
+use typenum::Unsigned as _;
+use types::{
+    capella::containers::HistoricalSummary,
+    combined::{BeaconState, SignedBeaconBlock},
+    config::Config,
+    phase0::{
+        containers::HistoricalBatch,
+        primitives::{Slot, H256},
+    },
+    preset::Preset,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
+};
+
+const ERA_EXTENSION: &str = "era";
+
+const HEADER_SIZE: u64 = 8;
+const INDEX_ENTRY_SIZE: u64 = 8;
+
+const VERSION: [u8; 2] = [0x65, 0x32];
+const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+// Every era file ends with a slot index containing a single entry pointing at the state.
+const STATE_INDEX_SIZE: u64 = HEADER_SIZE + 3 * INDEX_ENTRY_SIZE;
+
+// Fields preceding `block_roots` in `BeaconState` have the same sizes in all phases:
+// `genesis_time`, `genesis_validators_root`, `slot`, `fork` and `latest_block_header`.
+// `block_roots` is followed by `state_roots`, so the two can be read as a `HistoricalBatch`
+// without decompressing the rest of the state.
+const STATE_BLOCK_ROOTS_OFFSET: u64 = 8 + 32 + 8 + 16 + 112;
+
+pub struct EraStore {
+    // Paths to era files keyed by era number.
+    files: BTreeMap<u64, PathBuf>,
+    // Accumulators of the anchor state. Eras are verified against them.
+    trusted_history: RwLock<Option<TrustedHistory>>,
+    // Block roots of eras that have already been verified, keyed by era number.
+    verified_block_roots: Mutex<HashMap<u64, Arc<[H256]>>>,
+}
+
+struct TrustedHistory {
+    historical_roots: Vec<H256>,
+    historical_summaries: Vec<HistoricalSummary>,
+}
+
+impl EraStore {
+    /// Finds era files in `directory`.
+    ///
+    /// Files are expected to be named `<config-name>-<era-number>-<short-historical-root>.era`.
+    pub fn new(directory: impl AsRef<Path>) -> Result<Self> {
+        let directory = directory.as_ref();
+        let mut files = BTreeMap::new();
+
+        for entry in fs_err::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path.extension() != Some(OsStr::new(ERA_EXTENSION)) {
+                continue;
+            }
+
+            match era_number(&path) {
+                Some(era) => {
+                    files.insert(era, path);
+                }
+                None => warn!("ignoring era file with unexpected name: {path:?}"),
+            }
+        }
+
+        info!("found {} era files in {directory:?}", files.len());
+
+        Ok(Self {
+            files,
+            trusted_history: RwLock::new(None),
+            verified_block_roots: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Makes eras covered by the historical roots and summaries in `state` available.
+    ///
+    /// `state` must be trusted, like the anchor state the node was started from.
+    pub fn trust_history<P: Preset>(&self, state: &BeaconState<P>) {
+        let historical_summaries = match state {
+            BeaconState::Phase0(_) | BeaconState::Altair(_) | BeaconState::Bellatrix(_) => vec![],
+            BeaconState::Capella(state) => {
+                state.historical_summaries.into_iter().copied().collect()
+            }
+            BeaconState::Deneb(state) => state.historical_summaries.into_iter().copied().collect(),
+        };
+
+        *self.trusted_history.write() = Some(TrustedHistory {
+            historical_roots: state.historical_roots().into_iter().copied().collect(),
+            historical_summaries,
+        });
+    }
+
+    pub fn block_by_slot<P: Preset>(
+        &self,
+        config: &Config,
+        slot: Slot,
+    ) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
+        // Era `N` contains blocks from slots before the state at the start of era `N`.
+        let era = slot / P::SlotsPerHistoricalRoot::U64 + 1;
+
+        let Some(path) = self.files.get(&era) else {
+            return Ok(None);
+        };
+
+        let block_roots = self.verified_block_roots::<P>(era, path)?;
+
+        let Some(block) = read_block(config, File::open(path)?, slot)? else {
+            return Ok(None);
+        };
+
+        let index = usize::try_from(slot % P::SlotsPerHistoricalRoot::U64)?;
+        let expected = block_roots[index];
+        let actual = block.message().hash_tree_root();
+
+        ensure!(
+            actual == expected,
+            Error::BlockRootMismatch {
+                slot,
+                expected,
+                actual,
+            },
+        );
+
+        Ok(Some(block))
+    }
+
+    fn verified_block_roots<P: Preset>(&self, era: u64, path: &Path) -> Result<Arc<[H256]>> {
+        if let Some(block_roots) = self.verified_block_roots.lock().get(&era) {
+            return Ok(block_roots.clone());
+        }
+
+        let state_slot = era * P::SlotsPerHistoricalRoot::U64;
+        let batch = read_historical_batch::<P>(File::open(path)?, state_slot)?;
+
+        let trusted_history = self.trusted_history.read();
+        let trusted_history = trusted_history
+            .as_ref()
+            .ok_or(Error::EraNotCovered { era })?;
+
+        // Era `N` is accumulated at index `N - 1`. Historical roots stopped being accumulated in
+        // Capella and historical summaries continue where they left off.
+        let index = usize::try_from(era - 1)?;
+        let historical_root_count = trusted_history.historical_roots.len();
+
+        let verified = if index < historical_root_count {
+            trusted_history.historical_roots[index] == batch.hash_tree_root()
+        } else {
+            let summary = trusted_history
+                .historical_summaries
+                .get(index - historical_root_count)
+                .ok_or(Error::EraNotCovered { era })?;
+
+            summary.block_summary_root == batch.block_roots.hash_tree_root()
+                && summary.state_summary_root == batch.state_roots.hash_tree_root()
+        };
+
+        ensure!(verified, Error::HistoricalRootMismatch { era });
+
+        let block_roots = batch.block_roots.into_iter().copied().collect::<Arc<[_]>>();
+
+        self.verified_block_roots
+            .lock()
+            .insert(era, block_roots.clone());
+
+        info!("verified era file {path:?}");
+
+        Ok(block_roots)
+    }
+}
+
+/// Returns the name of the era file ending with `state`.
+pub fn file_name<P: Preset>(config: &Config, state: &BeaconState<P>) -> Result<String> {
+    let state_slot = state.slot();
+    let era = state_slot / P::SlotsPerHistoricalRoot::U64;
+
+    ensure!(
+        state_slot % P::SlotsPerHistoricalRoot::U64 == 0,
+        Error::StateNotAtEraBoundary { state_slot },
+    );
+
+    // The genesis era has no historical root, so the genesis validators root is used instead.
+    let root = if era == 0 {
+        state.genesis_validators_root()
+    } else {
+        last_historical_root(state).ok_or(Error::HistoricalRootMissing { era })?
+    };
+
+    let short_root = root.as_bytes()[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    Ok(format!(
+        "{}-{era:05}-{short_root}.{ERA_EXTENSION}",
+        config.config_name
+    ))
+}
+
+/// Writes an era file ending with `state`.
+///
+/// `blocks` must contain the blocks of the era in order of increasing slot.
+/// They are taken from an iterator to avoid keeping a whole era of blocks in memory.
+pub fn write<P: Preset>(
+    mut writer: impl Write,
+    state: &BeaconState<P>,
+    blocks: impl IntoIterator<Item = Result<Arc<SignedBeaconBlock<P>>>>,
+) -> Result<()> {
+    let state_slot = state.slot();
+    let slots_per_historical_root = P::SlotsPerHistoricalRoot::U64;
+
+    ensure!(
+        state_slot % slots_per_historical_root == 0,
+        Error::StateNotAtEraBoundary { state_slot },
+    );
+
+    let mut position = write_entry(&mut writer, VERSION, &[])?;
+    let mut block_positions = BTreeMap::new();
+
+    for block in blocks {
+        let block = block?;
+        block_positions.insert(block.message().slot(), position);
+        position += write_entry(
+            &mut writer,
+            COMPRESSED_SIGNED_BEACON_BLOCK,
+            &compress(&block.to_ssz()?)?,
+        )?;
+    }
+
+    let state_position = position;
+
+    position += write_entry(
+        &mut writer,
+        COMPRESSED_BEACON_STATE,
+        &compress(&state.to_ssz()?)?,
+    )?;
+
+    // The genesis era contains no blocks and no block index.
+    if let Some(starting_slot) = state_slot.checked_sub(slots_per_historical_root) {
+        let offsets = (starting_slot..state_slot)
+            .map(|slot| match block_positions.get(&slot) {
+                Some(block_position) => relative_offset(*block_position, position),
+                None => Ok(0),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        position += write_slot_index(&mut writer, starting_slot, &offsets)?;
+    }
+
+    let state_offset = relative_offset(state_position, position)?;
+
+    write_slot_index(&mut writer, state_slot, &[state_offset])?;
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn last_historical_root<P: Preset>(state: &BeaconState<P>) -> Option<H256> {
+    let historical_summaries = match state {
+        BeaconState::Phase0(_) | BeaconState::Altair(_) | BeaconState::Bellatrix(_) => None,
+        BeaconState::Capella(state) => Some(&state.historical_summaries),
+        BeaconState::Deneb(state) => Some(&state.historical_summaries),
+    };
+
+    // Historical roots stopped being accumulated in Capella.
+    // Historical summaries are appended in their place.
+    if let Some(historical_summaries) = historical_summaries {
+        if let Some(last_index) = historical_summaries.len_u64().checked_sub(1) {
+            // Era file names use the root of the whole summary rather than one of its fields.
+            let summary = historical_summaries.get(last_index).ok()?;
+            return Some(summary.hash_tree_root());
+        }
+    }
+
+    let historical_roots = state.historical_roots();
+    let last_index = historical_roots.len_u64().checked_sub(1)?;
+
+    historical_roots.get(last_index).ok().copied()
+}
+
+fn compress(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = FrameEncoder::new(vec![]);
+    encoder.write_all(bytes)?;
+    encoder
+        .into_inner()
+        .map_err(|error| error.into_error().into())
+}
+
+// Offsets in slot indices are relative to the start of the index.
+fn relative_offset(position: u64, index_position: u64) -> Result<i64> {
+    Ok(i64::try_from(position)? - i64::try_from(index_position)?)
+}
+
+// Returns the number of bytes written.
+fn write_entry(writer: &mut impl Write, entry_type: [u8; 2], data: &[u8]) -> Result<u64> {
+    let length = u32::try_from(data.len())?;
+
+    writer.write_all(&entry_type)?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&[0, 0])?;
+    writer.write_all(data)?;
+
+    Ok(HEADER_SIZE + u64::from(length))
+}
+
+fn write_slot_index(writer: &mut impl Write, starting_slot: Slot, offsets: &[i64]) -> Result<u64> {
+    let count = u64::try_from(offsets.len())?;
+
+    let data = starting_slot
+        .to_le_bytes()
+        .into_iter()
+        .chain(offsets.iter().flat_map(|offset| offset.to_le_bytes()))
+        .chain(count.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    write_entry(writer, SLOT_INDEX, &data)
+}
+
+fn era_number(path: &Path) -> Option<u64> {
+    let stem = path.file_stem()?.to_str()?;
+    let mut parts = stem.rsplitn(3, '-');
+    let _short_historical_root = parts.next()?;
+    let era_number = parts.next()?;
+    let _config_name = parts.next()?;
+    era_number.parse().ok()
+}
+
+fn read_block<P: Preset>(
+    config: &Config,
+    mut reader: impl Read + Seek,
+    slot: Slot,
+) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
+    let file_length = reader.seek(SeekFrom::End(0))?;
+
+    let block_index_end = file_length
+        .checked_sub(STATE_INDEX_SIZE)
+        .ok_or(Error::FileTooShort { file_length })?;
+
+    let Some(position) = block_position(&mut reader, block_index_end, slot)? else {
+        return Ok(None);
+    };
+
+    let compressed = read_entry(&mut reader, position, COMPRESSED_SIGNED_BEACON_BLOCK)?;
+
+    let mut bytes = vec![];
+    FrameDecoder::new(compressed.as_slice()).read_to_end(&mut bytes)?;
+
+    let block = Arc::<SignedBeaconBlock<P>>::from_ssz(config, bytes)?;
+    let block_slot = block.message().slot();
+
+    ensure!(
+        block_slot == slot,
+        Error::SlotMismatch {
+            expected: slot,
+            actual: block_slot,
+        },
+    );
+
+    Ok(Some(block))
+}
+
+// Reads the block and state roots of the era ending with the state at `state_slot`.
+fn read_historical_batch<P: Preset>(
+    mut reader: impl Read + Seek,
+    state_slot: Slot,
+) -> Result<HistoricalBatch<P>> {
+    let file_length = reader.seek(SeekFrom::End(0))?;
+
+    let position =
+        block_position(&mut reader, file_length, state_slot)?.ok_or(Error::MalformedSlotIndex)?;
+
+    let length = read_header(&mut reader, position, COMPRESSED_BEACON_STATE)?;
+    let mut decoder = FrameDecoder::new(reader.take(length.into()));
+
+    io::copy(
+        &mut decoder.by_ref().take(STATE_BLOCK_ROOTS_OFFSET),
+        &mut io::sink(),
+    )?;
+
+    let batch_size = 2 * P::SlotsPerHistoricalRoot::USIZE * H256::len_bytes();
+    let mut bytes = vec![0; batch_size];
+    decoder.read_exact(&mut bytes)?;
+
+    HistoricalBatch::from_ssz_default(bytes).map_err(Into::into)
+}
+
+// Looks up `slot` in the slot index ending at `index_end`.
+// Returns `None` if `slot` is empty or not covered by the index.
+fn block_position(
+    reader: &mut (impl Read + Seek),
+    index_end: u64,
+    slot: Slot,
+) -> Result<Option<u64>> {
+    let count_position = index_end
+        .checked_sub(INDEX_ENTRY_SIZE)
+        .ok_or(Error::MalformedSlotIndex)?;
+
+    let count = read_u64(reader, count_position)?;
+
+    let index_start = count
+        .checked_mul(INDEX_ENTRY_SIZE)
+        .and_then(|entries_size| entries_size.checked_add(HEADER_SIZE + 2 * INDEX_ENTRY_SIZE))
+        .and_then(|index_size| index_end.checked_sub(index_size))
+        .ok_or(Error::MalformedSlotIndex)?;
+
+    read_header(reader, index_start, SLOT_INDEX)?;
+
+    let starting_slot = read_u64(reader, index_start + HEADER_SIZE)?;
+
+    let Some(index) = slot
+        .checked_sub(starting_slot)
+        .filter(|index| *index < count)
+    else {
+        return Ok(None);
+    };
+
+    let offset_position = index_start + HEADER_SIZE + INDEX_ENTRY_SIZE * (index + 1);
+
+    // Offsets are relative to the start of the index. Empty slots are represented by 0.
+    let offset = i64::from_le_bytes(read_array(reader, offset_position)?);
+
+    if offset == 0 {
+        return Ok(None);
+    }
+
+    let position = index_start
+        .checked_add_signed(offset)
+        .ok_or(Error::MalformedSlotIndex)?;
+
+    Ok(Some(position))
+}
+
+fn read_entry(
+    reader: &mut (impl Read + Seek),
+    position: u64,
+    expected_type: [u8; 2],
+) -> Result<Vec<u8>> {
+    let length = read_header(reader, position, expected_type)?;
+    let mut data = vec![0; usize::try_from(length)?];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+// Returns the length of the entry and leaves `reader` positioned at the start of its data.
+fn read_header(
+    reader: &mut (impl Read + Seek),
+    position: u64,
+    expected_type: [u8; 2],
+) -> Result<u32> {
+    let header = read_array::<8>(reader, position)?;
+    let [type_0, type_1, length @ .., _, _] = header;
+    let actual_type = [type_0, type_1];
+
+    ensure!(
+        actual_type == expected_type,
+        Error::UnexpectedEntryType {
+            position,
+            expected: expected_type,
+            actual: actual_type,
+        },
+    );
+
+    Ok(u32::from_le_bytes(length))
+}
+
+fn read_u64(reader: &mut (impl Read + Seek), position: u64) -> Result<u64> {
+    read_array(reader, position).map(u64::from_le_bytes)
+}
+
+fn read_array<const N: usize>(reader: &mut (impl Read + Seek), position: u64) -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.seek(SeekFrom::Start(position))?;
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error(
+        "block at slot {slot} in era file has root {actual:?} \
+         but the era state has {expected:?}"
+    )]
+    BlockRootMismatch {
+        slot: Slot,
+        expected: H256,
+        actual: H256,
+    },
+    #[error("era {era} is not covered by the history of the anchor state and cannot be verified")]
+    EraNotCovered { era: u64 },
+    #[error("era file is too short ({file_length} bytes)")]
+    FileTooShort { file_length: u64 },
+    #[error("era file for era {era} does not match the historical root of the anchor state")]
+    HistoricalRootMismatch { era: u64 },
+    #[error("state for era {era} has no historical roots")]
+    HistoricalRootMissing { era: u64 },
+    #[error("era file contains a malformed slot index")]
+    MalformedSlotIndex,
+    #[error("era file contains block at slot {actual} in place of slot {expected}")]
+    SlotMismatch { expected: Slot, actual: Slot },
+    #[error("state at slot {state_slot} is not at the end of an era")]
+    StateNotAtEraBoundary { state_slot: Slot },
+    #[error(
+        "era file contains entry of type {actual:?} at position {position} \
+         where {expected:?} was expected"
+    )]
+    UnexpectedEntryType {
+        position: u64,
+        expected: [u8; 2],
+        actual: [u8; 2],
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use std_ext::ArcExt as _;
+    use tempfile::TempDir;
+    use types::{
+        capella::beacon_state::BeaconState as CapellaBeaconState,
+        phase0::containers::{
+            BeaconBlock as Phase0BeaconBlock, SignedBeaconBlock as Phase0SignedBeaconBlock,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    fn block_at(slot: Slot) -> Arc<SignedBeaconBlock<Minimal>> {
+        Arc::new(SignedBeaconBlock::from(Phase0SignedBeaconBlock {
+            message: Phase0BeaconBlock {
+                slot,
+                ..Phase0BeaconBlock::default()
+            },
+            ..Phase0SignedBeaconBlock::default()
+        }))
+    }
+
+    #[test]
+    fn blocks_written_to_era_file_can_be_read() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        *state.make_mut().slot_mut() = 128;
+
+        let mut bytes = vec![];
+        let blocks = [64, 66, 127].map(block_at).map(Ok);
+
+        write(&mut bytes, &state, blocks)?;
+
+        for slot in [64, 66, 127] {
+            let block = read_block::<Minimal>(&config, Cursor::new(&bytes), slot)?
+                .expect("era file contains a block at this slot");
+
+            assert_eq!(block.message().slot(), slot);
+        }
+
+        for slot in [63, 65, 128] {
+            assert!(read_block::<Minimal>(&config, Cursor::new(&bytes), slot)?.is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_are_only_served_from_eras_matching_trusted_history() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let blocks = [64, 66, 127].map(block_at);
+
+        *state.make_mut().slot_mut() = 128;
+
+        for block in &blocks {
+            *state
+                .make_mut()
+                .block_roots_mut()
+                .mod_index_mut(block.message().slot()) = block.message().hash_tree_root();
+        }
+
+        let directory = TempDir::new()?;
+        let path = directory.path().join("minimal-00002-00000000.era");
+
+        write(File::create(path)?, &state, blocks.clone().map(Ok))?;
+
+        let batch = HistoricalBatch::<Minimal> {
+            block_roots: state.block_roots().clone(),
+            state_roots: state.state_roots().clone(),
+        };
+
+        let trusted_state = |era_2_root| -> Result<_> {
+            let mut trusted_state = state.clone_arc();
+            let historical_roots = trusted_state.make_mut().historical_roots_mut();
+            historical_roots.push(H256::zero())?;
+            historical_roots.push(era_2_root)?;
+            Ok(trusted_state)
+        };
+
+        let era_store = EraStore::new(directory.path())?;
+
+        era_store
+            .block_by_slot::<Minimal>(&config, 66)
+            .expect_err("eras cannot be verified without a trusted state");
+
+        era_store.trust_history(&trusted_state(batch.hash_tree_root())?);
+
+        let block = era_store
+            .block_by_slot::<Minimal>(&config, 66)?
+            .expect("era file contains a block at this slot");
+
+        assert_eq!(block, blocks[1]);
+        assert!(era_store.block_by_slot::<Minimal>(&config, 65)?.is_none());
+
+        let era_store = EraStore::new(directory.path())?;
+
+        era_store.trust_history(&trusted_state(H256::repeat_byte(1))?);
+
+        era_store
+            .block_by_slot::<Minimal>(&config, 66)
+            .expect_err("era file does not match trusted history");
+
+        Ok(())
+    }
+
+    #[test]
+    fn genesis_era_file_is_named_after_genesis_validators_root() -> Result<()> {
+        let config = Config::minimal();
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let short_root = &format!("{:x}", state.genesis_validators_root())[..8];
+
+        assert_eq!(
+            file_name(&config, &state)?,
+            format!("minimal-00000-{short_root}.era"),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn capella_era_file_is_named_after_last_historical_summary() -> Result<()> {
+        let config = Config::minimal();
+        let summary = HistoricalSummary {
+            block_summary_root: H256::repeat_byte(1),
+            state_summary_root: H256::repeat_byte(2),
+        };
+
+        let mut state = CapellaBeaconState::<Minimal> {
+            slot: 128,
+            ..CapellaBeaconState::default()
+        };
+
+        state.historical_summaries.push(summary)?;
+
+        let state = BeaconState::from(state);
+        let short_root = &format!("{:x}", summary.hash_tree_root())[..8];
+
+        assert_eq!(
+            file_name(&config, &state)?,
+            format!("minimal-00002-{short_root}.era"),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn era_numbers_are_parsed_from_file_names() {
+        assert_eq!(
+            era_number(Path::new("mainnet-01234-0123abcd.era")),
+            Some(1234),
+        );
+        assert_eq!(era_number(Path::new("mainnet-0123abcd.era")), None);
+    }
+}
@@ -85,6 +85,46 @@ fn processes_blocks_from_medalla_in_their_slots() {
     }
 }
 
+#[test]
+fn blocks_are_processed_while_snapshot_is_held() {
+    let config = Arc::new(Config::medalla());
+    let genesis_state = medalla::GENESIS_BEACON_STATE.force().clone_arc();
+    let genesis_block = medalla::GENESIS_BEACON_BLOCK.force().clone_arc();
+    let genesis_block_root = genesis_block.message().hash_tree_root();
+    let blocks = medalla::beacon_blocks(1..=32, 4);
+
+    let last_block = blocks
+        .last()
+        .expect("Medalla should have blocks in the first epoch")
+        .clone_arc();
+
+    let (controller, _mutator_handle) =
+        TestController::quiet(config, genesis_block, genesis_state.clone_arc());
+
+    let snapshot = controller.snapshot();
+
+    for block in blocks {
+        controller.on_slot(block.message().slot());
+        controller.wait_for_tasks();
+        controller.on_gossip_block(block, GossipId::default());
+        controller.wait_for_tasks();
+    }
+
+    // The snapshot taken before the blocks were processed should not see any of them.
+    assert_eq!(snapshot.head_slot(), GENESIS_SLOT);
+    assert_eq!(snapshot.head_state(), genesis_state);
+    assert_eq!(snapshot.finalized_root(), genesis_block_root);
+
+    let head = controller.head().value;
+
+    assert_eq!(head.block_root, last_block.message().hash_tree_root());
+    assert_eq!(
+        controller.snapshot().head_slot(),
+        last_block.message().slot()
+    );
+    assert_ne!(controller.snapshot().head_state(), genesis_state);
+}
+
 // The blocks were not actually invalid.
 // We had missed the change to the type of `WithdrawalV1.amount` in `execution-apis`.
 #[test]
@@ -1091,6 +1131,23 @@ fn ignores_attestations_that_vote_for_old_blocks() {
     context.on_ignorable_singular_attestation(&state_1, attestation_epoch, 0);
 }
 
+#[test]
+fn latest_message_epochs_reflect_attestations_applied_to_fork_choice() {
+    let mut context = Context::minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, state_1) = context.empty_block(&state_0, 1, H256::default());
+
+    context.on_slot(start_of_epoch(2));
+    context.on_acceptable_block(&block_1);
+
+    context.assert_latest_message_epochs([(0, None), (1, None)]);
+
+    context.on_acceptable_singular_attestation(&state_1, 1, 0);
+
+    context.assert_latest_message_epochs([(0, Some(1)), (1, None)]);
+}
+
 // 0
 //  \
 //   1
@@ -2501,7 +2558,7 @@ fn payload_statuses_are_updated_if_latest_valid_hash_is_all_zeros() {
 }
 
 #[test]
-fn new_descendants_of_an_invalidated_block_are_immediately_invalidated() {
+fn new_descendants_of_an_invalidated_block_are_ignored() {
     let mut context = Context::bellatrix_minimal();
 
     let (_, state_0) = context.genesis();
@@ -2525,7 +2582,45 @@ fn new_descendants_of_an_invalidated_block_are_immediately_invalidated() {
     context.on_ignorable_block(&block_2);
 
     context.assert_payload_status(&block_1, Some(PayloadStatus::Invalid));
+    context.assert_payload_status(&block_2, None);
+}
+
+#[test]
+fn new_descendants_of_latest_valid_block_are_accepted_after_invalidation() {
+    let mut context = Context::bellatrix_minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, state_1) =
+        context.block_with_payload(&state_0, 1, H256::default(), H256::repeat_byte(1));
+    let (block_2, _) =
+        context.block_with_payload(&state_1, 2, H256::default(), H256::repeat_byte(2));
+    let (block_3, _) =
+        context.block_with_payload(&state_1, 3, H256::default(), H256::repeat_byte(3));
+
+    context.on_slot(block_3.message().slot());
+
+    context.on_acceptable_block(&block_1);
+    context.on_acceptable_block(&block_2);
+
+    context.on_notified_invalid_payload(&block_2, Some(&block_1));
+
+    context.assert_payload_status(&block_1, Some(PayloadStatus::Valid));
     context.assert_payload_status(&block_2, Some(PayloadStatus::Invalid));
+
+    context.on_acceptable_block(&block_3);
+
+    context.assert_payload_status(&block_3, Some(PayloadStatus::Optimistic));
+    context.assert_status(Status {
+        head: &block_3,
+        attesting_validators: Some(0),
+        store_justified_epoch: 0,
+        store_finalized_epoch: 0,
+        fork_count_viable: 2,
+        fork_count_total: 2,
+        finalized_block_count: 1,
+        unfinalized_block_count_in_fork: 2,
+        unfinalized_block_count_total: 2,
+    });
 }
 
 // This was originally based on [`NoViableHeadDueToOptimisticSync`] in Hive.
@@ -2769,3 +2864,67 @@ fn reorganizing_due_to_invalidation_sends_notifications_if_common_ancestor_is_un
         unfinalized_block_count_total: 1,
     });
 }
+
+#[test]
+fn head_follows_fork_with_more_attestations() -> Result<()> {
+    let mut context = Context::minimal();
+    let builder = context.chain_builder();
+    let genesis = builder.genesis::<Minimal>()?;
+    let trunk = builder.extend(&genesis, [1, 2])?;
+    let fork_base = trunk.last().expect("trunk should contain blocks");
+
+    let heavy_fork = builder
+        .clone()
+        .with_graffiti(H256::repeat_byte(1))
+        .extend(fork_base, 3..=6)?;
+
+    let light_fork = builder
+        .with_graffiti(H256::repeat_byte(2))
+        .with_participation(25)
+        .extend(fork_base, [4, 7])?;
+
+    let heavy_tip = heavy_fork.last().expect("heavy fork should contain blocks");
+    let light_tip = light_fork.last().expect("light fork should contain blocks");
+
+    assert_eq!(
+        context.genesis().0.message().hash_tree_root(),
+        genesis.root()
+    );
+
+    // Start after both forks to prevent proposer boost from deciding the head.
+    context.on_slot(8);
+
+    for built in trunk.iter().chain(&light_fork) {
+        context.on_acceptable_built_block(built);
+    }
+
+    context.assert_head(light_tip.slot(), light_tip.root());
+
+    for built in &heavy_fork {
+        context.on_acceptable_built_block(built);
+    }
+
+    context.assert_head(heavy_tip.slot(), heavy_tip.root());
+
+    Ok(())
+}
+
+#[test]
+fn blocks_with_blobs_are_accepted_after_their_sidecars() -> Result<()> {
+    let mut context = Context::deneb_minimal();
+    let builder = context.chain_builder().with_blobs_per_block(2);
+    let genesis = builder.genesis::<Minimal>()?;
+    let chain = builder.extend(&genesis, [1, 2, 5])?;
+    let tip = chain.last().expect("chain should contain blocks");
+
+    context.on_slot(tip.slot());
+
+    for built in &chain {
+        assert_eq!(built.blob_sidecars.len(), 2);
+        context.on_acceptable_built_block(built);
+    }
+
+    context.assert_head(tip.slot(), tip.root());
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use core::ops::Range;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use anyhow::Result;
 use clock::Tick;
 use crossbeam_utils::sync::WaitGroup;
 use eth2_libp2p::GossipId;
 use execution_engine::{MockExecutionEngine, PayloadStatusV1};
+use factory::{BuiltBlock, ChainBuilder};
 use fork_choice_store::PayloadStatus;
 use futures::channel::mpsc::UnboundedReceiver;
 use helper_functions::misc;
@@ -147,6 +151,11 @@ impl<P: Preset> Context<P> {
             .expect("block should be constructed successfully")
     }
 
+    #[must_use]
+    pub fn chain_builder(&self) -> ChainBuilder {
+        ChainBuilder::new(self.controller().chain_config().clone_arc())
+    }
+
     #[must_use]
     pub fn block_with_payload(
         &self,
@@ -253,7 +262,7 @@ impl<P: Preset> Context<P> {
     }
 
     pub fn on_blob_sidecar(&mut self, blob_sidecar: BlobSidecar<P>) -> Option<P2pMessage<P>> {
-        let subnet_id = misc::compute_subnet_for_blob_sidecar(blob_sidecar.index);
+        let subnet_id = misc::compute_subnet_for_blob_sidecar(self.config(), &blob_sidecar);
 
         self.controller().on_gossip_blob_sidecar(
             Arc::new(blob_sidecar),
@@ -270,6 +279,14 @@ impl<P: Preset> Context<P> {
         assert!(matches!(self.on_block(block), Some(P2pMessage::Accept(_))));
     }
 
+    pub fn on_acceptable_built_block(&mut self, built: &BuiltBlock<P>) {
+        for blob_sidecar in &built.blob_sidecars {
+            self.on_blob_sidecar(blob_sidecar.as_ref().clone());
+        }
+
+        self.on_acceptable_block(&built.block);
+    }
+
     pub fn on_ignorable_block(&mut self, block: &Arc<SignedBeaconBlock<P>>) {
         assert!(matches!(self.on_block(block), Some(P2pMessage::Ignore(_))));
     }
@@ -395,6 +412,18 @@ impl<P: Preset> Context<P> {
         self.controller().blocks_by_range(range)
     }
 
+    pub fn assert_latest_message_epochs(
+        &self,
+        expected_epochs: impl IntoIterator<Item = (ValidatorIndex, Option<Epoch>)>,
+    ) {
+        let expected_epochs = expected_epochs.into_iter().collect::<HashMap<_, _>>();
+        let actual_epochs = self
+            .controller()
+            .latest_message_epochs(expected_epochs.keys().copied());
+
+        assert_eq!(actual_epochs, expected_epochs);
+    }
+
     pub fn assert_genesis_time(&self, expected_time: UnixSeconds) {
         assert_eq!(self.controller().genesis_time(), expected_time);
     }
@@ -487,6 +516,22 @@ impl<P: Preset> Context<P> {
         );
     }
 
+    pub fn assert_invalid_block_count(&self, expected_count: usize) {
+        assert_eq!(
+            self.controller().store_snapshot().invalid_block_count(),
+            expected_count,
+        );
+    }
+
+    #[must_use]
+    pub fn is_invalid(&self, block: &SignedBeaconBlock<P>) -> bool {
+        let payload_status = self
+            .controller()
+            .payload_status(block.message().hash_tree_root());
+
+        payload_status == Some(PayloadStatus::Invalid)
+    }
+
     pub fn assert_optimistic(&self, block: &SignedBeaconBlock<P>, expected_optimistic: bool) {
         assert_eq!(
             self.controller()
@@ -587,6 +632,11 @@ impl Context<Minimal> {
         Self::with_config(Config::minimal().start_and_stay_in(Phase::Bellatrix))
             .expect("minimal configuration modified to start in Bellatrix is valid")
     }
+
+    pub fn deneb_minimal() -> Self {
+        Self::with_config(Config::minimal().start_and_stay_in(Phase::Deneb))
+            .expect("minimal configuration modified to start in Deneb is valid")
+    }
 }
 
 #[derive(Clone, Copy)]
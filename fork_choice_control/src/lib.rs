@@ -2,7 +2,13 @@
 //!
 //! This crate handles the following concerns:
 //! - [Persistence](`storage`).
-//! - [Exporting data from the database](`storage_tool`).
+//! - [Writing to storage without blocking fork choice](`storage_writer`).
+//! - [Exporting, pruning, verifying and inspecting data in the database](`storage_tool`).
+//! - [Exporting chain data for analysis](`chain_data`).
+//! - [Deleting blob sidecars outside the retention period](`blob_retention`).
+//! - [Journaling changes to the validator registry](`registry_journal`).
+//! - [Caching decompressed validator public keys across restarts](`storage_pubkey_cache`).
+//! - [Reading historical blocks from era files](`era_store`).
 //! - [Parallel processing and task priorities](`thread_pool`).
 //! - [Waiting for task completion](`Controller::wait_for_tasks`).
 //! - Delaying and retrying objects that cannot be processed immediately.
@@ -16,32 +22,60 @@
 //! [`storage`]: ::storage
 
 pub use crate::{
-    controller::Controller,
+    chain_data::{ChainDataField, ChainDataFormat},
+    controller::{Controller, MutatorHandle},
+    era_store::EraStore,
     messages::{
-        ApiMessage, BlockEvent, ChainReorgEvent, FinalizedCheckpointEvent, HeadEvent, P2pMessage,
-        SubnetMessage, SyncMessage, ValidatorMessage,
+        ApiMessage, BlockEvent, ChainReorgEvent, FinalizedCheckpointEvent, HeadEvent,
+        InvalidatedBlocksEvent, P2pMessage, SubnetMessage, SyncMessage, ValidatorMessage,
     },
     misc::{MutatorRejectionReason, VerifyAggregateAndProofResult, VerifyAttestationResult},
+    proposer_duties::{ProposerDuties, ProposerDuty},
+    pruning_progress::PruningProgress,
     queries::{BlockWithRoot, ForkChoiceContext, ForkTip, Snapshot},
+    registry_journal::{RegistryChange, RegistryChangeKind},
+    reorgs::{ReorgCause, ReorgRecord},
     specialized::{AdHocBenchController, BenchController},
     state_cache::Error as StateCacheError,
-    storage::{StateLoadStrategy, Storage, DEFAULT_ARCHIVAL_EPOCH_INTERVAL},
-    storage_tool::{export_state_and_blocks, replay_blocks},
+    storage::{
+        ArchivePruningReport, Error as StorageError, StateLoadStrategy, Storage,
+        DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+    },
+    storage_back_sync::BackSyncStatus,
+    storage_inspection::{EntryStatistics, StorageInspectionReport},
+    storage_tool::{
+        export_anchor, export_chain_data, export_era, export_state_and_blocks, import_anchor,
+        inspect_storage, prune_archive, replay_blocks, test_fork_upgrade, verify_storage,
+    },
+    storage_verification::{StorageIssue, StorageVerificationReport},
     wait::Wait,
 };
 
 pub mod checkpoint_sync;
 
+mod blob_retention;
+mod chain_data;
 mod controller;
+mod era_store;
 mod messages;
 mod misc;
 mod mutator;
+mod proposer_duties;
+mod pruning_progress;
 mod queries;
+mod registry_journal;
+mod reorgs;
 mod specialized;
 mod state_cache;
+mod state_diff;
 mod storage;
 mod storage_back_sync;
+mod storage_inspection;
+mod storage_pubkey_cache;
+mod storage_read_cache;
 mod storage_tool;
+mod storage_verification;
+mod storage_writer;
 mod tasks;
 mod thread_pool;
 mod unbounded_sink;
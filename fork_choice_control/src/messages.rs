@@ -13,7 +13,9 @@ use fork_choice_store::{
 };
 use helper_functions::{accessors, misc};
 use log::debug;
+use request_tracing::TraceContext;
 use serde::Serialize;
+use ssz::Ssz;
 use tap::Pipe as _;
 use types::{
     combined::{BeaconState, SignedBeaconBlock},
@@ -53,6 +55,7 @@ pub enum MutatorMessage<P: Preset, W> {
         origin: BlockOrigin,
         submission_time: Instant,
         rejected_block_root: Option<H256>,
+        trace: Option<TraceContext>,
     },
     AggregateAndProof {
         wait_group: W,
@@ -186,6 +189,7 @@ pub enum ApiMessage<P: Preset> {
     ChainReorgEvent(ChainReorgEvent),
     FinalizedCheckpoint(FinalizedCheckpointEvent),
     Head(HeadEvent),
+    InvalidatedBlocks(InvalidatedBlocksEvent),
 }
 
 impl<P: Preset> ApiMessage<P> {
@@ -224,15 +228,21 @@ impl<P: Preset> SyncMessage<P> {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
 pub struct BlockEvent {
     #[serde(with = "serde_utils::string_or_native")]
     pub slot: Slot,
     pub block: H256,
     pub execution_optimistic: bool,
+    // Not part of the event. Used to filter events in `GET /eth/v1/events`.
+    #[serde(skip)]
+    #[ssz(skip)]
+    pub proposer_index: ValidatorIndex,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
 pub struct ChainReorgEvent {
     #[serde(with = "serde_utils::string_or_native")]
     pub slot: Slot,
@@ -287,7 +297,8 @@ impl ChainReorgEvent {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
 pub struct FinalizedCheckpointEvent {
     pub block: H256,
     pub state: H256,
@@ -296,7 +307,8 @@ pub struct FinalizedCheckpointEvent {
     pub execution_optimistic: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
 pub struct HeadEvent {
     #[serde(with = "serde_utils::string_or_native")]
     pub slot: Slot,
@@ -331,3 +343,15 @@ impl HeadEvent {
         })
     }
 }
+
+// Not part of the Eth Beacon Node API.
+// Sent when the execution engine reports payloads as invalid and blocks become invalid as a result.
+// `latest_valid_hash` is zero if the execution engine did not return one.
+#[derive(Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct InvalidatedBlocksEvent {
+    pub execution_block_hash: ExecutionBlockHash,
+    pub latest_valid_hash: ExecutionBlockHash,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub count: u64,
+}
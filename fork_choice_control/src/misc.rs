@@ -119,6 +119,20 @@ pub struct VerifyAttestationResult<P: Preset> {
     pub origin: AttestationOrigin<GossipId>,
 }
 
+/// Where blobs missing from a block delayed until blobs were last requested from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum BlobRecoverySource {
+    Peers,
+    ExecutionLayer,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BlobRecovery {
+    pub source: BlobRecoverySource,
+    pub started_at: Instant,
+}
+
 #[allow(clippy::enum_variant_names)]
 #[derive(IntoStaticStr, Serialize)]
 #[strum(serialize_all = "snake_case")]
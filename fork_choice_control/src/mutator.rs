@@ -20,8 +20,8 @@ use std::{
         mpsc::{Receiver, Sender},
         Arc,
     },
-    thread::Builder,
-    time::Instant,
+    thread::{Builder, JoinHandle},
+    time::{Duration, Instant},
 };
 
 use anyhow::Result;
@@ -39,7 +39,7 @@ use futures::channel::{mpsc::Sender as MultiSender, oneshot::Sender as OneshotSe
 use helper_functions::{accessors, misc, predicates, verifier::NullVerifier};
 use itertools::{Either, Itertools as _};
 use log::{debug, error, info, warn};
-use prometheus_metrics::Metrics;
+use prometheus_metrics::{Metrics, OperationalCounter};
 use ssz::SszHash as _;
 use std_ext::ArcExt as _;
 use types::{
@@ -48,21 +48,27 @@ use types::{
     nonstandard::{RelativeEpoch, ValidationOutcome},
     phase0::{
         containers::Checkpoint,
-        primitives::{ExecutionBlockHash, Slot, ValidatorIndex, H256},
+        primitives::{Epoch, ExecutionBlockHash, Slot, ValidatorIndex, H256},
     },
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
 use crate::{
+    blob_retention::BlobRetention,
     messages::{MutatorMessage, P2pMessage, SubnetMessage, SyncMessage, ValidatorMessage},
     misc::{
-        Delayed, MutatorRejectionReason, PendingAggregateAndProof, PendingAttestation,
-        PendingBlobSidecar, PendingBlock, PendingChainLink, VerifyAggregateAndProofResult,
-        VerifyAttestationResult, WaitingForCheckpointState,
+        BlobRecovery, BlobRecoverySource, Delayed, MutatorRejectionReason,
+        PendingAggregateAndProof, PendingAttestation, PendingBlobSidecar, PendingBlock,
+        PendingChainLink, VerifyAggregateAndProofResult, VerifyAttestationResult,
+        WaitingForCheckpointState,
     },
+    proposer_duties::ProposerDutiesCache,
+    pruning_progress::PruningProgress,
+    reorgs::{ReorgCause, ReorgRecord},
     state_cache::StateCache,
     storage::Storage,
+    storage_writer::StorageWriter,
     tasks::{
         AggregateAndProofTask, AttestationTask, BlobSidecarTask, BlockAttestationsTask, BlockTask,
         CheckpointStateTask, PersistBlobSidecarsTask, PreprocessStateTask,
@@ -71,15 +77,23 @@ use crate::{
     unbounded_sink::UnboundedSink,
     wait::Wait,
     ApiMessage, BlockEvent, ChainReorgEvent, FinalizedCheckpointEvent, HeadEvent,
+    InvalidatedBlocksEvent,
 };
 
+// Blob sidecars are normally published right after the block. If they have not arrived by the time
+// this elapses, gossip is unlikely to deliver them and they have to be requested from elsewhere.
+// The same timeout is used between the request to peers and the request to the execution layer.
+const BLOB_RECOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
 #[allow(clippy::struct_field_names)]
 pub struct Mutator<P: Preset, E, W, AS, PS, NS, SS, VS> {
     store: Arc<Store<P>>,
     store_snapshot: Arc<ArcSwap<Store<P>>>,
     state_cache: Arc<StateCache<P, W>>,
+    proposer_duties_cache: Arc<ProposerDutiesCache>,
     execution_engine: E,
     delayed_until_blobs: HashMap<H256, PendingBlock<P>>,
+    blob_recovery: HashMap<H256, BlobRecovery>,
     delayed_until_block: HashMap<H256, Delayed<P>>,
     // We previously ignored objects that would have to be delayed more than one slot. This was
     // based on the assumption that one slot is enough to account for clock differences between
@@ -103,6 +117,10 @@ pub struct Mutator<P: Preset, E, W, AS, PS, NS, SS, VS> {
     // succession would still perform slot processing independently.
     waiting_for_checkpoint_states: HashMap<Checkpoint, WaitingForCheckpointState<P>>,
     storage: Arc<Storage<P>>,
+    storage_writer: StorageWriter<P, W>,
+    blob_retention: BlobRetention,
+    // The thread running the latest automatic pruning jobs, if any were started.
+    pruner: Option<JoinHandle<()>>,
     thread_pool: ThreadPool<P, E, W>,
     metrics: Option<Arc<Metrics>>,
     mutator_tx: Sender<MutatorMessage<P, W>>,
@@ -129,8 +147,10 @@ where
     pub fn new(
         store_snapshot: Arc<ArcSwap<Store<P>>>,
         state_cache: Arc<StateCache<P, W>>,
+        proposer_duties_cache: Arc<ProposerDutiesCache>,
         execution_engine: E,
         storage: Arc<Storage<P>>,
+        storage_writer: StorageWriter<P, W>,
         thread_pool: ThreadPool<P, E, W>,
         metrics: Option<Arc<Metrics>>,
         mutator_tx: Sender<MutatorMessage<P, W>>,
@@ -141,17 +161,26 @@ where
         sync_tx: SS,
         validator_tx: VS,
     ) -> Self {
+        let store = store_snapshot.load_full();
+        let blob_retention =
+            BlobRetention::new(store.chain_config(), storage.blob_retention_epochs());
+
         Self {
-            store: store_snapshot.load_full(),
+            store,
             store_snapshot,
             state_cache,
+            proposer_duties_cache,
             execution_engine,
             delayed_until_blobs: HashMap::new(),
+            blob_recovery: HashMap::new(),
             delayed_until_block: HashMap::new(),
             delayed_until_slot: BTreeMap::new(),
             delayed_until_payload: HashMap::new(),
             waiting_for_checkpoint_states: HashMap::new(),
+            blob_retention,
+            pruner: None,
             storage,
+            storage_writer,
             thread_pool,
             metrics,
             mutator_tx,
@@ -178,13 +207,18 @@ where
                     origin,
                     submission_time,
                     rejected_block_root,
-                } => self.handle_block(
-                    wait_group,
-                    result,
-                    origin,
-                    submission_time,
-                    rejected_block_root,
-                )?,
+                    trace,
+                } => request_tracing::resume_sync(trace, || {
+                    request_tracing::in_span_sync("handle_block", || {
+                        self.handle_block(
+                            wait_group,
+                            result,
+                            origin,
+                            submission_time,
+                            rejected_block_root,
+                        )
+                    })
+                })?,
                 MutatorMessage::AggregateAndProof { wait_group, result } => {
                     self.handle_aggregate_and_proof(&wait_group, result)?
                 }
@@ -298,6 +332,15 @@ where
             )?;
         }
 
+        // Save the replayed chain so that the state checkpoint advances past blocks finalized
+        // during the replay. Otherwise they would be replayed again after every restart.
+        self.storage_writer.append(
+            self.store.unfinalized_canonical_chain().cloned(),
+            self.store.finalized().iter().cloned(),
+            self.owned_store(),
+            wait_group,
+        );
+
         Ok(())
     }
 
@@ -366,10 +409,12 @@ where
         };
 
         if changes.is_finalized_checkpoint_updated() {
-            self.archive_finalized(wait_group)?;
+            self.archive_finalized(wait_group);
             self.prune_delayed_until_payload();
         }
 
+        self.recover_missing_blobs();
+
         self.update_store_snapshot();
 
         ValidatorMessage::Tick(wait_group.clone(), tick).send(&self.validator_tx);
@@ -394,22 +439,19 @@ where
         }
 
         if let ApplyTickChanges::Reorganized { old_head, .. } = changes {
-            self.notify_about_reorganization(wait_group.clone(), &old_head);
+            self.notify_about_reorganization(
+                wait_group.clone(),
+                &old_head,
+                ReorgCause::AttestationWeight,
+            );
             self.spawn_preprocess_head_state_for_next_slot_task();
         } else if self.store.tick().kind == TickKind::Attest {
             self.spawn_preprocess_head_state_for_next_slot_task();
         }
 
-        if tick.kind == TickKind::AttestFourth
-            && self.store.is_forward_synced()
-            && misc::slots_since_epoch_start::<P>(tick.slot) == 0
-        {
-            self.prune_old_blob_sidecars()?;
-        }
-
         if self.store.is_forward_synced() && misc::slots_since_epoch_start::<P>(tick.slot) == 0 {
             if tick.kind == TickKind::AttestFourth {
-                self.prune_old_blob_sidecars()?;
+                self.prune_storage()?;
             }
 
             if let Some(metrics) = self.metrics.as_ref() {
@@ -635,7 +677,11 @@ where
                 self.update_store_snapshot();
 
                 if let Some(old_head) = old_head {
-                    self.notify_about_reorganization(wait_group.clone(), &old_head);
+                    self.notify_about_reorganization(
+                        wait_group.clone(),
+                        &old_head,
+                        ReorgCause::AttestationWeight,
+                    );
                     self.spawn_preprocess_head_state_for_next_slot_task();
                 }
             }
@@ -792,7 +838,11 @@ where
                 self.update_store_snapshot();
 
                 if let Some(old_head) = old_head {
-                    self.notify_about_reorganization(wait_group.clone(), &old_head);
+                    self.notify_about_reorganization(
+                        wait_group.clone(),
+                        &old_head,
+                        ReorgCause::AttestationWeight,
+                    );
                     self.spawn_preprocess_head_state_for_next_slot_task();
                 }
             }
@@ -973,7 +1023,11 @@ where
         self.update_store_snapshot();
 
         if let Some(old_head) = old_head {
-            self.notify_about_reorganization(wait_group.clone(), &old_head);
+            self.notify_about_reorganization(
+                wait_group.clone(),
+                &old_head,
+                ReorgCause::AttestationWeight,
+            );
             self.spawn_preprocess_head_state_for_next_slot_task();
         }
 
@@ -995,7 +1049,11 @@ where
                 self.update_store_snapshot();
 
                 if let Some(old_head) = old_head {
-                    self.notify_about_reorganization(wait_group.clone(), &old_head);
+                    self.notify_about_reorganization(
+                        wait_group.clone(),
+                        &old_head,
+                        ReorgCause::AttesterSlashing,
+                    );
                     self.spawn_preprocess_head_state_for_next_slot_task();
                 }
             }
@@ -1173,7 +1231,12 @@ where
             .update_chain_payload_statuses(latest_valid_hash, None);
         self.update_store_snapshot();
 
-        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+        self.handle_potential_head_change(
+            wait_group,
+            &old_head,
+            head_was_optimistic,
+            ReorgCause::PayloadStatus,
+        );
     }
 
     fn handle_notified_new_payload(
@@ -1185,6 +1248,7 @@ where
         let old_head = self.store.head().clone();
         let head_was_optimistic = old_head.is_optimistic();
         let latest_valid_hash = payload_status.latest_valid_hash;
+        let old_invalid_block_count = self.store.invalid_block_count();
 
         let mut payload_action = PayloadAction::Accept;
 
@@ -1239,6 +1303,30 @@ where
 
         self.update_store_snapshot();
 
+        let invalidated_block_count = self
+            .store
+            .invalid_block_count()
+            .saturating_sub(old_invalid_block_count);
+
+        if invalidated_block_count > 0 {
+            warn!(
+                "execution engine invalidated {invalidated_block_count} blocks \
+                 (execution_block_hash: {execution_block_hash:?}, \
+                 latest_valid_hash: {latest_valid_hash:?})",
+            );
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_mutator_invalidated_blocks(invalidated_block_count);
+            }
+
+            ApiMessage::InvalidatedBlocks(InvalidatedBlocksEvent {
+                execution_block_hash,
+                latest_valid_hash: latest_valid_hash.unwrap_or_default(),
+                count: invalidated_block_count as u64,
+            })
+            .send(&self.api_tx);
+        }
+
         // Do not send API events about optimistic blocks.
         // Vouch treats all head events as non-optimistic.
         if let Some(chain_link) = self
@@ -1250,12 +1338,18 @@ where
                     slot: chain_link.slot(),
                     block: chain_link.block_root,
                     execution_optimistic: false,
+                    proposer_index: chain_link.block.message().proposer_index(),
                 })
                 .send(&self.api_tx);
             }
         }
 
-        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+        self.handle_potential_head_change(
+            wait_group,
+            &old_head,
+            head_was_optimistic,
+            ReorgCause::PayloadStatus,
+        );
     }
 
     fn handle_potential_head_change(
@@ -1263,6 +1357,7 @@ where
         wait_group: &W,
         old_head: &ChainLink<P>,
         head_was_optimistic: bool,
+        cause: ReorgCause,
     ) {
         let head = self.store.head();
         let head_changed = head.block_root != old_head.block_root;
@@ -1283,12 +1378,15 @@ where
         }
 
         if head_changed {
-            self.notify_about_reorganization(wait_group.clone(), old_head);
+            self.notify_about_reorganization(wait_group.clone(), old_head, cause);
             self.spawn_preprocess_head_state_for_next_slot_task();
         }
     }
 
     fn handle_stop(&self, save_to_storage: bool) -> Result<()> {
+        // Finish queued writes first. They would overwrite the state checkpoint saved below.
+        self.storage_writer.flush();
+
         if save_to_storage {
             let slots = self.storage.append(
                 self.store.unfinalized_canonical_chain(),
@@ -1377,9 +1475,39 @@ where
 
         let block = block.clone_arc();
         let is_valid = chain_link.is_valid();
+        let old_justified_checkpoints = (
+            self.store.justified_checkpoint(),
+            self.store.unrealized_justified_checkpoint(),
+        );
         let changes = self.store_mut().apply_block(chain_link)?;
+
+        // Attester slashings in the block are applied below and may change the head again,
+        // so the cause has to be determined before that.
+        let reorg_cause = ReorgCause::for_block(
+            &self.store,
+            block_root,
+            old_justified_checkpoints
+                != (
+                    self.store.justified_checkpoint(),
+                    self.store.unrealized_justified_checkpoint(),
+                ),
+        );
         let insertion_time = Instant::now();
 
+        self.delayed_until_blobs.remove(&block_root);
+
+        if let Some(BlobRecovery { source, .. }) = self.blob_recovery.remove(&block_root) {
+            info!("recovered missing blobs of block {block_root:?} (source: {source:?})");
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_mutator_blob_recovery_success(source.into());
+            }
+        }
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_operational_counter(OperationalCounter::ProcessedBlocks, 1);
+        }
+
         let unfinalized_states_in_memory = self.store.store_config().unfinalized_states_in_memory;
         let head_slot = self.store.head().slot();
 
@@ -1418,6 +1546,7 @@ where
                 slot: block_slot,
                 block: block_root,
                 execution_optimistic: false,
+                proposer_index: block.message().proposer_index(),
             })
             .send(&self.api_tx);
         }
@@ -1461,7 +1590,7 @@ where
         self.maybe_spawn_block_attestations_task(wait_group, &block);
 
         if changes.is_finalized_checkpoint_updated() {
-            self.archive_finalized(wait_group)?;
+            self.archive_finalized(wait_group);
             self.prune_delayed_until_payload();
         }
 
@@ -1541,7 +1670,7 @@ where
                 self.spawn_preprocess_head_state_for_next_slot_task();
             }
             ApplyBlockChanges::Reorganized { old_head, .. } => {
-                self.notify_about_reorganization(wait_group.clone(), &old_head);
+                self.notify_about_reorganization(wait_group.clone(), &old_head, reorg_cause);
                 self.spawn_preprocess_head_state_for_next_slot_task();
             }
             ApplyBlockChanges::AlternateChainExtended { .. } => {}
@@ -1584,7 +1713,12 @@ where
             metrics: self.metrics.clone(),
         });
 
-        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+        self.handle_potential_head_change(
+            wait_group,
+            &old_head,
+            head_was_optimistic,
+            ReorgCause::BlobSidecar,
+        );
     }
 
     fn notify_about_finalized_checkpoint(&self) {
@@ -1625,14 +1759,23 @@ where
         .send(&self.api_tx);
     }
 
-    fn notify_about_reorganization(&self, wait_group: W, old_head: &ChainLink<P>) {
+    fn notify_about_reorganization(
+        &self,
+        wait_group: W,
+        old_head: &ChainLink<P>,
+        cause: ReorgCause,
+    ) {
         let new_head = self.store.head().clone();
         let event = ChainReorgEvent::new(&self.store, old_head);
+        let reorg = ReorgRecord::new(&self.store, old_head, &event, cause);
 
         ApiMessage::ChainReorgEvent(event).send(&self.api_tx);
 
+        self.storage_writer.append_reorg(reorg, wait_group.clone());
+
         if let Some(metrics) = self.metrics.as_ref() {
             metrics.beacon_reorgs_total.inc();
+            metrics.register_operational_counter(OperationalCounter::Reorgs, 1);
         }
 
         info!(
@@ -2067,6 +2210,90 @@ where
         gossip_ids
     }
 
+    fn recover_missing_blobs(&mut self) {
+        self.prune_delayed_until_blobs();
+
+        if !self.store.is_forward_synced() {
+            return;
+        }
+
+        let mut started = vec![];
+
+        for (block_root, pending_block) in &self.delayed_until_blobs {
+            let source = match self.blob_recovery.get(block_root) {
+                None if pending_block.submission_time.elapsed() >= BLOB_RECOVERY_TIMEOUT => {
+                    BlobRecoverySource::Peers
+                }
+                Some(recovery)
+                    if recovery.source == BlobRecoverySource::Peers
+                        && recovery.started_at.elapsed() >= BLOB_RECOVERY_TIMEOUT
+                        && !E::IS_NULL =>
+                {
+                    BlobRecoverySource::ExecutionLayer
+                }
+                _ => continue,
+            };
+
+            let missing_blob_indices = self.store.indices_of_missing_blobs(&pending_block.block);
+
+            if missing_blob_indices.is_empty() {
+                continue;
+            }
+
+            debug!(
+                "requesting missing blobs after availability timeout \
+                 (block_root: {block_root:?}, indices: {missing_blob_indices:?}, \
+                  source: {source:?})",
+            );
+
+            match source {
+                BlobRecoverySource::Peers => {
+                    let blob_ids = missing_blob_indices
+                        .into_iter()
+                        .map(|index| BlobIdentifier {
+                            block_root: *block_root,
+                            index,
+                        })
+                        .collect_vec();
+
+                    let slot = pending_block.block.message().slot();
+
+                    // Let the sync service choose a peer instead of asking the one that sent
+                    // the block again.
+                    P2pMessage::BlobsNeeded(blob_ids, slot, None).send(&self.p2p_tx);
+                }
+                BlobRecoverySource::ExecutionLayer => {
+                    self.execution_engine
+                        .get_blobs(pending_block.block.clone_arc(), missing_blob_indices);
+                }
+            }
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_mutator_blob_recovery_attempt(source.into());
+            }
+
+            started.push((
+                *block_root,
+                BlobRecovery {
+                    source,
+                    started_at: Instant::now(),
+                },
+            ));
+        }
+
+        self.blob_recovery.extend(started);
+    }
+
+    fn prune_delayed_until_blobs(&mut self) {
+        let finalized_slot = self.store.finalized_slot();
+
+        self.delayed_until_blobs
+            .retain(|_, pending_block| pending_block.block.message().slot() > finalized_slot);
+
+        self.blob_recovery
+            .retain(|block_root, _| self.delayed_until_blobs.contains_key(block_root));
+    }
+
     fn prune_delayed_until_payload(&mut self) {
         let finalized_slot = self.store.finalized_slot();
 
@@ -2182,76 +2409,79 @@ where
 
         self.spawn(PreprocessStateTask {
             state_cache: self.state_cache.clone_arc(),
+            proposer_duties_cache: self.proposer_duties_cache.clone_arc(),
             head_block_root: self.store.head().block_root,
             next_slot: self.store.slot() + 1,
             metrics: self.metrics.clone(),
         })
     }
 
-    fn archive_finalized(&mut self, wait_group: &W) -> Result<()> {
+    fn archive_finalized(&mut self, wait_group: &W) {
         if let Some(latest_archivable_index) = self.store.latest_archivable_index() {
             debug!("archiving finalized blocks and anchor state…");
 
             let store = self.owned_store();
-            let storage = self.storage.clone_arc();
-            let sync_tx = self.sync_tx.clone();
-            let wait_group = wait_group.clone();
 
             let mut archived = self.store_mut().archive_finalized(latest_archivable_index);
             archived.push_back(self.store.anchor().clone());
 
-            Builder::new()
-                .name("store-archiver".to_owned())
-                .spawn(move || {
-                    debug!("saving finalized blocks and anchor state…");
-
-                    match storage.append(core::iter::empty(), archived.iter(), &store) {
-                        Ok(slots) => {
-                            if let Some(chain_link) = archived.back() {
-                                let finalized_block = chain_link.block.clone_arc();
-                                SyncMessage::Finalized(finalized_block).send(&sync_tx);
-                            }
-
-                            debug!(
-                                "finalized blocks and anchor state saved \
-                                 (appended block slots: {slots:?})",
-                            )
-                        }
-                        Err(error) => error!("saving to storage failed: {error:?}"),
-                    }
+            self.storage_writer
+                .append_finalized(archived, store, wait_group.clone());
+        }
+    }
 
-                    drop(wait_group);
-                })?;
+    // Pruning jobs run one after another under a single guard so that none of them is skipped
+    // because another one is running. Pruning can take longer than an epoch. Instead of piling up
+    // threads waiting for the guard, a new run is only started once the previous one finishes.
+    // The limits the jobs prune up to never decrease, so the next run catches up.
+    fn prune_storage(&mut self) -> Result<()> {
+        if self
+            .pruner
+            .as_ref()
+            .is_some_and(|pruner| !pruner.is_finished())
+        {
+            debug!("postponing storage pruning because the previous run is still in progress");
+            return Ok(());
         }
 
-        Ok(())
-    }
+        let blob_up_to_slot = self
+            .blob_retention
+            .on_epoch::<P>(self.store.current_epoch(), self.store.finalized_epoch());
+
+        let history_retain_epochs = self.storage.prune_history_epochs();
+        let payload_retain_epochs = self.storage.execution_payload_retention_epochs();
+
+        if blob_up_to_slot.is_none()
+            && history_retain_epochs.is_none()
+            && payload_retain_epochs.is_none()
+        {
+            return Ok(());
+        }
 
-    fn prune_old_blob_sidecars(&self) -> Result<()> {
         let storage = self.storage.clone_arc();
-        let current_epoch = misc::compute_epoch_at_slot::<P>(self.store.slot());
-        let up_to_epoch = current_epoch.saturating_sub(
-            self.store
-                .chain_config()
-                .min_epochs_for_blob_sidecars_requests,
-        );
-        let up_to_slot = misc::compute_start_slot_at_epoch::<P>(up_to_epoch);
+        let finalized_epoch = self.store.finalized_epoch();
 
-        Builder::new()
-            .name("old-blob-pruner".to_owned())
+        let pruner = Builder::new()
+            .name("storage-pruner".to_owned())
             .spawn(move || {
-                debug!("pruning old blob sidecards from storage up to slot {up_to_slot}…");
+                // Pruning requested through the HTTP API may be running. Wait for it to finish.
+                let _pruning = storage.lock_pruning_blocking();
 
-                match storage.prune_old_blob_sidecars(up_to_slot) {
-                    Ok(()) => {
-                        debug!("pruned old blob sidecards from storage up to slot {up_to_slot}");
-                    }
-                    Err(error) => {
-                        error!("pruning old blob sidecards from storage failed: {error:?}")
-                    }
+                if let Some(up_to_slot) = blob_up_to_slot {
+                    prune_old_blob_sidecars(&storage, up_to_slot);
+                }
+
+                if let Some(retain_epochs) = history_retain_epochs {
+                    prune_history(&storage, finalized_epoch, retain_epochs);
+                }
+
+                if let Some(retain_epochs) = payload_retain_epochs {
+                    prune_execution_payloads(&storage, finalized_epoch, retain_epochs);
                 }
             })?;
 
+        self.pruner = Some(pruner);
+
         Ok(())
     }
 
@@ -2300,6 +2530,13 @@ where
 
             let (high_priority_tasks, low_priority_tasks) = self.thread_pool.task_counts();
 
+            metrics.set_collection_length(
+                &[&type_name, "delayed_until_blobs"],
+                self.delayed_until_blobs.len(),
+            );
+
+            metrics.set_collection_length(&[&type_name, "blob_recovery"], self.blob_recovery.len());
+
             metrics.set_collection_length(
                 &[&type_name, "delayed_until_block"],
                 self.delayed_until_block.len(),
@@ -2389,3 +2626,57 @@ fn reply_block_validation_result_to_http_api(
         }
     }
 }
+
+fn prune_old_blob_sidecars<P: Preset>(storage: &Storage<P>, up_to_slot: Slot) {
+    debug!("pruning old blob sidecards from storage up to slot {up_to_slot}…");
+
+    match storage.prune_old_blob_sidecars(up_to_slot, &PruningProgress::default()) {
+        Ok(()) => debug!("pruned old blob sidecards from storage up to slot {up_to_slot}"),
+        Err(error) => error!("pruning old blob sidecards from storage failed: {error:?}"),
+    }
+}
+
+fn prune_history<P: Preset>(storage: &Storage<P>, finalized_epoch: Epoch, retain_epochs: u64) {
+    debug!(
+        "pruning history older than {retain_epochs} epochs \
+         before finalized epoch {finalized_epoch}…",
+    );
+
+    match storage.prune_history(finalized_epoch, retain_epochs, &PruningProgress::default()) {
+        Ok(report) => {
+            if !report.pruned_state_slots.is_empty() || report.pruned_block_count > 0 {
+                info!(
+                    "pruned {} states and {} blocks older than {retain_epochs} epochs \
+                     before finalized epoch {finalized_epoch}",
+                    report.pruned_state_slots.len(),
+                    report.pruned_block_count,
+                );
+            }
+        }
+        Err(error) => error!("pruning history failed: {error:?}"),
+    }
+}
+
+fn prune_execution_payloads<P: Preset>(
+    storage: &Storage<P>,
+    finalized_epoch: Epoch,
+    retain_epochs: u64,
+) {
+    debug!(
+        "pruning execution payloads older than {retain_epochs} epochs \
+         before finalized epoch {finalized_epoch}…",
+    );
+
+    match storage.prune_execution_payloads(
+        finalized_epoch,
+        retain_epochs,
+        &PruningProgress::default(),
+    ) {
+        Ok(0) => {}
+        Ok(pruned_payload_count) => info!(
+            "pruned {pruned_payload_count} execution payloads older than \
+             {retain_epochs} epochs before finalized epoch {finalized_epoch}",
+        ),
+        Err(error) => error!("pruning execution payloads failed: {error:?}"),
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use bls::PublicKeyBytes;
+use helper_functions::{accessors, misc};
+use itertools::Itertools as _;
+use parking_lot::Mutex;
+use std_ext::ArcExt as _;
+use types::{
+    combined::BeaconState,
+    phase0::primitives::{Epoch, Slot, ValidatorIndex, H256},
+    preset::Preset,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProposerDuty {
+    pub slot: Slot,
+    pub validator_index: ValidatorIndex,
+    pub pubkey: PublicKeyBytes,
+}
+
+/// Proposers for every slot of an epoch.
+///
+/// Proposers are determined by the state at the start of the epoch, which in turn is determined by
+/// the last block before the epoch. The root of that block is the dependent root. Duties for the
+/// next epoch computed from the head are valid as long as the head remains the last block before
+/// the epoch, so they may change if a block is added in the current epoch or a reorg occurs.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ProposerDuties {
+    pub dependent_root: H256,
+    pub duties: Vec<ProposerDuty>,
+}
+
+impl ProposerDuties {
+    fn compute<P: Preset>(
+        state: &BeaconState<P>,
+        epoch: Epoch,
+        dependent_root: H256,
+    ) -> Result<Self> {
+        misc::slots_in_epoch::<P>(epoch)
+            .map(|slot| {
+                let validator_index = accessors::get_beacon_proposer_index_at_slot(state, slot)?;
+                let pubkey = accessors::public_key(state, validator_index)?.to_bytes();
+
+                Ok(ProposerDuty {
+                    slot,
+                    validator_index,
+                    pubkey,
+                })
+            })
+            .try_collect()
+            .map(|duties| Self {
+                dependent_root,
+                duties,
+            })
+    }
+}
+
+// Entries are keyed by dependent root, so a reorg does not require invalidating anything.
+// Duties computed for blocks that are no longer canonical are simply never looked up again.
+#[derive(Default)]
+pub struct ProposerDutiesCache {
+    duties: Mutex<HashMap<(Epoch, H256), Arc<ProposerDuties>>>,
+}
+
+impl ProposerDutiesCache {
+    pub fn get(&self, epoch: Epoch, dependent_root: H256) -> Option<Arc<ProposerDuties>> {
+        self.duties.lock().get(&(epoch, dependent_root)).cloned()
+    }
+
+    pub fn get_or_try_insert<P: Preset>(
+        &self,
+        state: &BeaconState<P>,
+        epoch: Epoch,
+        dependent_root: H256,
+    ) -> Result<Arc<ProposerDuties>> {
+        if let Some(duties) = self.get(epoch, dependent_root) {
+            return Ok(duties);
+        }
+
+        let computed = Arc::new(ProposerDuties::compute(state, epoch, dependent_root)?);
+        let mut duties = self.duties.lock();
+        let latest_epoch = duties.keys().map(|(cached_epoch, _)| *cached_epoch).max();
+
+        // Only duties for the current and next epochs are worth keeping.
+        // Duties for older epochs are computed on demand without evicting newer ones.
+        if latest_epoch.is_some_and(|latest_epoch| epoch + 1 < latest_epoch) {
+            return Ok(computed);
+        }
+
+        duties.retain(|(cached_epoch, _), _| *cached_epoch + 1 >= epoch);
+        duties.insert((epoch, dependent_root), computed.clone_arc());
+
+        Ok(computed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{config::Config, preset::Minimal};
+
+    use super::*;
+
+    #[test]
+    fn proposer_duties_cache_is_keyed_by_dependent_root() -> Result<()> {
+        let config = Config::minimal();
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let cache = ProposerDutiesCache::default();
+        let root_a = H256::repeat_byte(1);
+        let root_b = H256::repeat_byte(2);
+
+        assert_eq!(cache.get(1, root_a), None);
+
+        let duties_a = cache.get_or_try_insert(&state, 1, root_a)?;
+        let duties_b = cache.get_or_try_insert(&state, 1, root_b)?;
+
+        assert!(Arc::ptr_eq(
+            &cache.get_or_try_insert(&state, 1, root_a)?,
+            &duties_a
+        ));
+        assert_eq!(cache.get(1, root_b), Some(duties_b));
+        assert_eq!(duties_a.dependent_root, root_a);
+        assert_eq!(
+            duties_a.duties.iter().map(|duty| duty.slot).collect_vec(),
+            misc::slots_in_epoch::<Minimal>(1).collect_vec(),
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Progress of a pruning operation that can be observed while it runs on another thread.
+///
+/// Freed bytes are measured as values are written by [`Storage`] before they are compressed by
+/// the database, so they overestimate the space actually reclaimed on disk.
+///
+/// [`Storage`]: crate::Storage
+#[derive(Default, Debug)]
+pub struct PruningProgress {
+    total: AtomicU64,
+    processed: AtomicU64,
+    freed_bytes: AtomicU64,
+}
+
+impl PruningProgress {
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn processed(&self) -> u64 {
+        self.processed.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn freed_bytes(&self) -> u64 {
+        self.freed_bytes.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_total(&self, total: usize) {
+        self.total.store(total as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_processed(&self, freed_bytes: u64) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.freed_bytes.fetch_add(freed_bytes, Ordering::Relaxed);
+    }
+}
@@ -1,12 +1,12 @@
 use core::{fmt::Debug, ops::Range};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::{bail, ensure, Result};
-use arc_swap::Guard;
 use eth2_libp2p::GossipId;
 use execution_engine::ExecutionEngine;
 use fork_choice_store::{
     AggregateAndProofOrigin, AttestationOrigin, ChainLink, PayloadStatus, Segment, Store,
+    ValidatorRegistry,
 };
 use helper_functions::misc;
 use itertools::Itertools as _;
@@ -14,12 +14,14 @@ use serde::Serialize;
 use std_ext::ArcExt;
 use thiserror::Error;
 use types::{
-    combined::{BeaconState, SignedBeaconBlock},
+    combined::{BeaconState, SignedBeaconBlock, SignedBlindedBeaconBlock},
     deneb::containers::{BlobIdentifier, BlobSidecar},
     nonstandard::{Phase, WithStatus},
     phase0::{
         containers::{Attestation, Checkpoint, SignedAggregateAndProof},
-        primitives::{Epoch, ExecutionBlockHash, Gwei, Slot, SubnetId, UnixSeconds, H256},
+        primitives::{
+            Epoch, ExecutionBlockHash, Gwei, Slot, SubnetId, UnixSeconds, ValidatorIndex, H256,
+        },
     },
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
@@ -28,6 +30,9 @@ use types::{
 use crate::{
     controller::Controller,
     misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
+    proposer_duties::ProposerDuties,
+    registry_journal::{RegistryChange, MAX_REGISTRY_CHANGES_PER_QUERY},
+    reorgs::{ReorgRecord, MAX_REORGS_PER_QUERY},
     state_cache::StateCache,
     storage::Storage,
     wait::Wait,
@@ -80,6 +85,14 @@ where
         store.last_finalized().state(&store).genesis_time()
     }
 
+    /// Returns validator public keys and indices as of the last finalized state.
+    ///
+    /// Use [`ValidatorRegistry::index_of_public_key`] instead of scanning states for public keys.
+    #[must_use]
+    pub fn validator_registry(&self) -> ValidatorRegistry {
+        self.store_snapshot().validator_registry().clone()
+    }
+
     #[must_use]
     pub fn anchor_block(&self) -> Arc<SignedBeaconBlock<P>> {
         self.store_snapshot().anchor().block.clone_arc()
@@ -283,6 +296,29 @@ where
         self.store_snapshot().is_forward_synced()
     }
 
+    /// Returns the target epochs of the latest votes of validators counted by fork choice.
+    ///
+    /// Validators whose votes are ignored because of equivocation are omitted.
+    #[must_use]
+    pub fn latest_message_epochs(
+        &self,
+        validator_indices: impl IntoIterator<Item = ValidatorIndex>,
+    ) -> HashMap<ValidatorIndex, Option<Epoch>> {
+        let store = self.store_snapshot();
+
+        validator_indices
+            .into_iter()
+            .filter(|validator_index| !store.is_equivocating(*validator_index))
+            .map(|validator_index| {
+                let epoch = store
+                    .latest_message(validator_index)
+                    .map(|latest_message| latest_message.epoch);
+
+                (validator_index, epoch)
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn state_by_chain_link(&self, chain_link: &ChainLink<P>) -> Arc<BeaconState<P>> {
         chain_link.state(&self.store_snapshot())
@@ -363,6 +399,16 @@ where
         Ok(None)
     }
 
+    /// Returns the blinded version of a finalized block if it is stored separately from its
+    /// execution payload. The block may be readable with [`Self::block_by_root`] even if this
+    /// returns `None`, and vice versa once the payload has been pruned.
+    pub fn stored_blinded_block_by_root(
+        &self,
+        block_root: H256,
+    ) -> Result<Option<SignedBlindedBeaconBlock<P>>> {
+        self.storage().finalized_blinded_block_by_root(block_root)
+    }
+
     pub fn block_by_slot(&self, slot: Slot) -> Result<Option<WithStatus<BlockWithRoot<P>>>> {
         let store = self.store_snapshot();
 
@@ -452,6 +498,29 @@ where
         self.blob_sidecars_by_ids(blob_ids)
     }
 
+    pub fn reorgs(&self, from_slot: Slot) -> Result<Vec<ReorgRecord>> {
+        self.storage().reorgs(from_slot, MAX_REORGS_PER_QUERY)
+    }
+
+    /// Returns changes to the validator registry that take effect in epochs from `start_epoch` to
+    /// `end_epoch` inclusive.
+    ///
+    /// Changes are journaled only once the states containing them are finalized.
+    /// Only changes for which `filter` returns `true` count toward the limit.
+    pub fn registry_changes(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        filter: impl Fn(&RegistryChange) -> bool,
+    ) -> Result<Vec<RegistryChange>> {
+        self.storage().registry_changes(
+            start_epoch,
+            end_epoch,
+            MAX_REGISTRY_CHANGES_PER_QUERY,
+            filter,
+        )
+    }
+
     pub fn blocks_by_root(
         &self,
         block_roots: impl IntoIterator<Item = H256> + Send,
@@ -534,10 +603,49 @@ where
             .dependent_root(self.store_snapshot().as_ref(), state, epoch)
     }
 
+    /// Returns proposers for `epoch`, which may be as late as the next epoch.
+    ///
+    /// Duties for the next epoch are only a prediction.
+    /// They remain valid as long as [`ProposerDuties::dependent_root`] stays canonical.
+    pub fn proposer_duties(&self, epoch: Epoch) -> Result<WithStatus<Arc<ProposerDuties>>> {
+        let store = self.store_snapshot();
+        let head = store.head();
+
+        // If the head is older than `epoch`, it is the dependent root.
+        // Duties prewarmed for it can be returned without loading the state.
+        if head.slot() < misc::compute_start_slot_at_epoch::<P>(epoch) {
+            if let Some(duties) = self.proposer_duties_cache().get(epoch, head.block_root) {
+                return Ok(WithStatus {
+                    value: duties,
+                    optimistic: head.is_optimistic(),
+                    finalized: store.is_slot_finalized(head.slot()),
+                });
+            }
+        }
+
+        let WithStatus {
+            value: state,
+            optimistic,
+            finalized,
+        } = self.preprocessed_state_at_epoch(epoch)?;
+
+        let dependent_root = self.dependent_root(&state, epoch)?;
+
+        let duties =
+            self.proposer_duties_cache()
+                .get_or_try_insert(&state, epoch, dependent_root)?;
+
+        Ok(WithStatus {
+            value: duties,
+            optimistic,
+            finalized,
+        })
+    }
+
     #[must_use]
     pub fn snapshot(&self) -> Snapshot<P, W> {
         Snapshot {
-            store_snapshot: self.store_snapshot(),
+            store_snapshot: self.owned_store_snapshot(),
             state_cache: self.state_cache().clone_arc(),
             storage: self.storage(),
         }
@@ -699,6 +807,9 @@ pub struct BlockWithRoot<P: Preset> {
 
 /// A snapshot of the fork choice store that can also look up values in the database.
 ///
+/// Holding a [`Snapshot`] does not delay processing of new objects.
+/// Changes made to the fork choice store after the [`Snapshot`] was taken are not visible in it.
+///
 /// Note that the contents of the database are not snapshotted.
 /// They may change between calls to methods of a single [`Snapshot`].
 /// If database-level snapshotting turns out to be necessary we may have to go back to RocksDB. See:
@@ -709,9 +820,10 @@ pub struct BlockWithRoot<P: Preset> {
 /// [wiki]: https://github.com/facebook/rocksdb/wiki/Snapshot/e09da0053d05583919354cfaf834b8e8edd97be8
 #[allow(clippy::struct_field_names)]
 pub struct Snapshot<'storage, P: Preset, W> {
-    // Use a `Guard` instead of an owned snapshot unlike in tasks based on the intuition that
-    // `Snapshot`s will be less common than tasks.
-    store_snapshot: Guard<Arc<Store<P>>>,
+    // Use an owned snapshot rather than a `Guard`. `Snapshot`s are held for as long as it takes to
+    // respond to API requests, which can include slot processing. `Guard`s are meant to be
+    // short-lived. `arc_swap` only has a few fast slots per thread for them.
+    store_snapshot: Arc<Store<P>>,
     state_cache: Arc<StateCache<P, W>>,
     storage: &'storage Storage<P>,
 }
@@ -0,0 +1,252 @@
+//! Journal of validator registry changes.
+//!
+//! [`Storage`] compares the validator registry of every finalized state that starts a new epoch
+//! with the one previously journaled and persists the differences keyed by epoch. Queries such as
+//! "validators activated in epoch X" can then be answered from a handful of small entries instead
+//! of scanning the registry of whole states.
+//!
+//! [`Storage`]: crate::Storage
+
+use helper_functions::accessors;
+use serde::{Deserialize, Serialize};
+use ssz::{ReadError, Size, Ssz, SszRead, SszSize, SszWrite};
+use types::{
+    combined::BeaconState,
+    phase0::{
+        consts::FAR_FUTURE_EPOCH,
+        containers::Validator,
+        primitives::{Epoch, ValidatorIndex, H256},
+    },
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+// Entries for a single epoch are bounded by churn limits except when deposits are first processed
+// in bulk, so this should only truncate responses for very long epoch ranges.
+pub const MAX_REGISTRY_CHANGES_PER_QUERY: usize = 65536;
+
+/// Position of the journal stored in the same batch as the entries it covers.
+///
+/// The journal resumes from the state in `state_root` after a restart. Comparing against an older
+/// state like the state checkpoint would journal the same changes again under later epochs.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct RegistryJournalProgress {
+    /// Epoch of the last journaled state.
+    pub epoch: Epoch,
+    /// Root of the last journaled state.
+    pub state_root: H256,
+}
+
+impl RegistryJournalProgress {
+    pub(crate) const KEY: &'static str = "cregistry";
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryChangeKind {
+    /// A new validator was added to the registry by a deposit.
+    Deposited,
+    /// An activation epoch was assigned to the validator.
+    Activated,
+    /// An exit epoch was assigned to the validator, either voluntarily or by ejection or slashing.
+    Exited,
+    Slashed,
+    /// Withdrawal credentials of the validator were changed, usually from BLS to execution ones.
+    CredentialsChanged,
+}
+
+impl RegistryChangeKind {
+    pub(crate) const fn to_byte(self) -> u8 {
+        match self {
+            Self::Deposited => 0,
+            Self::Activated => 1,
+            Self::Exited => 2,
+            Self::Slashed => 3,
+            Self::CredentialsChanged => 4,
+        }
+    }
+
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Deposited),
+            1 => Some(Self::Activated),
+            2 => Some(Self::Exited),
+            3 => Some(Self::Slashed),
+            4 => Some(Self::CredentialsChanged),
+            _ => None,
+        }
+    }
+}
+
+impl SszSize for RegistryChangeKind {
+    const SIZE: Size = Size::Fixed { size: 1 };
+}
+
+impl<C> SszRead<C> for RegistryChangeKind {
+    fn from_ssz_unchecked(_context: &C, bytes: &[u8]) -> Result<Self, ReadError> {
+        Self::from_byte(bytes[0]).ok_or(ReadError::Custom {
+            message: "unknown registry change kind",
+        })
+    }
+}
+
+impl SszWrite for RegistryChangeKind {
+    fn write_fixed(&self, bytes: &mut [u8]) {
+        bytes[0] = self.to_byte();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct RegistryChange {
+    /// The epoch the change takes effect in.
+    ///
+    /// Activations and exits are scheduled in advance, so this is the activation or exit epoch.
+    /// For other changes this is the epoch of the first journaled state that contains them.
+    #[serde(with = "serde_utils::string_or_native")]
+    pub epoch: Epoch,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub validator_index: ValidatorIndex,
+    pub kind: RegistryChangeKind,
+}
+
+/// Returns changes to the validator registry between `old_state` and `new_state`.
+///
+/// This compares every validator, but it is only done once per epoch on the storage writer thread.
+#[must_use]
+pub fn registry_changes<P: Preset>(
+    old_state: &BeaconState<P>,
+    new_state: &BeaconState<P>,
+) -> Vec<RegistryChange> {
+    let current_epoch = accessors::get_current_epoch(new_state);
+    let old_validators = old_state.validators();
+    let mut changes = vec![];
+
+    for (validator_index, new) in (0..).zip(new_state.validators()) {
+        let old = old_validators.get(validator_index).ok();
+
+        let mut push = |epoch, kind| {
+            changes.push(RegistryChange {
+                epoch,
+                validator_index,
+                kind,
+            });
+        };
+
+        if old.is_none() {
+            push(current_epoch, RegistryChangeKind::Deposited);
+        }
+
+        let was = |predicate: fn(&Validator) -> bool| old.is_some_and(predicate);
+
+        if new.activation_epoch != FAR_FUTURE_EPOCH
+            && !was(|old| old.activation_epoch != FAR_FUTURE_EPOCH)
+        {
+            push(new.activation_epoch, RegistryChangeKind::Activated);
+        }
+
+        if new.exit_epoch != FAR_FUTURE_EPOCH && !was(|old| old.exit_epoch != FAR_FUTURE_EPOCH) {
+            push(new.exit_epoch, RegistryChangeKind::Exited);
+        }
+
+        if new.slashed && !was(|old| old.slashed) {
+            push(current_epoch, RegistryChangeKind::Slashed);
+        }
+
+        if old.is_some_and(|old| old.withdrawal_credentials != new.withdrawal_credentials) {
+            push(current_epoch, RegistryChangeKind::CredentialsChanged);
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use ssz::SszReadDefault as _;
+    use types::{
+        phase0::{beacon_state::BeaconState as Phase0BeaconState, primitives::H256},
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn registry_change_kinds_survive_ssz_round_trip() -> Result<()> {
+        for kind in [
+            RegistryChangeKind::Deposited,
+            RegistryChangeKind::Activated,
+            RegistryChangeKind::Exited,
+            RegistryChangeKind::Slashed,
+            RegistryChangeKind::CredentialsChanged,
+        ] {
+            assert_eq!(RegistryChangeKind::from_ssz_default(kind.to_ssz()?)?, kind);
+        }
+
+        assert!(RegistryChangeKind::from_ssz_default([5]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn registry_changes_are_detected_per_validator() -> Result<()> {
+        let pending = Validator {
+            activation_epoch: FAR_FUTURE_EPOCH,
+            exit_epoch: FAR_FUTURE_EPOCH,
+            ..Validator::default()
+        };
+
+        let active = Validator {
+            activation_epoch: 0,
+            ..pending.clone()
+        };
+
+        let old_state = BeaconState::from(Phase0BeaconState::<Minimal> {
+            validators: [pending.clone(), active.clone(), active.clone()].try_into()?,
+            ..Phase0BeaconState::default()
+        });
+
+        let new_state = BeaconState::from(Phase0BeaconState::<Minimal> {
+            slot: 16,
+            validators: [
+                Validator {
+                    activation_epoch: 5,
+                    ..pending.clone()
+                },
+                Validator {
+                    slashed: true,
+                    exit_epoch: 10,
+                    ..active.clone()
+                },
+                Validator {
+                    withdrawal_credentials: H256::repeat_byte(1),
+                    ..active
+                },
+                pending,
+            ]
+            .try_into()?,
+            ..Phase0BeaconState::default()
+        });
+
+        let change = |epoch, validator_index, kind| RegistryChange {
+            epoch,
+            validator_index,
+            kind,
+        };
+
+        assert_eq!(
+            registry_changes(&old_state, &new_state),
+            [
+                change(5, 0, RegistryChangeKind::Activated),
+                change(10, 1, RegistryChangeKind::Exited),
+                change(2, 1, RegistryChangeKind::Slashed),
+                change(2, 2, RegistryChangeKind::CredentialsChanged),
+                change(2, 3, RegistryChangeKind::Deposited),
+            ],
+        );
+
+        Ok(())
+    }
+}
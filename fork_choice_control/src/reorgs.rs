@@ -0,0 +1,171 @@
+use fork_choice_store::{ChainLink, Store};
+use serde::Serialize;
+use ssz::{ReadError, Size, Ssz, SszRead, SszSize, SszWrite};
+use types::{
+    phase0::primitives::{Epoch, Slot, H256},
+    preset::Preset,
+};
+
+use crate::messages::ChainReorgEvent;
+
+// Reorgs are rare, so this should only truncate responses when querying from genesis on a chain
+// with a lot of forking.
+pub const MAX_REORGS_PER_QUERY: usize = 1024;
+
+// Roughly 36 days on mainnet. Records are small and reorgs are rare, so this is mostly a bound on
+// how much history a node with frequent reorgs accumulates.
+pub const REORG_RETENTION_EPOCHS: Epoch = 8192;
+
+/// What caused the fork choice store to switch to another branch.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReorgCause {
+    /// A timely block received proposer boost.
+    ProposerBoost,
+    /// A block that did not receive proposer boost became the head.
+    /// This happens when it arrives too late in its slot or after it, or wins a tie.
+    LateBlock,
+    /// Votes changed either when attestations were received or when queued ones were applied
+    /// at the start of a slot. The latter also removes proposer boost from the previous block.
+    AttestationWeight,
+    /// Votes of validators were discarded because of an attester slashing.
+    AttesterSlashing,
+    /// An execution engine reported the status of a payload on either branch.
+    PayloadStatus,
+    /// Blob sidecars made a block on another branch available.
+    BlobSidecar,
+    /// A block updated the justified checkpoint, which changed the branches that can be the head.
+    Justification,
+}
+
+impl ReorgCause {
+    /// Classifies a reorganization caused by applying the block with root `block_root`.
+    ///
+    /// Must be called right after [`Store::apply_block`], before anything else modifies `store`.
+    /// `justified_checkpoints_updated` should be `true` if the block updated either the justified
+    /// or the unrealized justified checkpoint.
+    #[must_use]
+    pub fn for_block<P: Preset>(
+        store: &Store<P>,
+        block_root: H256,
+        justified_checkpoints_updated: bool,
+    ) -> Self {
+        // A block that updates the justified checkpoint can make the old head non-viable.
+        // In that case the new head is not necessarily the block itself.
+        if justified_checkpoints_updated {
+            Self::Justification
+        } else if store.proposer_boost_root() == block_root {
+            Self::ProposerBoost
+        } else {
+            Self::LateBlock
+        }
+    }
+
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::ProposerBoost => 0,
+            Self::LateBlock => 1,
+            Self::AttestationWeight => 2,
+            Self::AttesterSlashing => 3,
+            Self::PayloadStatus => 4,
+            Self::BlobSidecar => 5,
+            Self::Justification => 6,
+        }
+    }
+
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::ProposerBoost),
+            1 => Some(Self::LateBlock),
+            2 => Some(Self::AttestationWeight),
+            3 => Some(Self::AttesterSlashing),
+            4 => Some(Self::PayloadStatus),
+            5 => Some(Self::BlobSidecar),
+            6 => Some(Self::Justification),
+            _ => None,
+        }
+    }
+}
+
+impl SszSize for ReorgCause {
+    const SIZE: Size = Size::Fixed { size: 1 };
+}
+
+impl<C> SszRead<C> for ReorgCause {
+    fn from_ssz_unchecked(_context: &C, bytes: &[u8]) -> Result<Self, ReadError> {
+        Self::from_byte(bytes[0]).ok_or(ReadError::Custom {
+            message: "unknown reorg cause",
+        })
+    }
+}
+
+impl SszWrite for ReorgCause {
+    fn write_fixed(&self, bytes: &mut [u8]) {
+        bytes[0] = self.to_byte();
+    }
+}
+
+/// A reorganization observed by this node, persisted for later analysis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct ReorgRecord {
+    /// The slot the node was in when it switched branches.
+    #[serde(with = "serde_utils::string_or_native")]
+    pub slot: Slot,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub depth: u64,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub old_head_slot: Slot,
+    pub old_head_block: H256,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub new_head_slot: Slot,
+    pub new_head_block: H256,
+    pub cause: ReorgCause,
+}
+
+impl ReorgRecord {
+    #[must_use]
+    pub fn new<P: Preset>(
+        store: &Store<P>,
+        old_head: &ChainLink<P>,
+        event: &ChainReorgEvent,
+        cause: ReorgCause,
+    ) -> Self {
+        Self {
+            slot: store.slot(),
+            depth: event.depth,
+            old_head_slot: old_head.slot(),
+            old_head_block: event.old_head_block,
+            new_head_slot: event.slot,
+            new_head_block: event.new_head_block,
+            cause,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use ssz::SszReadDefault as _;
+
+    use super::*;
+
+    #[test]
+    fn reorg_causes_survive_ssz_round_trip() -> Result<()> {
+        for cause in [
+            ReorgCause::ProposerBoost,
+            ReorgCause::LateBlock,
+            ReorgCause::AttestationWeight,
+            ReorgCause::AttesterSlashing,
+            ReorgCause::PayloadStatus,
+            ReorgCause::BlobSidecar,
+            ReorgCause::Justification,
+        ] {
+            assert_eq!(ReorgCause::from_ssz_default(cause.to_ssz()?)?, cause);
+        }
+
+        assert!(ReorgCause::from_ssz_default([7]).is_err());
+
+        Ok(())
+    }
+}
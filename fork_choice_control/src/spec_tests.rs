@@ -163,8 +163,44 @@ fn function_name(case: Case) {
     run_case::<preset>(&config, case);
 }
 
+// The optimistic sync test vectors only check the head after blocks are invalidated.
+// These extend them to check that invalidated blocks stay out of fork choice.
+#[duplicate_item(
+    glob                                                                                                  function_name                               phase;
+    ["consensus-spec-tests/tests/minimal/bellatrix/sync/optimistic/pyspec_tests/from_syncing_to_invalid"] [bellatrix_from_syncing_to_invalid_children] [Bellatrix];
+    ["consensus-spec-tests/tests/minimal/capella/sync/optimistic/pyspec_tests/from_syncing_to_invalid"]   [capella_from_syncing_to_invalid_children]   [Capella];
+    ["consensus-spec-tests/tests/minimal/deneb/sync/optimistic/pyspec_tests/from_syncing_to_invalid"]     [deneb_from_syncing_to_invalid_children]     [Deneb];
+)]
+#[test_resources(glob)]
+fn function_name(case: Case) {
+    let config = Arc::new(Config::minimal().start_and_stay_in(Phase::phase));
+    let (mut context, blocks) = run_case::<Minimal>(&config, case);
+
+    let invalid_blocks = blocks
+        .iter()
+        .filter(|block| context.is_invalid(block))
+        .collect::<Vec<_>>();
+
+    assert!(!invalid_blocks.is_empty());
+
+    context.assert_invalid_block_count(invalid_blocks.len());
+
+    for block in &invalid_blocks {
+        let child = child_with_zero_signature(block);
+
+        context.on_slot(child.message().slot());
+        context.on_ignorable_block(&child);
+        context.assert_payload_status(&child, None);
+    }
+
+    context.assert_invalid_block_count(invalid_blocks.len());
+}
+
 #[allow(clippy::too_many_lines)]
-fn run_case<P: Preset>(config: &Arc<Config>, case: Case) {
+fn run_case<P: Preset>(
+    config: &Arc<Config>,
+    case: Case,
+) -> (Context<P>, Vec<Arc<SignedBeaconBlock<P>>>) {
     let anchor_block = case
         .ssz::<_, BeaconBlock<P>>(config.as_ref(), "anchor_block")
         .with_zero_signature()
@@ -180,6 +216,7 @@ fn run_case<P: Preset>(config: &Arc<Config>, case: Case) {
     };
 
     let mut context = Context::<P>::new(config.clone_arc(), anchor_block, anchor_state, false);
+    let mut blocks = vec![];
 
     for step in steps {
         match step {
@@ -243,6 +280,8 @@ fn run_case<P: Preset>(config: &Arc<Config>, case: Case) {
                 } else {
                     context.on_invalid_block(&block);
                 }
+
+                blocks.push(block);
             }
             Step::MergeBlock { pow_block } => {
                 let block_hash = pow_block
@@ -309,4 +348,40 @@ fn run_case<P: Preset>(config: &Arc<Config>, case: Case) {
             }
         }
     }
+
+    (context, blocks)
+}
+
+// Blocks are ignored if their parents are invalid, so the contents of the child do not matter.
+fn child_with_zero_signature<P: Preset>(
+    parent: &SignedBeaconBlock<P>,
+) -> Arc<SignedBeaconBlock<P>> {
+    let parent_root = parent.message().hash_tree_root();
+    let slot = parent.message().slot() + 1;
+    let (mut message, _) = parent.clone().split();
+
+    match &mut message {
+        BeaconBlock::Phase0(block) => {
+            block.slot = slot;
+            block.parent_root = parent_root;
+        }
+        BeaconBlock::Altair(block) => {
+            block.slot = slot;
+            block.parent_root = parent_root;
+        }
+        BeaconBlock::Bellatrix(block) => {
+            block.slot = slot;
+            block.parent_root = parent_root;
+        }
+        BeaconBlock::Capella(block) => {
+            block.slot = slot;
+            block.parent_root = parent_root;
+        }
+        BeaconBlock::Deneb(block) => {
+            block.slot = slot;
+            block.parent_root = parent_root;
+        }
+    }
+
+    Arc::new(message.with_zero_signature())
 }
@@ -83,6 +83,30 @@ where
         self.on_notified_new_payload(execution_block_hash, payload_status)
     }
 
+    /// Creates a controller that sends payloads to `execution_engine`.
+    ///
+    /// This is intended for integration tests in other crates.
+    /// Tests in this crate use `TestController` instead.
+    #[must_use]
+    pub fn with_execution_engine(
+        chain_config: Arc<ChainConfig>,
+        anchor_block: Arc<SignedBeaconBlock<P>>,
+        anchor_state: Arc<BeaconState<P>>,
+        execution_engine: E,
+    ) -> (Arc<Self>, MutatorHandle<P, WaitGroup>) {
+        let store_config = StoreConfig::minimal(&chain_config);
+
+        Self::new_internal(
+            chain_config,
+            store_config,
+            anchor_block,
+            anchor_state,
+            execution_engine,
+            None,
+            futures::sink::drain(),
+        )
+    }
+
     /// Waits until currently spawned tasks are completed.
     ///
     /// If the mutator spawns new tasks while handling messages from old ones,
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use anyhow::{ensure, Result};
+use thiserror::Error;
+use types::phase0::primitives::H256;
+
+// Smaller blocks find more matches but make the index of the base state larger.
+const BLOCK_SIZE: usize = 64;
+
+// Multiplier of the polynomial rolling hash. Any large odd number works.
+const HASH_MULTIPLIER: u64 = 0x100_0000_01b3;
+
+// Weight of the first byte of a block in its hash. Needed to remove it when rolling the hash.
+#[allow(clippy::cast_possible_truncation)]
+const OUTGOING_BYTE_MULTIPLIER: u64 = HASH_MULTIPLIER.wrapping_pow(BLOCK_SIZE as u32 - 1);
+
+const COPY_TAG: u8 = 0;
+const INSERT_TAG: u8 = 1;
+
+/// Difference between the SSZ serializations of two states.
+///
+/// Archival states stored as diffs are reconstructed from the SSZ bytes of a full state, which is
+/// much cheaper than replaying the blocks in between. The diff is computed on bytes rather than
+/// fields so that it works with states of every phase. Matching content is found even if it moved,
+/// which happens to every field after a list that grew.
+#[derive(PartialEq, Eq, Debug)]
+pub struct StateDiff {
+    base_block_root: H256,
+    base_length: usize,
+    target_length: usize,
+    instructions: Vec<Instruction>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum Instruction {
+    Copy { offset: usize, length: usize },
+    Insert(Vec<u8>),
+}
+
+impl StateDiff {
+    #[must_use]
+    pub fn compute(base_block_root: H256, base: &[u8], target: &[u8]) -> Self {
+        let index = index_blocks(base);
+
+        let mut instructions = vec![];
+        let mut literal_start = 0;
+        let mut position = 0;
+        let mut hash = None;
+
+        while position + BLOCK_SIZE <= target.len() {
+            let current_hash = match hash {
+                Some(previous_hash) => roll_hash(
+                    previous_hash,
+                    target[position - 1],
+                    target[position + BLOCK_SIZE - 1],
+                ),
+                None => hash_block(&target[position..position + BLOCK_SIZE]),
+            };
+
+            let matching_offset = index.get(&current_hash).copied().filter(|offset| {
+                base[*offset..*offset + BLOCK_SIZE] == target[position..position + BLOCK_SIZE]
+            });
+
+            let Some(offset) = matching_offset else {
+                hash = Some(current_hash);
+                position += 1;
+                continue;
+            };
+
+            // Extend the match in both directions. Extending backwards shortens the preceding
+            // insertion, which is common when only a few bytes of a block changed.
+            let mut start = position;
+            let mut base_start = offset;
+
+            while start > literal_start
+                && base_start > 0
+                && base[base_start - 1] == target[start - 1]
+            {
+                start -= 1;
+                base_start -= 1;
+            }
+
+            let mut end = position + BLOCK_SIZE;
+            let mut base_end = offset + BLOCK_SIZE;
+
+            while end < target.len() && base_end < base.len() && base[base_end] == target[end] {
+                end += 1;
+                base_end += 1;
+            }
+
+            if literal_start < start {
+                instructions.push(Instruction::Insert(target[literal_start..start].to_vec()));
+            }
+
+            instructions.push(Instruction::Copy {
+                offset: base_start,
+                length: end - start,
+            });
+
+            literal_start = end;
+            position = end;
+            hash = None;
+        }
+
+        if literal_start < target.len() {
+            instructions.push(Instruction::Insert(target[literal_start..].to_vec()));
+        }
+
+        Self {
+            base_block_root,
+            base_length: base.len(),
+            target_length: target.len(),
+            instructions,
+        }
+    }
+
+    #[must_use]
+    pub const fn base_block_root(&self) -> H256 {
+        self.base_block_root
+    }
+
+    pub fn apply(&self, base: &[u8]) -> Result<Vec<u8>> {
+        ensure!(
+            base.len() == self.base_length,
+            Error::BaseLengthMismatch {
+                expected: self.base_length,
+                actual: base.len(),
+            },
+        );
+
+        let mut target = Vec::with_capacity(self.target_length);
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Copy { offset, length } => {
+                    let bytes = offset
+                        .checked_add(*length)
+                        .and_then(|end| base.get(*offset..end))
+                        .ok_or(Error::CopyOutOfBounds)?;
+
+                    target.extend_from_slice(bytes);
+                }
+                Instruction::Insert(bytes) => target.extend_from_slice(bytes),
+            }
+        }
+
+        ensure!(
+            target.len() == self.target_length,
+            Error::TargetLengthMismatch {
+                expected: self.target_length,
+                actual: target.len(),
+            },
+        );
+
+        Ok(target)
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        bytes.extend_from_slice(self.base_block_root.as_bytes());
+        write_length(&mut bytes, self.base_length);
+        write_length(&mut bytes, self.target_length);
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Copy { offset, length } => {
+                    bytes.push(COPY_TAG);
+                    write_length(&mut bytes, *offset);
+                    write_length(&mut bytes, *length);
+                }
+                Instruction::Insert(literal) => {
+                    bytes.push(INSERT_TAG);
+                    write_length(&mut bytes, literal.len());
+                    bytes.extend_from_slice(literal);
+                }
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        let base_block_root = H256::from_slice(take(&mut bytes, H256::len_bytes())?);
+        let base_length = read_length(&mut bytes)?;
+        let target_length = read_length(&mut bytes)?;
+        let mut instructions = vec![];
+
+        while let Some((tag, rest)) = bytes.split_first() {
+            bytes = rest;
+
+            let instruction = match *tag {
+                COPY_TAG => Instruction::Copy {
+                    offset: read_length(&mut bytes)?,
+                    length: read_length(&mut bytes)?,
+                },
+                INSERT_TAG => {
+                    let length = read_length(&mut bytes)?;
+                    Instruction::Insert(take(&mut bytes, length)?.to_vec())
+                }
+                _ => return Err(Error::UnknownInstruction { tag: *tag }.into()),
+            };
+
+            instructions.push(instruction);
+        }
+
+        Ok(Self {
+            base_block_root,
+            base_length,
+            target_length,
+            instructions,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("state diff is truncated")]
+    Truncated,
+    #[error("state diff contains unknown instruction {tag}")]
+    UnknownInstruction { tag: u8 },
+    #[error("state diff copies bytes outside of base state")]
+    CopyOutOfBounds,
+    #[error("state diff expects base state of {expected} bytes, found {actual}")]
+    BaseLengthMismatch { expected: usize, actual: usize },
+    #[error("state diff produced {actual} bytes instead of {expected}")]
+    TargetLengthMismatch { expected: usize, actual: usize },
+}
+
+// Only blocks at multiples of `BLOCK_SIZE` are indexed. Matches are found at any position in the
+// target because the target is scanned with a rolling hash.
+fn index_blocks(base: &[u8]) -> HashMap<u64, usize> {
+    let mut index = HashMap::with_capacity(base.len() / BLOCK_SIZE);
+
+    for (block_index, block) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+        index
+            .entry(hash_block(block))
+            .or_insert(block_index * BLOCK_SIZE);
+    }
+
+    index
+}
+
+fn hash_block(block: &[u8]) -> u64 {
+    block.iter().fold(0, |hash, byte| {
+        hash.wrapping_mul(HASH_MULTIPLIER)
+            .wrapping_add(u64::from(*byte))
+    })
+}
+
+fn roll_hash(hash: u64, outgoing: u8, incoming: u8) -> u64 {
+    hash.wrapping_sub(u64::from(outgoing).wrapping_mul(OUTGOING_BYTE_MULTIPLIER))
+        .wrapping_mul(HASH_MULTIPLIER)
+        .wrapping_add(u64::from(incoming))
+}
+
+fn write_length(bytes: &mut Vec<u8>, length: usize) {
+    bytes.extend_from_slice(&(length as u64).to_le_bytes());
+}
+
+fn read_length(bytes: &mut &[u8]) -> Result<usize> {
+    let length_bytes = take(bytes, core::mem::size_of::<u64>())?;
+    let length = u64::from_le_bytes(length_bytes.try_into()?);
+    usize::try_from(length).map_err(Into::into)
+}
+
+fn take<'bytes>(bytes: &mut &'bytes [u8], length: usize) -> Result<&'bytes [u8]> {
+    ensure!(bytes.len() >= length, Error::Truncated);
+
+    let (taken, rest) = bytes.split_at(length);
+    *bytes = rest;
+
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(0, 0; "both empty")]
+    #[test_case(1000, 0; "target empty")]
+    #[test_case(0, 1000; "base empty")]
+    #[test_case(1000, 1000; "same length")]
+    #[test_case(1000, 1500; "target longer")]
+    #[test_case(1500, 1000; "base longer")]
+    fn diff_reconstructs_target(base_length: usize, target_length: usize) -> Result<()> {
+        let base = pseudorandom_bytes(base_length, 1);
+        let target = pseudorandom_bytes(target_length, 2);
+
+        assert_round_trip(&base, &target)
+    }
+
+    #[test]
+    fn diff_reuses_shifted_and_modified_content() -> Result<()> {
+        let base = pseudorandom_bytes(64 * 1024, 1);
+
+        // Insert bytes in the middle and modify a few bytes after them,
+        // similar to a list growing and balances changing.
+        let mut target = base[..16 * 1024].to_vec();
+        target.extend(pseudorandom_bytes(100, 2));
+        target.extend_from_slice(&base[16 * 1024..]);
+        target[40 * 1024] ^= 1;
+        target[50 * 1024] ^= 1;
+
+        let diff = StateDiff::compute(H256::zero(), &base, &target);
+
+        assert!(diff.to_bytes().len() < 1024);
+
+        assert_round_trip(&base, &target)
+    }
+
+    #[test]
+    fn apply_rejects_different_base() {
+        let base = pseudorandom_bytes(1000, 1);
+        let target = pseudorandom_bytes(1000, 2);
+        let diff = StateDiff::compute(H256::zero(), &base, &target);
+
+        diff.apply(&base[1..])
+            .expect_err("applying a diff to a base of different length should fail");
+    }
+
+    #[test]
+    fn from_bytes_rejects_truncated_diff() {
+        let base = pseudorandom_bytes(1000, 1);
+        let target = pseudorandom_bytes(1000, 2);
+        let bytes = StateDiff::compute(H256::zero(), &base, &target).to_bytes();
+
+        StateDiff::from_bytes(&bytes[..bytes.len() - 1])
+            .expect_err("decoding a truncated diff should fail");
+    }
+
+    fn assert_round_trip(base: &[u8], target: &[u8]) -> Result<()> {
+        let base_block_root = H256::repeat_byte(1);
+        let diff = StateDiff::compute(base_block_root, base, target);
+        let decoded = StateDiff::from_bytes(&diff.to_bytes())?;
+
+        assert_eq!(decoded, diff);
+        assert_eq!(decoded.base_block_root(), base_block_root);
+        assert_eq!(decoded.apply(base)?, target);
+
+        Ok(())
+    }
+
+    fn pseudorandom_bytes(length: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+
+        (0..length)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+
+                state.to_be_bytes()[0]
+            })
+            .collect()
+    }
+}
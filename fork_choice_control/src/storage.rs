@@ -1,9 +1,11 @@
 use core::{fmt::Display, marker::PhantomData, num::NonZeroU64};
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, path::Path, sync::Arc, thread};
 
 use anyhow::{bail, ensure, Context as _, Error as AnyhowError, Result};
 use arithmetic::U64Ext as _;
-use database::Database;
+use bytesize::ByteSize;
+use cached::{Cached as _, SizedCache};
+use database::{Database, DurabilityLevel};
 use derive_more::Display;
 use fork_choice_store::{ChainLink, Store};
 use genesis::GenesisProvider;
@@ -11,13 +13,16 @@ use helper_functions::{accessors, misc};
 use itertools::Itertools as _;
 use log::{debug, info, warn};
 use nonzero_ext::nonzero;
+use parking_lot::{Mutex, MutexGuard, RwLock};
+use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
 use reqwest::{Client, Url};
-use ssz::{Ssz, SszRead, SszReadDefault as _, SszWrite};
+use semver::Version;
+use ssz::{Ssz, SszRead, SszReadDefault, SszWrite};
 use std_ext::ArcExt as _;
 use thiserror::Error;
 use transition_functions::combined;
 use types::{
-    combined::{BeaconState, SignedBeaconBlock},
+    combined::{BeaconState, ExecutionPayload, SignedBeaconBlock, SignedBlindedBeaconBlock},
     config::Config,
     deneb::{
         containers::{BlobIdentifier, BlobSidecar},
@@ -26,20 +31,63 @@ use types::{
     nonstandard::BlobSidecarWithId,
     phase0::{
         consts::GENESIS_SLOT,
-        primitives::{Epoch, Slot, H256},
+        primitives::{Epoch, Slot, ValidatorIndex, H256},
     },
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
-use crate::checkpoint_sync::{self, FinalizedCheckpoint};
+use crate::{
+    checkpoint_sync::{self, CheckpointFiles, FinalizedCheckpoint},
+    era_store::EraStore,
+    pruning_progress::PruningProgress,
+    registry_journal::{self, RegistryChange, RegistryJournalProgress},
+    reorgs::{ReorgRecord, REORG_RETENTION_EPOCHS},
+    state_diff::StateDiff,
+    storage_back_sync::BackSyncStatus,
+    storage_read_cache::StorageReadCache,
+};
+
+// Directory names match the ones used by the node so that backups can be restored by copying them.
+const BACKUP_DATABASE_DIRECTORY: &str = "beacon_fork_choice";
+const BACKUP_ARCHIVE_DIRECTORY: &str = "beacon_archive";
 
 pub const DEFAULT_ARCHIVAL_EPOCH_INTERVAL: NonZeroU64 = nonzero!(32_u64);
 
+// Keys that were written by older versions of Grandine and are no longer read.
+const LEGACY_STATE_CHECKPOINT_KEY: &str = "cstate";
+
+// Number of `SlotByStateRoot` entries to write at once when indexing old blocks.
+const STATE_ROOT_INDEXING_BATCH_SIZE: usize = 1024;
+
+// Number of bytes of cold data to move to the archive database at once.
+// Archival states can be hundreds of megabytes each, so this is limited by size rather than count.
+const COLD_DATA_MIGRATION_BATCH_SIZE: ByteSize = ByteSize::mib(256);
+
+// Number of blocks deserialized in parallel while the previous batch is being applied.
+const BLOCK_PREFETCH_BATCH_SIZE: usize = 32;
+
+// Reconstructing a state far from an archived one can take minutes.
+// Clients querying historical data tend to request the same or nearby states repeatedly.
+const RECONSTRUCTED_STATE_CACHE_SIZE: usize = 8;
+
+/// A step that upgrades stored data from one schema version to the next.
+///
+/// Migrations must be idempotent. The entries returned by `apply` are written in the same batch
+/// as the new schema version, but migrations may write other batches before that.
+struct Migration<P: Preset> {
+    // The `meta.json` schema version the migration was introduced in.
+    // Used to tell which migrations databases created before `SchemaVersion` was recorded need.
+    introduced_in: Version,
+    description: &'static str,
+    apply: fn(&Storage<P>) -> Result<Vec<(String, Vec<u8>)>>,
+}
+
 pub enum StateLoadStrategy<P: Preset> {
     Auto {
         state_slot: Option<Slot>,
         checkpoint_sync_url: Option<Url>,
+        checkpoint_files: Option<CheckpointFiles>,
         genesis_provider: GenesisProvider<P>,
     },
     Remote {
@@ -52,11 +100,38 @@ pub enum StateLoadStrategy<P: Preset> {
 }
 
 #[allow(clippy::struct_field_names)]
-pub struct Storage<P> {
+pub struct Storage<P: Preset> {
     config: Arc<Config>,
-    pub(crate) database: Database,
+    database: Database,
+    // Finalized blocks and archival states make up most of the data stored by archive nodes but
+    // are rarely read. Storing them separately allows keeping them on slower and cheaper storage
+    // while unfinalized blocks, indices and checkpoints stay on fast storage.
+    archive_database: Option<Database>,
     pub(crate) archival_epoch_interval: NonZeroU64,
     prune_storage: bool,
+    prune_history_epochs: Option<u64>,
+    archival_snapshot_interval: Option<NonZeroU64>,
+    blob_retention_epochs: Option<u64>,
+    split_execution_payloads: bool,
+    execution_payload_retention_epochs: Option<u64>,
+    // Post-block states reconstructed by `Storage::stored_state`, keyed by block root.
+    reconstructed_states: Mutex<SizedCache<H256, Arc<BeaconState<P>>>>,
+    // Finalized blocks and stored states read recently, mostly by the HTTP API.
+    read_cache: StorageReadCache<P>,
+    // Held for reading while writing and for writing while taking snapshots for backups.
+    // A single batch may be split between both databases.
+    backup_lock: RwLock<()>,
+    // Held while pruning, whether started by the mutator or through the HTTP API.
+    // Concurrent pruning tasks would compete for the database and delete the same entries.
+    pruning_lock: Mutex<()>,
+    // Source of finalized blocks older than the ones in the database.
+    era_store: Option<EraStore>,
+    // The last finalized state whose validator registry was journaled.
+    // After a restart it is loaded using the root stored in `RegistryJournalProgress`.
+    registry_journal_base: Mutex<Option<Arc<BeaconState<P>>>>,
+    // The `meta.json` schema version of the data directory before it was updated to the current one.
+    // `None` if the data directory is new, already up to date or the database is in memory.
+    previous_schema_version: Option<Version>,
     phantom: PhantomData<P>,
 }
 
@@ -64,18 +139,110 @@ impl<P: Preset> Storage<P> {
     pub fn new(
         config: Arc<Config>,
         database: Database,
+        archive_database: Option<Database>,
         archival_epoch_interval: NonZeroU64,
         prune_storage: bool,
     ) -> Self {
         Self {
             config,
             database,
+            archive_database,
             archival_epoch_interval,
             prune_storage,
+            prune_history_epochs: None,
+            archival_snapshot_interval: None,
+            blob_retention_epochs: None,
+            split_execution_payloads: false,
+            execution_payload_retention_epochs: None,
+            reconstructed_states: Mutex::new(SizedCache::with_size(RECONSTRUCTED_STATE_CACHE_SIZE)),
+            read_cache: StorageReadCache::new(ByteSize(0)),
+            backup_lock: RwLock::new(()),
+            pruning_lock: Mutex::new(()),
+            era_store: None,
+            registry_journal_base: Mutex::new(None),
+            previous_schema_version: None,
             phantom: PhantomData,
         }
     }
 
+    /// Makes the mutator periodically delete finalized history older than `prune_history_epochs`
+    /// epochs before the latest finalized epoch. See [`Self::prune_history`].
+    #[must_use]
+    pub const fn with_prune_history_epochs(self, prune_history_epochs: Option<u64>) -> Self {
+        Self {
+            prune_history_epochs,
+            ..self
+        }
+    }
+
+    /// Makes archival states only be stored in full in epochs that are multiples of
+    /// `archival_snapshot_interval`. Other archival states are stored as [`StateDiff`]s against
+    /// the latest full one. `archival_snapshot_interval` should be a multiple of
+    /// `archival_epoch_interval`.
+    #[must_use]
+    pub const fn with_archival_snapshot_interval(
+        self,
+        archival_snapshot_interval: Option<NonZeroU64>,
+    ) -> Self {
+        Self {
+            archival_snapshot_interval,
+            ..self
+        }
+    }
+
+    /// Makes the mutator keep blob sidecars for `blob_retention_epochs` epochs instead of
+    /// `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` epochs. Shorter retention is not allowed.
+    #[must_use]
+    pub const fn with_blob_retention_epochs(self, blob_retention_epochs: Option<u64>) -> Self {
+        Self {
+            blob_retention_epochs,
+            ..self
+        }
+    }
+
+    /// Makes finalized blocks with execution payloads be stored as a blinded block and a separate
+    /// execution payload. Blinded blocks can then be served without reconstructing them, and
+    /// payloads can be pruned with [`Self::prune_execution_payloads`] while keeping the blocks.
+    /// Blocks stored whole before this was enabled remain readable.
+    #[must_use]
+    pub const fn with_split_execution_payloads(self, split_execution_payloads: bool) -> Self {
+        Self {
+            split_execution_payloads,
+            ..self
+        }
+    }
+
+    /// Makes the mutator periodically delete execution payloads stored separately from finalized
+    /// blocks in epochs more than `execution_payload_retention_epochs` epochs before the latest
+    /// finalized epoch. Only has an effect with [`Self::with_split_execution_payloads`].
+    #[must_use]
+    pub const fn with_execution_payload_retention_epochs(
+        self,
+        execution_payload_retention_epochs: Option<u64>,
+    ) -> Self {
+        Self {
+            execution_payload_retention_epochs,
+            ..self
+        }
+    }
+
+    /// Makes finalized blocks and stored states read from the database be kept in memory until
+    /// their SSZ encodings add up to more than `read_cache_size`. Caching is disabled by default.
+    #[must_use]
+    pub fn with_read_cache_size(self, read_cache_size: ByteSize) -> Self {
+        Self {
+            read_cache: StorageReadCache::new(read_cache_size),
+            ..self
+        }
+    }
+
+    /// Makes [`Self::block_by_slot`] read blocks missing from the database from era files.
+    /// Allows serving history from before the anchor without back-syncing it.
+    #[must_use]
+    pub fn with_era_store(self, era_store: Option<EraStore>) -> Self {
+        Self { era_store, ..self }
+    }
+
     /// Returns an instance that uses an in-memory database.
     ///
     /// The trait-based dependency injection used elsewhere makes it harder to select
@@ -86,12 +253,97 @@ impl<P: Preset> Storage<P> {
         Self {
             config,
             database: Database::in_memory(),
+            archive_database: None,
             archival_epoch_interval: DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
             prune_storage: false,
+            prune_history_epochs: None,
+            archival_snapshot_interval: None,
+            blob_retention_epochs: None,
+            split_execution_payloads: false,
+            execution_payload_retention_epochs: None,
+            reconstructed_states: Mutex::new(SizedCache::with_size(RECONSTRUCTED_STATE_CACHE_SIZE)),
+            read_cache: StorageReadCache::new(ByteSize(0)),
+            backup_lock: RwLock::new(()),
+            pruning_lock: Mutex::new(()),
+            era_store: None,
+            registry_journal_base: Mutex::new(None),
+            previous_schema_version: None,
             phantom: PhantomData,
         }
     }
 
+    // Migration `n` upgrades data from schema version `n` to `n + 1`.
+    // Migrations must only ever be appended. Reordering or removing them corrupts databases.
+    const MIGRATIONS: &'static [Migration<P>] = &[
+        Migration {
+            introduced_in: Version::new(0, 2, 2),
+            description: "delete legacy state checkpoint",
+            apply: Self::delete_legacy_state_checkpoint,
+        },
+        Migration {
+            introduced_in: Version::new(0, 2, 3),
+            description: "index state roots of finalized blocks",
+            apply: Self::index_state_roots_of_finalized_blocks,
+        },
+        Migration {
+            introduced_in: Version::new(0, 2, 6),
+            description: "move cold data to the archive database",
+            apply: Self::move_cold_data_to_archive_database,
+        },
+    ];
+
+    /// The schema version of data written by this version of Grandine.
+    ///
+    /// This is separate from the schema version in `meta.json`, which covers the whole data
+    /// directory. It is recorded under [`SchemaVersion::KEY`] after every migration.
+    pub const SCHEMA_VERSION: u64 = Self::MIGRATIONS.len() as u64;
+
+    /// Tells [`Self::load`] which `meta.json` schema version the data directory had before.
+    ///
+    /// It is only used to upgrade databases that do not have a schema version recorded yet.
+    #[must_use]
+    pub fn with_previous_schema_version(self, previous_schema_version: Option<Version>) -> Self {
+        Self {
+            previous_schema_version,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn is_pruning(&self) -> bool {
+        self.pruning_lock.is_locked()
+    }
+
+    /// Returns a guard that must be held while pruning, or `None` if pruning is already running.
+    pub(crate) fn try_lock_pruning(&self) -> Option<MutexGuard<'_, ()>> {
+        self.pruning_lock.try_lock()
+    }
+
+    pub(crate) fn lock_pruning(&self) -> Result<MutexGuard<'_, ()>> {
+        self.try_lock_pruning()
+            .ok_or_else(|| Error::PruningInProgress.into())
+    }
+
+    /// Like [`Self::lock_pruning`], but waits for pruning that is already running to finish.
+    pub(crate) fn lock_pruning_blocking(&self) -> MutexGuard<'_, ()> {
+        self.pruning_lock.lock()
+    }
+
+    #[must_use]
+    pub(crate) const fn prune_history_epochs(&self) -> Option<u64> {
+        self.prune_history_epochs
+    }
+
+    #[must_use]
+    pub(crate) const fn execution_payload_retention_epochs(&self) -> Option<u64> {
+        self.execution_payload_retention_epochs
+    }
+
+    #[must_use]
+    pub(crate) const fn blob_retention_epochs(&self) -> Option<u64> {
+        self.blob_retention_epochs
+    }
+
     #[must_use]
     pub(crate) const fn config(&self) -> &Arc<Config> {
         &self.config
@@ -102,6 +354,8 @@ impl<P: Preset> Storage<P> {
         client: &Client,
         state_load_strategy: StateLoadStrategy<P>,
     ) -> Result<(StateStorage<P>, bool)> {
+        self.migrate()?;
+
         let anchor_block;
         let anchor_state;
         let unfinalized_blocks: UnfinalizedBlocks<P>;
@@ -111,6 +365,7 @@ impl<P: Preset> Storage<P> {
             StateLoadStrategy::Auto {
                 state_slot,
                 checkpoint_sync_url,
+                checkpoint_files,
                 genesis_provider,
             } => 'block: {
                 // Attempt to load local state first: either latest or from specified slot.
@@ -119,6 +374,34 @@ impl<P: Preset> Storage<P> {
                     None => self.load_latest_state()?,
                 };
 
+                if let Some(CheckpointFiles {
+                    block_file,
+                    state_file,
+                }) = checkpoint_files
+                {
+                    // Load the checkpoint only if local state is not present.
+                    // Failing to load it is fatal because the files were specified explicitly.
+                    if local_state_storage.is_none() {
+                        let FinalizedCheckpoint { block, state } =
+                            checkpoint_sync::load_finalized_from_files(
+                                &self.config,
+                                &block_file,
+                                &state_file,
+                            )?;
+
+                        anchor_block = block;
+                        anchor_state = state;
+                        unfinalized_blocks = Box::new(core::iter::empty());
+                        loaded_from_remote = false;
+                        break 'block;
+                    }
+
+                    warn!(
+                        "skipping checkpoint state and block files: existing database found; \
+                         pass --force-checkpoint-sync to load them anyway",
+                    );
+                }
+
                 if let Some(url) = checkpoint_sync_url {
                     // Do checkpoint sync only if local state is not present.
                     if local_state_storage.is_none() {
@@ -189,24 +472,244 @@ impl<P: Preset> Storage<P> {
             }
         }
 
-        let anchor_slot = anchor_block.message().slot();
-        let anchor_block_root = anchor_block.message().hash_tree_root();
-        let anchor_state_root = anchor_block.message().state_root();
+        info!("loaded state at slot {}", anchor_block.message().slot());
 
-        info!("loaded state at slot {anchor_slot}");
+        // The cache only speeds up startup, so failing to read it should not prevent it.
+        if let Err(error) = self.load_public_keys_from_cache(&anchor_state) {
+            warn!("failed to load validator public keys from cache: {error:#}");
+        }
 
-        self.database.put_batch([
-            serialize(FinalizedBlockByRoot(anchor_block_root), &anchor_block)?,
-            serialize(BlockRootBySlot(anchor_slot), anchor_block_root)?,
-            serialize(SlotByStateRoot(anchor_state_root), anchor_slot)?,
-            serialize(StateByBlockRoot(anchor_block_root), &anchor_state)?,
-        ])?;
+        self.store_anchor(&anchor_block, &anchor_state)?;
+
+        if let Some(era_store) = self.era_store.as_ref() {
+            era_store.trust_history(&anchor_state);
+        }
 
         let state_storage = (anchor_state, anchor_block, unfinalized_blocks);
 
         Ok((state_storage, loaded_from_remote))
     }
 
+    /// Stores `block` and `state` in an empty database so that the next [`Self::load`] starts
+    /// from them, like it would with [`StateLoadStrategy::Anchor`].
+    pub(crate) fn import_anchor(
+        &self,
+        block: &SignedBeaconBlock<P>,
+        state: &BeaconState<P>,
+    ) -> Result<()> {
+        ensure!(!self.contains_finalized_blocks()?, Error::DatabaseNotEmpty);
+
+        self.migrate()?;
+        self.store_anchor(block, state)
+    }
+
+    fn store_anchor(&self, block: &SignedBeaconBlock<P>, state: &BeaconState<P>) -> Result<()> {
+        let slot = block.message().slot();
+        let block_root = block.message().hash_tree_root();
+        let state_root = block.message().state_root();
+
+        let mut batch = self.finalized_block_entries(block_root, block)?;
+
+        batch.push(serialize(BlockRootBySlot(slot), block_root)?);
+        batch.push(serialize(SlotByStateRoot(state_root), slot)?);
+
+        batch.push(serialize(StateByBlockRoot(block_root), state)?);
+
+        self.put_batch(batch)
+    }
+
+    // Blocks from phases without execution payloads are always stored whole.
+    pub(crate) fn finalized_block_entries(
+        &self,
+        block_root: H256,
+        block: &SignedBeaconBlock<P>,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let payload_header = match block.message().body().post_bellatrix() {
+            Some(body) if self.split_execution_payloads => body.execution_payload().to_header(),
+            _ => return Ok(vec![serialize(FinalizedBlockByRoot(block_root), block)?]),
+        };
+
+        let execution_payload = block
+            .clone()
+            .execution_payload()
+            .expect("blocks with post-Bellatrix bodies have execution payloads");
+
+        let (message, signature) = block.clone().split();
+        let blinded_block = message
+            .into_blinded(payload_header, None)?
+            .with_signature(signature);
+
+        Ok(vec![
+            serialize(BlindedBlockByRoot(block_root), blinded_block)?,
+            serialize(ExecutionPayloadByRoot(block_root), execution_payload)?,
+        ])
+    }
+
+    fn contains_finalized_blocks(&self) -> Result<bool> {
+        let first = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT).to_string()..)?
+            .next()
+            .transpose()?;
+
+        Ok(first.is_some_and(|(key_bytes, _)| BlockRootBySlot::has_prefix(&key_bytes)))
+    }
+
+    /// Upgrades stored data to [`Self::SCHEMA_VERSION`] by running migrations in order.
+    ///
+    /// The schema version is written together with the last batch of every migration,
+    /// so interrupted upgrades resume with the migration that did not finish.
+    pub(crate) fn migrate(&self) -> Result<()> {
+        let stored_version = self.get::<u64>(SchemaVersion::KEY)?;
+
+        let mut version = match stored_version {
+            Some(version) => version,
+            None => self.unversioned_schema_version()?,
+        };
+
+        ensure!(
+            version <= Self::SCHEMA_VERSION,
+            Error::SchemaVersionTooNew {
+                stored: version,
+                supported: Self::SCHEMA_VERSION,
+            },
+        );
+
+        if stored_version.is_none() && version == Self::SCHEMA_VERSION {
+            return self.put_batch([serialize(SchemaVersion::KEY, version)?]);
+        }
+
+        while let Some(migration) = Self::MIGRATIONS.get(usize::try_from(version)?) {
+            info!(
+                "migrating storage from schema version {version} to {}: {}",
+                version + 1,
+                migration.description,
+            );
+
+            let mut batch = (migration.apply)(self)?;
+
+            version += 1;
+
+            batch.push(serialize(SchemaVersion::KEY, version)?);
+
+            self.put_batch(batch)?;
+        }
+
+        Ok(())
+    }
+
+    // Databases without a recorded schema version are either new or were created before schema
+    // versions were recorded. In the latter case `meta.json` tells which migrations they need.
+    // If it was already updated, an earlier upgrade may have been interrupted before recording
+    // anything, so all migrations are run again. They are idempotent.
+    fn unversioned_schema_version(&self) -> Result<u64> {
+        if self.database.iterator_ascending(""..)?.next().is_none() {
+            return Ok(Self::SCHEMA_VERSION);
+        }
+
+        let Some(previous_version) = &self.previous_schema_version else {
+            return Ok(0);
+        };
+
+        let applied = Self::MIGRATIONS
+            .iter()
+            .take_while(|migration| migration.introduced_in <= *previous_version)
+            .count();
+
+        Ok(applied.try_into()?)
+    }
+
+    fn delete_legacy_state_checkpoint(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        self.database.delete(LEGACY_STATE_CHECKPOINT_KEY)?;
+        Ok(vec![])
+    }
+
+    // `SlotByStateRoot` was introduced after some databases had already been created.
+    fn index_state_roots_of_finalized_blocks(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        let results = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT).to_string()..)?;
+
+        let mut batch = vec![];
+
+        for result in results {
+            let (key_bytes, value_bytes) = result?;
+
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let BlockRootBySlot(slot) = key_bytes.try_into()?;
+            let block_root = H256::from_ssz_default(value_bytes)?;
+
+            // Blocks whose execution payloads have been pruned can only be read blinded.
+            let state_root = match self.finalized_blinded_block_by_root(block_root)? {
+                Some(block) => block.message().state_root(),
+                None => match self.finalized_block_by_root(block_root)? {
+                    Some(block) => block.message().state_root(),
+                    None => continue,
+                },
+            };
+
+            if !self.contains_key(SlotByStateRoot(state_root))? {
+                batch.push(serialize(SlotByStateRoot(state_root), slot)?);
+            }
+
+            if batch.len() >= STATE_ROOT_INDEXING_BATCH_SIZE {
+                self.put_batch(core::mem::take(&mut batch))?;
+            }
+        }
+
+        Ok(batch)
+    }
+
+    // Finalized blocks and execution payloads used to be stored in the main database even if a
+    // separate archive database was configured. Entries are copied before they are deleted,
+    // so an interrupted migration can be run again without losing any.
+    fn move_cold_data_to_archive_database(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        let Some(archive_database) = self.archive_database.as_ref() else {
+            return Ok(vec![]);
+        };
+
+        let mut batch = vec![];
+        let mut batch_size = 0;
+
+        let move_batch = |batch: Vec<(String, Vec<u8>)>| -> Result<()> {
+            let keys = batch
+                .iter()
+                .map(|(key_string, _)| key_string.clone())
+                .collect_vec();
+            archive_database.put_batch(batch)?;
+            self.database
+                .write_batch(core::iter::empty::<(&[u8], &[u8])>(), keys)
+        };
+
+        for result in self.database.iterator_ascending(""..)? {
+            let (key_bytes, value_bytes) = result?;
+
+            // All keys written by `Storage` are strings.
+            let Ok(key_string) = core::str::from_utf8(&key_bytes) else {
+                continue;
+            };
+
+            if KeyClass::of(key_string) != KeyClass::Cold {
+                continue;
+            }
+
+            batch_size += value_bytes.len() as u64;
+            batch.push((key_string.to_owned(), value_bytes));
+
+            if batch_size >= COLD_DATA_MIGRATION_BATCH_SIZE.as_u64() {
+                move_batch(core::mem::take(&mut batch))?;
+                batch_size = 0;
+            }
+        }
+
+        move_batch(batch)?;
+
+        Ok(vec![])
+    }
+
     fn load_latest_state(&self) -> Result<OptionalStateStorage<P>> {
         if let Some((state, block, blocks)) = self.load_state_and_blocks_from_checkpoint()? {
             Ok(OptionalStateStorage::Full((state, block, blocks)))
@@ -231,6 +734,22 @@ impl<P: Preset> Storage<P> {
         let mut checkpoint_state_appended = false;
         let mut archival_state_appended = false;
         let mut batch = vec![];
+        let mut deleted_keys = vec![];
+        let mut finalized_states = vec![];
+        let mut registry_journal_base = self.registry_journal_base.lock().clone();
+        let registry_journal_progress = self.registry_journal_progress()?;
+
+        if registry_journal_base.is_none() {
+            if let Some(RegistryJournalProgress { state_root, .. }) = registry_journal_progress {
+                registry_journal_base = self.stored_state_by_state_root(state_root)?;
+            }
+        }
+
+        // States up to this epoch are already journaled. This only matters if the last journaled
+        // state could not be loaded, in which case the next state becomes the base without being
+        // journaled. Changes between them are lost, but none are journaled twice.
+        let mut registry_journal_epoch = registry_journal_progress.map(|progress| progress.epoch);
+        let mut registry_journal_updated = None;
 
         let unfinalized = unfinalized.zip(core::iter::repeat(false));
         let finalized = finalized.rev().zip(core::iter::repeat(true));
@@ -240,8 +759,16 @@ impl<P: Preset> Storage<P> {
             .filter(|(chain_link, is_finalized)| *is_finalized || chain_link.is_valid())
             .peekable();
 
-        if let Some(StateCheckpoint { head_slot, .. }) = self.load_state_checkpoint()? {
+        if let Some(StateCheckpoint {
+            head_slot, state, ..
+        }) = self.load_state_checkpoint()?
+        {
             store_head_slot = head_slot;
+
+            // The journal starts from the state checkpoint when it is enabled for the first time.
+            if registry_journal_progress.is_none() {
+                registry_journal_base.get_or_insert(state);
+            }
         }
 
         if let Some((chain_link, _)) = chain.peek() {
@@ -259,7 +786,11 @@ impl<P: Preset> Storage<P> {
             if !self.prune_storage {
                 if finalized {
                     slots.finalized.push(state_slot);
-                    batch.push(serialize(FinalizedBlockByRoot(block_root), block)?);
+                    batch.extend(self.finalized_block_entries(block_root, block)?);
+
+                    // The block is now stored as a finalized block, so its unfinalized copy is
+                    // redundant. It is deleted along with the write so that it is never left over.
+                    deleted_keys.push(UnfinalizedBlockByRoot(block_root).to_string());
                 } else {
                     slots.unfinalized.push(state_slot);
                     batch.push(serialize(UnfinalizedBlockByRoot(block_root), block)?);
@@ -269,6 +800,8 @@ impl<P: Preset> Storage<P> {
             }
 
             if finalized {
+                finalized_states.push((state.clone_arc(), block.message().state_root()));
+
                 if !self.prune_storage {
                     batch.push(serialize(
                         SlotByStateRoot(block.message().state_root()),
@@ -298,6 +831,8 @@ impl<P: Preset> Storage<P> {
                             },
                         )?);
 
+                        batch.extend(self.public_key_cache_entries(state)?);
+
                         checkpoint_state_appended = true;
                     }
                 }
@@ -310,7 +845,7 @@ impl<P: Preset> Storage<P> {
                     if append_state {
                         info!("saving state in slot {state_slot}");
 
-                        batch.push(serialize(StateByBlockRoot(block_root), state)?);
+                        batch.push(self.archival_state_entry(block_root, state)?);
 
                         archival_state_appended = true;
                     }
@@ -318,7 +853,45 @@ impl<P: Preset> Storage<P> {
             }
         }
 
-        self.database.put_batch(batch)?;
+        // Finalized chain links are iterated from newest to oldest above.
+        for (state, state_root) in finalized_states.into_iter().rev() {
+            let epoch = accessors::get_current_epoch(&state);
+
+            if registry_journal_epoch.is_some_and(|journaled| epoch <= journaled) {
+                continue;
+            }
+
+            if let Some(base) = &registry_journal_base {
+                if epoch <= accessors::get_current_epoch(base) {
+                    continue;
+                }
+
+                for change in registry_journal::registry_changes(base, &state) {
+                    let RegistryChange {
+                        epoch,
+                        validator_index,
+                        kind,
+                    } = change;
+
+                    batch.push(serialize(
+                        RegistryChangeByEpoch(epoch, validator_index, kind.to_byte()),
+                        change,
+                    )?);
+                }
+            }
+
+            registry_journal_base = Some(state);
+            registry_journal_epoch = Some(epoch);
+            registry_journal_updated = Some(RegistryJournalProgress { epoch, state_root });
+        }
+
+        if let Some(progress) = registry_journal_updated {
+            batch.push(serialize(RegistryJournalProgress::KEY, progress)?);
+        }
+
+        self.write_batch(batch, deleted_keys)?;
+
+        *self.registry_journal_base.lock() = registry_journal_base;
 
         Ok(slots)
     }
@@ -350,11 +923,89 @@ impl<P: Preset> Storage<P> {
             persisted_blob_ids.push(blob_id);
         }
 
-        self.database.put_batch(batch)?;
+        self.put_batch(batch)?;
 
         Ok(persisted_blob_ids)
     }
 
+    /// Saves `reorg` and deletes records older than [`REORG_RETENTION_EPOCHS`] relative to it.
+    pub(crate) fn append_reorg(&self, reorg: ReorgRecord) -> Result<()> {
+        let ReorgRecord {
+            slot,
+            old_head_block,
+            new_head_block,
+            ..
+        } = reorg;
+
+        self.put_batch([serialize(
+            ReorgBySlot(slot, old_head_block, new_head_block),
+            reorg,
+        )?])?;
+
+        let retained_epoch = Self::epoch_at_slot(slot).saturating_sub(REORG_RETENTION_EPOCHS);
+
+        self.prune_reorgs(misc::compute_start_slot_at_epoch::<P>(retained_epoch))
+    }
+
+    /// Deletes reorg records from slots before `up_to_slot`.
+    pub(crate) fn prune_reorgs(&self, up_to_slot: Slot) -> Result<()> {
+        let results = self.database.iterator_descending(
+            ..=ReorgBySlot(up_to_slot, H256::zero(), H256::zero()).to_string(),
+        )?;
+
+        let keys = itertools::process_results(results, |pairs| {
+            pairs
+                .map(|(key_bytes, _)| key_bytes)
+                .take_while(|key_bytes| ReorgBySlot::has_prefix(key_bytes))
+                .map(|key_bytes| String::from_utf8(key_bytes.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+        })??;
+
+        self.delete_keys(keys)
+    }
+
+    pub(crate) fn reorgs(&self, from_slot: Slot, limit: usize) -> Result<Vec<ReorgRecord>> {
+        let results = self
+            .database
+            .iterator_ascending(ReorgBySlot(from_slot, H256::zero(), H256::zero()).to_string()..)?;
+
+        itertools::process_results(results, |pairs| {
+            pairs
+                .take_while(|(key_bytes, _)| ReorgBySlot::has_prefix(key_bytes))
+                .take(limit)
+                .map(|(_, value_bytes)| ReorgRecord::from_ssz_default(value_bytes))
+                .collect::<Result<Vec<_>, _>>()
+        })?
+        .map_err(Into::into)
+    }
+
+    pub(crate) fn registry_changes(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        limit: usize,
+        filter: impl Fn(&RegistryChange) -> bool,
+    ) -> Result<Vec<RegistryChange>> {
+        let results = self
+            .database
+            .iterator_ascending(RegistryChangeByEpoch(start_epoch, 0, 0).to_string()..)?;
+
+        itertools::process_results(results, |pairs| {
+            pairs
+                .take_while(|(key_bytes, _)| RegistryChangeByEpoch::has_prefix(key_bytes))
+                .map(|(_, value_bytes)| RegistryChange::from_ssz_default(value_bytes))
+                .take_while(|result| {
+                    result
+                        .as_ref()
+                        .map_or(true, |change| change.epoch <= end_epoch)
+                })
+                .filter(|result| result.as_ref().map_or(true, &filter))
+                .take(limit)
+                .collect::<Result<Vec<_>, _>>()
+        })?
+        .map_err(Into::into)
+    }
+
     pub(crate) fn blob_sidecar_by_id(
         &self,
         blob_id: BlobIdentifier,
@@ -364,9 +1015,12 @@ impl<P: Preset> Storage<P> {
         self.get(BlobSidecarByBlobId(block_root, index))
     }
 
-    pub(crate) fn prune_old_blob_sidecars(&self, up_to_slot: Slot) -> Result<()> {
-        let mut blobs_to_remove: Vec<BlobIdentifier> = vec![];
-        let mut keys_to_remove = vec![];
+    pub(crate) fn prune_old_blob_sidecars(
+        &self,
+        up_to_slot: Slot,
+        progress: &PruningProgress,
+    ) -> Result<()> {
+        let mut blobs_to_remove = vec![];
 
         let results = self
             .database
@@ -381,63 +1035,521 @@ impl<P: Preset> Storage<P> {
 
             // Deserialize-serialize BlobIdentifier as an additional measure
             // to prevent other types of data getting accidentally deleted.
-            blobs_to_remove.push(BlobIdentifier::from_ssz_default(value_bytes)?);
-            keys_to_remove.push(key_bytes);
-        }
+            let blob_id = BlobIdentifier::from_ssz_default(&value_bytes)?;
+            let key_string = String::from_utf8(key_bytes.into_owned())?;
 
-        for blob_id in blobs_to_remove {
-            self.database.delete(blob_id.to_ssz()?)?;
+            blobs_to_remove.push((blob_id, key_string));
         }
 
-        for key in keys_to_remove {
-            self.database.delete(key)?;
-        }
+        progress.set_total(blobs_to_remove.len());
 
-        Ok(())
-    }
+        for (BlobIdentifier { block_root, index }, key_string) in blobs_to_remove {
+            // The index is deleted after the blob sidecar so that an interrupted deletion
+            // can be completed by pruning again.
+            let freed_bytes = self.delete_keys_measuring([
+                BlobSidecarByBlobId(block_root, index).to_string(),
+                key_string,
+            ])?;
 
-    pub(crate) fn checkpoint_state_slot(&self) -> Result<Option<Slot>> {
-        if let Some(StateCheckpoint { head_slot, .. }) = self.load_state_checkpoint()? {
-            return Ok(Some(head_slot));
+            progress.record_processed(freed_bytes);
         }
 
-        Ok(None)
+        Ok(())
     }
 
-    pub(crate) fn genesis_block_root(&self, store: &Store<P>) -> Result<H256> {
-        self.block_root_by_slot_with_store(store, GENESIS_SLOT)?
-            .ok_or(Error::GenesisBlockRootNotFound)
-            .map_err(Into::into)
-    }
+    /// Deletes unfinalized blocks in slots up to the stored checkpoint that did not become
+    /// finalized, along with `BlockRootBySlot` entries that still point to them.
+    ///
+    /// Unfinalized blocks that did become finalized are deleted as well.
+    /// They are also stored as finalized blocks, so the copies are redundant.
+    /// Blocks after the checkpoint are left alone because they may not have been stored as
+    /// finalized blocks yet even if fork choice already considers them finalized.
+    pub(crate) fn prune_orphaned_blocks(&self, progress: &PruningProgress) -> Result<()> {
+        let Some(BlockCheckpoint { block }) = self.load_block_checkpoint()? else {
+            return Ok(());
+        };
 
-    pub(crate) fn contains_finalized_block(&self, block_root: H256) -> Result<bool> {
-        self.contains_key(FinalizedBlockByRoot(block_root))
-    }
+        let checkpoint_slot = block.message().slot();
 
-    pub(crate) fn contains_unfinalized_block(&self, block_root: H256) -> Result<bool> {
-        self.contains_key(UnfinalizedBlockByRoot(block_root))
-    }
+        let results = self
+            .database
+            .iterator_ascending(UnfinalizedBlockByRoot(H256::zero()).to_string()..)?;
 
-    pub(crate) fn finalized_block_by_root(
-        &self,
-        block_root: H256,
-    ) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
-        self.get(FinalizedBlockByRoot(block_root))
-    }
+        let mut block_roots = vec![];
 
-    pub(crate) fn unfinalized_block_by_root(
-        &self,
-        block_root: H256,
-    ) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
-        self.get(UnfinalizedBlockByRoot(block_root))
-    }
+        for result in results {
+            let (key_bytes, _) = result?;
+
+            if !UnfinalizedBlockByRoot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let UnfinalizedBlockByRoot(block_root) = key_bytes.try_into()?;
+
+            block_roots.push(block_root);
+        }
+
+        let mut blocks_to_prune = vec![];
+
+        for block_root in block_roots {
+            if let Some(block) = self.unfinalized_block_by_root(block_root)? {
+                let slot = block.message().slot();
+
+                if slot <= checkpoint_slot {
+                    blocks_to_prune.push((slot, block_root));
+                }
+            }
+        }
+
+        progress.set_total(blocks_to_prune.len());
+
+        for (slot, block_root) in blocks_to_prune {
+            let mut keys = vec![UnfinalizedBlockByRoot(block_root).to_string()];
+
+            if !self.contains_finalized_block(block_root)?
+                && self.block_root_by_slot(slot)? == Some(block_root)
+            {
+                keys.push(BlockRootBySlot(slot).to_string());
+            }
+
+            progress.record_processed(self.delete_keys_measuring(keys)?);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes archival states (and optionally finalized blocks) in slots before `up_to_slot`.
+    ///
+    /// The newest archival state before `up_to_slot` is kept so that states after it can still be
+    /// reconstructed by replaying blocks. The genesis block and state are never deleted.
+    /// Nothing is deleted if `dry_run` is `true`.
+    pub(crate) fn prune_archive(
+        &self,
+        up_to_slot: Slot,
+        include_blocks: bool,
+        dry_run: bool,
+        progress: &PruningProgress,
+    ) -> Result<ArchivePruningReport> {
+        let results = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT + 1).to_string()..)?;
+
+        let mut blocks = vec![];
+
+        for result in results {
+            let (key_bytes, value_bytes) = result?;
+
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let BlockRootBySlot(slot) = key_bytes.try_into()?;
+
+            if slot >= up_to_slot {
+                break;
+            }
+
+            blocks.push((slot, H256::from_ssz_default(value_bytes)?));
+        }
+
+        let mut states = vec![];
+
+        for (slot, block_root) in blocks.iter().copied() {
+            if self.contains_state(block_root)? {
+                states.push((slot, block_root));
+            }
+        }
+
+        let mut retained_state = states.pop();
+
+        // A state stored as a diff cannot be reconstructed once its base is deleted.
+        // Retain the base instead and prune the diffs after it.
+        if let Some((_, block_root)) = retained_state {
+            if let Some(diff) = self.state_diff(block_root)? {
+                let base_block_root = diff.base_block_root();
+
+                if let Some(position) = states
+                    .iter()
+                    .position(|(_, block_root)| *block_root == base_block_root)
+                {
+                    states.extend(retained_state);
+                    retained_state = Some(states.remove(position));
+                }
+            }
+        }
+
+        let retained_state_slot = retained_state.map(|(slot, _)| slot);
+        let state_slots = states.into_iter().map(|(slot, _)| slot).collect_vec();
+
+        // Blocks after the retained state are needed to reconstruct later states.
+        // Without a retained state they could only be replayed from genesis, so none are pruned.
+        let blocks_to_prune = match retained_state_slot {
+            Some(retained_state_slot) if include_blocks => {
+                blocks.partition_point(|(slot, _)| *slot < retained_state_slot)
+            }
+            _ => 0,
+        };
+
+        let report = ArchivePruningReport {
+            retained_state_slot,
+            pruned_state_slots: state_slots,
+            pruned_block_count: blocks_to_prune,
+        };
+
+        if dry_run {
+            return Ok(report);
+        }
+
+        progress.set_total(report.pruned_state_slots.len() + blocks_to_prune);
+
+        for (slot, block_root) in blocks.iter().copied() {
+            if report.pruned_state_slots.binary_search(&slot).is_ok() {
+                progress.record_processed(self.delete_state(block_root)?);
+            }
+        }
+
+        for (slot, block_root) in blocks.into_iter().take(blocks_to_prune) {
+            let mut keys = vec![];
+
+            // Blocks whose execution payloads have been pruned can only be read blinded.
+            let state_root = match self.finalized_blinded_block_by_root(block_root)? {
+                Some(block) => Some(block.message().state_root()),
+                None => self
+                    .finalized_block_by_root(block_root)?
+                    .map(|block| block.message().state_root()),
+            };
+
+            if let Some(state_root) = state_root {
+                keys.push(SlotByStateRoot(state_root).to_string());
+            }
+
+            keys.push(FinalizedBlockByRoot(block_root).to_string());
+            keys.push(BlindedBlockByRoot(block_root).to_string());
+            keys.push(ExecutionPayloadByRoot(block_root).to_string());
+            keys.push(BlockRootBySlot(slot).to_string());
+
+            progress.record_processed(self.delete_keys_measuring(keys)?);
+        }
+
+        Ok(report)
+    }
+
+    /// Deletes finalized states and blocks in epochs more than `retain_epochs` epochs before
+    /// `finalized_epoch`, along with their `SlotByStateRoot` and `BlockRootBySlot` entries.
+    ///
+    /// This is what `--prune-history-epochs` does once per epoch, but it may also be called
+    /// directly. Like [`Self::prune_archive`], it keeps the newest state before the retention
+    /// window along with the blocks after it, so every state in the window can be reconstructed.
+    pub fn prune_history(
+        &self,
+        finalized_epoch: Epoch,
+        retain_epochs: u64,
+        progress: &PruningProgress,
+    ) -> Result<ArchivePruningReport> {
+        let up_to_epoch = finalized_epoch.saturating_sub(retain_epochs);
+        let up_to_slot = misc::compute_start_slot_at_epoch::<P>(up_to_epoch);
+
+        self.prune_archive(up_to_slot, true, false, progress)
+    }
+
+    /// Deletes execution payloads stored separately from finalized blocks in epochs more than
+    /// `retain_epochs` epochs before `finalized_epoch`. Blinded blocks are kept.
+    /// Returns the number of payloads deleted.
+    ///
+    /// This is what `--execution-payload-retention-epochs` does once per epoch, but it may also be
+    /// called directly. Blocks without payloads cannot be replayed, so payloads after the newest
+    /// state before the retention window are kept, and states before it can no longer be
+    /// reconstructed.
+    pub fn prune_execution_payloads(
+        &self,
+        finalized_epoch: Epoch,
+        retain_epochs: u64,
+        progress: &PruningProgress,
+    ) -> Result<usize> {
+        let up_to_epoch = finalized_epoch.saturating_sub(retain_epochs);
+        let up_to_slot = misc::compute_start_slot_at_epoch::<P>(up_to_epoch);
+
+        let results = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT).to_string()..)?;
+
+        let mut block_roots = vec![];
+
+        for result in results {
+            let (key_bytes, value_bytes) = result?;
+
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let BlockRootBySlot(slot) = key_bytes.try_into()?;
+
+            if slot >= up_to_slot {
+                break;
+            }
+
+            block_roots.push(H256::from_ssz_default(value_bytes)?);
+        }
+
+        let mut retained_state_position = None;
+
+        for (position, block_root) in block_roots.iter().copied().enumerate().rev() {
+            if self.contains_state(block_root)? {
+                retained_state_position = Some(position);
+                break;
+            }
+        }
+
+        let Some(retained_state_position) = retained_state_position else {
+            return Ok(0);
+        };
+
+        block_roots.truncate(retained_state_position);
+
+        let mut payloads_to_prune = vec![];
+
+        for block_root in block_roots {
+            if self.contains_key(ExecutionPayloadByRoot(block_root))? {
+                payloads_to_prune.push(block_root);
+            }
+        }
+
+        let pruned_payload_count = payloads_to_prune.len();
+
+        progress.set_total(pruned_payload_count);
+
+        for block_root in payloads_to_prune {
+            let key = ExecutionPayloadByRoot(block_root).to_string();
+            progress.record_processed(self.delete_keys_measuring([key])?);
+        }
+
+        Ok(pruned_payload_count)
+    }
+
+    pub(crate) fn checkpoint_state_slot(&self) -> Result<Option<Slot>> {
+        if let Some(StateCheckpoint { head_slot, .. }) = self.load_state_checkpoint()? {
+            return Ok(Some(head_slot));
+        }
+
+        Ok(None)
+    }
+
+    pub(crate) fn checkpoint_anchor(
+        &self,
+    ) -> Result<Option<(Arc<SignedBeaconBlock<P>>, Arc<BeaconState<P>>)>> {
+        let anchor = self
+            .load_state_and_blocks_from_checkpoint()?
+            .map(|(state, block, _)| (block, state));
+
+        Ok(anchor)
+    }
+
+    pub(crate) fn checkpoint_state(&self) -> Result<Option<Arc<BeaconState<P>>>> {
+        if let Some(StateCheckpoint { state, .. }) = self.load_state_checkpoint()? {
+            return Ok(Some(state));
+        }
+
+        Ok(None)
+    }
+
+    pub(crate) fn genesis_block_root(&self, store: &Store<P>) -> Result<H256> {
+        self.block_root_by_slot_with_store(store, GENESIS_SLOT)?
+            .ok_or(Error::GenesisBlockRootNotFound)
+            .map_err(Into::into)
+    }
+
+    // Finalized blocks whose execution payloads have been pruned are considered to be stored
+    // even though only their blinded versions can be read.
+    pub(crate) fn contains_finalized_block(&self, block_root: H256) -> Result<bool> {
+        Ok(self.contains_key(FinalizedBlockByRoot(block_root))?
+            || self.contains_key(BlindedBlockByRoot(block_root))?)
+    }
+
+    pub(crate) fn contains_unfinalized_block(&self, block_root: H256) -> Result<bool> {
+        self.contains_key(UnfinalizedBlockByRoot(block_root))
+    }
+
+    pub(crate) fn finalized_block_by_root(
+        &self,
+        block_root: H256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
+        if let Some(block) = self.read_cache.finalized_block(block_root) {
+            return Ok(Some(block));
+        }
+
+        let generation = self.read_cache.generation();
+
+        if let Some(block_bytes) = self.get_bytes(FinalizedBlockByRoot(block_root).to_string())? {
+            let block = Arc::<SignedBeaconBlock<P>>::from_ssz(&self.config, &block_bytes)?;
+
+            self.read_cache.insert_finalized_block(
+                generation,
+                block_root,
+                block.clone_arc(),
+                block_bytes.len(),
+            );
+
+            return Ok(Some(block));
+        }
+
+        let Some(blinded_block) = self.finalized_blinded_block_by_root(block_root)? else {
+            return Ok(None);
+        };
+
+        // Returning `None` would make the block look missing and callers would skip it.
+        let Some(payload_bytes) = self.get_bytes(ExecutionPayloadByRoot(block_root).to_string())?
+        else {
+            bail!(Error::ExecutionPayloadPruned { block_root });
+        };
+
+        let execution_payload = match blinded_block {
+            SignedBlindedBeaconBlock::Bellatrix(_) => {
+                ExecutionPayload::Bellatrix(SszReadDefault::from_ssz_default(&payload_bytes)?)
+            }
+            SignedBlindedBeaconBlock::Capella(_) => {
+                ExecutionPayload::Capella(SszReadDefault::from_ssz_default(&payload_bytes)?)
+            }
+            SignedBlindedBeaconBlock::Deneb(_) => {
+                ExecutionPayload::Deneb(SszReadDefault::from_ssz_default(&payload_bytes)?)
+            }
+        };
+
+        let (message, signature) = blinded_block.split();
+
+        let block = Arc::new(
+            message
+                .with_execution_payload(execution_payload)?
+                .with_signature(signature),
+        );
+
+        // Weigh the reassembled block like blocks stored in one piece.
+        // The payload alone would let the cache hold more than its configured size.
+        let block_size = block.to_ssz()?.len();
+
+        self.read_cache.insert_finalized_block(
+            generation,
+            block_root,
+            block.clone_arc(),
+            block_size,
+        );
+
+        Ok(Some(block))
+    }
+
+    /// Returns the blinded version of a finalized block if it was stored separately from its
+    /// execution payload. See [`Self::with_split_execution_payloads`].
+    pub(crate) fn finalized_blinded_block_by_root(
+        &self,
+        block_root: H256,
+    ) -> Result<Option<SignedBlindedBeaconBlock<P>>> {
+        self.get(BlindedBlockByRoot(block_root))
+    }
+
+    pub(crate) fn unfinalized_block_by_root(
+        &self,
+        block_root: H256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<P>>>> {
+        self.get(UnfinalizedBlockByRoot(block_root))
+    }
 
     pub(crate) fn block_root_by_slot(&self, slot: Slot) -> Result<Option<H256>> {
         self.get(BlockRootBySlot(slot))
     }
 
     fn state_by_block_root(&self, block_root: H256) -> Result<Option<Arc<BeaconState<P>>>> {
-        self.get(StateByBlockRoot(block_root))
+        if let Some(state) = self.read_cache.state(block_root) {
+            return Ok(Some(state));
+        }
+
+        let generation = self.read_cache.generation();
+
+        if let Some(state_bytes) = self.get_bytes(StateByBlockRoot(block_root).to_string())? {
+            let state = Arc::<BeaconState<P>>::from_ssz(&self.config, &state_bytes)?;
+            self.read_cache.insert_state(
+                generation,
+                block_root,
+                state.clone_arc(),
+                state_bytes.len(),
+            );
+            return Ok(Some(state));
+        }
+
+        let Some(diff) = self.state_diff(block_root)? else {
+            return Ok(None);
+        };
+
+        let base_block_root = diff.base_block_root();
+
+        let base_bytes = self
+            .get_bytes(StateByBlockRoot(base_block_root).to_string())?
+            .ok_or(Error::StateDiffBaseNotFound {
+                block_root,
+                base_block_root,
+            })?;
+
+        let state_bytes = diff.apply(&base_bytes)?;
+        let state = Arc::<BeaconState<P>>::from_ssz(&self.config, &state_bytes)?;
+
+        self.read_cache
+            .insert_state(generation, block_root, state.clone_arc(), state_bytes.len());
+
+        Ok(Some(state))
+    }
+
+    pub(crate) fn state_diff(&self, block_root: H256) -> Result<Option<StateDiff>> {
+        self.get_bytes(StateDiffByBlockRoot(block_root).to_string())?
+            .map(|bytes| StateDiff::from_bytes(&bytes))
+            .transpose()
+    }
+
+    pub(crate) fn archival_state_entry(
+        &self,
+        block_root: H256,
+        state: &BeaconState<P>,
+    ) -> Result<(String, Vec<u8>)> {
+        let state_bytes = state.to_ssz()?;
+
+        if let Some(base_block_root) = self.state_diff_base(state.slot())? {
+            if let Some(base_bytes) =
+                self.get_bytes(StateByBlockRoot(base_block_root).to_string())?
+            {
+                let diff = StateDiff::compute(base_block_root, &base_bytes, &state_bytes);
+                return Ok((
+                    StateDiffByBlockRoot(block_root).to_string(),
+                    diff.to_bytes(),
+                ));
+            }
+        }
+
+        Ok((StateByBlockRoot(block_root).to_string(), state_bytes))
+    }
+
+    // Finds the latest full archival state stored since the start of the current snapshot period.
+    // A full state is stored in the middle of a period if there was none to compute a diff against.
+    fn state_diff_base(&self, state_slot: Slot) -> Result<Option<H256>> {
+        let Some(snapshot_interval) = self.archival_snapshot_interval else {
+            return Ok(None);
+        };
+
+        let state_epoch = Self::epoch_at_slot(state_slot);
+        let snapshot_epoch = state_epoch - state_epoch % snapshot_interval;
+        let mut epoch = state_epoch;
+
+        while epoch > snapshot_epoch {
+            epoch = epoch
+                .saturating_sub(self.archival_epoch_interval.get())
+                .max(snapshot_epoch);
+
+            let slot = misc::compute_start_slot_at_epoch::<P>(epoch);
+
+            if let Some(block_root) = self.block_root_by_slot(slot)? {
+                if self.contains_key_in_any_database(StateByBlockRoot(block_root))? {
+                    return Ok(Some(block_root));
+                }
+            }
+        }
+
+        Ok(None)
     }
 
     pub(crate) fn slot_by_state_root(&self, state_root: H256) -> Result<Option<Slot>> {
@@ -464,7 +1576,7 @@ impl<P: Preset> Storage<P> {
         slot: Slot,
     ) -> Result<Option<(Arc<SignedBeaconBlock<P>>, H256)>> {
         let Some(block_root) = self.block_root_by_slot(slot)? else {
-            return Ok(None);
+            return self.era_block_by_slot(slot);
         };
 
         let Some(block) = self.finalized_block_by_root(block_root)? else {
@@ -474,21 +1586,32 @@ impl<P: Preset> Storage<P> {
         Ok(Some((block, block_root)))
     }
 
-    pub(crate) fn stored_state(&self, slot: Slot) -> Result<Option<Arc<BeaconState<P>>>> {
-        let (mut state, state_block, blocks) = match self.load_state_by_iteration(slot)? {
-            OptionalStateStorage::None | OptionalStateStorage::UnfinalizedOnly(_) => {
-                return Ok(None)
-            }
-            OptionalStateStorage::Full(state_storage) => state_storage,
+    fn era_block_by_slot(&self, slot: Slot) -> Result<Option<(Arc<SignedBeaconBlock<P>>, H256)>> {
+        let Some(era_store) = self.era_store.as_ref() else {
+            return Ok(None);
         };
 
-        state.set_cached_root(state_block.message().state_root());
+        let block = era_store.block_by_slot(&self.config, slot)?;
+
+        Ok(block.map(|block| {
+            let block_root = block.message().hash_tree_root();
+            (block, block_root)
+        }))
+    }
+
+    pub(crate) fn stored_state(&self, slot: Slot) -> Result<Option<Arc<BeaconState<P>>>> {
+        let Some((mut state, block_roots)) = self.state_and_block_roots_by_iteration(slot)? else {
+            return Ok(None);
+        };
 
         // State may be persisted only once in several epochs.
-        // `blocks` here are needed to transition state closer to `slot`.
-        for result in blocks.rev() {
-            let block = result?;
-            combined::trusted_state_transition(&self.config, state.make_mut(), &block)?;
+        // Blocks with `block_roots` are needed to transition state closer to `slot`.
+        if let Some(latest_block_root) = block_roots.first().copied() {
+            self.replay_blocks(&mut state, block_roots)?;
+
+            self.reconstructed_states
+                .lock()
+                .cache_set(latest_block_root, state.clone_arc());
         }
 
         if state.slot() < slot {
@@ -666,24 +1789,171 @@ impl<P: Preset> Storage<P> {
         ))
     }
 
-    fn load_block_checkpoint(&self) -> Result<Option<BlockCheckpoint<P>>> {
-        self.get(BlockCheckpoint::<P>::KEY)
-    }
-
-    fn load_state_checkpoint(&self) -> Result<Option<StateCheckpoint<P>>> {
-        self.get(StateCheckpoint::<P>::KEY)
-    }
+    // Returns the nearest cached or stored state at or before `start_from_slot` along with
+    // the roots of blocks after it in descending order of slots.
+    fn state_and_block_roots_by_iteration(
+        &self,
+        start_from_slot: Slot,
+    ) -> Result<Option<(Arc<BeaconState<P>>, Vec<H256>)>> {
+        let results = self
+            .database
+            .iterator_descending(..=BlockRootBySlot(start_from_slot).to_string())?;
 
-    fn contains_key(&self, key: impl Display) -> Result<bool> {
-        let key_string = key.to_string();
+        let mut block_roots = vec![];
 
-        self.database.contains_key(key_string)
-    }
+        for result in results {
+            let (key_bytes, value_bytes) = result?;
 
-    fn get<V: SszRead<Config>>(&self, key: impl Display) -> Result<Option<V>> {
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let block_root = H256::from_ssz_default(value_bytes)?;
+
+            let cached_state = self
+                .reconstructed_states
+                .lock()
+                .cache_get(&block_root)
+                .cloned();
+
+            if let Some(state) = cached_state {
+                return Ok(Some((state, block_roots)));
+            }
+
+            if let Some(state) = self.state_by_block_root(block_root)? {
+                let slot = state.slot();
+
+                ensure!(
+                    misc::is_epoch_start::<P>(slot),
+                    Error::PersistedSlotCannotContainAnchor { slot },
+                );
+
+                let block = self
+                    .finalized_block_by_root(block_root)?
+                    .ok_or(Error::BlockNotFound { block_root })?;
+
+                state.set_cached_root(block.message().state_root());
+
+                return Ok(Some((state, block_roots)));
+            }
+
+            block_roots.push(block_root);
+        }
+
+        Ok(None)
+    }
+
+    // Blocks are loaded and deserialized in parallel in batches on a separate thread.
+    // The next batch is prepared while blocks from the previous one are being applied.
+    fn replay_blocks(
+        &self,
+        state: &mut Arc<BeaconState<P>>,
+        mut block_roots: Vec<H256>,
+    ) -> Result<()> {
+        block_roots.reverse();
+
+        thread::scope(|scope| {
+            let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+
+            scope.spawn(move || {
+                for batch in block_roots.chunks(BLOCK_PREFETCH_BATCH_SIZE) {
+                    let result = batch
+                        .par_iter()
+                        .map(|block_root| self.block_by_root(*block_root))
+                        .collect::<Result<Vec<_>>>();
+
+                    let failed = result.is_err();
+
+                    // Stop if replaying failed or if loading the batch did.
+                    if sender.send(result).is_err() || failed {
+                        break;
+                    }
+                }
+            });
+
+            for result in receiver {
+                for block in result? {
+                    combined::trusted_state_transition(&self.config, state.make_mut(), &block)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn block_by_root(&self, block_root: H256) -> Result<Arc<SignedBeaconBlock<P>>> {
+        if let Some(block) = self.finalized_block_by_root(block_root)? {
+            return Ok(block);
+        }
+
+        if let Some(block) = self.unfinalized_block_by_root(block_root)? {
+            return Ok(block);
+        }
+
+        bail!(Error::BlockNotFound { block_root })
+    }
+
+    fn registry_journal_progress(&self) -> Result<Option<RegistryJournalProgress>> {
+        self.get(RegistryJournalProgress::KEY)
+    }
+
+    pub(crate) fn back_sync_status(&self) -> Result<Option<BackSyncStatus>> {
+        self.get(BackSyncStatus::KEY)
+    }
+
+    fn load_block_checkpoint(&self) -> Result<Option<BlockCheckpoint<P>>> {
+        self.get(BlockCheckpoint::<P>::KEY)
+    }
+
+    fn load_state_checkpoint(&self) -> Result<Option<StateCheckpoint<P>>> {
+        self.get(StateCheckpoint::<P>::KEY)
+    }
+
+    fn contains_state(&self, block_root: H256) -> Result<bool> {
+        Ok(
+            self.contains_key_in_any_database(StateByBlockRoot(block_root))?
+                || self.contains_key_in_any_database(StateDiffByBlockRoot(block_root))?,
+        )
+    }
+
+    // Returns the number of bytes freed.
+    fn delete_state(&self, block_root: H256) -> Result<u64> {
+        self.delete_keys_measuring([
+            StateByBlockRoot(block_root).to_string(),
+            StateDiffByBlockRoot(block_root).to_string(),
+        ])
+    }
+
+    // Archival states may be in either database. See `get_bytes`.
+    fn contains_key_in_any_database(&self, key: impl Display) -> Result<bool> {
+        let key_string = key.to_string();
+
+        if let Some(archive_database) = self.archive_database.as_ref() {
+            if archive_database.contains_key(&key_string)? {
+                return Ok(true);
+            }
+        }
+
+        self.database.contains_key(key_string)
+    }
+
+    pub(crate) fn contains_key(&self, key: impl Display) -> Result<bool> {
         let key_string = key.to_string();
 
-        if let Some(value_bytes) = self.database.get(key_string)? {
+        if let Some(database) = self.database_for_key_class(KeyClass::of(&key_string)) {
+            if database.contains_key(&key_string)? {
+                return Ok(true);
+            }
+        }
+
+        // Cold data stored before the archive database was configured remains in the main one.
+        self.database.contains_key(key_string)
+    }
+
+    fn get<V: SszRead<Config>>(&self, key: impl Display) -> Result<Option<V>> {
+        let key_string = key.to_string();
+
+        if let Some(value_bytes) = self.get_bytes(key_string)? {
             let value = V::from_ssz(&self.config, value_bytes)?;
             return Ok(Some(value));
         }
@@ -691,18 +1961,181 @@ impl<P: Preset> Storage<P> {
         Ok(None)
     }
 
-    fn blocks_by_roots(&self, block_roots: Vec<H256>) -> UnfinalizedBlocks<P> {
-        Box::new(block_roots.into_iter().map(|block_root| {
-            if let Some(block) = self.finalized_block_by_root(block_root)? {
-                return Ok(block);
+    pub(crate) fn get_bytes(&self, key_string: String) -> Result<Option<Vec<u8>>> {
+        if let Some(archive_database) = self.database_for_key_class(KeyClass::of(&key_string)) {
+            if let Some(value_bytes) = archive_database.get(&key_string)? {
+                return Ok(Some(value_bytes));
             }
+        }
 
-            if let Some(block) = self.unfinalized_block_by_root(block_root)? {
-                return Ok(block);
+        // Cold data stored before the archive database was configured remains in the main one.
+        self.database.get(key_string)
+    }
+
+    pub(crate) fn put_batch(
+        &self,
+        batch: impl IntoIterator<Item = (String, Vec<u8>)>,
+    ) -> Result<()> {
+        self.write_batch(batch, core::iter::empty())
+    }
+
+    // Like `put_batch`, but also deletes `deleted_keys` in the same transactions.
+    // Keys are deleted from both databases like in `delete_keys`.
+    pub(crate) fn write_batch(
+        &self,
+        batch: impl IntoIterator<Item = (String, Vec<u8>)>,
+        deleted_keys: impl IntoIterator<Item = String>,
+    ) -> Result<()> {
+        let batch = batch.into_iter().collect_vec();
+        let deleted_keys = deleted_keys.into_iter().collect_vec();
+
+        // The node restarts from the latest checkpoint. Losing it would make the node
+        // fall back to an older one or resync, so it is written durably regardless of settings.
+        // The blocks and states it refers to may be in the archive database,
+        // so the part of the batch written there must be just as durable.
+        let contains_checkpoint = batch.iter().any(|(key_string, _)| {
+            key_string == StateCheckpoint::<P>::KEY || key_string == BlockCheckpoint::<P>::KEY
+        });
+
+        let write_batch = |database: &Database,
+                           batch: Vec<(String, Vec<u8>)>,
+                           deleted_keys: Vec<&String>| {
+            if contains_checkpoint {
+                database.write_batch_with_durability(batch, deleted_keys, DurabilityLevel::Always)
+            } else {
+                database.write_batch(batch, deleted_keys)
             }
+        };
 
-            bail!(Error::BlockNotFound { block_root })
-        }))
+        // Only finalized blocks and stored states are cached, all of which are cold.
+        let clear_read_cache = deleted_keys
+            .iter()
+            .any(|key_string| KeyClass::of(key_string) == KeyClass::Cold);
+
+        let _backup_guard = self.backup_lock.read();
+
+        match self.archive_database.as_ref() {
+            Some(archive_database) => {
+                let (cold_batch, hot_batch): (Vec<_>, Vec<_>) = batch
+                    .into_iter()
+                    .partition(|(key_string, _)| KeyClass::of(key_string) == KeyClass::Cold);
+
+                let cold_deleted_keys = deleted_keys
+                    .iter()
+                    .filter(|key_string| KeyClass::of(key_string) == KeyClass::Cold)
+                    .collect();
+
+                // The databases cannot be written to atomically. Cold data is written first so
+                // that the main database never refers to missing blocks or states. If writing to
+                // the main database fails, the cold data is left without references to it.
+                // That is harmless. It is overwritten if the same blocks are appended again.
+                write_batch(archive_database, cold_batch, cold_deleted_keys)?;
+
+                // Cold data stored before the archive database was configured may be in the main
+                // database, so deleted keys are deleted from it regardless of their class.
+                write_batch(&self.database, hot_batch, deleted_keys.iter().collect())?;
+            }
+            None => write_batch(&self.database, batch, deleted_keys.iter().collect())?,
+        }
+
+        if clear_read_cache {
+            self.read_cache.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Copies the databases to subdirectories of `directory`.
+    ///
+    /// Snapshots of both databases are taken between batches written by [`Self::put_batch`],
+    /// so the backup never contains part of a batch. Writes resume once both are taken
+    /// and do not end up in the backup.
+    pub fn backup(&self, directory: &Path, size: ByteSize) -> Result<()> {
+        let backup_guard = self.backup_lock.write();
+        let database_directory = directory.join(BACKUP_DATABASE_DIRECTORY);
+        let archive_directory = directory.join(BACKUP_ARCHIVE_DIRECTORY);
+
+        self.database.backup_to(database_directory, size, || {
+            match self.archive_database.as_ref() {
+                Some(archive_database) => {
+                    archive_database.backup_to(archive_directory, size, || {
+                        drop(backup_guard);
+                        Ok(())
+                    })
+                }
+                None => {
+                    drop(backup_guard);
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Compacts both databases and returns the number of bytes reclaimed on disk.
+    ///
+    /// Space freed by pruning is otherwise reused for new data but not returned to the OS.
+    pub fn compact(&self) -> Result<u64> {
+        self.databases().map(Database::compact).sum()
+    }
+
+    #[must_use]
+    pub fn supports_online_compaction(&self) -> bool {
+        self.databases().all(Database::supports_online_compaction)
+    }
+
+    // Cold data may be in either database, so both have to be checked.
+    pub(crate) fn databases(&self) -> impl Iterator<Item = &Database> {
+        core::iter::once(&self.database).chain(self.archive_database.as_ref())
+    }
+
+    pub(crate) fn delete_keys(&self, keys: impl IntoIterator<Item = String>) -> Result<()> {
+        let _backup_guard = self.backup_lock.read();
+
+        for key_string in keys {
+            if let Some(database) = self.database_for_key_class(KeyClass::of(&key_string)) {
+                database.delete(&key_string)?;
+            }
+
+            self.database.delete(key_string)?;
+        }
+
+        self.read_cache.clear();
+
+        Ok(())
+    }
+
+    // Like `delete_keys`, but also returns the number of bytes the deleted values took up.
+    fn delete_keys_measuring(&self, keys: impl IntoIterator<Item = String>) -> Result<u64> {
+        let keys = keys.into_iter().collect_vec();
+        let mut freed_bytes = 0;
+
+        for key_string in &keys {
+            for database in self.databases() {
+                if let Some(value_bytes) = database.get(key_string)? {
+                    freed_bytes += value_bytes.len() as u64;
+                }
+            }
+        }
+
+        self.delete_keys(keys)?;
+
+        Ok(freed_bytes)
+    }
+
+    // Returns `None` if data of the class should be stored in the main database.
+    fn database_for_key_class(&self, key_class: KeyClass) -> Option<&Database> {
+        match key_class {
+            KeyClass::Hot => None,
+            KeyClass::Cold => self.archive_database.as_ref(),
+        }
+    }
+
+    fn blocks_by_roots(&self, block_roots: Vec<H256>) -> UnfinalizedBlocks<P> {
+        Box::new(
+            block_roots
+                .into_iter()
+                .map(|block_root| self.block_by_root(block_root)),
+        )
     }
 
     pub(crate) fn epoch_at_slot(slot: Slot) -> Epoch {
@@ -713,15 +2146,59 @@ impl<P: Preset> Storage<P> {
 #[cfg(test)]
 impl<P: Preset> Storage<P> {
     pub fn finalized_block_count(&self) -> Result<usize> {
-        let results = self
-            .database
-            .iterator_ascending(FinalizedBlockByRoot(H256::zero()).to_string()..)?;
+        let mut count = 0;
 
-        itertools::process_results(results, |pairs| {
-            pairs
-                .take_while(|(key_bytes, _)| FinalizedBlockByRoot::has_prefix(key_bytes))
-                .count()
-        })
+        for database in self.databases() {
+            let whole =
+                database.iterator_ascending(FinalizedBlockByRoot(H256::zero()).to_string()..)?;
+
+            count += itertools::process_results(whole, |pairs| {
+                pairs
+                    .take_while(|(key_bytes, _)| FinalizedBlockByRoot::has_prefix(key_bytes))
+                    .count()
+            })?;
+
+            let blinded =
+                database.iterator_ascending(BlindedBlockByRoot(H256::zero()).to_string()..)?;
+
+            count += itertools::process_results(blinded, |pairs| {
+                pairs
+                    .take_while(|(key_bytes, _)| BlindedBlockByRoot::has_prefix(key_bytes))
+                    .count()
+            })?;
+        }
+
+        Ok(count)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum KeyClass {
+    // Unfinalized blocks, blob sidecars, indices and checkpoints.
+    Hot,
+    // Finalized blocks, execution payloads and archival states.
+    Cold,
+}
+
+impl KeyClass {
+    fn of(key_string: &str) -> Self {
+        let key_bytes = key_string.as_bytes();
+
+        // `FinalizedBlockByRoot::PREFIX` is also a prefix of `UnfinalizedBlockByRoot::PREFIX`.
+        if UnfinalizedBlockByRoot::has_prefix(key_bytes) {
+            return Self::Hot;
+        }
+
+        if FinalizedBlockByRoot::has_prefix(key_bytes)
+            || BlindedBlockByRoot::has_prefix(key_bytes)
+            || ExecutionPayloadByRoot::has_prefix(key_bytes)
+            || StateByBlockRoot::has_prefix(key_bytes)
+            || StateDiffByBlockRoot::has_prefix(key_bytes)
+        {
+            Self::Cold
+        } else {
+            Self::Hot
+        }
     }
 }
 
@@ -731,6 +2208,13 @@ pub struct AppendedBlockSlots {
     pub unfinalized: Vec<Slot>,
 }
 
+#[derive(Default, Debug)]
+pub struct ArchivePruningReport {
+    pub retained_state_slot: Option<Slot>,
+    pub pruned_state_slots: Vec<Slot>,
+    pub pruned_block_count: usize,
+}
+
 type UnfinalizedBlocks<'storage, P> =
     Box<dyn DoubleEndedIterator<Item = Result<Arc<SignedBeaconBlock<P>>>> + Send + 'storage>;
 
@@ -758,7 +2242,7 @@ type StateStorage<'storage, P> = (
 // A `bound_for_read` attribute like this must be added when deriving `SszRead` for any type that
 // contains a block or state. The name of the `C` type parameter is hardcoded in `ssz_derive`.
 #[ssz(bound_for_read = "BeaconState<P>: SszRead<C>", derive_hash = false)]
-struct StateCheckpoint<P: Preset> {
+pub(crate) struct StateCheckpoint<P: Preset> {
     block_root: H256,
     head_slot: Slot,
     state: Arc<BeaconState<P>>,
@@ -766,7 +2250,7 @@ struct StateCheckpoint<P: Preset> {
 
 impl<P: Preset> StateCheckpoint<P> {
     // This was renamed from `cstate` for compatibility with old schema versions.
-    const KEY: &'static str = "cstate2";
+    pub(crate) const KEY: &'static str = "cstate2";
 }
 
 #[derive(Ssz)]
@@ -777,12 +2261,18 @@ impl<P: Preset> StateCheckpoint<P> {
     derive_hash = false,
     transparent
 )]
-struct BlockCheckpoint<P: Preset> {
+pub(crate) struct BlockCheckpoint<P: Preset> {
     block: Arc<SignedBeaconBlock<P>>,
 }
 
 impl<P: Preset> BlockCheckpoint<P> {
-    const KEY: &'static str = "cblock";
+    pub(crate) const KEY: &'static str = "cblock";
+}
+
+pub(crate) struct SchemaVersion;
+
+impl SchemaVersion {
+    pub(crate) const KEY: &'static str = "version";
 }
 
 #[derive(Display)]
@@ -808,7 +2298,7 @@ impl TryFrom<Cow<'_, [u8]>> for BlockRootBySlot {
 }
 
 impl BlockRootBySlot {
-    const PREFIX: &'static str = "r";
+    pub(crate) const PREFIX: &'static str = "r";
 
     fn has_prefix(bytes: &[u8]) -> bool {
         bytes.starts_with(Self::PREFIX.as_bytes())
@@ -820,9 +2310,32 @@ impl BlockRootBySlot {
 pub struct FinalizedBlockByRoot(pub H256);
 
 impl FinalizedBlockByRoot {
-    const PREFIX: &'static str = "b";
+    pub(crate) const PREFIX: &'static str = "b";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:x}", Self::PREFIX)]
+pub struct BlindedBlockByRoot(pub H256);
+
+impl BlindedBlockByRoot {
+    pub(crate) const PREFIX: &'static str = "l";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:x}", Self::PREFIX)]
+pub struct ExecutionPayloadByRoot(pub H256);
+
+impl ExecutionPayloadByRoot {
+    pub(crate) const PREFIX: &'static str = "e";
 
-    #[cfg(test)]
     fn has_prefix(bytes: &[u8]) -> bool {
         bytes.starts_with(Self::PREFIX.as_bytes())
     }
@@ -832,8 +2345,30 @@ impl FinalizedBlockByRoot {
 #[display(fmt = "{}{_0:x}", Self::PREFIX)]
 pub struct UnfinalizedBlockByRoot(pub H256);
 
+impl TryFrom<Cow<'_, [u8]>> for UnfinalizedBlockByRoot {
+    type Error = AnyhowError;
+
+    fn try_from(bytes: Cow<[u8]>) -> Result<Self> {
+        let payload =
+            bytes
+                .strip_prefix(Self::PREFIX.as_bytes())
+                .ok_or_else(|| Error::IncorrectPrefix {
+                    bytes: bytes.to_vec(),
+                })?;
+
+        let string = core::str::from_utf8(payload)?;
+        let block_root = string.parse()?;
+
+        Ok(Self(block_root))
+    }
+}
+
 impl UnfinalizedBlockByRoot {
-    const PREFIX: &'static str = "b_nf";
+    pub(crate) const PREFIX: &'static str = "b_nf";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
 }
 
 #[derive(Display)]
@@ -841,7 +2376,23 @@ impl UnfinalizedBlockByRoot {
 pub struct StateByBlockRoot(pub H256);
 
 impl StateByBlockRoot {
-    const PREFIX: &'static str = "s";
+    pub(crate) const PREFIX: &'static str = "s";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:x}", Self::PREFIX)]
+pub struct StateDiffByBlockRoot(pub H256);
+
+impl StateDiffByBlockRoot {
+    pub(crate) const PREFIX: &'static str = "d";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
 }
 
 #[derive(Display)]
@@ -849,7 +2400,7 @@ impl StateByBlockRoot {
 pub struct SlotByStateRoot(pub H256);
 
 impl SlotByStateRoot {
-    const PREFIX: &'static str = "t";
+    pub(crate) const PREFIX: &'static str = "t";
 }
 
 #[derive(Display)]
@@ -857,7 +2408,7 @@ impl SlotByStateRoot {
 pub struct BlobSidecarByBlobId(pub H256, pub BlobIndex);
 
 impl BlobSidecarByBlobId {
-    const PREFIX: &'static str = "o";
+    pub(crate) const PREFIX: &'static str = "o";
 }
 
 #[derive(Display)]
@@ -865,7 +2416,31 @@ impl BlobSidecarByBlobId {
 pub struct SlotBlobId(pub Slot, pub H256, pub BlobIndex);
 
 impl SlotBlobId {
-    const PREFIX: &'static str = "i";
+    pub(crate) const PREFIX: &'static str = "i";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:020}{_1:x}{_2:x}", Self::PREFIX)]
+pub struct ReorgBySlot(pub Slot, pub H256, pub H256);
+
+impl ReorgBySlot {
+    pub(crate) const PREFIX: &'static str = "g";
+
+    fn has_prefix(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::PREFIX.as_bytes())
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:020}{_1:020}{_2}", Self::PREFIX)]
+pub struct RegistryChangeByEpoch(pub Epoch, pub ValidatorIndex, pub u8);
+
+impl RegistryChangeByEpoch {
+    pub(crate) const PREFIX: &'static str = "j";
 
     fn has_prefix(bytes: &[u8]) -> bool {
         bytes.starts_with(Self::PREFIX.as_bytes())
@@ -891,10 +2466,881 @@ pub enum Error {
     CheckpointBlockRootMismatch { requested: H256, computed: H256 },
     #[error("persisted slot cannot contain anchor: {slot}")]
     PersistedSlotCannotContainAnchor { slot: Slot },
+    #[error("database already contains blocks; anchors can only be imported into an empty one")]
+    DatabaseNotEmpty,
+    #[error("data is already being pruned")]
+    PruningInProgress,
     #[error("storage key has incorrect prefix: {bytes:?}")]
     IncorrectPrefix { bytes: Vec<u8> },
+    #[error(
+        "storage schema version {stored} is newer than the latest version supported \
+         by this version of Grandine ({supported}); upgrade Grandine to use this database"
+    )]
+    SchemaVersionTooNew { stored: u64, supported: u64 },
+    #[error(
+        "state diff for block {block_root:?} is based on state \
+         for block {base_block_root:?}, which is not stored"
+    )]
+    StateDiffBaseNotFound {
+        block_root: H256,
+        base_block_root: H256,
+    },
+    #[error(
+        "execution payload of block {block_root:?} has been pruned; \
+         only the blinded block is available"
+    )]
+    ExecutionPayloadPruned { block_root: H256 },
 }
 
 pub fn serialize(key: impl Display, value: impl SszWrite) -> Result<(String, Vec<u8>)> {
     Ok((key.to_string(), value.to_ssz()?))
 }
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        capella::containers::{
+            BeaconBlock as CapellaBeaconBlock, BeaconBlockBody as CapellaBeaconBlockBody,
+            ExecutionPayload as CapellaExecutionPayload,
+        },
+        combined::BeaconBlock,
+        nonstandard::Phase,
+        phase0::{
+            beacon_state::BeaconState as Phase0BeaconState,
+            containers::{
+                BeaconBlock as Phase0BeaconBlock, SignedBeaconBlock as Phase0SignedBeaconBlock,
+            },
+        },
+        preset::Minimal,
+    };
+
+    use ssz::SszHash as _;
+
+    use crate::{registry_journal::RegistryChangeKind, reorgs::ReorgCause};
+
+    use super::*;
+
+    #[test]
+    fn test_archival_states_are_routed_to_archive_database() -> Result<()> {
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            Database::in_memory(),
+            Some(Database::in_memory()),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        let archive_database = storage
+            .archive_database
+            .as_ref()
+            .expect("archive database is configured above");
+
+        let block_root = H256::repeat_byte(1);
+        let block_root_key = BlockRootBySlot(1).to_string();
+        let state_key = StateByBlockRoot(block_root).to_string();
+
+        storage.put_batch([
+            serialize(BlockRootBySlot(1), block_root)?,
+            serialize(StateByBlockRoot(block_root), 1_u64)?,
+        ])?;
+
+        assert!(storage.database.contains_key(&block_root_key)?);
+        assert!(!storage.database.contains_key(&state_key)?);
+        assert!(!archive_database.contains_key(&block_root_key)?);
+        assert!(archive_database.contains_key(&state_key)?);
+
+        assert_eq!(storage.block_root_by_slot(1)?, Some(block_root));
+        assert_eq!(storage.get(StateByBlockRoot(block_root))?, Some(1_u64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalized_blocks_are_routed_to_archive_database() -> Result<()> {
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            Database::in_memory(),
+            Some(Database::in_memory()),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        let archive_database = storage
+            .archive_database
+            .as_ref()
+            .expect("archive database is configured above");
+
+        let finalized_root = H256::repeat_byte(1);
+        let unfinalized_root = H256::repeat_byte(2);
+        let finalized_key = FinalizedBlockByRoot(finalized_root).to_string();
+        let unfinalized_key = UnfinalizedBlockByRoot(unfinalized_root).to_string();
+        let payload_key = ExecutionPayloadByRoot(finalized_root).to_string();
+
+        storage.put_batch([
+            serialize(FinalizedBlockByRoot(finalized_root), 1_u64)?,
+            serialize(UnfinalizedBlockByRoot(unfinalized_root), 2_u64)?,
+            serialize(ExecutionPayloadByRoot(finalized_root), 3_u64)?,
+        ])?;
+
+        assert!(archive_database.contains_key(&finalized_key)?);
+        assert!(archive_database.contains_key(&payload_key)?);
+        assert!(!archive_database.contains_key(&unfinalized_key)?);
+        assert!(storage.database.contains_key(&unfinalized_key)?);
+        assert!(!storage.database.contains_key(&finalized_key)?);
+
+        assert!(storage.contains_key(FinalizedBlockByRoot(finalized_root))?);
+        assert!(storage.contains_unfinalized_block(unfinalized_root)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unfinalized_copies_are_deleted_with_finalized_writes() -> Result<()> {
+        for archive_database in [None, Some(Database::in_memory())] {
+            let storage = Storage::<Minimal>::new(
+                Arc::new(Config::minimal()),
+                Database::in_memory(),
+                archive_database,
+                DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+                false,
+            );
+
+            let block_root = H256::repeat_byte(1);
+
+            storage.put_batch([serialize(UnfinalizedBlockByRoot(block_root), 1_u64)?])?;
+
+            storage.write_batch(
+                [serialize(FinalizedBlockByRoot(block_root), 1_u64)?],
+                [UnfinalizedBlockByRoot(block_root).to_string()],
+            )?;
+
+            assert!(!storage.contains_unfinalized_block(block_root)?);
+            assert!(storage.contains_key(FinalizedBlockByRoot(block_root))?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_changes_are_queried_by_epoch_range() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        let changes = [
+            (3, 7, RegistryChangeKind::Activated),
+            (4, 2, RegistryChangeKind::Exited),
+            (4, 2, RegistryChangeKind::Slashed),
+            (6, 1, RegistryChangeKind::Deposited),
+        ]
+        .map(|(epoch, validator_index, kind)| RegistryChange {
+            epoch,
+            validator_index,
+            kind,
+        });
+
+        storage.put_batch(
+            changes
+                .into_iter()
+                .map(|change| {
+                    serialize(
+                        RegistryChangeByEpoch(
+                            change.epoch,
+                            change.validator_index,
+                            change.kind.to_byte(),
+                        ),
+                        change,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )?;
+
+        let all = |_: &RegistryChange| true;
+        let slashings = |change: &RegistryChange| change.kind == RegistryChangeKind::Slashed;
+
+        assert_eq!(
+            storage.registry_changes(4, 5, usize::MAX, all)?,
+            changes[1..3]
+        );
+        assert_eq!(storage.registry_changes(0, 10, 2, all)?, changes[..2]);
+        assert_eq!(storage.registry_changes(7, 10, usize::MAX, all)?, []);
+
+        // The limit applies to changes that pass the filter.
+        assert_eq!(
+            storage.registry_changes(0, 10, 1, slashings)?,
+            changes[2..3]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archival_states_in_main_database_remain_readable() -> Result<()> {
+        let database = Database::in_memory();
+        let block_root = H256::repeat_byte(1);
+
+        database.put_batch([serialize(StateByBlockRoot(block_root), 1_u64)?])?;
+
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            database,
+            Some(Database::in_memory()),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        assert_eq!(storage.get(StateByBlockRoot(block_root))?, Some(1_u64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_archive_retains_newest_state_before_horizon() -> Result<()> {
+        let storage = storage_with_archive()?;
+        let report = storage.prune_archive(20, true, false, &PruningProgress::default())?;
+
+        assert_eq!(report.retained_state_slot, Some(16));
+        assert_eq!(report.pruned_state_slots, [8]);
+        assert_eq!(report.pruned_block_count, 15);
+
+        assert!(!storage.contains_state(block_root_at(8))?);
+        assert!(storage.contains_state(block_root_at(16))?);
+        assert!(storage.contains_state(block_root_at(24))?);
+
+        assert_eq!(storage.block_root_by_slot(15)?, None);
+        assert_eq!(storage.block_root_by_slot(16)?, Some(block_root_at(16)));
+        assert!(!storage.contains_finalized_block(block_root_at(15))?);
+        assert!(storage.contains_finalized_block(block_root_at(16))?);
+        assert_eq!(storage.slot_by_state_root(state_root_at(15))?, None);
+        assert_eq!(storage.slot_by_state_root(state_root_at(16))?, Some(16));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_archive_keeps_blocks_unless_requested() -> Result<()> {
+        let storage = storage_with_archive()?;
+        let report = storage.prune_archive(20, false, false, &PruningProgress::default())?;
+
+        assert_eq!(report.pruned_state_slots, [8]);
+        assert_eq!(report.pruned_block_count, 0);
+
+        assert!(!storage.contains_state(block_root_at(8))?);
+        assert_eq!(storage.block_root_by_slot(1)?, Some(block_root_at(1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_archive_dry_run_deletes_nothing() -> Result<()> {
+        let storage = storage_with_archive()?;
+        let report = storage.prune_archive(Slot::MAX, true, true, &PruningProgress::default())?;
+
+        assert_eq!(report.retained_state_slot, Some(24));
+        assert_eq!(report.pruned_state_slots, [8, 16]);
+        assert_eq!(report.pruned_block_count, 23);
+
+        assert!(storage.contains_state(block_root_at(8))?);
+        assert!(storage.contains_state(block_root_at(16))?);
+        assert_eq!(storage.block_root_by_slot(1)?, Some(block_root_at(1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_history_deletes_entries_before_retention_window() -> Result<()> {
+        let storage = storage_with_archive()?;
+        let report = storage.prune_history(3, 1, &PruningProgress::default())?;
+
+        assert_eq!(report.retained_state_slot, Some(8));
+        assert!(report.pruned_state_slots.is_empty());
+        assert_eq!(report.pruned_block_count, 7);
+
+        assert!(storage.contains_state(block_root_at(8))?);
+        assert_eq!(storage.block_root_by_slot(7)?, None);
+        assert_eq!(storage.block_root_by_slot(8)?, Some(block_root_at(8)));
+        assert_eq!(storage.slot_by_state_root(state_root_at(7))?, None);
+
+        let report = storage.prune_history(4, 1, &PruningProgress::default())?;
+
+        assert_eq!(report.retained_state_slot, Some(16));
+        assert_eq!(report.pruned_state_slots, [8]);
+        assert_eq!(report.pruned_block_count, 8);
+
+        assert!(!storage.contains_state(block_root_at(8))?);
+        assert_eq!(storage.block_root_by_slot(15)?, None);
+        assert_eq!(storage.block_root_by_slot(16)?, Some(block_root_at(16)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pruning_invalidates_read_cache() -> Result<()> {
+        let storage = storage_with_archive()?.with_read_cache_size(ByteSize::mib(1));
+
+        assert_eq!(
+            storage.finalized_block_by_root(block_root_at(7))?,
+            Some(block_at(7)),
+        );
+
+        storage.prune_history(3, 1, &PruningProgress::default())?;
+
+        assert_eq!(storage.finalized_block_by_root(block_root_at(7))?, None);
+        assert_eq!(
+            storage.finalized_block_by_root(block_root_at(8))?,
+            Some(block_at(8)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_execution_payloads_are_reassembled_into_blocks() -> Result<()> {
+        let storage = storage_with_split_execution_payloads()?;
+        let block_root = capella_block_at(3).message().hash_tree_root();
+
+        assert!(!storage.contains_key(FinalizedBlockByRoot(block_root))?);
+        assert!(storage.contains_finalized_block(block_root)?);
+        assert_eq!(storage.finalized_block_count()?, 16);
+
+        assert_eq!(
+            storage.finalized_block_by_root(block_root)?,
+            Some(capella_block_at(3)),
+        );
+
+        let blinded_block = storage
+            .finalized_blinded_block_by_root(block_root)?
+            .expect("block is stored as blinded block");
+
+        assert_eq!(blinded_block.message().hash_tree_root(), block_root);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_execution_payloads_keeps_blinded_blocks() -> Result<()> {
+        let storage = storage_with_split_execution_payloads()?;
+        let root_at = |slot| capella_block_at(slot).message().hash_tree_root();
+
+        storage.put_batch([serialize(StateByBlockRoot(root_at(8)), 8_u64)?])?;
+        assert_eq!(
+            storage.prune_execution_payloads(3, 1, &PruningProgress::default())?,
+            7,
+        );
+
+        let error = storage
+            .finalized_block_by_root(root_at(7))
+            .expect_err("execution payload of block in slot 7 should be pruned");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::ExecutionPayloadPruned { .. }),
+        ));
+        assert!(storage.contains_finalized_block(root_at(7))?);
+        assert!(storage
+            .finalized_blinded_block_by_root(root_at(7))?
+            .is_some());
+
+        assert_eq!(
+            storage.finalized_block_by_root(root_at(8))?,
+            Some(capella_block_at(8)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archival_states_between_snapshots_are_stored_as_diffs() -> Result<()> {
+        let storage = Storage {
+            archival_epoch_interval: nonzero!(1_u64),
+            ..storage_with_archive()?
+        }
+        .with_archival_snapshot_interval(Some(nonzero!(2_u64)));
+
+        let snapshot_state = state_at(16);
+        let state = state_at(24);
+
+        storage.put_batch([storage.archival_state_entry(block_root_at(16), &snapshot_state)?])?;
+        storage.put_batch([storage.archival_state_entry(block_root_at(24), &state)?])?;
+
+        let diff = storage
+            .state_diff(block_root_at(24))?
+            .expect("state in slot 24 is between snapshots");
+
+        assert_eq!(diff.base_block_root(), block_root_at(16));
+        assert_eq!(storage.state_diff(block_root_at(16))?, None);
+        assert_eq!(storage.state_by_block_root(block_root_at(24))?, Some(state));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_archive_retains_base_of_newest_state_diff() -> Result<()> {
+        let storage = storage_with_archive()?;
+        let diff = StateDiff::compute(block_root_at(16), &[], &[]);
+
+        storage.delete_state(block_root_at(24))?;
+        storage.put_batch([(
+            StateDiffByBlockRoot(block_root_at(24)).to_string(),
+            diff.to_bytes(),
+        )])?;
+
+        let report = storage.prune_archive(Slot::MAX, true, false, &PruningProgress::default())?;
+
+        assert_eq!(report.retained_state_slot, Some(16));
+        assert_eq!(report.pruned_state_slots, [8, 24]);
+        assert_eq!(report.pruned_block_count, 15);
+
+        assert!(storage.contains_state(block_root_at(16))?);
+        assert!(!storage.contains_state(block_root_at(24))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_state_replays_blocks_and_caches_result() -> Result<()> {
+        let config = Arc::new(Config::minimal());
+        let storage = Storage::<Minimal>::in_memory(config.clone_arc());
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let genesis_block = genesis::beacon_block(&genesis_state);
+        let genesis_block_root = genesis_block.message().hash_tree_root();
+
+        let mut batch = vec![
+            serialize(BlockRootBySlot(GENESIS_SLOT), genesis_block_root)?,
+            serialize(FinalizedBlockByRoot(genesis_block_root), &genesis_block)?,
+            serialize(StateByBlockRoot(genesis_block_root), genesis_state.as_ref())?,
+        ];
+
+        let mut state = genesis_state;
+
+        for slot in 1..=3 {
+            let (block, post_state) = factory::empty_block(&config, state, slot, H256::zero())?;
+            let block_root = block.message().hash_tree_root();
+
+            batch.push(serialize(BlockRootBySlot(slot), block_root)?);
+            batch.push(serialize(FinalizedBlockByRoot(block_root), block.as_ref())?);
+
+            state = post_state;
+        }
+
+        storage.put_batch(batch)?;
+
+        let mut expected_state = state.clone_arc();
+        combined::process_slots(&config, expected_state.make_mut(), 4)?;
+
+        let reconstructed_state = storage
+            .stored_state(4)?
+            .expect("state should be reconstructed from the genesis state");
+
+        assert_eq!(
+            reconstructed_state.hash_tree_root(),
+            expected_state.hash_tree_root(),
+        );
+
+        // The post-block state is cached, so the stored state is no longer needed.
+        storage.delete_state(genesis_block_root)?;
+
+        let cached_state = storage
+            .stored_state(3)?
+            .expect("state should be reconstructed from the cached state");
+
+        assert_eq!(cached_state.hash_tree_root(), state.hash_tree_root());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_old_blob_sidecars_deletes_sidecars_and_indices() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        for slot in [1, 20] {
+            let blob_id = BlobIdentifier {
+                block_root: block_root_at(slot),
+                index: 0,
+            };
+
+            storage.put_batch([
+                serialize(
+                    BlobSidecarByBlobId(blob_id.block_root, blob_id.index),
+                    BlobSidecar::<Minimal>::default(),
+                )?,
+                serialize(SlotBlobId(slot, blob_id.block_root, blob_id.index), blob_id)?,
+            ])?;
+        }
+
+        let progress = PruningProgress::default();
+
+        storage.prune_old_blob_sidecars(16, &progress)?;
+
+        let old_blob_id = BlobIdentifier {
+            block_root: block_root_at(1),
+            index: 0,
+        };
+
+        let new_blob_id = BlobIdentifier {
+            block_root: block_root_at(20),
+            index: 0,
+        };
+
+        assert!(storage.blob_sidecar_by_id(old_blob_id)?.is_none());
+        assert!(!storage.contains_key(SlotBlobId(1, old_blob_id.block_root, 0))?);
+        assert!(storage.blob_sidecar_by_id(new_blob_id)?.is_some());
+        assert!(storage.contains_key(SlotBlobId(20, new_blob_id.block_root, 0))?);
+
+        assert_eq!(progress.total(), 1);
+        assert_eq!(progress.processed(), 1);
+        assert!(progress.freed_bytes() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_orphaned_blocks_deletes_unfinalized_blocks_up_to_checkpoint() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+        let orphaned_block_root = H256::repeat_byte(0xaa);
+
+        storage.put_batch([
+            serialize(
+                BlockCheckpoint::<Minimal>::KEY,
+                BlockCheckpoint {
+                    block: block_at(16),
+                },
+            )?,
+            serialize(UnfinalizedBlockByRoot(orphaned_block_root), block_at(10))?,
+            serialize(BlockRootBySlot(10), orphaned_block_root)?,
+            serialize(FinalizedBlockByRoot(block_root_at(12)), block_at(12))?,
+            serialize(UnfinalizedBlockByRoot(block_root_at(12)), block_at(12))?,
+            serialize(BlockRootBySlot(12), block_root_at(12))?,
+            serialize(UnfinalizedBlockByRoot(block_root_at(20)), block_at(20))?,
+            serialize(BlockRootBySlot(20), block_root_at(20))?,
+        ])?;
+
+        let progress = PruningProgress::default();
+
+        storage.prune_orphaned_blocks(&progress)?;
+
+        assert!(!storage.contains_unfinalized_block(orphaned_block_root)?);
+        assert_eq!(storage.block_root_by_slot(10)?, None);
+
+        // Finalized blocks stay indexed when their redundant unfinalized copies are deleted.
+        assert!(!storage.contains_unfinalized_block(block_root_at(12))?);
+        assert!(storage.contains_finalized_block(block_root_at(12))?);
+        assert_eq!(storage.block_root_by_slot(12)?, Some(block_root_at(12)));
+
+        assert!(storage.contains_unfinalized_block(block_root_at(20))?);
+        assert_eq!(storage.block_root_by_slot(20)?, Some(block_root_at(20)));
+
+        assert_eq!(progress.total(), 2);
+        assert_eq!(progress.processed(), 2);
+        assert!(progress.freed_bytes() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_anchor_requires_empty_database() -> Result<()> {
+        let config = Arc::new(Config::minimal());
+        let storage = Storage::<Minimal>::in_memory(config.clone_arc());
+        let (genesis_state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let genesis_block = genesis::beacon_block(&genesis_state);
+        let genesis_block_root = genesis_block.message().hash_tree_root();
+
+        storage.import_anchor(&genesis_block, &genesis_state)?;
+
+        assert_eq!(
+            storage.block_root_by_slot(GENESIS_SLOT)?,
+            Some(genesis_block_root),
+        );
+
+        let OptionalStateStorage::Full((state, block, _)) = storage.load_latest_state()? else {
+            panic!("imported anchor should be loaded as the latest state");
+        };
+
+        assert_eq!(block.message().hash_tree_root(), genesis_block_root);
+        assert_eq!(state.hash_tree_root(), genesis_state.hash_tree_root());
+
+        storage
+            .import_anchor(&genesis_block, &genesis_state)
+            .expect_err("database already contains the anchor");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_records_schema_version_in_new_database() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        storage.migrate()?;
+
+        assert_eq!(
+            storage.get(SchemaVersion::KEY)?,
+            Some(Storage::<Minimal>::SCHEMA_VERSION),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_upgrades_unversioned_database_from_older_schema_version() -> Result<()> {
+        let storage =
+            storage_with_archive()?.with_previous_schema_version(Some(Version::new(0, 2, 1)));
+
+        storage.put_batch([serialize(LEGACY_STATE_CHECKPOINT_KEY, 0_u64)?])?;
+        storage
+            .database
+            .delete(SlotByStateRoot(state_root_at(4)).to_string())?;
+
+        storage.migrate()?;
+
+        assert!(!storage.contains_key(LEGACY_STATE_CHECKPOINT_KEY)?);
+        assert_eq!(storage.slot_by_state_root(state_root_at(4))?, Some(4));
+
+        assert_eq!(
+            storage.get(SchemaVersion::KEY)?,
+            Some(Storage::<Minimal>::SCHEMA_VERSION),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_only_runs_migrations_introduced_after_previous_schema_version() -> Result<()> {
+        let storage =
+            storage_with_archive()?.with_previous_schema_version(Some(Version::new(0, 2, 2)));
+
+        storage.put_batch([serialize(LEGACY_STATE_CHECKPOINT_KEY, 0_u64)?])?;
+        storage
+            .database
+            .delete(SlotByStateRoot(state_root_at(4)).to_string())?;
+
+        storage.migrate()?;
+
+        assert!(storage.contains_key(LEGACY_STATE_CHECKPOINT_KEY)?);
+        assert_eq!(storage.slot_by_state_root(state_root_at(4))?, Some(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_resumes_from_recorded_schema_version() -> Result<()> {
+        // `meta.json` may have been updated before an earlier upgrade was interrupted.
+        // The version recorded in the database takes precedence.
+        let storage = storage_with_archive()?;
+
+        storage.put_batch([
+            serialize(SchemaVersion::KEY, 1_u64)?,
+            serialize(LEGACY_STATE_CHECKPOINT_KEY, 0_u64)?,
+        ])?;
+
+        storage
+            .database
+            .delete(SlotByStateRoot(state_root_at(4)).to_string())?;
+
+        storage.migrate()?;
+
+        assert!(storage.contains_key(LEGACY_STATE_CHECKPOINT_KEY)?);
+        assert_eq!(storage.slot_by_state_root(state_root_at(4))?, Some(4));
+
+        assert_eq!(
+            storage.get(SchemaVersion::KEY)?,
+            Some(Storage::<Minimal>::SCHEMA_VERSION),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_moves_cold_data_to_archive_database() -> Result<()> {
+        let database = Database::in_memory();
+
+        database.put_batch([
+            serialize(FinalizedBlockByRoot(block_root_at(1)), block_at(1))?,
+            serialize(UnfinalizedBlockByRoot(block_root_at(2)), block_at(2))?,
+            serialize(StateByBlockRoot(block_root_at(1)), 1_u64)?,
+            serialize(BlockRootBySlot(1), block_root_at(1))?,
+        ])?;
+
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            database,
+            Some(Database::in_memory()),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        )
+        .with_previous_schema_version(Some(Version::new(0, 2, 5)));
+
+        storage.migrate()?;
+
+        let archive_database = storage
+            .archive_database
+            .as_ref()
+            .expect("archive database is configured above");
+
+        for key_string in [
+            FinalizedBlockByRoot(block_root_at(1)).to_string(),
+            StateByBlockRoot(block_root_at(1)).to_string(),
+        ] {
+            assert!(archive_database.contains_key(&key_string)?);
+            assert!(!storage.database.contains_key(&key_string)?);
+        }
+
+        for key_string in [
+            UnfinalizedBlockByRoot(block_root_at(2)).to_string(),
+            BlockRootBySlot(1).to_string(),
+        ] {
+            assert!(!archive_database.contains_key(&key_string)?);
+            assert!(storage.database.contains_key(&key_string)?);
+        }
+
+        assert_eq!(
+            storage.get(StateByBlockRoot(block_root_at(1)))?,
+            Some(1_u64)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_schema_version() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+        let newer_version = Storage::<Minimal>::SCHEMA_VERSION + 1;
+
+        storage.put_batch([serialize(SchemaVersion::KEY, newer_version)?])?;
+
+        let error = storage
+            .migrate()
+            .expect_err("migrating from a newer schema version should fail");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::SchemaVersionTooNew { stored, .. }) if *stored == newer_version,
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_old_reorgs_are_pruned_when_new_ones_are_appended() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+        let latest_slot = misc::compute_start_slot_at_epoch::<Minimal>(REORG_RETENTION_EPOCHS + 1);
+
+        for slot in [1, 8, latest_slot] {
+            storage.append_reorg(reorg_at(slot))?;
+        }
+
+        assert_eq!(
+            storage.reorgs(0, usize::MAX)?,
+            [reorg_at(8), reorg_at(latest_slot)],
+        );
+
+        Ok(())
+    }
+
+    fn reorg_at(slot: Slot) -> ReorgRecord {
+        ReorgRecord {
+            slot,
+            depth: 1,
+            old_head_slot: slot,
+            old_head_block: block_root_at(slot),
+            new_head_slot: slot,
+            new_head_block: state_root_at(slot),
+            cause: ReorgCause::AttestationWeight,
+        }
+    }
+
+    // Blocks in slots 1 to 24 with archival states in slots 8, 16 and 24.
+    // The state in slot 8 is in the main database as if it was stored by an older version.
+    fn storage_with_archive() -> Result<Storage<Minimal>> {
+        let database = Database::in_memory();
+
+        database.put_batch([serialize(StateByBlockRoot(block_root_at(8)), 8_u64)?])?;
+
+        let storage = Storage::new(
+            Arc::new(Config::minimal()),
+            database,
+            Some(Database::in_memory()),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        for slot in 1..=24 {
+            let block_root = block_root_at(slot);
+
+            storage.put_batch([
+                serialize(FinalizedBlockByRoot(block_root), block_at(slot))?,
+                serialize(BlockRootBySlot(slot), block_root)?,
+                serialize(SlotByStateRoot(state_root_at(slot)), slot)?,
+            ])?;
+        }
+
+        storage.put_batch([
+            serialize(StateByBlockRoot(block_root_at(16)), 16_u64)?,
+            serialize(StateByBlockRoot(block_root_at(24)), 24_u64)?,
+        ])?;
+
+        Ok(storage)
+    }
+
+    // Capella blocks in slots 1 to 16 stored as blinded blocks and execution payloads.
+    fn storage_with_split_execution_payloads() -> Result<Storage<Minimal>> {
+        let config = Config::minimal().start_and_stay_in(Phase::Capella);
+        let storage = Storage::in_memory(Arc::new(config)).with_split_execution_payloads(true);
+
+        for slot in 1..=16 {
+            let block = capella_block_at(slot);
+            let block_root = block.message().hash_tree_root();
+
+            let mut batch = storage.finalized_block_entries(block_root, &block)?;
+            batch.push(serialize(BlockRootBySlot(slot), block_root)?);
+            storage.put_batch(batch)?;
+        }
+
+        Ok(storage)
+    }
+
+    fn capella_block_at(slot: Slot) -> Arc<SignedBeaconBlock<Minimal>> {
+        let block = CapellaBeaconBlock {
+            slot,
+            body: CapellaBeaconBlockBody {
+                execution_payload: CapellaExecutionPayload {
+                    block_number: slot,
+                    ..CapellaExecutionPayload::default()
+                },
+                ..CapellaBeaconBlockBody::default()
+            },
+            ..CapellaBeaconBlock::default()
+        };
+
+        Arc::new(BeaconBlock::from(block).into())
+    }
+
+    fn state_at(slot: Slot) -> Arc<BeaconState<Minimal>> {
+        Arc::new(
+            Phase0BeaconState {
+                slot,
+                ..Phase0BeaconState::default()
+            }
+            .into(),
+        )
+    }
+
+    fn block_at(slot: Slot) -> Arc<SignedBeaconBlock<Minimal>> {
+        Arc::new(
+            Phase0SignedBeaconBlock {
+                message: Phase0BeaconBlock {
+                    slot,
+                    state_root: state_root_at(slot),
+                    ..Phase0BeaconBlock::default()
+                },
+                ..Phase0SignedBeaconBlock::default()
+            }
+            .into(),
+        )
+    }
+
+    fn block_root_at(slot: Slot) -> H256 {
+        H256::from_low_u64_be(slot)
+    }
+
+    fn state_root_at(slot: Slot) -> H256 {
+        H256::from_low_u64_le(slot)
+    }
+}
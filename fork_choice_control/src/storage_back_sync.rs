@@ -6,23 +6,49 @@ use features::Feature;
 use genesis::GenesisProvider;
 use helper_functions::misc;
 use log::info;
-use ssz::SszHash as _;
+use ssz::{Ssz, SszHash as _};
 use std_ext::ArcExt as _;
 use transition_functions::combined;
 use types::{
     combined::SignedBeaconBlock,
-    phase0::{consts::GENESIS_SLOT, primitives::Slot},
+    phase0::{
+        consts::GENESIS_SLOT,
+        primitives::{Slot, H256},
+    },
     preset::Preset,
     traits::SignedBeaconBlock as _,
 };
 
 use crate::{
-    storage::{
-        serialize, BlockRootBySlot, Error, FinalizedBlockByRoot, SlotByStateRoot, StateByBlockRoot,
-    },
+    storage::{serialize, BlockRootBySlot, Error, SlotByStateRoot, StateByBlockRoot},
     Storage,
 };
 
+/// Progress of back sync stored in the same batch as the blocks it refers to.
+///
+/// Back sync resumes from `verified_up_to_root` after a restart instead of requesting blocks that
+/// are already stored.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct BackSyncStatus {
+    /// Slot of the earliest block stored by back sync so far.
+    pub earliest_available_slot: Slot,
+    /// Slot of the block back sync stops at.
+    pub target_slot: Slot,
+    /// Root of the block in `earliest_available_slot`.
+    /// It and all blocks after it have been verified to be ancestors of the anchor block.
+    pub verified_up_to_root: H256,
+}
+
+impl BackSyncStatus {
+    pub(crate) const KEY: &'static str = "cbacksync";
+
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.earliest_available_slot <= self.target_slot
+    }
+}
+
 impl<P: Preset> Storage<P> {
     pub(crate) fn archive_back_sync_states(
         &self,
@@ -72,12 +98,12 @@ impl<P: Preset> Storage<P> {
                     info!("archiving back sync state in slot {slot}");
 
                     let block_root = block.message().hash_tree_root();
-                    batch.push(serialize(StateByBlockRoot(block_root), &state)?);
+                    batch.push(self.archival_state_entry(block_root, &state)?);
                 }
             }
         }
 
-        self.database.put_batch(batch)?;
+        self.put_batch(batch)?;
 
         info!(
             "back sync state archival completed (start_slot: {start_slot}, end_slot: {end_slot})",
@@ -86,21 +112,23 @@ impl<P: Preset> Storage<P> {
         Ok(())
     }
 
+    // Blocks and progress are written atomically so that neither can get ahead of the other.
     pub(crate) fn store_back_sync_blocks(
         &self,
         blocks: impl IntoIterator<Item = Arc<SignedBeaconBlock<P>>>,
+        status: BackSyncStatus,
     ) -> Result<()> {
-        let mut batch = vec![];
+        let mut batch = vec![serialize(BackSyncStatus::KEY, status)?];
 
         for block in blocks {
             let slot = block.message().slot();
             let block_root = block.message().hash_tree_root();
 
             batch.push(serialize(BlockRootBySlot(slot), block_root)?);
-            batch.push(serialize(FinalizedBlockByRoot(block_root), block)?);
+            batch.extend(self.finalized_block_entries(block_root, &block)?);
         }
 
-        self.database.put_batch(batch)
+        self.put_batch(batch)
     }
 }
 
@@ -111,7 +139,9 @@ mod tests {
     use anyhow::anyhow;
     use database::Database;
     use eth2_cache_utils::mainnet;
+    use factory::ChainBuilder;
     use itertools::{EitherOrBoth, Itertools as _};
+    use types::{config::Config, preset::Minimal};
 
     use super::*;
 
@@ -138,7 +168,17 @@ mod tests {
         let (block_96_root, state_96_root) = roots(96)?;
         let (block_128_root, state_128_root) = roots(128)?;
 
-        storage.store_back_sync_blocks(blocks.iter().cloned())?;
+        let status = BackSyncStatus {
+            earliest_available_slot: 1,
+            target_slot: GENESIS_SLOT,
+            verified_up_to_root: block_1_root,
+        };
+
+        assert_eq!(storage.back_sync_status()?, None);
+
+        storage.store_back_sync_blocks(blocks.iter().cloned(), status)?;
+
+        assert_eq!(storage.back_sync_status()?, Some(status));
 
         let empty_slots = (GENESIS_SLOT..=128)
             .merge_join_by(blocks, |slot, block| slot.cmp(&block.message().slot()))
@@ -191,10 +231,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_archive_back_sync_states_with_skipped_slots() -> Result<()> {
+        let builder = ChainBuilder::new(Arc::new(Config::minimal())).with_participation(50);
+        let genesis = builder.genesis::<Minimal>()?;
+        let blocks = builder.extend(&genesis, [1, 2, 5, 8, 9, 16])?;
+        let storage = build_test_storage::<Minimal>();
+        let earliest = &blocks[0];
+
+        let status = BackSyncStatus {
+            earliest_available_slot: earliest.slot(),
+            target_slot: GENESIS_SLOT,
+            verified_up_to_root: earliest.root(),
+        };
+
+        storage
+            .store_back_sync_blocks(blocks.iter().map(|built| built.block.clone_arc()), status)?;
+        storage.archive_back_sync_states(0, 16, GenesisProvider::Custom(genesis.state))?;
+
+        for empty_slot in [3, 4, 6, 7, 10, 15] {
+            assert_eq!(storage.block_root_by_slot(empty_slot)?, None);
+        }
+
+        for built in &blocks {
+            let state_root = built.block.message().state_root();
+
+            assert_eq!(
+                storage.block_root_by_slot(built.slot())?,
+                Some(built.root())
+            );
+            assert_eq!(storage.slot_by_state_root(state_root)?, Some(built.slot()));
+
+            assert_eq!(
+                storage
+                    .stored_state_by_state_root(state_root)?
+                    .map(|state| state.hash_tree_root()),
+                Some(state_root),
+            );
+        }
+
+        Ok(())
+    }
+
     fn build_test_storage<P: Preset>() -> Storage<P> {
         Storage::new(
             Arc::new(P::default_config()),
             Database::in_memory(),
+            None,
             NonZeroU64::MIN,
             false,
         )
@@ -0,0 +1,127 @@
+use core::ops::RangeInclusive;
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use types::{phase0::primitives::Slot, preset::Preset};
+
+use crate::{storage_verification::Key, Storage};
+
+#[derive(Default, Debug)]
+pub struct StorageInspectionReport {
+    pub entries: BTreeMap<&'static str, EntryStatistics>,
+}
+
+impl StorageInspectionReport {
+    #[must_use]
+    pub fn total_bytes(&self) -> u64 {
+        self.entries
+            .values()
+            .map(EntryStatistics::total_bytes)
+            .sum()
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct EntryStatistics {
+    pub count: usize,
+    pub key_bytes: u64,
+    /// Size of values as stored, i.e., after compression.
+    pub value_bytes: u64,
+    /// Earliest and latest slots of entries with slots in their keys.
+    pub slots: Option<RangeInclusive<Slot>>,
+}
+
+impl EntryStatistics {
+    #[must_use]
+    pub const fn total_bytes(&self) -> u64 {
+        self.key_bytes + self.value_bytes
+    }
+
+    fn record(&mut self, key_length: usize, value_length: usize, slot: Option<Slot>) {
+        self.count += 1;
+        self.key_bytes += key_length as u64;
+        self.value_bytes += value_length as u64;
+
+        if let Some(slot) = slot {
+            self.slots = Some(match self.slots.take() {
+                Some(slots) => slot.min(*slots.start())..=slot.max(*slots.end()),
+                None => slot..=slot,
+            });
+        }
+    }
+}
+
+impl<P: Preset> Storage<P> {
+    /// Counts entries and the space they take up, grouped by key prefix.
+    ///
+    /// Values are not decompressed or decoded, so this is much faster than [`Self::verify`].
+    pub(crate) fn inspect(&self) -> Result<StorageInspectionReport> {
+        let mut report = StorageInspectionReport::default();
+
+        for database in self.databases() {
+            for result in database.stored_sizes()? {
+                let (key_bytes, value_length) = result?;
+                let key_string = String::from_utf8_lossy(&key_bytes);
+
+                // Malformed keys are reported by `Storage::verify`.
+                let key = Key::parse::<P>(&key_string).unwrap_or(Key::Unknown);
+
+                report.entries.entry(key.kind()).or_default().record(
+                    key_bytes.len(),
+                    value_length,
+                    key.slot(),
+                );
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use database::Database;
+    use types::{config::Config, preset::Minimal};
+
+    use crate::storage::{BlockRootBySlot, SlotBlobId, DEFAULT_ARCHIVAL_EPOCH_INTERVAL};
+
+    use super::*;
+
+    #[test]
+    fn inspect_groups_entries_by_prefix() -> Result<()> {
+        let database = Database::in_memory();
+
+        database.put_batch([
+            (BlockRootBySlot(3).to_string(), [0; 32]),
+            (BlockRootBySlot(7).to_string(), [0; 32]),
+            (SlotBlobId(5, Default::default(), 0).to_string(), [0; 32]),
+            ("unexpected".to_owned(), [0; 32]),
+        ])?;
+
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            database,
+            None,
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        let report = storage.inspect()?;
+        let block_roots = &report.entries["block roots by slot"];
+        let blob_ids = &report.entries["blob sidecar slot index"];
+        let unknown = &report.entries["unknown"];
+
+        assert_eq!(block_roots.count, 2);
+        assert_eq!(block_roots.key_bytes, 42);
+        assert_eq!(block_roots.slots, Some(3..=7));
+        assert_eq!(blob_ids.count, 1);
+        assert_eq!(blob_ids.slots, Some(5..=5));
+        assert_eq!(unknown.count, 1);
+        assert_eq!(unknown.slots, None);
+        assert_eq!(report.entries.len(), 3);
+
+        Ok(())
+    }
+}
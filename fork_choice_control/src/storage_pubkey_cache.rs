@@ -0,0 +1,237 @@
+//! Cache of decompressed validator public keys.
+//!
+//! Decompressing every public key in a large validator registry takes a long time. Without this
+//! cache it would be done again after every restart before the first duties can be performed.
+//! Public keys of validators never change and indices of finalized validators are never reused,
+//! so keys are appended to the cache in chunks as finalized states grow the registry.
+//!
+//! Keys are stored uncompressed. Loading them only requires checking that they compress to the
+//! bytes in the registry, which guards against corrupted or mismatched entries.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use bls::PublicKey;
+use derive_more::Display;
+use itertools::Itertools as _;
+use log::{debug, info};
+use rayon::{
+    iter::{IndexedParallelIterator as _, IntoParallelRefIterator as _, ParallelIterator as _},
+    slice::ParallelSlice as _,
+};
+use ssz::SszReadDefault as _;
+use types::{combined::BeaconState, preset::Preset, traits::BeaconState as _};
+
+use crate::{storage::serialize, Storage};
+
+// Chunks of 8192 keys take up 768 KiB each.
+// Only the last chunk has to be rewritten when validators are added to the registry.
+const CHUNK_SIZE: usize = 8192;
+
+// Entries are added in the same order as validators, so a chunk may be written partially filled.
+#[derive(Display)]
+#[display(fmt = "{}{_0:020}", Self::PREFIX)]
+pub struct PublicKeyChunkByIndex(pub u64);
+
+impl PublicKeyChunkByIndex {
+    pub(crate) const PREFIX: &'static str = "k";
+}
+
+pub(crate) struct PublicKeyCacheLength;
+
+impl PublicKeyCacheLength {
+    pub(crate) const KEY: &'static str = "cpubkeys";
+}
+
+impl<P: Preset> Storage<P> {
+    /// Returns entries that extend the cache to cover all validators in `state`.
+    ///
+    /// `state` must be finalized. Public keys that are not already decompressed are decompressed
+    /// in parallel, which only takes long the first time this is done for a registry.
+    pub(crate) fn public_key_cache_entries(
+        &self,
+        state: &BeaconState<P>,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let cached_length = self.public_key_cache_length()?;
+        let validator_count = state.validators().len_usize();
+
+        if cached_length >= validator_count {
+            return Ok(vec![]);
+        }
+
+        let first_chunk_index = cached_length / CHUNK_SIZE;
+        let first_chunk_start = first_chunk_index * CHUNK_SIZE;
+
+        let public_keys = state
+            .validators()
+            .into_iter()
+            .skip(first_chunk_start)
+            .map(|validator| &validator.pubkey)
+            .collect_vec();
+
+        let mut batch = vec![];
+
+        for (chunk_index, chunk) in
+            (u64::try_from(first_chunk_index)?..).zip(public_keys.chunks(CHUNK_SIZE))
+        {
+            let chunk_bytes = chunk
+                .par_iter()
+                .map(|public_key| match public_key.decompress() {
+                    Ok(public_key) => public_key.to_uncompressed(),
+                    // Invalid keys cannot end up in a registry,
+                    // but an entry is needed to keep the rest of the chunk aligned.
+                    Err(_) => [0; PublicKey::UNCOMPRESSED_SIZE],
+                })
+                .flatten_iter()
+                .collect();
+
+            batch.push((PublicKeyChunkByIndex(chunk_index).to_string(), chunk_bytes));
+        }
+
+        debug!(
+            "caching public keys of validators {cached_length}..{validator_count} \
+             in {} chunks",
+            batch.len(),
+        );
+
+        batch.push(serialize(
+            PublicKeyCacheLength::KEY,
+            u64::try_from(validator_count)?,
+        )?);
+
+        Ok(batch)
+    }
+
+    /// Decompresses public keys in `state` using the cache.
+    ///
+    /// Keys missing from the cache or not matching the ones in `state` are left as they are.
+    /// They will be decompressed normally when first used.
+    pub(crate) fn load_public_keys_from_cache(&self, state: &BeaconState<P>) -> Result<()> {
+        let started_at = Instant::now();
+        let cached_length = self.public_key_cache_length()?;
+
+        if cached_length == 0 {
+            return Ok(());
+        }
+
+        let public_keys = state
+            .validators()
+            .into_iter()
+            .take(cached_length)
+            .map(|validator| &validator.pubkey)
+            .collect_vec();
+
+        let mut loaded = 0;
+
+        for (chunk_index, chunk) in (0..).zip(public_keys.chunks(CHUNK_SIZE)) {
+            let Some(chunk_bytes) =
+                self.get_bytes(PublicKeyChunkByIndex(chunk_index).to_string())?
+            else {
+                break;
+            };
+
+            loaded += chunk
+                .par_iter()
+                .zip(chunk_bytes.par_chunks_exact(PublicKey::UNCOMPRESSED_SIZE))
+                .filter(|(public_key, uncompressed)| {
+                    public_key
+                        .decompress_from_uncompressed(uncompressed)
+                        .is_ok()
+                })
+                .count();
+        }
+
+        info!(
+            "loaded {loaded} of {} validator public keys from cache in {:?}",
+            public_keys.len(),
+            started_at.elapsed(),
+        );
+
+        Ok(())
+    }
+
+    fn public_key_cache_length(&self) -> Result<usize> {
+        let Some(value_bytes) = self.get_bytes(PublicKeyCacheLength::KEY.to_owned())? else {
+            return Ok(0);
+        };
+
+        let length = u64::from_ssz_default(value_bytes)?;
+
+        Ok(usize::try_from(length)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bls::{PublicKeyBytes, SecretKey, SecretKeyBytes};
+    use ssz::PersistentList;
+    use std_ext::CopyExt as _;
+    use tap::{Conv as _, TryConv as _};
+    use types::{
+        config::Config,
+        phase0::{beacon_state::BeaconState as Phase0BeaconState, containers::Validator},
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn public_keys_are_loaded_from_cache() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        let public_key = b"????????????????????????????????"
+            .copy()
+            .conv::<SecretKeyBytes>()
+            .try_conv::<SecretKey>()?
+            .to_public_key();
+
+        let state = |validator_count| -> Result<_> {
+            let validator = Validator {
+                pubkey: PublicKeyBytes::from(public_key).into(),
+                ..Validator::default()
+            };
+
+            let mut validators = PersistentList::default();
+
+            for _ in 0..validator_count {
+                validators.push(validator.clone())?;
+            }
+
+            Ok(BeaconState::from(Phase0BeaconState::<Minimal> {
+                validators,
+                ..Phase0BeaconState::default()
+            }))
+        };
+
+        storage.put_batch(storage.public_key_cache_entries(&state(CHUNK_SIZE + 1)?)?)?;
+
+        assert_eq!(storage.public_key_cache_length()?, CHUNK_SIZE + 1);
+        assert!(storage
+            .public_key_cache_entries(&state(CHUNK_SIZE)?)?
+            .is_empty());
+
+        // Only the last chunk and the length should be rewritten.
+        assert_eq!(
+            storage
+                .public_key_cache_entries(&state(CHUNK_SIZE + 2)?)?
+                .len(),
+            2
+        );
+
+        let restarted_state = state(CHUNK_SIZE + 2)?;
+
+        storage.load_public_keys_from_cache(&restarted_state)?;
+
+        let (cached, uncached) = restarted_state
+            .validators()
+            .into_iter()
+            .partition::<Vec<_>, _>(|validator| validator.pubkey.is_decompressed());
+
+        assert_eq!(cached.len(), CHUNK_SIZE + 1);
+        assert_eq!(uncached.len(), 1);
+
+        Ok(())
+    }
+}
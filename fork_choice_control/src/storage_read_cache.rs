@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use bytesize::ByteSize;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std_ext::ArcExt as _;
+use types::{
+    combined::{BeaconState, SignedBeaconBlock},
+    phase0::primitives::H256,
+    preset::Preset,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    FinalizedBlock(H256),
+    State(H256),
+}
+
+enum Value<P: Preset> {
+    Block(Arc<SignedBeaconBlock<P>>),
+    State(Arc<BeaconState<P>>),
+}
+
+struct Entry<P: Preset> {
+    value: Value<P>,
+    size: u64,
+}
+
+struct Entries<P: Preset> {
+    lru: LruCache<Key, Entry<P>>,
+    size: u64,
+    generation: u64,
+}
+
+/// Blocks and states recently deserialized by [`Storage`], keyed by block root.
+///
+/// Entries are weighted by the size of their SSZ encoding. Deserialized states do not share data
+/// with states already in memory, and their in-memory representation with cached hashes is larger
+/// than their encoding, so the memory actually used can exceed the capacity.
+/// Values larger than the capacity are not cached at all.
+///
+/// Values must be inserted with the [generation](Self::generation) obtained before reading them
+/// from the database. Values read before the last [`Self::clear`] may have been deleted since,
+/// so they are not inserted.
+///
+/// [`Storage`]: crate::Storage
+pub struct StorageReadCache<P: Preset> {
+    capacity: ByteSize,
+    entries: Mutex<Entries<P>>,
+}
+
+impl<P: Preset> StorageReadCache<P> {
+    pub fn new(capacity: ByteSize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries {
+                lru: LruCache::unbounded(),
+                size: 0,
+                generation: 0,
+            }),
+        }
+    }
+
+    pub fn finalized_block(&self, block_root: H256) -> Option<Arc<SignedBeaconBlock<P>>> {
+        match self.get(Key::FinalizedBlock(block_root))? {
+            Value::Block(block) => Some(block),
+            Value::State(_) => None,
+        }
+    }
+
+    pub fn state(&self, block_root: H256) -> Option<Arc<BeaconState<P>>> {
+        match self.get(Key::State(block_root))? {
+            Value::State(state) => Some(state),
+            Value::Block(_) => None,
+        }
+    }
+
+    pub fn insert_finalized_block(
+        &self,
+        generation: u64,
+        block_root: H256,
+        block: Arc<SignedBeaconBlock<P>>,
+        size: usize,
+    ) {
+        self.insert(
+            generation,
+            Key::FinalizedBlock(block_root),
+            Value::Block(block),
+            size,
+        );
+    }
+
+    pub fn insert_state(
+        &self,
+        generation: u64,
+        block_root: H256,
+        state: Arc<BeaconState<P>>,
+        size: usize,
+    ) {
+        self.insert(
+            generation,
+            Key::State(block_root),
+            Value::State(state),
+            size,
+        );
+    }
+
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.entries.lock().generation
+    }
+
+    // Pruning deletes entries in bulk and rarely, so it is simpler to drop everything than to
+    // work out which cached values the deleted keys correspond to.
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock();
+        entries.lru.clear();
+        entries.size = 0;
+        entries.generation += 1;
+    }
+
+    #[must_use]
+    pub fn size(&self) -> ByteSize {
+        ByteSize(self.entries.lock().size)
+    }
+
+    fn get(&self, key: Key) -> Option<Value<P>> {
+        let mut entries = self.entries.lock();
+
+        let value = match &entries.lru.get(&key)?.value {
+            Value::Block(block) => Value::Block(block.clone_arc()),
+            Value::State(state) => Value::State(state.clone_arc()),
+        };
+
+        Some(value)
+    }
+
+    fn insert(&self, generation: u64, key: Key, value: Value<P>, size: usize) {
+        let size = size as u64;
+
+        if size > self.capacity.as_u64() {
+            return;
+        }
+
+        let mut entries = self.entries.lock();
+
+        if entries.generation != generation {
+            return;
+        }
+
+        if let Some(replaced) = entries.lru.put(key, Entry { value, size }) {
+            entries.size -= replaced.size;
+        }
+
+        entries.size += size;
+
+        while entries.size > self.capacity.as_u64() {
+            let Some((_, evicted)) = entries.lru.pop_lru() else {
+                break;
+            };
+
+            entries.size -= evicted.size;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        phase0::{
+            beacon_state::BeaconState as Phase0BeaconState,
+            containers::SignedBeaconBlock as Phase0SignedBeaconBlock,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    fn block() -> Arc<SignedBeaconBlock<Minimal>> {
+        Arc::new(Phase0SignedBeaconBlock::default().into())
+    }
+
+    fn state() -> Arc<BeaconState<Minimal>> {
+        Arc::new(Phase0BeaconState::default().into())
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_when_over_capacity() {
+        let cache = StorageReadCache::new(ByteSize(100));
+
+        cache.insert_finalized_block(0, H256::repeat_byte(1), block(), 40);
+        cache.insert_state(0, H256::repeat_byte(2), state(), 40);
+
+        assert!(cache.finalized_block(H256::repeat_byte(1)).is_some());
+
+        cache.insert_finalized_block(0, H256::repeat_byte(3), block(), 40);
+
+        assert!(cache.finalized_block(H256::repeat_byte(1)).is_some());
+        assert!(cache.state(H256::repeat_byte(2)).is_none());
+        assert!(cache.finalized_block(H256::repeat_byte(3)).is_some());
+        assert_eq!(cache.size(), ByteSize(80));
+    }
+
+    #[test]
+    fn values_larger_than_capacity_are_not_cached() {
+        let cache = StorageReadCache::new(ByteSize(100));
+
+        cache.insert_state(0, H256::repeat_byte(1), state(), 40);
+        cache.insert_state(0, H256::repeat_byte(2), state(), 101);
+
+        assert!(cache.state(H256::repeat_byte(1)).is_some());
+        assert!(cache.state(H256::repeat_byte(2)).is_none());
+        assert_eq!(cache.size(), ByteSize(40));
+    }
+
+    #[test]
+    fn blocks_and_states_are_cached_separately() {
+        let cache = StorageReadCache::new(ByteSize(100));
+
+        cache.insert_state(0, H256::zero(), state(), 10);
+
+        assert!(cache.finalized_block(H256::zero()).is_none());
+        assert!(cache.state(H256::zero()).is_some());
+
+        cache.clear();
+
+        assert!(cache.state(H256::zero()).is_none());
+        assert_eq!(cache.size(), ByteSize(0));
+    }
+
+    #[test]
+    fn values_read_before_clearing_are_not_cached() {
+        let cache = StorageReadCache::new(ByteSize(100));
+        let generation = cache.generation();
+
+        cache.clear();
+        cache.insert_state(generation, H256::zero(), state(), 10);
+
+        assert!(cache.state(H256::zero()).is_none());
+
+        cache.insert_state(cache.generation(), H256::zero(), state(), 10);
+
+        assert!(cache.state(H256::zero()).is_some());
+    }
+}
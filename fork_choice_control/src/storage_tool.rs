@@ -1,23 +1,73 @@
-use std::path::{Path, PathBuf};
+use std::{
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
+use fs_err::File;
 use genesis::GenesisProvider;
+use helper_functions::{accessors, fork, misc};
 use log::info;
 use ssz::{SszHash as _, SszRead, SszWrite as _};
 use std_ext::ArcExt as _;
 use thiserror::Error;
 use transition_functions::combined;
+use typenum::Unsigned as _;
 use types::{
-    combined::BeaconState, config::Config, phase0::primitives::Slot, preset::Preset,
-    traits::BeaconState as _,
+    combined::{BeaconState, SignedBeaconBlock},
+    config::Config,
+    nonstandard::Phase,
+    phase0::primitives::{Epoch, Slot, H256},
+    preset::Preset,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
-use crate::Storage;
+use crate::{
+    chain_data::{ChainDataField, ChainDataFormat, ChainDataWriter},
+    checkpoint_sync::{self, FinalizedCheckpoint},
+    era_store,
+    pruning_progress::PruningProgress,
+    storage::ArchivePruningReport,
+    storage_inspection::StorageInspectionReport,
+    storage_verification::StorageVerificationReport,
+    Storage,
+};
 
 #[derive(Debug, Error)]
 enum Error {
     #[error("state file is missing for slot: {slot}")]
     StateFileMissing { slot: Slot },
+    #[error("no finalized state found in storage")]
+    FinalizedStateMissing,
+    #[error("slot range to export is empty (from: {from_slot}, to: {to_slot})")]
+    EmptySlotRange { from_slot: Slot, to_slot: Slot },
+    #[error("anchor slot {slot} is not at the start of an epoch")]
+    AnchorNotAtEpochStart { slot: Slot },
+    #[error("no block found in storage at slot {slot}")]
+    AnchorBlockMissing { slot: Slot },
+    #[error("no state found in storage for slot {slot}")]
+    AnchorStateMissing { slot: Slot },
+    #[error("no era ends in epochs from {start_epoch} to {end_epoch}")]
+    EmptyEraRange {
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    },
+    #[error("no state found in storage for end of era {era} at slot {slot}")]
+    EraStateMissing { era: u64, slot: Slot },
+    #[error("block {block_root:?} in slot {slot} is missing from storage")]
+    EraBlockMissing { slot: Slot, block_root: H256 },
+    #[error("{phase} state cannot be upgraded to {target}")]
+    UnsupportedUpgrade { phase: Phase, target: Phase },
+    #[error(
+        "refusing to prune data within the weak subjectivity period \
+         (epochs to retain: {retain_epochs}, weak subjectivity period: {weak_subjectivity_period})"
+    )]
+    WithinWeakSubjectivityPeriod {
+        retain_epochs: u64,
+        weak_subjectivity_period: u64,
+    },
 }
 
 pub fn export_state_and_blocks<P: Preset>(
@@ -82,6 +132,254 @@ pub fn export_state_and_blocks<P: Preset>(
     Ok(())
 }
 
+/// Writes an era file for every era that ends in an epoch from `start_epoch` to `end_epoch`.
+///
+/// Blocks are looked up by the roots in the state at the end of each era,
+/// so only canonical blocks are written even if the database contains others.
+///
+/// Returns the number of files written.
+pub fn export_era<P: Preset>(
+    storage: &Storage<P>,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+    output_dir: &Path,
+) -> Result<u64> {
+    let slots_per_historical_root = P::SlotsPerHistoricalRoot::U64;
+    let start_slot = misc::compute_start_slot_at_epoch::<P>(start_epoch);
+    let end_slot = misc::compute_start_slot_at_epoch::<P>(end_epoch);
+    let first_era = start_slot.div_ceil(slots_per_historical_root);
+    let last_era = end_slot / slots_per_historical_root;
+
+    ensure!(
+        first_era <= last_era,
+        Error::EmptyEraRange {
+            start_epoch,
+            end_epoch,
+        },
+    );
+
+    fs_err::create_dir_all(output_dir)?;
+
+    for era in first_era..=last_era {
+        let state_slot = era * slots_per_historical_root;
+
+        let state = storage
+            .stored_state(state_slot)?
+            .ok_or(Error::EraStateMissing {
+                era,
+                slot: state_slot,
+            })?;
+
+        let path = output_dir.join(era_store::file_name(storage.config(), &state)?);
+        let writer = BufWriter::new(File::create(&path)?);
+
+        era_store::write(writer, &state, era_blocks(storage, &state))?;
+
+        info!("era {era} exported to {path:?}");
+    }
+
+    Ok(last_era - first_era + 1)
+}
+
+// `state.block_roots` contains the root of the latest block at or before each slot in the era.
+// A block belongs to the era if its root appears for the first time at its own slot.
+fn era_blocks<'storage, P: Preset>(
+    storage: &'storage Storage<P>,
+    state: &'storage BeaconState<P>,
+) -> impl Iterator<Item = Result<Arc<SignedBeaconBlock<P>>>> + 'storage {
+    let state_slot = state.slot();
+    let starting_slot = state_slot.saturating_sub(P::SlotsPerHistoricalRoot::U64);
+    let mut previous_block_root = None;
+
+    (starting_slot..state_slot).filter_map(move |slot| {
+        let block_root = *state.block_roots().mod_index(slot);
+
+        if previous_block_root.replace(block_root) == Some(block_root) {
+            return None;
+        }
+
+        match storage.finalized_block_by_root(block_root) {
+            Ok(Some(block)) if block.message().slot() == slot => Some(Ok(block)),
+            // The block is from an earlier era and the first slots of this one are empty.
+            Ok(Some(_)) => None,
+            Ok(None) => Some(Err(Error::EraBlockMissing { slot, block_root }.into())),
+            Err(error) => Some(Err(error)),
+        }
+    })
+}
+
+const ANCHOR_BLOCK_FILE_NAME: &str = "anchor_block.ssz";
+const ANCHOR_STATE_FILE_NAME: &str = "anchor_state.ssz";
+
+/// Writes the block and post-block state at `slot` to SSZ files in `output_dir` so that
+/// [`import_anchor`] can bootstrap another database from them.
+/// `slot` defaults to the slot of the latest state checkpoint.
+///
+/// Returns the slot of the exported anchor.
+pub fn export_anchor<P: Preset>(
+    storage: &Storage<P>,
+    slot: Option<Slot>,
+    output_dir: &Path,
+) -> Result<Slot> {
+    let (block, state) = match slot {
+        Some(slot) => {
+            ensure!(
+                misc::is_epoch_start::<P>(slot),
+                Error::AnchorNotAtEpochStart { slot },
+            );
+
+            let (block, _) = storage
+                .block_by_slot(slot)?
+                .ok_or(Error::AnchorBlockMissing { slot })?;
+
+            let state = storage
+                .stored_state(slot)?
+                .ok_or(Error::AnchorStateMissing { slot })?;
+
+            (block, state)
+        }
+        None => storage
+            .checkpoint_anchor()?
+            .ok_or(Error::FinalizedStateMissing)?,
+    };
+
+    let slot = state.slot();
+
+    // The checkpoint state is advanced to the start of the epoch even if that slot is empty.
+    ensure!(
+        block.message().slot() == slot,
+        Error::AnchorBlockMissing { slot },
+    );
+
+    fs_err::create_dir_all(output_dir)?;
+    fs_err::write(output_dir.join(ANCHOR_BLOCK_FILE_NAME), block.to_ssz()?)?;
+    fs_err::write(output_dir.join(ANCHOR_STATE_FILE_NAME), state.to_ssz()?)?;
+
+    Ok(slot)
+}
+
+/// Stores the anchor written by [`export_anchor`] in an empty database.
+/// Loading from the database afterwards is equivalent to [`StateLoadStrategy::Anchor`].
+///
+/// Returns the slot of the imported anchor.
+///
+/// [`StateLoadStrategy::Anchor`]: crate::StateLoadStrategy::Anchor
+pub fn import_anchor<P: Preset>(storage: &Storage<P>, input_dir: &Path) -> Result<Slot> {
+    let FinalizedCheckpoint { block, state } = checkpoint_sync::load_finalized_from_files(
+        storage.config(),
+        &input_dir.join(ANCHOR_BLOCK_FILE_NAME),
+        &input_dir.join(ANCHOR_STATE_FILE_NAME),
+    )?;
+
+    storage.import_anchor(&block, &state)?;
+
+    Ok(block.message().slot())
+}
+
+/// Prunes archival states (and optionally blocks) older than `retain_epochs` epochs before the
+/// latest finalized epoch.
+///
+/// Pruning within the weak subjectivity period is refused unless
+/// `allow_within_weak_subjectivity_period` is `true`. Other nodes may rely on being able to sync
+/// from this node's data in that period.
+pub fn prune_archive<P: Preset>(
+    storage: &Storage<P>,
+    retain_epochs: u64,
+    include_blocks: bool,
+    dry_run: bool,
+    allow_within_weak_subjectivity_period: bool,
+) -> Result<ArchivePruningReport> {
+    let finalized_state = storage
+        .checkpoint_state()?
+        .ok_or(Error::FinalizedStateMissing)?;
+
+    let weak_subjectivity_period =
+        accessors::compute_weak_subjectivity_period(storage.config(), &finalized_state);
+
+    ensure!(
+        allow_within_weak_subjectivity_period || retain_epochs >= weak_subjectivity_period,
+        Error::WithinWeakSubjectivityPeriod {
+            retain_epochs,
+            weak_subjectivity_period,
+        },
+    );
+
+    let finalized_epoch = misc::compute_epoch_at_slot::<P>(finalized_state.slot());
+    let up_to_epoch = finalized_epoch.saturating_sub(retain_epochs);
+    let up_to_slot = misc::compute_start_slot_at_epoch::<P>(up_to_epoch);
+
+    info!(
+        "pruning archive up to slot {up_to_slot} \
+         (finalized epoch: {finalized_epoch}, weak subjectivity period: {weak_subjectivity_period})",
+    );
+
+    storage.prune_archive(
+        up_to_slot,
+        include_blocks,
+        dry_run,
+        &PruningProgress::default(),
+    )
+}
+
+/// Writes data of finalized blocks in slots from `from_slot` to `to_slot` (inclusive) to
+/// `output_path`. `to_slot` defaults to the slot of the latest finalized state in storage.
+///
+/// Returns the number of exported blocks.
+pub fn export_chain_data<P: Preset>(
+    storage: &Storage<P>,
+    from_slot: Slot,
+    to_slot: Option<Slot>,
+    output_path: &Path,
+    format: ChainDataFormat,
+    fields: Vec<ChainDataField>,
+) -> Result<usize> {
+    let finalized_slot = storage
+        .checkpoint_state()?
+        .ok_or(Error::FinalizedStateMissing)?
+        .slot();
+
+    let to_slot = to_slot.map_or(finalized_slot, |slot| slot.min(finalized_slot));
+
+    ensure!(
+        from_slot <= to_slot,
+        Error::EmptySlotRange { from_slot, to_slot },
+    );
+
+    info!("exporting chain data from slot {from_slot} to slot {to_slot} as {format}");
+
+    let mut writer = ChainDataWriter::create(output_path, format, fields)?;
+    let mut block_count = 0;
+
+    for slot in from_slot..=to_slot {
+        if let Some((block, block_root)) = storage.block_by_slot(slot)? {
+            writer.write_block(&block, block_root)?;
+            block_count += 1;
+        }
+    }
+
+    writer.finish()?;
+
+    Ok(block_count)
+}
+
+/// Checks that all entries in storage can be decoded and that indices are consistent with them.
+///
+/// Issues affecting only indices are fixed if `repair` is `true`.
+pub fn verify_storage<P: Preset>(
+    storage: &Storage<P>,
+    repair: bool,
+) -> Result<StorageVerificationReport> {
+    info!("verifying storage (repair: {repair})");
+
+    storage.verify(repair)
+}
+
+pub fn inspect_storage<P: Preset>(storage: &Storage<P>) -> Result<StorageInspectionReport> {
+    info!("inspecting storage");
+
+    storage.inspect()
+}
+
 pub fn replay_blocks<P: Preset>(
     config: &Config,
     input_dir: &Path,
@@ -117,6 +415,61 @@ pub fn replay_blocks<P: Preset>(
     Ok(())
 }
 
+pub fn test_fork_upgrade<P: Preset>(
+    config: &Config,
+    state_file: &Path,
+    target: Phase,
+    slots: u64,
+) -> Result<()> {
+    let pre_state = BeaconState::<P>::from_ssz(config, fs_err::read(state_file)?)?;
+    let phase = pre_state.phase();
+    let slot = pre_state.slot();
+
+    info!(
+        "upgrading {phase} state at slot {slot} to {target} (state root: {:?})",
+        pre_state.hash_tree_root(),
+    );
+
+    let upgrade_start = Instant::now();
+
+    let mut state: BeaconState<P> = match (pre_state, target) {
+        (BeaconState::Phase0(state), Phase::Altair) => {
+            fork::upgrade_to_altair(config, state.as_ref().clone())?.into()
+        }
+        (BeaconState::Altair(state), Phase::Bellatrix) => {
+            fork::upgrade_to_bellatrix(config, state.as_ref().clone()).into()
+        }
+        (BeaconState::Bellatrix(state), Phase::Capella) => {
+            fork::upgrade_to_capella(config, state.as_ref().clone()).into()
+        }
+        (BeaconState::Capella(state), Phase::Deneb) => {
+            fork::upgrade_to_deneb(config, state.as_ref().clone()).into()
+        }
+        _ => bail!(Error::UnsupportedUpgrade { phase, target }),
+    };
+
+    info!(
+        "state upgraded in {:?} (state root: {:?})",
+        upgrade_start.elapsed(),
+        state.hash_tree_root(),
+    );
+
+    if slots > 0 {
+        let processing_start = Instant::now();
+
+        combined::process_slots(config, &mut state, slot + slots)?;
+
+        info!(
+            "{slots} slots processed in {:?} (slot: {}, state root: {:?})",
+            processing_start.elapsed(),
+            state.slot(),
+            state.hash_tree_root(),
+        );
+    }
+
+    Ok(())
+}
+
 fn from_prefixed_file<T: SszRead<Config>>(
     config: &Config,
     input_dir: &Path,
@@ -0,0 +1,889 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{ensure, Result};
+use bls::PublicKey;
+use derive_more::Display;
+use itertools::Itertools as _;
+use ssz::{SszHash as _, SszRead, SszReadDefault as _};
+use thiserror::Error;
+use types::{
+    bellatrix::containers::ExecutionPayload as BellatrixExecutionPayload,
+    capella::containers::ExecutionPayload as CapellaExecutionPayload,
+    combined::{BeaconState, SignedBeaconBlock, SignedBlindedBeaconBlock},
+    config::Config,
+    deneb::{
+        containers::{BlobIdentifier, BlobSidecar, ExecutionPayload as DenebExecutionPayload},
+        primitives::BlobIndex,
+    },
+    phase0::primitives::{Epoch, Slot, H256},
+    preset::Preset,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
+};
+
+use crate::{
+    registry_journal::{RegistryChange, RegistryJournalProgress},
+    reorgs::ReorgRecord,
+    state_diff::StateDiff,
+    storage::{
+        serialize, BlindedBlockByRoot, BlobSidecarByBlobId, BlockCheckpoint, BlockRootBySlot,
+        ExecutionPayloadByRoot, FinalizedBlockByRoot, RegistryChangeByEpoch, ReorgBySlot,
+        SchemaVersion, SlotBlobId, SlotByStateRoot, StateByBlockRoot, StateCheckpoint,
+        StateDiffByBlockRoot, UnfinalizedBlockByRoot,
+    },
+    storage_back_sync::BackSyncStatus,
+    storage_pubkey_cache::{PublicKeyCacheLength, PublicKeyChunkByIndex},
+    Storage,
+};
+
+// Computing hash tree roots of every stored state would take hours on archive nodes.
+// Every object is still decoded, which catches most kinds of corruption.
+const HASH_TREE_ROOT_SAMPLE_INTERVAL: usize = 64;
+
+#[derive(Debug, Display)]
+pub enum StorageIssue {
+    #[display(fmt = "entry {key} cannot be decoded: {error}")]
+    Undecodable { key: String, error: String },
+    #[display(fmt = "hash tree root of entry {key} is {computed:?}")]
+    HashTreeRootMismatch { key: String, computed: H256 },
+    #[display(fmt = "state stored for block {block_root:?} has no matching block")]
+    StateWithoutBlock { block_root: H256 },
+    #[display(fmt = "state diff for block {block_root:?} is based on missing state \
+               for block {base_block_root:?}")]
+    MissingStateDiffBase {
+        block_root: H256,
+        base_block_root: H256,
+    },
+    #[display(fmt = "block root index for slot {slot} refers to missing block {block_root:?}")]
+    DanglingBlockRootBySlot { slot: Slot, block_root: H256 },
+    #[display(
+        fmt = "block root index for slot {slot} refers to block {block_root:?} in slot {block_slot}"
+    )]
+    BlockSlotMismatch {
+        slot: Slot,
+        block_root: H256,
+        block_slot: Slot,
+    },
+    #[display(fmt = "finalized block {block_root:?} in slot {slot} is not in block root index")]
+    MissingBlockRootBySlot { slot: Slot, block_root: H256 },
+    #[display(
+        fmt = "finalized block {block_root:?} in slot {slot} has parent {parent_root:?}, \
+               which is not the previous block in block root index"
+    )]
+    BrokenParentLink {
+        slot: Slot,
+        block_root: H256,
+        parent_root: H256,
+    },
+    #[display(fmt = "multiple finalized blocks in slot {slot}: {block_roots:?}")]
+    ConflictingFinalizedBlocks { slot: Slot, block_roots: [H256; 2] },
+    #[display(fmt = "state root {state_root:?} of finalized block in slot {slot} is not indexed")]
+    MissingSlotByStateRoot { state_root: H256, slot: Slot },
+    #[display(fmt = "blob sidecar {blob_id:?} does not match its key")]
+    BlobSidecarMismatch { blob_id: BlobIdentifier },
+    #[display(fmt = "blob sidecar {blob_id:?} belongs to a block that is not stored")]
+    OrphanedBlobSidecar { blob_id: BlobIdentifier },
+    #[display(fmt = "blob sidecar {blob_id:?} in slot {slot} is not in blob index")]
+    MissingSlotBlobId { slot: Slot, blob_id: BlobIdentifier },
+    #[display(fmt = "blob index entry {key} refers to missing or different blob sidecar")]
+    DanglingSlotBlobId { key: String },
+}
+
+impl StorageIssue {
+    /// Returns `true` if the issue can be fixed by adding or removing index entries.
+    #[must_use]
+    pub const fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Self::DanglingBlockRootBySlot { .. }
+                | Self::BlockSlotMismatch { .. }
+                | Self::MissingBlockRootBySlot { .. }
+                | Self::MissingSlotByStateRoot { .. }
+                | Self::MissingSlotBlobId { .. }
+                | Self::DanglingSlotBlobId { .. }
+        )
+    }
+
+    const fn is_corruption(&self) -> bool {
+        matches!(
+            self,
+            Self::Undecodable { .. }
+                | Self::HashTreeRootMismatch { .. }
+                | Self::MissingStateDiffBase { .. }
+                | Self::ConflictingFinalizedBlocks { .. }
+                | Self::BrokenParentLink { .. }
+                | Self::BlobSidecarMismatch { .. }
+        )
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct StorageVerificationReport {
+    pub entry_counts: BTreeMap<&'static str, usize>,
+    pub hash_tree_roots_checked: usize,
+    pub issues: Vec<StorageIssue>,
+    pub repaired_issues: usize,
+}
+
+impl StorageVerificationReport {
+    #[must_use]
+    pub fn suggestions(&self) -> Vec<&'static str> {
+        let mut suggestions = vec![];
+
+        if self.issues.iter().any(StorageIssue::is_corruption) {
+            suggestions.push(
+                "the database contains corrupted data; \
+                 restore it from a backup or resync with --force-checkpoint-sync",
+            );
+        }
+
+        if self.repaired_issues == 0 && self.issues.iter().any(StorageIssue::is_repairable) {
+            suggestions.push("index issues can be fixed with `grandine db verify --repair`");
+        }
+
+        if self.issues.iter().any(|issue| {
+            matches!(
+                issue,
+                StorageIssue::StateWithoutBlock { .. } | StorageIssue::OrphanedBlobSidecar { .. },
+            )
+        }) {
+            suggestions.push(
+                "states and blob sidecars without blocks are left behind by unclean shutdowns \
+                 and do not affect the node",
+            );
+        }
+
+        suggestions
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BlockSummary {
+    slot: Slot,
+    parent_root: H256,
+    state_root: H256,
+    finalized: bool,
+}
+
+struct StateSummary {
+    block_root: H256,
+    slot: Slot,
+    computed_root: Option<H256>,
+}
+
+struct StateDiffSummary {
+    block_root: H256,
+    base_block_root: H256,
+}
+
+struct BlobSidecarSummary {
+    blob_id: BlobIdentifier,
+    slot: Slot,
+}
+
+struct SlotBlobIdEntry {
+    key: String,
+    slot: Slot,
+    key_blob_id: BlobIdentifier,
+    blob_id: BlobIdentifier,
+}
+
+#[derive(Default)]
+struct Entries {
+    blocks: HashMap<H256, BlockSummary>,
+    block_roots_by_slot: BTreeMap<Slot, H256>,
+    states: Vec<StateSummary>,
+    state_diffs: Vec<StateDiffSummary>,
+    blob_sidecars: Vec<BlobSidecarSummary>,
+    slot_blob_ids: Vec<SlotBlobIdEntry>,
+}
+
+pub(crate) enum Key {
+    SchemaVersion,
+    BlockCheckpoint,
+    StateCheckpoint,
+    BackSyncStatus,
+    RegistryJournalProgress,
+    FinalizedBlock(H256),
+    UnfinalizedBlock(H256),
+    BlindedBlock(H256),
+    ExecutionPayload(H256),
+    BlockRootBySlot(Slot),
+    State(H256),
+    StateDiff(H256),
+    SlotByStateRoot,
+    BlobSidecar(BlobIdentifier),
+    SlotBlobId(Slot, BlobIdentifier),
+    Reorg(Slot),
+    RegistryChange(Epoch),
+    PublicKeyCacheLength,
+    PublicKeyChunk(u64),
+    Unknown,
+}
+
+impl Key {
+    pub(crate) fn parse<P: Preset>(key: &str) -> Result<Self> {
+        let parse_blob_id = |payload: &str| -> Result<BlobIdentifier> {
+            let (root, index) = split_key(payload, 64)?;
+
+            Ok(BlobIdentifier {
+                block_root: root.parse()?,
+                index: index.parse::<BlobIndex>()?,
+            })
+        };
+
+        // `UnfinalizedBlockByRoot::PREFIX` starts with `FinalizedBlockByRoot::PREFIX`.
+        let key = if key == SchemaVersion::KEY {
+            Self::SchemaVersion
+        } else if key == BlockCheckpoint::<P>::KEY {
+            Self::BlockCheckpoint
+        } else if key == StateCheckpoint::<P>::KEY {
+            Self::StateCheckpoint
+        } else if key == BackSyncStatus::KEY {
+            Self::BackSyncStatus
+        } else if key == RegistryJournalProgress::KEY {
+            Self::RegistryJournalProgress
+        } else if key == PublicKeyCacheLength::KEY {
+            Self::PublicKeyCacheLength
+        } else if let Some(payload) = key.strip_prefix(UnfinalizedBlockByRoot::PREFIX) {
+            Self::UnfinalizedBlock(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(FinalizedBlockByRoot::PREFIX) {
+            Self::FinalizedBlock(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(BlindedBlockByRoot::PREFIX) {
+            Self::BlindedBlock(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(ExecutionPayloadByRoot::PREFIX) {
+            Self::ExecutionPayload(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(BlockRootBySlot::PREFIX) {
+            Self::BlockRootBySlot(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(StateByBlockRoot::PREFIX) {
+            Self::State(payload.parse()?)
+        } else if let Some(payload) = key.strip_prefix(StateDiffByBlockRoot::PREFIX) {
+            Self::StateDiff(payload.parse()?)
+        } else if key.starts_with(SlotByStateRoot::PREFIX) {
+            Self::SlotByStateRoot
+        } else if let Some(payload) = key.strip_prefix(BlobSidecarByBlobId::PREFIX) {
+            Self::BlobSidecar(parse_blob_id(payload)?)
+        } else if let Some(payload) = key.strip_prefix(SlotBlobId::PREFIX) {
+            let (slot, blob_id) = split_key(payload, 20)?;
+            Self::SlotBlobId(slot.parse()?, parse_blob_id(blob_id)?)
+        } else if let Some(payload) = key.strip_prefix(ReorgBySlot::PREFIX) {
+            let (slot, _) = split_key(payload, 20)?;
+            Self::Reorg(slot.parse()?)
+        } else if let Some(payload) = key.strip_prefix(RegistryChangeByEpoch::PREFIX) {
+            let (epoch, _) = split_key(payload, 20)?;
+            Self::RegistryChange(epoch.parse()?)
+        } else if let Some(payload) = key.strip_prefix(PublicKeyChunkByIndex::PREFIX) {
+            Self::PublicKeyChunk(payload.parse()?)
+        } else {
+            Self::Unknown
+        };
+
+        Ok(key)
+    }
+
+    pub(crate) const fn kind(&self) -> &'static str {
+        match self {
+            Self::SchemaVersion => "schema version",
+            Self::BlockCheckpoint => "block checkpoint",
+            Self::StateCheckpoint => "state checkpoint",
+            Self::BackSyncStatus => "back sync status",
+            Self::RegistryJournalProgress => "registry journal progress",
+            Self::FinalizedBlock(_) => "finalized blocks",
+            Self::UnfinalizedBlock(_) => "unfinalized blocks",
+            Self::BlindedBlock(_) => "blinded blocks",
+            Self::ExecutionPayload(_) => "execution payloads",
+            Self::BlockRootBySlot(_) => "block roots by slot",
+            Self::State(_) => "states",
+            Self::StateDiff(_) => "state diffs",
+            Self::SlotByStateRoot => "slots by state root",
+            Self::BlobSidecar(_) => "blob sidecars",
+            Self::SlotBlobId(_, _) => "blob sidecar slot index",
+            Self::Reorg(_) => "reorgs",
+            Self::RegistryChange(_) => "registry changes",
+            Self::PublicKeyCacheLength => "public key cache length",
+            Self::PublicKeyChunk(_) => "public key cache",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Returns the slot encoded in the key, if any.
+    pub(crate) const fn slot(&self) -> Option<Slot> {
+        match self {
+            Self::BlockRootBySlot(slot) | Self::SlotBlobId(slot, _) | Self::Reorg(slot) => {
+                Some(*slot)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<P: Preset> Storage<P> {
+    /// Checks that every entry can be decoded, that indices are consistent with the data and that
+    /// finalized blocks in the block root index link to their predecessors.
+    ///
+    /// If `repair` is `true`, issues that only affect indices are fixed.
+    /// Blocks, states and blob sidecars are never modified.
+    pub(crate) fn verify(&self, repair: bool) -> Result<StorageVerificationReport> {
+        let mut report = StorageVerificationReport::default();
+        let mut entries = Entries::default();
+
+        for database in self.databases() {
+            for result in database.iterator_ascending(""..)? {
+                let (key_bytes, value_bytes) = result?;
+                let key_string = String::from_utf8_lossy(&key_bytes).into_owned();
+
+                if let Err(error) =
+                    self.verify_entry(&key_string, &value_bytes, &mut entries, &mut report)
+                {
+                    report.issues.push(StorageIssue::Undecodable {
+                        key: key_string,
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        self.check_indices(&entries, &mut report)?;
+
+        if repair {
+            report.repaired_issues = self.repair(&report.issues)?;
+        }
+
+        Ok(report)
+    }
+
+    fn verify_entry(
+        &self,
+        key_string: &str,
+        value_bytes: &[u8],
+        entries: &mut Entries,
+        report: &mut StorageVerificationReport,
+    ) -> Result<()> {
+        let key = Key::parse::<P>(key_string)?;
+        let count = report.entry_counts.entry(key.kind()).or_default();
+        let sampled = *count % HASH_TREE_ROOT_SAMPLE_INTERVAL == 0;
+
+        *count += 1;
+
+        match key {
+            Key::SchemaVersion => {
+                u64::from_ssz_default(value_bytes)?;
+            }
+            Key::BlockCheckpoint => {
+                self.decode::<BlockCheckpoint<P>>(value_bytes)?;
+            }
+            Key::StateCheckpoint => {
+                self.decode::<StateCheckpoint<P>>(value_bytes)?;
+            }
+            Key::BackSyncStatus => {
+                BackSyncStatus::from_ssz_default(value_bytes)?;
+            }
+            Key::RegistryJournalProgress => {
+                RegistryJournalProgress::from_ssz_default(value_bytes)?;
+            }
+            Key::FinalizedBlock(block_root) | Key::UnfinalizedBlock(block_root) => {
+                let block = self.decode::<SignedBeaconBlock<P>>(value_bytes)?;
+
+                if sampled {
+                    let computed = block.message().hash_tree_root();
+
+                    report.hash_tree_roots_checked += 1;
+
+                    if computed != block_root {
+                        report.issues.push(StorageIssue::HashTreeRootMismatch {
+                            key: key_string.to_owned(),
+                            computed,
+                        });
+                    }
+                }
+
+                entries.blocks.insert(
+                    block_root,
+                    BlockSummary {
+                        slot: block.message().slot(),
+                        parent_root: block.message().parent_root(),
+                        state_root: block.message().state_root(),
+                        finalized: matches!(key, Key::FinalizedBlock(_)),
+                    },
+                );
+            }
+            Key::BlindedBlock(block_root) => {
+                let block = self.decode::<SignedBlindedBeaconBlock<P>>(value_bytes)?;
+
+                // Blinded blocks have the same hash tree roots as the full blocks.
+                if sampled {
+                    let computed = block.message().hash_tree_root();
+
+                    report.hash_tree_roots_checked += 1;
+
+                    if computed != block_root {
+                        report.issues.push(StorageIssue::HashTreeRootMismatch {
+                            key: key_string.to_owned(),
+                            computed,
+                        });
+                    }
+                }
+
+                entries.blocks.insert(
+                    block_root,
+                    BlockSummary {
+                        slot: block.message().slot(),
+                        parent_root: block.message().parent_root(),
+                        state_root: block.message().state_root(),
+                        finalized: true,
+                    },
+                );
+            }
+            // The phase of a payload is only known from its block. Payloads of different phases
+            // have different fixed parts, so one that decodes as none of them is corrupted.
+            Key::ExecutionPayload(_) => {
+                let decodes = DenebExecutionPayload::<P>::from_ssz_default(value_bytes).is_ok()
+                    || CapellaExecutionPayload::<P>::from_ssz_default(value_bytes).is_ok();
+
+                if !decodes {
+                    BellatrixExecutionPayload::<P>::from_ssz_default(value_bytes)?;
+                }
+            }
+            Key::BlockRootBySlot(slot) => {
+                let block_root = H256::from_ssz_default(value_bytes)?;
+                entries.block_roots_by_slot.insert(slot, block_root);
+            }
+            Key::State(block_root) => {
+                let state = self.decode::<BeaconState<P>>(value_bytes)?;
+
+                let computed_root = sampled.then(|| {
+                    report.hash_tree_roots_checked += 1;
+                    state.hash_tree_root()
+                });
+
+                entries.states.push(StateSummary {
+                    block_root,
+                    slot: state.slot(),
+                    computed_root,
+                });
+            }
+            Key::StateDiff(block_root) => {
+                let diff = StateDiff::from_bytes(value_bytes)?;
+
+                entries.state_diffs.push(StateDiffSummary {
+                    block_root,
+                    base_block_root: diff.base_block_root(),
+                });
+            }
+            Key::SlotByStateRoot => {
+                Slot::from_ssz_default(value_bytes)?;
+            }
+            Key::BlobSidecar(blob_id) => {
+                let blob_sidecar = self.decode::<BlobSidecar<P>>(value_bytes)?;
+                let header = blob_sidecar.signed_block_header.message;
+
+                if blob_sidecar.index != blob_id.index
+                    || header.hash_tree_root() != blob_id.block_root
+                {
+                    report
+                        .issues
+                        .push(StorageIssue::BlobSidecarMismatch { blob_id });
+                }
+
+                entries.blob_sidecars.push(BlobSidecarSummary {
+                    blob_id,
+                    slot: header.slot,
+                });
+            }
+            Key::SlotBlobId(slot, key_blob_id) => {
+                entries.slot_blob_ids.push(SlotBlobIdEntry {
+                    key: key_string.to_owned(),
+                    slot,
+                    key_blob_id,
+                    blob_id: BlobIdentifier::from_ssz_default(value_bytes)?,
+                });
+            }
+            Key::Reorg(_) => {
+                ReorgRecord::from_ssz_default(value_bytes)?;
+            }
+            Key::RegistryChange(_) => {
+                RegistryChange::from_ssz_default(value_bytes)?;
+            }
+            Key::PublicKeyCacheLength => {
+                u64::from_ssz_default(value_bytes)?;
+            }
+            Key::PublicKeyChunk(_) => {
+                let length = value_bytes.len();
+
+                ensure!(
+                    length % PublicKey::UNCOMPRESSED_SIZE == 0,
+                    Error::PublicKeyChunkMisaligned { length },
+                );
+            }
+            Key::Unknown => {}
+        }
+
+        Ok(())
+    }
+
+    fn check_indices(
+        &self,
+        entries: &Entries,
+        report: &mut StorageVerificationReport,
+    ) -> Result<()> {
+        let Entries {
+            blocks,
+            block_roots_by_slot,
+            states,
+            ..
+        } = entries;
+
+        let mut valid_block_roots_by_slot = BTreeMap::new();
+
+        for (slot, block_root) in block_roots_by_slot
+            .iter()
+            .map(|(slot, root)| (*slot, *root))
+        {
+            match blocks.get(&block_root) {
+                None => report
+                    .issues
+                    .push(StorageIssue::DanglingBlockRootBySlot { slot, block_root }),
+                Some(block) if block.slot != slot => {
+                    report.issues.push(StorageIssue::BlockSlotMismatch {
+                        slot,
+                        block_root,
+                        block_slot: block.slot,
+                    });
+                }
+                Some(_) => {
+                    valid_block_roots_by_slot.insert(slot, block_root);
+                }
+            }
+        }
+
+        for (block_root, block) in blocks.iter().map(|(root, block)| (*root, *block)) {
+            if !block.finalized {
+                continue;
+            }
+
+            let slot = block.slot;
+
+            match valid_block_roots_by_slot.get(&slot).copied() {
+                Some(indexed_root) if indexed_root == block_root => {}
+                // The index may still refer to an orphaned unfinalized block in the same slot.
+                Some(indexed_root) if blocks[&indexed_root].finalized => {
+                    report
+                        .issues
+                        .push(StorageIssue::ConflictingFinalizedBlocks {
+                            slot,
+                            block_roots: [indexed_root, block_root],
+                        });
+                }
+                _ => report
+                    .issues
+                    .push(StorageIssue::MissingBlockRootBySlot { slot, block_root }),
+            }
+
+            if !self.contains_key(SlotByStateRoot(block.state_root))? {
+                report.issues.push(StorageIssue::MissingSlotByStateRoot {
+                    state_root: block.state_root,
+                    slot,
+                });
+            }
+        }
+
+        for state in states {
+            let Some(block) = blocks.get(&state.block_root) else {
+                report.issues.push(StorageIssue::StateWithoutBlock {
+                    block_root: state.block_root,
+                });
+                continue;
+            };
+
+            // States in slots after the block have empty slots processed on top of it.
+            if state.slot != block.slot {
+                continue;
+            }
+
+            if let Some(computed) = state.computed_root {
+                if computed != block.state_root {
+                    report.issues.push(StorageIssue::HashTreeRootMismatch {
+                        key: StateByBlockRoot(state.block_root).to_string(),
+                        computed,
+                    });
+                }
+            }
+        }
+
+        check_parent_links(blocks, &valid_block_roots_by_slot, report);
+        check_state_diffs(entries, report);
+        check_blob_sidecar_indices(entries, report);
+
+        Ok(())
+    }
+
+    fn repair(&self, issues: &[StorageIssue]) -> Result<usize> {
+        let mut keys_to_delete = vec![];
+        let mut batch = vec![];
+
+        for issue in issues {
+            match issue {
+                StorageIssue::DanglingBlockRootBySlot { slot, .. }
+                | StorageIssue::BlockSlotMismatch { slot, .. } => {
+                    keys_to_delete.push(BlockRootBySlot(*slot).to_string());
+                }
+                StorageIssue::DanglingSlotBlobId { key } => {
+                    keys_to_delete.push(key.clone());
+                }
+                StorageIssue::MissingBlockRootBySlot { slot, block_root } => {
+                    batch.push(serialize(BlockRootBySlot(*slot), block_root)?);
+                }
+                StorageIssue::MissingSlotByStateRoot { state_root, slot } => {
+                    batch.push(serialize(SlotByStateRoot(*state_root), slot)?);
+                }
+                StorageIssue::MissingSlotBlobId { slot, blob_id } => {
+                    let BlobIdentifier { block_root, index } = *blob_id;
+                    batch.push(serialize(SlotBlobId(*slot, block_root, index), blob_id)?);
+                }
+                _ => {}
+            }
+        }
+
+        let repaired_issues = keys_to_delete.len() + batch.len();
+
+        // Delete first so that replacements of invalid entries are not deleted with them.
+        self.delete_keys(keys_to_delete)?;
+        self.put_batch(batch)?;
+
+        Ok(repaired_issues)
+    }
+
+    fn decode<V: SszRead<Config>>(&self, value_bytes: &[u8]) -> Result<V> {
+        V::from_ssz(self.config(), value_bytes).map_err(Into::into)
+    }
+}
+
+// Finalized blocks in the index should form a chain. A block whose parent is not stored at all
+// starts a range of history that was never synced or has been pruned, which is not an issue.
+fn check_parent_links(
+    blocks: &HashMap<H256, BlockSummary>,
+    block_roots_by_slot: &BTreeMap<Slot, H256>,
+    report: &mut StorageVerificationReport,
+) {
+    let finalized_block_roots = block_roots_by_slot
+        .values()
+        .copied()
+        .filter(|block_root| blocks[block_root].finalized);
+
+    for (previous_root, block_root) in finalized_block_roots.tuple_windows() {
+        let BlockSummary {
+            slot, parent_root, ..
+        } = blocks[&block_root];
+
+        if parent_root != previous_root && blocks.contains_key(&parent_root) {
+            report.issues.push(StorageIssue::BrokenParentLink {
+                slot,
+                block_root,
+                parent_root,
+            });
+        }
+    }
+}
+
+fn check_state_diffs(entries: &Entries, report: &mut StorageVerificationReport) {
+    let Entries {
+        blocks,
+        states,
+        state_diffs,
+        ..
+    } = entries;
+
+    let full_state_block_roots = states
+        .iter()
+        .map(|state| state.block_root)
+        .collect::<HashSet<_>>();
+
+    for diff in state_diffs {
+        if !blocks.contains_key(&diff.block_root) {
+            report.issues.push(StorageIssue::StateWithoutBlock {
+                block_root: diff.block_root,
+            });
+        }
+
+        if !full_state_block_roots.contains(&diff.base_block_root) {
+            report.issues.push(StorageIssue::MissingStateDiffBase {
+                block_root: diff.block_root,
+                base_block_root: diff.base_block_root,
+            });
+        }
+    }
+}
+
+fn check_blob_sidecar_indices(entries: &Entries, report: &mut StorageVerificationReport) {
+    let Entries {
+        blocks,
+        blob_sidecars,
+        slot_blob_ids,
+        ..
+    } = entries;
+
+    let stored_blob_ids = blob_sidecars
+        .iter()
+        .map(|blob_sidecar| blob_sidecar.blob_id)
+        .collect::<HashSet<_>>();
+
+    let mut indexed_blob_ids = HashSet::new();
+
+    for entry in slot_blob_ids {
+        if entry.blob_id == entry.key_blob_id && stored_blob_ids.contains(&entry.blob_id) {
+            indexed_blob_ids.insert(entry.blob_id);
+        } else {
+            report.issues.push(StorageIssue::DanglingSlotBlobId {
+                key: entry.key.clone(),
+            });
+        }
+    }
+
+    for BlobSidecarSummary { blob_id, slot } in blob_sidecars {
+        if !indexed_blob_ids.contains(blob_id) {
+            report.issues.push(StorageIssue::MissingSlotBlobId {
+                slot: *slot,
+                blob_id: *blob_id,
+            });
+        }
+
+        if !blocks.contains_key(&blob_id.block_root) {
+            report
+                .issues
+                .push(StorageIssue::OrphanedBlobSidecar { blob_id: *blob_id });
+        }
+    }
+}
+
+fn split_key(payload: &str, position: usize) -> Result<(&str, &str)> {
+    payload
+        .get(..position)
+        .zip(payload.get(position..))
+        .ok_or_else(|| {
+            Error::KeyTooShort {
+                length: payload.len(),
+            }
+            .into()
+        })
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("storage key is too short (payload length: {length})")]
+    KeyTooShort { length: usize },
+    #[error("public key cache chunk does not consist of whole keys (length: {length})")]
+    PublicKeyChunkMisaligned { length: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use types::{
+        combined::SignedBeaconBlock,
+        phase0::containers::{
+            BeaconBlock as Phase0BeaconBlock, SignedBeaconBlock as Phase0SignedBeaconBlock,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn verification_finds_and_repairs_index_issues() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        let block = SignedBeaconBlock::<Minimal>::from(Phase0SignedBeaconBlock {
+            message: Phase0BeaconBlock {
+                slot: 1,
+                state_root: H256::repeat_byte(1),
+                ..Phase0BeaconBlock::default()
+            },
+            ..Phase0SignedBeaconBlock::default()
+        });
+
+        let block_root = block.message().hash_tree_root();
+        let missing_root = H256::repeat_byte(2);
+
+        storage.put_batch([
+            serialize(FinalizedBlockByRoot(block_root), &block)?,
+            serialize(BlockRootBySlot(2), missing_root)?,
+            (UnfinalizedBlockByRoot(missing_root).to_string(), vec![0]),
+        ])?;
+
+        let report = storage.verify(false)?;
+
+        assert_eq!(report.issues.len(), 4);
+        assert_eq!(report.repaired_issues, 0);
+        assert_eq!(report.entry_counts["finalized blocks"], 1);
+        assert_eq!(report.hash_tree_roots_checked, 1);
+
+        assert!(report
+            .issues
+            .iter()
+            .any(|issue| matches!(issue, StorageIssue::Undecodable { .. },)));
+
+        assert_eq!(
+            report
+                .issues
+                .iter()
+                .filter(|issue| issue.is_repairable())
+                .count(),
+            3,
+        );
+
+        assert_eq!(storage.verify(true)?.repaired_issues, 3);
+
+        let report = storage.verify(false)?;
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(storage.block_root_by_slot(1)?, Some(block_root));
+        assert_eq!(storage.block_root_by_slot(2)?, None);
+        assert_eq!(storage.slot_by_state_root(H256::repeat_byte(1))?, Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn verification_finds_broken_parent_links() -> Result<()> {
+        let storage = Storage::<Minimal>::in_memory(Arc::new(Config::minimal()));
+
+        let block_at = |slot, parent_root| {
+            SignedBeaconBlock::<Minimal>::from(Phase0SignedBeaconBlock {
+                message: Phase0BeaconBlock {
+                    slot,
+                    parent_root,
+                    ..Phase0BeaconBlock::default()
+                },
+                ..Phase0SignedBeaconBlock::default()
+            })
+        };
+
+        // The block in slot 3 skips the one in slot 2. The one in slot 5 has no stored parent.
+        let block_1 = block_at(1, H256::zero());
+        let block_2 = block_at(2, block_1.message().hash_tree_root());
+        let block_3 = block_at(3, block_1.message().hash_tree_root());
+        let block_5 = block_at(5, H256::repeat_byte(4));
+
+        for block in [&block_1, &block_2, &block_3, &block_5] {
+            let slot = block.message().slot();
+            let block_root = block.message().hash_tree_root();
+
+            storage.put_batch([
+                serialize(FinalizedBlockByRoot(block_root), block)?,
+                serialize(BlockRootBySlot(slot), block_root)?,
+                serialize(SlotByStateRoot(H256::zero()), slot)?,
+            ])?;
+        }
+
+        let report = storage.verify(false)?;
+
+        assert_eq!(report.issues.len(), 1);
+
+        assert!(matches!(
+            report.issues[0],
+            StorageIssue::BrokenParentLink { slot: 3, .. },
+        ));
+
+        Ok(())
+    }
+}
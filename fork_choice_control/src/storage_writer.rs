@@ -0,0 +1,175 @@
+//! Persisting finalized chain links and reorg records without blocking the fork choice thread.
+//!
+//! Serializing a batch that contains a full `BeaconState` can take long enough to delay head
+//! updates. [`Mutator`] hands chain links off to a dedicated writer thread through a bounded queue
+//! instead. Writes are performed one at a time in the order they were queued, so an older state
+//! checkpoint can never overwrite a newer one. The queue is bounded to limit the number of states
+//! kept in memory. The mutator waits for the writer to catch up if the queue is full.
+//!
+//! Each write is a single atomic batch. If the application stops before a queued write completes,
+//! the database is left at an earlier state checkpoint. Blocks after it are replayed from storage
+//! or synced again on startup. The replayed chain is queued to be saved like any other, so blocks
+//! finalized during the replay do not have to be replayed again after the next restart.
+//!
+//! [`Mutator`]: crate::mutator::Mutator
+
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender, SyncSender},
+        Arc,
+    },
+    thread::Builder,
+};
+
+use anyhow::Result;
+use fork_choice_store::{ChainLink, Store};
+use log::{debug, error};
+use std_ext::ArcExt as _;
+use types::preset::Preset;
+
+use crate::{
+    messages::SyncMessage, reorgs::ReorgRecord, storage::Storage, unbounded_sink::UnboundedSink,
+    wait::Wait,
+};
+
+// Archiving happens at most once per epoch in normal operation.
+// A longer queue would only matter during sync, where waiting for the writer is acceptable.
+const QUEUE_CAPACITY: usize = 4;
+
+enum WriteMessage<P: Preset, W> {
+    Chain {
+        unfinalized: Vec<ChainLink<P>>,
+        finalized: Vec<ChainLink<P>>,
+        store: Arc<Store<P>>,
+        wait_group: W,
+    },
+    Reorg {
+        reorg: ReorgRecord,
+        wait_group: W,
+    },
+    Flush(Sender<()>),
+}
+
+pub struct StorageWriter<P: Preset, W> {
+    tx: SyncSender<WriteMessage<P, W>>,
+}
+
+impl<P: Preset, W: Wait> StorageWriter<P, W> {
+    pub fn new(
+        storage: Arc<Storage<P>>,
+        sync_tx: impl UnboundedSink<SyncMessage<P>>,
+    ) -> Result<Self> {
+        let (tx, rx) = mpsc::sync_channel(QUEUE_CAPACITY);
+
+        Builder::new()
+            .name("storage-writer".to_owned())
+            .spawn(move || run(&storage, &sync_tx, rx))?;
+
+        Ok(Self { tx })
+    }
+
+    /// Queues `chain_links` to be saved as finalized.
+    ///
+    /// Blocks if the queue is full. `wait_group` is dropped once the write completes.
+    pub fn append_finalized(
+        &self,
+        chain_links: impl IntoIterator<Item = ChainLink<P>>,
+        store: Arc<Store<P>>,
+        wait_group: W,
+    ) {
+        self.append(core::iter::empty(), chain_links, store, wait_group);
+    }
+
+    /// Queues `unfinalized` and `finalized` chain links to be saved.
+    ///
+    /// `unfinalized` must be ordered from newest to oldest, `finalized` from oldest to newest.
+    /// This is the order expected by `Storage::append`.
+    ///
+    /// Blocks if the queue is full. `wait_group` is dropped once the write completes.
+    pub fn append(
+        &self,
+        unfinalized: impl IntoIterator<Item = ChainLink<P>>,
+        finalized: impl IntoIterator<Item = ChainLink<P>>,
+        store: Arc<Store<P>>,
+        wait_group: W,
+    ) {
+        let message = WriteMessage::Chain {
+            unfinalized: unfinalized.into_iter().collect(),
+            finalized: finalized.into_iter().collect(),
+            store,
+            wait_group,
+        };
+
+        // Don't log the value because it contains entire `BeaconState`s.
+        if self.tx.send(message).is_err() {
+            error!("chain not saved because the storage writer thread stopped");
+        }
+    }
+
+    /// Queues `reorg` to be saved. Records past their retention period are pruned at the same time.
+    ///
+    /// Blocks if the queue is full. `wait_group` is dropped once the write completes.
+    pub fn append_reorg(&self, reorg: ReorgRecord, wait_group: W) {
+        let message = WriteMessage::Reorg { reorg, wait_group };
+
+        if self.tx.send(message).is_err() {
+            error!("reorg not saved because the storage writer thread stopped: {reorg:?}");
+        }
+    }
+
+    /// Waits until all previously queued writes are completed.
+    pub fn flush(&self) {
+        let (reply_tx, reply_rx) = mpsc::channel();
+
+        if self.tx.send(WriteMessage::Flush(reply_tx)).is_ok() {
+            reply_rx.recv().ok();
+        }
+    }
+}
+
+// The thread exits once the `StorageWriter` is dropped and all queued writes are completed.
+fn run<P: Preset, W>(
+    storage: &Storage<P>,
+    sync_tx: &impl UnboundedSink<SyncMessage<P>>,
+    rx: Receiver<WriteMessage<P, W>>,
+) {
+    for message in rx {
+        match message {
+            WriteMessage::Chain {
+                unfinalized,
+                finalized,
+                store,
+                wait_group,
+            } => {
+                debug!("saving finalized blocks and anchor state…");
+
+                match storage.append(unfinalized.iter(), finalized.iter(), &store) {
+                    Ok(slots) => {
+                        if let Some(chain_link) = finalized.last() {
+                            let finalized_block = chain_link.block.clone_arc();
+                            SyncMessage::Finalized(finalized_block).send(sync_tx);
+                        }
+
+                        debug!(
+                            "finalized blocks and anchor state saved \
+                             (appended block slots: {slots:?})",
+                        )
+                    }
+                    Err(error) => error!("saving to storage failed: {error:?}"),
+                }
+
+                drop(wait_group);
+            }
+            WriteMessage::Reorg { reorg, wait_group } => {
+                if let Err(error) = storage.append_reorg(reorg) {
+                    error!("saving reorg to storage failed: {error:?}");
+                }
+
+                drop(wait_group);
+            }
+            WriteMessage::Flush(reply_tx) => {
+                reply_tx.send(()).ok();
+            }
+        }
+    }
+}
@@ -34,6 +34,7 @@ use types::{
 use crate::{
     messages::MutatorMessage,
     misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
+    proposer_duties::ProposerDutiesCache,
     state_cache::StateCache,
     storage::Storage,
 };
@@ -135,6 +136,7 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for BlockTask<P, E, W> {
             origin,
             submission_time,
             rejected_block_root,
+            trace: request_tracing::current(),
         }
         .send(&mutator_tx);
     }
@@ -413,6 +415,7 @@ impl<P: Preset, W> Run for CheckpointStateTask<P, W> {
 
 pub struct PreprocessStateTask<P: Preset, W> {
     pub state_cache: Arc<StateCache<P, W>>,
+    pub proposer_duties_cache: Arc<ProposerDutiesCache>,
     pub head_block_root: H256,
     pub next_slot: Slot,
     pub metrics: Option<Arc<Metrics>>,
@@ -422,6 +425,7 @@ impl<P: Preset, W> Run for PreprocessStateTask<P, W> {
     fn run(self) {
         let Self {
             state_cache,
+            proposer_duties_cache,
             head_block_root,
             next_slot,
             metrics,
@@ -436,6 +440,18 @@ impl<P: Preset, W> Run for PreprocessStateTask<P, W> {
                 if let Err(error) = initialize_preprocessed_state_cache(&state) {
                     warn!("failed to initialize preprocessed state's cache values: {error:?}");
                 }
+
+                // The head is the last block before `next_slot`, so if `next_slot` starts an epoch,
+                // the head is the dependent root for proposers in that epoch.
+                if misc::is_epoch_start::<P>(next_slot) {
+                    let epoch = misc::compute_epoch_at_slot::<P>(next_slot);
+
+                    if let Err(error) =
+                        proposer_duties_cache.get_or_try_insert(&state, epoch, head_block_root)
+                    {
+                        warn!("failed to compute proposer duties for epoch {epoch}: {error:?}");
+                    }
+                }
             }
             Err(error) => {
                 warn!("failed to preprocess beacon state for the next slot: {error:?}");
@@ -448,6 +464,8 @@ fn initialize_preprocessed_state_cache<P: Preset>(state: &impl BeaconState<P>) -
     accessors::get_or_try_init_beacon_proposer_index(state, false)?;
     accessors::get_or_init_active_validator_indices_shuffled(state, RelativeEpoch::Current, false);
     accessors::get_or_init_active_validator_indices_shuffled(state, RelativeEpoch::Next, false);
+    accessors::get_or_init_committee_boundaries(state, RelativeEpoch::Current, false);
+    accessors::get_or_init_committee_boundaries(state, RelativeEpoch::Next, false);
     accessors::get_or_init_total_active_balance(state, false);
     accessors::get_or_init_validator_indices(state, false);
 
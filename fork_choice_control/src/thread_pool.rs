@@ -19,7 +19,9 @@ use educe::Educe;
 use execution_engine::ExecutionEngine;
 use log::debug;
 use parking_lot::{Condvar, Mutex};
+use request_tracing::TraceContext;
 use std_ext::ArcExt as _;
+use strum::IntoStaticStr;
 use types::preset::Preset;
 
 use crate::{
@@ -93,12 +95,15 @@ pub struct Critical<P: Preset, E, W> {
     // `VecDeque` is that it never automatically shrinks, so the application may hold on to a large
     // allocation permanently. An unrolled linked list (other than `crossbeam_queue::SegQueue`)
     // might be the best of both worlds.
-    high_priority_tasks: VecDeque<HighPriorityTask<P, E, W>>,
-    low_priority_tasks: VecDeque<LowPriorityTask<P, W>>,
+    //
+    // Tasks are stored along with the request they were spawned for (if any).
+    high_priority_tasks: VecDeque<(HighPriorityTask<P, E, W>, Option<TraceContext>)>,
+    low_priority_tasks: VecDeque<(LowPriorityTask<P, W>, Option<TraceContext>)>,
 }
 
 // TODO(feature/deneb): Figure out if `BlobSidecarTask` should be a high priority task.
-#[derive(From)]
+#[derive(From, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 enum HighPriorityTask<P: Preset, E, W> {
     Block(BlockTask<P, E, W>),
     BlobSidecar(BlobSidecarTask<P, W>),
@@ -120,7 +125,8 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for HighPriorityTask<P, E,
     }
 }
 
-#[derive(From)]
+#[derive(From, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 enum LowPriorityTask<P: Preset, W> {
     AggregateAndProof(AggregateAndProofTask<P, W>),
     Attestation(AttestationTask<P, W>),
@@ -147,55 +153,73 @@ pub trait Spawn<P: Preset, E, W> {
 
 impl<P: Preset, E, W> Spawn<P, E, W> for BlockTask<P, E, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.high_priority_tasks.push_back(self.into())
+        critical
+            .high_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for BlobSidecarTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.high_priority_tasks.push_back(self.into())
+        critical
+            .high_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for CheckpointStateTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.high_priority_tasks.push_back(self.into())
+        critical
+            .high_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for PreprocessStateTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.high_priority_tasks.push_back(self.into())
+        critical
+            .high_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for AggregateAndProofTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.low_priority_tasks.push_back(self.into())
+        critical
+            .low_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for AttestationTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.low_priority_tasks.push_back(self.into())
+        critical
+            .low_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for BlockAttestationsTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.low_priority_tasks.push_back(self.into())
+        critical
+            .low_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for AttesterSlashingTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.low_priority_tasks.push_back(self.into())
+        critical
+            .low_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
 impl<P: Preset, E, W> Spawn<P, E, W> for PersistBlobSidecarsTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
-        critical.low_priority_tasks.push_back(self.into())
+        critical
+            .low_priority_tasks
+            .push_back((self.into(), request_tracing::current()))
     }
 }
 
@@ -210,17 +234,17 @@ fn run_worker<P: Preset, E: ExecutionEngine<P> + Send, W>(shared: &Shared<P, E,
                 break 'outer;
             }
 
-            if let Some(task) = critical.high_priority_tasks.pop_front() {
+            if let Some((task, trace)) = critical.high_priority_tasks.pop_front() {
                 drop(critical);
                 debug!("thread {} received high priority task", thread_name());
-                task.run_and_handle_panics();
+                run_traced((&task).into(), task, trace);
                 continue 'outer;
             }
 
-            if let Some(task) = critical.low_priority_tasks.pop_front() {
+            if let Some((task, trace)) = critical.low_priority_tasks.pop_front() {
                 drop(critical);
                 debug!("thread {} received low priority task", thread_name());
-                task.run_and_handle_panics();
+                run_traced((&task).into(), task, trace);
                 continue 'outer;
             }
 
@@ -231,6 +255,12 @@ fn run_worker<P: Preset, E: ExecutionEngine<P> + Send, W>(shared: &Shared<P, E,
     debug!("thread {} stopping", thread_name());
 }
 
+fn run_traced(name: &'static str, task: impl Run, trace: Option<TraceContext>) {
+    request_tracing::resume_sync(trace, || {
+        request_tracing::in_span_sync(name, || task.run_and_handle_panics());
+    });
+}
+
 // Keeping the `Thread` and its name around as locals in `run_worker` seems to add a small amount of
 // overhead. This function lets us keep the logging without penalizing the case when it's disabled.
 fn thread_name() -> String {
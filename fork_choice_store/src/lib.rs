@@ -74,12 +74,13 @@ pub use crate::{
     misc::{
         AggregateAndProofAction, AggregateAndProofOrigin, ApplyBlockChanges, ApplyTickChanges,
         AttestationAction, AttestationOrigin, AttesterSlashingOrigin, BlobSidecarAction,
-        BlobSidecarOrigin, BlockAction, BlockOrigin, ChainLink, PayloadAction, PayloadStatus,
-        ValidAttestation,
+        BlobSidecarOrigin, BlockAction, BlockOrigin, ChainLink, LatestMessage, PayloadAction,
+        PayloadStatus, ValidAttestation,
     },
     segment::Segment,
     store::Store,
     store_config::StoreConfig,
+    validator_registry::{RegisteredValidator, ValidatorRegistry},
 };
 
 mod blob_cache;
@@ -90,3 +91,4 @@ mod state_cache;
 mod store;
 mod store_config;
 mod supersets;
+mod validator_registry;
@@ -358,6 +358,9 @@ pub enum AttestationOrigin<I> {
     GossipBatch(SubnetId, I),
     Own(SubnetId),
     Api(SubnetId, OneshotSender<Result<ValidationOutcome>>),
+    // Attestations submitted through the HTTP API by an authenticated local validator client.
+    // Their signatures were already checked when they were produced.
+    TrustedApi(SubnetId, OneshotSender<Result<ValidationOutcome>>),
     Block,
     // Some test cases in `consensus-spec-tests` contain data that cannot occur in normal operation.
     // `fork_choice` test cases contain bare aggregate attestations.
@@ -370,7 +373,7 @@ impl<I> AttestationOrigin<I> {
     pub fn split(self) -> (Option<I>, Option<OneshotSender<Result<ValidationOutcome>>>) {
         match self {
             Self::Gossip(_, gossip_id) | Self::GossipBatch(_, gossip_id) => (Some(gossip_id), None),
-            Self::Api(_, sender) => (None, Some(sender)),
+            Self::Api(_, sender) | Self::TrustedApi(_, sender) => (None, Some(sender)),
             Self::Own(_) | Self::Block | Self::Test => (None, None),
         }
     }
@@ -381,7 +384,8 @@ impl<I> AttestationOrigin<I> {
             Self::Gossip(subnet_id, _)
             | Self::GossipBatch(subnet_id, _)
             | Self::Own(subnet_id)
-            | Self::Api(subnet_id, _) => Some(subnet_id),
+            | Self::Api(subnet_id, _)
+            | Self::TrustedApi(subnet_id, _) => Some(subnet_id),
             Self::Block | Self::Test => None,
         }
     }
@@ -414,6 +418,7 @@ impl<I> AttestationOrigin<I> {
             | Self::GossipBatch(_, _)
             | Self::Own(_)
             | Self::Api(_, _)
+            | Self::TrustedApi(_, _)
             | Self::Test => true,
             Self::Block => false,
         }
@@ -422,21 +427,28 @@ impl<I> AttestationOrigin<I> {
     #[must_use]
     pub const fn must_be_singular(&self) -> bool {
         match self {
-            Self::Gossip(_, _) | Self::GossipBatch(_, _) | Self::Own(_) | Self::Api(_, _) => true,
+            Self::Gossip(_, _)
+            | Self::GossipBatch(_, _)
+            | Self::Own(_)
+            | Self::Api(_, _)
+            | Self::TrustedApi(_, _) => true,
             Self::Block | Self::Test => false,
         }
     }
 
     #[must_use]
     pub const fn should_generate_event(&self) -> bool {
-        matches!(self, Self::Gossip(_, _) | Self::Api(_, _))
+        matches!(
+            self,
+            Self::Gossip(_, _) | Self::Api(_, _) | Self::TrustedApi(_, _),
+        )
     }
 
     #[must_use]
     pub fn validate_indexed(&self) -> bool {
         match self {
             Self::Gossip(_, _) | Self::Api(_, _) | Self::Test => true,
-            Self::GossipBatch(_, _) | Self::Block => false,
+            Self::GossipBatch(_, _) | Self::TrustedApi(_, _) | Self::Block => false,
             Self::Own(_) => !Feature::TrustOwnAttestationSignatures.is_enabled(),
         }
     }
@@ -444,7 +456,10 @@ impl<I> AttestationOrigin<I> {
     #[must_use]
     pub const fn send_to_validator(&self) -> bool {
         match self {
-            Self::Gossip(_, _) | Self::GossipBatch(_, _) | Self::Api(_, _) => true,
+            Self::Gossip(_, _)
+            | Self::GossipBatch(_, _)
+            | Self::Api(_, _)
+            | Self::TrustedApi(_, _) => true,
             Self::Own(_) | Self::Block | Self::Test => false,
         }
     }
@@ -457,6 +472,7 @@ impl<I> AttestationOrigin<I> {
             Self::GossipBatch(_, _) => "GossipBatch",
             Self::Own(_) => "Own",
             Self::Api(_, _) => "Api",
+            Self::TrustedApi(_, _) => "TrustedApi",
             Self::Block => "Block",
             Self::Test => "Test",
         }
@@ -484,6 +500,7 @@ impl AttesterSlashingOrigin {
 #[derive(Debug)]
 pub enum BlobSidecarOrigin {
     Api,
+    ExecutionLayer,
     Gossip(SubnetId, GossipId),
     Requested(PeerId),
     Own,
@@ -494,7 +511,7 @@ impl BlobSidecarOrigin {
     pub fn gossip_id(self) -> Option<GossipId> {
         match self {
             Self::Gossip(_, gossip_id) => Some(gossip_id),
-            Self::Api | Self::Own | Self::Requested(_) => None,
+            Self::Api | Self::ExecutionLayer | Self::Own | Self::Requested(_) => None,
         }
     }
 
@@ -503,7 +520,7 @@ impl BlobSidecarOrigin {
         match self {
             Self::Gossip(_, gossip_id) => Some(gossip_id.source),
             Self::Requested(peer_id) => Some(*peer_id),
-            Self::Api | Self::Own => None,
+            Self::Api | Self::ExecutionLayer | Self::Own => None,
         }
     }
 
@@ -511,7 +528,7 @@ impl BlobSidecarOrigin {
     pub const fn subnet_id(&self) -> Option<SubnetId> {
         match self {
             Self::Gossip(subnet_id, _) => Some(*subnet_id),
-            Self::Api | Self::Own | Self::Requested(_) => None,
+            Self::Api | Self::ExecutionLayer | Self::Own | Self::Requested(_) => None,
         }
     }
 }
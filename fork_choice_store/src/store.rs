@@ -71,6 +71,7 @@ use crate::{
     state_cache::StateCache,
     store_config::StoreConfig,
     supersets::AggregateAndProofSets as AggregateAndProofSupersets,
+    validator_registry::ValidatorRegistry,
 };
 
 /// [`Store`] from the Fork Choice specification.
@@ -122,6 +123,9 @@ pub struct Store<P: Preset> {
     // The fork choice store only deals with active validator indices, which cannot diverge.
     // Validators can only become eligible for activation after they are finalized.
     latest_messages: Vector<Option<Arc<LatestMessage>>>,
+    // `Store.validator_registry` is updated along with `Store.latest_messages` for the same reason.
+    // Validator indices assigned in unfinalized blocks may differ between forks.
+    validator_registry: ValidatorRegistry,
     // `consensus-specs` doesn't explicitly state it, but `Store.checkpoint_states` is effectively a
     // cache, as its contents can be recomputed at any time using data from other fields.
     //
@@ -269,6 +273,7 @@ impl<P: Preset> Store<P> {
             justified_active_balances: Self::active_balances(&anchor_state),
             timely_proposer_score: OnceLock::new(),
             latest_messages,
+            validator_registry: ValidatorRegistry::new(&anchor_state),
             checkpoint_states: HashMap::unit(checkpoint, anchor_state),
             current_slot_attestations: vector![],
             preprocessed_states: StateCache::default(),
@@ -371,11 +376,27 @@ impl<P: Preset> Store<P> {
         self.proposer_boost_root
     }
 
+    #[must_use]
+    pub fn latest_message(&self, validator_index: ValidatorIndex) -> Option<&LatestMessage> {
+        let index = usize::try_from(validator_index).ok()?;
+        self.latest_messages.get(index)?.as_deref()
+    }
+
+    #[must_use]
+    pub fn is_equivocating(&self, validator_index: ValidatorIndex) -> bool {
+        self.equivocating_indices.contains(&validator_index)
+    }
+
     #[must_use]
     pub const fn finalized(&self) -> &Vector<ChainLink<P>> {
         &self.finalized
     }
 
+    #[must_use]
+    pub const fn validator_registry(&self) -> &ValidatorRegistry {
+        &self.validator_registry
+    }
+
     #[must_use]
     pub const fn unfinalized(&self) -> &OrdMap<SegmentId, Segment<P>> {
         &self.unfinalized
@@ -701,6 +722,14 @@ impl<P: Preset> Store<P> {
             .all(UnfinalizedBlock::is_invalid)
     }
 
+    #[must_use]
+    pub fn invalid_block_count(&self) -> usize {
+        self.unfinalized
+            .values()
+            .map(|segment| segment.len().get() - segment.non_invalid_len())
+            .sum()
+    }
+
     #[must_use]
     pub fn is_segment_viable(&self, segment: &Segment<P>) -> bool {
         segment
@@ -964,6 +993,20 @@ impl<P: Preset> Store<P> {
             return Ok(BlockAction::Ignore);
         }
 
+        // The [Optimistic Sync specification] says:
+        // > When a block transitions from `NOT_VALIDATED` -> `INVALIDATED`,
+        // > all *descendants* of the block MUST also transition from `NOT_VALIDATED` -> `INVALIDATED`.
+        //
+        // Descendants received after the invalidation would end up invalidated as soon as they are
+        // inserted, so there is no point in running the state transition on them.
+        // The [Networking specification] says to [IGNORE] blocks whose parents fail validation.
+        //
+        // [Optimistic Sync specification]: https://github.com/ethereum/consensus-specs/blob/a1e46d1ae47dd9d097725801575b46907c12a1f8/sync/optimistic.md#how-to-optimistically-import-blocks
+        // [Networking specification]:      https://github.com/ethereum/consensus-specs/blob/a1e46d1ae47dd9d097725801575b46907c12a1f8/specs/bellatrix/p2p-interface.md#beacon_block
+        if parent.is_invalid() {
+            return Ok(BlockAction::Ignore);
+        }
+
         // > Make a copy of the state to avoid mutability issues
         let mut state = self
             .preprocessed_states
@@ -1628,7 +1671,7 @@ impl<P: Preset> Store<P> {
 
         // [REJECT] The sidecar is for the correct subnet -- i.e. compute_subnet_for_blob_sidecar(blob_sidecar.index) == subnet_id.
         if let Some(actual) = origin.subnet_id() {
-            let expected = misc::compute_subnet_for_blob_sidecar(blob_sidecar.index);
+            let expected = misc::compute_subnet_for_blob_sidecar(&self.chain_config, &blob_sidecar);
 
             ensure!(
                 actual == expected,
@@ -1826,6 +1869,7 @@ impl<P: Preset> Store<P> {
 
             if finalized_checkpoint_updated {
                 self.extend_latest_messages_after_finalization();
+                self.update_validator_registry_after_finalization();
                 self.prune_after_finalization();
             }
         }
@@ -1930,6 +1974,7 @@ impl<P: Preset> Store<P> {
 
         if finalized_checkpoint_updated {
             self.extend_latest_messages_after_finalization();
+            self.update_validator_registry_after_finalization();
             self.prune_after_finalization();
         }
 
@@ -2392,6 +2437,11 @@ impl<P: Preset> Store<P> {
         self.latest_messages.extend(added_vacancies);
     }
 
+    fn update_validator_registry_after_finalization(&mut self) {
+        let state = self.last_finalized().state(self);
+        self.validator_registry.update(&state);
+    }
+
     fn prune_after_finalization(&mut self) {
         if let Some(partially_finalized_location) = self.finalize_blocks() {
             self.prune_orphans(partially_finalized_location);
@@ -0,0 +1,213 @@
+use bls::PublicKeyBytes;
+use im::{HashMap, Vector};
+use types::{
+    phase0::primitives::{Epoch, ValidatorIndex},
+    preset::Preset,
+    traits::BeaconState,
+};
+
+/// Validator public keys and indices as of the last finalized state.
+///
+/// Validators are never removed from `BeaconState.validators` and never change positions in it,
+/// so a mapping taken from the finalized state is valid in all states the store can contain.
+/// Looking up validators here avoids building the public key map of every state separately.
+///
+/// The registry is updated incrementally. Public keys are only read for validators added since the
+/// previous update.
+#[derive(Clone, Default)]
+pub struct ValidatorRegistry {
+    indices: HashMap<PublicKeyBytes, ValidatorIndex>,
+    validators: Vector<RegisteredValidator>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RegisteredValidator {
+    pub pubkey: PublicKeyBytes,
+    pub activation_epoch: Epoch,
+    pub exit_epoch: Epoch,
+}
+
+impl RegisteredValidator {
+    /// Activation and exit epochs are those from the last finalized state.
+    /// Validators that were not yet scheduled for activation or exit in it are treated as such.
+    #[must_use]
+    pub const fn is_active_at(self, epoch: Epoch) -> bool {
+        self.activation_epoch <= epoch && epoch < self.exit_epoch
+    }
+}
+
+impl ValidatorRegistry {
+    #[must_use]
+    pub fn new<P: Preset>(state: &(impl BeaconState<P> + ?Sized)) -> Self {
+        let mut registry = Self::default();
+        registry.update(state);
+        registry
+    }
+
+    pub fn update<P: Preset>(&mut self, state: &(impl BeaconState<P> + ?Sized)) {
+        let registered_count = self.validators.len();
+
+        // Activation and exit epochs of registered validators may have changed since the last
+        // update. Only replace entries that differ to avoid copying unchanged chunks of the vector.
+        for (position, validator) in state
+            .validators()
+            .into_iter()
+            .take(registered_count)
+            .enumerate()
+        {
+            let registered = self.validators[position];
+
+            if registered.activation_epoch != validator.activation_epoch
+                || registered.exit_epoch != validator.exit_epoch
+            {
+                self.validators.set(
+                    position,
+                    RegisteredValidator {
+                        activation_epoch: validator.activation_epoch,
+                        exit_epoch: validator.exit_epoch,
+                        ..registered
+                    },
+                );
+            }
+        }
+
+        for (validator_index, validator) in (0..).zip(state.validators()).skip(registered_count) {
+            let pubkey = validator.pubkey.to_bytes();
+
+            self.indices.insert(pubkey, validator_index);
+
+            self.validators.push_back(RegisteredValidator {
+                pubkey,
+                activation_epoch: validator.activation_epoch,
+                exit_epoch: validator.exit_epoch,
+            });
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, validator_index: ValidatorIndex) -> Option<RegisteredValidator> {
+        let position = usize::try_from(validator_index).ok()?;
+        self.validators.get(position).copied()
+    }
+
+    /// Looks up the index of the validator with `pubkey` in `state`.
+    ///
+    /// `state` must be either a descendant or an ancestor of the state the registry was last
+    /// updated with. Validators added after it are looked up in `state` directly.
+    #[must_use]
+    pub fn index_of_public_key<P: Preset>(
+        &self,
+        state: &(impl BeaconState<P> + ?Sized),
+        pubkey: PublicKeyBytes,
+    ) -> Option<ValidatorIndex> {
+        let validators = state.validators();
+
+        if let Some(validator_index) = self.indices.get(&pubkey).copied() {
+            return (validator_index < validators.len_u64()).then_some(validator_index);
+        }
+
+        let registered_count = self.validators.len().try_into().ok()?;
+
+        (registered_count..validators.len_u64()).find(|validator_index| {
+            validators
+                .get(*validator_index)
+                .is_ok_and(|validator| validator.pubkey.to_bytes() == pubkey)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz::PersistentList;
+    use try_from_iterator::TryFromIterator as _;
+    use types::{
+        phase0::{
+            beacon_state::BeaconState as Phase0BeaconState, consts::FAR_FUTURE_EPOCH,
+            containers::Validator,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_validator_registry_index_of_public_key() {
+        let finalized_state = state_with_validators(2);
+        let head_state = state_with_validators(4);
+        let old_state = state_with_validators(1);
+        let registry = ValidatorRegistry::new(&finalized_state);
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.index_of_public_key(&head_state, pubkey(1)),
+            Some(1)
+        );
+        assert_eq!(
+            registry.index_of_public_key(&head_state, pubkey(3)),
+            Some(3)
+        );
+        assert_eq!(registry.index_of_public_key(&head_state, pubkey(4)), None);
+        assert_eq!(registry.index_of_public_key(&old_state, pubkey(0)), Some(0));
+        assert_eq!(registry.index_of_public_key(&old_state, pubkey(1)), None);
+    }
+
+    #[test]
+    fn test_validator_registry_update() {
+        let mut state = state_with_validators(2);
+        let mut registry = ValidatorRegistry::new(&state);
+
+        assert!(!registry
+            .get(0)
+            .expect("validator 0 is registered")
+            .is_active_at(1));
+
+        state = state_with_validators(3);
+        state
+            .validators
+            .get_mut(0)
+            .expect("state contains validator 0")
+            .activation_epoch = 1;
+
+        registry.update(&state);
+
+        assert_eq!(registry.len(), 3);
+        assert!(registry
+            .get(0)
+            .expect("validator 0 is registered")
+            .is_active_at(1));
+        assert_eq!(
+            registry.get(2).map(|validator| validator.pubkey),
+            Some(pubkey(2))
+        );
+        assert_eq!(registry.get(3), None);
+    }
+
+    fn state_with_validators(count: u8) -> Phase0BeaconState<Minimal> {
+        let validators = (0..count).map(|index| Validator {
+            pubkey: pubkey(index).into(),
+            activation_epoch: FAR_FUTURE_EPOCH,
+            exit_epoch: FAR_FUTURE_EPOCH,
+            ..Validator::default()
+        });
+
+        Phase0BeaconState {
+            validators: PersistentList::try_from_iter(validators)
+                .expect("number of validators is below the limit"),
+            ..Phase0BeaconState::default()
+        }
+    }
+
+    fn pubkey(index: u8) -> PublicKeyBytes {
+        PublicKeyBytes::repeat_byte(index + 1)
+    }
+}
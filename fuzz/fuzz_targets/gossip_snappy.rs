@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use once_cell::sync::Lazy;
+use types::{config::Config, fuzzing, preset::Mainnet};
+
+static CONFIG: Lazy<Config> = Lazy::new(Config::mainnet);
+
+fuzz_target!(|data: &[u8]| fuzzing::decode_gossip_payload::<Mainnet>(&CONFIG, data));
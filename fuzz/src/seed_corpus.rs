@@ -0,0 +1,93 @@
+//! Fills the corpora of all fuzz targets with messages from `ssz_static` cases in
+//! `consensus-spec-tests`.
+//!
+//! Run from the `fuzz` directory after downloading the spec tests:
+//!
+//! ```text
+//! cargo run --bin seed_corpus
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use log::info;
+use types::{fuzzing, fuzzing::GossipMessageKind, nonstandard::Phase};
+
+const SPEC_TESTS: &str = "../consensus-spec-tests/tests/mainnet/*/ssz_static/*/*/*";
+const SSZ_GOSSIP_CORPUS: &str = "corpus/ssz_gossip";
+const GOSSIP_SNAPPY_CORPUS: &str = "corpus/gossip_snappy";
+const REQ_RESP_SNAPPY_CORPUS: &str = "corpus/req_resp_snappy";
+
+fn main() -> Result<()> {
+    binary_utils::initialize_logger(module_path!(), false, true)?;
+
+    for corpus in [
+        SSZ_GOSSIP_CORPUS,
+        GOSSIP_SNAPPY_CORPUS,
+        REQ_RESP_SNAPPY_CORPUS,
+    ] {
+        fs_err::create_dir_all(corpus)?;
+    }
+
+    let mut seeds = 0;
+
+    for case in glob::glob(SPEC_TESTS)? {
+        let case = case?;
+
+        let Some(kind) = kind_of_case(&case) else {
+            continue;
+        };
+
+        let compressed = fs_err::read(case.join("serialized.ssz_snappy"))?;
+
+        let ssz_bytes = snap::raw::Decoder::new()
+            .decompress_vec(compressed.as_slice())
+            .with_context(|| format!("failed to decompress {case:?}"))?;
+
+        let mut components = case
+            .components()
+            .rev()
+            .take(5)
+            .filter_map(|component| component.as_os_str().to_str())
+            .collect::<Vec<_>>();
+
+        components.reverse();
+
+        let file_name = components.join("_");
+
+        fs_err::write(
+            Path::new(SSZ_GOSSIP_CORPUS).join(&file_name),
+            fuzzing::seed(kind, ssz_bytes.as_slice()),
+        )?;
+
+        fs_err::write(
+            Path::new(GOSSIP_SNAPPY_CORPUS).join(&file_name),
+            fuzzing::seed_gossip_payload(kind, ssz_bytes.as_slice())?,
+        )?;
+
+        fs_err::write(
+            Path::new(REQ_RESP_SNAPPY_CORPUS).join(&file_name),
+            fuzzing::seed_req_resp_payload(kind, ssz_bytes.as_slice())?,
+        )?;
+
+        seeds += 1;
+    }
+
+    info!("wrote {seeds} seeds to each corpus in the corpus directory");
+
+    Ok(())
+}
+
+// Case directories look like `{phase}/ssz_static/{container}/{handler}/{case}`.
+fn kind_of_case(case: &Path) -> Option<GossipMessageKind> {
+    let mut components = case
+        .components()
+        .rev()
+        .filter_map(|component| component.as_os_str().to_str())
+        .skip(2);
+
+    let container = components.next()?;
+    let phase = components.nth(1)?.parse::<Phase>().ok()?;
+
+    GossipMessageKind::of_spec_test(phase, container)
+}
@@ -1,7 +1,11 @@
 use std::path::PathBuf;
 
 use clap::Subcommand;
-use types::phase0::primitives::Slot;
+use fork_choice_control::{ChainDataField, ChainDataFormat};
+use types::{
+    nonstandard::Phase,
+    phase0::primitives::{Epoch, Slot},
+};
 
 #[derive(Clone, Subcommand)]
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
@@ -20,6 +24,73 @@ pub enum GrandineCommand {
         output_dir: Option<PathBuf>,
     },
 
+    /// Export data of finalized blocks for analysis in external tools
+    /// (example: grandine export-chain-data --format csv --output chain.csv)
+    ExportChainData {
+        /// First slot to export (inclusive)
+        #[clap(long, value_name = "SLOT", default_value_t = 0)]
+        from: Slot,
+
+        /// Last slot to export (inclusive, defaults to the latest finalized slot)
+        #[clap(long, value_name = "SLOT")]
+        to: Option<Slot>,
+
+        /// Output file format (csv, or parquet if built with the `parquet` feature)
+        #[clap(long, value_name = "FORMAT", default_value_t = ChainDataFormat::Csv)]
+        format: ChainDataFormat,
+
+        /// Comma-separated list of fields to export (available: slot, block_root, parent_root,
+        /// proposer, attestation_count, deposit_count, blob_count, graffiti)
+        #[clap(
+            long,
+            value_name = "FIELDS",
+            value_delimiter = ',',
+            default_value = "slot,block_root,proposer,attestation_count,blob_count,graffiti"
+        )]
+        fields: Vec<ChainDataField>,
+
+        /// Output file
+        #[clap(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Export an anchor block and state to ssz files for bootstrapping another node
+    /// (example: grandine export-state --slot 8192 --path anchor)
+    ExportState {
+        /// Slot of the anchor. Must be at the start of an epoch
+        /// (defaults to the latest finalized checkpoint)
+        #[clap(long, value_name = "SLOT")]
+        slot: Option<Slot>,
+
+        /// Output directory
+        #[clap(long, value_name = "DIR")]
+        path: PathBuf,
+    },
+
+    /// Export finalized blocks and states to era files for distributing history
+    /// (example: grandine export-era --start-epoch 0 --end-epoch 1024 --path era)
+    ExportEra {
+        /// Export eras ending in this epoch or later
+        #[clap(long, value_name = "EPOCH")]
+        start_epoch: Epoch,
+
+        /// Export eras ending in this epoch or earlier
+        #[clap(long, value_name = "EPOCH")]
+        end_epoch: Epoch,
+
+        /// Output directory
+        #[clap(long, value_name = "DIR")]
+        path: PathBuf,
+    },
+
+    /// Bootstrap an empty database from files written by export-state
+    /// (example: grandine import-state --path anchor)
+    ImportState {
+        /// Directory containing the exported anchor
+        #[clap(long, value_name = "DIR")]
+        path: PathBuf,
+    },
+
     /// Replay blocks within slot range
     /// (example: grandine replay --from 0 --to 5)
     Replay {
@@ -36,10 +107,59 @@ pub enum GrandineCommand {
         input_dir: Option<PathBuf>,
     },
 
+    /// Prune archival states and blocks older than a number of epochs before the finalized epoch
+    /// (example: grandine prune --retain-epochs 10000 --dry-run)
+    Prune {
+        /// Number of epochs before the latest finalized epoch to retain data for
+        /// (defaults to retaining everything)
+        #[clap(long, value_name = "EPOCHS")]
+        retain_epochs: Option<u64>,
+
+        /// Prune finalized blocks as well as archival states
+        #[clap(long)]
+        include_blocks: bool,
+
+        /// Report what would be pruned without deleting anything
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Allow pruning data within the weak subjectivity period.
+        /// Nodes syncing from this one may be unable to do so afterwards.
+        #[clap(long)]
+        i_know_what_i_am_doing: bool,
+    },
+
+    /// Upgrade a state to the next phase and process slots on top of it to rehearse a fork
+    /// (example: grandine test-fork-upgrade --state state.ssz --to deneb)
+    TestForkUpgrade {
+        /// SSZ file of a state in the phase preceding the target phase
+        #[clap(long, value_name = "FILE")]
+        state: PathBuf,
+
+        /// Phase to upgrade the state to
+        #[clap(long, value_name = "PHASE")]
+        to: Phase,
+
+        /// Number of slots to process after the upgrade
+        #[clap(long, value_name = "SLOTS", default_value_t = 3)]
+        slots: u64,
+    },
+
     /// Import/export slashing protection interchange file
     /// (example: grandine interchange import file.json)
     #[clap(subcommand)]
     Interchange(InterchangeCommand),
+
+    /// Inspect the beacon node database
+    /// (example: grandine db verify)
+    #[clap(subcommand)]
+    Db(DbCommand),
+
+    /// Check the environment for problems that would prevent the beacon node from running well:
+    /// directory permissions, free disk space, port availability, execution client connectivity
+    /// and JWT validity, clock accuracy, open files limit and CPU features
+    /// (example: grandine doctor)
+    Doctor,
 }
 
 #[derive(Clone, Subcommand)]
@@ -52,3 +172,22 @@ pub enum InterchangeCommand {
     /// (example: grandine interchange export file.json)
     Export { file_path: PathBuf },
 }
+
+#[derive(Clone, Subcommand)]
+#[cfg_attr(test, derive(PartialEq, Eq, Debug))]
+pub enum DbCommand {
+    /// Check that all entries can be decoded, that indices are consistent and that finalized blocks
+    /// link to each other
+    /// (example: grandine db verify --repair)
+    Verify {
+        /// Fix issues that only affect indices. Blocks, states and blob sidecars are never modified
+        #[clap(long)]
+        repair: bool,
+    },
+    /// Print entry counts, sizes and slot ranges by key prefix without modifying the database
+    /// (example: grandine db inspect)
+    Inspect,
+    /// Return space freed by pruning to the operating system. The beacon node must not be running
+    /// (example: grandine db compact)
+    Compact,
+}
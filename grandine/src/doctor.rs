@@ -0,0 +1,545 @@
+use core::time::Duration;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Result};
+use bytesize::ByteSize;
+use chrono::{DateTime, Utc};
+use eth1_api::{Auth, AuthOptions};
+use log::{info, warn};
+use reqwest::{header::DATE, Client, StatusCode, Url};
+use serde_json::json;
+use strum::Display;
+use sysinfo::Disks;
+use thiserror::Error;
+
+use crate::grandine_config::GrandineConfig;
+
+// The `Date` header only has a resolution of one second.
+const MAX_CLOCK_OFFSET: Duration = Duration::from_secs(2);
+const MIN_OPEN_FILES_LIMIT: u64 = 4096;
+const PROBE_FILE_NAME: &str = ".grandine_doctor";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display)]
+#[strum(serialize_all = "UPPERCASE")]
+enum Outcome {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    outcome: Outcome,
+    details: String,
+    hint: Option<&'static str>,
+}
+
+impl Check {
+    const fn pass(name: &'static str, details: String) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Pass,
+            details,
+            hint: None,
+        }
+    }
+
+    const fn skip(name: &'static str, details: String) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Skip,
+            details,
+            hint: None,
+        }
+    }
+
+    const fn warn(name: &'static str, details: String, hint: &'static str) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Warn,
+            details,
+            hint: Some(hint),
+        }
+    }
+
+    const fn fail(name: &'static str, details: String, hint: &'static str) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Fail,
+            details,
+            hint: Some(hint),
+        }
+    }
+
+    fn log(&self) {
+        let Self {
+            name,
+            outcome,
+            details,
+            hint,
+        } = self;
+
+        match outcome {
+            Outcome::Pass | Outcome::Skip => info!("[{outcome}] {name}: {details}"),
+            Outcome::Warn | Outcome::Fail => warn!("[{outcome}] {name}: {details}"),
+        }
+
+        if let Some(hint) = hint {
+            info!("    hint: {hint}");
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("{failed} preflight checks failed")]
+struct PreflightChecksFailed {
+    failed: usize,
+}
+
+/// Checks that the environment is suitable for running the beacon node with `config` and
+/// prints a report. Fails if any check fails.
+///
+/// Nothing is left in the data directory. Writability is checked with a file that is removed
+/// right after it is created.
+pub async fn run(config: &GrandineConfig) -> Result<()> {
+    let mut checks = directory_checks(config);
+
+    checks.push(disk_space_check(config));
+    checks.push(ports_check(config));
+    checks.extend(execution_client_checks(config).await);
+    checks.push(open_files_limit_check());
+    checks.push(cpu_features_check());
+
+    for check in &checks {
+        check.log();
+    }
+
+    let count = |outcome| {
+        checks
+            .iter()
+            .filter(|check| check.outcome == outcome)
+            .count()
+    };
+
+    let failed = count(Outcome::Fail);
+
+    info!(
+        "preflight checks finished (passed: {}, warnings: {}, failed: {failed}, skipped: {})",
+        count(Outcome::Pass),
+        count(Outcome::Warn),
+        count(Outcome::Skip),
+    );
+
+    ensure!(failed == 0, PreflightChecksFailed { failed });
+
+    Ok(())
+}
+
+// Directories are created on startup, so ones that do not exist yet only need writable ancestors.
+fn directory_checks(config: &GrandineConfig) -> Vec<Check> {
+    const NAME: &str = "directory permissions";
+    const HINT: &str = "make the directory writable by the user running Grandine \
+                        or choose a different one with --data-dir";
+
+    if config.in_memory {
+        return vec![Check::skip(NAME, "databases are kept in memory".to_owned())];
+    }
+
+    database_directories(config)
+        .into_keys()
+        .map(|directory| {
+            let Some(existing) = nearest_existing_ancestor(&directory) else {
+                return Check::fail(NAME, format!("{directory:?} cannot be created"), HINT);
+            };
+
+            let probe_path = existing.join(PROBE_FILE_NAME);
+
+            match fs_err::write(&probe_path, b"").and_then(|()| fs_err::remove_file(&probe_path)) {
+                Ok(()) if existing == directory => {
+                    Check::pass(NAME, format!("{directory:?} is writable"))
+                }
+                Ok(()) => Check::pass(
+                    NAME,
+                    format!("{directory:?} will be created in writable directory {existing:?}"),
+                ),
+                Err(error) => {
+                    Check::fail(NAME, format!("cannot write to {existing:?}: {error}"), HINT)
+                }
+            }
+        })
+        .collect()
+}
+
+// MDBX databases grow up to their configured sizes, so those are upper bounds of disk usage.
+fn disk_space_check(config: &GrandineConfig) -> Check {
+    const NAME: &str = "free disk space";
+    const HINT: &str = "free up disk space, move databases to a larger volume \
+                        with --hot-directory and --cold-directory \
+                        or lower --database-size";
+
+    if config.in_memory {
+        return Check::skip(NAME, "databases are kept in memory".to_owned());
+    }
+
+    let disks = Disks::new_with_refreshed_list();
+    let mut required_by_mount_point = BTreeMap::<&Path, (u64, u64)>::new();
+
+    for (directory, size) in database_directories(config) {
+        let Some(existing) = nearest_existing_ancestor(&directory) else {
+            continue;
+        };
+
+        let Ok(existing) = existing.canonicalize() else {
+            continue;
+        };
+
+        let disk = disks
+            .list()
+            .iter()
+            .filter(|disk| existing.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len());
+
+        let Some(disk) = disk else {
+            continue;
+        };
+
+        let (required, _) = required_by_mount_point
+            .entry(disk.mount_point())
+            .or_insert((0, disk.available_space()));
+
+        *required += size;
+    }
+
+    if required_by_mount_point.is_empty() {
+        return Check::skip(NAME, "no disks found for database directories".to_owned());
+    }
+
+    let insufficient = required_by_mount_point
+        .iter()
+        .filter(|(_, (required, available))| available < required)
+        .map(|(mount_point, (required, available))| {
+            format!(
+                "{mount_point:?} has {} available but databases on it may grow up to {}",
+                ByteSize::b(*available),
+                ByteSize::b(*required),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if insufficient.is_empty() {
+        return Check::pass(
+            NAME,
+            "enough space for configured database sizes".to_owned(),
+        );
+    }
+
+    Check::warn(NAME, insufficient.join("; "), HINT)
+}
+
+fn ports_check(config: &GrandineConfig) -> Check {
+    const NAME: &str = "port availability";
+    const HINT: &str = "stop the process using the port or choose a different one";
+
+    let result = crate::ensure_ports_not_in_use(
+        config.http_api_config.address,
+        &config.network_config,
+        config.metrics_config.metrics_server_config.as_ref(),
+    );
+
+    match result {
+        Ok(()) => Check::pass(NAME, "all configured ports are free".to_owned()),
+        Err(error) => Check::fail(NAME, format!("{error:#}"), HINT),
+    }
+}
+
+// The clock is compared with the `Date` header of the first execution client that responds.
+async fn execution_client_checks(config: &GrandineConfig) -> Vec<Check> {
+    const NAME: &str = "execution client";
+    const CLOCK: &str = "system clock";
+    const JWT_HINT: &str =
+        "pass the JWT secret file used by the execution client with --jwt-secret";
+    const REACHABILITY_HINT: &str = "make sure the execution client is running and its \
+                                     Engine API is reachable at the URL passed to --eth1-rpc-urls";
+    const CLOCK_HINT: &str = "synchronize the system clock using NTP \
+                              (e.g., with chrony or systemd-timesyncd)";
+
+    if config.eth1_rpc_urls.is_empty() {
+        return vec![
+            Check::skip(NAME, "no execution client configured".to_owned()),
+            Check::skip(CLOCK, "no execution client to compare with".to_owned()),
+        ];
+    }
+
+    let AuthOptions {
+        secrets_path,
+        id,
+        version,
+    } = &config.auth_options;
+
+    let auth = Auth::new(AuthOptions {
+        secrets_path: secrets_path.clone(),
+        id: id.clone(),
+        version: version.clone(),
+    });
+
+    let auth = match auth {
+        Ok(auth) => auth,
+        Err(error) => {
+            return vec![
+                Check::fail(
+                    NAME,
+                    format!("JWT secret cannot be loaded: {error:#}"),
+                    JWT_HINT,
+                ),
+                Check::skip(CLOCK, "no execution client to compare with".to_owned()),
+            ]
+        }
+    };
+
+    let client = match Client::builder().timeout(config.request_timeout).build() {
+        Ok(client) => client,
+        Err(error) => {
+            return vec![
+                Check::fail(
+                    NAME,
+                    format!("HTTP client cannot be built: {error}"),
+                    REACHABILITY_HINT,
+                ),
+                Check::skip(CLOCK, "no execution client to compare with".to_owned()),
+            ]
+        }
+    };
+
+    let mut checks = vec![];
+    let mut clock_check = None;
+
+    for url in &config.eth1_rpc_urls {
+        match query_execution_client(&client, &auth, url).await {
+            Ok((status, _))
+                if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN =>
+            {
+                checks.push(Check::fail(
+                    NAME,
+                    format!("{url} rejected the JWT ({status})"),
+                    JWT_HINT,
+                ));
+            }
+            Ok((status, _)) if !status.is_success() => {
+                checks.push(Check::fail(
+                    NAME,
+                    format!("{url} responded with {status}"),
+                    REACHABILITY_HINT,
+                ));
+            }
+            Ok((_, date)) => {
+                checks.push(Check::pass(NAME, format!("{url} accepted the JWT")));
+
+                if clock_check.is_none() {
+                    clock_check =
+                        date.map(|date| clock_check_against(url, date, CLOCK, CLOCK_HINT));
+                }
+            }
+            Err(error) => {
+                checks.push(Check::fail(
+                    NAME,
+                    format!("{url} is unreachable: {error:#}"),
+                    REACHABILITY_HINT,
+                ));
+            }
+        }
+    }
+
+    checks.push(
+        clock_check.unwrap_or_else(|| {
+            Check::skip(CLOCK, "no execution client reported its time".to_owned())
+        }),
+    );
+
+    checks
+}
+
+// `engine_exchangeCapabilities` requires authentication but has no side effects.
+async fn query_execution_client(
+    client: &Client,
+    auth: &Auth,
+    url: &Url,
+) -> Result<(StatusCode, Option<DateTime<Utc>>)> {
+    let mut request = client.post(url.clone()).json(&json!({
+        "jsonrpc": "2.0",
+        "method": "engine_exchangeCapabilities",
+        "params": [[]],
+        "id": 1,
+    }));
+
+    if let Some(headers) = auth.headers()? {
+        request = request.headers(headers);
+    }
+
+    let response = request.send().await?;
+
+    let date = response
+        .headers()
+        .get(DATE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .map(|date| date.with_timezone(&Utc));
+
+    Ok((response.status(), date))
+}
+
+fn clock_check_against(
+    url: &Url,
+    remote_time: DateTime<Utc>,
+    name: &'static str,
+    hint: &'static str,
+) -> Check {
+    let offset = Utc::now().signed_duration_since(remote_time);
+    let offset_millis = offset.num_milliseconds().unsigned_abs();
+    let details = format!("clock differs from {url} by {offset_millis} ms");
+
+    if u128::from(offset_millis) > MAX_CLOCK_OFFSET.as_millis() {
+        return Check::fail(name, details, hint);
+    }
+
+    Check::pass(name, details)
+}
+
+fn open_files_limit_check() -> Check {
+    const NAME: &str = "open files limit";
+    const HINT: &str = "raise the limit with `ulimit -n 65536` \
+                        or `LimitNOFILE=65536` in the systemd unit";
+
+    if !cfg!(target_os = "linux") {
+        return Check::skip(NAME, "only checked on Linux".to_owned());
+    }
+
+    // The format is documented in `proc(5)`.
+    let soft_limit = fs_err::read_to_string("/proc/self/limits")
+        .ok()
+        .and_then(|limits| {
+            limits
+                .lines()
+                .find_map(|line| line.strip_prefix("Max open files"))
+                .and_then(|values| values.split_whitespace().next())
+                .map(ToOwned::to_owned)
+        });
+
+    match soft_limit.as_deref() {
+        Some("unlimited") => Check::pass(NAME, "unlimited".to_owned()),
+        Some(limit) => match limit.parse::<u64>() {
+            Ok(limit) if limit >= MIN_OPEN_FILES_LIMIT => Check::pass(NAME, limit.to_string()),
+            Ok(limit) => Check::warn(
+                NAME,
+                format!("{limit} is lower than the recommended {MIN_OPEN_FILES_LIMIT}"),
+                HINT,
+            ),
+            Err(_) => Check::skip(NAME, format!("cannot parse limit {limit:?}")),
+        },
+        None => Check::skip(NAME, "cannot read /proc/self/limits".to_owned()),
+    }
+}
+
+fn cpu_features_check() -> Check {
+    const NAME: &str = "CPU features";
+    const HINT: &str = "signature verification and hashing will be slower on this CPU";
+
+    let missing = missing_cpu_features();
+
+    if missing.is_empty() {
+        return Check::pass(
+            NAME,
+            format!("using {} SHA-256 backend", hashing::Backend::selected()),
+        );
+    }
+
+    Check::warn(NAME, format!("missing {}", missing.join(", ")), HINT)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn missing_cpu_features() -> Vec<&'static str> {
+    let mut missing = vec![];
+
+    // BLST uses ADX and BMI2 for faster field arithmetic.
+    if !std::is_x86_feature_detected!("adx") {
+        missing.push("adx");
+    }
+
+    if !std::is_x86_feature_detected!("bmi2") {
+        missing.push("bmi2");
+    }
+
+    if !std::is_x86_feature_detected!("sha") {
+        missing.push("sha");
+    }
+
+    missing
+}
+
+#[cfg(target_arch = "aarch64")]
+fn missing_cpu_features() -> Vec<&'static str> {
+    let mut missing = vec![];
+
+    if !std::arch::is_aarch64_feature_detected!("sha2") {
+        missing.push("sha2");
+    }
+
+    missing
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const fn missing_cpu_features() -> Vec<&'static str> {
+    vec![]
+}
+
+// Returns directories databases will be stored in along with the sizes they may grow up to.
+fn database_directories(config: &GrandineConfig) -> BTreeMap<PathBuf, u64> {
+    let storage_config = &config.storage_config;
+
+    let store_directory = storage_config
+        .directories
+        .store_directory
+        .clone()
+        .unwrap_or_default();
+
+    let mut directories = BTreeMap::new();
+
+    *directories.entry(store_directory).or_default() += storage_config.eth1_db_size.as_u64();
+    *directories
+        .entry(storage_config.hot_directory())
+        .or_default() += storage_config.db_size.as_u64();
+
+    if let Some(cold_dir) = &storage_config.cold_dir {
+        *directories.entry(cold_dir.clone()).or_default() += storage_config.db_size.as_u64();
+    }
+
+    directories
+}
+
+fn nearest_existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_existing_ancestor_skips_missing_directories() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let missing = directory.path().join("a").join("b");
+
+        assert_eq!(
+            nearest_existing_ancestor(&missing),
+            Some(directory.path().to_path_buf()),
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use core::{
     fmt::Display,
     num::{NonZeroU16, NonZeroU64},
-    ops::Not as _,
+    ops::{Not as _, Range},
     time::Duration,
 };
 use std::{
@@ -17,6 +17,7 @@ use builder_api::{
 };
 use bytesize::ByteSize;
 use clap::{error::ErrorKind, Args, CommandFactory as _, Error as ClapError, Parser, ValueEnum};
+use database::{Codec, DatabaseBackend, DurabilityLevel};
 use derive_more::Display;
 use directories::Directories;
 use educe::Educe;
@@ -27,18 +28,19 @@ use features::Feature;
 use fork_choice_control::DEFAULT_ARCHIVAL_EPOCH_INTERVAL;
 use fork_choice_store::StoreConfig;
 use grandine_version::{APPLICATION_NAME, APPLICATION_VERSION};
-use http_api::HttpApiConfig;
+use http_api::{HttpApiConfig, TlsConfig};
 use itertools::{EitherOrBoth, Itertools as _};
 use log::warn;
 use metrics::{MetricsServerConfig, MetricsServiceConfig};
-use p2p::{Enr, Multiaddr, NetworkConfig};
+use p2p::{Enr, GossipSlotTolerance, Multiaddr, NetworkConfig};
 use prometheus_metrics::Metrics;
 use reqwest::{header::HeaderValue, Url};
 use runtime::{
     MetricsConfig, StorageConfig, DEFAULT_ETH1_DB_SIZE, DEFAULT_ETH2_DB_SIZE,
+    DEFAULT_GOSSIP_FUTURE_SLOT_TOLERANCE, DEFAULT_GOSSIP_PAST_SLOT_TOLERANCE,
     DEFAULT_LIBP2P_IPV4_PORT, DEFAULT_LIBP2P_IPV6_PORT, DEFAULT_LIBP2P_QUIC_IPV4_PORT,
     DEFAULT_LIBP2P_QUIC_IPV6_PORT, DEFAULT_METRICS_PORT, DEFAULT_REQUEST_TIMEOUT,
-    DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
+    DEFAULT_STORAGE_READ_CACHE_SIZE, DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
@@ -53,7 +55,8 @@ use types::{
     config::Config as ChainConfig,
     nonstandard::Phase,
     phase0::primitives::{
-        Epoch, ExecutionAddress, ExecutionBlockHash, ExecutionBlockNumber, Slot, H256,
+        Epoch, ExecutionAddress, ExecutionBlockHash, ExecutionBlockNumber, Slot, ValidatorIndex,
+        H256,
     },
     preset::PresetName,
 };
@@ -67,6 +70,7 @@ use crate::{
     consts::GRANDINE_DONATION_ADDRESS,
     grandine_config::GrandineConfig,
     predefined_network::PredefinedNetwork,
+    profile::Profile,
     validators::Validators,
 };
 
@@ -102,6 +106,11 @@ pub struct GrandineArgs {
     #[clap(long)]
     features: Vec<Feature>,
 
+    /// Set of features and default settings to use.
+    /// Options passed explicitly take precedence over settings from the profile
+    #[clap(long, value_enum)]
+    profile: Option<Profile>,
+
     #[clap(subcommand)]
     command: Option<GrandineCommand>,
 }
@@ -186,7 +195,38 @@ struct HttpApiOptions {
 
     /// HTTP API timeout in milliseconds
     #[clap(long, default_value_t = HttpApiOptions::default_timeout())]
-    timeout: u64,
+    timeout: NonZeroU64,
+
+    /// PEM file with the TLS certificate chain for the HTTP API server.
+    /// Enables HTTPS. The certificate is reloaded when the file changes.
+    #[clap(long, value_name = "PEM_FILE", requires = "http_tls_private_key")]
+    http_tls_certificate: Option<PathBuf>,
+
+    /// PEM file with the PKCS #8 private key of the HTTP API server TLS certificate
+    #[clap(long, value_name = "PEM_FILE", requires = "http_tls_certificate")]
+    http_tls_private_key: Option<PathBuf>,
+
+    /// File with a hex-encoded BLS secret key used to sign statements about the head of the chain.
+    /// Enables GET /grandine/v1/node/head_statement. Must not be a validator key.
+    #[clap(long, value_name = "KEY_FILE")]
+    head_statement_key_file: Option<PathBuf>,
+
+    /// File with a token shared with validator clients running on the same machine.
+    /// Attestations submitted over a loopback interface with the token in an
+    /// `Authorization: Bearer` header skip signature verification.
+    #[clap(long, value_name = "TOKEN_FILE")]
+    http_trusted_client_token_file: Option<PathBuf>,
+
+    /// Log HTTP API requests that take at least this many milliseconds
+    /// along with the time spent in each step of handling them
+    #[clap(long, value_name = "MILLISECONDS")]
+    http_slow_request_threshold: Option<u64>,
+
+    /// Directory to write backups requested with POST /grandine/v1/backup to.
+    /// Enables the endpoint together with the `ServeEffectfulEndpoints` feature.
+    /// Each backup is written to a new subdirectory.
+    #[clap(long, value_name = "DIRECTORY")]
+    http_backup_directory: Option<PathBuf>,
 }
 
 impl From<HttpApiOptions> for HttpApiConfig {
@@ -197,14 +237,33 @@ impl From<HttpApiOptions> for HttpApiConfig {
             http_allowed_origins,
             max_events,
             timeout,
+            http_tls_certificate,
+            http_tls_private_key,
+            head_statement_key_file,
+            http_trusted_client_token_file,
+            http_slow_request_threshold,
+            http_backup_directory,
         } = http_api_options;
 
         let mut http_api_config = Self {
+            backup_directory: http_backup_directory,
+            head_statement_key_file,
             max_events,
-            timeout: Some(Duration::from_millis(timeout)),
+            trusted_client_token_file: http_trusted_client_token_file,
+            slow_request_threshold: http_slow_request_threshold.map(Duration::from_millis),
+            timeout: Some(Duration::from_millis(timeout.get())),
             ..Self::with_address(http_address, http_port)
         };
 
+        if let Some((certificate_file, private_key_file)) =
+            http_tls_certificate.zip(http_tls_private_key)
+        {
+            http_api_config = http_api_config.with_tls(TlsConfig {
+                certificate_file,
+                private_key_file,
+            });
+        }
+
         if !http_allowed_origins.is_empty() {
             // `tower_http::cors::AllowOrigin::list` panics if a wildcard is passed to it.
             if http_allowed_origins.contains(&HeaderValue::from_static("*")) {
@@ -228,11 +287,13 @@ impl From<HttpApiOptions> for HttpApiConfig {
 impl HttpApiOptions {
     // `Duration::as_millis` returns `u128`. See <https://github.com/rust-lang/rust/issues/58580>.
     // `#[clap(value_parser = …)]` cannot be used because `Duration` does not implement `Display`.
-    fn default_timeout() -> u64 {
+    fn default_timeout() -> NonZeroU64 {
         DEFAULT_TIMEOUT
             .as_millis()
             .try_into()
-            .expect("default timeout in milliseconds should fit in u64")
+            .ok()
+            .and_then(NonZeroU64::new)
+            .expect("default timeout in milliseconds should fit in u64 and be nonzero")
     }
 }
 
@@ -240,23 +301,47 @@ impl HttpApiOptions {
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Args)]
 struct BeaconNodeOptions {
-    #[clap(long, default_value_t = ValidatorConfig::default().max_empty_slots)]
-    max_empty_slots: u64,
+    /// [default: 32 or value from --profile]
+    #[clap(long)]
+    max_empty_slots: Option<u64>,
 
     /// Beacon node API URL to load recent finalized checkpoint and sync from it
     /// [default: None]
     #[clap(long)]
     checkpoint_sync_url: Option<Url>,
 
-    /// Force checkpoint sync. Requires --checkpoint-sync-url
+    /// Force checkpoint sync even if the database is not empty.
+    /// Requires --checkpoint-sync-url or --checkpoint-state
     /// [default: disabled]
-    #[clap(long, requires = "checkpoint_sync_url")]
+    #[clap(long)]
     force_checkpoint_sync: bool,
 
+    /// Load anchor state for checkpoint sync from a local SSZ file instead of a remote beacon node.
+    /// The state must be the post-state of --checkpoint-block at the start of an epoch.
+    /// Only used if the database is empty unless --force-checkpoint-sync is passed
+    /// [default: None]
+    #[clap(
+        long,
+        requires = "checkpoint_block",
+        conflicts_with = "checkpoint_sync_url"
+    )]
+    checkpoint_state: Option<PathBuf>,
+
+    /// Load anchor block for checkpoint sync from a local SSZ file. Requires --checkpoint-state
+    /// [default: None]
+    #[clap(long, requires = "checkpoint_state")]
+    checkpoint_block: Option<PathBuf>,
+
     /// List of Eth1 RPC URLs
     #[clap(long, num_args = 1..)]
     eth1_rpc_urls: Vec<Url>,
 
+    /// Append Engine API requests and responses to a file for replay debugging.
+    /// JWTs are redacted.
+    /// [default: disabled]
+    #[clap(long)]
+    eth1_api_recording_file: Option<PathBuf>,
+
     /// Parent directory for application data files
     /// [default: $HOME/.grandine/{network}]
     #[clap(long)]
@@ -272,6 +357,62 @@ struct BeaconNodeOptions {
     #[clap(long)]
     network_dir: Option<PathBuf>,
 
+    /// Directory to store unfinalized blocks, indices, checkpoints and auxiliary databases in
+    /// [default: {store_directory}]
+    #[clap(long)]
+    hot_directory: Option<PathBuf>,
+
+    /// Directory to store finalized blocks and archival states in separately from other data.
+    /// Allows keeping recent data on fast storage and finalized history on slower storage.
+    /// Blocks are moved here when they become finalized.
+    /// [default: {hot_directory}]
+    #[clap(long, alias = "archive-directory", conflicts_with = "prune_storage")]
+    cold_directory: Option<PathBuf>,
+
+    /// Directory with era files to serve finalized blocks from when they are not in the database.
+    /// Lets checkpoint-synced nodes serve blocks from before the checkpoint without back-syncing.
+    #[clap(long, value_name = "DIRECTORY")]
+    era_directory: Option<PathBuf>,
+
+    /// Storage engine for the Eth2 database
+    /// (`mdbx`, or `rocksdb` if built with the `rocksdb` feature).
+    /// Existing databases are not converted between engines.
+    #[clap(long, default_value_t = DatabaseBackend::default())]
+    database_backend: DatabaseBackend,
+
+    /// How often writes to the Eth2 database are flushed to disk
+    /// (`always`, `every-batch` or `async`). Weaker levels are faster but may lose the latest
+    /// writes on power loss. Checkpoints the node restarts from are always flushed.
+    /// Defaults to `always` for MDBX and `async` for RocksDB.
+    #[clap(long)]
+    database_durability: Option<DurabilityLevel>,
+
+    /// Compression applied to values stored in the Eth2 database (`snappy` or `zstd`).
+    /// Existing entries stay readable after changing it.
+    #[clap(long, default_value_t = Codec::default())]
+    storage_compression: Codec,
+
+    /// Max total size of finalized blocks and stored states kept in memory after being read from
+    /// the Eth2 database. Speeds up repeated HTTP API queries for the same blocks and states.
+    /// Disabled by default.
+    #[clap(long, default_value_t = DEFAULT_STORAGE_READ_CACHE_SIZE)]
+    storage_read_cache_size: ByteSize,
+
+    /// Store execution payloads of finalized blocks separately from the blocks.
+    /// Lets blinded blocks be served without rebuilding them and lets payloads be pruned
+    /// independently, either through the HTTP API or with --execution-payload-retention-epochs.
+    /// Blocks stored before enabling it are not converted.
+    /// [default: disabled]
+    #[clap(long, conflicts_with = "prune_storage")]
+    split_execution_payloads: bool,
+
+    /// Number of epochs before the latest finalized epoch to keep execution payloads for.
+    /// Older payloads are deleted once per epoch while blinded blocks are kept.
+    /// Must be at least MIN_EPOCHS_FOR_BLOCK_REQUESTS.
+    /// Requires --split-execution-payloads. [default: keep all payloads]
+    #[clap(long, value_name = "EPOCHS", requires = "split_execution_payloads")]
+    execution_payload_retention_epochs: Option<u64>,
+
     #[clap(long, default_value_t = DEFAULT_ARCHIVAL_EPOCH_INTERVAL)]
     archival_epoch_interval: NonZeroU64,
 
@@ -280,9 +421,29 @@ struct BeaconNodeOptions {
     #[clap(long)]
     prune_storage: bool,
 
+    /// Delete finalized states and blocks older than EPOCHS epochs before the latest finalized
+    /// epoch. Other nodes will not be able to sync the deleted history from this node.
+    /// [default: disabled]
+    #[clap(long, value_name = "EPOCHS", conflicts_with = "prune_storage")]
+    prune_history_epochs: Option<u64>,
+
+    /// Store archival states in full only every EPOCHS epochs and store the ones in between
+    /// as differences from them. Must be a multiple of --archival-epoch-interval.
+    /// Reduces disk usage of archive nodes at the cost of slower historical state reads.
+    /// [default: disabled]
+    #[clap(long, value_name = "EPOCHS", conflicts_with = "prune_storage")]
+    archival_snapshot_interval: Option<NonZeroU64>,
+
+    /// Keep blob sidecars for EPOCHS epochs instead of MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS.
+    /// Must not be less than MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS.
+    /// [default: MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS]
+    #[clap(long, value_name = "EPOCHS")]
+    blob_retention_epochs: Option<u64>,
+
     /// Number of unfinalized states to keep in memory.
-    #[clap(long, default_value_t = StoreConfig::default().unfinalized_states_in_memory)]
-    unfinalized_states_in_memory: u64,
+    /// [default: 128 or value from --profile]
+    #[clap(long)]
+    unfinalized_states_in_memory: Option<u64>,
 
     /// Max size of the Eth2 database
     #[clap(long, default_value_t = DEFAULT_ETH2_DB_SIZE)]
@@ -294,7 +455,7 @@ struct BeaconNodeOptions {
 
     /// Default global request timeout for various services in milliseconds
     #[clap(long, default_value_t = DEFAULT_REQUEST_TIMEOUT)]
-    request_timeout: u64,
+    request_timeout: NonZeroU64,
 
     /// State slot
     /// [default: None]
@@ -354,6 +515,31 @@ struct BeaconNodeOptions {
     #[clap(long)]
     track_liveness: bool,
 
+    /// Number of slots the head may go without advancing while peers report newer heads
+    /// before attempting to recover block sync and the execution client connection.
+    /// The application exits if recovery fails.
+    /// [default: disabled]
+    #[clap(long)]
+    head_watchdog_slots: Option<NonZeroU64>,
+
+    /// Number of slots ahead of the current slot that gossip objects may be from.
+    /// Objects further in the future are ignored without verification.
+    #[clap(long, default_value_t = DEFAULT_GOSSIP_FUTURE_SLOT_TOLERANCE)]
+    gossip_future_slot_tolerance: u64,
+
+    /// Number of slots behind the current slot that gossip attestations, aggregates
+    /// and sync committee messages may be from.
+    /// Older objects are ignored without verification.
+    #[clap(long, default_value_t = DEFAULT_GOSSIP_PAST_SLOT_TOLERANCE)]
+    gossip_past_slot_tolerance: u64,
+
+    /// Follow the chain and serve read-only HTTP API endpoints only.
+    /// Requests that submit objects or change node configuration are rejected.
+    /// Validator keys are not allowed in this mode.
+    /// [default: disabled]
+    #[clap(long)]
+    read_only: bool,
+
     /// Enable in-memory mode.
     /// No data will be stored in data-dir.
     /// [default: disabled]
@@ -462,8 +648,9 @@ struct NetworkConfigOptions {
     libp2p_nodes: Vec<Multiaddr>,
 
     /// Target number of network peers
-    #[clap(long, default_value_t = DEFAULT_TARGET_PEERS)]
-    target_peers: usize,
+    /// [default: 100 or value from --profile]
+    #[clap(long)]
+    target_peers: Option<usize>,
 
     /// List of trusted peers
     #[clap(long)]
@@ -517,7 +704,7 @@ impl NetworkConfigOptions {
         network_config.upnp_enabled = !disable_upnp;
         network_config.network_dir = in_memory.not().then_some(network_dir);
         network_config.metrics_enabled = metrics;
-        network_config.target_peers = target_peers;
+        network_config.target_peers = target_peers.unwrap_or(DEFAULT_TARGET_PEERS);
         network_config.trusted_peers = trusted_peers;
 
         if let Some(listen_address_ipv6) = listen_address_ipv6 {
@@ -669,19 +856,21 @@ struct ValidatorOptions {
     #[clap(long)]
     builder_api_url: Option<Url>,
 
-    /// External block builder URL
-    #[clap(long)]
-    builder_url: Option<Url>,
+    /// List of external block builder (relay) URLs
+    #[clap(long, num_args = 1..)]
+    builder_url: Vec<Url>,
 
     /// Always use specified external block builder without checking for circuit breaker conditions
     #[clap(long)]
     builder_disable_checks: bool,
 
-    /// Max allowed consecutive missing blocks to trigger circuit breaker condition and switch to local execution engine for payload construction
+    /// Max allowed consecutive missing blocks to trigger circuit breaker condition and switch to local execution engine for payload construction.
+    /// Must not exceed --builder-max-skipped-slots-per-epoch
     #[clap(long, default_value_t = DEFAULT_BUILDER_MAX_SKIPPED_SLOTS)]
     builder_max_skipped_slots: u64,
 
-    /// Max allowed missing blocks in the last rolling epoch to trigger circuit breaker condition and switch to local execution engine for payload construction
+    /// Max allowed missing blocks in the last rolling epoch to trigger circuit breaker condition and switch to local execution engine for payload construction.
+    /// Must be less than SLOTS_PER_EPOCH
     #[clap(long, default_value_t = DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH)]
     builder_max_skipped_slots_per_epoch: u64,
 
@@ -701,9 +890,38 @@ struct ValidatorOptions {
     #[clap(long)]
     use_validator_key_cache: bool,
 
+    /// Range of validator indices to derive insecure interop keys for (for example, `0..1024`).
+    /// Meant for development networks only
+    #[clap(long, value_parser = parse_validator_indices, conflicts_with("keystore_dir"))]
+    interop_validators: Option<Range<ValidatorIndex>>,
+
     /// Number of epochs to keep slashing protection data for
     #[clap(long, default_value_t = DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT)]
     slashing_protection_history_limit: u64,
+
+    /// Track validator duties without signing until activated through the HTTP API
+    /// or by the primary beacon node missing heartbeats
+    #[clap(long)]
+    standby: bool,
+
+    /// Beacon node API URL of the primary node in an active/passive setup
+    /// [default: None]
+    #[clap(long, requires("standby"))]
+    primary_beacon_node_url: Option<Url>,
+
+    /// Number of consecutive missed primary node heartbeats (checked once per slot) after which standby is activated
+    #[clap(long, default_value_t = ValidatorConfig::default().primary_missed_heartbeat_limit)]
+    primary_missed_heartbeat_limit: NonZeroU64,
+
+    /// Start even if the slashing protection database shows that validator keys
+    /// have signed messages in epochs later than the chain head
+    #[clap(long)]
+    skip_slashing_protection_check: bool,
+
+    /// Send `balance_deltas` events with the attestation, sync committee and slashing deltas of
+    /// own validators after every epoch. Costs an extra epoch transition per epoch
+    #[clap(long)]
+    balance_delta_events: bool,
 }
 
 #[derive(Clone, Copy, Sequence, ValueEnum)]
@@ -778,6 +996,7 @@ impl GrandineArgs {
             validator_options,
             graffiti,
             mut features,
+            profile,
             command,
             ..
         } = self;
@@ -803,14 +1022,29 @@ impl GrandineArgs {
             max_empty_slots,
             checkpoint_sync_url,
             eth1_rpc_urls,
+            eth1_api_recording_file,
             force_checkpoint_sync,
+            checkpoint_state,
+            checkpoint_block,
             data_dir,
             store_directory,
             network_dir,
+            hot_directory,
+            cold_directory,
+            era_directory,
+            database_backend,
+            database_durability,
+            storage_compression,
+            storage_read_cache_size,
+            split_execution_payloads,
+            execution_payload_retention_epochs,
             database_size,
             eth1_database_size,
             archival_epoch_interval,
             prune_storage,
+            prune_history_epochs,
+            archival_snapshot_interval,
+            blob_retention_epochs,
             unfinalized_states_in_memory,
             request_timeout,
             state_slot,
@@ -826,6 +1060,10 @@ impl GrandineArgs {
             metrics_port,
             remote_metrics_url,
             track_liveness,
+            head_watchdog_slots,
+            gossip_future_slot_tolerance,
+            gossip_past_slot_tolerance,
+            read_only,
             in_memory,
         } = beacon_node_options;
 
@@ -848,10 +1086,16 @@ impl GrandineArgs {
             builder_max_skipped_slots,
             builder_max_skipped_slots_per_epoch,
             use_validator_key_cache,
+            interop_validators,
             web3signer_public_keys,
             web3signer_api_urls,
             web3signer_urls,
             slashing_protection_history_limit,
+            standby,
+            primary_beacon_node_url,
+            primary_missed_heartbeat_limit,
+            skip_slashing_protection_check,
+            balance_delta_events,
         } = validator_options;
 
         if in_memory {
@@ -921,6 +1165,22 @@ impl GrandineArgs {
 
         let predefined_network = network.predefined_network();
 
+        let max_empty_slots = max_empty_slots
+            .or_else(|| profile.and_then(Profile::max_empty_slots))
+            .unwrap_or(ValidatorConfig::default().max_empty_slots);
+
+        let unfinalized_states_in_memory = unfinalized_states_in_memory
+            .or_else(|| profile.and_then(Profile::unfinalized_states_in_memory))
+            .unwrap_or(StoreConfig::default().unfinalized_states_in_memory);
+
+        network_config_options.target_peers = network_config_options
+            .target_peers
+            .or_else(|| profile.and_then(Profile::target_peers));
+
+        if let Some(profile) = profile {
+            features.extend(profile.features());
+        }
+
         if predefined_network.is_none() && eth1_rpc_urls.is_empty() {
             ensure!(
                 genesis_state_file.is_some(),
@@ -1032,11 +1292,15 @@ impl GrandineArgs {
         let metrics_server_config = metrics.then_some(MetricsServerConfig {
             metrics_address,
             metrics_port,
-            timeout: request_timeout,
+            timeout: request_timeout.get(),
             directories: directories.clone_arc(),
         });
 
-        let http_api_config = HttpApiConfig::from(http_api_options);
+        let http_api_config = HttpApiConfig {
+            read_only,
+            ..HttpApiConfig::from(http_api_options)
+        };
+
         if let Some(metrics_server_config) = metrics_server_config.as_ref() {
             ensure!(
                 http_api_config.address != metrics_server_config.into(),
@@ -1059,15 +1323,31 @@ impl GrandineArgs {
             metrics_service_config,
         };
 
-        let validators = keystore_dir
-            .zip(keystore_password_file.or(keystore_password_dir))
-            .map(
-                |(keystore_dir, keystore_password_file)| Validators::KeystoreDirectory {
-                    keystore_dir,
-                    keystore_password_file,
-                },
-            )
-            .unwrap_or_default();
+        let validators = match interop_validators {
+            Some(validator_indices) => Validators::Interop { validator_indices },
+            None => keystore_dir
+                .zip(keystore_password_file.or(keystore_password_dir))
+                .map(
+                    |(keystore_dir, keystore_password_file)| Validators::KeystoreDirectory {
+                        keystore_dir,
+                        keystore_password_file,
+                    },
+                )
+                .unwrap_or_default(),
+        };
+
+        // `clap` cannot check this. `requires` only accepts a single argument.
+        ensure!(
+            !force_checkpoint_sync || checkpoint_sync_url.is_some() || checkpoint_state.is_some(),
+            Error::ForceCheckpointSyncWithoutCheckpoint,
+        );
+
+        // Trusting objects produced by the node itself is only safe on development networks.
+        ensure!(
+            profile != Some(Profile::DevnetFast)
+                || chain_config.config_name != ChainConfig::mainnet().config_name,
+            Error::DevnetProfileOnMainnet,
+        );
 
         let minimum = StoreConfig::min_unfinalized_states_in_memory(&chain_config);
 
@@ -1076,6 +1356,48 @@ impl GrandineArgs {
             Error::UnfinalizedStatesInMemoryTooLow { minimum },
         );
 
+        if let Some(snapshot_interval) = archival_snapshot_interval {
+            ensure!(
+                snapshot_interval.get() % archival_epoch_interval == 0,
+                Error::ArchivalSnapshotIntervalNotMultiple {
+                    snapshot_interval,
+                    archival_epoch_interval,
+                },
+            );
+        }
+
+        if let Some(retention_epochs) = blob_retention_epochs {
+            let minimum = chain_config.min_epochs_for_blob_sidecars_requests;
+
+            ensure!(
+                retention_epochs >= minimum,
+                Error::BlobRetentionEpochsTooLow { minimum },
+            );
+        }
+
+        // Blocks must be served to peers for at least `MIN_EPOCHS_FOR_BLOCK_REQUESTS` epochs.
+        let prune_history_epochs = prune_history_epochs.map(|retain_epochs| {
+            let minimum = chain_config.min_epochs_for_block_requests();
+
+            if retain_epochs < minimum {
+                warn!(
+                    "--prune-history-epochs {retain_epochs} is below MIN_EPOCHS_FOR_BLOCK_REQUESTS; \
+                     retaining {minimum} epochs instead",
+                );
+            }
+
+            retain_epochs.max(minimum)
+        });
+
+        if let Some(retention_epochs) = execution_payload_retention_epochs {
+            let minimum = chain_config.min_epochs_for_block_requests();
+
+            ensure!(
+                retention_epochs >= minimum,
+                Error::ExecutionPayloadRetentionEpochsTooLow { minimum },
+            );
+        }
+
         let features = features
             .into_iter()
             .chain(disable_block_verification_pool.then_some(Feature::DisableBlockVerificationPool))
@@ -1089,15 +1411,25 @@ impl GrandineArgs {
             version: jwt_version,
         };
 
-        let builder_url = if builder_url.is_none() && builder_api_url.is_some() {
+        let builder_urls = if builder_url.is_empty() && builder_api_url.is_some() {
             warn!("--builder-api-url option is deprecated. Use --builder-url instead.");
-            builder_api_url
+            builder_api_url.into_iter().collect()
         } else {
             builder_url
         };
 
-        let builder_config = builder_url.map(|url| BuilderConfig {
-            builder_api_url: url,
+        // The limits have no effect without a builder,
+        // so configurations that do not use one should keep working whatever they are set to.
+        if !builder_urls.is_empty() {
+            validate_builder_skipped_slots(
+                &chain_config,
+                builder_max_skipped_slots,
+                builder_max_skipped_slots_per_epoch,
+            )?;
+        }
+
+        let builder_config = (!builder_urls.is_empty()).then(|| BuilderConfig {
+            builder_api_urls: builder_urls,
             builder_disable_checks,
             builder_max_skipped_slots,
             builder_max_skipped_slots_per_epoch,
@@ -1122,6 +1454,20 @@ impl GrandineArgs {
             eth1_db_size: eth1_database_size,
             archival_epoch_interval,
             prune_storage,
+            prune_history_epochs,
+            archival_snapshot_interval,
+            blob_retention_epochs,
+            hot_dir: hot_directory,
+            cold_dir: cold_directory,
+            era_directory,
+            database_backend,
+            durability: database_durability
+                .unwrap_or_else(|| database_backend.default_durability()),
+            compression: storage_compression,
+            read_cache_size: storage_read_cache_size,
+            split_execution_payloads,
+            execution_payload_retention_epochs,
+            previous_schema_version: None,
         };
 
         network_config_options.print_upnp_warning();
@@ -1133,8 +1479,11 @@ impl GrandineArgs {
             genesis_state_file,
             checkpoint_sync_url,
             force_checkpoint_sync,
+            checkpoint_state_file: checkpoint_state,
+            checkpoint_block_file: checkpoint_block,
             back_sync,
             eth1_rpc_urls,
+            eth1_api_recording_file,
             data_dir: directories.data_dir.clone().unwrap_or_default(),
             validators,
             keystore_storage_password_file,
@@ -1149,11 +1498,12 @@ impl GrandineArgs {
             ),
             storage_config,
             unfinalized_states_in_memory,
-            request_timeout: Duration::from_millis(request_timeout),
+            request_timeout: Duration::from_millis(request_timeout.get()),
             command,
             slashing_enabled,
             slashing_history_limit,
             features,
+            profile,
             state_slot,
             auth_options,
             builder_config,
@@ -1161,8 +1511,18 @@ impl GrandineArgs {
             http_api_config,
             metrics_config,
             track_liveness,
+            head_watchdog_slots,
+            gossip_slot_tolerance: GossipSlotTolerance {
+                future_slots: gossip_future_slot_tolerance,
+                past_slots: gossip_past_slot_tolerance,
+            },
             use_validator_key_cache,
             slashing_protection_history_limit,
+            standby,
+            primary_beacon_node_url,
+            primary_missed_heartbeat_limit,
+            skip_slashing_protection_check,
+            balance_delta_events,
             in_memory,
         })
     }
@@ -1183,8 +1543,14 @@ struct Difference {
 
 #[derive(Debug, Error)]
 enum Error {
+    #[error("--force-checkpoint-sync requires --checkpoint-sync-url or --checkpoint-state")]
+    ForceCheckpointSyncWithoutCheckpoint,
+    #[error("--profile devnet-fast cannot be used with the mainnet configuration")]
+    DevnetProfileOnMainnet,
     #[error("graffiti must be no longer than {} bytes", H256::len_bytes())]
     GraffitiTooLong,
+    #[error("validator index range must be in the form start..end and not be empty")]
+    InvalidValidatorIndexRange,
     // `clap` cannot check this. `clap::builder::PossibleValue` does not have a `requires` method.
     #[error("--configuration-file must be specified when connecting to custom network")]
     MissingConfigurationFileForCustom,
@@ -1207,10 +1573,69 @@ enum Error {
         differences.iter().format(", "),
     )]
     ConfigMismatch { differences: Vec<Difference> },
+    #[error(
+        "--builder-max-skipped-slots ({consecutive}) must not exceed \
+         --builder-max-skipped-slots-per-epoch ({per_epoch})"
+    )]
+    BuilderMaxSkippedSlotsExceedsPerEpoch { consecutive: u64, per_epoch: u64 },
+    #[error(
+        "--builder-max-skipped-slots-per-epoch ({per_epoch}) must be less than \
+         SLOTS_PER_EPOCH ({slots_per_epoch})"
+    )]
+    BuilderMaxSkippedSlotsPerEpochTooHigh {
+        per_epoch: u64,
+        slots_per_epoch: NonZeroU64,
+    },
     #[error("--unfinalized-states-in-memory must be at least {minimum}")]
     UnfinalizedStatesInMemoryTooLow { minimum: u64 },
     #[error("identical addresses specified for metrics server and HTTP API server")]
     IdenticalHttpApiAndMetricsUrl,
+    #[error(
+        "--archival-snapshot-interval ({snapshot_interval}) must be a multiple of \
+         --archival-epoch-interval ({archival_epoch_interval})"
+    )]
+    ArchivalSnapshotIntervalNotMultiple {
+        snapshot_interval: NonZeroU64,
+        archival_epoch_interval: NonZeroU64,
+    },
+    #[error(
+        "--blob-retention-epochs must be at least \
+         MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS ({minimum})"
+    )]
+    BlobRetentionEpochsTooLow { minimum: u64 },
+    #[error(
+        "--execution-payload-retention-epochs must be at least \
+         MIN_EPOCHS_FOR_BLOCK_REQUESTS ({minimum})"
+    )]
+    ExecutionPayloadRetentionEpochsTooLow { minimum: u64 },
+}
+
+// The circuit breaker counts consecutive missed slots toward the rolling epoch total.
+// Limits that can never be reached would silently have no effect.
+fn validate_builder_skipped_slots(
+    chain_config: &ChainConfig,
+    consecutive: u64,
+    per_epoch: u64,
+) -> Result<()> {
+    let slots_per_epoch = chain_config.preset_base.phase0_preset().slots_per_epoch();
+
+    ensure!(
+        per_epoch < slots_per_epoch.get(),
+        Error::BuilderMaxSkippedSlotsPerEpochTooHigh {
+            per_epoch,
+            slots_per_epoch,
+        },
+    );
+
+    ensure!(
+        consecutive <= per_epoch,
+        Error::BuilderMaxSkippedSlotsExceedsPerEpoch {
+            consecutive,
+            per_epoch,
+        },
+    );
+
+    Ok(())
 }
 
 fn parse_graffiti(string: &str) -> Result<H256> {
@@ -1222,6 +1647,21 @@ fn parse_graffiti(string: &str) -> Result<H256> {
     Ok(graffiti)
 }
 
+fn parse_validator_indices(string: &str) -> Result<Range<ValidatorIndex>> {
+    let (start, end) = string
+        .split_once("..")
+        .ok_or(Error::InvalidValidatorIndexRange)?;
+
+    let validator_indices = start.parse()?..end.parse()?;
+
+    ensure!(
+        !validator_indices.is_empty(),
+        Error::InvalidValidatorIndexRange,
+    );
+
+    Ok(validator_indices)
+}
+
 fn verify_preset<T: DeserializeOwned + Serialize>(
     chain_config: &ChainConfig,
     preset: &T,
@@ -1323,9 +1763,11 @@ fn compare_with_file<T: DeserializeOwned + Serialize>(
 mod tests {
     use std::net::{Ipv4Addr, SocketAddr};
 
+    use fork_choice_control::{ChainDataField, ChainDataFormat};
     use tempfile::NamedTempFile;
+    use test_case::test_case;
 
-    use crate::commands::InterchangeCommand;
+    use crate::commands::{DbCommand, InterchangeCommand};
 
     use super::*;
 
@@ -1403,6 +1845,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eth1_api_recording_file_option() {
+        assert_eq!(config_from_args([]).eth1_api_recording_file, None);
+
+        let config = config_from_args(["--eth1-api-recording-file", "engine_api.jsonl"]);
+
+        assert_eq!(
+            config.eth1_api_recording_file,
+            Some(PathBuf::from("engine_api.jsonl")),
+        );
+    }
+
     #[test]
     fn eth1_rpc_urls_value_delimiter_not_allowed() {
         try_config_from_args([
@@ -1440,6 +1894,354 @@ mod tests {
         );
     }
 
+    #[test]
+    fn archive_directory_option() {
+        assert_eq!(config_from_args([]).storage_config.cold_dir, None);
+
+        let config = config_from_args(["--archive-directory", "/mnt/hdd/grandine"]);
+
+        assert_eq!(
+            config.storage_config.cold_dir,
+            Some(PathBuf::from("/mnt/hdd/grandine")),
+        );
+    }
+
+    #[test]
+    fn hot_and_cold_directory_options() {
+        let config = config_from_args([]);
+
+        assert_eq!(config.storage_config.hot_dir, None);
+        assert_eq!(
+            config.storage_config.hot_directory(),
+            config
+                .storage_config
+                .directories
+                .store_directory
+                .clone()
+                .unwrap_or_default(),
+        );
+
+        let config = config_from_args([
+            "--hot-directory",
+            "/mnt/nvme/grandine",
+            "--cold-directory",
+            "/mnt/hdd/grandine",
+        ]);
+
+        assert_eq!(
+            config.storage_config.hot_directory(),
+            PathBuf::from("/mnt/nvme/grandine"),
+        );
+        assert_eq!(
+            config.storage_config.cold_dir,
+            Some(PathBuf::from("/mnt/hdd/grandine")),
+        );
+    }
+
+    #[test]
+    fn era_directory_option() {
+        assert_eq!(config_from_args([]).storage_config.era_directory, None);
+
+        let config = config_from_args(["--era-directory", "/mnt/hdd/era"]);
+
+        assert_eq!(
+            config.storage_config.era_directory,
+            Some(PathBuf::from("/mnt/hdd/era")),
+        );
+    }
+
+    #[test]
+    fn database_backend_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.database_backend,
+            DatabaseBackend::Mdbx,
+        );
+
+        let config = config_from_args(["--database-backend", "rocksdb"]);
+
+        assert_eq!(
+            config.storage_config.database_backend,
+            DatabaseBackend::RocksDb
+        );
+    }
+
+    #[test]
+    fn storage_compression_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.compression,
+            Codec::Snappy
+        );
+
+        let config = config_from_args(["--storage-compression", "zstd"]);
+
+        assert_eq!(config.storage_config.compression, Codec::Zstd);
+    }
+
+    #[test]
+    fn storage_read_cache_size_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.read_cache_size,
+            DEFAULT_STORAGE_READ_CACHE_SIZE,
+        );
+
+        let config = config_from_args(["--storage-read-cache-size", "64MiB"]);
+
+        assert_eq!(config.storage_config.read_cache_size, ByteSize::mib(64));
+    }
+
+    #[test]
+    fn database_durability_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.durability,
+            DurabilityLevel::Always,
+        );
+
+        let config = config_from_args(["--database-durability", "every-batch"]);
+
+        assert_eq!(
+            config.storage_config.durability,
+            DurabilityLevel::EveryBatch,
+        );
+
+        let config = config_from_args(["--database-backend", "rocksdb"]);
+
+        assert_eq!(config.storage_config.durability, DurabilityLevel::Async);
+    }
+
+    #[test]
+    fn split_execution_payloads_option() {
+        assert!(!config_from_args([]).storage_config.split_execution_payloads);
+
+        let config = config_from_args(["--split-execution-payloads"]);
+
+        assert!(config.storage_config.split_execution_payloads);
+
+        try_config_from_args(["--split-execution-payloads", "--prune-storage"])
+            .expect_err("--split-execution-payloads should conflict with --prune-storage");
+    }
+
+    #[test]
+    fn execution_payload_retention_epochs_option() {
+        assert_eq!(
+            config_from_args([])
+                .storage_config
+                .execution_payload_retention_epochs,
+            None,
+        );
+
+        let config = config_from_args([
+            "--split-execution-payloads",
+            "--execution-payload-retention-epochs",
+            "40000",
+        ]);
+
+        assert_eq!(
+            config.storage_config.execution_payload_retention_epochs,
+            Some(40000),
+        );
+
+        try_config_from_args(["--execution-payload-retention-epochs", "40000"]).expect_err(
+            "--execution-payload-retention-epochs should require --split-execution-payloads",
+        );
+
+        try_config_from_args([
+            "--split-execution-payloads",
+            "--execution-payload-retention-epochs",
+            "256",
+        ])
+        .expect_err(
+            "--execution-payload-retention-epochs should not be less than the spec minimum",
+        );
+    }
+
+    #[test]
+    fn profile_option() {
+        let config = config_from_args([]);
+
+        assert_eq!(config.profile, None);
+        assert_eq!(config.max_empty_slots, 32);
+        assert_eq!(config.unfinalized_states_in_memory, 128);
+        assert_eq!(config.network_config.target_peers, DEFAULT_TARGET_PEERS);
+
+        let config = config_from_args([
+            "--network",
+            "holesky",
+            "--profile",
+            "devnet-fast",
+            "--features",
+            "DebugP2p",
+        ]);
+
+        assert_eq!(config.profile, Some(Profile::DevnetFast));
+        assert_eq!(config.max_empty_slots, 128);
+        assert_eq!(config.unfinalized_states_in_memory, 256);
+        assert_eq!(config.network_config.target_peers, 16);
+        assert!(config.features.contains(&Feature::DebugP2p));
+        assert!(config.features.contains(&Feature::TrustOwnBlockSignatures));
+    }
+
+    #[test]
+    fn options_take_precedence_over_profile() {
+        let config = config_from_args([
+            "--network",
+            "holesky",
+            "--profile",
+            "devnet-fast",
+            "--target-peers",
+            "50",
+            "--unfinalized-states-in-memory",
+            "64",
+        ]);
+
+        assert_eq!(config.max_empty_slots, 128);
+        assert_eq!(config.unfinalized_states_in_memory, 64);
+        assert_eq!(config.network_config.target_peers, 50);
+    }
+
+    #[test]
+    fn devnet_profile_is_rejected_on_mainnet() {
+        try_config_from_args(["--profile", "devnet-fast"])
+            .expect_err("--profile devnet-fast should be rejected on mainnet");
+
+        try_config_from_args(["--profile", "devnet-fast", "--network", "mainnet"])
+            .expect_err("--profile devnet-fast should be rejected on mainnet");
+
+        let config = config_from_args(["--profile", "testnet-experimental"]);
+
+        assert_eq!(config.profile, Some(Profile::TestnetExperimental));
+    }
+
+    #[test]
+    fn archive_directory_conflicts_with_prune_storage() {
+        try_config_from_args([
+            "--archive-directory",
+            "/mnt/hdd/grandine",
+            "--prune-storage",
+        ])
+        .expect_err("--archive-directory should conflict with --prune-storage");
+    }
+
+    #[test]
+    fn prune_history_epochs_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.prune_history_epochs,
+            None
+        );
+
+        let config = config_from_args(["--prune-history-epochs", "40000"]);
+
+        assert_eq!(config.storage_config.prune_history_epochs, Some(40000));
+    }
+
+    #[test]
+    fn prune_history_epochs_is_clamped_to_min_epochs_for_block_requests() {
+        let config = config_from_args(["--prune-history-epochs", "8192"]);
+
+        assert_eq!(
+            config.storage_config.prune_history_epochs,
+            Some(ChainConfig::mainnet().min_epochs_for_block_requests()),
+        );
+    }
+
+    #[test]
+    fn prune_history_epochs_conflicts_with_prune_storage() {
+        try_config_from_args(["--prune-history-epochs", "8192", "--prune-storage"])
+            .expect_err("--prune-history-epochs should conflict with --prune-storage");
+    }
+
+    #[test]
+    fn archival_snapshot_interval_option() {
+        assert_eq!(
+            config_from_args([])
+                .storage_config
+                .archival_snapshot_interval,
+            None,
+        );
+
+        let config = config_from_args(["--archival-snapshot-interval", "1024"]);
+
+        assert_eq!(
+            config.storage_config.archival_snapshot_interval,
+            NonZeroU64::new(1024),
+        );
+
+        try_config_from_args(["--archival-snapshot-interval", "1000"])
+            .expect_err("--archival-snapshot-interval should be a multiple of archival interval");
+    }
+
+    #[test]
+    fn blob_retention_epochs_option() {
+        assert_eq!(
+            config_from_args([]).storage_config.blob_retention_epochs,
+            None,
+        );
+
+        let config = config_from_args(["--blob-retention-epochs", "100000"]);
+
+        assert_eq!(config.storage_config.blob_retention_epochs, Some(100_000));
+
+        try_config_from_args(["--blob-retention-epochs", "10"])
+            .expect_err("--blob-retention-epochs should not be less than the spec minimum");
+    }
+
+    #[test]
+    fn checkpoint_state_and_block_options() {
+        let config = config_from_args([
+            "--checkpoint-state",
+            "state.ssz",
+            "--checkpoint-block",
+            "block.ssz",
+        ]);
+
+        assert_eq!(
+            config.checkpoint_state_file,
+            Some(PathBuf::from("state.ssz")),
+        );
+
+        assert_eq!(
+            config.checkpoint_block_file,
+            Some(PathBuf::from("block.ssz")),
+        );
+    }
+
+    #[test]
+    fn checkpoint_state_requires_checkpoint_block() {
+        try_config_from_args(["--checkpoint-state", "state.ssz"])
+            .expect_err("--checkpoint-state should require --checkpoint-block");
+
+        try_config_from_args(["--checkpoint-block", "block.ssz"])
+            .expect_err("--checkpoint-block should require --checkpoint-state");
+    }
+
+    #[test]
+    fn force_checkpoint_sync_requires_checkpoint() {
+        try_config_from_args(["--force-checkpoint-sync"])
+            .expect_err("--force-checkpoint-sync should require a checkpoint");
+
+        let config = config_from_args([
+            "--force-checkpoint-sync",
+            "--checkpoint-state",
+            "state.ssz",
+            "--checkpoint-block",
+            "block.ssz",
+        ]);
+
+        assert!(config.force_checkpoint_sync);
+    }
+
+    #[test]
+    fn checkpoint_state_conflicts_with_checkpoint_sync_url() {
+        try_config_from_args([
+            "--checkpoint-state",
+            "state.ssz",
+            "--checkpoint-block",
+            "block.ssz",
+            "--checkpoint-sync-url",
+            "http://localhost:5052",
+        ])
+        .expect_err("--checkpoint-state should conflict with --checkpoint-sync-url");
+    }
+
     #[test]
     fn default_network() {
         assert_eq!(
@@ -1469,6 +2271,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn http_tls_options() {
+        let config = config_from_args([
+            "--http-tls-certificate",
+            "certificate.pem",
+            "--http-tls-private-key",
+            "private_key.pem",
+        ]);
+
+        assert_eq!(
+            config.http_api_config.tls,
+            Some(TlsConfig {
+                certificate_file: PathBuf::from("certificate.pem"),
+                private_key_file: PathBuf::from("private_key.pem"),
+            }),
+        );
+
+        // `Debug` is the only way to inspect the contents of `AllowOrigin`.
+        assert_eq!(
+            format!("{:?}", config.http_api_config.allow_origin),
+            "List([\"https://127.0.0.1:5052\"])",
+        );
+    }
+
+    #[test]
+    fn head_statement_key_file_option() {
+        let config = config_from_args(["--head-statement-key-file", "head_statement.key"]);
+
+        assert_eq!(
+            config.http_api_config.head_statement_key_file,
+            Some(PathBuf::from("head_statement.key")),
+        );
+    }
+
+    #[test]
+    fn read_only_option() {
+        assert!(!config_from_args([]).http_api_config.read_only);
+        assert!(config_from_args(["--read-only"]).http_api_config.read_only);
+    }
+
+    #[test]
+    fn http_trusted_client_token_file_option() {
+        let config = config_from_args(["--http-trusted-client-token-file", "token.txt"]);
+
+        assert_eq!(
+            config.http_api_config.trusted_client_token_file,
+            Some(PathBuf::from("token.txt")),
+        );
+    }
+
+    #[test]
+    fn http_backup_directory_option() {
+        assert_eq!(config_from_args([]).http_api_config.backup_directory, None);
+
+        let config = config_from_args(["--http-backup-directory", "backups"]);
+
+        assert_eq!(
+            config.http_api_config.backup_directory,
+            Some(PathBuf::from("backups")),
+        );
+    }
+
+    #[test]
+    fn http_slow_request_threshold_option() {
+        let config = config_from_args(["--http-slow-request-threshold", "500"]);
+
+        assert_eq!(
+            config.http_api_config.slow_request_threshold,
+            Some(Duration::from_millis(500)),
+        );
+    }
+
+    #[test]
+    fn http_tls_certificate_requires_private_key() {
+        assert!(try_config_from_args(["--http-tls-certificate", "certificate.pem"]).is_err());
+    }
+
     #[test]
     fn http_allowed_origins_option_single_occurence() {
         let config = config_from_args(["--http-allowed-origins", "*"]);
@@ -1550,6 +2429,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validators_from_interop_validators() {
+        let config = config_from_args(["--interop-validators", "16..64"]);
+
+        assert_eq!(
+            config.validators,
+            Validators::Interop {
+                validator_indices: 16..64,
+            },
+        );
+    }
+
+    #[test_case("16"; "missing end")]
+    #[test_case("64..16"; "empty range")]
+    #[test_case("0..=16"; "inclusive range")]
+    fn validators_from_invalid_interop_validators(range: &str) {
+        try_config_from_args(["--interop-validators", range])
+            .expect_err("invalid validator index ranges should be rejected");
+    }
+
+    #[test]
+    fn validators_from_interop_validators_and_keystore_dir() {
+        try_config_from_args([
+            "--interop-validators",
+            "0..16",
+            "--keystore-dir",
+            "dir_value",
+            "--keystore-password-file",
+            "pass_file",
+        ])
+        .expect_err("passing both --interop-validators and --keystore-dir should fail");
+    }
+
     #[test]
     fn validators_from_keystore_password_dir_and_file() {
         try_config_from_args([
@@ -1651,6 +2563,81 @@ mod tests {
         .expect_err("parse_graffiti should fail");
     }
 
+    #[test]
+    fn standby_options() {
+        let config = config_from_args([
+            "--standby",
+            "--primary-beacon-node-url",
+            "http://localhost:5052",
+        ]);
+
+        assert!(config.standby);
+        assert_eq!(
+            config.primary_beacon_node_url.as_ref().map(Url::as_str),
+            Some("http://localhost:5052/"),
+        );
+        assert_eq!(
+            config.primary_missed_heartbeat_limit,
+            ValidatorConfig::default().primary_missed_heartbeat_limit,
+        );
+    }
+
+    #[test]
+    fn skip_slashing_protection_check_option() {
+        let config = config_from_args(["--skip-slashing-protection-check"]);
+
+        assert!(config.skip_slashing_protection_check);
+    }
+
+    #[test]
+    fn balance_delta_events_option() {
+        assert!(!config_from_args([]).balance_delta_events);
+        assert!(config_from_args(["--balance-delta-events"]).balance_delta_events);
+    }
+
+    #[test]
+    fn primary_beacon_node_url_requires_standby() {
+        try_config_from_args(["--primary-beacon-node-url", "http://localhost:5052"])
+            .expect_err("--primary-beacon-node-url should require --standby");
+    }
+
+    #[test_case("--timeout", "0")]
+    #[test_case("--request-timeout", "0")]
+    #[test_case("--primary-missed-heartbeat-limit", "0")]
+    fn out_of_range_values_are_rejected(option: &str, value: &str) {
+        try_config_from_args([option, value]).expect_err("value should be rejected");
+    }
+
+    #[test_case("--builder-max-skipped-slots-per-epoch", "32")]
+    #[test_case("--builder-max-skipped-slots", "6")]
+    fn out_of_range_builder_skipped_slots_are_rejected_with_builder(option: &str, value: &str) {
+        try_config_from_args(["--builder-url", "http://localhost:18550", option, value])
+            .expect_err("value should be rejected when a builder is configured");
+
+        let config = config_from_args([option, value]);
+
+        assert!(config.builder_config.is_none());
+    }
+
+    #[test]
+    fn builder_skipped_slots_below_slots_per_epoch_are_accepted() {
+        let config = config_from_args([
+            "--builder-url",
+            "http://localhost:18550",
+            "--builder-max-skipped-slots",
+            "31",
+            "--builder-max-skipped-slots-per-epoch",
+            "31",
+        ]);
+
+        let builder_config = config
+            .builder_config
+            .expect("builder should be configured when --builder-url is passed");
+
+        assert_eq!(builder_config.builder_max_skipped_slots, 31);
+        assert_eq!(builder_config.builder_max_skipped_slots_per_epoch, 31);
+    }
+
     #[test]
     fn interchange_import_subcommand() {
         let config = config_from_args(["interchange", "import", "test.json"]);
@@ -1675,6 +2662,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn db_verify_subcommand() {
+        let config = config_from_args(["db", "verify", "--repair"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Db(DbCommand::Verify { repair: true })),
+        );
+    }
+
+    #[test]
+    fn db_inspect_subcommand() {
+        let config = config_from_args(["db", "inspect"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Db(DbCommand::Inspect)),
+        );
+    }
+
+    #[test]
+    fn db_compact_subcommand() {
+        let config = config_from_args(["db", "compact"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Db(DbCommand::Compact)),
+        );
+    }
+
+    #[test]
+    fn doctor_subcommand() {
+        let config = config_from_args(["doctor"]);
+
+        assert_eq!(config.command, Some(GrandineCommand::Doctor));
+    }
+
     #[test]
     fn export_subcommand() {
         let config = config_from_args([
@@ -1697,6 +2721,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn export_chain_data_subcommand() {
+        let config = config_from_args([
+            "export-chain-data",
+            "--format",
+            "csv",
+            "--fields",
+            "slot,proposer,attestation_count,blob_count,graffiti",
+            "--output",
+            "chain.csv",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::ExportChainData {
+                from: 0,
+                to: None,
+                format: ChainDataFormat::Csv,
+                fields: vec![
+                    ChainDataField::Slot,
+                    ChainDataField::Proposer,
+                    ChainDataField::AttestationCount,
+                    ChainDataField::BlobCount,
+                    ChainDataField::Graffiti,
+                ],
+                output: PathBuf::from("chain.csv"),
+            }),
+        );
+    }
+
+    #[test]
+    fn export_state_subcommand() {
+        let config = config_from_args(["export-state", "--slot", "8192", "--path", "anchor"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::ExportState {
+                slot: Some(8192),
+                path: PathBuf::from("anchor"),
+            }),
+        );
+    }
+
+    #[test]
+    fn export_era_subcommand() {
+        let config = config_from_args([
+            "export-era",
+            "--start-epoch",
+            "0",
+            "--end-epoch",
+            "1024",
+            "--path",
+            "era",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::ExportEra {
+                start_epoch: 0,
+                end_epoch: 1024,
+                path: PathBuf::from("era"),
+            }),
+        );
+    }
+
+    #[test]
+    fn import_state_subcommand() {
+        let config = config_from_args(["import-state", "--path", "anchor"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::ImportState {
+                path: PathBuf::from("anchor"),
+            }),
+        );
+    }
+
     #[test]
     fn replay_subcommand() {
         let config =
@@ -1712,6 +2813,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn prune_subcommand() {
+        let config = config_from_args([
+            "prune",
+            "--retain-epochs",
+            "10000",
+            "--include-blocks",
+            "--dry-run",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Prune {
+                retain_epochs: Some(10000),
+                include_blocks: true,
+                dry_run: true,
+                i_know_what_i_am_doing: false,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_fork_upgrade_subcommand() {
+        let config =
+            config_from_args(["test-fork-upgrade", "--state", "state.ssz", "--to", "deneb"]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::TestForkUpgrade {
+                state: PathBuf::from("state.ssz"),
+                to: Phase::Deneb,
+                slots: 3,
+            }),
+        );
+    }
+
     fn config_from_args<'a>(arguments: impl IntoIterator<Item = &'a str>) -> GrandineConfig {
         try_config_from_args(arguments)
             .expect("GrandineArgs should be successfully parsed from arguments")
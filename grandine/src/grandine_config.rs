@@ -1,4 +1,4 @@
-use core::time::Duration;
+use core::{num::NonZeroU64, time::Duration};
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
 use builder_api::BuilderConfig;
@@ -6,8 +6,8 @@ use eth1_api::AuthOptions;
 use features::Feature;
 use http_api::HttpApiConfig;
 use itertools::Itertools as _;
-use log::info;
-use p2p::NetworkConfig;
+use log::{info, warn};
+use p2p::{GossipSlotTolerance, NetworkConfig};
 use reqwest::Url;
 use runtime::{MetricsConfig, StorageConfig};
 use signer::Web3SignerConfig;
@@ -17,7 +17,8 @@ use types::{
 };
 
 use crate::{
-    commands::GrandineCommand, predefined_network::PredefinedNetwork, validators::Validators,
+    commands::GrandineCommand, predefined_network::PredefinedNetwork, profile::Profile,
+    validators::Validators,
 };
 
 // False positive. The `bool`s are independent.
@@ -30,8 +31,11 @@ pub struct GrandineConfig {
     pub genesis_state_file: Option<PathBuf>,
     pub checkpoint_sync_url: Option<Url>,
     pub force_checkpoint_sync: bool,
+    pub checkpoint_state_file: Option<PathBuf>,
+    pub checkpoint_block_file: Option<PathBuf>,
     pub back_sync: bool,
     pub eth1_rpc_urls: Vec<Url>,
+    pub eth1_api_recording_file: Option<PathBuf>,
     pub data_dir: PathBuf,
     pub validators: Validators,
     pub keystore_storage_password_file: Option<PathBuf>,
@@ -46,6 +50,7 @@ pub struct GrandineConfig {
     pub slashing_enabled: bool,
     pub slashing_history_limit: u64,
     pub features: Vec<Feature>,
+    pub profile: Option<Profile>,
     pub state_slot: Option<Slot>,
     pub auth_options: AuthOptions,
     pub builder_config: Option<BuilderConfig>,
@@ -53,8 +58,15 @@ pub struct GrandineConfig {
     pub http_api_config: HttpApiConfig,
     pub metrics_config: MetricsConfig,
     pub track_liveness: bool,
+    pub head_watchdog_slots: Option<NonZeroU64>,
+    pub gossip_slot_tolerance: GossipSlotTolerance,
     pub use_validator_key_cache: bool,
     pub slashing_protection_history_limit: u64,
+    pub standby: bool,
+    pub primary_beacon_node_url: Option<Url>,
+    pub primary_missed_heartbeat_limit: NonZeroU64,
+    pub skip_slashing_protection_check: bool,
+    pub balance_delta_events: bool,
     pub in_memory: bool,
 }
 
@@ -64,8 +76,10 @@ impl GrandineConfig {
         let Self {
             predefined_network,
             chain_config,
+            profile,
             back_sync,
             eth1_rpc_urls,
+            eth1_api_recording_file,
             data_dir,
             graffiti,
             suggested_fee_recipient,
@@ -79,13 +93,31 @@ impl GrandineConfig {
             http_api_config,
             metrics_config,
             checkpoint_sync_url,
+            checkpoint_state_file,
+            checkpoint_block_file,
             use_validator_key_cache,
+            standby,
+            primary_beacon_node_url,
+            skip_slashing_protection_check,
+            balance_delta_events,
             ..
         } = self;
 
         let StorageConfig {
             db_size,
             archival_epoch_interval,
+            prune_history_epochs,
+            archival_snapshot_interval,
+            blob_retention_epochs,
+            hot_dir,
+            cold_dir,
+            era_directory,
+            database_backend,
+            durability,
+            compression,
+            read_cache_size,
+            split_execution_payloads,
+            execution_payload_retention_epochs,
             ..
         } = storage_config;
 
@@ -97,15 +129,54 @@ impl GrandineConfig {
             ),
         }
 
+        if let Some(profile) = profile {
+            info!("profile: {profile}");
+        }
+
         info!("data directory: {data_dir:?}");
+
+        if let Some(hot_dir) = hot_dir {
+            info!("hot database directory: {hot_dir:?}");
+        }
+
+        if let Some(cold_dir) = cold_dir {
+            info!("cold database directory: {cold_dir:?}");
+        }
+
+        if let Some(era_directory) = era_directory {
+            info!("era directory: {era_directory:?}");
+        }
+
+        info!("Eth2 database backend: {database_backend}");
+        info!("Eth2 database durability: {durability}");
+        info!("Eth2 database compression: {compression}");
+        if read_cache_size.as_u64() > 0 {
+            info!(
+                "Eth2 database read cache size: {}",
+                read_cache_size.to_string_as(true),
+            );
+        }
         info!("Eth2 database upper limit: {}", db_size.to_string_as(true));
 
+        if *split_execution_payloads {
+            info!("storing execution payloads of finalized blocks separately");
+        }
+
+        if let Some(execution_payload_retention_epochs) = execution_payload_retention_epochs {
+            info!("execution payload retention: {execution_payload_retention_epochs} epochs");
+        }
+
         info!(
             "Eth1 database upper limit: {}",
             storage_config.eth1_db_size.to_string_as(true),
         );
 
         info!("Eth1 RPC URLs: [{}]", eth1_rpc_urls.iter().format(", "));
+
+        if let Some(eth1_api_recording_file) = eth1_api_recording_file {
+            info!("recording Engine API requests to {eth1_api_recording_file:?}");
+        }
+
         info!("graffiti: {graffiti:?}");
         info!("HTTP API address: {}", http_api_config.address);
 
@@ -117,6 +188,18 @@ impl GrandineConfig {
         }
 
         info!("archival interval: {archival_epoch_interval} epochs");
+
+        if let Some(prune_history_epochs) = prune_history_epochs {
+            info!("history retention: {prune_history_epochs} epochs");
+        }
+
+        if let Some(archival_snapshot_interval) = archival_snapshot_interval {
+            info!("archival snapshot interval: {archival_snapshot_interval} epochs");
+        }
+
+        if let Some(blob_retention_epochs) = blob_retention_epochs {
+            info!("blob sidecar retention: {blob_retention_epochs} epochs");
+        }
         info!("slasher enabled: {slashing_enabled}");
 
         if let Some(client_version) = &network_config.identify_agent_version {
@@ -129,8 +212,8 @@ impl GrandineConfig {
 
         if let Some(builder_config) = builder_config {
             info!(
-                "using external block builder (API URL: {})",
-                builder_config.builder_api_url,
+                "using external block builders (API URLs: [{}])",
+                builder_config.builder_api_urls.iter().format(", "),
             );
         }
 
@@ -138,6 +221,11 @@ impl GrandineConfig {
             info!("checkpoint sync url: {checkpoint_sync_url}");
         }
 
+        if let (Some(state_file), Some(block_file)) = (checkpoint_state_file, checkpoint_block_file)
+        {
+            info!("checkpoint state file: {state_file:?}, checkpoint block file: {block_file:?}");
+        }
+
         if !web3signer_config.urls.is_empty() {
             info!(
                 "using Web3Signer API to sign validator messages (API URLs: [{}])",
@@ -155,5 +243,20 @@ impl GrandineConfig {
         if *use_validator_key_cache {
             info!("using validator key cache");
         }
+
+        if *standby {
+            match primary_beacon_node_url {
+                Some(url) => info!("validator in standby mode (primary beacon node: {url})"),
+                None => info!("validator in standby mode until activated through the HTTP API"),
+            }
+        }
+
+        if *skip_slashing_protection_check {
+            warn!("slashing protection history check at startup is disabled");
+        }
+
+        if *balance_delta_events {
+            info!("sending balance_deltas events for own validators");
+        }
     }
 }
@@ -1,4 +1,4 @@
-use core::{future::Future, panic::AssertUnwindSafe, pin::pin};
+use core::{future::Future, num::NonZeroU64, panic::AssertUnwindSafe, pin::pin};
 use std::{
     net::{SocketAddr, TcpListener, UdpSocket},
     path::PathBuf,
@@ -9,20 +9,24 @@ use std::{
 use allocator as _;
 use anyhow::{bail, ensure, Context as _, Result};
 use builder_api::BuilderConfig;
+use bytesize::ByteSize;
 use clap::{Error as ClapError, Parser as _};
 use database::Database;
 use eth1::{Eth1Chain, Eth1Config};
 use eth1_api::Auth;
 use features::Feature;
-use fork_choice_control::{StateLoadStrategy, Storage};
+use fork_choice_control::{
+    checkpoint_sync::{self, CheckpointFiles, FinalizedCheckpoint},
+    ArchivePruningReport, EntryStatistics, StateLoadStrategy, Storage, StorageVerificationReport,
+};
 use fork_choice_store::StoreConfig;
 use genesis::GenesisProvider;
 use http_api::HttpApiConfig;
 use log::{error, info, warn};
 use metrics::MetricsServerConfig;
-use p2p::{ListenAddr, NetworkConfig};
+use p2p::{GossipSlotTolerance, ListenAddr, NetworkConfig};
 use reqwest::{Client, ClientBuilder, Url};
-use runtime::{MetricsConfig, StorageConfig};
+use runtime::{ChainHeadStalled, MetricsConfig, StorageConfig};
 use signer::Signer;
 use slasher::SlasherConfig;
 use slashing_protection::SlashingProtector;
@@ -40,7 +44,7 @@ use validator::ValidatorConfig;
 use validator_key_cache::ValidatorKeyCache;
 
 use crate::{
-    commands::{GrandineCommand, InterchangeCommand},
+    commands::{DbCommand, GrandineCommand, InterchangeCommand},
     grandine_args::GrandineArgs,
     grandine_config::GrandineConfig,
     predefined_network::PredefinedNetwork,
@@ -56,9 +60,11 @@ use types::preset::Minimal;
 mod commands;
 mod config_dir;
 mod consts;
+mod doctor;
 mod grandine_args;
 mod grandine_config;
 mod predefined_network;
+mod profile;
 mod validators;
 
 #[cfg(not(any(feature = "preset-any", test, doc)))]
@@ -80,8 +86,11 @@ struct Context {
     validator_config: Arc<ValidatorConfig>,
     checkpoint_sync_url: Option<Url>,
     force_checkpoint_sync: bool,
+    checkpoint_state_file: Option<PathBuf>,
+    checkpoint_block_file: Option<PathBuf>,
     back_sync: bool,
     eth1_rpc_urls: Vec<Url>,
+    eth1_api_recording_file: Option<PathBuf>,
     network_config: NetworkConfig,
     storage_config: StorageConfig,
     command: Option<GrandineCommand>,
@@ -93,6 +102,8 @@ struct Context {
     http_api_config: HttpApiConfig,
     metrics_config: MetricsConfig,
     track_liveness: bool,
+    head_watchdog_slots: Option<NonZeroU64>,
+    gossip_slot_tolerance: GossipSlotTolerance,
     slashing_protection_history_limit: u64,
 }
 
@@ -115,6 +126,9 @@ impl Context {
 
             match result {
                 Ok(Ok(())) => break Ok(()),
+                // Restarting the runtime in the same process is unlikely to help after
+                // the head watchdog has already tried to recover.
+                Ok(Err(error)) if error.is::<ChainHeadStalled>() => break Err(error),
                 Ok(Err(error)) => error!("application runtime failed: {error:?}"),
                 Err(error) => error!("application runtime panicked: {error:?}"),
             }
@@ -132,8 +146,11 @@ impl Context {
             validator_config,
             checkpoint_sync_url,
             force_checkpoint_sync,
+            checkpoint_state_file,
+            checkpoint_block_file,
             back_sync,
             eth1_rpc_urls,
+            eth1_api_recording_file,
             network_config,
             storage_config,
             command,
@@ -145,6 +162,8 @@ impl Context {
             http_api_config,
             metrics_config,
             track_liveness,
+            head_watchdog_slots,
+            gossip_slot_tolerance,
             slashing_protection_history_limit,
         } = self;
 
@@ -155,6 +174,10 @@ impl Context {
             ensure!(signer.no_keys(), Error::MissingEth1RpcUrlsWithValidators);
         }
 
+        if http_api_config.read_only {
+            ensure!(signer.no_keys(), Error::ValidatorsInReadOnlyMode);
+        }
+
         let default_deposit_tree = predefined_network.map(PredefinedNetwork::genesis_deposit_tree);
 
         if let Some(deposit_tree) = default_deposit_tree {
@@ -166,6 +189,7 @@ impl Context {
             eth1_rpc_urls,
             deposit_contract_starting_block,
             default_deposit_tree,
+            eth1_api_recording_file,
         });
 
         let (eth1_api_to_metrics_tx, eth1_api_to_metrics_rx) = metrics_config
@@ -216,24 +240,48 @@ impl Context {
         if let Some(command) = command {
             return handle_command(
                 chain_config,
-                storage_config,
+                &storage_config,
                 command,
                 genesis_provider,
                 slashing_protection_history_limit,
             );
         }
 
+        let checkpoint_files =
+            checkpoint_state_file
+                .zip(checkpoint_block_file)
+                .map(|(state_file, block_file)| CheckpointFiles {
+                    block_file,
+                    state_file,
+                });
+
         let state_load_strategy = if force_checkpoint_sync {
-            StateLoadStrategy::Remote {
-                checkpoint_sync_url: checkpoint_sync_url.expect(
-                    "the requires attribute for force_checkpoint_sync \
-                     ensures checkpoint_sync_url is present",
-                ),
+            match checkpoint_files {
+                Some(CheckpointFiles {
+                    block_file,
+                    state_file,
+                }) => {
+                    let FinalizedCheckpoint { block, state } =
+                        checkpoint_sync::load_finalized_from_files(
+                            &chain_config,
+                            &block_file,
+                            &state_file,
+                        )?;
+
+                    StateLoadStrategy::Anchor { block, state }
+                }
+                None => StateLoadStrategy::Remote {
+                    checkpoint_sync_url: checkpoint_sync_url.expect(
+                        "GrandineArgs::try_into_config ensures checkpoint_sync_url is present \
+                         if force_checkpoint_sync is set without checkpoint files",
+                    ),
+                },
             }
         } else {
             StateLoadStrategy::Auto {
                 state_slot,
                 checkpoint_sync_url,
+                checkpoint_files,
                 genesis_provider: genesis_provider.clone(),
             }
         };
@@ -255,6 +303,8 @@ impl Context {
             back_sync,
             metrics_config,
             track_liveness,
+            head_watchdog_slots,
+            gossip_slot_tolerance,
             eth1_api_to_metrics_tx,
             eth1_api_to_metrics_rx,
             slashing_protection_history_limit,
@@ -269,6 +319,8 @@ enum Error {
     PresetNotIncluded { preset_name: PresetName },
     #[error("--eth1-rpc-urls must be specified when validators are present")]
     MissingEth1RpcUrlsWithValidators,
+    #[error("validators cannot be used with --read-only")]
+    ValidatorsInReadOnlyMode,
     #[error(
         "{service} port ({port}) is already in use; \
          make sure no other instance of the application is running \
@@ -305,8 +357,14 @@ fn try_main() -> Result<()> {
         .map_err(GrandineArgs::clap_error)?;
 
     info!("starting beacon node");
+    info!("using {} SHA-256 backend", hashing::Backend::selected());
     config.report();
 
+    // Preflight checks must run before anything is set up, including the data directory.
+    if matches!(config.command, Some(GrandineCommand::Doctor)) {
+        return block_on(doctor::run(&config));
+    }
+
     let GrandineConfig {
         predefined_network,
         chain_config,
@@ -314,8 +372,11 @@ fn try_main() -> Result<()> {
         genesis_state_file,
         checkpoint_sync_url,
         force_checkpoint_sync,
+        checkpoint_state_file,
+        checkpoint_block_file,
         back_sync,
         eth1_rpc_urls,
+        eth1_api_recording_file,
         data_dir,
         validators,
         keystore_storage_password_file,
@@ -323,13 +384,14 @@ fn try_main() -> Result<()> {
         max_empty_slots,
         suggested_fee_recipient,
         network_config,
-        storage_config,
+        mut storage_config,
         request_timeout,
         unfinalized_states_in_memory,
         command,
         slashing_enabled,
         slashing_history_limit,
         features,
+        profile: _,
         state_slot,
         auth_options,
         builder_config,
@@ -337,8 +399,15 @@ fn try_main() -> Result<()> {
         http_api_config,
         metrics_config,
         track_liveness,
+        head_watchdog_slots,
+        gossip_slot_tolerance,
         use_validator_key_cache,
         slashing_protection_history_limit,
+        standby,
+        primary_beacon_node_url,
+        primary_missed_heartbeat_limit,
+        skip_slashing_protection_check,
+        balance_delta_events,
         in_memory,
     } = config;
 
@@ -350,6 +419,10 @@ fn try_main() -> Result<()> {
         ..
     } = &metrics_config;
 
+    if let Some(metrics) = metrics {
+        binary_utils::track_log_metrics(metrics.clone_arc());
+    }
+
     // Don't check ports for command runs. None of the commands need a network connection.
     // Check ports before `Context::run_with_restart` to avoid logging an error repeatedly.
     // The ports could in theory be freed or taken between restarts, but it's not likely.
@@ -362,8 +435,11 @@ fn try_main() -> Result<()> {
         .map_err(GrandineArgs::clap_error)?;
     }
 
-    if !in_memory {
-        runtime::initialize_schema(data_dir)?;
+    // Inspection must not modify the data directory, including the schema version it reports.
+    let inspecting = matches!(command, Some(GrandineCommand::Db(DbCommand::Inspect)));
+
+    if !in_memory && !inspecting {
+        storage_config.previous_schema_version = runtime::initialize_schema(data_dir)?;
     }
 
     let validator_config = Arc::new(ValidatorConfig {
@@ -371,6 +447,11 @@ fn try_main() -> Result<()> {
         max_empty_slots,
         suggested_fee_recipient,
         keystore_storage_password_file,
+        standby,
+        primary_beacon_node_url,
+        primary_missed_heartbeat_limit,
+        skip_slashing_protection_check,
+        balance_delta_events,
     });
 
     let store_config = StoreConfig {
@@ -440,8 +521,11 @@ fn try_main() -> Result<()> {
         validator_config,
         checkpoint_sync_url,
         force_checkpoint_sync,
+        checkpoint_state_file,
+        checkpoint_block_file,
         back_sync,
         eth1_rpc_urls,
+        eth1_api_recording_file,
         network_config,
         storage_config,
         command,
@@ -453,6 +537,8 @@ fn try_main() -> Result<()> {
         http_api_config,
         metrics_config,
         track_liveness,
+        head_watchdog_slots,
+        gossip_slot_tolerance,
         slashing_protection_history_limit,
     };
 
@@ -558,42 +644,39 @@ fn ensure_ports_not_in_use(
     Ok(())
 }
 
+#[allow(clippy::too_many_lines)]
 fn handle_command<P: Preset>(
     chain_config: Arc<ChainConfig>,
-    storage_config: StorageConfig,
+    storage_config: &StorageConfig,
     command: GrandineCommand,
     genesis_provider: GenesisProvider<P>,
     slashing_protection_history_limit: u64,
 ) -> Result<()> {
     let StorageConfig {
-        db_size,
         directories,
         archival_epoch_interval,
+        archival_snapshot_interval,
         ..
     } = storage_config;
 
+    let persistent_storage = || -> Result<Storage<P>> {
+        Ok(Storage::new(
+            chain_config.clone_arc(),
+            storage_config.storage_database()?,
+            storage_config.archive_database()?,
+            *archival_epoch_interval,
+            false,
+        )
+        .with_archival_snapshot_interval(*archival_snapshot_interval))
+    };
+
     match command {
         GrandineCommand::Export {
             from,
             to,
             output_dir,
         } => {
-            let storage_database = Database::persistent(
-                "beacon_fork_choice",
-                directories
-                    .store_directory
-                    .clone()
-                    .unwrap_or_default()
-                    .join("beacon_fork_choice"),
-                db_size,
-            )?;
-
-            let storage = Storage::new(
-                chain_config,
-                storage_database,
-                archival_epoch_interval,
-                false,
-            );
+            let storage = persistent_storage()?;
 
             let output_dir = output_dir.unwrap_or(std::env::current_dir()?);
 
@@ -607,6 +690,81 @@ fn handle_command<P: Preset>(
 
             info!("state and blocks exported to {output_dir:?}");
         }
+        GrandineCommand::ExportChainData {
+            from,
+            to,
+            format,
+            fields,
+            output,
+        } => {
+            let storage = persistent_storage()?;
+
+            let block_count = fork_choice_control::export_chain_data(
+                &storage, from, to, &output, format, fields,
+            )?;
+
+            info!("data of {block_count} blocks exported to {output:?}");
+        }
+        GrandineCommand::ExportState { slot, path } => {
+            let storage = persistent_storage()?;
+            let slot = fork_choice_control::export_anchor(&storage, slot, &path)?;
+
+            info!("anchor block and state at slot {slot} exported to {path:?}");
+        }
+        GrandineCommand::ExportEra {
+            start_epoch,
+            end_epoch,
+            path,
+        } => {
+            let storage = persistent_storage()?;
+
+            let file_count =
+                fork_choice_control::export_era(&storage, start_epoch, end_epoch, &path)?;
+
+            info!("{file_count} era files exported to {path:?}");
+        }
+        GrandineCommand::ImportState { path } => {
+            let storage = persistent_storage()?;
+            let slot = fork_choice_control::import_anchor(&storage, &path)?;
+
+            info!("anchor block and state at slot {slot} imported from {path:?}");
+        }
+        GrandineCommand::Prune {
+            retain_epochs,
+            include_blocks,
+            dry_run,
+            i_know_what_i_am_doing,
+        } => {
+            let Some(retain_epochs) = retain_epochs else {
+                info!("no retention horizon specified; keeping all data");
+                return Ok(());
+            };
+
+            let storage = persistent_storage()?;
+
+            let ArchivePruningReport {
+                retained_state_slot,
+                pruned_state_slots,
+                pruned_block_count,
+            } = fork_choice_control::prune_archive(
+                &storage,
+                retain_epochs,
+                include_blocks,
+                dry_run,
+                i_know_what_i_am_doing,
+            )?;
+
+            let verb = if dry_run { "would be pruned" } else { "pruned" };
+
+            info!(
+                "archival states {verb}: {} (first slot: {:?}, last slot: {:?}), \
+                 blocks {verb}: {pruned_block_count}, \
+                 oldest retained state slot: {retained_state_slot:?}",
+                pruned_state_slots.len(),
+                pruned_state_slots.first(),
+                pruned_state_slots.last(),
+            );
+        }
         GrandineCommand::Replay {
             from,
             to,
@@ -615,6 +773,85 @@ fn handle_command<P: Preset>(
             let input_dir = input_dir.unwrap_or(std::env::current_dir()?);
             fork_choice_control::replay_blocks::<P>(&chain_config, &input_dir, from, to)?;
         }
+        GrandineCommand::TestForkUpgrade { state, to, slots } => {
+            fork_choice_control::test_fork_upgrade::<P>(&chain_config, &state, to, slots)?;
+        }
+        GrandineCommand::Db(DbCommand::Verify { repair }) => {
+            let storage = persistent_storage()?;
+            let report = fork_choice_control::verify_storage(&storage, repair)?;
+
+            let StorageVerificationReport {
+                entry_counts,
+                hash_tree_roots_checked,
+                issues,
+                repaired_issues,
+            } = &report;
+
+            for (kind, count) in entry_counts {
+                info!("{kind}: {count}");
+            }
+
+            info!("hash tree roots checked: {hash_tree_roots_checked}");
+
+            for issue in issues {
+                warn!("{issue}");
+            }
+
+            info!(
+                "storage verified (issues found: {}, issues repaired: {repaired_issues})",
+                issues.len(),
+            );
+
+            for suggestion in report.suggestions() {
+                info!("{suggestion}");
+            }
+        }
+        GrandineCommand::Db(DbCommand::Inspect) => {
+            let (database, archive_database) = storage_config.read_only_databases()?;
+
+            let storage = Storage::<P>::new(
+                chain_config.clone_arc(),
+                database,
+                archive_database,
+                *archival_epoch_interval,
+                false,
+            );
+
+            let report = fork_choice_control::inspect_storage(&storage)?;
+
+            let schema_version =
+                runtime::read_schema_version(directories.data_dir.clone().unwrap_or_default())?;
+
+            info!(
+                "schema version: {}",
+                schema_version.as_deref().unwrap_or("unknown"),
+            );
+
+            for (kind, statistics) in &report.entries {
+                let EntryStatistics { count, slots, .. } = statistics;
+                let size = ByteSize::b(statistics.total_bytes());
+
+                match slots {
+                    Some(slots) => info!(
+                        "{kind}: {count} entries, {size} (slots {} to {})",
+                        slots.start(),
+                        slots.end(),
+                    ),
+                    None => info!("{kind}: {count} entries, {size}"),
+                }
+            }
+
+            info!("total size: {}", ByteSize::b(report.total_bytes()));
+        }
+        GrandineCommand::Db(DbCommand::Compact) => {
+            let reclaimed_bytes = storage_config.compact_databases()?;
+
+            info!(
+                "databases compacted (space reclaimed: {})",
+                ByteSize::b(reclaimed_bytes),
+            );
+        }
+        GrandineCommand::Doctor => unreachable!("preflight checks are run in try_main"),
         GrandineCommand::Interchange(interchange_command) => {
             let genesis_validators_root = genesis_provider.state().genesis_validators_root();
 
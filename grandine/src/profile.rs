@@ -0,0 +1,69 @@
+use clap::ValueEnum;
+use features::Feature;
+use strum::Display;
+
+/// Named sets of runtime features and settings.
+///
+/// Features from a profile are enabled in addition to those passed with `--features`.
+/// Settings from a profile are only used for options that are not passed explicitly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, ValueEnum)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Profile {
+    /// Default settings. Objects produced by the node itself are fully validated
+    MainnetConservative,
+    /// Publishes messages as early as possible and caches target states
+    TestnetExperimental,
+    /// Trusts objects produced by the node itself and keeps more states in memory.
+    /// Only suitable for local development networks. Cannot be used on mainnet
+    DevnetFast,
+}
+
+impl Profile {
+    #[must_use]
+    pub const fn features(self) -> &'static [Feature] {
+        match self {
+            Self::MainnetConservative => &[],
+            Self::TestnetExperimental => &[
+                Feature::CacheTargetStates,
+                Feature::LogBlockProcessingTime,
+                Feature::PublishAttestationsEarly,
+                Feature::PublishSyncCommitteeMessagesEarly,
+            ],
+            Self::DevnetFast => &[
+                Feature::CacheTargetStates,
+                Feature::SubscribeToAllAttestationSubnets,
+                Feature::SubscribeToAllSyncCommitteeSubnets,
+                Feature::TrustOwnAttestationSignatures,
+                Feature::TrustOwnAttesterSlashingSignatures,
+                Feature::TrustOwnBlockSignatures,
+                Feature::TrustOwnStateRoots,
+            ],
+        }
+    }
+
+    #[must_use]
+    pub const fn max_empty_slots(self) -> Option<u64> {
+        match self {
+            Self::MainnetConservative | Self::TestnetExperimental => None,
+            // Development networks are often left without validators for long periods.
+            Self::DevnetFast => Some(128),
+        }
+    }
+
+    #[must_use]
+    pub const fn unfinalized_states_in_memory(self) -> Option<u64> {
+        match self {
+            Self::MainnetConservative | Self::TestnetExperimental => None,
+            Self::DevnetFast => Some(256),
+        }
+    }
+
+    #[must_use]
+    pub const fn target_peers(self) -> Option<usize> {
+        match self {
+            Self::MainnetConservative | Self::TestnetExperimental => None,
+            // Development networks rarely have more nodes than this.
+            Self::DevnetFast => Some(16),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use core::ops::Range;
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
@@ -13,6 +14,7 @@ use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use signer::KeyOrigin;
 use std_ext::ArcExt;
 use tap::{Pipe as _, TryConv as _};
+use types::phase0::primitives::ValidatorIndex;
 use validator_key_cache::ValidatorKeyCache;
 use zeroize::Zeroizing;
 
@@ -28,6 +30,9 @@ pub enum Validators {
         keystore_dir: PathBuf,
         keystore_password_file: PathBuf,
     },
+    Interop {
+        validator_indices: Range<ValidatorIndex>,
+    },
 }
 
 impl Validators {
@@ -85,6 +90,17 @@ impl Validators {
                 keystore_dir,
                 keystore_password_file,
             } => Self::keymap_from_paths(keystore_dir, keystore_password_file)?,
+            Self::Interop { validator_indices } => {
+                let keymanager_keypairs =
+                    keystore_storage.keypairs().map(|(public_key, secret_key)| {
+                        (public_key, secret_key, KeyOrigin::KeymanagerAPI)
+                    });
+
+                return Ok(interop_keypairs(validator_indices)
+                    .into_iter()
+                    .chain(keymanager_keypairs)
+                    .collect());
+            }
         }
         .into_par_iter()
         .map(|(keystore_path, password_path)| {
@@ -156,3 +172,22 @@ impl Validators {
         Ok(keypairs)
     }
 }
+
+fn interop_keypairs(
+    validator_indices: Range<ValidatorIndex>,
+) -> Vec<(PublicKeyBytes, Arc<SecretKey>, KeyOrigin)> {
+    warn!(
+        "using insecure interop keys for validators {}..{}; \
+         these keys are public and must only be used on development networks",
+        validator_indices.start, validator_indices.end,
+    );
+
+    validator_indices
+        .into_par_iter()
+        .map(|validator_index| {
+            let secret_key = Arc::new(interop::secret_key(validator_index));
+            let public_key = secret_key.to_public_key().into();
+            (public_key, secret_key, KeyOrigin::Interop)
+        })
+        .collect()
+}
@@ -0,0 +1,120 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::OnceLock;
+
+use ethereum_types::H256;
+use generic_array::GenericArray;
+use openssl::sha::Sha256 as OpenSslSha256;
+
+use crate::{portable, Sha256BlockSize, Sha256State};
+
+static SELECTED: OnceLock<Backend> = OnceLock::new();
+
+/// Implementation of SHA-256 used by the hashing functions.
+///
+/// The backend is chosen once at runtime based on the features supported by the CPU.
+/// The first supported one in [`Backend::ALL`] is used.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Backend {
+    /// `sha2::compress256` using the SHA extensions.
+    /// On AArch64 it uses the equivalent ARMv8 instructions.
+    ShaNi,
+    /// OpenSSL, which uses AVX2 and BMI2 on CPUs without the SHA extensions.
+    /// Its overhead outweighs the speedup on CPUs that have them.
+    Avx2,
+    /// A scalar implementation in this crate that works on any CPU.
+    Portable,
+}
+
+impl Display for Backend {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str(self.name())
+    }
+}
+
+impl Backend {
+    pub const ALL: [Self; 3] = [Self::ShaNi, Self::Avx2, Self::Portable];
+
+    #[must_use]
+    pub fn selected() -> Self {
+        *SELECTED.get_or_init(Self::detect)
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ShaNi => "sha-ni",
+            Self::Avx2 => "avx2",
+            Self::Portable => "portable",
+        }
+    }
+
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            match self {
+                // These are the features `sha2` checks for before using the SHA extensions.
+                Self::ShaNi => {
+                    std::is_x86_feature_detected!("sha")
+                        && std::is_x86_feature_detected!("sse2")
+                        && std::is_x86_feature_detected!("ssse3")
+                        && std::is_x86_feature_detected!("sse4.1")
+                }
+                Self::Avx2 => {
+                    std::is_x86_feature_detected!("avx2") && std::is_x86_feature_detected!("bmi2")
+                }
+                Self::Portable => true,
+            }
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            match self {
+                Self::ShaNi => std::arch::is_aarch64_feature_detected!("sha2"),
+                Self::Avx2 => false,
+                Self::Portable => true,
+            }
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            matches!(self, Self::Portable)
+        }
+    }
+
+    fn detect() -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.is_supported())
+            .unwrap_or(Self::Portable)
+    }
+
+    // `blocks` must contain a message of `message_length` bytes followed by SHA-256 padding.
+    // OpenSSL pads messages itself, so only the message is passed to it.
+    pub(crate) fn hash_blocks(
+        self,
+        blocks: &[GenericArray<u8, Sha256BlockSize>],
+        message_length: usize,
+    ) -> H256 {
+        match self {
+            Self::ShaNi => Sha256State::default().compress_multiple(blocks).output(),
+            Self::Avx2 => {
+                let mut hasher = OpenSslSha256::new();
+                let mut remaining = message_length;
+
+                for block in blocks {
+                    let length = remaining.min(block.len());
+                    hasher.update(&block[..length]);
+                    remaining -= length;
+                }
+
+                H256(hasher.finish())
+            }
+            Self::Portable => {
+                let mut state = Sha256State::default();
+                portable::compress(&mut state.0, blocks);
+                state.output()
+            }
+        }
+    }
+}
@@ -6,6 +6,11 @@ use sha2::{
     Sha256,
 };
 
+pub use crate::backend::Backend;
+
+mod backend;
+mod portable;
+
 #[rustfmt::skip]
 pub const ZERO_HASHES: [H256; 41] = [
     H256(hex!("0000000000000000000000000000000000000000000000000000000000000000")),
@@ -158,10 +163,6 @@ impl Default for Sha256State {
 }
 
 impl Sha256State {
-    fn compress_single(self, block: Sha256Block) -> Self {
-        self.compress_multiple(core::slice::from_ref(GenericArray::from_slice(&block)))
-    }
-
     // Moving blocks into an array is faster than calling `sha2::compress256` multiple times.
     fn compress_multiple(mut self, blocks: &[GenericArray<u8, Sha256BlockSize>]) -> Self {
         sha2::compress256(&mut self.0, blocks);
@@ -185,7 +186,7 @@ pub fn hash_64(value: u64) -> H256 {
     let mut block = BLOCK_WITH_PADDING_FOR_64_BITS;
     block[..8].copy_from_slice(&value.to_le_bytes());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 8)
 }
 
 #[inline]
@@ -194,7 +195,7 @@ pub fn hash_256(bytes: H256) -> H256 {
     let mut block = BLOCK_WITH_PADDING_FOR_256_BITS;
     block[..32].copy_from_slice(bytes.as_bytes());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 32)
 }
 
 #[inline]
@@ -204,7 +205,7 @@ pub fn hash_256_8(a: H256, b: u8) -> H256 {
     block[..32].copy_from_slice(a.as_bytes());
     block[32] = b;
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 32 + 1)
 }
 
 #[inline]
@@ -215,7 +216,7 @@ pub fn hash_256_8_32(a: H256, b: u8, c: u32) -> H256 {
     block[32] = b;
     block[32 + 1..32 + 1 + 4].copy_from_slice(&c.to_le_bytes());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 32 + 1 + 4)
 }
 
 #[inline]
@@ -225,7 +226,7 @@ pub fn hash_256_64(a: H256, b: u64) -> H256 {
     block[..32].copy_from_slice(a.as_bytes());
     block[32..32 + 8].copy_from_slice(&b.to_le_bytes());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 32 + 8)
 }
 
 #[inline]
@@ -236,7 +237,7 @@ pub fn hash_32_64_256(a: [u8; 4], b: u64, c: H256) -> H256 {
     block[4..4 + 8].copy_from_slice(&b.to_le_bytes());
     block[4 + 8..4 + 8 + 32].copy_from_slice(c.as_bytes());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 4 + 8 + 32)
 }
 
 // This function is only ever called with `PublicKeyBytes`,
@@ -247,21 +248,26 @@ pub fn hash_384(bytes: impl AsRef<[u8; 48]>) -> H256 {
     let mut block = BLOCK_WITH_PADDING_FOR_384_BITS;
     block[..48].copy_from_slice(bytes.as_ref());
 
-    Sha256State::default().compress_single(block).output()
+    hash_block(block, 48)
 }
 
 #[inline]
 #[must_use]
 pub fn hash_256_256(left: H256, right: H256) -> H256 {
+    hash_256_256_with_backend(Backend::selected(), left, right)
+}
+
+// This is only public to allow benchmarking individual backends.
+#[inline]
+#[must_use]
+pub fn hash_256_256_with_backend(backend: Backend, left: H256, right: H256) -> H256 {
     let mut block = GenericArray::default();
     block[..32].copy_from_slice(left.as_bytes());
     block[32..].copy_from_slice(right.as_bytes());
 
     let padding_block = *GenericArray::from_slice(&PADDING_BLOCK_FOR_512_BITS);
 
-    Sha256State::default()
-        .compress_multiple(&[block, padding_block])
-        .output()
+    backend.hash_blocks(&[block, padding_block], 64)
 }
 
 // This function is only ever called with `SignatureBytes`,
@@ -275,14 +281,24 @@ pub fn hash_768(bytes: impl AsRef<[u8; 96]>) -> H256 {
     let mut block_2 = *GenericArray::from_slice(&BLOCK_WITH_PADDING_FOR_768_BITS);
     block_2[..32].copy_from_slice(&bytes.as_ref()[64..]);
 
-    Sha256State::default()
-        .compress_multiple(&[block_1, block_2])
-        .output()
+    hash_blocks(&[block_1, block_2], 96)
+}
+
+fn hash_block(block: Sha256Block, message_length: usize) -> H256 {
+    hash_blocks(
+        core::slice::from_ref(GenericArray::from_slice(&block)),
+        message_length,
+    )
+}
+
+fn hash_blocks(blocks: &[GenericArray<u8, Sha256BlockSize>], message_length: usize) -> H256 {
+    Backend::selected().hash_blocks(blocks, message_length)
 }
 
 #[cfg(test)]
 mod tests {
     use itertools::Itertools as _;
+    use sha2::Digest as _;
 
     use super::*;
 
@@ -292,4 +308,26 @@ mod tests {
             assert_eq!(hash_256_256(lower, lower), higher);
         }
     }
+
+    #[test]
+    fn supported_backends_produce_identical_hashes() {
+        let value = 0x0123_4567_89ab_cdef;
+        let expected_hash = H256(Sha256::digest(u64::to_le_bytes(value)).into());
+
+        let mut block = BLOCK_WITH_PADDING_FOR_64_BITS;
+        block[..8].copy_from_slice(&value.to_le_bytes());
+
+        let blocks = core::slice::from_ref(GenericArray::from_slice(&block));
+
+        for backend in Backend::ALL
+            .into_iter()
+            .filter(|backend| backend.is_supported())
+        {
+            assert_eq!(backend.hash_blocks(blocks, 8), expected_hash);
+
+            for (lower, higher) in ZERO_HASHES.into_iter().tuple_windows() {
+                assert_eq!(hash_256_256_with_backend(backend, lower, lower), higher);
+            }
+        }
+    }
 }
@@ -0,0 +1,76 @@
+// A straightforward implementation of the SHA-256 compression function as specified in FIPS 180-4.
+// `sha2::compress256` cannot be used for this because it switches to the SHA extensions on its own.
+
+use generic_array::GenericArray;
+
+use crate::Sha256BlockSize;
+
+#[rustfmt::skip]
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5, 0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3, 0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc, 0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7, 0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13, 0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3, 0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5, 0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208, 0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+// The names of the working variables match the specification.
+#[allow(clippy::many_single_char_names)]
+pub fn compress(state: &mut [u32; 8], blocks: &[GenericArray<u8, Sha256BlockSize>]) {
+    for block in blocks {
+        let mut schedule = [0; 64];
+
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().expect("chunks have 4 bytes each"));
+        }
+
+        for index in 16..schedule.len() {
+            let w15 = schedule[index - 15];
+            let w2 = schedule[index - 2];
+            let sigma_0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+            let sigma_1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+
+            schedule[index] = schedule[index - 16]
+                .wrapping_add(sigma_0)
+                .wrapping_add(schedule[index - 7])
+                .wrapping_add(sigma_1);
+        }
+
+        let mut working = *state;
+
+        for (constant, word) in ROUND_CONSTANTS.into_iter().zip(schedule) {
+            let [a, b, c, d, e, f, g, h] = working;
+
+            let big_sigma_1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+
+            let temporary_1 = h
+                .wrapping_add(big_sigma_1)
+                .wrapping_add(choice)
+                .wrapping_add(constant)
+                .wrapping_add(word);
+
+            let big_sigma_0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temporary_2 = big_sigma_0.wrapping_add(majority);
+
+            working = [
+                temporary_1.wrapping_add(temporary_2),
+                a,
+                b,
+                c,
+                d.wrapping_add(temporary_1),
+                e,
+                f,
+                g,
+            ];
+        }
+
+        for (current, new) in state.iter_mut().zip(working) {
+            *current = current.wrapping_add(new);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use core::{
 use std::sync::Arc;
 
 use anyhow::{bail, ensure, Result};
+use arithmetic::U64Ext as _;
 use bit_field::BitField as _;
 use bls::{AggregatePublicKey, CachedPublicKey, PublicKeyBytes};
 use im::HashMap;
@@ -20,17 +21,20 @@ use typenum::{IsGreaterOrEqual, Sub1, True, Unsigned as _};
 use types::{
     altair::{
         consts::{
-            DOMAIN_SYNC_COMMITTEE, TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX,
-            TIMELY_TARGET_FLAG_INDEX,
+            DOMAIN_SYNC_COMMITTEE, SYNC_REWARD_WEIGHT, TIMELY_HEAD_FLAG_INDEX,
+            TIMELY_SOURCE_FLAG_INDEX, TIMELY_TARGET_FLAG_INDEX, WEIGHT_DENOMINATOR,
         },
         containers::SyncCommittee,
         primitives::{ParticipationFlags, SubcommitteeIndex},
     },
-    cache::{IndexSlice, PackedIndices},
+    cache::{CommitteeBoundaries, IndexSlice, PackedIndices},
     config::Config,
     nonstandard::{AttestationEpoch, Participation, RelativeEpoch},
     phase0::{
-        consts::{DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER, GENESIS_EPOCH},
+        consts::{
+            DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER, ETH_TO_GWEI, GENESIS_EPOCH,
+            SAFETY_DECAY,
+        },
         containers::{Attestation, AttestationData, AttesterSlashing, IndexedAttestation},
         primitives::{
             CommitteeIndex, DomainType, Epoch, Gwei, Slot, SubnetId, ValidatorIndex, H256,
@@ -346,6 +350,38 @@ pub fn get_validator_activation_churn_limit<P: Preset>(
     get_validator_churn_limit(config, state).min(config.max_per_epoch_activation_churn_limit)
 }
 
+/// <https://github.com/ethereum/consensus-specs/blob/v1.4.0/specs/phase0/weak-subjectivity.md#compute_weak_subjectivity_period>
+#[must_use]
+pub fn compute_weak_subjectivity_period<P: Preset>(
+    config: &Config,
+    state: &impl BeaconState<P>,
+) -> u64 {
+    let ws_period = config.min_validator_withdrawability_delay;
+    let n = active_validator_count_u64(state, RelativeEpoch::Current);
+
+    // The specification does not handle this case because it divides by `N`.
+    if n == 0 {
+        return ws_period;
+    }
+
+    let t = total_active_balance(state) / n / ETH_TO_GWEI;
+    let big_t = P::MAX_EFFECTIVE_BALANCE / ETH_TO_GWEI;
+    let delta = get_validator_churn_limit(config, state);
+    let big_delta = P::MaxDeposits::U64 * P::SlotsPerEpoch::U64;
+    let d = SAFETY_DECAY;
+
+    if big_t * (200 + 3 * d) < t * (200 + 12 * d) {
+        let epochs_for_validator_set_churn =
+            n * (t * (200 + 12 * d) - big_t * (200 + 3 * d)) / (600 * delta * (2 * t + big_t));
+
+        let epochs_for_balance_top_ups = n * (200 + 3 * d) / (600 * big_delta);
+
+        ws_period + epochs_for_validator_set_churn.max(epochs_for_balance_top_ups)
+    } else {
+        ws_period + 3 * n * d * t / (200 * big_delta * (big_t - t))
+    }
+}
+
 fn get_seed<P: Preset>(
     state: &impl BeaconState<P>,
     relative_epoch: RelativeEpoch,
@@ -386,6 +422,35 @@ pub fn get_committee_count_per_slot<P: Preset>(
     misc::committee_count_from_active_validator_count::<P>(active_validator_count)
 }
 
+pub fn committee_boundaries<P: Preset>(
+    state: &impl BeaconState<P>,
+    relative_epoch: RelativeEpoch,
+) -> &CommitteeBoundaries {
+    get_or_init_committee_boundaries(state, relative_epoch, true)
+}
+
+pub fn get_or_init_committee_boundaries<P: Preset>(
+    state: &impl BeaconState<P>,
+    relative_epoch: RelativeEpoch,
+    _report_cache_miss: bool,
+) -> &CommitteeBoundaries {
+    state.cache().committee_boundaries[relative_epoch].get_or_init(|| {
+        let committees_per_slot = get_committee_count_per_slot(state, relative_epoch);
+        let committees_in_epoch = committees_per_slot * P::SlotsPerEpoch::U64;
+        let validator_count = active_validator_count_u64(state, relative_epoch);
+
+        let boundaries = (0..=committees_in_epoch)
+            .map(|index_in_epoch| {
+                (validator_count * index_in_epoch / committees_in_epoch)
+                    .try_into()
+                    .expect("boundaries cannot exceed the number of active validators")
+            })
+            .collect();
+
+        CommitteeBoundaries::new(committees_per_slot, boundaries)
+    })
+}
+
 pub fn beacon_committee<P: Preset>(
     state: &impl BeaconState<P>,
     slot: Slot,
@@ -393,22 +458,22 @@ pub fn beacon_committee<P: Preset>(
 ) -> Result<IndexSlice> {
     let epoch = misc::compute_epoch_at_slot::<P>(slot);
     let relative_epoch = relative_epoch(state, epoch)?;
-    let committees_per_slot = get_committee_count_per_slot(state, relative_epoch);
+    let boundaries = committee_boundaries(state, relative_epoch);
+    let committees_per_slot = boundaries.committees_per_slot();
 
     ensure!(
         committee_index < committees_per_slot,
         Error::CommitteeIndexOutOfBounds,
     );
 
-    let indices = active_validator_indices_shuffled(state, relative_epoch);
-    let validator_count = ValidatorIndex::try_from(indices.len())?;
-    let committees_in_epoch = committees_per_slot * P::SlotsPerEpoch::U64;
     let slots_since_epoch_start = misc::slots_since_epoch_start::<P>(slot);
     let index_in_epoch = slots_since_epoch_start * committees_per_slot + committee_index;
-    let start = (validator_count * index_in_epoch / committees_in_epoch).try_into()?;
-    let end = (validator_count * (index_in_epoch + 1) / committees_in_epoch).try_into()?;
 
-    Ok(indices.slice(start..end))
+    let range = boundaries
+        .committee(index_in_epoch)
+        .expect("committee index was checked against the number of committees per slot");
+
+    Ok(active_validator_indices_shuffled(state, relative_epoch).slice(range))
 }
 
 pub fn beacon_committees<P: Preset>(
@@ -417,7 +482,7 @@ pub fn beacon_committees<P: Preset>(
 ) -> Result<impl Iterator<Item = IndexSlice>> {
     let epoch = misc::compute_epoch_at_slot::<P>(slot);
     let relative_epoch = relative_epoch(state, epoch)?;
-    let committees_per_slot = get_committee_count_per_slot(state, relative_epoch);
+    let committees_per_slot = committee_boundaries(state, relative_epoch).committees_per_slot();
 
     Ok((0..committees_per_slot).map(move |committee_index| {
         beacon_committee(state, slot, committee_index)
@@ -655,6 +720,21 @@ pub fn get_base_reward_per_increment<P: Preset>(state: &impl BeaconState<P>) ->
         .div(total_active_balance(state).sqrt())
 }
 
+/// Computes the amount each sync committee member gains for being included in the sync aggregate of
+/// a block or loses for being left out of it.
+///
+/// Derived from [`process_sync_aggregate`].
+///
+/// [`process_sync_aggregate`]: https://github.com/ethereum/consensus-specs/blob/0b76c8367ed19014d104e3fbd4718e73f459a748/specs/altair/beacon-chain.md#sync-aggregate-processing
+pub fn get_sync_committee_participant_reward<P: Preset>(state: &impl BeaconState<P>) -> Gwei {
+    let total_active_increments = total_active_balance(state) / P::EFFECTIVE_BALANCE_INCREMENT;
+    let total_base_rewards = get_base_reward_per_increment(state) * total_active_increments;
+    let max_participant_rewards = (total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR)
+        .div_typenum::<P::SlotsPerEpoch>();
+
+    max_participant_rewards.div_typenum::<P::SyncCommitteeSize>()
+}
+
 pub fn get_attestation_participation_flags<P: Preset>(
     state: &impl BeaconState<P>,
     data: AttestationData,
@@ -808,9 +888,14 @@ pub fn initialize_shuffled_indices<'attestations, P: Preset>(
 
 #[cfg(test)]
 mod tests {
+    use ssz::PersistentList;
+    use test_case::test_case;
     use types::{
-        phase0::{beacon_state::BeaconState as Phase0BeaconState, containers::Validator},
-        preset::Minimal,
+        phase0::{
+            beacon_state::BeaconState as Phase0BeaconState, consts::FAR_FUTURE_EPOCH,
+            containers::Validator,
+        },
+        preset::{Mainnet, Minimal},
     };
 
     use super::*;
@@ -903,6 +988,32 @@ mod tests {
         );
     }
 
+    // The expected values are from the table in the weak subjectivity guide.
+    #[test_case(28, 1 << 15 => 504)]
+    #[test_case(28, 1 << 18 => 2241)]
+    #[test_case(32, 1 << 15 => 665)]
+    #[test_case(32, 1 << 18 => 3532)]
+    fn test_compute_weak_subjectivity_period(
+        average_balance_in_eth: Gwei,
+        validator_count: usize,
+    ) -> u64 {
+        let validator = Validator {
+            effective_balance: average_balance_in_eth * ETH_TO_GWEI,
+            exit_epoch: FAR_FUTURE_EPOCH,
+            ..Validator::default()
+        };
+
+        let state = Phase0BeaconState::<Mainnet> {
+            validators: PersistentList::try_from_iter(
+                core::iter::repeat(validator).take(validator_count),
+            )
+            .expect("length is under maximum"),
+            ..Phase0BeaconState::default()
+        };
+
+        compute_weak_subjectivity_period(&Config::mainnet(), &state)
+    }
+
     #[test]
     fn test_get_active_validator_indices() {
         let state = Phase0BeaconState::<Minimal> {
@@ -930,4 +1041,32 @@ mod tests {
 
         itertools::assert_equal(indices, [0, 2]);
     }
+
+    #[test]
+    fn test_beacon_committees_partition_active_validators() -> Result<()> {
+        let validator = Validator {
+            exit_epoch: FAR_FUTURE_EPOCH,
+            ..Validator::default()
+        };
+
+        let state = Phase0BeaconState::<Minimal> {
+            validators: PersistentList::try_from_iter(core::iter::repeat(validator).take(20))?,
+            ..Phase0BeaconState::default()
+        };
+
+        assert_eq!(
+            committee_boundaries(&state, RelativeEpoch::Current).committees_per_slot(),
+            1,
+        );
+
+        let mut members = misc::slots_in_epoch::<Minimal>(GENESIS_EPOCH)
+            .map(|slot| beacon_committees(&state, slot))
+            .process_results(|committees| committees.flatten().flatten().collect_vec())?;
+
+        members.sort_unstable();
+
+        itertools::assert_equal(members, 0..20);
+
+        Ok(())
+    }
 }
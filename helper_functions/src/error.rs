@@ -9,6 +9,8 @@ pub(crate) enum Error {
     AttestationSourceMismatch,
     #[error("attesting indices are not sorted and unique")]
     AttestingIndicesNotSortedAndUnique,
+    #[error("block has no blob KZG commitment at index")]
+    BlobKzgCommitmentMissing,
     #[error("commitee index is out of bounds")]
     CommitteeIndexOutOfBounds,
     #[error("aggregation bitlist length does not match committee length")]
@@ -23,6 +25,10 @@ pub(crate) enum Error {
     EpochOverflow,
     #[error("failed to select proposer")]
     FailedToSelectProposer,
+    #[error("historical state does not match accumulated roots")]
+    HistoricalStateMismatch,
+    #[error("historical state is needed to prove block root")]
+    HistoricalStateMissing,
     #[error("no validators are active")]
     NoActiveValidators,
     #[error("permutated prefix maximum overflowed")]
@@ -55,6 +61,8 @@ pub enum SignatureKind {
     ContributionAndProof,
     #[display("deposit signature")]
     Deposit,
+    #[display("head statement signature")]
+    HeadStatement,
     #[display("collection of multiple signatures")]
     Multi,
     #[display("RANDAO reveal")]
@@ -0,0 +1,217 @@
+use ssz::{SszHash as _, H256};
+use types::{
+    combined::{BeaconState, SignedBeaconBlock},
+    preset::Preset,
+};
+
+// `ssz_derive` does not expose roots of individual fields, so they have to be listed here.
+// The order must match the order of fields in the containers.
+macro_rules! field_roots {
+    ($container: expr, [$($field: ident),* $(,)?] $(,)?) => {
+        vec![$($container.$field.hash_tree_root()),*]
+    };
+}
+
+// Fields added in Altair and later forks follow the ones shared by all post-Altair states.
+macro_rules! post_altair_state_field_roots {
+    ($state: expr, [$($field: ident),* $(,)?] $(,)?) => {
+        field_roots!(
+            $state,
+            [
+                genesis_time,
+                genesis_validators_root,
+                slot,
+                fork,
+                latest_block_header,
+                block_roots,
+                state_roots,
+                historical_roots,
+                eth1_data,
+                eth1_data_votes,
+                eth1_deposit_index,
+                validators,
+                balances,
+                randao_mixes,
+                slashings,
+                previous_epoch_participation,
+                current_epoch_participation,
+                justification_bits,
+                previous_justified_checkpoint,
+                current_justified_checkpoint,
+                finalized_checkpoint,
+                inactivity_scores,
+                current_sync_committee,
+                next_sync_committee,
+                $($field,)*
+            ],
+        )
+    };
+}
+
+pub fn state_field_roots<P: Preset>(state: &BeaconState<P>) -> Vec<H256> {
+    match state {
+        BeaconState::Phase0(state) => field_roots!(
+            state,
+            [
+                genesis_time,
+                genesis_validators_root,
+                slot,
+                fork,
+                latest_block_header,
+                block_roots,
+                state_roots,
+                historical_roots,
+                eth1_data,
+                eth1_data_votes,
+                eth1_deposit_index,
+                validators,
+                balances,
+                randao_mixes,
+                slashings,
+                previous_epoch_attestations,
+                current_epoch_attestations,
+                justification_bits,
+                previous_justified_checkpoint,
+                current_justified_checkpoint,
+                finalized_checkpoint,
+            ],
+        ),
+        BeaconState::Altair(state) => post_altair_state_field_roots!(state, []),
+        BeaconState::Bellatrix(state) => {
+            post_altair_state_field_roots!(state, [latest_execution_payload_header])
+        }
+        BeaconState::Capella(state) => post_altair_state_field_roots!(
+            state,
+            [
+                latest_execution_payload_header,
+                next_withdrawal_index,
+                next_withdrawal_validator_index,
+                historical_summaries,
+            ],
+        ),
+        BeaconState::Deneb(state) => post_altair_state_field_roots!(
+            state,
+            [
+                latest_execution_payload_header,
+                next_withdrawal_index,
+                next_withdrawal_validator_index,
+                historical_summaries,
+            ],
+        ),
+    }
+}
+
+pub fn block_field_roots<P: Preset>(block: &SignedBeaconBlock<P>) -> Vec<H256> {
+    match block {
+        SignedBeaconBlock::Phase0(block) => {
+            field_roots!(
+                block.message,
+                [slot, proposer_index, parent_root, state_root, body]
+            )
+        }
+        SignedBeaconBlock::Altair(block) => {
+            field_roots!(
+                block.message,
+                [slot, proposer_index, parent_root, state_root, body]
+            )
+        }
+        SignedBeaconBlock::Bellatrix(block) => {
+            field_roots!(
+                block.message,
+                [slot, proposer_index, parent_root, state_root, body]
+            )
+        }
+        SignedBeaconBlock::Capella(block) => {
+            field_roots!(
+                block.message,
+                [slot, proposer_index, parent_root, state_root, body]
+            )
+        }
+        SignedBeaconBlock::Deneb(block) => {
+            field_roots!(
+                block.message,
+                [slot, proposer_index, parent_root, state_root, body]
+            )
+        }
+    }
+}
+
+pub fn block_body_field_roots<P: Preset>(block: &SignedBeaconBlock<P>) -> Vec<H256> {
+    match block {
+        SignedBeaconBlock::Phase0(block) => field_roots!(
+            block.message.body,
+            [
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+            ],
+        ),
+        SignedBeaconBlock::Altair(block) => field_roots!(
+            block.message.body,
+            [
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+            ],
+        ),
+        SignedBeaconBlock::Bellatrix(block) => field_roots!(
+            block.message.body,
+            [
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+                execution_payload,
+            ],
+        ),
+        SignedBeaconBlock::Capella(block) => field_roots!(
+            block.message.body,
+            [
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+                execution_payload,
+                bls_to_execution_changes,
+            ],
+        ),
+        SignedBeaconBlock::Deneb(block) => field_roots!(
+            block.message.body,
+            [
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+                execution_payload,
+                bls_to_execution_changes,
+                blob_kzg_commitments,
+            ],
+        ),
+    }
+}
@@ -0,0 +1,386 @@
+// Proofs of ancestry for block roots, verifiable against the root of a single `BeaconState`.
+//
+// Block roots of the last `SLOTS_PER_HISTORICAL_ROOT` slots are proven directly against
+// `state.block_roots`. Older block roots are first proven against the `block_roots` of the state
+// at the end of their period and then against the corresponding entry of
+// `state.historical_summaries` (or `state.historical_roots` for periods before Capella).
+//
+// Both `HistoricalBatch` and `HistoricalSummary` have the root of `block_roots` as their first
+// field, so the proofs for the two accumulators have the same shape:
+// ```text
+// state root
+// └ state.historical_summaries (field 27) or state.historical_roots (field 7)
+//   └ data root (length mixed in)
+//     └ historical_summaries[period] or historical_roots[period]
+//       └ block_summary_root or HistoricalBatch.block_roots (sibling is the state roots part)
+//         └ block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]
+// ```
+
+use anyhow::{ensure, Result};
+use hashing::ZERO_HASHES;
+use itertools::Itertools as _;
+use ssz::{PersistentList, SszHash};
+use typenum::Unsigned as _;
+use types::{
+    capella::containers::HistoricalSummary,
+    combined::BeaconState,
+    phase0::primitives::{Slot, H256},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+use crate::{error::Error, field_roots::state_field_roots};
+
+const STATE_DEPTH: u32 = 5;
+const BLOCK_ROOTS_FIELD_INDEX: u64 = 5;
+const HISTORICAL_ROOTS_FIELD_INDEX: u64 = 7;
+const HISTORICAL_SUMMARIES_FIELD_INDEX: u64 = 27;
+
+pub struct BlockRootProof {
+    pub block_root: H256,
+    /// Generalized index of the block root in the tree of the state the proof was constructed for.
+    pub gindex: u64,
+    /// Sibling nodes from the leaf up, suitable for [`is_valid_merkle_branch`].
+    ///
+    /// [`is_valid_merkle_branch`]: crate::predicates::is_valid_merkle_branch
+    pub branch: Vec<H256>,
+}
+
+impl BlockRootProof {
+    /// The index to pass to [`is_valid_merkle_branch`](crate::predicates::is_valid_merkle_branch).
+    #[must_use]
+    pub const fn index(&self) -> u64 {
+        self.gindex ^ (1 << self.gindex.ilog2())
+    }
+}
+
+/// Returns the slot of the state needed to prove the block root at `slot` against `state`.
+///
+/// Returns `None` if the block root can be proven using `state` alone.
+#[must_use]
+pub fn historical_state_slot<P: Preset>(state: &BeaconState<P>, slot: Slot) -> Option<Slot> {
+    let slots_per_historical_root = P::SlotsPerHistoricalRoot::U64;
+
+    if slot + slots_per_historical_root >= state.slot() {
+        return None;
+    }
+
+    Some((slot / slots_per_historical_root + 1) * slots_per_historical_root)
+}
+
+/// Constructs a Merkle proof of the block root at `slot` against `state.hash_tree_root()`.
+///
+/// `historical_state` must be the state at the slot returned by [`historical_state_slot`]
+/// if that returns `Some`. It is ignored otherwise.
+pub fn block_root_proof<P: Preset>(
+    state: &BeaconState<P>,
+    historical_state: Option<&BeaconState<P>>,
+    slot: Slot,
+) -> Result<BlockRootProof> {
+    ensure!(slot < state.slot(), Error::SlotOutOfRange);
+
+    let slots_per_historical_root = P::SlotsPerHistoricalRoot::U64;
+    let block_roots_depth = P::SlotsPerHistoricalRoot::U64.ilog2();
+    let historical_roots_depth = P::HistoricalRootsLimit::U64.ilog2();
+    let index_in_block_roots = slot % slots_per_historical_root;
+
+    let Some(historical_state_slot) = historical_state_slot(state, slot) else {
+        let block_roots = state.block_roots();
+        let block_root = *block_roots.mod_index(slot);
+
+        let branch = merkle_branch(
+            block_roots.into_iter().copied(),
+            index_in_block_roots,
+            block_roots_depth,
+        )
+        .into_iter()
+        .chain(state_branch(state, BLOCK_ROOTS_FIELD_INDEX))
+        .collect();
+
+        let gindex = (1 << (STATE_DEPTH + block_roots_depth))
+            | BLOCK_ROOTS_FIELD_INDEX << block_roots_depth
+            | index_in_block_roots;
+
+        return Ok(BlockRootProof {
+            block_root,
+            gindex,
+            branch,
+        });
+    };
+
+    let historical_state = historical_state.ok_or(Error::HistoricalStateMissing)?;
+
+    ensure!(
+        historical_state.slot() == historical_state_slot,
+        Error::HistoricalStateMismatch,
+    );
+
+    let block_roots = historical_state.block_roots();
+    let block_roots_root = block_roots.hash_tree_root();
+    let state_roots_root = historical_state.state_roots().hash_tree_root();
+    let block_root = *block_roots.mod_index(slot);
+
+    let period = slot / slots_per_historical_root;
+    let historical_roots = state.historical_roots();
+    let frozen_periods = historical_roots.len_usize().try_into()?;
+
+    let (field_index, accumulator_index, accumulator_roots) = match historical_summaries(state) {
+        Some(historical_summaries) if period >= frozen_periods => {
+            let summary_index = period - frozen_periods;
+
+            let summary = historical_summaries
+                .get(summary_index)
+                .map_err(|_| Error::SlotOutOfRange)?;
+
+            ensure!(
+                summary.block_summary_root == block_roots_root
+                    && summary.state_summary_root == state_roots_root,
+                Error::HistoricalStateMismatch,
+            );
+
+            let roots = historical_summaries
+                .into_iter()
+                .map(SszHash::hash_tree_root)
+                .collect_vec();
+
+            (HISTORICAL_SUMMARIES_FIELD_INDEX, summary_index, roots)
+        }
+        _ => {
+            let historical_root = historical_roots
+                .get(period)
+                .map_err(|_| Error::SlotOutOfRange)?;
+
+            ensure!(
+                *historical_root == hashing::hash_256_256(block_roots_root, state_roots_root),
+                Error::HistoricalStateMismatch,
+            );
+
+            let roots = historical_roots.into_iter().copied().collect_vec();
+
+            (HISTORICAL_ROOTS_FIELD_INDEX, period, roots)
+        }
+    };
+
+    let accumulator_length = accumulator_roots.len().try_into()?;
+
+    let branch = merkle_branch(
+        block_roots.into_iter().copied(),
+        index_in_block_roots,
+        block_roots_depth,
+    )
+    .into_iter()
+    .chain(core::iter::once(state_roots_root))
+    .chain(merkle_branch(
+        accumulator_roots,
+        accumulator_index,
+        historical_roots_depth,
+    ))
+    .chain(core::iter::once(length_chunk(accumulator_length)))
+    .chain(state_branch(state, field_index))
+    .collect();
+
+    // The `0` bits below the field index and the block roots index select the
+    // data root of the list and the `block_roots` part of the accumulated entry.
+    let depth = block_roots_depth + 1 + historical_roots_depth + 1 + STATE_DEPTH;
+    let gindex = (1 << depth)
+        | field_index << (depth - STATE_DEPTH)
+        | accumulator_index << (block_roots_depth + 1)
+        | index_in_block_roots;
+
+    Ok(BlockRootProof {
+        block_root,
+        gindex,
+        branch,
+    })
+}
+
+fn historical_summaries<P: Preset>(
+    state: &BeaconState<P>,
+) -> Option<&PersistentList<HistoricalSummary, P::HistoricalRootsLimit>> {
+    match state {
+        BeaconState::Phase0(_) | BeaconState::Altair(_) | BeaconState::Bellatrix(_) => None,
+        BeaconState::Capella(state) => Some(&state.historical_summaries),
+        BeaconState::Deneb(state) => Some(&state.historical_summaries),
+    }
+}
+
+fn state_branch<P: Preset>(state: &BeaconState<P>, field_index: u64) -> Vec<H256> {
+    merkle_branch(state_field_roots(state), field_index, STATE_DEPTH)
+}
+
+// Missing leaves and subtrees are treated as zero hashes, which makes this work for lists too.
+// The length of a list has to be mixed in separately.
+fn merkle_branch(leaves: impl IntoIterator<Item = H256>, index: u64, depth: u32) -> Vec<H256> {
+    let mut nodes = leaves.into_iter().collect_vec();
+    let mut index = index;
+    let depth = depth
+        .try_into()
+        .expect("number of bits in u64 should fit in usize");
+
+    let mut branch = Vec::with_capacity(depth);
+
+    for zero_hash in ZERO_HASHES.into_iter().take(depth) {
+        let sibling = usize::try_from(index ^ 1)
+            .ok()
+            .and_then(|sibling_index| nodes.get(sibling_index))
+            .copied()
+            .unwrap_or(zero_hash);
+
+        branch.push(sibling);
+
+        if nodes.len() % 2 == 1 {
+            nodes.push(zero_hash);
+        }
+
+        nodes = nodes
+            .into_iter()
+            .tuples()
+            .map(|(left, right)| hashing::hash_256_256(left, right))
+            .collect();
+
+        index /= 2;
+    }
+
+    branch
+}
+
+fn length_chunk(length: u64) -> H256 {
+    let mut chunk = H256::zero();
+    chunk[..core::mem::size_of::<u64>()].copy_from_slice(&length.to_le_bytes());
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        capella::{beacon_state::BeaconState as CapellaBeaconState, containers::HistoricalSummary},
+        phase0::beacon_state::BeaconState as Phase0BeaconState,
+        preset::Minimal,
+    };
+
+    use crate::predicates;
+
+    use super::*;
+
+    fn root(byte: u8) -> H256 {
+        H256::repeat_byte(byte)
+    }
+
+    fn assert_valid(state: &BeaconState<Minimal>, proof: &BlockRootProof) {
+        assert_eq!(1 << proof.branch.len(), proof.gindex & !proof.index());
+
+        assert!(predicates::is_valid_merkle_branch(
+            proof.block_root,
+            proof.branch.iter().copied(),
+            proof.index(),
+            state.hash_tree_root(),
+        ));
+    }
+
+    fn historical_phase0_state() -> Phase0BeaconState<Minimal> {
+        let mut state = Phase0BeaconState::<Minimal> {
+            slot: 64,
+            ..Phase0BeaconState::default()
+        };
+
+        for slot in 0..64 {
+            *state.block_roots.mod_index_mut(slot) = root(slot.try_into().expect("slot fits"));
+            *state.state_roots.mod_index_mut(slot) = root(0xff);
+        }
+
+        state
+    }
+
+    #[test]
+    fn proves_recent_block_roots() -> Result<()> {
+        let mut state = Phase0BeaconState::<Minimal> {
+            slot: 100,
+            ..Phase0BeaconState::default()
+        };
+
+        *state.block_roots.mod_index_mut(90) = root(1);
+
+        let state = BeaconState::from(state);
+
+        assert_eq!(historical_state_slot(&state, 90), None);
+
+        let proof = block_root_proof(&state, None, 90)?;
+
+        assert_eq!(proof.block_root, root(1));
+        assert_valid(&state, &proof);
+
+        Ok(())
+    }
+
+    #[test]
+    fn proves_block_roots_accumulated_in_historical_roots() -> Result<()> {
+        let historical_state = BeaconState::from(historical_phase0_state());
+
+        let mut state = Phase0BeaconState::<Minimal> {
+            slot: 200,
+            ..Phase0BeaconState::default()
+        };
+
+        let historical_root = hashing::hash_256_256(
+            historical_state.block_roots().hash_tree_root(),
+            historical_state.state_roots().hash_tree_root(),
+        );
+
+        state.historical_roots.push(historical_root)?;
+        state.historical_roots.push(root(2))?;
+
+        let state = BeaconState::from(state);
+
+        assert_eq!(historical_state_slot(&state, 10), Some(64));
+        assert!(block_root_proof(&state, None, 10).is_err());
+
+        let proof = block_root_proof(&state, Some(&historical_state), 10)?;
+
+        assert_eq!(proof.block_root, root(10));
+        assert_valid(&state, &proof);
+
+        Ok(())
+    }
+
+    #[test]
+    fn proves_block_roots_accumulated_in_historical_summaries() -> Result<()> {
+        let historical_state = BeaconState::from(historical_phase0_state());
+
+        let mut state = CapellaBeaconState::<Minimal> {
+            slot: 200,
+            ..CapellaBeaconState::default()
+        };
+
+        state.historical_summaries.push(HistoricalSummary {
+            block_summary_root: historical_state.block_roots().hash_tree_root(),
+            state_summary_root: historical_state.state_roots().hash_tree_root(),
+        })?;
+
+        let state = BeaconState::from(state);
+        let proof = block_root_proof(&state, Some(&historical_state), 33)?;
+
+        assert_eq!(proof.block_root, root(33));
+        assert_valid(&state, &proof);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_mismatched_historical_states() {
+        let historical_state = BeaconState::from(historical_phase0_state());
+
+        let mut state = Phase0BeaconState::<Minimal> {
+            slot: 200,
+            ..Phase0BeaconState::default()
+        };
+
+        state
+            .historical_roots
+            .push(root(3))
+            .expect("historical_roots should have room for one root");
+
+        let state = BeaconState::from(state);
+
+        assert!(block_root_proof(&state, Some(&historical_state), 10).is_err());
+    }
+}
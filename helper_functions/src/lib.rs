@@ -3,7 +3,9 @@ pub mod altair;
 pub mod bellatrix;
 pub mod error;
 pub mod fork;
+pub mod historical_proofs;
 pub mod misc;
+pub mod multiproofs;
 pub mod mutators;
 pub mod phase0;
 pub mod predicates;
@@ -11,6 +13,8 @@ pub mod signing;
 pub mod slot_report;
 pub mod verifier;
 
+mod field_roots;
+
 // The runner for `bls/eth_fast_aggregate_verify` test cases uses `Verifier` from this crate.
 // The runner had to be moved here due to an unexpected issue with cyclic dependencies. See:
 // - <https://github.com/rust-lang/rust/issues/59305>
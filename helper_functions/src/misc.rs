@@ -17,7 +17,7 @@ use types::{
     combined::SignedBeaconBlock,
     config::Config,
     deneb::{
-        consts::{BlobSidecarSubnetCount, VERSIONED_HASH_VERSION_KZG},
+        consts::VERSIONED_HASH_VERSION_KZG,
         containers::BlobSidecar,
         primitives::{Blob, BlobIndex, KzgCommitment, KzgProof, VersionedHash},
     },
@@ -198,9 +198,16 @@ pub fn compute_subnet_for_attestation<P: Preset>(
 }
 
 /// [`compute_subnet_for_blob_sidecar`](https://github.com/ethereum/consensus-specs/blob/v1.4.0-beta.1/specs/deneb/validator.md#sidecar)
+///
+/// The number of subnets is taken from the phase of the sidecar's block
+/// because later phases may increase it along with the maximum number of blobs per block.
 #[must_use]
-pub fn compute_subnet_for_blob_sidecar(blob_index: BlobIndex) -> SubnetId {
-    blob_index.mod_typenum::<BlobSidecarSubnetCount>()
+pub fn compute_subnet_for_blob_sidecar<P: Preset>(
+    config: &Config,
+    blob_sidecar: &BlobSidecar<P>,
+) -> SubnetId {
+    let phase = config.phase_at_slot::<P>(blob_sidecar.signed_block_header.message.slot);
+    blob_sidecar.index % config.blob_sidecar_subnet_count(phase)
 }
 
 /// <https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/altair/validator.md#broadcast-sync-committee-message>
@@ -450,6 +457,36 @@ pub fn construct_blob_sidecars<P: Preset>(
     Ok(vec![])
 }
 
+pub fn construct_blob_sidecar<P: Preset>(
+    signed_block: &SignedBeaconBlock<P>,
+    index: BlobIndex,
+    blob: Blob<P>,
+    kzg_proof: KzgProof,
+) -> Result<BlobSidecar<P>> {
+    let post_deneb_block_body = signed_block
+        .message()
+        .body()
+        .post_deneb()
+        .ok_or(Error::BlobKzgCommitmentMissing)?;
+
+    let kzg_commitment = *post_deneb_block_body
+        .blob_kzg_commitments()
+        .get(usize::try_from(index)?)
+        .ok_or(Error::BlobKzgCommitmentMissing)?;
+
+    Ok(BlobSidecar {
+        index,
+        blob,
+        kzg_commitment,
+        kzg_proof,
+        signed_block_header: signed_block.to_header(),
+        kzg_commitment_inclusion_proof: kzg_commitment_inclusion_proof(
+            post_deneb_block_body,
+            index,
+        )?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -457,7 +494,10 @@ mod tests {
     use hex_literal::hex;
     use itertools::iproduct;
     use nonzero_ext::nonzero;
+    use ssz::ContiguousList;
     use types::{
+        combined::BeaconBlock,
+        deneb::containers::BeaconBlock as DenebBeaconBlock,
         nonstandard::RelativeEpoch,
         phase0::{
             beacon_state::BeaconState as Phase0BeaconState,
@@ -577,4 +617,48 @@ mod tests {
             compute_subscribed_subnets::<Minimal>(node_id, &config, epoch).ok();
         }
     }
+
+    #[test]
+    fn test_compute_subnet_for_blob_sidecar_uses_configured_subnet_count() {
+        let blob_sidecar = BlobSidecar::<Minimal> {
+            index: 7,
+            ..BlobSidecar::default()
+        };
+
+        let mut config = Config::minimal();
+
+        assert_eq!(compute_subnet_for_blob_sidecar(&config, &blob_sidecar), 1);
+
+        config.blob_sidecar_subnet_count = nonzero!(9_u64);
+
+        assert_eq!(compute_subnet_for_blob_sidecar(&config, &blob_sidecar), 7);
+    }
+
+    #[test]
+    fn test_construct_blob_sidecar_matches_construct_blob_sidecars() -> Result<()> {
+        let mut block = DenebBeaconBlock::<Minimal>::default();
+
+        block.body.blob_kzg_commitments =
+            ContiguousList::try_from(vec![KzgCommitment::repeat_byte(1); 2])?;
+
+        let block = BeaconBlock::from(block).with_zero_signature();
+        let blobs = [Blob::<Minimal>::default(), Blob::<Minimal>::default()];
+        let proofs = [KzgProof::repeat_byte(2), KzgProof::repeat_byte(3)];
+
+        let expected = construct_blob_sidecars(&block, blobs.into_iter(), proofs.into_iter())?;
+        let actual = construct_blob_sidecar(
+            &block,
+            1,
+            Blob::<Minimal>::default(),
+            KzgProof::repeat_byte(3),
+        )?;
+
+        assert_eq!(actual, expected[1]);
+        assert!(
+            construct_blob_sidecar(&block, 2, Blob::<Minimal>::default(), KzgProof::default())
+                .is_err()
+        );
+
+        Ok(())
+    }
 }
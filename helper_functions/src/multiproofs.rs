@@ -0,0 +1,140 @@
+// Merkle multiproofs of parts of blocks and states, verifiable against their roots.
+//
+// Generalized indices are relative to the root of the block or state being proven.
+// Proofs can include any node down to the roots of fields. Proofs for blocks can also include
+// fields of the block body. Proofs for states can also include fields of the checkpoints, which
+// is needed to prove finality to light clients:
+// ```text
+// state root
+// └ state.finalized_checkpoint (field 20, gindex 52)
+//   └ root (gindex 105)
+// ```
+
+use ssz::{MerkleNodes, Multiproof, MultiproofError, SszHash as _};
+use types::{
+    combined::{BeaconState, SignedBeaconBlock},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+use crate::field_roots::{block_body_field_roots, block_field_roots, state_field_roots};
+
+const BODY_FIELD_INDEX: u64 = 4;
+const PREVIOUS_JUSTIFIED_CHECKPOINT_FIELD_INDEX: u64 = 18;
+const CURRENT_JUSTIFIED_CHECKPOINT_FIELD_INDEX: u64 = 19;
+const FINALIZED_CHECKPOINT_FIELD_INDEX: u64 = 20;
+
+/// Constructs a multiproof of the nodes at `gindices` against `block.message().hash_tree_root()`.
+pub fn block_multiproof<P: Preset>(
+    block: &SignedBeaconBlock<P>,
+    gindices: &[u64],
+) -> Result<Multiproof, MultiproofError> {
+    let mut nodes = MerkleNodes::default();
+    let field_roots = block_field_roots(block);
+    let body_gindex = field_gindex(field_roots.len(), BODY_FIELD_INDEX);
+
+    nodes.insert_subtree(1, field_roots);
+    nodes.insert_subtree(body_gindex, block_body_field_roots(block));
+
+    nodes.multiproof(gindices)
+}
+
+/// Constructs a multiproof of the nodes at `gindices` against `state.hash_tree_root()`.
+pub fn state_multiproof<P: Preset>(
+    state: &BeaconState<P>,
+    gindices: &[u64],
+) -> Result<Multiproof, MultiproofError> {
+    let mut nodes = MerkleNodes::default();
+    let field_roots = state_field_roots(state);
+    let field_count = field_roots.len();
+
+    nodes.insert_subtree(1, field_roots);
+
+    for (field_index, checkpoint) in [
+        (
+            PREVIOUS_JUSTIFIED_CHECKPOINT_FIELD_INDEX,
+            state.previous_justified_checkpoint(),
+        ),
+        (
+            CURRENT_JUSTIFIED_CHECKPOINT_FIELD_INDEX,
+            state.current_justified_checkpoint(),
+        ),
+        (
+            FINALIZED_CHECKPOINT_FIELD_INDEX,
+            state.finalized_checkpoint(),
+        ),
+    ] {
+        nodes.insert_subtree(
+            field_gindex(field_count, field_index),
+            [checkpoint.epoch.hash_tree_root(), checkpoint.root],
+        );
+    }
+
+    nodes.multiproof(gindices)
+}
+
+fn field_gindex(field_count: usize, field_index: u64) -> u64 {
+    1 << field_count.next_power_of_two().ilog2() | field_index
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        altair::beacon_state::BeaconState as AltairBeaconState,
+        combined::BeaconBlock,
+        deneb::containers::BeaconBlock as DenebBeaconBlock,
+        phase0::{containers::Checkpoint, primitives::H256},
+        preset::Minimal,
+        traits::SignedBeaconBlock as _,
+    };
+
+    use super::*;
+
+    #[test]
+    fn state_multiproof_proves_finality_and_sync_committees() -> Result<(), MultiproofError> {
+        let root = H256::repeat_byte(1);
+
+        let state = BeaconState::from(AltairBeaconState::<Minimal> {
+            slot: 100,
+            finalized_checkpoint: Checkpoint { epoch: 3, root },
+            ..AltairBeaconState::default()
+        });
+
+        let multiproof = state_multiproof(&state, &[105, 54, 55])?;
+
+        assert_eq!(multiproof.leaves[0], root);
+        assert!(multiproof.verify(state.hash_tree_root()));
+
+        // Separate branches would contain 6 + 5 + 5 nodes.
+        // Most of them are shared or can be computed from other leaves.
+        assert_eq!(multiproof.proof.len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_multiproof_proves_fields_of_body() -> Result<(), MultiproofError> {
+        let block = BeaconBlock::from(DenebBeaconBlock::<Minimal> {
+            slot: 7,
+            ..DenebBeaconBlock::default()
+        })
+        .with_zero_signature();
+
+        // `block.slot` and `block.body.execution_payload`.
+        let multiproof = block_multiproof(&block, &[8, 12 << 4 | 9])?;
+
+        assert!(multiproof.verify(block.message().hash_tree_root()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiproofs_of_nodes_below_fields_are_rejected() {
+        let block = BeaconBlock::from(DenebBeaconBlock::<Minimal>::default()).with_zero_signature();
+
+        assert_eq!(
+            block_multiproof(&block, &[8 << 1]),
+            Err(MultiproofError::NodeMissing { gindex: 8 << 1 }),
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+
+use anyhow::{Error as AnyhowError, Result};
+use bytesize::ByteSize;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use futures::lock::Mutex;
+use serde::Serialize;
+use slashing_protection::SlashingProtector;
+use types::preset::Preset;
+
+use crate::error::Error;
+
+#[derive(Serialize)]
+pub struct BackupResponse {
+    directory: PathBuf,
+}
+
+/// Directory to write backups to. Backups are disabled if it is not set.
+#[derive(Clone, Default)]
+pub struct BackupDirectory(pub Option<PathBuf>);
+
+/// `POST /grandine/v1/backup`
+///
+/// The beacon databases and the slashing protection database are written in the same layout as
+/// the store directory, so a node can be restored by pointing `--data-dir` at the backup.
+pub async fn post_backup<P: Preset, W: Wait>(
+    controller: ApiController<P, W>,
+    slashing_protector: Arc<Mutex<SlashingProtector>>,
+    BackupDirectory(backup_directory): BackupDirectory,
+    db_size: ByteSize,
+) -> Result<BackupResponse, Error> {
+    let backup_directory = backup_directory.ok_or(Error::BackupsNotEnabled)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(AnyhowError::new)?
+        .as_secs();
+
+    let directory = backup_directory.join(format!("backup_{timestamp}"));
+
+    // The lock is only held while opening a separate connection so that signing is not blocked.
+    let slashing_protection_backup = slashing_protector.lock().await.prepare_backup()?;
+
+    // Copying a mainnet database takes long enough to stall other requests.
+    tokio::task::spawn_blocking({
+        let directory = directory.clone();
+
+        move || {
+            controller.backup_storage(&directory, db_size)?;
+            slashing_protection_backup.write_to(&directory)
+        }
+    })
+    .await??;
+
+    Ok(BackupResponse { directory })
+}
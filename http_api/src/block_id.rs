@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
+use anyhow::Error as AnyhowError;
 use eth1_api::ApiController;
-use fork_choice_control::Wait;
+use fork_choice_control::{StorageError, Wait};
 use genesis::GenesisProvider;
 use http_api_utils::BlockId;
 use types::{
@@ -20,9 +21,10 @@ pub fn block<P: Preset, W: Wait>(
         BlockId::Genesis => Some(WithStatus::valid_and_finalized(genesis_provider.block())),
         BlockId::Finalized => Some(controller.last_finalized_block()),
         BlockId::Slot(slot) => controller
-            .block_by_slot(slot)?
+            .block_by_slot(slot)
+            .map_err(block_lookup_error)?
             .map(|with_status| with_status.map(|block_with_root| block_with_root.block)),
-        BlockId::Root(root) => controller.block_by_root(root)?,
+        BlockId::Root(root) => controller.block_by_root(root).map_err(block_lookup_error)?,
     }
     .ok_or(Error::BlockNotFound)
 }
@@ -45,3 +47,11 @@ pub fn block_root<P: Preset, W: Wait>(
     }
     .ok_or(Error::BlockNotFound)
 }
+
+// Let clients know to fall back to `GET /eth/v1/beacon/blinded_blocks/{block_id}`.
+fn block_lookup_error(error: AnyhowError) -> Error {
+    match error.downcast_ref() {
+        Some(StorageError::ExecutionPayloadPruned { .. }) => Error::ExecutionPayloadPruned,
+        _ => Error::Internal(error),
+    }
+}
@@ -0,0 +1,70 @@
+use axum::extract::State;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use genesis::GenesisProvider;
+use helper_functions::historical_proofs;
+use serde::Serialize;
+use ssz::SszHash as _;
+use types::{
+    nonstandard::WithStatus,
+    phase0::primitives::{Slot, H256},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+use crate::{error::Error, extractors::EthPath, response::EthResponse, state_id::StateId};
+
+#[derive(Serialize)]
+pub struct StateBlockRootProofResponse {
+    #[serde(with = "serde_utils::string_or_native")]
+    slot: Slot,
+    block_root: H256,
+    state_root: H256,
+    #[serde(with = "serde_utils::string_or_native")]
+    gindex: u64,
+    branch: Vec<H256>,
+}
+
+/// `GET /grandine/v1/beacon/states/{state_id}/block_root_proof/{slot}`
+///
+/// Not part of the standard API. Proves the block root at `slot` against the root of the state.
+/// Block roots older than `SLOTS_PER_HISTORICAL_ROOT` slots are proven through
+/// `historical_summaries` or `historical_roots`, which requires an archived state.
+pub async fn get_state_block_root_proof<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath((state_id, slot)): EthPath<(StateId, Slot)>,
+) -> Result<EthResponse<StateBlockRootProofResponse>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider)?;
+
+    if slot >= state.slot() {
+        return Err(Error::SlotNotBeforeState);
+    }
+
+    let historical_state = historical_proofs::historical_state_slot(&state, slot)
+        .map(|historical_state_slot| {
+            controller
+                .state_at_slot(historical_state_slot)?
+                .map(WithStatus::value)
+                .ok_or(Error::StateNotFound)
+        })
+        .transpose()?;
+
+    let proof = historical_proofs::block_root_proof(&state, historical_state.as_deref(), slot)?;
+
+    let response = StateBlockRootProofResponse {
+        slot,
+        block_root: proof.block_root,
+        state_root: state.hash_tree_root(),
+        gindex: proof.gindex,
+        branch: proof.branch,
+    };
+
+    Ok(EthResponse::json(response)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use serde::Serialize;
+use types::preset::Preset;
+
+use crate::error::Error;
+
+#[derive(Serialize)]
+pub struct CompactionResponse {
+    reclaimed_bytes: u64,
+}
+
+/// `POST /grandine/v1/admin/compact`
+///
+/// Only RocksDB databases can be compacted while the node is running.
+/// MDBX databases can be compacted with `grandine db compact` while the node is stopped.
+pub async fn post_compaction<P: Preset, W: Wait>(
+    controller: ApiController<P, W>,
+) -> Result<CompactionResponse, Error> {
+    if !controller.storage_supports_online_compaction() {
+        return Err(Error::OnlineCompactionUnsupported);
+    }
+
+    // Compacting a mainnet database can take minutes.
+    let reclaimed_bytes =
+        tokio::task::spawn_blocking(move || controller.compact_storage()).await??;
+
+    Ok(CompactionResponse { reclaimed_bytes })
+}
@@ -0,0 +1,125 @@
+// Fingerprints of the effective configuration for detecting drift between nodes of a fleet.
+//
+// Only settings that are expected to be identical across a fleet are included.
+// Paths, ports and credentials normally differ between nodes and are left out.
+//
+// Feature flags can be changed at runtime through `PATCH /features`,
+// so fingerprints are computed on every request rather than once at startup.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use features::Feature;
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+use types::{config::Config as ChainConfig, phase0::primitives::H256};
+
+use crate::global;
+
+/// Storage settings that affect which data a node keeps.
+#[derive(Clone, Copy, Default, Debug, Serialize)]
+pub struct StorageSettings {
+    pub in_memory: bool,
+    pub db_size: u64,
+    pub eth1_db_size: u64,
+    pub archival_epoch_interval: u64,
+    pub prune_storage: bool,
+    pub prune_history_epochs: Option<u64>,
+    pub archival_snapshot_interval: Option<u64>,
+    pub blob_retention_epochs: Option<u64>,
+    pub separate_archive: bool,
+    pub database_backend: &'static str,
+    pub compression: &'static str,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize)]
+pub struct ConfigFingerprint {
+    fingerprint: H256,
+    chain_config: H256,
+    features: H256,
+    storage: H256,
+}
+
+/// `GET /grandine/v1/config_fingerprint`
+pub fn get_config_fingerprint(
+    chain_config: &ChainConfig,
+    storage_settings: StorageSettings,
+) -> Result<ConfigFingerprint> {
+    fingerprint(chain_config, &global::get_features(), storage_settings)
+}
+
+fn fingerprint(
+    chain_config: &ChainConfig,
+    features: &BTreeMap<Feature, bool>,
+    storage_settings: StorageSettings,
+) -> Result<ConfigFingerprint> {
+    let chain_config = hash_json(chain_config)?;
+    let features = hash_json(features)?;
+    let storage = hash_json(storage_settings)?;
+
+    // Hashes of individual parts are included to make it easier to find what differs.
+    let fingerprint = hash_json([chain_config, features, storage])?;
+
+    Ok(ConfigFingerprint {
+        fingerprint,
+        chain_config,
+        features,
+        storage,
+    })
+}
+
+// Serialization to JSON is deterministic as long as values contain no hash maps.
+fn hash_json(value: impl Serialize) -> Result<H256> {
+    let bytes = serde_json::to_vec(&value)?;
+    Ok(H256(Sha256::digest(bytes).into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_changes_only_with_relevant_parts() -> Result<()> {
+        let mainnet = ChainConfig::mainnet();
+        let features = BTreeMap::from([(Feature::DebugP2p, false)]);
+        let storage_settings = StorageSettings::default();
+
+        let original = fingerprint(&mainnet, &features, storage_settings)?;
+
+        assert_eq!(
+            fingerprint(&mainnet, &features, storage_settings)?,
+            original
+        );
+
+        let changed_chain_config =
+            fingerprint(&ChainConfig::holesky(), &features, storage_settings)?;
+
+        assert_ne!(changed_chain_config.fingerprint, original.fingerprint);
+        assert_ne!(changed_chain_config.chain_config, original.chain_config);
+        assert_eq!(changed_chain_config.features, original.features);
+        assert_eq!(changed_chain_config.storage, original.storage);
+
+        let changed_features = fingerprint(
+            &mainnet,
+            &BTreeMap::from([(Feature::DebugP2p, true)]),
+            storage_settings,
+        )?;
+
+        assert_ne!(changed_features.fingerprint, original.fingerprint);
+        assert_ne!(changed_features.features, original.features);
+
+        let changed_storage = fingerprint(
+            &mainnet,
+            &features,
+            StorageSettings {
+                prune_storage: true,
+                ..storage_settings
+            },
+        )?;
+
+        assert_ne!(changed_storage.fingerprint, original.fingerprint);
+        assert_ne!(changed_storage.storage, original.storage);
+
+        Ok(())
+    }
+}
@@ -40,6 +40,7 @@ use types::{
 use validator::{Validator, ValidatorChannels, ValidatorConfig};
 
 use crate::{
+    config_fingerprint::StorageSettings,
     http_api_config::HttpApiConfig,
     middleware,
     routing::{self, TestState},
@@ -100,6 +101,7 @@ impl<P: Preset> Context<P> {
             futures::channel::mpsc::unbounded();
         let (sync_to_api_tx, sync_to_api_rx) = futures::channel::mpsc::unbounded();
         let (subnet_service_tx, subnet_service_rx) = futures::channel::mpsc::unbounded();
+        let (subnet_service_to_attestation_verifier_tx, _) = futures::channel::mpsc::unbounded();
         let (validator_to_api_tx, validator_to_api_rx) = futures::channel::mpsc::unbounded();
         let (validator_to_liveness_tx, validator_to_liveness_rx) =
             futures::channel::mpsc::unbounded();
@@ -145,6 +147,7 @@ impl<P: Preset> Context<P> {
         let storage = Arc::new(Storage::new(
             chain_config.clone_arc(),
             Database::in_memory(),
+            None,
             DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
             false,
         ));
@@ -211,7 +214,7 @@ impl<P: Preset> Context<P> {
         }
 
         let execution_service =
-            ExecutionService::new(eth1_api, controller.clone_arc(), execution_service_rx);
+            ExecutionService::new(eth1_api, controller.clone_arc(), None, execution_service_rx);
 
         let signer = Signer::new(validator_keys, client, Web3SignerConfig::default(), None);
         let validator_keys = Arc::new(signer.keys().copied().collect());
@@ -290,7 +293,7 @@ impl<P: Preset> Context<P> {
             None,
             keymanager.proposer_configs().clone_arc(),
             signer,
-            slashing_protector,
+            slashing_protector.clone_arc(),
             sync_committee_agg_pool.clone_arc(),
             bls_to_execution_change_pool.clone_arc(),
             None,
@@ -305,6 +308,7 @@ impl<P: Preset> Context<P> {
             attestation_agg_pool.clone_arc(),
             NodeId::ZERO,
             subnet_service_to_p2p_tx,
+            subnet_service_to_attestation_verifier_tx,
             fc_to_subnet_rx,
             subnet_service_rx,
         );
@@ -333,6 +337,8 @@ impl<P: Preset> Context<P> {
             validator_config,
             network_config,
             http_api_config,
+            storage_settings: StorageSettings::default(),
+            slashing_protector,
             attestation_agg_pool,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
@@ -389,7 +395,7 @@ impl<P: Preset> Context<P> {
             .map(|secret_key| {
                 let secret_key = Arc::new(secret_key);
                 let public_key = secret_key.to_public_key().into();
-                (public_key, secret_key, KeyOrigin::LocalFileSystem)
+                (public_key, secret_key, KeyOrigin::Interop)
             })
             .collect()
     }
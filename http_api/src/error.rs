@@ -13,14 +13,20 @@ use itertools::Itertools as _;
 use serde::{Serialize, Serializer};
 use thiserror::Error;
 use tokio::task::JoinError;
-use types::{deneb::primitives::BlobIndex, phase0::primitives::Slot};
+use types::{deneb::primitives::BlobIndex, nonstandard::Phase, phase0::primitives::Slot};
+
+use crate::state_field::StateField;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("attestation cannot be found")]
     AttestationNotFound,
+    #[error("backups not enabled")]
+    BackupsNotEnabled,
     #[error("block not found")]
     BlockNotFound,
+    #[error("broadcast validation consensus_and_equivocation is not supported")]
+    BroadcastValidationNotSupported,
     #[error(transparent)]
     Canceled(#[from] Canceled),
     #[error(
@@ -38,8 +44,12 @@ pub enum Error {
     EpochNotInSyncCommitteePeriod,
     #[error("epoch is out of range for the randao_mixes of the state")]
     EpochOutOfRangeForStateRandao,
+    #[error("rewards for epoch are not known until the end of the next epoch")]
+    EpochRewardsNotAvailable,
     #[error("execution payload not available")]
     ExecutionPayloadNotAvailable,
+    #[error("execution payload of block has been pruned; only the blinded block is available")]
+    ExecutionPayloadPruned,
     #[error("no event topics specified")]
     EventTopicsEmpty,
     #[error("too many empty slots after head: {head_slot} + {max_empty_slots} < {slot}")]
@@ -48,8 +58,12 @@ pub enum Error {
         max_empty_slots: u64,
         slot: Slot,
     },
+    #[error("head statements not enabled")]
+    HeadStatementsNotEnabled,
     #[error("head has not been fully verified by an execution engine")]
     HeadIsOptimistic,
+    #[error("head is not available yet")]
+    HeadNotAvailable,
     #[error("internal error")]
     Internal(#[from] AnyhowError),
     #[error("invalid aggregates and proofs")]
@@ -68,6 +82,8 @@ pub enum Error {
     InvalidContributionAndProofs(Vec<IndexedError>),
     #[error("invalid epoch")]
     InvalidEpoch(#[source] AnyhowError),
+    #[error("invalid generalized indices")]
+    InvalidGeneralizedIndices(#[source] AnyhowError),
     #[error("invalid JSON body")]
     InvalidJsonBody(#[source] AnyhowError),
     #[error("invalid peer ID")]
@@ -77,6 +93,8 @@ pub enum Error {
         SignatureBytes::empty()
     )]
     InvalidRandaoReveal,
+    #[error("invalid state field")]
+    InvalidStateField(#[source] AnyhowError),
     #[error("invalid state ID")]
     InvalidStateId(#[source] AnyhowError),
     #[error("invalid validator ID")]
@@ -85,6 +103,10 @@ pub enum Error {
     InvalidProposerSlashing(#[source] AnyhowError),
     #[error("invalid public key")]
     InvalidPublicKey(#[source] AnyhowError),
+    #[error("invalid pruning job ID")]
+    InvalidPruningJobId(#[source] AnyhowError),
+    #[error("invalid pruning target")]
+    InvalidPruningTarget(#[source] AnyhowError),
     #[error("invalid query string")]
     InvalidQuery(#[source] AnyhowError),
     #[error("invalid voluntary exit, it will never pass validation so it's rejected")]
@@ -101,24 +123,46 @@ pub enum Error {
     LivenessTrackingNotEnabled,
     #[error("matching head block for attestation is not found")]
     MatchingAttestationHeadBlockNotFound,
+    #[error("none of the media types in the Accept header are supported")]
+    MediaTypesNotAcceptable,
+    #[error(
+        "beacon node is running in read-only mode and does not accept requests on this endpoint"
+    )]
+    NodeIsReadOnly,
     #[error("beacon node is currently syncing and not serving requests on this endpoint")]
     NodeIsSyncing,
+    #[error("database backend does not support compaction while the beacon node is running")]
+    OnlineCompactionUnsupported,
     #[error("peer not found")]
     PeerNotFound,
     #[error("proposal slot is not later than parent state slot")]
     ProposalSlotNotLaterThanStateSlot,
+    #[error("pruning job {job_id} is still running")]
+    PruningJobRunning { job_id: u64 },
+    #[error("pruning job not found")]
+    PruningJobNotFound,
+    #[error("data is already being pruned")]
+    PruningInProgress,
+    #[error("slot is not before state slot")]
+    SlotNotBeforeState,
     #[error("slot does not belong in epoch")]
     SlotNotInEpoch,
     #[error("state not found")]
     StateNotFound,
+    #[error("{phase} state has no field {field}")]
+    StateFieldNotPresent { field: StateField, phase: Phase },
     #[error("head is not available")]
     SlotHeadNotAvailable,
+    #[error("retention period not configured and not specified in request")]
+    StateRetentionNotConfigured,
     #[error("state is pre-Capella")]
     StatePreCapella,
     #[error("target state not found")]
     TargetStateNotFound,
     #[error(transparent)]
     TaskJoinFailed(#[from] JoinError),
+    #[error("too many generalized indices: {count} (at most {maximum} are allowed)")]
+    TooManyGeneralizedIndices { count: usize, maximum: usize },
     #[error("unable to produce attestation")]
     UnableToProduceAttestation(#[source] AnyhowError),
     #[error("unable to produce beacon block")]
@@ -172,16 +216,20 @@ impl Error {
         match self {
             Self::AttestationNotFound
             | Self::BlockNotFound
+            | Self::ExecutionPayloadPruned
             | Self::MatchingAttestationHeadBlockNotFound
             | Self::PeerNotFound
+            | Self::PruningJobNotFound
             | Self::StateNotFound
             | Self::TargetStateNotFound
             | Self::ValidatorNotFound => StatusCode::NOT_FOUND,
-            Self::CommitteesAtSlotMismatch { .. }
+            Self::BroadcastValidationNotSupported
+            | Self::CommitteesAtSlotMismatch { .. }
             | Self::CurrentSlotHasNoSyncCommittee
             | Self::EpochBeforePrevious { .. }
             | Self::EpochNotInSyncCommitteePeriod
             | Self::EpochOutOfRangeForStateRandao
+            | Self::EpochRewardsNotAvailable
             | Self::EventTopicsEmpty
             | Self::InvalidAggregatesAndProofs(_)
             | Self::InvalidAttestations(_)
@@ -191,12 +239,16 @@ impl Error {
             | Self::InvalidBlockId(_)
             | Self::InvalidContributionAndProofs(_)
             | Self::InvalidEpoch(_)
+            | Self::InvalidGeneralizedIndices(_)
             | Self::InvalidJsonBody(_)
             | Self::InvalidQuery(_)
             | Self::InvalidPeerId(_)
             | Self::InvalidProposerSlashing(_)
             | Self::InvalidPublicKey(_)
+            | Self::InvalidPruningJobId(_)
+            | Self::InvalidPruningTarget(_)
             | Self::InvalidSignedVoluntaryExit(_)
+            | Self::InvalidStateField(_)
             | Self::InvalidStateId(_)
             | Self::InvalidSignedBlsToExecutionChanges(_)
             | Self::InvalidSyncCommitteeMessages(_)
@@ -205,22 +257,34 @@ impl Error {
             | Self::InvalidValidatorIndex(_)
             | Self::InvalidValidatorSignatures(_)
             | Self::ProposalSlotNotLaterThanStateSlot
+            | Self::SlotNotBeforeState
             | Self::SlotNotInEpoch
-            | Self::StatePreCapella => StatusCode::BAD_REQUEST,
+            | Self::StateFieldNotPresent { .. }
+            | Self::StatePreCapella
+            | Self::StateRetentionNotConfigured
+            | Self::TooManyGeneralizedIndices { .. } => StatusCode::BAD_REQUEST,
             // | Self::ValidatorNotInCommittee { .. }
             Self::Internal(_)
+            | Self::BackupsNotEnabled
             | Self::Canceled(_)
             | Self::ExecutionPayloadNotAvailable
+            | Self::HeadStatementsNotEnabled
             | Self::LivenessTrackingNotEnabled
             | Self::SlotHeadNotAvailable
             | Self::TaskJoinFailed(_)
             | Self::UnableToProduceAttestation { .. }
             | Self::UnableToProduceBeaconBlock
             | Self::UnableToProduceBlindedBlock => StatusCode::INTERNAL_SERVER_ERROR,
-            Self::EndpointNotImplemented => StatusCode::NOT_IMPLEMENTED,
-            Self::HeadFarBehind { .. } | Self::HeadIsOptimistic | Self::NodeIsSyncing => {
-                StatusCode::SERVICE_UNAVAILABLE
+            Self::EndpointNotImplemented | Self::OnlineCompactionUnsupported => {
+                StatusCode::NOT_IMPLEMENTED
             }
+            Self::NodeIsReadOnly => StatusCode::FORBIDDEN,
+            Self::MediaTypesNotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::PruningInProgress | Self::PruningJobRunning { .. } => StatusCode::CONFLICT,
+            Self::HeadFarBehind { .. }
+            | Self::HeadIsOptimistic
+            | Self::HeadNotAvailable
+            | Self::NodeIsSyncing => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 
@@ -1,65 +1,309 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
 use axum::response::sse::Event;
+use futures::stream::Stream;
+use log::debug;
+use prometheus_metrics::Metrics;
 use serde::Serialize;
-use serde_json::Result;
 use serde_with::DeserializeFromStr;
+use ssz::SszWrite;
 use strum::{AsRefStr, EnumString};
-use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
+use types::phase0::primitives::{CommitteeIndex, ValidatorIndex};
 
-#[derive(Clone, Copy, AsRefStr, EnumString, DeserializeFromStr)]
+// The discriminants are part of the SSZ event stream format. Do not reorder the variants.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, AsRefStr, EnumString, DeserializeFromStr)]
 #[strum(serialize_all = "snake_case")]
+#[repr(u8)]
 pub enum Topic {
-    Attestation,
-    Block,
-    BlsToExecutionChange,
-    ChainReorg,
-    ContributionAndProof,
-    FinalizedCheckpoint,
-    Head,
-    VoluntaryExit,
+    Attestation = 0,
+    Block = 1,
+    BlsToExecutionChange = 2,
+    ChainReorg = 3,
+    ContributionAndProof = 4,
+    FinalizedCheckpoint = 5,
+    Head = 6,
+    VoluntaryExit = 7,
+    // Not part of the Eth Beacon Node API. Only sent when `--balance-delta-events` is enabled.
+    BalanceDeltas = 8,
+    // Not part of the Eth Beacon Node API.
+    InvalidatedBlocks = 9,
 }
 
 impl Topic {
-    pub fn build(self, data: impl Serialize) -> Result<Event> {
+    fn build(self, data: impl Serialize) -> serde_json::Result<Event> {
         Event::default().event(self).json_data(data)
     }
 }
 
+/// Properties of events that subscribers of `GET /eth/v1/events` can filter on.
+#[derive(Clone, Copy, Default)]
+pub struct EventAttributes {
+    pub proposer_index: Option<ValidatorIndex>,
+    pub committee_index: Option<CommitteeIndex>,
+}
+
+#[derive(Clone)]
+pub struct JsonEvent {
+    attributes: EventAttributes,
+    event: Event,
+}
+
+/// Filters that are applied to events before they are written to a connection.
+///
+/// An empty set of indices accepts all events. Filters only apply to events that have the
+/// corresponding attribute, so a proposer filter does not affect `head` events, for example.
+#[derive(Default)]
+pub struct EventFilter {
+    pub proposer_indices: HashSet<ValidatorIndex>,
+    pub committee_indices: HashSet<CommitteeIndex>,
+}
+
+impl EventFilter {
+    fn accepts(&self, attributes: EventAttributes) -> bool {
+        let EventAttributes {
+            proposer_index,
+            committee_index,
+        } = attributes;
+
+        let matches = |indices: &HashSet<u64>, index: Option<u64>| {
+            indices.is_empty() || index.map_or(true, |index| indices.contains(&index))
+        };
+
+        matches(&self.proposer_indices, proposer_index)
+            && matches(&self.committee_indices, committee_index)
+    }
+}
+
+// Events for a single connection and topic.
+struct Subscription {
+    topic: Topic,
+    receiver: Receiver<JsonEvent>,
+    filter: Arc<EventFilter>,
+    // Limits the number of events queued for the connection.
+    // Older events are dropped when the limit is exceeded.
+    queue_limit: Option<usize>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl Subscription {
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some(queue_limit) = self.queue_limit {
+                let mut dropped = 0;
+
+                while self.receiver.len() > queue_limit {
+                    match self.receiver.try_recv() {
+                        Ok(_) => dropped += 1,
+                        Err(_) => break,
+                    }
+                }
+
+                self.record_dropped(dropped);
+            }
+
+            match self.receiver.recv().await {
+                Ok(JsonEvent { attributes, event }) => {
+                    if self.filter.accepts(attributes) {
+                        return Some(event);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => self.record_dropped(skipped),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn record_dropped(&self, count: u64) {
+        if count == 0 {
+            return;
+        }
+
+        debug!(
+            "dropped {count} events in topic {} because the client fell behind",
+            self.topic.as_ref(),
+        );
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_dropped_events(self.topic.as_ref(), count);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SszEvent {
+    pub topic: Topic,
+    pub bytes: Arc<[u8]>,
+}
+
+struct TopicChannels {
+    json: Sender<JsonEvent>,
+    ssz: Sender<SszEvent>,
+}
+
+impl TopicChannels {
+    fn new(max_events: usize) -> Self {
+        Self {
+            json: broadcast::channel(max_events).0,
+            ssz: broadcast::channel(max_events).0,
+        }
+    }
+}
+
 pub struct EventChannels {
-    pub attestations: Sender<Event>,
-    pub blocks: Sender<Event>,
-    pub bls_to_execution_changes: Sender<Event>,
-    pub chain_reorgs: Sender<Event>,
-    pub contribution_and_proofs: Sender<Event>,
-    pub finalized_checkpoints: Sender<Event>,
-    pub heads: Sender<Event>,
-    pub voluntary_exits: Sender<Event>,
+    attestations: TopicChannels,
+    balance_deltas: TopicChannels,
+    blocks: TopicChannels,
+    bls_to_execution_changes: TopicChannels,
+    chain_reorgs: TopicChannels,
+    contribution_and_proofs: TopicChannels,
+    finalized_checkpoints: TopicChannels,
+    heads: TopicChannels,
+    invalidated_blocks: TopicChannels,
+    voluntary_exits: TopicChannels,
 }
 
 impl EventChannels {
     pub fn new(max_events: usize) -> Self {
         Self {
-            attestations: broadcast::channel(max_events).0,
-            blocks: broadcast::channel(max_events).0,
-            bls_to_execution_changes: broadcast::channel(max_events).0,
-            chain_reorgs: broadcast::channel(max_events).0,
-            contribution_and_proofs: broadcast::channel(max_events).0,
-            finalized_checkpoints: broadcast::channel(max_events).0,
-            heads: broadcast::channel(max_events).0,
-            voluntary_exits: broadcast::channel(max_events).0,
+            attestations: TopicChannels::new(max_events),
+            balance_deltas: TopicChannels::new(max_events),
+            blocks: TopicChannels::new(max_events),
+            bls_to_execution_changes: TopicChannels::new(max_events),
+            chain_reorgs: TopicChannels::new(max_events),
+            contribution_and_proofs: TopicChannels::new(max_events),
+            finalized_checkpoints: TopicChannels::new(max_events),
+            heads: TopicChannels::new(max_events),
+            invalidated_blocks: TopicChannels::new(max_events),
+            voluntary_exits: TopicChannels::new(max_events),
         }
     }
 
-    pub fn receiver_for(&self, topic: Topic) -> Receiver<Event> {
+    pub fn stream_for(
+        &self,
+        topic: Topic,
+        filter: Arc<EventFilter>,
+        queue_limit: Option<usize>,
+        metrics: Option<Arc<Metrics>>,
+    ) -> impl Stream<Item = Event> {
+        let subscription = Subscription {
+            topic,
+            receiver: self.channels_for(topic).json.subscribe(),
+            filter,
+            queue_limit,
+            metrics,
+        };
+
+        futures::stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next_event().await?;
+            Some((event, subscription))
+        })
+    }
+
+    pub fn ssz_receiver_for(&self, topic: Topic) -> Receiver<SszEvent> {
+        self.channels_for(topic).ssz.subscribe()
+    }
+
+    // Events are only encoded in formats that have receivers.
+    // Encoding full objects as JSON can be costly, so the check is worth doing.
+    pub fn send(&self, topic: Topic, data: impl Serialize + SszWrite) -> Result<usize> {
+        self.send_with_attributes(topic, data, EventAttributes::default())
+    }
+
+    pub fn send_with_attributes(
+        &self,
+        topic: Topic,
+        data: impl Serialize + SszWrite,
+        attributes: EventAttributes,
+    ) -> Result<usize> {
+        let TopicChannels { json, ssz } = self.channels_for(topic);
+
+        let mut receivers = 0;
+
+        if json.receiver_count() > 0 {
+            let event = JsonEvent {
+                attributes,
+                event: topic.build(&data)?,
+            };
+
+            receivers += json.send(event).unwrap_or_default();
+        }
+
+        if ssz.receiver_count() > 0 {
+            let event = SszEvent {
+                topic,
+                bytes: data.to_ssz()?.into(),
+            };
+
+            receivers += ssz.send(event).unwrap_or_default();
+        }
+
+        Ok(receivers)
+    }
+
+    const fn channels_for(&self, topic: Topic) -> &TopicChannels {
         match topic {
             Topic::Attestation => &self.attestations,
+            Topic::BalanceDeltas => &self.balance_deltas,
             Topic::Block => &self.blocks,
             Topic::BlsToExecutionChange => &self.bls_to_execution_changes,
             Topic::ChainReorg => &self.chain_reorgs,
             Topic::ContributionAndProof => &self.contribution_and_proofs,
             Topic::FinalizedCheckpoint => &self.finalized_checkpoints,
             Topic::Head => &self.heads,
+            Topic::InvalidatedBlocks => &self.invalidated_blocks,
             Topic::VoluntaryExit => &self.voluntary_exits,
         }
-        .subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn events_are_filtered_by_proposer_index() -> Result<()> {
+        let event_channels = EventChannels::new(16);
+
+        let filter = Arc::new(EventFilter {
+            proposer_indices: HashSet::from([7]),
+            ..EventFilter::default()
+        });
+
+        let stream = event_channels.stream_for(Topic::Block, filter, None, None);
+
+        for proposer_index in [1, 7, 2] {
+            let attributes = EventAttributes {
+                proposer_index: Some(proposer_index),
+                ..EventAttributes::default()
+            };
+
+            event_channels.send_with_attributes(Topic::Block, proposer_index, attributes)?;
+        }
+
+        drop(event_channels);
+
+        assert_eq!(stream.count().await, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn events_exceeding_queue_limit_are_dropped() -> Result<()> {
+        let event_channels = EventChannels::new(16);
+        let stream = event_channels.stream_for(Topic::Head, Arc::default(), Some(2), None);
+
+        for slot in 0_u64..5 {
+            event_channels.send(Topic::Head, slot)?;
+        }
+
+        drop(event_channels);
+
+        assert_eq!(stream.count().await, 2);
+
+        Ok(())
     }
 }
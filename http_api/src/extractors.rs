@@ -13,7 +13,7 @@ use axum::{
     body::Body,
     extract::{FromRef, FromRequest, FromRequestParts, Path, RawBody},
     headers::ContentType,
-    http::{request::Parts, Request},
+    http::{header::ACCEPT, request::Parts, Request},
     Json, RequestExt as _, RequestPartsExt as _, TypedHeader,
 };
 use axum_extra::extract::Query;
@@ -29,6 +29,7 @@ use ssz::SszRead;
 use types::{
     altair::containers::SignedContributionAndProof,
     config::Config,
+    nonstandard::Phase,
     phase0::{
         containers::{
             Attestation, AttesterSlashing, ProposerSlashing, SignedAggregateAndProof,
@@ -42,10 +43,14 @@ use validator::ValidatorProposerData;
 
 use crate::{
     error::Error,
+    misc::SszReadAtPhase,
+    pruning::{PruningJobId, PruningTarget},
+    response::{JsonOrSsz, ETH_CONSENSUS_VERSION},
     standard::{
         KeystoreDeleteQuery, KeystoreImportQuery, RemoteKeysDeleteQuery, RemoteKeysImportQuery,
         SetFeeRecipientQuery, SetGasLimitQuery, SetGraffitiQuery,
     },
+    state_field::StateField,
     state_id::StateId,
     validator_status::ValidatorId,
 };
@@ -134,6 +139,38 @@ impl<S> FromRequestParts<S> for EthPath<Epoch> {
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for EthPath<PruningTarget> {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extract::<Path<String>>()
+            .await
+            .map_err(AnyhowError::new)?
+            .parse()
+            .map(Self)
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidPruningTarget)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for EthPath<PruningJobId> {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extract::<Path<String>>()
+            .await
+            .map_err(AnyhowError::new)?
+            .parse()
+            .map(Self)
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidPruningJobId)
+    }
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for EthPath<(StateId, ValidatorId)> {
     type Rejection = Error;
@@ -158,6 +195,30 @@ impl<S> FromRequestParts<S> for EthPath<(StateId, ValidatorId)> {
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for EthPath<(StateId, StateField)> {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Path((state_id, field)) = parts
+            .extract::<Path<(String, String)>>()
+            .await
+            .map_err(AnyhowError::new)?;
+
+        let state_id = state_id
+            .parse()
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidStateId)?;
+
+        let field = field
+            .parse()
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidStateField)?;
+
+        Ok(Self((state_id, field)))
+    }
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for EthPath<PublicKeyBytes> {
     type Rejection = Error;
@@ -476,6 +537,8 @@ impl<S> FromRequest<S, Body> for EthJson<RemoteKeysDeleteQuery> {
     }
 }
 
+// SSZ bodies are decoded according to the `Eth-Consensus-Version` header if it is present.
+// Otherwise the phase is determined from the slot.
 pub struct EthJsonOrSsz<T>(pub T);
 
 #[async_trait]
@@ -483,7 +546,7 @@ impl<S, T> FromRequest<S, Body> for EthJsonOrSsz<T>
 where
     Arc<Config>: FromRef<S>,
     S: Sync,
-    T: SszRead<Config> + DeserializeOwned + 'static,
+    T: SszRead<Config> + SszReadAtPhase + DeserializeOwned + 'static,
 {
     type Rejection = Error;
 
@@ -493,10 +556,21 @@ where
                 request.extract_parts::<TypedHeader<ContentType>>().await?;
 
             if content_type == ContentType::octet_stream() {
+                let phase = request
+                    .headers()
+                    .get(ETH_CONSENSUS_VERSION)
+                    .map(|value| value.to_str()?.parse::<Phase>().map_err(AnyhowError::new))
+                    .transpose()?;
+
                 let config = Arc::from_ref(state);
                 let RawBody(body) = request.extract().await?;
                 let bytes = hyper::body::to_bytes(body).await?;
-                let block = T::from_ssz(&config, bytes)?;
+
+                let block = match phase {
+                    Some(phase) => T::from_ssz_at_phase(phase, &bytes)?,
+                    None => T::from_ssz(&config, bytes)?,
+                };
+
                 return Ok(Self(block));
             }
 
@@ -508,3 +582,51 @@ where
         run.await.map_err(Error::InvalidBlock)
     }
 }
+
+// Negotiating the format up front lets handlers reject unacceptable requests before doing any work.
+//
+// `axum` recommends using `axum::TypedHeader` instead of extracting all headers,
+// but the `headers` crate does not provide a type for the `Accept` header.
+// See <https://github.com/hyperium/headers/issues/53>.
+#[async_trait]
+impl<S> FromRequestParts<S> for JsonOrSsz {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.headers.get(ACCEPT) {
+            Some(accept) => accept
+                .to_str()
+                .map_or(Some(Self::Json), Self::negotiate)
+                .ok_or(Error::MediaTypesNotAcceptable),
+            None => Ok(Self::Json),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(None => matches Ok(JsonOrSsz::Json))]
+    #[test_case(Some("application/octet-stream") => matches Ok(JsonOrSsz::Ssz))]
+    #[test_case(Some("text/html") => matches Err(Error::MediaTypesNotAcceptable))]
+    #[tokio::test]
+    async fn json_or_ssz_is_negotiated_from_accept_header(
+        accept: Option<&str>,
+    ) -> Result<JsonOrSsz, Error> {
+        let mut request = Request::builder();
+
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+
+        let (mut parts, ()) = request
+            .body(())
+            .expect("request in test should be valid")
+            .into_parts();
+
+        JsonOrSsz::from_request_parts(&mut parts, &()).await
+    }
+}
@@ -5,6 +5,7 @@ use features::Feature;
 use futures::channel::mpsc::UnboundedSender;
 use log::info;
 use metrics::ApiToMetrics;
+use prometheus_metrics::{Metrics, OperationalCounterValues};
 use types::nonstandard::SystemStats;
 
 /// `GET /system/stats`
@@ -20,6 +21,17 @@ pub async fn get_system_stats(
     receiver.await?
 }
 
+/// `GET /system/operational_counters`
+pub fn get_operational_counters(
+    metrics: Option<&Metrics>,
+) -> Result<BTreeMap<&'static str, OperationalCounterValues>> {
+    let metrics = metrics.ok_or_else(|| anyhow!("metrics are not enabled"))?;
+
+    enum_iterator::all()
+        .map(|counter| Ok((counter.as_str(), metrics.operational_counter(counter)?)))
+        .collect()
+}
+
 /// `GET /features`
 pub fn get_features() -> BTreeMap<Feature, bool> {
     enum_iterator::all::<Feature>()
@@ -3,8 +3,9 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::Result;
 use bls::PublicKeyBytes;
+use builder_api::RelayReport;
 use eth1_api::ApiController;
-use fork_choice_control::Wait;
+use fork_choice_control::{RegistryChange, RegistryChangeKind, ReorgRecord, Wait};
 use futures::channel::mpsc::UnboundedSender;
 use genesis::GenesisProvider;
 use helper_functions::{
@@ -12,6 +13,8 @@ use helper_functions::{
     slot_report::{Assignment, Delta, RealSlotReport, SyncAggregateRewards},
 };
 use itertools::{chain, izip, Itertools as _};
+use keymanager::KeyManager;
+use p2p::{ApiToP2p, NodePeer, NodePeersQuery, NodeReachability};
 use serde::{Deserialize, Serialize};
 use std_ext::ArcExt as _;
 use transition_functions::{
@@ -29,7 +32,7 @@ use transition_functions::{
 };
 use typenum::Unsigned as _;
 use types::{
-    altair::containers::SyncAggregate,
+    altair::{containers::SyncAggregate, primitives::SyncCommitteePeriod},
     combined::{BeaconState, SignedBeaconBlock},
     nonstandard::{
         AttestationEpoch, AttestationOutcome, GweiVec, RelativeEpoch, SlotVec, UsizeVec, WithStatus,
@@ -43,7 +46,14 @@ use types::{
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 use unwrap_none::UnwrapNone as _;
-use validator::ApiToValidator;
+use validator::{ApiToValidator, BlockInclusionReport, StandbyStatus, SyncCommitteeTotals};
+
+use crate::{
+    error::Error,
+    head_statement::{HeadStatementSigner, SignedHeadStatement},
+    misc::BackSyncedStatus,
+    network_overview::{NetworkOverview, NetworkOverviewCache},
+};
 
 // `AttestationPerformance::for_previous_epoch` has to process slot reports in chronological order.
 //
@@ -66,6 +76,31 @@ impl EpochRangeWithKeysQuery {
     }
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReorgsQuery {
+    #[serde(default)]
+    from_slot: Slot,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeystoreRegistryChangesQuery {
+    start_epoch: Epoch,
+    end_epoch: Option<Epoch>,
+    kind: Option<RegistryChangeKind>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryChangesQuery {
+    start_epoch: Epoch,
+    end_epoch: Option<Epoch>,
+    kind: Option<RegistryChangeKind>,
+    #[serde(default)]
+    owned: bool,
+}
+
 #[derive(Serialize)]
 pub struct GetBeaconHeadResponse {
     block_root: H256,
@@ -74,6 +109,15 @@ pub struct GetBeaconHeadResponse {
     slot: Slot,
 }
 
+#[derive(Serialize)]
+pub struct GetBackfillStatusResponse {
+    is_back_synced: bool,
+    earliest_available_slot: Option<Slot>,
+    target_slot: Option<Slot>,
+    verified_up_to_root: Option<H256>,
+    remaining_slots: Option<u64>,
+}
+
 #[derive(Serialize)]
 pub struct GetValidatorStatisticsResponse {
     // The epochs are not redundant.
@@ -880,12 +924,13 @@ pub fn get_validator_owned<P: Preset, W: Wait>(
     validator_keys: &HashSet<PublicKeyBytes>,
 ) -> BTreeMap<PublicKeyBytes, ValidatorIndex> {
     let head_state = controller.head_state().value;
+    let validator_registry = controller.validator_registry();
 
     validator_keys
         .iter()
         .copied()
         .filter_map(|pubkey| {
-            let validator_index = accessors::index_of_public_key(&head_state, pubkey)?;
+            let validator_index = validator_registry.index_of_public_key(&head_state, pubkey)?;
             Some((pubkey, validator_index))
         })
         .collect()
@@ -897,6 +942,7 @@ pub async fn get_validator_registered<P: Preset, W: Wait>(
     api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
 ) -> Result<BTreeMap<PublicKeyBytes, ValidatorIndex>> {
     let head_state = controller.head_state().value;
+    let validator_registry = controller.validator_registry();
     let (sender, receiver) = futures::channel::oneshot::channel();
 
     ApiToValidator::RegisteredValidators(sender).send(&api_to_validator_tx);
@@ -905,7 +951,7 @@ pub async fn get_validator_registered<P: Preset, W: Wait>(
         .await?
         .into_iter()
         .filter_map(|pubkey| {
-            let validator_index = accessors::index_of_public_key(&head_state, pubkey)?;
+            let validator_index = validator_registry.index_of_public_key(&head_state, pubkey)?;
             Some((pubkey, validator_index))
         })
         .collect();
@@ -913,6 +959,202 @@ pub async fn get_validator_registered<P: Preset, W: Wait>(
     Ok(validator_indices)
 }
 
+pub async fn get_builder_relays<P: Preset>(
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+) -> Result<Vec<RelayReport>> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::BuilderRelayReports(sender).send(&api_to_validator_tx);
+
+    receiver.await.map_err(Into::into)
+}
+
+/// `GET /grandine/v1/validator/standby`
+pub async fn get_validator_standby<P: Preset>(
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+) -> Result<StandbyStatus> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::StandbyStatus(sender).send(&api_to_validator_tx);
+
+    receiver.await.map_err(Into::into)
+}
+
+/// `POST /grandine/v1/validator/standby/activate`
+pub async fn post_validator_standby_activate<P: Preset>(
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+) -> Result<StandbyStatus> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::ActivateFromStandby(sender).send(&api_to_validator_tx);
+
+    receiver.await?
+}
+
+/// `GET /grandine/v1/validator/sync_committee_performance`
+pub async fn get_validator_sync_committee_performance<P: Preset>(
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+) -> Result<BTreeMap<SyncCommitteePeriod, BTreeMap<ValidatorIndex, SyncCommitteeTotals>>> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::SyncCommitteePerformance(sender).send(&api_to_validator_tx);
+
+    receiver.await.map_err(Into::into)
+}
+
+/// `GET /grandine/v1/debug/block_inclusion`
+///
+/// Responds with 503 while the validator has no head to report on, such as right after startup.
+pub async fn get_debug_block_inclusion<P: Preset>(
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+) -> Result<BlockInclusionReport, Error> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::BlockInclusionReport(sender).send(&api_to_validator_tx);
+
+    receiver.await??.ok_or(Error::HeadNotAvailable)
+}
+
+/// `GET /grandine/v1/node/peers`
+pub async fn get_node_peers<P: Preset>(
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+    query: NodePeersQuery,
+) -> Result<Vec<NodePeer>> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToP2p::RequestPeersWithRequestStats(query, sender).send(api_to_p2p_tx);
+
+    receiver.await.map_err(Into::into)
+}
+
+/// `GET /grandine/v1/node/reachability`
+///
+/// Reachability is inferred from inbound connections, so ports are reported as `unknown` until
+/// the node has been running for a while with enough outbound peers.
+pub async fn get_node_reachability<P: Preset>(
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+) -> Result<NodeReachability> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToP2p::RequestReachability(sender).send(api_to_p2p_tx);
+
+    receiver.await.map_err(Into::into)
+}
+
+/// `GET /grandine/v1/network_overview`
+pub async fn get_network_overview<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    network_overview: &NetworkOverviewCache,
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+) -> Result<NetworkOverview> {
+    network_overview.get(controller, api_to_p2p_tx).await
+}
+
+/// `GET /grandine/v1/node/head_statement`
+pub fn get_node_head_statement<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    head_statement_signer: Option<&HeadStatementSigner>,
+) -> Result<SignedHeadStatement, Error> {
+    let head_statement_signer = head_statement_signer.ok_or(Error::HeadStatementsNotEnabled)?;
+    Ok(head_statement_signer.latest(controller))
+}
+
+/// `GET /grandine/v1/node/backfill_status`
+///
+/// Fields other than `is_back_synced` are `null` if back sync has never run.
+/// That is the case for nodes that synced from genesis.
+pub fn get_node_backfill_status<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    is_back_synced: &BackSyncedStatus,
+) -> Result<GetBackfillStatusResponse> {
+    let status = controller.back_sync_status()?;
+
+    Ok(GetBackfillStatusResponse {
+        is_back_synced: is_back_synced.get(),
+        earliest_available_slot: status.map(|status| status.earliest_available_slot),
+        target_slot: status.map(|status| status.target_slot),
+        verified_up_to_root: status.map(|status| status.verified_up_to_root),
+        remaining_slots: status.map(|status| {
+            status
+                .earliest_available_slot
+                .saturating_sub(status.target_slot)
+        }),
+    })
+}
+
+/// `GET /grandine/v1/debug/reorgs?from_slot={from_slot}`
+pub fn get_debug_reorgs<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    query: ReorgsQuery,
+) -> Result<Vec<ReorgRecord>> {
+    controller.reorgs(query.from_slot)
+}
+
+/// `GET /grandine/v1/validator/registry_changes?start_epoch={start_epoch}&end_epoch={end_epoch}&kind={kind}&owned={owned}`
+///
+/// Only changes contained in finalized states are returned.
+/// If `owned` is `true`, only changes to validators with keys loaded by this node are returned.
+pub fn get_validator_registry_changes<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    validator_keys: &HashSet<PublicKeyBytes>,
+    query: RegistryChangesQuery,
+) -> Result<Vec<RegistryChange>> {
+    let RegistryChangesQuery {
+        start_epoch,
+        end_epoch,
+        kind,
+        owned,
+    } = query;
+
+    let owned_indices = owned.then(|| {
+        get_validator_owned(controller, validator_keys)
+            .into_values()
+            .collect::<HashSet<_>>()
+    });
+
+    // Filters are applied while reading the journal so that the limit on returned changes
+    // does not cut off matching changes in favor of ones that would be filtered out.
+    controller.registry_changes(start_epoch, end_epoch.unwrap_or(start_epoch), |change| {
+        kind.map_or(true, |kind| change.kind == kind)
+            && owned_indices
+                .as_ref()
+                .map_or(true, |indices| indices.contains(&change.validator_index))
+    })
+}
+
+/// `GET /grandine/v1/keystores/registry_changes?start_epoch={start_epoch}&end_epoch={end_epoch}&kind={kind}`
+///
+/// Returns journaled changes to validators with keys currently managed by the keymanager,
+/// including keystores and remote keys imported after startup.
+pub async fn get_keystore_registry_changes<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    keymanager: &KeyManager,
+    query: KeystoreRegistryChangesQuery,
+) -> Result<Vec<RegistryChange>> {
+    let KeystoreRegistryChangesQuery {
+        start_epoch,
+        end_epoch,
+        kind,
+    } = query;
+
+    let validator_keys = keymanager
+        .keystores()
+        .list_validating_pubkeys()
+        .await
+        .into_iter()
+        .map(|validating_pubkey| validating_pubkey.validating_pubkey)
+        .collect();
+
+    let managed_indices = get_validator_owned(controller, &validator_keys)
+        .into_values()
+        .collect::<HashSet<_>>();
+
+    controller.registry_changes(start_epoch, end_epoch.unwrap_or(start_epoch), |change| {
+        kind.map_or(true, |kind| change.kind == kind)
+            && managed_indices.contains(&change.validator_index)
+    })
+}
+
 fn previous_epoch_proposal_assignments(
     state: &BeaconState<impl Preset>,
 ) -> Result<HashMap<ValidatorIndex, SlotVec>> {
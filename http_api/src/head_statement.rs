@@ -0,0 +1,185 @@
+//! Signed statements about the head of the chain as seen by this node.
+//!
+//! Statements are signed with a dedicated BLS key that must not belong to any validator.
+//! They let external services attest to the availability of the node without any access to
+//! validator keys. Signatures can be checked the same way as those of consensus messages,
+//! using [`DOMAIN_APPLICATION_HEAD_STATEMENT`] and the genesis validators root of the network.
+
+use core::{convert::Infallible as Never, time::Duration};
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use bls::{PublicKeyBytes, SecretKey, SecretKeyBytes, SignatureBytes};
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use helper_functions::{error::SignatureKind, signing::SignForAllForksWithGenesis};
+use hex::FromHex as _;
+use log::info;
+use parking_lot::Mutex;
+use serde::Serialize;
+use ssz::Ssz;
+use types::{
+    phase0::{
+        containers::Checkpoint,
+        primitives::{DomainType, Slot, H256, H32},
+    },
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+/// Application domain for statements signed by beacon nodes rather than validators.
+///
+/// Application domains have [`DOMAIN_APPLICATION_MASK`] set, so signatures over head statements
+/// can never be valid for consensus messages even if the key is misused.
+///
+/// [`DOMAIN_APPLICATION_MASK`]: https://github.com/ethereum/consensus-specs/blob/0b76c8367ed19014d104e3fbd4718e73f459a748/specs/phase0/beacon-chain.md#domain-types
+pub const DOMAIN_APPLICATION_HEAD_STATEMENT: DomainType = H32([0x68, 0x64, 0x00, 0x01]);
+
+#[derive(Clone, Copy, Serialize, Ssz)]
+#[ssz(derive_read = false, derive_size = false, derive_write = false)]
+pub struct HeadStatement {
+    #[serde(with = "serde_utils::string_or_native")]
+    slot: Slot,
+    #[serde(with = "serde_utils::string_or_native")]
+    head_slot: Slot,
+    head_block_root: H256,
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    execution_optimistic: bool,
+    is_syncing: bool,
+}
+
+impl<P: Preset> SignForAllForksWithGenesis<P> for HeadStatement {
+    const DOMAIN_TYPE: DomainType = DOMAIN_APPLICATION_HEAD_STATEMENT;
+    const SIGNATURE_KIND: SignatureKind = SignatureKind::HeadStatement;
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct SignedHeadStatement {
+    message: HeadStatement,
+    public_key: PublicKeyBytes,
+    signature: SignatureBytes,
+}
+
+pub struct HeadStatementSigner {
+    secret_key: SecretKey,
+    public_key: PublicKeyBytes,
+    latest: Mutex<Option<SignedHeadStatement>>,
+}
+
+impl HeadStatementSigner {
+    pub fn load(key_file: &Path) -> Result<Self> {
+        let contents = fs_err::read_to_string(key_file)?;
+
+        let secret_key = parse_secret_key(&contents)
+            .with_context(|| format!("invalid head statement key in {}", key_file.display()))?;
+
+        let public_key = secret_key.to_public_key().into();
+
+        info!("signing head statements with public key {public_key:?}");
+
+        Ok(Self {
+            secret_key,
+            public_key,
+            latest: Mutex::default(),
+        })
+    }
+
+    pub fn latest<P: Preset, W: Wait>(
+        &self,
+        controller: &ApiController<P, W>,
+    ) -> SignedHeadStatement {
+        let latest = *self.latest.lock();
+
+        // The first statement may not have been signed yet if the node has just started.
+        latest.unwrap_or_else(|| self.sign(controller))
+    }
+
+    pub async fn run<P: Preset, W: Wait>(&self, controller: ApiController<P, W>) -> Result<Never> {
+        let seconds_per_slot = controller.chain_config().seconds_per_slot.get();
+        let mut interval = tokio::time::interval(Duration::from_secs(seconds_per_slot));
+
+        loop {
+            interval.tick().await;
+            self.sign(&controller);
+        }
+    }
+
+    fn sign<P: Preset, W: Wait>(&self, controller: &ApiController<P, W>) -> SignedHeadStatement {
+        let head = controller.head();
+        let state = controller.state_by_chain_link(&head.value);
+
+        let message = HeadStatement {
+            slot: controller.slot(),
+            head_slot: head.value.slot(),
+            head_block_root: head.value.block_root,
+            justified_checkpoint: state.current_justified_checkpoint(),
+            finalized_checkpoint: state.finalized_checkpoint(),
+            execution_optimistic: head.optimistic,
+            is_syncing: !controller.is_forward_synced(),
+        };
+
+        let signed_statement = SignedHeadStatement {
+            message,
+            public_key: self.public_key,
+            signature: message
+                .sign(controller.chain_config(), &*state, &self.secret_key)
+                .into(),
+        };
+
+        *self.latest.lock() = Some(signed_statement);
+
+        signed_statement
+    }
+}
+
+fn parse_secret_key(contents: &str) -> Result<SecretKey> {
+    let digits = contents.trim();
+    let digits = digits.strip_prefix("0x").unwrap_or(digits);
+    let secret_key_bytes = SecretKeyBytes::from_hex(digits)?;
+    SecretKey::try_from(secret_key_bytes).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use bls::CachedPublicKey;
+    use types::{config::Config, preset::Minimal};
+
+    use super::*;
+
+    #[test]
+    fn head_statements_are_signed_with_key_from_file_contents() -> Result<()> {
+        let config = Config::minimal();
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let expected_secret_key = interop::secret_key(0);
+        let contents = format!(
+            "0x{}\n",
+            hex::encode(expected_secret_key.to_bytes().as_ref())
+        );
+        let secret_key = parse_secret_key(&contents)?;
+
+        assert_eq!(secret_key, expected_secret_key);
+
+        let statement = HeadStatement {
+            slot: 3,
+            head_slot: 2,
+            head_block_root: H256::repeat_byte(1),
+            justified_checkpoint: Checkpoint::default(),
+            finalized_checkpoint: Checkpoint::default(),
+            execution_optimistic: false,
+            is_syncing: false,
+        };
+
+        let signature = statement.sign(&config, &*state, &secret_key).into();
+        let public_key = CachedPublicKey::from(secret_key.to_public_key());
+
+        SignForAllForksWithGenesis::<Minimal>::verify(
+            &statement,
+            &config,
+            &*state,
+            signature,
+            &public_key,
+        )
+    }
+}
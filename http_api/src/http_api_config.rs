@@ -1,5 +1,8 @@
 use core::time::Duration;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
 
 use educe::Educe;
 use hyper::{server::conn::AddrIncoming, Result};
@@ -10,9 +13,24 @@ use tower_http::cors::AllowOrigin;
 pub struct HttpApiConfig {
     pub address: SocketAddr,
     pub allow_origin: AllowOrigin,
+    // Backups requested through the HTTP API are written to subdirectories of this directory.
+    pub backup_directory: Option<PathBuf>,
+    pub head_statement_key_file: Option<PathBuf>,
     pub max_events: usize,
+    // Rejects requests that would change the state of the node or submit objects to the network.
+    pub read_only: bool,
+    // Requests taking at least this long are logged along with their span trees.
+    pub slow_request_threshold: Option<Duration>,
     // `HttpApiConfig.timeout` is optional to prevent timeouts in tests.
     pub timeout: Option<Duration>,
+    pub tls: Option<TlsConfig>,
+    pub trusted_client_token_file: Option<PathBuf>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TlsConfig {
+    pub certificate_file: PathBuf,
+    pub private_key_file: PathBuf,
 }
 
 impl HttpApiConfig {
@@ -20,15 +38,26 @@ impl HttpApiConfig {
     pub fn with_address(ip_address: impl Into<IpAddr>, port: u16) -> Self {
         let address = (ip_address, port).into();
 
-        let allowed_origin = format!("http://{address}")
-            .try_into()
-            .expect("http:// followed by a socket address should be a valid header value");
-
         Self {
             address,
-            allow_origin: AllowOrigin::list([allowed_origin]),
+            allow_origin: same_origin("http", address),
+            backup_directory: None,
+            head_statement_key_file: None,
             max_events: 100,
+            read_only: false,
+            slow_request_threshold: None,
             timeout: None,
+            tls: None,
+            trusted_client_token_file: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_tls(self, tls: TlsConfig) -> Self {
+        Self {
+            allow_origin: same_origin("https", self.address),
+            tls: Some(tls),
+            ..self
         }
     }
 
@@ -36,3 +65,11 @@ impl HttpApiConfig {
         AddrIncoming::bind(&self.address)
     }
 }
+
+fn same_origin(scheme: &str, address: SocketAddr) -> AllowOrigin {
+    let allowed_origin = format!("{scheme}://{address}")
+        .try_into()
+        .expect("scheme followed by a socket address should be a valid header value");
+
+    AllowOrigin::list([allowed_origin])
+}
@@ -1,23 +1,38 @@
 pub use crate::{
-    http_api_config::HttpApiConfig,
+    config_fingerprint::StorageSettings,
+    http_api_config::{HttpApiConfig, TlsConfig},
     task::{Channels, HttpApi},
 };
 
+mod backup;
 mod block_id;
+mod block_root_proof;
+mod compaction;
+mod config_fingerprint;
 mod error;
 mod events;
 mod extractors;
 mod full_config;
 mod global;
 mod gui;
+mod head_statement;
 mod http_api_config;
 mod middleware;
 mod misc;
+mod multiproof;
+mod network_overview;
+mod pruning;
 mod response;
 mod routing;
+mod ssz_events;
 mod standard;
+mod state_diff;
+mod state_field;
 mod state_id;
 mod task;
+mod tls;
+mod trusted_client;
+mod validator_duties;
 mod validator_status;
 
 #[cfg(test)]
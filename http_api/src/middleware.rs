@@ -38,6 +38,13 @@ pub async fn is_synced(
         .ok_or(Error::NodeIsSyncing)
 }
 
+pub async fn is_writable(
+    State(read_only): State<bool>,
+    request: Request<Body>,
+) -> Result<Request<Body>, Error> {
+    (!read_only).then_some(request).ok_or(Error::NodeIsReadOnly)
+}
+
 #[cfg(test)]
 pub async fn wait_for_tasks<P: Preset>(
     State(controller): State<TestApiController<P>>,
@@ -46,3 +53,76 @@ pub async fn wait_for_tasks<P: Preset>(
     tokio::task::spawn_blocking(move || controller.wait_for_tasks()).await?;
     Ok(request)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        routing::{get, post},
+        Router,
+    };
+    use std_ext::ArcExt as _;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn is_synced_rejects_requests_until_node_is_synced() -> anyhow::Result<()> {
+        let synced_status = Arc::new(SyncedStatus::new(false));
+
+        let router = Router::new().route(
+            "/duties",
+            post(|| async {}).route_layer(axum::middleware::map_request_with_state(
+                synced_status.clone_arc(),
+                is_synced,
+            )),
+        );
+
+        let request = || Request::post("/duties").body(Body::empty());
+
+        let response = router.clone().oneshot(request()?).await?;
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        synced_status.set(true);
+
+        let response = router.oneshot(request()?).await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn is_writable_rejects_only_layered_routes_in_read_only_mode() -> anyhow::Result<()> {
+        let router = |read_only| {
+            let read_routes = Router::new().route("/pool", get(|| async {}));
+
+            let write_routes = Router::new().route(
+                "/pool",
+                post(|| async {}).route_layer(axum::middleware::map_request_with_state(
+                    read_only,
+                    is_writable,
+                )),
+            );
+
+            read_routes.merge(write_routes)
+        };
+
+        let get_request = || Request::get("/pool").body(Body::empty());
+        let post_request = || Request::post("/pool").body(Body::empty());
+
+        let response = router(true).oneshot(get_request()?).await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router(true).oneshot(post_request()?).await?;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router(false).oneshot(post_request()?).await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use bls::SignatureBytes;
 use enum_iterator::Sequence as _;
@@ -11,7 +12,7 @@ use types::{
     altair::containers::SignedBeaconBlock as AltairSignedBeaconBlock,
     bellatrix::containers::SignedBeaconBlock as BellatrixSignedBeaconBlock,
     capella::containers::SignedBeaconBlock as CapellaSignedBeaconBlock,
-    combined::{BeaconBlock, SignedBeaconBlock},
+    combined::{BeaconBlock, SignedBeaconBlock, SignedBlindedBeaconBlock},
     config::Config,
     deneb::{
         containers::SignedBeaconBlock as DenebSignedBeaconBlock,
@@ -20,12 +21,10 @@ use types::{
     nonstandard::{Phase, WithBlobsAndMev},
     phase0::{containers::SignedBeaconBlock as Phase0SignedBeaconBlock, primitives::Slot},
     preset::Preset,
+    traits::{BeaconBlock as _, SignedBeaconBlock as _},
 };
 use validator::ValidatorBlindedBlock;
 
-#[cfg(test)]
-use std::sync::Arc;
-
 #[cfg(test)]
 use ::{
     crossbeam_utils::sync::WaitGroup,
@@ -175,6 +174,84 @@ impl<P: Preset> From<WithBlobsAndMev<ValidatorBlindedBlock<P>, P>>
     }
 }
 
+#[derive(Serialize)]
+#[serde(bound = "", untagged)]
+pub enum SignedAPIBlindedBlock<P: Preset> {
+    SignedBlindedBeaconBlock(SignedBlindedBeaconBlock<P>),
+    // Blocks from phases without execution payloads are returned unchanged.
+    SignedBeaconBlock(Arc<SignedBeaconBlock<P>>),
+}
+
+impl<P: Preset> SszSize for SignedAPIBlindedBlock<P> {
+    const SIZE: Size = Size::for_untagged_union([
+        SignedBlindedBeaconBlock::<P>::SIZE,
+        SignedBeaconBlock::<P>::SIZE,
+    ]);
+}
+
+impl<P: Preset> SszWrite for SignedAPIBlindedBlock<P> {
+    fn write_variable(&self, bytes: &mut Vec<u8>) -> Result<(), WriteError> {
+        match self {
+            Self::SignedBlindedBeaconBlock(block) => block.write_variable(bytes),
+            Self::SignedBeaconBlock(block) => block.write_variable(bytes),
+        }
+    }
+}
+
+impl<P: Preset> From<Arc<SignedBeaconBlock<P>>> for SignedAPIBlindedBlock<P> {
+    fn from(block: Arc<SignedBeaconBlock<P>>) -> Self {
+        let Some(body) = block.message().body().post_bellatrix() else {
+            return Self::SignedBeaconBlock(block);
+        };
+
+        let payload_header = body.execution_payload().to_header();
+        let (message, signature) = SignedBeaconBlock::clone(&block).split();
+
+        let blinded_block = message
+            .into_blinded(payload_header, None)
+            .expect("phases should match because payload header was taken from block");
+
+        Self::SignedBlindedBeaconBlock(blinded_block.with_signature(signature))
+    }
+}
+
+/// Types that can be decoded from SSZ when their phase is known in advance.
+///
+/// Clients specify the phase of SSZ request bodies in the `Eth-Consensus-Version` header.
+/// Relying on it avoids guessing the phase from the slot, which is not at the same offset in all
+/// containers.
+pub trait SszReadAtPhase: Sized {
+    fn from_ssz_at_phase(phase: Phase, bytes: &[u8]) -> Result<Self, ReadError>;
+}
+
+impl<T: SszReadAtPhase> SszReadAtPhase for Box<T> {
+    fn from_ssz_at_phase(phase: Phase, bytes: &[u8]) -> Result<Self, ReadError> {
+        T::from_ssz_at_phase(phase, bytes).map(Self::new)
+    }
+}
+
+impl<P: Preset> SszReadAtPhase for SignedBlindedBeaconBlock<P> {
+    fn from_ssz_at_phase(phase: Phase, bytes: &[u8]) -> Result<Self, ReadError> {
+        let block = match phase {
+            Phase::Phase0 => {
+                return Err(ReadError::Custom {
+                    message: "blinded blocks do not exist in Phase 0",
+                });
+            }
+            Phase::Altair => {
+                return Err(ReadError::Custom {
+                    message: "blinded blocks do not exist in Altair",
+                });
+            }
+            Phase::Bellatrix => Self::Bellatrix(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Capella => Self::Capella(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Deneb => Self::Deneb(SszReadDefault::from_ssz_default(bytes)?),
+        };
+
+        Ok(block)
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(bound = "", untagged)]
 pub enum SignedAPIBlock<P: Preset> {
@@ -255,3 +332,107 @@ impl<P: Preset> SszRead<Config> for SignedAPIBlock<P> {
         Ok(api_block)
     }
 }
+
+impl<P: Preset> SszReadAtPhase for SignedAPIBlock<P> {
+    fn from_ssz_at_phase(phase: Phase, bytes: &[u8]) -> Result<Self, ReadError> {
+        let api_block = match phase {
+            Phase::Phase0 => Self::Phase0(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Altair => Self::Altair(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Bellatrix => Self::Bellatrix(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Capella => Self::Capella(SszReadDefault::from_ssz_default(bytes)?),
+            Phase::Deneb => Self::Deneb(SszReadDefault::from_ssz_default(bytes)?),
+        };
+
+        Ok(api_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{bail, Result};
+    use std_ext::ArcExt as _;
+    use types::{
+        capella::containers::{
+            BeaconBlock as CapellaBeaconBlock, BeaconBlockBody as CapellaBeaconBlockBody,
+            ExecutionPayload as CapellaExecutionPayload,
+        },
+        combined::BlindedBeaconBlock,
+        deneb::containers::BeaconBlock as DenebBeaconBlock,
+        phase0::primitives::ExecutionBlockHash,
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn signed_api_block_is_decoded_at_phase_from_header() -> Result<()> {
+        let block_with_blobs = SignedDenebBlockWithBlobs::<Minimal> {
+            signed_block: DenebSignedBeaconBlock {
+                message: DenebBeaconBlock::default(),
+                signature: SignatureBytes::default(),
+            },
+            kzg_proofs: ContiguousList::default(),
+            blobs: ContiguousList::default(),
+        };
+
+        let bytes = block_with_blobs.to_ssz()?;
+        let api_block = SignedAPIBlock::<Minimal>::from_ssz_at_phase(Phase::Deneb, &bytes)?;
+
+        assert!(matches!(api_block, SignedAPIBlock::Deneb(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn blinded_blocks_cannot_be_decoded_at_phases_without_execution_payloads() {
+        for phase in [Phase::Phase0, Phase::Altair] {
+            assert!(SignedBlindedBeaconBlock::<Minimal>::from_ssz_at_phase(phase, &[]).is_err());
+        }
+    }
+    #[test]
+    fn signed_api_blinded_block_replaces_execution_payload_with_header() -> Result<()> {
+        let execution_payload = CapellaExecutionPayload {
+            block_hash: ExecutionBlockHash::repeat_byte(1),
+            ..CapellaExecutionPayload::default()
+        };
+
+        let block = Arc::new(SignedBeaconBlock::<Minimal>::from(
+            CapellaSignedBeaconBlock {
+                message: CapellaBeaconBlock {
+                    slot: 1,
+                    body: CapellaBeaconBlockBody {
+                        execution_payload,
+                        ..CapellaBeaconBlockBody::default()
+                    },
+                    ..CapellaBeaconBlock::default()
+                },
+                signature: SignatureBytes::default(),
+            },
+        ));
+
+        let SignedAPIBlindedBlock::SignedBlindedBeaconBlock(blinded_block) =
+            SignedAPIBlindedBlock::from(block.clone_arc())
+        else {
+            bail!("block with execution payload should be blinded");
+        };
+
+        let (message, _) = blinded_block.split();
+
+        assert!(matches!(message, BlindedBeaconBlock::Capella(_)));
+        assert_eq!(message.hash_tree_root(), block.message().hash_tree_root());
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_api_blinded_block_keeps_blocks_without_execution_payloads() {
+        let block = Arc::new(SignedBeaconBlock::<Minimal>::from(
+            Phase0SignedBeaconBlock::default(),
+        ));
+
+        assert!(matches!(
+            SignedAPIBlindedBlock::from(block),
+            SignedAPIBlindedBlock::SignedBeaconBlock(_),
+        ));
+    }
+}
@@ -0,0 +1,128 @@
+use axum::extract::State;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use genesis::GenesisProvider;
+use helper_functions::multiproofs;
+use http_api_utils::BlockId;
+use serde::{Deserialize, Serialize};
+use ssz::{Multiproof, SszHash as _};
+use types::{
+    nonstandard::WithStatus, phase0::primitives::H256, preset::Preset,
+    traits::SignedBeaconBlock as _,
+};
+
+use crate::{
+    block_id,
+    error::Error,
+    extractors::{EthPath, EthQuery},
+    response::EthResponse,
+    state_id::StateId,
+};
+
+// The trees that proofs are constructed from have fewer nodes than this.
+// Larger requests cannot be valid and would only make the node hash and sort more.
+const MAX_GINDICES: usize = 256;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultiproofQuery {
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_vec_from_string_or_vec")]
+    gindices: Vec<u64>,
+}
+
+impl MultiproofQuery {
+    fn gindices(&self) -> Result<&[u64], Error> {
+        let count = self.gindices.len();
+
+        if count > MAX_GINDICES {
+            return Err(Error::TooManyGeneralizedIndices {
+                count,
+                maximum: MAX_GINDICES,
+            });
+        }
+
+        Ok(&self.gindices)
+    }
+}
+
+#[derive(Serialize)]
+pub struct MultiproofResponse {
+    root: H256,
+    #[serde(with = "serde_utils::string_or_native_sequence")]
+    gindices: Vec<u64>,
+    leaves: Vec<H256>,
+    proof: Vec<H256>,
+}
+
+impl MultiproofResponse {
+    fn new(root: H256, multiproof: Multiproof) -> Self {
+        let Multiproof {
+            gindices,
+            leaves,
+            proof,
+        } = multiproof;
+
+        Self {
+            root,
+            gindices,
+            leaves,
+            proof,
+        }
+    }
+}
+
+/// `GET /grandine/v1/beacon/states/{state_id}/multiproof`
+///
+/// Not part of the standard API. Proves the nodes at `gindices` against the root of the state.
+/// Nodes are available down to the roots of fields and the fields of checkpoints.
+pub async fn get_state_multiproof<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(state_id): EthPath<StateId>,
+    EthQuery(query): EthQuery<MultiproofQuery>,
+) -> Result<EthResponse<MultiproofResponse>, Error> {
+    let gindices = query.gindices()?;
+
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider)?;
+
+    let multiproof = multiproofs::state_multiproof(&state, gindices)
+        .map_err(|error| Error::InvalidGeneralizedIndices(error.into()))?;
+
+    let response = MultiproofResponse::new(state.hash_tree_root(), multiproof);
+
+    Ok(EthResponse::json(response)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
+
+/// `GET /grandine/v1/beacon/blocks/{block_id}/multiproof`
+///
+/// Not part of the standard API. Proves the nodes at `gindices` against the root of the block.
+/// Nodes are available down to the roots of fields and the fields of the block body.
+pub async fn get_block_multiproof<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(block_id): EthPath<BlockId>,
+    EthQuery(query): EthQuery<MultiproofQuery>,
+) -> Result<EthResponse<MultiproofResponse>, Error> {
+    let gindices = query.gindices()?;
+
+    let WithStatus {
+        value: block,
+        optimistic,
+        finalized,
+    } = block_id::block(block_id, &controller, &genesis_provider)?;
+
+    let multiproof = multiproofs::block_multiproof(&block, gindices)
+        .map_err(|error| Error::InvalidGeneralizedIndices(error.into()))?;
+
+    let response = MultiproofResponse::new(block.message().hash_tree_root(), multiproof);
+
+    Ok(EthResponse::json(response)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
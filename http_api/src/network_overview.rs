@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use futures::channel::mpsc::UnboundedSender;
+use helper_functions::{accessors, misc};
+use p2p::ApiToP2p;
+use parking_lot::Mutex;
+use serde::Serialize;
+use transition_functions::combined::{self, Statistics};
+use types::{
+    phase0::{
+        consts::GENESIS_SLOT,
+        primitives::{Epoch, Gwei, H256},
+    },
+    preset::Preset,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
+};
+
+// Proposers of blocks from this many epochs are counted when estimating client diversity.
+const GRAFFITI_EPOCHS: u64 = 4;
+
+// Participation is expressed in basis points to avoid floating point arithmetic.
+const MAX_BASIS_POINTS: u64 = 10_000;
+
+// Lowercase substrings looked for in graffiti. Validators commonly include the name of their
+// consensus client, either by hand or through client defaults.
+const CLIENT_NAMES: &[(&str, &str)] = &[
+    ("caplin", "Caplin"),
+    ("grandine", "Grandine"),
+    ("lighthouse", "Lighthouse"),
+    ("lodestar", "Lodestar"),
+    ("nimbus", "Nimbus"),
+    ("prysm", "Prysm"),
+    ("teku", "Teku"),
+];
+
+const UNKNOWN_CLIENT: &str = "Unknown";
+
+#[derive(Clone, Serialize)]
+pub struct NetworkOverview {
+    epoch: Epoch,
+    participation: Participation,
+    block_clients: BTreeMap<&'static str, u64>,
+    peer_clients: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct Participation {
+    epoch: Epoch,
+    target_participating_balance: Gwei,
+    total_active_balance: Gwei,
+    target_participation_basis_points: u64,
+}
+
+/// Summary of network health recomputed at most once per epoch.
+#[derive(Default)]
+pub struct NetworkOverviewCache {
+    latest: Mutex<Option<NetworkOverview>>,
+}
+
+impl NetworkOverviewCache {
+    pub async fn get<P: Preset, W: Wait>(
+        &self,
+        controller: &ApiController<P, W>,
+        api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+    ) -> Result<NetworkOverview> {
+        let head_state = controller.head_state().value;
+        let epoch = accessors::get_current_epoch(&head_state);
+
+        let latest = self.latest.lock().clone();
+
+        if let Some(overview) = latest.filter(|overview| overview.epoch == epoch) {
+            return Ok(overview);
+        }
+
+        let participation_epoch = accessors::get_previous_epoch(&head_state);
+
+        let target_participating_balance = match combined::statistics(&head_state)? {
+            Statistics::Phase0(statistics) => statistics.previous_epoch_target_attesting_balance,
+            Statistics::Altair(statistics) => {
+                statistics.previous_epoch_target_participating_balance
+            }
+        };
+
+        let total_active_balance = accessors::total_active_balance(&head_state);
+
+        let participation = Participation {
+            epoch: participation_epoch,
+            target_participating_balance,
+            total_active_balance,
+            target_participation_basis_points: target_participating_balance
+                .saturating_mul(MAX_BASIS_POINTS)
+                .checked_div(total_active_balance)
+                .unwrap_or_default(),
+        };
+
+        let start_slot =
+            misc::compute_start_slot_at_epoch::<P>(epoch.saturating_sub(GRAFFITI_EPOCHS))
+                .max(GENESIS_SLOT + 1);
+
+        let mut block_clients = BTreeMap::new();
+
+        for block_with_root in controller.blocks_by_range(start_slot..head_state.slot() + 1)? {
+            let graffiti = block_with_root.block.message().body().graffiti();
+            *block_clients
+                .entry(client_from_graffiti(graffiti))
+                .or_default() += 1;
+        }
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        ApiToP2p::RequestPeerClients(sender).send(api_to_p2p_tx);
+
+        let overview = NetworkOverview {
+            epoch,
+            participation,
+            block_clients,
+            peer_clients: receiver.await?,
+        };
+
+        *self.latest.lock() = Some(overview.clone());
+
+        Ok(overview)
+    }
+}
+
+fn client_from_graffiti(graffiti: H256) -> &'static str {
+    let graffiti = String::from_utf8_lossy(graffiti.as_bytes()).to_lowercase();
+
+    CLIENT_NAMES
+        .iter()
+        .find(|(substring, _)| graffiti.contains(substring))
+        .map_or(UNKNOWN_CLIENT, |(_, client)| *client)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(b"Lighthouse/v4.5.0-441fc16", "Lighthouse")]
+    #[test_case(b"teku/v23.10.0", "Teku")]
+    #[test_case(b"Grandine/0.3.0", "Grandine")]
+    #[test_case(b"PRYSM on a Raspberry Pi", "Prysm")]
+    #[test_case(b"hello world", UNKNOWN_CLIENT)]
+    #[test_case(b"", UNKNOWN_CLIENT)]
+    fn client_is_detected_from_graffiti(text: &[u8], expected_client: &str) {
+        let mut graffiti = H256::zero();
+
+        graffiti
+            .as_bytes_mut()
+            .iter_mut()
+            .zip(text)
+            .for_each(|(destination, source)| *destination = *source);
+
+        assert_eq!(client_from_graffiti(graffiti), expected_client);
+    }
+}
@@ -0,0 +1,321 @@
+use core::{num::ParseIntError, str::FromStr};
+use std::{collections::BTreeMap, sync::Arc, time::SystemTime};
+
+use anyhow::Result;
+use eth1_api::ApiController;
+use fork_choice_control::{PruningProgress, Wait};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std_ext::ArcExt as _;
+use strum::EnumString;
+use types::{phase0::primitives::UnixSeconds, preset::Preset};
+
+use crate::error::Error;
+
+// Finished jobs are forgotten once this many newer jobs have been started.
+const MAX_RETAINED_JOBS: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
+#[serde(transparent)]
+pub struct PruningJobId(u64);
+
+impl FromStr for PruningJobId {
+    type Err = ParseIntError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        string.parse().map(Self)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PruningTarget {
+    BlobSidecars,
+    ExecutionPayloads,
+    OrphanedBlocks,
+    States,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PruningJobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PruningQuery {
+    retain_epochs: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct PruningJobResponse {
+    job_id: PruningJobId,
+    target: PruningTarget,
+    status: PruningJobStatus,
+    started_at: UnixSeconds,
+    finished_at: Option<UnixSeconds>,
+    total: u64,
+    processed: u64,
+    freed_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+struct Outcome {
+    finished_at: UnixSeconds,
+    error: Option<String>,
+}
+
+struct PruningJob {
+    job_id: PruningJobId,
+    target: PruningTarget,
+    started_at: UnixSeconds,
+    progress: PruningProgress,
+    outcome: Mutex<Option<Outcome>>,
+}
+
+impl PruningJob {
+    fn is_running(&self) -> bool {
+        self.outcome.lock().is_none()
+    }
+
+    fn finish(&self, result: Result<()>) {
+        let error = result.err().map(|error| format!("{error:#}"));
+
+        *self.outcome.lock() = Some(Outcome {
+            finished_at: unix_time(),
+            error,
+        });
+    }
+
+    fn response(&self) -> PruningJobResponse {
+        let outcome = self.outcome.lock();
+
+        let status = match outcome.as_ref() {
+            None => PruningJobStatus::Running,
+            Some(Outcome { error: None, .. }) => PruningJobStatus::Completed,
+            Some(Outcome { error: Some(_), .. }) => PruningJobStatus::Failed,
+        };
+
+        PruningJobResponse {
+            job_id: self.job_id,
+            target: self.target,
+            status,
+            started_at: self.started_at,
+            finished_at: outcome.as_ref().map(|outcome| outcome.finished_at),
+            total: self.progress.total(),
+            processed: self.progress.processed(),
+            freed_bytes: self.progress.freed_bytes(),
+            error: outcome.as_ref().and_then(|outcome| outcome.error.clone()),
+        }
+    }
+}
+
+/// Pruning jobs started through the HTTP API.
+///
+/// Only one job may run at a time. Pruning different data concurrently would compete for the
+/// database without finishing any sooner.
+#[derive(Default)]
+pub struct PruningJobs {
+    jobs: Mutex<BTreeMap<PruningJobId, Arc<PruningJob>>>,
+}
+
+impl PruningJobs {
+    fn start(&self, target: PruningTarget) -> Result<Arc<PruningJob>, Error> {
+        let mut jobs = self.jobs.lock();
+
+        if let Some(job) = jobs.values().find(|job| job.is_running()) {
+            return Err(Error::PruningJobRunning {
+                job_id: job.job_id.0,
+            });
+        }
+
+        let job_id = jobs
+            .keys()
+            .next_back()
+            .map_or(PruningJobId(0), |job_id| PruningJobId(job_id.0 + 1));
+
+        let job = Arc::new(PruningJob {
+            job_id,
+            target,
+            started_at: unix_time(),
+            progress: PruningProgress::default(),
+            outcome: Mutex::default(),
+        });
+
+        jobs.insert(job_id, job.clone_arc());
+
+        while jobs.len() > MAX_RETAINED_JOBS {
+            jobs.pop_first();
+        }
+
+        Ok(job)
+    }
+
+    fn get(&self, job_id: PruningJobId) -> Option<Arc<PruningJob>> {
+        self.jobs.lock().get(&job_id).cloned()
+    }
+}
+
+/// `POST /grandine/v1/admin/prune/{target}`
+///
+/// Pruning runs in the background. The response describes the job as it was when it started.
+/// Its progress can be followed through `GET /grandine/v1/admin/prune/jobs/{job_id}`.
+///
+/// `states` pruning applies the retention period set by `--prune-history-epochs`.
+/// `execution_payloads` pruning applies the one set by `--execution-payload-retention-epochs`,
+/// falling back to `--prune-history-epochs`. Both can be overridden with the `retain_epochs` query
+/// parameter. Retention periods shorter than `MIN_EPOCHS_FOR_BLOCK_REQUESTS` are raised to it so
+/// that blocks peers are entitled to request are kept.
+pub async fn post_pruning_job<P: Preset, W: Wait>(
+    controller: ApiController<P, W>,
+    pruning_jobs: Arc<PruningJobs>,
+    target: PruningTarget,
+    query: PruningQuery,
+) -> Result<PruningJobResponse, Error> {
+    // Resolve the retention period before starting the job so that a missing one is reported
+    // in the response rather than as a failed job.
+    let retain_epochs = match target {
+        PruningTarget::ExecutionPayloads => query
+            .retain_epochs
+            .or_else(|| controller.execution_payload_retention_epochs())
+            .or_else(|| controller.prune_history_epochs())
+            .ok_or(Error::StateRetentionNotConfigured)?,
+        PruningTarget::States => query
+            .retain_epochs
+            .or_else(|| controller.prune_history_epochs())
+            .ok_or(Error::StateRetentionNotConfigured)?,
+        PruningTarget::BlobSidecars | PruningTarget::OrphanedBlocks => 0,
+    };
+
+    let retain_epochs = match target {
+        PruningTarget::ExecutionPayloads | PruningTarget::States => {
+            retain_epochs.max(controller.chain_config().min_epochs_for_block_requests())
+        }
+        PruningTarget::BlobSidecars | PruningTarget::OrphanedBlocks => retain_epochs,
+    };
+
+    // Pruning started by the mutator holds the same lock as jobs started here.
+    if controller.is_pruning() {
+        return Err(Error::PruningInProgress);
+    }
+
+    let job = pruning_jobs.start(target)?;
+    let response = job.response();
+
+    info!("started pruning job {} ({target:?})", job.job_id.0);
+
+    // Pruning can take minutes on mainnet, so the handle is dropped instead of awaited.
+    tokio::task::spawn_blocking(move || {
+        let progress = &job.progress;
+
+        let result = match target {
+            PruningTarget::BlobSidecars => controller.prune_blob_sidecars(progress),
+            PruningTarget::ExecutionPayloads => controller
+                .prune_execution_payloads(retain_epochs, progress)
+                .map(|_| ()),
+            PruningTarget::OrphanedBlocks => controller.prune_orphaned_blocks(progress),
+            PruningTarget::States => controller
+                .prune_history(retain_epochs, progress)
+                .map(|_| ()),
+        };
+
+        match &result {
+            Ok(()) => info!(
+                "pruning job {} ({target:?}) completed, freeing {} bytes",
+                job.job_id.0,
+                progress.freed_bytes(),
+            ),
+            Err(error) => warn!(
+                "pruning job {} ({target:?}) failed: {error:?}",
+                job.job_id.0
+            ),
+        }
+
+        job.finish(result);
+    });
+
+    Ok(response)
+}
+
+/// `GET /grandine/v1/admin/prune/jobs/{job_id}`
+pub fn get_pruning_job(
+    pruning_jobs: &PruningJobs,
+    job_id: PruningJobId,
+) -> Result<PruningJobResponse, Error> {
+    let job = pruning_jobs.get(job_id).ok_or(Error::PruningJobNotFound)?;
+    Ok(job.response())
+}
+
+fn unix_time() -> UnixSeconds {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn only_one_pruning_job_runs_at_a_time() {
+        let pruning_jobs = PruningJobs::default();
+
+        let job = pruning_jobs
+            .start(PruningTarget::BlobSidecars)
+            .expect("no other job is running");
+
+        assert!(matches!(
+            pruning_jobs.start(PruningTarget::States),
+            Err(Error::PruningJobRunning { job_id: 0 }),
+        ));
+
+        job.finish(Ok(()));
+
+        let next_job = pruning_jobs
+            .start(PruningTarget::States)
+            .expect("previous job has finished");
+
+        next_job.finish(Err(anyhow!("database is full")));
+
+        let response =
+            get_pruning_job(&pruning_jobs, PruningJobId(0)).expect("job 0 was started above");
+
+        assert_eq!(response.status, PruningJobStatus::Completed);
+        assert_eq!(response.error, None);
+
+        let response =
+            get_pruning_job(&pruning_jobs, PruningJobId(1)).expect("job 1 was started above");
+
+        assert_eq!(response.status, PruningJobStatus::Failed);
+        assert_eq!(response.error.as_deref(), Some("database is full"));
+
+        assert!(matches!(
+            get_pruning_job(&pruning_jobs, PruningJobId(2)),
+            Err(Error::PruningJobNotFound),
+        ));
+    }
+
+    #[test]
+    fn old_pruning_jobs_are_forgotten() {
+        let pruning_jobs = PruningJobs::default();
+
+        for _ in 0..=MAX_RETAINED_JOBS {
+            pruning_jobs
+                .start(PruningTarget::OrphanedBlocks)
+                .expect("no other job is running")
+                .finish(Ok(()));
+        }
+
+        assert!(pruning_jobs.get(PruningJobId(0)).is_none());
+        assert!(pruning_jobs.get(PruningJobId(1)).is_some());
+    }
+}
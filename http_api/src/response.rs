@@ -1,28 +1,85 @@
+use core::cmp::Reverse;
+
 use anyhow::Result;
 use axum::{
-    http::{header::ACCEPT, HeaderMap, HeaderValue},
+    http::{HeaderMap, HeaderValue},
     response::{IntoResponse, Response},
     Json,
 };
-use mime::APPLICATION_OCTET_STREAM;
+use itertools::Itertools as _;
+use mime::{Mime, APPLICATION_JSON, APPLICATION_OCTET_STREAM};
 use serde::Serialize;
 use ssz::SszWrite;
 use types::{bellatrix::primitives::Wei, nonstandard::Phase, phase0::primitives::H256};
 
 use crate::error::Error;
 
-const ETH_CONSENSUS_VERSION: &str = "eth-consensus-version";
+pub const ETH_CONSENSUS_VERSION: &str = "eth-consensus-version";
 const ETH_CONSENSUS_BLOCK_VALUE: &str = "eth-consensus-block-value";
 const ETH_EXECUTION_PAYLOAD_BLINDED: &str = "eth-execution-payload-blinded";
 const ETH_EXECUTION_PAYLOAD_VALUE: &str = "eth-execution-payload-value";
 
 pub struct AlwaysJson;
 
+// Quality values are stored in thousandths to avoid comparing floating point numbers.
+const MAX_QUALITY: u16 = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum JsonOrSsz {
     Json,
     Ssz,
 }
 
+impl JsonOrSsz {
+    // Media ranges are matched as described in RFC 9110, section 12.5.1.
+    // The quality of a format is taken from the most specific media range that matches it.
+    // Ties are resolved in favor of the format listed first and then in favor of JSON.
+    // Returns `None` if neither format is acceptable.
+    pub fn negotiate(accept: &str) -> Option<Self> {
+        let media_ranges = accept
+            .split(',')
+            .enumerate()
+            .filter_map(|(position, media_range)| {
+                let media_range = media_range.trim().parse::<Mime>().ok()?;
+
+                let quality = match media_range.get_param("q") {
+                    Some(quality) => parse_quality(quality.as_str())?,
+                    None => MAX_QUALITY,
+                };
+
+                Some((position, media_range, quality))
+            })
+            .collect_vec();
+
+        // Malformed headers are ignored as if they were absent.
+        if media_ranges.is_empty() {
+            return Some(Self::Json);
+        }
+
+        let preference = |media_type: &Mime| {
+            media_ranges
+                .iter()
+                .filter_map(|(position, media_range, quality)| {
+                    let specificity = specificity(media_range, media_type)?;
+                    Some((specificity, *quality, *position))
+                })
+                .max_by_key(|(specificity, _, _)| *specificity)
+                .filter(|(_, quality, _)| *quality > 0)
+                .map(|(_, quality, position)| (quality, Reverse(position)))
+        };
+
+        match (
+            preference(&APPLICATION_JSON),
+            preference(&APPLICATION_OCTET_STREAM),
+        ) {
+            (Some(json), Some(ssz)) if ssz > json => Some(Self::Ssz),
+            (Some(_), _) => Some(Self::Json),
+            (None, Some(_)) => Some(Self::Ssz),
+            (None, None) => None,
+        }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Serialize)]
 pub struct EthResponse<T, M = (), F = AlwaysJson> {
@@ -238,15 +295,81 @@ impl<T> EthResponse<T, (), AlwaysJson> {
 }
 
 impl<T> EthResponse<T, (), JsonOrSsz> {
-    // `axum` recommends using `axum::TypedHeader` instead of extracting all headers,
-    // but the `headers` crate does not provide a type for the `Accept` header.
-    // See <https://github.com/hyperium/headers/issues/53>.
-    pub fn json_or_ssz(data: T, request_headers: &HeaderMap) -> Self {
-        let format = match request_headers.get(ACCEPT) {
-            Some(accept) if accept == APPLICATION_OCTET_STREAM.as_ref() => JsonOrSsz::Ssz,
-            _ => JsonOrSsz::Json,
-        };
-
+    pub const fn json_or_ssz(data: T, format: JsonOrSsz) -> Self {
         Self::new(data, format)
     }
 }
+
+// Parses a quality value as defined in RFC 9110, section 12.4.2.
+fn parse_quality(value: &str) -> Option<u16> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+    if fraction.len() > 3 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let integer = match integer {
+        "0" => 0,
+        "1" => MAX_QUALITY,
+        _ => return None,
+    };
+
+    let fraction = format!("{fraction:0<3}").parse::<u16>().ok()?;
+    let quality = integer + fraction;
+
+    (quality <= MAX_QUALITY).then_some(quality)
+}
+
+fn specificity(media_range: &Mime, media_type: &Mime) -> Option<u8> {
+    if media_range.type_() == mime::STAR && media_range.subtype() == mime::STAR {
+        return Some(0);
+    }
+
+    if media_range.type_() != media_type.type_() {
+        return None;
+    }
+
+    if media_range.subtype() == mime::STAR {
+        return Some(1);
+    }
+
+    (media_range.subtype() == media_type.subtype()).then_some(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("application/json" => Some(JsonOrSsz::Json))]
+    #[test_case("application/octet-stream" => Some(JsonOrSsz::Ssz))]
+    #[test_case("*/*" => Some(JsonOrSsz::Json))]
+    #[test_case("application/*" => Some(JsonOrSsz::Json))]
+    #[test_case("application/octet-stream;q=1,application/json;q=0.9" => Some(JsonOrSsz::Ssz))]
+    #[test_case("application/json;q=0.9,application/octet-stream" => Some(JsonOrSsz::Ssz))]
+    #[test_case("application/octet-stream;q=0.5,application/json;q=0.9" => Some(JsonOrSsz::Json))]
+    #[test_case("application/octet-stream,application/json" => Some(JsonOrSsz::Ssz))]
+    #[test_case("application/json, application/octet-stream" => Some(JsonOrSsz::Json))]
+    #[test_case("*/*;q=0.1, application/json;q=0" => Some(JsonOrSsz::Ssz))]
+    #[test_case("text/html" => None)]
+    #[test_case("application/json;q=0" => None)]
+    #[test_case("text/html, application/octet-stream;q=2" => None)]
+    #[test_case("not a media type" => Some(JsonOrSsz::Json))]
+    fn negotiate(accept: &str) -> Option<JsonOrSsz> {
+        JsonOrSsz::negotiate(accept)
+    }
+
+    #[test_case("1" => Some(1000))]
+    #[test_case("0" => Some(0))]
+    #[test_case("0.9" => Some(900))]
+    #[test_case("0.125" => Some(125))]
+    #[test_case("1.000" => Some(1000))]
+    #[test_case("1.5" => None)]
+    #[test_case("0.1234" => None)]
+    #[test_case("2" => None)]
+    #[test_case(".5" => None)]
+    fn quality(value: &str) -> Option<u16> {
+        parse_quality(value)
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use axum::{
     extract::{FromRef, State},
@@ -6,10 +6,11 @@ use axum::{
     Json, Router,
 };
 use bls::PublicKeyBytes;
+use bytesize::ByteSize;
 use eth1_api::ApiController;
 use features::Feature;
 use fork_choice_control::Wait;
-use futures::channel::mpsc::UnboundedSender;
+use futures::{channel::mpsc::UnboundedSender, lock::Mutex};
 use genesis::GenesisProvider;
 use keymanager::KeyManager;
 use liveness_tracker::ApiToLiveness;
@@ -18,42 +19,57 @@ use operation_pools::{AttestationAggPool, BlsToExecutionChangePool, SyncCommitte
 use p2p::{ApiToP2p, NetworkConfig, ToSubnetService};
 use prometheus_metrics::Metrics;
 use serde_qs::axum::QsQuery;
+use slashing_protection::SlashingProtector;
 use std_ext::ArcExt as _;
 use types::{config::Config as ChainConfig, preset::Preset};
 use validator::{ApiToValidator, ValidatorConfig};
 
 use crate::{
+    backup::{self, BackupDirectory},
+    block_root_proof, compaction,
+    config_fingerprint::{self, StorageSettings},
     error::Error,
     events::EventChannels,
+    extractors::{EthPath, EthQuery},
     global::{self},
-    gui, middleware,
+    gui,
+    head_statement::HeadStatementSigner,
+    middleware,
     misc::{BackSyncedStatus, SyncedStatus},
+    multiproof,
+    network_overview::NetworkOverviewCache,
+    pruning::{self, PruningJobs},
+    ssz_events,
     standard::{
-        beacon_events, beacon_heads, beacon_state, blob_sidecars, block, block_attestations,
-        block_headers, block_id_headers, block_rewards, block_root, config_spec, debug_fork_choice,
-        deposit_contract, expected_withdrawals, fork_schedule, genesis,
-        keymanager_delete_fee_recipient, keymanager_delete_gas_limit, keymanager_delete_graffiti,
-        keymanager_delete_keystores, keymanager_delete_remote_keys, keymanager_get_gas_limit,
-        keymanager_get_graffiti, keymanager_import_keystores, keymanager_import_remote_keys,
-        keymanager_list_fee_recipient, keymanager_list_remote_keys,
+        attestation_rewards, beacon_events, beacon_heads, beacon_state, blinded_block,
+        blob_sidecars, block, block_attestations, block_headers, block_id_headers, block_rewards,
+        block_root, config_spec, debug_fork_choice, deposit_contract, expected_withdrawals,
+        fork_schedule, genesis, keymanager_delete_fee_recipient, keymanager_delete_gas_limit,
+        keymanager_delete_graffiti, keymanager_delete_keystores, keymanager_delete_remote_keys,
+        keymanager_get_gas_limit, keymanager_get_graffiti, keymanager_import_keystores,
+        keymanager_import_remote_keys, keymanager_list_fee_recipient, keymanager_list_remote_keys,
         keymanager_list_validating_pubkeys, keymanager_set_fee_recipient, keymanager_set_gas_limit,
         keymanager_set_graffiti, node_health, node_identity, node_peer, node_peer_count,
         node_peers, node_syncing_status, node_version, pool_attestations, pool_attester_slashings,
         pool_bls_to_execution_changes, pool_proposer_slashings, pool_voluntary_exits,
-        publish_blinded_block, publish_block, state_committees, state_finality_checkpoints,
-        state_fork, state_randao, state_root, state_sync_committees, state_validator,
-        state_validator_balances, state_validators, submit_pool_attestations,
-        submit_pool_attester_slashing, submit_pool_bls_to_execution_change,
-        submit_pool_proposer_slashing, submit_pool_sync_committees, submit_pool_voluntary_exit,
-        sync_committee_rewards, validator_aggregate_attestation, validator_attestation_data,
-        validator_attester_duties, validator_beacon_committee_selections, validator_blinded_block,
-        validator_block, validator_block_v3, validator_liveness, validator_prepare_beacon_proposer,
+        publish_blinded_block, publish_blinded_block_v2, publish_block, publish_block_v2,
+        state_committees, state_finality_checkpoints, state_fork, state_randao, state_root,
+        state_sync_committees, state_validator, state_validator_balances, state_validators,
+        submit_pool_attestations, submit_pool_attester_slashing,
+        submit_pool_bls_to_execution_change, submit_pool_proposer_slashing,
+        submit_pool_sync_committees, submit_pool_voluntary_exit, sync_committee_rewards,
+        validator_aggregate_attestation, validator_attestation_data, validator_attester_duties,
+        validator_beacon_committee_selections, validator_blinded_block, validator_block,
+        validator_block_v3, validator_liveness, validator_prepare_beacon_proposer,
         validator_proposer_duties, validator_publish_aggregate_and_proofs,
         validator_publish_contributions_and_proofs, validator_register_validator,
         validator_subscribe_to_beacon_committee, validator_subscribe_to_sync_committees,
         validator_sync_committee_contribution, validator_sync_committee_duties,
         validator_sync_committee_selections,
     },
+    state_diff, state_field,
+    trusted_client::TrustedClientToken,
+    validator_duties,
 };
 
 #[cfg(test)]
@@ -77,12 +93,20 @@ pub struct NormalState<P: Preset, W: Wait> {
     pub validator_config: Arc<ValidatorConfig>,
     pub metrics: Option<Arc<Metrics>>,
     pub network_config: Arc<NetworkConfig>,
+    pub storage_settings: StorageSettings,
+    pub slashing_protector: Arc<Mutex<SlashingProtector>>,
+    pub backup_directory: Option<PathBuf>,
     pub attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
     pub sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
     pub bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
     pub is_synced: Arc<SyncedStatus>,
     pub is_back_synced: Arc<BackSyncedStatus>,
     pub event_channels: Arc<EventChannels>,
+    pub network_overview: Arc<NetworkOverviewCache>,
+    pub pruning_jobs: Arc<PruningJobs>,
+    pub head_statement_signer: Option<Arc<HeadStatementSigner>>,
+    pub trusted_client_token: Option<Arc<TrustedClientToken>>,
+    pub read_only: bool,
     pub api_to_liveness_tx: Option<UnboundedSender<ApiToLiveness>>,
     pub api_to_metrics_tx: Option<UnboundedSender<ApiToMetrics>>,
     pub api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
@@ -170,6 +194,48 @@ impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<EventChannels> {
     }
 }
 
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<NetworkOverviewCache> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.network_overview.clone_arc()
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<PruningJobs> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.pruning_jobs.clone_arc()
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for StorageSettings {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.storage_settings
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<Mutex<SlashingProtector>> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.slashing_protector.clone_arc()
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for BackupDirectory {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        Self(state.backup_directory.clone())
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<Arc<HeadStatementSigner>> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.head_statement_signer.clone()
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<Arc<TrustedClientToken>> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.trusted_client_token.clone()
+    }
+}
+
 impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<UnboundedSender<ApiToLiveness>> {
     fn from_ref(state: &NormalState<P, W>) -> Self {
         state.api_to_liveness_tx.clone()
@@ -219,9 +285,9 @@ pub struct TestState<P: Preset> {
 }
 
 pub fn normal_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router {
-    gui_routes()
+    gui_routes(state.clone())
         .merge(eth_v1_beacon_routes(state.clone()))
-        .merge(eth_v2_beacon_routes())
+        .merge(eth_v2_beacon_routes(state.clone()))
         .merge(eth_v1_builder_routes())
         .merge(eth_v1_config_routes())
         .merge(eth_v1_debug_routes())
@@ -231,11 +297,13 @@ pub fn normal_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router {
         .merge(eth_v1_validator_routes(state.clone()))
         .merge(eth_v2_validator_routes(state.clone()))
         .merge(eth_v3_validator_routes(state.clone()))
-        .merge(eth_v1_keymanager_routes())
+        .merge(eth_v1_keymanager_routes(state.read_only))
         .with_state(state)
 }
 
-fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
+fn gui_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<NormalState<P, W>> {
+    let read_only = state.read_only;
+
     Router::new()
         .route(
             "/beacon/head",
@@ -286,6 +354,301 @@ fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
                 middleware::feature_is_enabled,
             )),
         )
+        .route(
+            "/builder/relays",
+            get(|extracted| async {
+                let State(api_to_validator_tx) = extracted;
+
+                gui::get_builder_relays(api_to_validator_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeLeakyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/validator/standby",
+            get(|extracted| async {
+                let State(api_to_validator_tx) = extracted;
+
+                gui::get_validator_standby(api_to_validator_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/validator/standby/activate",
+            post(|extracted| async {
+                let State(api_to_validator_tx) = extracted;
+
+                gui::post_validator_standby_activate(api_to_validator_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeEffectfulEndpoints,
+                middleware::feature_is_enabled,
+            ))
+            .route_layer(axum::middleware::map_request_with_state(
+                read_only,
+                middleware::is_writable,
+            )),
+        )
+        .route(
+            "/grandine/v1/validator/duties/all/:epoch",
+            post(validator_duties::validator_all_duties).route_layer(
+                axum::middleware::map_request_with_state(state, middleware::is_synced),
+            ),
+        )
+        .route(
+            "/grandine/v1/validator/registry_changes",
+            get(|extracted| async {
+                let (State(controller), State::<Arc<_>>(validator_keys), EthQuery(query)) =
+                    extracted;
+
+                gui::get_validator_registry_changes(&controller, &validator_keys, query)
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeLeakyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/keystores/registry_changes",
+            get(|extracted| async {
+                let (State(controller), State::<Arc<_>>(keymanager), EthQuery(query)) = extracted;
+
+                gui::get_keystore_registry_changes(&controller, &keymanager, query)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeLeakyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/validator/sync_committee_performance",
+            get(|extracted| async {
+                let State(api_to_validator_tx) = extracted;
+
+                gui::get_validator_sync_committee_performance(api_to_validator_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeLeakyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/node/peers",
+            get(|extracted| async {
+                let (State(api_to_p2p_tx), EthQuery(query)) = extracted;
+
+                gui::get_node_peers(&api_to_p2p_tx, query)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/node/reachability",
+            get(|extracted| async {
+                let State(api_to_p2p_tx) = extracted;
+
+                gui::get_node_reachability(&api_to_p2p_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/node/backfill_status",
+            get(|extracted| async {
+                let (State(controller), State::<Arc<BackSyncedStatus>>(is_back_synced)) = extracted;
+
+                gui::get_node_backfill_status(&controller, &is_back_synced)
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/node/head_statement",
+            get(|extracted| async {
+                let (State(controller), State::<Option<Arc<_>>>(head_statement_signer)) = extracted;
+
+                gui::get_node_head_statement(&controller, head_statement_signer.as_deref())
+                    .map(Json)
+            }),
+        )
+        .route(
+            "/grandine/v1/config_fingerprint",
+            get(|extracted| async {
+                let (State::<Arc<ChainConfig>>(chain_config), State(storage_settings)) = extracted;
+
+                config_fingerprint::get_config_fingerprint(&chain_config, storage_settings)
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/backup",
+            post(|extracted| async {
+                let (
+                    State(controller),
+                    State(slashing_protector),
+                    State(backup_directory),
+                    State::<StorageSettings>(storage_settings),
+                ) = extracted;
+
+                backup::post_backup(
+                    controller,
+                    slashing_protector,
+                    backup_directory,
+                    ByteSize(storage_settings.db_size),
+                )
+                .await
+                .map(Json)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeEffectfulEndpoints,
+                middleware::feature_is_enabled,
+            ))
+            .route_layer(axum::middleware::map_request_with_state(
+                read_only,
+                middleware::is_writable,
+            )),
+        )
+        .route(
+            "/grandine/v1/admin/compact",
+            post(|extracted| async {
+                let State(controller) = extracted;
+
+                compaction::post_compaction(controller).await.map(Json)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeEffectfulEndpoints,
+                middleware::feature_is_enabled,
+            ))
+            .route_layer(axum::middleware::map_request_with_state(
+                read_only,
+                middleware::is_writable,
+            )),
+        )
+        .route(
+            "/grandine/v1/admin/prune/:target",
+            post(|extracted| async {
+                let (State(controller), State(pruning_jobs), EthPath(target), EthQuery(query)) =
+                    extracted;
+
+                pruning::post_pruning_job(controller, pruning_jobs, target, query)
+                    .await
+                    .map(Json)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeEffectfulEndpoints,
+                middleware::feature_is_enabled,
+            ))
+            .route_layer(axum::middleware::map_request_with_state(
+                read_only,
+                middleware::is_writable,
+            )),
+        )
+        .route(
+            "/grandine/v1/admin/prune/jobs/:job_id",
+            get(|extracted| async {
+                let (State::<Arc<PruningJobs>>(pruning_jobs), EthPath(job_id)) = extracted;
+
+                pruning::get_pruning_job(&pruning_jobs, job_id).map(Json)
+            }),
+        )
+        .route(
+            "/grandine/v1/network_overview",
+            get(|extracted| async {
+                let (State(controller), State::<Arc<_>>(network_overview), State(api_to_p2p_tx)) =
+                    extracted;
+
+                gui::get_network_overview(&controller, &network_overview, &api_to_p2p_tx)
+                    .await
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeCostlyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/debug/state_diff",
+            get(|extracted| async {
+                let (State(controller), State(genesis_provider), EthQuery(query)) = extracted;
+
+                state_diff::get_state_diff(&controller, genesis_provider, query)
+                    .await
+                    .map(Json)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeCostlyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/debug/block_inclusion",
+            get(|extracted| async {
+                let State(api_to_validator_tx) = extracted;
+
+                gui::get_debug_block_inclusion(api_to_validator_tx)
+                    .await
+                    .map(Json)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeCostlyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
+        .route(
+            "/grandine/v1/debug/reorgs",
+            get(|extracted| async {
+                let (State(controller), EthQuery(query)) = extracted;
+
+                gui::get_debug_reorgs(&controller, query)
+                    .map(Json)
+                    .map_err(Error::Internal)
+            }),
+        )
+        .route(
+            "/grandine/v1/beacon/states/:state_id/field/:field_name",
+            get(state_field::get_state_field),
+        )
+        .route(
+            "/grandine/v1/beacon/states/:state_id/block_root_proof/:slot",
+            get(block_root_proof::get_state_block_root_proof),
+        )
+        .route(
+            "/grandine/v1/beacon/states/:state_id/multiproof",
+            get(multiproof::get_state_multiproof),
+        )
+        .route(
+            "/grandine/v1/beacon/blocks/:block_id/multiproof",
+            get(multiproof::get_block_multiproof),
+        )
+        .route(
+            "/grandine/v1/events/ssz",
+            get(ssz_events::get_ssz_events).route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeCostlyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
         .route(
             "/validator/owned",
             get(|extracted| async {
@@ -309,12 +672,15 @@ fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
         )
         .route(
             "/features",
-            patch(|Json(features)| async { global::patch_features(features) }).route_layer(
-                axum::middleware::map_request_with_state(
+            patch(|Json(features)| async { global::patch_features(features) })
+                .route_layer(axum::middleware::map_request_with_state(
                     Feature::ServeEffectfulEndpoints,
                     middleware::feature_is_enabled,
-                ),
-            ),
+                ))
+                .route_layer(axum::middleware::map_request_with_state(
+                    read_only,
+                    middleware::is_writable,
+                )),
         )
         .route(
             "/system/stats",
@@ -331,6 +697,20 @@ fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
                 middleware::feature_is_enabled,
             )),
         )
+        .route(
+            "/system/operational_counters",
+            get(|extracted| async {
+                let State::<Option<Arc<Metrics>>>(metrics) = extracted;
+
+                global::get_operational_counters(metrics.as_deref())
+                    .map(Json)
+                    .map_err(Error::Internal)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeLeakyEndpoints,
+                middleware::feature_is_enabled,
+            )),
+        )
 }
 
 // TODO(Grandine Team): The standard routes should be restricted with `Feature`s too. The easiest way
@@ -373,46 +753,87 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
         .route("/eth/v1/beacon/headers/:block_id", get(block_id_headers));
 
     let block_routes = Router::new()
+        .route(
+            "/eth/v1/beacon/blinded_blocks/:block_id",
+            get(blinded_block),
+        )
         .route("/eth/v1/beacon/blocks/:block_id/root", get(block_root))
         .route(
             "/eth/v1/beacon/blocks/:block_id/attestations",
             get(block_attestations),
+        );
+
+    let pool_routes = Router::new()
+        .route("/eth/v1/beacon/pool/attestations", get(pool_attestations))
+        .route(
+            "/eth/v1/beacon/pool/bls_to_execution_changes",
+            get(pool_bls_to_execution_changes),
+        )
+        .route(
+            "/eth/v1/beacon/pool/voluntary_exits",
+            get(pool_voluntary_exits),
+        )
+        .route(
+            "/eth/v1/beacon/pool/attester_slashings",
+            get(pool_attester_slashings),
         )
+        .route(
+            "/eth/v1/beacon/pool/proposer_slashings",
+            get(pool_proposer_slashings),
+        );
+
+    let read_only = state.read_only;
+
+    // Routes that publish objects to the network.
+    let submission_routes = Router::new()
         .route(
             "/eth/v1/beacon/blocks",
             post(publish_block).route_layer(axum::middleware::map_request_with_state(
                 state.clone(),
                 middleware::is_synced,
             )),
-        );
-
-    let pool_routes = Router::new()
+        )
+        .route(
+            "/eth/v1/beacon/blinded_blocks",
+            post(publish_blinded_block).route_layer(axum::middleware::map_request_with_state(
+                state,
+                middleware::is_synced,
+            )),
+        )
         .route(
             "/eth/v1/beacon/pool/attestations",
-            get(pool_attestations).post(submit_pool_attestations),
+            post(submit_pool_attestations),
         )
         .route(
             "/eth/v1/beacon/pool/bls_to_execution_changes",
-            get(pool_bls_to_execution_changes).post(submit_pool_bls_to_execution_change),
+            post(submit_pool_bls_to_execution_change),
         )
         .route(
             "/eth/v1/beacon/pool/voluntary_exits",
-            get(pool_voluntary_exits).post(submit_pool_voluntary_exit),
+            post(submit_pool_voluntary_exit),
         )
         .route(
             "/eth/v1/beacon/pool/attester_slashings",
-            get(pool_attester_slashings).post(submit_pool_attester_slashing),
+            post(submit_pool_attester_slashing),
         )
         .route(
             "/eth/v1/beacon/pool/proposer_slashings",
-            get(pool_proposer_slashings).post(submit_pool_proposer_slashing),
+            post(submit_pool_proposer_slashing),
         )
         .route(
             "/eth/v1/beacon/pool/sync_committees",
             post(submit_pool_sync_committees),
-        );
+        )
+        .route_layer(axum::middleware::map_request_with_state(
+            read_only,
+            middleware::is_writable,
+        ));
 
     let reward_routes = Router::new()
+        .route(
+            "/eth/v1/beacon/rewards/attestations/:epoch",
+            post(attestation_rewards),
+        )
         .route(
             "/eth/v1/beacon/rewards/blocks/:block_id",
             get(block_rewards),
@@ -423,24 +844,42 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
         );
 
     Router::new()
-        .route(
-            "/eth/v1/beacon/blinded_blocks",
-            post(publish_blinded_block).route_layer(axum::middleware::map_request_with_state(
-                state,
-                middleware::is_synced,
-            )),
-        )
         .route("/eth/v1/beacon/blob_sidecars/:block_id", get(blob_sidecars))
         .route("/eth/v1/beacon/genesis", get(genesis))
         .merge(state_routes)
         .merge(header_routes)
         .merge(block_routes)
         .merge(pool_routes)
+        .merge(submission_routes)
         .merge(reward_routes)
 }
 
-fn eth_v2_beacon_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
-    Router::new().route("/eth/v2/beacon/blocks/:block_id", get(block))
+fn eth_v2_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<NormalState<P, W>> {
+    let read_only = state.read_only;
+
+    let submission_routes = Router::new()
+        .route(
+            "/eth/v2/beacon/blocks",
+            post(publish_block_v2).route_layer(axum::middleware::map_request_with_state(
+                state.clone(),
+                middleware::is_synced,
+            )),
+        )
+        .route(
+            "/eth/v2/beacon/blinded_blocks",
+            post(publish_blinded_block_v2).route_layer(axum::middleware::map_request_with_state(
+                state,
+                middleware::is_synced,
+            )),
+        )
+        .route_layer(axum::middleware::map_request_with_state(
+            read_only,
+            middleware::is_writable,
+        ));
+
+    Router::new()
+        .route("/eth/v2/beacon/blocks/:block_id", get(block))
+        .merge(submission_routes)
 }
 
 fn eth_v1_builder_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
@@ -506,6 +945,32 @@ fn eth_v1_validator_routes<P: Preset, W: Wait>(
             "/eth/v1/validator/aggregate_attestation",
             get(validator_aggregate_attestation),
         )
+        .route(
+            "/eth/v1/validator/sync_committee_contribution",
+            get(validator_sync_committee_contribution),
+        )
+        .route(
+            "/eth/v1/validator/liveness/:epoch",
+            post(validator_liveness),
+        )
+        .route(
+            "/eth/v1/validator/beacon_committee_selections",
+            post(validator_beacon_committee_selections),
+        )
+        .route(
+            "/eth/v1/validator/sync_committee_selections",
+            post(validator_sync_committee_selections),
+        )
+        .merge(validator_submission_routes(state.read_only))
+        .layer(axum::middleware::map_request_with_state(
+            state,
+            middleware::is_synced,
+        ))
+}
+
+// Routes that publish objects to the network or change what the node subscribes to.
+fn validator_submission_routes<P: Preset, W: Wait>(read_only: bool) -> Router<NormalState<P, W>> {
+    Router::new()
         .route(
             "/eth/v1/validator/aggregate_and_proofs",
             post(validator_publish_aggregate_and_proofs),
@@ -518,10 +983,6 @@ fn eth_v1_validator_routes<P: Preset, W: Wait>(
             "/eth/v1/validator/sync_committee_subscriptions",
             post(validator_subscribe_to_sync_committees),
         )
-        .route(
-            "/eth/v1/validator/sync_committee_contribution",
-            get(validator_sync_committee_contribution),
-        )
         .route(
             "/eth/v1/validator/contribution_and_proofs",
             post(validator_publish_contributions_and_proofs),
@@ -534,25 +995,14 @@ fn eth_v1_validator_routes<P: Preset, W: Wait>(
             "/eth/v1/validator/register_validator",
             post(validator_register_validator),
         )
-        .route(
-            "/eth/v1/validator/liveness/:epoch",
-            post(validator_liveness),
-        )
-        .route(
-            "/eth/v1/validator/beacon_committee_selections",
-            post(validator_beacon_committee_selections),
-        )
-        .route(
-            "/eth/v1/validator/sync_committee_selections",
-            post(validator_sync_committee_selections),
-        )
-        .layer(axum::middleware::map_request_with_state(
-            state,
-            middleware::is_synced,
+        .route_layer(axum::middleware::map_request_with_state(
+            read_only,
+            middleware::is_writable,
         ))
 }
 
-fn eth_v1_keymanager_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
+// Key manager routes are disabled entirely in read-only mode. Such nodes have no keys to manage.
+fn eth_v1_keymanager_routes<P: Preset, W: Wait>(read_only: bool) -> Router<NormalState<P, W>> {
     Router::new()
         .route(
             "/eth/v1/validator/:pubkey/feerecipient",
@@ -596,6 +1046,10 @@ fn eth_v1_keymanager_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
         .route("/eth/v1/remotekeys", get(keymanager_list_remote_keys))
         .route("/eth/v1/remotekeys", post(keymanager_import_remote_keys))
         .route("/eth/v1/remotekeys", delete(keymanager_delete_remote_keys))
+        .route_layer(axum::middleware::map_request_with_state(
+            read_only,
+            middleware::is_writable,
+        ))
 }
 
 fn eth_v2_validator_routes<P: Preset, W: Wait>(
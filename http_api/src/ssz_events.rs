@@ -0,0 +1,140 @@
+//! An alternative to `GET /eth/v1/events` for consumers that cannot afford JSON encoding.
+//!
+//! Clients subscribe to topics by sending text messages containing JSON objects like
+//! `{"subscribe":["block","head"]}` and `{"unsubscribe":["head"]}`.
+//! Topics are named the same as in `GET /eth/v1/events`.
+//!
+//! Events are sent as binary messages. The first byte of each message identifies the topic
+//! (see the discriminants of [`Topic`]). The rest of the message is the SSZ encoding of the event.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+};
+use log::{debug, warn};
+use serde::Deserialize;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt as _, StreamMap,
+};
+
+use crate::events::{EventChannels, SszEvent, Topic};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+enum SubscriptionRequest {
+    Subscribe(Vec<Topic>),
+    Unsubscribe(Vec<Topic>),
+}
+
+/// `GET /grandine/v1/events/ssz`
+pub async fn get_ssz_events(
+    State(event_channels): State<Arc<EventChannels>>,
+    web_socket_upgrade: WebSocketUpgrade,
+) -> Response {
+    web_socket_upgrade.on_upgrade(|socket| async move {
+        if let Err(error) = stream_events(socket, &event_channels).await {
+            debug!("SSZ event stream closed: {error:?}");
+        }
+    })
+}
+
+async fn stream_events(mut socket: WebSocket, event_channels: &EventChannels) -> Result<()> {
+    let mut subscriptions = StreamMap::new();
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let Some(message) = message else {
+                    return Ok(());
+                };
+
+                match message? {
+                    Message::Text(text) => match serde_json::from_str(&text)? {
+                        SubscriptionRequest::Subscribe(topics) => {
+                            for topic in topics {
+                                let receiver = event_channels.ssz_receiver_for(topic);
+                                subscriptions.insert(topic, BroadcastStream::new(receiver));
+                            }
+                        }
+                        SubscriptionRequest::Unsubscribe(topics) => {
+                            for topic in topics {
+                                subscriptions.remove(&topic);
+                            }
+                        }
+                    },
+                    Message::Close(_) => return Ok(()),
+                    Message::Binary(_) | Message::Ping(_) | Message::Pong(_) => {}
+                }
+            }
+
+            Some((topic, result)) = subscriptions.next(), if !subscriptions.is_empty() => {
+                match result {
+                    Ok(event) => socket.send(Message::Binary(encode(&event))).await?,
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        warn!(
+                            "SSZ event stream lagged behind and skipped {skipped} events \
+                             in topic {}",
+                            topic.as_ref(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn encode(event: &SszEvent) -> Vec<u8> {
+    let SszEvent { topic, bytes } = event;
+
+    core::iter::once(*topic as u8)
+        .chain(bytes.iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn subscription_requests_are_deserialized_from_topic_names() -> Result<()> {
+        let request = serde_json::from_value(json!({"subscribe": ["block", "chain_reorg"]}))?;
+
+        assert!(matches!(
+            request,
+            SubscriptionRequest::Subscribe(topics)
+                if topics == [Topic::Block, Topic::ChainReorg],
+        ));
+
+        let request = serde_json::from_value(json!({"unsubscribe": ["head"]}))?;
+
+        assert!(matches!(
+            request,
+            SubscriptionRequest::Unsubscribe(topics) if topics == [Topic::Head],
+        ));
+
+        assert!(
+            serde_json::from_value::<SubscriptionRequest>(json!({"subscribe": ["blob"]})).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_prefixed_with_topic() {
+        let event = SszEvent {
+            topic: Topic::Head,
+            bytes: [1, 2, 3].into(),
+        };
+
+        assert_eq!(encode(&event), [6, 1, 2, 3]);
+    }
+}
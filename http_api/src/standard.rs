@@ -5,12 +5,13 @@
 // This makes `http_api::routing` less messy at the cost of coupling to `axum` even more.
 #![allow(clippy::unused_async)]
 
+use core::convert::Infallible;
 use std::{collections::HashSet, sync::Arc};
 
 use anyhow::{ensure, Error as AnyhowError, Result};
 use axum::{
     extract::State,
-    http::{HeaderMap, StatusCode},
+    http::StatusCode,
     response::{
         sse::{Event, KeepAlive},
         IntoResponse as _, Response, Sse,
@@ -48,7 +49,10 @@ use serde_with::{As, DisplayFromStr};
 use ssz::{ContiguousList, SszHash as _};
 use std_ext::ArcExt as _;
 use tap::Pipe as _;
-use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use transition_functions::{
+    altair::EpochReport as AltairEpochReport, combined::EpochReport,
+    phase0::EpochReport as Phase0EpochReport,
+};
 use try_from_iterator::TryFromIterator as _;
 use typenum::Unsigned as _;
 use types::{
@@ -79,7 +83,7 @@ use types::{
         },
     },
     preset::Preset,
-    traits::{BeaconState as _, SignedBeaconBlock as _},
+    traits::{BeaconState as _, PostAltairBeaconState, SignedBeaconBlock as _},
 };
 use validator::{ApiToValidator, ValidatorBlindedBlock, ValidatorConfig, ValidatorProposerData};
 use zeroize::Zeroizing;
@@ -87,12 +91,13 @@ use zeroize::Zeroizing;
 use crate::{
     block_id,
     error::{Error, IndexedError},
-    events::{EventChannels, Topic},
+    events::{EventChannels, EventFilter, Topic},
     extractors::{EthJson, EthJsonOrSsz, EthPath, EthQuery},
     full_config::FullConfig,
-    misc::{APIBlock, BackSyncedStatus, SignedAPIBlock, SyncedStatus},
+    misc::{APIBlock, BackSyncedStatus, SignedAPIBlindedBlock, SignedAPIBlock, SyncedStatus},
     response::{EthResponse, JsonOrSsz},
     state_id::StateId,
+    trusted_client::TrustedClient,
     validator_status::{ValidatorId, ValidatorStatus},
 };
 
@@ -171,6 +176,16 @@ pub struct ValidatorBlockQuery {
     skip_randao_verification: bool,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorBlockQueryV3 {
+    randao_reveal: SignatureBytes,
+    graffiti: Option<H256>,
+    #[serde(default, with = "serde_utils::bool_as_empty_string")]
+    skip_randao_verification: bool,
+    builder_boost_factor: Option<u64>,
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct SyncCommitteeContributionQuery {
@@ -200,6 +215,33 @@ pub struct EventsQuery {
         deserialize_with = "serde_aux::field_attributes::deserialize_vec_from_string_or_vec"
     )]
     topics: Vec<Topic>,
+    #[serde(
+        default,
+        deserialize_with = "serde_aux::field_attributes::deserialize_vec_from_string_or_vec"
+    )]
+    proposer_indices: Vec<ValidatorIndex>,
+    #[serde(
+        default,
+        deserialize_with = "serde_aux::field_attributes::deserialize_vec_from_string_or_vec"
+    )]
+    committee_indices: Vec<CommitteeIndex>,
+    queue_limit: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastValidation {
+    #[default]
+    Gossip,
+    Consensus,
+    ConsensusAndEquivocation,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishBlockQuery {
+    #[serde(default)]
+    broadcast_validation: BroadcastValidation,
 }
 
 #[derive(Deserialize)]
@@ -222,6 +264,50 @@ pub struct RootResponse {
     root: H256,
 }
 
+#[derive(Serialize)]
+pub struct AttestationRewardsResponse {
+    ideal_rewards: Vec<IdealAttestationRewards>,
+    total_rewards: Vec<TotalAttestationRewards>,
+}
+
+#[derive(Serialize)]
+pub struct IdealAttestationRewards {
+    #[serde(with = "serde_utils::string_or_native")]
+    effective_balance: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    head: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    target: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    source: Gwei,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "As::<Option<DisplayFromStr>>"
+    )]
+    inclusion_delay: Option<Gwei>,
+    #[serde(with = "serde_utils::string_or_native")]
+    inactivity: i64,
+}
+
+#[derive(Serialize)]
+pub struct TotalAttestationRewards {
+    #[serde(with = "serde_utils::string_or_native")]
+    validator_index: ValidatorIndex,
+    #[serde(with = "serde_utils::string_or_native")]
+    head: i64,
+    #[serde(with = "serde_utils::string_or_native")]
+    target: i64,
+    #[serde(with = "serde_utils::string_or_native")]
+    source: i64,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "As::<Option<DisplayFromStr>>"
+    )]
+    inclusion_delay: Option<Gwei>,
+    #[serde(with = "serde_utils::string_or_native")]
+    inactivity: i64,
+}
+
 #[derive(Serialize)]
 pub struct BlockRewardsResponse {
     #[serde(with = "serde_utils::string_or_native")]
@@ -550,6 +636,8 @@ pub async fn state_validators<P: Preset, W: Wait>(
         finalized,
     } = state_id.state(&controller, genesis_provider)?;
 
+    let requested_indices = requested_validator_indices(&controller, &state, &query.id);
+
     let validators = izip!(
         0..,
         state.validators(),
@@ -558,11 +646,7 @@ pub async fn state_validators<P: Preset, W: Wait>(
     .filter(|(index, validator, _)| {
         let validator_status = ValidatorStatus::new(validator, &state);
 
-        let allowed_by_id = query.id.is_empty()
-            || query.id.iter().any(|validator_id| match validator_id {
-                ValidatorId::ValidatorIndex(validator_index) => index == validator_index,
-                ValidatorId::PublicKey(pubkey) => validator.pubkey.as_bytes() == pubkey,
-            });
+        let allowed_by_id = query.id.is_empty() || requested_indices.contains(index);
 
         let allowed_by_status = query.status.is_empty()
             || query
@@ -598,7 +682,7 @@ pub async fn state_validator<P: Preset, W: Wait>(
     } = state_id.state(&controller, genesis_provider)?;
 
     let validator_index = validator_id
-        .validator_index(&state)
+        .validator_index(&controller.validator_registry(), &state)
         .ok_or(Error::ValidatorNotFound)?;
 
     let validator = state
@@ -637,20 +721,13 @@ pub async fn state_validator_balances<P: Preset, W: Wait>(
         finalized,
     } = state_id.state(&controller, genesis_provider)?;
 
-    let balances = izip!(
-        0..,
-        state.validators(),
-        state.balances().into_iter().copied(),
-    )
-    .filter(|(index, validator, _)| {
-        query.id.is_empty()
-            || query.id.iter().any(|validator_id| match validator_id {
-                ValidatorId::ValidatorIndex(validator_index) => index == validator_index,
-                ValidatorId::PublicKey(pubkey) => validator.pubkey.as_bytes() == pubkey,
-            })
-    })
-    .map(|(index, _, balance)| StateValidatorBalanceResponse { index, balance })
-    .collect();
+    let requested_indices = requested_validator_indices(&controller, &state, &query.id);
+
+    let balances = (0..)
+        .zip(state.balances().into_iter().copied())
+        .filter(|(index, _)| query.id.is_empty() || requested_indices.contains(index))
+        .map(|(index, balance)| StateValidatorBalanceResponse { index, balance })
+        .collect();
 
     Ok(EthResponse::json(balances)
         .execution_optimistic(optimistic)
@@ -758,10 +835,12 @@ pub async fn state_sync_committees<P: Preset, W: Wait>(
         return Err(Error::EpochNotInSyncCommitteePeriod);
     };
 
+    let validator_registry = controller.validator_registry();
+
     let validator_indices = committee
         .pubkeys
         .iter()
-        .filter_map(|pubkey| accessors::index_of_public_key(state, pubkey.to_bytes()))
+        .filter_map(|pubkey| validator_registry.index_of_public_key(state, pubkey.to_bytes()))
         .collect_vec();
 
     let validators = validator_indices.clone();
@@ -921,7 +1000,7 @@ pub async fn block<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(genesis_provider): State<GenesisProvider<P>>,
     EthPath(block_id): EthPath<BlockId>,
-    headers: HeaderMap,
+    format: JsonOrSsz,
 ) -> Result<EthResponse<Arc<SignedBeaconBlock<P>>, (), JsonOrSsz>, Error> {
     let WithStatus {
         value: block,
@@ -931,7 +1010,44 @@ pub async fn block<P: Preset, W: Wait>(
 
     let version = block.phase();
 
-    Ok(EthResponse::json_or_ssz(block, &headers)
+    Ok(EthResponse::json_or_ssz(block, format)
+        .execution_optimistic(optimistic)
+        .finalized(finalized)
+        .version(version))
+}
+
+/// `GET /eth/v1/beacon/blinded_blocks/{block_id}`
+pub async fn blinded_block<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(block_id): EthPath<BlockId>,
+    format: JsonOrSsz,
+) -> Result<EthResponse<SignedAPIBlindedBlock<P>, (), JsonOrSsz>, Error> {
+    // Finalized blocks stored separately from their execution payloads are served as they are.
+    // They remain available after the payloads are pruned.
+    if let Some(block_root) = stored_block_root(block_id, &controller)? {
+        if let Some(block) = controller.stored_blinded_block_by_root(block_root)? {
+            let version = block.phase();
+
+            return Ok(EthResponse::json_or_ssz(
+                SignedAPIBlindedBlock::SignedBlindedBeaconBlock(block),
+                format,
+            )
+            .execution_optimistic(false)
+            .finalized(true)
+            .version(version));
+        }
+    }
+
+    let WithStatus {
+        value: block,
+        optimistic,
+        finalized,
+    } = block_id::block(block_id, &controller, &genesis_provider)?;
+
+    let version = block.phase();
+
+    Ok(EthResponse::json_or_ssz(block.into(), format)
         .execution_optimistic(optimistic)
         .finalized(finalized)
         .version(version))
@@ -983,7 +1099,7 @@ pub async fn blob_sidecars<P: Preset, W: Wait>(
     State(genesis_provider): State<GenesisProvider<P>>,
     EthPath(block_id): EthPath<BlockId>,
     EthQuery(query): EthQuery<BlobSidecarsQuery>,
-    headers: HeaderMap,
+    format: JsonOrSsz,
 ) -> Result<
     EthResponse<ContiguousList<Arc<BlobSidecar<P>>, P::MaxBlobsPerBlock>, (), JsonOrSsz>,
     Error,
@@ -1007,7 +1123,7 @@ pub async fn blob_sidecars<P: Preset, W: Wait>(
     let blob_sidecars =
         ContiguousList::try_from_iter(blob_sidecars.into_iter()).map_err(AnyhowError::new)?;
 
-    Ok(EthResponse::json_or_ssz(blob_sidecars, &headers))
+    Ok(EthResponse::json_or_ssz(blob_sidecars, format))
 }
 
 /// `POST /eth/v1/beacon/blocks`
@@ -1015,6 +1131,72 @@ pub async fn publish_block<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
     EthJsonOrSsz(signed_api_block): EthJsonOrSsz<Box<SignedAPIBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_api_block(
+        *signed_api_block,
+        BroadcastValidation::Gossip,
+        controller,
+        api_to_p2p_tx,
+    )
+    .await
+}
+
+/// `POST /eth/v2/beacon/blocks`
+pub async fn publish_block_v2<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    EthQuery(query): EthQuery<PublishBlockQuery>,
+    EthJsonOrSsz(signed_api_block): EthJsonOrSsz<Box<SignedAPIBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_api_block(
+        *signed_api_block,
+        query.broadcast_validation,
+        controller,
+        api_to_p2p_tx,
+    )
+    .await
+}
+
+/// `POST /eth/v1/beacon/blinded_blocks`
+pub async fn publish_blinded_block<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
+    EthJsonOrSsz(signed_blinded_block): EthJsonOrSsz<Box<SignedBlindedBeaconBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_signed_blinded_block(
+        signed_blinded_block,
+        BroadcastValidation::Gossip,
+        controller,
+        api_to_p2p_tx,
+        api_to_validator_tx,
+    )
+    .await
+}
+
+/// `POST /eth/v2/beacon/blinded_blocks`
+pub async fn publish_blinded_block_v2<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
+    EthQuery(query): EthQuery<PublishBlockQuery>,
+    EthJsonOrSsz(signed_blinded_block): EthJsonOrSsz<Box<SignedBlindedBeaconBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_signed_blinded_block(
+        signed_blinded_block,
+        query.broadcast_validation,
+        controller,
+        api_to_p2p_tx,
+        api_to_validator_tx,
+    )
+    .await
+}
+
+async fn publish_api_block<P: Preset, W: Wait>(
+    signed_api_block: SignedAPIBlock<P>,
+    broadcast_validation: BroadcastValidation,
+    controller: ApiController<P, W>,
+    api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
 ) -> Result<StatusCode, Error> {
     let (signed_beacon_block, proofs, blobs) = signed_api_block.split();
 
@@ -1024,18 +1206,19 @@ pub async fn publish_block<P: Preset, W: Wait>(
     publish_signed_block(
         Arc::new(signed_beacon_block),
         blob_sidecars,
+        broadcast_validation,
         controller,
         api_to_p2p_tx,
     )
     .await
 }
 
-/// `POST /eth/v1/beacon/blinded_blocks`
-pub async fn publish_blinded_block<P: Preset, W: Wait>(
-    State(controller): State<ApiController<P, W>>,
-    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
-    State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
-    EthJsonOrSsz(signed_blinded_block): EthJsonOrSsz<Box<SignedBlindedBeaconBlock<P>>>,
+async fn publish_signed_blinded_block<P: Preset, W: Wait>(
+    signed_blinded_block: Box<SignedBlindedBeaconBlock<P>>,
+    broadcast_validation: BroadcastValidation,
+    controller: ApiController<P, W>,
+    api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
 ) -> Result<StatusCode, Error> {
     let (message, signature) = signed_blinded_block.as_ref().clone().split();
     let (sender, receiver) = futures::channel::oneshot::channel();
@@ -1065,12 +1248,45 @@ pub async fn publish_blinded_block<P: Preset, W: Wait>(
     publish_signed_block(
         signed_beacon_block,
         blob_sidecars,
+        broadcast_validation,
         controller,
         api_to_p2p_tx,
     )
     .await
 }
 
+/// `POST /eth/v1/beacon/rewards/attestations/{epoch}`
+pub async fn attestation_rewards<P: Preset, W: Wait>(
+    State(chain_config): State<Arc<ChainConfig>>,
+    State(controller): State<ApiController<P, W>>,
+    EthPath(epoch): EthPath<Epoch>,
+    EthJson(validator_ids): EthJson<Vec<ValidatorId>>,
+) -> Result<EthResponse<AttestationRewardsResponse>, Error> {
+    // Rewards for attestations from `epoch` are applied at the end of the epoch after it.
+    let current_epoch = misc::compute_epoch_at_slot::<P>(controller.slot());
+
+    if epoch.saturating_add(1) >= current_epoch {
+        return Err(Error::EpochRewardsNotAvailable);
+    }
+
+    let last_slot = misc::compute_start_slot_at_epoch::<P>(epoch + 2) - 1;
+
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = controller
+        .state_at_slot(last_slot)?
+        .ok_or(Error::StateNotFound)?;
+
+    let rewards =
+        calculate_attestation_rewards(&chain_config, &controller, state, epoch, &validator_ids)?;
+
+    Ok(EthResponse::json(rewards)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
+
 /// `GET /eth/v1/beacon/rewards/blocks/{block_id}`
 pub async fn block_rewards<P: Preset, W: Wait>(
     State(chain_config): State<Arc<ChainConfig>>,
@@ -1124,13 +1340,15 @@ pub async fn sync_committee_rewards<P: Preset, W: Wait>(
     )?
     .sync_committee_deltas;
 
+    let validator_registry = controller.validator_registry();
+
     let response = if validator_ids.is_empty() {
         sync_committee_deltas.into_iter().pipe(Either::Left)
     } else {
         validator_ids
             .into_iter()
             .filter_map(|validator_id| {
-                let validator_index = validator_id.validator_index(&state)?;
+                let validator_index = validator_id.validator_index(&validator_registry, &state)?;
                 let delta = *sync_committee_deltas.get(&validator_index)?;
                 Some((validator_index, delta))
             })
@@ -1247,6 +1465,7 @@ pub async fn pool_attester_slashings<P: Preset>(
 pub async fn submit_pool_attestations<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    TrustedClient(trusted): TrustedClient,
     EthJson(attestations): EthJson<Vec<Arc<Attestation<P>>>>,
 ) -> Result<(), Error> {
     let grouped_by_target = attestations
@@ -1291,6 +1510,7 @@ pub async fn submit_pool_attestations<P: Preset, W: Wait>(
                     index,
                     attestation,
                     target_state.clone(),
+                    trusted,
                 )
             })
         })
@@ -1489,7 +1709,7 @@ pub async fn beacon_state<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(genesis_provider): State<GenesisProvider<P>>,
     EthPath(state_id): EthPath<StateId>,
-    headers: HeaderMap,
+    format: JsonOrSsz,
 ) -> Result<EthResponse<Arc<BeaconState<P>>, (), JsonOrSsz>, Error> {
     let WithStatus {
         value: state,
@@ -1499,7 +1719,7 @@ pub async fn beacon_state<P: Preset, W: Wait>(
 
     let version = state.phase();
 
-    Ok(EthResponse::json_or_ssz(state, &headers)
+    Ok(EthResponse::json_or_ssz(state, format)
         .execution_optimistic(optimistic)
         .finalized(finalized)
         .version(version))
@@ -1515,19 +1735,33 @@ pub async fn beacon_heads<P: Preset, W: Wait>(
 /// `GET /eth/v1/events`
 pub async fn beacon_events(
     State(event_channels): State<Arc<EventChannels>>,
+    State(metrics): State<Option<Arc<Metrics>>>,
     EthQuery(events): EthQuery<EventsQuery>,
-) -> Result<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>, Error> {
-    let EventsQuery { topics } = events;
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Error> {
+    let EventsQuery {
+        topics,
+        proposer_indices,
+        committee_indices,
+        queue_limit,
+    } = events;
 
     if topics.is_empty() {
         return Err(Error::EventTopicsEmpty);
     }
 
+    let filter = Arc::new(EventFilter {
+        proposer_indices: proposer_indices.into_iter().collect(),
+        committee_indices: committee_indices.into_iter().collect(),
+    });
+
     topics
         .into_iter()
-        .map(|topic| event_channels.receiver_for(topic))
-        .map(BroadcastStream::new)
+        .map(|topic| {
+            event_channels.stream_for(topic, filter.clone_arc(), queue_limit, metrics.clone())
+        })
+        .map(Box::pin)
         .pipe(futures::stream::select_all)
+        .map(Ok)
         .pipe(Sse::new)
         .keep_alive(KeepAlive::default())
         .pipe(Ok)
@@ -1662,15 +1896,27 @@ pub async fn validator_attester_duties<P: Preset, W: Wait>(
         .into_iter()
         .collect::<HashSet<ValidatorIndex>>();
 
-    let committees_at_slot = accessors::get_committee_count_per_slot(&state, relative_epoch);
+    let response =
+        attester_duties(&state, epoch, relative_epoch, &indices).map_err(Error::Internal)?;
+
+    Ok(EthResponse::json(response)
+        .dependent_root(dependent_root)
+        .execution_optimistic(optimistic))
+}
 
-    let response = misc::slots_in_epoch::<P>(epoch)
+pub fn attester_duties<P: Preset>(
+    state: &BeaconState<P>,
+    epoch: Epoch,
+    relative_epoch: RelativeEpoch,
+    indices: &HashSet<ValidatorIndex>,
+) -> Result<Vec<ValidatorAttesterDutyResponse>> {
+    let committees_at_slot = accessors::get_committee_count_per_slot(state, relative_epoch);
+
+    misc::slots_in_epoch::<P>(epoch)
         .map(|slot| {
-            accessors::beacon_committees(&state, slot)?
+            accessors::beacon_committees(state, slot)?
                 .zip(0..)
                 .flat_map(|(committee, committee_index)| {
-                    let state = &state;
-
                     committee
                         .into_iter()
                         .enumerate()
@@ -1693,31 +1939,56 @@ pub async fn validator_attester_duties<P: Preset, W: Wait>(
         })
         .flatten_ok()
         .try_collect()
-        .map_err(Error::Internal)?;
-
-    Ok(EthResponse::json(response)
-        .dependent_root(dependent_root)
-        .execution_optimistic(optimistic))
 }
 
 /// `GET /eth/v1/validator/duties/proposer/{epoch}`
+///
+/// Duties for the next epoch are predicted from the head and are prewarmed at the end of the
+/// current epoch. Clients must refetch them if the `dependent_root` in the response changes.
 pub async fn validator_proposer_duties<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     EthPath(epoch): EthPath<Epoch>,
 ) -> Result<EthResponse<Vec<ValidatorProposerDutyResponse>>, Error> {
     let WithStatus {
-        value: state,
+        value: proposer_duties,
         optimistic,
         // `duties` responses are not supposed to contain a `finalized` field.
         finalized: _,
-    } = controller.preprocessed_state_at_epoch(epoch)?;
+    } = controller.proposer_duties(epoch)?;
 
-    let dependent_root = controller.dependent_root(&state, epoch)?;
+    let response = proposer_duties
+        .duties
+        .iter()
+        .map(|duty| ValidatorProposerDutyResponse {
+            pubkey: duty.pubkey,
+            validator_index: duty.validator_index,
+            slot: duty.slot,
+        })
+        .collect();
 
-    let response = misc::slots_in_epoch::<P>(epoch)
+    Ok(EthResponse::json(response)
+        .dependent_root(proposer_duties.dependent_root)
+        .execution_optimistic(optimistic))
+}
+
+/// Computes proposer duties of `indices` directly from `state`.
+///
+/// `state` must be in `epoch`. Unlike `validator_proposer_duties`, this bypasses the proposer
+/// duties cache, which only stores duties of all validators.
+pub fn proposer_duties<P: Preset>(
+    state: &BeaconState<P>,
+    epoch: Epoch,
+    indices: &HashSet<ValidatorIndex>,
+) -> Result<Vec<ValidatorProposerDutyResponse>> {
+    misc::slots_in_epoch::<P>(epoch)
         .map(|slot| {
-            let validator_index = accessors::get_beacon_proposer_index_at_slot(&state, slot)?;
-            let pubkey = accessors::public_key(&state, validator_index)?.to_bytes();
+            let validator_index = accessors::get_beacon_proposer_index_at_slot(state, slot)?;
+            Ok((slot, validator_index))
+        })
+        .filter_ok(|(_, validator_index)| indices.contains(validator_index))
+        .map(|result| {
+            let (slot, validator_index) = result?;
+            let pubkey = accessors::public_key(state, validator_index)?.to_bytes();
 
             Ok(ValidatorProposerDutyResponse {
                 pubkey,
@@ -1725,12 +1996,7 @@ pub async fn validator_proposer_duties<P: Preset, W: Wait>(
                 slot,
             })
         })
-        .try_collect()
-        .map_err(Error::Internal)?;
-
-    Ok(EthResponse::json(response)
-        .dependent_root(dependent_root)
-        .execution_optimistic(optimistic))
+        .collect()
 }
 
 // TODO(Grandine Team): This returns incorrect duties if called before Altair.
@@ -1759,6 +2025,16 @@ pub async fn validator_sync_committee_duties<P: Preset, W: Wait>(
         return Ok(EthResponse::json(vec![]).execution_optimistic(optimistic));
     };
 
+    let duties = sync_committee_duties(state, epoch, validator_indices)?;
+
+    Ok(EthResponse::json(duties).execution_optimistic(optimistic))
+}
+
+pub fn sync_committee_duties<P: Preset>(
+    state: &dyn PostAltairBeaconState<P>,
+    epoch: Epoch,
+    validator_indices: impl IntoIterator<Item = ValidatorIndex>,
+) -> Result<Vec<ValidatorSyncDutyResponse>, Error> {
     let requested_period = misc::sync_committee_period::<P>(epoch);
     let state_epoch = misc::compute_epoch_at_slot::<P>(state.slot());
     let state_period = misc::sync_committee_period::<P>(state_epoch);
@@ -1771,7 +2047,7 @@ pub async fn validator_sync_committee_duties<P: Preset, W: Wait>(
         return Err(Error::EpochNotInSyncCommitteePeriod);
     };
 
-    let duties = validator_indices
+    validator_indices
         .into_iter()
         .map(|validator_index| {
             let validator_pubkey = accessors::public_key(state, validator_index)?;
@@ -1794,9 +2070,8 @@ pub async fn validator_sync_committee_duties<P: Preset, W: Wait>(
             }))
         })
         .filter_map(Result::transpose)
-        .collect::<Result<_>>()?;
-
-    Ok(EthResponse::json(duties).execution_optimistic(optimistic))
+        .collect::<Result<_>>()
+        .map_err(Error::Internal)
 }
 
 /// `GET /eth/v1/validator/aggregate_attestation`
@@ -1844,6 +2119,8 @@ pub async fn validator_blinded_block<P: Preset>(
         randao_reveal,
         slot,
         skip_randao_verification,
+        None,
+        request_tracing::current(),
     )
     .send(&api_to_validator_tx);
 
@@ -1867,7 +2144,7 @@ pub async fn validator_block<P: Preset>(
     State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
     EthPath(slot): EthPath<Slot>,
     EthQuery(query): EthQuery<ValidatorBlockQuery>,
-    headers: HeaderMap,
+    format: JsonOrSsz,
 ) -> Result<EthResponse<APIBlock<BeaconBlock<P>, P>, (), JsonOrSsz>, Error> {
     let ValidatorBlockQuery {
         randao_reveal,
@@ -1888,13 +2165,14 @@ pub async fn validator_block<P: Preset>(
         randao_reveal,
         slot,
         skip_randao_verification,
+        request_tracing::current(),
     )
     .send(&api_to_validator_tx);
 
     let beacon_block = receiver.await??.ok_or(Error::UnableToProduceBeaconBlock)?;
     let version = beacon_block.value.phase();
 
-    Ok(EthResponse::json_or_ssz(beacon_block.into(), &headers).version(version))
+    Ok(EthResponse::json_or_ssz(beacon_block.into(), format).version(version))
 }
 
 /// `GET /eth/v3/validator/blocks/{slot}`
@@ -1903,13 +2181,14 @@ pub async fn validator_block_v3<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
     EthPath(slot): EthPath<Slot>,
-    EthQuery(query): EthQuery<ValidatorBlockQuery>,
-    headers: HeaderMap,
+    EthQuery(query): EthQuery<ValidatorBlockQueryV3>,
+    format: JsonOrSsz,
 ) -> Result<EthResponse<APIBlock<ValidatorBlindedBlock<P>, P>, (), JsonOrSsz>, Error> {
-    let ValidatorBlockQuery {
+    let ValidatorBlockQueryV3 {
         randao_reveal,
         graffiti,
         skip_randao_verification,
+        builder_boost_factor,
     } = query;
 
     if skip_randao_verification && !randao_reveal.is_empty() {
@@ -1925,6 +2204,8 @@ pub async fn validator_block_v3<P: Preset, W: Wait>(
         randao_reveal,
         slot,
         skip_randao_verification,
+        builder_boost_factor,
+        request_tracing::current(),
     )
     .send(&api_to_validator_tx);
 
@@ -1946,7 +2227,7 @@ pub async fn validator_block_v3<P: Preset, W: Wait>(
     let rewards =
         calculate_block_rewards(&chain_config, &controller, &Arc::new(signed_beacon_block))?;
 
-    Ok(EthResponse::json_or_ssz(validator_block.into(), &headers)
+    Ok(EthResponse::json_or_ssz(validator_block.into(), format)
         .version(version)
         .consensus_block_value(Wei::from_u64(rewards.total))
         .execution_payload_blinded(blinded)
@@ -2529,25 +2810,205 @@ pub fn calculate_block_rewards<P: Preset, W: Wait>(
     })
 }
 
+#[allow(clippy::too_many_lines)]
+fn calculate_attestation_rewards<P: Preset, W: Wait>(
+    chain_config: &ChainConfig,
+    controller: &ApiController<P, W>,
+    mut state: Arc<BeaconState<P>>,
+    epoch: Epoch,
+    validator_ids: &[ValidatorId],
+) -> Result<AttestationRewardsResponse> {
+    let total_active_balance = accessors::total_active_balance(&*state);
+    let requested_indices = requested_validator_indices(controller, &state, validator_ids);
+    let report = transition_functions::combined::epoch_report(chain_config, state.make_mut())?;
+
+    // The leak is determined after justification and finalization, which `state` now reflects.
+    // Its slot has been advanced past the epoch being processed, so the predicate cannot be used.
+    let finality_delay = epoch - state.finalized_checkpoint().epoch;
+    let in_inactivity_leak = finality_delay > P::MIN_EPOCHS_TO_INACTIVITY_PENALTY;
+
+    let increment = P::EFFECTIVE_BALANCE_INCREMENT.get();
+    let effective_balances = (1..=P::MAX_EFFECTIVE_BALANCE / increment).map(|n| n * increment);
+
+    // An empty list of validators means all of them, including ones with no rewards or penalties.
+    let is_requested = |validator_index: &ValidatorIndex| {
+        validator_ids.is_empty() || requested_indices.contains(validator_index)
+    };
+
+    let signed_delta = |reward: Gwei, penalty: Gwei| -> Result<i64> {
+        Ok(i64::try_from(reward)? - i64::try_from(penalty)?)
+    };
+
+    let (ideal_rewards, total_rewards) = match report {
+        EpochReport::Phase0(Phase0EpochReport {
+            statistics,
+            epoch_deltas,
+            ..
+        }) => {
+            let ideal_rewards = effective_balances
+                .map(|effective_balance| {
+                    let deltas = transition_functions::phase0::ideal_epoch_deltas::<P>(
+                        statistics,
+                        in_inactivity_leak,
+                        effective_balance,
+                    );
+
+                    Ok(IdealAttestationRewards {
+                        effective_balance,
+                        head: deltas.head_reward,
+                        target: deltas.target_reward,
+                        source: deltas.source_reward,
+                        inclusion_delay: Some(deltas.inclusion_delay_reward),
+                        inactivity: signed_delta(0, deltas.canceling_penalty)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            let total_rewards = (0..)
+                .zip(epoch_deltas)
+                .filter(|(validator_index, _)| is_requested(validator_index))
+                .map(|(validator_index, deltas)| {
+                    Ok(TotalAttestationRewards {
+                        validator_index,
+                        head: signed_delta(deltas.head_reward, deltas.head_penalty)?,
+                        target: signed_delta(deltas.target_reward, deltas.target_penalty)?,
+                        source: signed_delta(deltas.source_reward, deltas.source_penalty)?,
+                        inclusion_delay: Some(deltas.inclusion_delay_reward),
+                        inactivity: signed_delta(
+                            0,
+                            deltas.canceling_penalty + deltas.inactivity_penalty,
+                        )?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            (ideal_rewards, total_rewards)
+        }
+        EpochReport::PostAltair(AltairEpochReport {
+            statistics,
+            epoch_deltas,
+            ..
+        }) => {
+            let ideal_rewards = effective_balances
+                .map(|effective_balance| {
+                    let deltas = transition_functions::altair::ideal_epoch_deltas::<P>(
+                        statistics,
+                        total_active_balance,
+                        in_inactivity_leak,
+                        effective_balance,
+                    );
+
+                    IdealAttestationRewards {
+                        effective_balance,
+                        head: deltas.head_reward,
+                        target: deltas.target_reward,
+                        source: deltas.source_reward,
+                        inclusion_delay: None,
+                        inactivity: 0,
+                    }
+                })
+                .collect();
+
+            let total_rewards = (0..)
+                .zip(epoch_deltas)
+                .filter(|(validator_index, _)| is_requested(validator_index))
+                .map(|(validator_index, deltas)| {
+                    Ok(TotalAttestationRewards {
+                        validator_index,
+                        head: signed_delta(deltas.head_reward, 0)?,
+                        target: signed_delta(deltas.target_reward, deltas.target_penalty)?,
+                        source: signed_delta(deltas.source_reward, deltas.source_penalty)?,
+                        inclusion_delay: None,
+                        inactivity: signed_delta(0, deltas.inactivity_penalty)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            (ideal_rewards, total_rewards)
+        }
+    };
+
+    Ok(AttestationRewardsResponse {
+        ideal_rewards,
+        total_rewards,
+    })
+}
+
+// Resolving public keys up front avoids comparing every requested key with every validator.
+fn requested_validator_indices<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    state: &BeaconState<P>,
+    validator_ids: &[ValidatorId],
+) -> HashSet<ValidatorIndex> {
+    if validator_ids.is_empty() {
+        return HashSet::new();
+    }
+
+    let validator_registry = controller.validator_registry();
+
+    validator_ids
+        .iter()
+        .filter_map(|validator_id| validator_id.validator_index(&validator_registry, state))
+        .collect()
+}
+
+// Blocks identified by `head`, `genesis` or `finalized` are always available whole,
+// so only ones identified by root or slot may be stored blinded.
+fn stored_block_root<P: Preset, W: Wait>(
+    block_id: BlockId,
+    controller: &ApiController<P, W>,
+) -> Result<Option<H256>, Error> {
+    let block_root = match block_id {
+        BlockId::Root(block_root) => Some(block_root),
+        BlockId::Slot(slot) => controller.block_root_by_slot(slot)?,
+        BlockId::Head | BlockId::Genesis | BlockId::Finalized => None,
+    };
+
+    Ok(block_root)
+}
+
 async fn publish_signed_block<P: Preset, W: Wait>(
     block: Arc<SignedBeaconBlock<P>>,
     blob_sidecars: Vec<BlobSidecar<P>>,
+    broadcast_validation: BroadcastValidation,
     controller: ApiController<P, W>,
     api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
 ) -> Result<StatusCode, Error> {
-    for blob_sidecar in blob_sidecars {
-        let blob_sidecar = Arc::new(blob_sidecar);
+    if broadcast_validation == BroadcastValidation::ConsensusAndEquivocation {
+        return Err(Error::BroadcastValidationNotSupported);
+    }
+
+    let blob_sidecars = blob_sidecars.into_iter().map(Arc::new).collect_vec();
+
+    // Blocks cannot be validated until their blob sidecars are available.
+    for blob_sidecar in &blob_sidecars {
         controller.on_api_blob_sidecar(blob_sidecar.clone_arc());
-        ApiToP2p::PublishBlobSidecar(blob_sidecar).send(&api_to_p2p_tx);
     }
 
-    ApiToP2p::PublishBeaconBlock(block.clone_arc()).send(&api_to_p2p_tx);
+    let validate_before_broadcast = broadcast_validation == BroadcastValidation::Consensus;
+
+    if !validate_before_broadcast {
+        broadcast_block(&block, blob_sidecars.iter(), &api_to_p2p_tx);
+    }
 
     let (sender, mut receiver) = futures::channel::mpsc::channel(1);
 
     controller.on_api_block(block.clone_arc(), sender);
 
-    let status_code = match receiver.next().await.transpose() {
+    let outcome = receiver.next().await.transpose();
+
+    if validate_before_broadcast {
+        match outcome {
+            Ok(Some(ValidationOutcome::Accept)) => {
+                broadcast_block(&block, blob_sidecars.iter(), &api_to_p2p_tx);
+            }
+            Err(error) => return Err(Error::InvalidBlock(error)),
+            // Ignored blocks are already known and have been broadcast by whoever sent them.
+            Ok(Some(ValidationOutcome::Ignore) | None) => {}
+        }
+    }
+
+    let status_code = match outcome {
         Ok(Some(ValidationOutcome::Accept)) => StatusCode::OK,
         Ok(Some(ValidationOutcome::Ignore)) => {
             // We log only the root with `info!` because this is not an exceptional case.
@@ -2570,11 +3031,24 @@ async fn publish_signed_block<P: Preset, W: Wait>(
     Ok(status_code)
 }
 
+fn broadcast_block<'sidecars, P: Preset>(
+    block: &Arc<SignedBeaconBlock<P>>,
+    blob_sidecars: impl Iterator<Item = &'sidecars Arc<BlobSidecar<P>>>,
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+) {
+    for blob_sidecar in blob_sidecars {
+        ApiToP2p::PublishBlobSidecar(blob_sidecar.clone_arc()).send(api_to_p2p_tx);
+    }
+
+    ApiToP2p::PublishBeaconBlock(block.clone_arc()).send(api_to_p2p_tx);
+}
+
 async fn submit_attestation_to_pool<P: Preset, W: Wait>(
     controller: ApiController<P, W>,
     index: usize,
     attestation: Arc<Attestation<P>>,
     target_state: Option<Arc<BeaconState<P>>>,
+    trusted: bool,
 ) -> Result<(Arc<Attestation<P>>, SubnetId, ValidationOutcome), IndexedError> {
     let run = async {
         let AttestationData {
@@ -2603,7 +3077,15 @@ async fn submit_attestation_to_pool<P: Preset, W: Wait>(
 
         let (sender, receiver) = futures::channel::oneshot::channel();
 
-        controller.on_api_singular_attestation(attestation.clone_arc(), subnet_id, sender);
+        if trusted {
+            controller.on_trusted_api_singular_attestation(
+                attestation.clone_arc(),
+                subnet_id,
+                sender,
+            );
+        } else {
+            controller.on_api_singular_attestation(attestation.clone_arc(), subnet_id, sender);
+        }
 
         let validation_outcome = receiver.await??;
 
@@ -2627,7 +3109,7 @@ mod tests {
     use serde::de::DeserializeOwned;
     use serde_json::json;
     use ssz::BitList;
-    use types::preset::Mainnet;
+    use types::{phase0::consts::FAR_FUTURE_EPOCH, preset::Mainnet};
 
     use super::*;
 
@@ -2738,6 +3220,85 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn fork_schedule_starts_with_genesis_fork() -> Result<()> {
+        let response = fork_schedule::<Mainnet>(State(Arc::new(ChainConfig::mainnet()))).await;
+
+        assert_eq!(
+            serde_json::to_value(response)?,
+            json!({
+                "data": [
+                    {
+                        "previous_version": "0x00000000",
+                        "current_version": "0x00000000",
+                        "epoch": "0",
+                    },
+                    {
+                        "previous_version": "0x00000000",
+                        "current_version": "0x01000000",
+                        "epoch": "74240",
+                    },
+                    {
+                        "previous_version": "0x01000000",
+                        "current_version": "0x02000000",
+                        "epoch": "144896",
+                    },
+                    {
+                        "previous_version": "0x02000000",
+                        "current_version": "0x03000000",
+                        "epoch": "194048",
+                    },
+                    {
+                        "previous_version": "0x03000000",
+                        "current_version": "0x04000000",
+                        "epoch": "269568",
+                    },
+                ],
+            }),
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fork_schedule_omits_unscheduled_forks() -> Result<()> {
+        let chain_config = ChainConfig {
+            bellatrix_fork_epoch: FAR_FUTURE_EPOCH,
+            capella_fork_epoch: FAR_FUTURE_EPOCH,
+            deneb_fork_epoch: FAR_FUTURE_EPOCH,
+            ..ChainConfig::mainnet()
+        };
+
+        let response = fork_schedule::<Mainnet>(State(Arc::new(chain_config))).await;
+        let epochs = serde_json::to_value(response)?["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|fork| fork["epoch"].clone())
+            .collect_vec();
+
+        assert_eq!(epochs, [json!("0"), json!("74240")]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deposit_contract_returns_chain_id_as_string() -> Result<()> {
+        let response = deposit_contract(State(Arc::new(ChainConfig::mainnet()))).await;
+
+        assert_eq!(
+            serde_json::to_value(response)?,
+            json!({
+                "data": {
+                    "address": "0x00000000219ab540356cbb839cbe05303d7705fa",
+                    "chain_id": "1",
+                },
+            }),
+        );
+
+        Ok(())
+    }
+
     async fn extract_query<T: DeserializeOwned + 'static>(query: impl Display + Send) -> Result<T> {
         Request::get(format!("/?{query}"))
             .body(())?
@@ -0,0 +1,281 @@
+use anyhow::Result;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use genesis::GenesisProvider;
+use itertools::{EitherOrBoth, Itertools as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use serde_with::{As, DisplayFromStr};
+use ssz::SszHash as _;
+use types::{
+    combined::BeaconState,
+    nonstandard::WithStatus,
+    phase0::primitives::{Slot, H256},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+use crate::{error::Error, state_id::StateId};
+
+// Mainnet states contain lists with around a million elements.
+// Reporting every differing element would produce unusably large responses.
+const DEFAULT_MAX_ELEMENT_DIFFERENCES: usize = 64;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateDiffQuery {
+    #[serde(with = "As::<DisplayFromStr>")]
+    from: StateId,
+    #[serde(with = "As::<DisplayFromStr>")]
+    to: StateId,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct StateDiff {
+    from_slot: Slot,
+    to_slot: Slot,
+    from_state_root: H256,
+    to_state_root: H256,
+    fields: Vec<FieldDiff>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize)]
+struct FieldDiff {
+    field: String,
+    #[serde(flatten)]
+    difference: Difference,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Difference {
+    // Fields missing from one of the states are represented by `null`.
+    Value {
+        from: Value,
+        to: Value,
+    },
+    List {
+        from_length: usize,
+        to_length: usize,
+        differing_elements: usize,
+        elements: Vec<ElementDiff>,
+    },
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize)]
+struct ElementDiff {
+    index: usize,
+    from: Option<Value>,
+    to: Option<Value>,
+}
+
+/// `GET /grandine/v1/debug/state_diff`
+pub async fn get_state_diff<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    genesis_provider: GenesisProvider<P>,
+    query: StateDiffQuery,
+) -> Result<StateDiff, Error> {
+    let StateDiffQuery { from, to, limit } = query;
+
+    let WithStatus { value: from, .. } = from.state(controller, genesis_provider.clone())?;
+    let WithStatus { value: to, .. } = to.state(controller, genesis_provider)?;
+
+    let max_element_differences = limit.unwrap_or(DEFAULT_MAX_ELEMENT_DIFFERENCES);
+
+    // Serializing and comparing mainnet states takes long enough to stall other requests.
+    tokio::task::spawn_blocking(move || diff_states(&from, &to, max_element_differences))
+        .await?
+        .map_err(Error::Internal)
+}
+
+fn diff_states<P: Preset>(
+    from: &BeaconState<P>,
+    to: &BeaconState<P>,
+    max_element_differences: usize,
+) -> Result<StateDiff> {
+    let from_value = serde_json::to_value(from)?;
+    let to_value = serde_json::to_value(to)?;
+
+    let fields = match (&from_value, &to_value) {
+        (Value::Object(from_fields), Value::Object(to_fields)) => {
+            diff_fields(from_fields, to_fields, max_element_differences)
+        }
+        _ => diff_values(&from_value, &to_value, max_element_differences)
+            .map(|difference| FieldDiff {
+                field: String::new(),
+                difference,
+            })
+            .into_iter()
+            .collect(),
+    };
+
+    Ok(StateDiff {
+        from_slot: from.slot(),
+        to_slot: to.slot(),
+        from_state_root: from.hash_tree_root(),
+        to_state_root: to.hash_tree_root(),
+        fields,
+    })
+}
+
+// States from different phases have different sets of fields.
+// Fields are reported in the order they appear in `from`, followed by fields only present in `to`.
+fn diff_fields(
+    from_fields: &Map<String, Value>,
+    to_fields: &Map<String, Value>,
+    max_element_differences: usize,
+) -> Vec<FieldDiff> {
+    let common_and_removed = from_fields.iter().map(|(field, from)| {
+        let to = to_fields.get(field).unwrap_or(&Value::Null);
+        (field, from, to)
+    });
+
+    let added = to_fields
+        .iter()
+        .filter(|(field, _)| !from_fields.contains_key(*field))
+        .map(|(field, to)| (field, &Value::Null, to));
+
+    common_and_removed
+        .chain(added)
+        .filter_map(|(field, from, to)| {
+            let difference = diff_values(from, to, max_element_differences)?;
+
+            Some(FieldDiff {
+                field: field.clone(),
+                difference,
+            })
+        })
+        .collect()
+}
+
+// Only differing values are cloned. Unchanged lists in mainnet states take up hundreds of megabytes.
+fn diff_values(from: &Value, to: &Value, max_element_differences: usize) -> Option<Difference> {
+    if from == to {
+        return None;
+    }
+
+    let difference = match (from, to) {
+        (Value::Array(from), Value::Array(to)) => {
+            let mut differing_elements = 0;
+            let mut elements = vec![];
+
+            for (index, pair) in from.iter().zip_longest(to).enumerate() {
+                let (from, to) = match pair {
+                    EitherOrBoth::Both(from, to) if from == to => continue,
+                    EitherOrBoth::Both(from, to) => (Some(from), Some(to)),
+                    EitherOrBoth::Left(from) => (Some(from), None),
+                    EitherOrBoth::Right(to) => (None, Some(to)),
+                };
+
+                differing_elements += 1;
+
+                if elements.len() < max_element_differences {
+                    elements.push(ElementDiff {
+                        index,
+                        from: from.cloned(),
+                        to: to.cloned(),
+                    });
+                }
+            }
+
+            Difference::List {
+                from_length: from.len(),
+                to_length: to.len(),
+                differing_elements,
+                elements,
+            }
+        }
+        _ => Difference::Value {
+            from: from.clone(),
+            to: to.clone(),
+        },
+    };
+
+    Some(difference)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fields(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(fields) => fields,
+            _ => unreachable!("value in test should be an object"),
+        }
+    }
+
+    #[test]
+    fn identical_fields_are_omitted() {
+        let from = fields(json!({ "slot": "1", "balances": ["32", "32"] }));
+        let to = from.clone();
+
+        assert!(diff_fields(&from, &to, DEFAULT_MAX_ELEMENT_DIFFERENCES).is_empty());
+    }
+
+    #[test]
+    fn scalar_and_missing_fields_are_reported_as_values() {
+        let from = fields(json!({ "slot": "1", "previous_epoch_attestations": [] }));
+        let to = fields(json!({ "slot": "2", "inactivity_scores": [] }));
+
+        assert_eq!(
+            diff_fields(&from, &to, DEFAULT_MAX_ELEMENT_DIFFERENCES),
+            [
+                FieldDiff {
+                    field: "slot".to_owned(),
+                    difference: Difference::Value {
+                        from: json!("1"),
+                        to: json!("2"),
+                    },
+                },
+                FieldDiff {
+                    field: "previous_epoch_attestations".to_owned(),
+                    difference: Difference::Value {
+                        from: json!([]),
+                        to: Value::Null,
+                    },
+                },
+                FieldDiff {
+                    field: "inactivity_scores".to_owned(),
+                    difference: Difference::Value {
+                        from: Value::Null,
+                        to: json!([]),
+                    },
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn list_differences_are_bounded() {
+        let from = fields(json!({ "balances": ["1", "2", "3", "4"] }));
+        let to = fields(json!({ "balances": ["1", "5", "6", "4", "7"] }));
+
+        assert_eq!(
+            diff_fields(&from, &to, 2),
+            [FieldDiff {
+                field: "balances".to_owned(),
+                difference: Difference::List {
+                    from_length: 4,
+                    to_length: 5,
+                    differing_elements: 3,
+                    elements: vec![
+                        ElementDiff {
+                            index: 1,
+                            from: Some(json!("2")),
+                            to: Some(json!("5")),
+                        },
+                        ElementDiff {
+                            index: 2,
+                            from: Some(json!("3")),
+                            to: Some(json!("6")),
+                        },
+                    ],
+                },
+            }],
+        );
+    }
+}
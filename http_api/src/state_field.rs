@@ -0,0 +1,328 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use enum_iterator::Sequence;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use genesis::GenesisProvider;
+use serde::Serialize;
+use ssz::{BitVector, Hc, Size, SszSize, SszWrite, WriteError};
+use std_ext::ArcExt as _;
+use strum::{Display, EnumString};
+use types::{
+    altair::containers::SyncCommittee,
+    bellatrix::containers::ExecutionPayloadHeader as BellatrixExecutionPayloadHeader,
+    capella::containers::ExecutionPayloadHeader as CapellaExecutionPayloadHeader,
+    collections::{
+        Attestations, Balances, EpochParticipation, Eth1DataVotes, HistoricalRoots,
+        HistoricalSummaries, InactivityScores, RandaoMixes, RecentRoots, Slashings, Validators,
+    },
+    combined::BeaconState,
+    deneb::containers::ExecutionPayloadHeader as DenebExecutionPayloadHeader,
+    nonstandard::WithStatus,
+    phase0::{
+        consts::JustificationBitsLength,
+        containers::{BeaconBlockHeader, Checkpoint, Eth1Data, Fork},
+        primitives::H256,
+    },
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+use crate::{
+    error::Error,
+    extractors::EthPath,
+    response::{EthResponse, JsonOrSsz},
+    state_id::StateId,
+};
+
+/// Top-level fields of `BeaconState` in all phases.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString, Sequence)]
+#[strum(serialize_all = "snake_case")]
+pub enum StateField {
+    GenesisTime,
+    GenesisValidatorsRoot,
+    Slot,
+    Fork,
+    LatestBlockHeader,
+    BlockRoots,
+    StateRoots,
+    HistoricalRoots,
+    Eth1Data,
+    Eth1DataVotes,
+    Eth1DepositIndex,
+    Validators,
+    Balances,
+    RandaoMixes,
+    Slashings,
+    PreviousEpochAttestations,
+    CurrentEpochAttestations,
+    PreviousEpochParticipation,
+    CurrentEpochParticipation,
+    JustificationBits,
+    PreviousJustifiedCheckpoint,
+    CurrentJustifiedCheckpoint,
+    FinalizedCheckpoint,
+    InactivityScores,
+    CurrentSyncCommittee,
+    NextSyncCommittee,
+    LatestExecutionPayloadHeader,
+    NextWithdrawalIndex,
+    NextWithdrawalValidatorIndex,
+    HistoricalSummaries,
+}
+
+// Collections in `BeaconState` are persistent, so cloning them out of the state is cheap.
+// The variants are serialized the same way as the corresponding fields of `BeaconState`.
+#[derive(Serialize)]
+#[serde(bound = "", untagged)]
+pub enum StateFieldValue<P: Preset> {
+    Uint64(#[serde(with = "serde_utils::string_or_native")] u64),
+    Root(H256),
+    Fork(Fork),
+    BlockHeader(BeaconBlockHeader),
+    RecentRoots(RecentRoots<P>),
+    HistoricalRoots(HistoricalRoots<P>),
+    Eth1Data(Eth1Data),
+    Eth1DataVotes(Eth1DataVotes<P>),
+    Validators(Validators<P>),
+    Balances(#[serde(with = "serde_utils::string_or_native_sequence")] Balances<P>),
+    RandaoMixes(RandaoMixes<P>),
+    Slashings(#[serde(with = "serde_utils::string_or_native_sequence")] Slashings<P>),
+    PendingAttestations(Attestations<P>),
+    EpochParticipation(
+        #[serde(with = "serde_utils::string_or_native_sequence")] EpochParticipation<P>,
+    ),
+    JustificationBits(BitVector<JustificationBitsLength>),
+    Checkpoint(Checkpoint),
+    InactivityScores(#[serde(with = "serde_utils::string_or_native_sequence")] InactivityScores<P>),
+    SyncCommittee(Arc<Hc<SyncCommittee<P>>>),
+    BellatrixExecutionPayloadHeader(Box<BellatrixExecutionPayloadHeader<P>>),
+    CapellaExecutionPayloadHeader(Box<CapellaExecutionPayloadHeader<P>>),
+    DenebExecutionPayloadHeader(Box<DenebExecutionPayloadHeader<P>>),
+    HistoricalSummaries(HistoricalSummaries<P>),
+}
+
+impl<P: Preset> SszSize for StateFieldValue<P> {
+    const SIZE: Size = Size::Variable { minimum_size: 0 };
+}
+
+// A field is encoded on its own rather than as part of a container,
+// so fixed-size fields are written without offsets the same way variable-size ones are.
+impl<P: Preset> SszWrite for StateFieldValue<P> {
+    fn write_variable(&self, bytes: &mut Vec<u8>) -> Result<(), WriteError> {
+        let field_bytes = match self {
+            Self::Uint64(value) => value.to_ssz(),
+            Self::Root(value) => value.to_ssz(),
+            Self::Fork(value) => value.to_ssz(),
+            Self::BlockHeader(value) => value.to_ssz(),
+            Self::RecentRoots(value) => value.to_ssz(),
+            Self::HistoricalRoots(value) => value.to_ssz(),
+            Self::Eth1Data(value) => value.to_ssz(),
+            Self::Eth1DataVotes(value) => value.to_ssz(),
+            Self::Validators(value) => value.to_ssz(),
+            Self::Balances(value) | Self::InactivityScores(value) => value.to_ssz(),
+            Self::RandaoMixes(value) => value.to_ssz(),
+            Self::Slashings(value) => value.to_ssz(),
+            Self::PendingAttestations(value) => value.to_ssz(),
+            Self::EpochParticipation(value) => value.to_ssz(),
+            Self::JustificationBits(value) => value.to_ssz(),
+            Self::Checkpoint(value) => value.to_ssz(),
+            Self::SyncCommittee(value) => value.to_ssz(),
+            Self::BellatrixExecutionPayloadHeader(value) => value.to_ssz(),
+            Self::CapellaExecutionPayloadHeader(value) => value.to_ssz(),
+            Self::DenebExecutionPayloadHeader(value) => value.to_ssz(),
+            Self::HistoricalSummaries(value) => value.to_ssz(),
+        }?;
+
+        bytes.extend(field_bytes);
+
+        Ok(())
+    }
+}
+
+impl<P: Preset> StateFieldValue<P> {
+    /// Returns `None` if `field` is not present in the phase of `state`.
+    fn extract(state: &BeaconState<P>, field: StateField) -> Option<Self> {
+        let value = match field {
+            StateField::GenesisTime => Self::Uint64(state.genesis_time()),
+            StateField::GenesisValidatorsRoot => Self::Root(state.genesis_validators_root()),
+            StateField::Slot => Self::Uint64(state.slot()),
+            StateField::Fork => Self::Fork(state.fork()),
+            StateField::LatestBlockHeader => Self::BlockHeader(state.latest_block_header()),
+            StateField::BlockRoots => Self::RecentRoots(state.block_roots().clone()),
+            StateField::StateRoots => Self::RecentRoots(state.state_roots().clone()),
+            StateField::HistoricalRoots => Self::HistoricalRoots(state.historical_roots().clone()),
+            StateField::Eth1Data => Self::Eth1Data(state.eth1_data()),
+            StateField::Eth1DataVotes => Self::Eth1DataVotes(state.eth1_data_votes().clone()),
+            StateField::Eth1DepositIndex => Self::Uint64(state.eth1_deposit_index()),
+            StateField::Validators => Self::Validators(state.validators().clone()),
+            StateField::Balances => Self::Balances(state.balances().clone()),
+            StateField::RandaoMixes => Self::RandaoMixes(state.randao_mixes().clone()),
+            StateField::Slashings => Self::Slashings(state.slashings().clone()),
+            StateField::PreviousEpochAttestations => match state {
+                BeaconState::Phase0(state) => {
+                    Self::PendingAttestations(state.previous_epoch_attestations.clone())
+                }
+                _ => return None,
+            },
+            StateField::CurrentEpochAttestations => match state {
+                BeaconState::Phase0(state) => {
+                    Self::PendingAttestations(state.current_epoch_attestations.clone())
+                }
+                _ => return None,
+            },
+            StateField::PreviousEpochParticipation => Self::EpochParticipation(
+                state.post_altair()?.previous_epoch_participation().clone(),
+            ),
+            StateField::CurrentEpochParticipation => {
+                Self::EpochParticipation(state.post_altair()?.current_epoch_participation().clone())
+            }
+            StateField::JustificationBits => Self::JustificationBits(state.justification_bits()),
+            StateField::PreviousJustifiedCheckpoint => {
+                Self::Checkpoint(state.previous_justified_checkpoint())
+            }
+            StateField::CurrentJustifiedCheckpoint => {
+                Self::Checkpoint(state.current_justified_checkpoint())
+            }
+            StateField::FinalizedCheckpoint => Self::Checkpoint(state.finalized_checkpoint()),
+            StateField::InactivityScores => {
+                Self::InactivityScores(state.post_altair()?.inactivity_scores().clone())
+            }
+            StateField::CurrentSyncCommittee => {
+                Self::SyncCommittee(state.post_altair()?.current_sync_committee().clone_arc())
+            }
+            StateField::NextSyncCommittee => {
+                Self::SyncCommittee(state.post_altair()?.next_sync_committee().clone_arc())
+            }
+            StateField::LatestExecutionPayloadHeader => match state {
+                BeaconState::Phase0(_) | BeaconState::Altair(_) => return None,
+                BeaconState::Bellatrix(state) => Self::BellatrixExecutionPayloadHeader(Box::new(
+                    state.latest_execution_payload_header.clone(),
+                )),
+                BeaconState::Capella(state) => Self::CapellaExecutionPayloadHeader(Box::new(
+                    state.latest_execution_payload_header.clone(),
+                )),
+                BeaconState::Deneb(state) => Self::DenebExecutionPayloadHeader(Box::new(
+                    state.latest_execution_payload_header.clone(),
+                )),
+            },
+            StateField::NextWithdrawalIndex => {
+                Self::Uint64(state.post_capella()?.next_withdrawal_index())
+            }
+            StateField::NextWithdrawalValidatorIndex => {
+                Self::Uint64(state.post_capella()?.next_withdrawal_validator_index())
+            }
+            StateField::HistoricalSummaries => match state {
+                BeaconState::Phase0(_) | BeaconState::Altair(_) | BeaconState::Bellatrix(_) => {
+                    return None
+                }
+                BeaconState::Capella(state) => {
+                    Self::HistoricalSummaries(state.historical_summaries.clone())
+                }
+                BeaconState::Deneb(state) => {
+                    Self::HistoricalSummaries(state.historical_summaries.clone())
+                }
+            },
+        };
+
+        Some(value)
+    }
+}
+
+/// `GET /grandine/v1/beacon/states/{state_id}/field/{field_name}`
+pub async fn get_state_field<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath((state_id, field)): EthPath<(StateId, StateField)>,
+    format: JsonOrSsz,
+) -> Result<EthResponse<StateFieldValue<P>, (), JsonOrSsz>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider)?;
+
+    let phase = state.phase();
+    let value = StateFieldValue::extract(&state, field)
+        .ok_or(Error::StateFieldNotPresent { field, phase })?;
+
+    Ok(EthResponse::json_or_ssz(value, format)
+        .execution_optimistic(optimistic)
+        .finalized(finalized)
+        .version(phase))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use try_from_iterator::TryFromIterator as _;
+    use types::{
+        altair::beacon_state::BeaconState as AltairBeaconState,
+        bellatrix::beacon_state::BeaconState as BellatrixBeaconState,
+        capella::beacon_state::BeaconState as CapellaBeaconState,
+        deneb::beacon_state::BeaconState as DenebBeaconState,
+        phase0::beacon_state::BeaconState as Phase0BeaconState, preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_state_field_parses_snake_case_names() {
+        assert_eq!(
+            "inactivity_scores".parse::<StateField>().ok(),
+            Some(StateField::InactivityScores),
+        );
+
+        assert_eq!("inactivityScores".parse::<StateField>().ok(), None);
+    }
+
+    #[test]
+    fn test_state_field_json_matches_full_state() -> Result<(), serde_json::Error> {
+        let states: [BeaconState<Minimal>; 5] = [
+            BeaconState::Phase0(Phase0BeaconState::default().into()),
+            BeaconState::Altair(AltairBeaconState::default().into()),
+            BeaconState::Bellatrix(BellatrixBeaconState::default().into()),
+            BeaconState::Capella(CapellaBeaconState::default().into()),
+            BeaconState::Deneb(DenebBeaconState::default().into()),
+        ];
+
+        for state in states {
+            let Value::Object(fields) = serde_json::to_value(&state)? else {
+                panic!("states should be serialized as JSON objects");
+            };
+
+            for field in enum_iterator::all::<StateField>() {
+                let expected = fields.get(&field.to_string());
+                let actual = StateFieldValue::extract(&state, field)
+                    .map(serde_json::to_value)
+                    .transpose()?;
+
+                assert_eq!(actual.as_ref(), expected, "{field} in {}", state.phase());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_field_ssz_matches_field() -> Result<(), WriteError> {
+        let balances = Balances::<Minimal>::try_from_iter([1, 2, 3])
+            .expect("number of balances is below the limit");
+
+        let state = BeaconState::<Minimal>::Deneb(
+            DenebBeaconState {
+                balances: balances.clone(),
+                ..DenebBeaconState::default()
+            }
+            .into(),
+        );
+
+        let value = StateFieldValue::extract(&state, StateField::Balances)
+            .expect("balances are present in all phases");
+
+        assert_eq!(value.to_ssz()?, balances.to_ssz()?);
+
+        Ok(())
+    }
+}
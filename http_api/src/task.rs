@@ -7,7 +7,8 @@ use eth1_api::ApiController;
 use fork_choice_control::{ApiMessage, Wait};
 use futures::{
     channel::mpsc::{UnboundedReceiver, UnboundedSender},
-    future::{FutureExt as _, TryFutureExt as _},
+    future::{self, FutureExt as _, TryFutureExt as _},
+    lock::Mutex,
     select,
     stream::StreamExt as _,
 };
@@ -22,15 +23,20 @@ use operation_pools::{
 };
 use p2p::{ApiToP2p, NetworkConfig, SyncToApi, ToSubnetService};
 use prometheus_metrics::Metrics;
+use slashing_protection::SlashingProtector;
 use std_ext::ArcExt as _;
 use types::preset::Preset;
 use validator::{ApiToValidator, ValidatorConfig, ValidatorToApi};
 
 use crate::{
-    events::{EventChannels, Topic},
+    config_fingerprint::StorageSettings,
+    events::{EventAttributes, EventChannels, Topic},
+    head_statement::HeadStatementSigner,
     http_api_config::HttpApiConfig,
     misc::{BackSyncedStatus, SyncedStatus},
     routing::{self, NormalState},
+    tls,
+    trusted_client::TrustedClientToken,
 };
 
 pub struct Channels<P: Preset> {
@@ -54,6 +60,8 @@ pub struct HttpApi<P: Preset, W: Wait> {
     pub validator_config: Arc<ValidatorConfig>,
     pub network_config: Arc<NetworkConfig>,
     pub http_api_config: HttpApiConfig,
+    pub storage_settings: StorageSettings,
+    pub slashing_protector: Arc<Mutex<SlashingProtector>>,
     pub attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
     pub sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
     pub bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
@@ -85,6 +93,8 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             validator_config,
             network_config,
             http_api_config,
+            storage_settings,
+            slashing_protector,
             attestation_agg_pool,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
@@ -95,8 +105,14 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
         let HttpApiConfig {
             address,
             allow_origin,
+            backup_directory,
+            head_statement_key_file,
             max_events,
+            read_only,
+            slow_request_threshold,
             timeout,
+            tls,
+            trusted_client_token_file,
         } = http_api_config;
 
         let Channels {
@@ -115,6 +131,26 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
         let is_back_synced = Arc::new(BackSyncedStatus::default());
         let event_channels = Arc::new(EventChannels::new(max_events));
 
+        let head_statement_signer = head_statement_key_file
+            .as_deref()
+            .map(HeadStatementSigner::load)
+            .transpose()?
+            .map(Arc::new);
+
+        let trusted_client_token = trusted_client_token_file
+            .as_deref()
+            .map(TrustedClientToken::load)
+            .transpose()?
+            .map(Arc::new);
+
+        let sign_head_statements = match head_statement_signer.clone() {
+            Some(signer) => {
+                let controller = controller.clone_arc();
+                async move { signer.run(controller).await }.left_future()
+            }
+            None => future::pending().right_future(),
+        };
+
         let state = NormalState {
             chain_config: controller.chain_config().clone_arc(),
             controller,
@@ -124,12 +160,20 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             validator_config,
             metrics: metrics.clone(),
             network_config,
+            storage_settings,
+            slashing_protector,
+            backup_directory,
             attestation_agg_pool,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
             is_synced: is_synced.clone_arc(),
             is_back_synced: is_back_synced.clone_arc(),
             event_channels: event_channels.clone_arc(),
+            network_overview: Arc::default(),
+            pruning_jobs: Arc::default(),
+            head_statement_signer,
+            trusted_client_token,
+            read_only,
             api_to_liveness_tx,
             api_to_metrics_tx,
             api_to_p2p_tx,
@@ -138,11 +182,22 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
         };
 
         let router = extend_router(state.clone(), routing::normal_routes(state));
-        let router =
-            http_api_utils::extend_router_with_middleware(router, timeout, allow_origin, metrics);
+        let router = http_api_utils::extend_router_with_middleware(
+            router,
+            timeout,
+            allow_origin,
+            metrics,
+            slow_request_threshold,
+        );
 
         let service = router.into_make_service_with_connect_info::<SocketAddr>();
-        let serve_requests = Server::builder(incoming).serve(service).err_into();
+        let serve_requests = match tls {
+            Some(tls_config) => tls::serve(incoming, service, tls_config).left_future(),
+            None => Server::builder(incoming)
+                .serve(service)
+                .err_into()
+                .right_future(),
+        };
 
         let handle_events = handle_events(
             is_synced,
@@ -159,6 +214,7 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
         select! {
             result = serve_requests.fuse() => result,
             result = handle_events.fuse() => result,
+            result = sign_head_statements.fuse() => result.map(|never| match never {}),
         }
     }
 }
@@ -172,17 +228,6 @@ async fn handle_events<P: Preset>(
     mut sync_to_api_rx: UnboundedReceiver<SyncToApi>,
     mut validator_to_api_rx: UnboundedReceiver<ValidatorToApi<P>>,
 ) -> Result<()> {
-    let EventChannels {
-        attestations,
-        blocks,
-        bls_to_execution_changes,
-        chain_reorgs,
-        contribution_and_proofs,
-        finalized_checkpoints,
-        heads,
-        voluntary_exits,
-    } = event_channels.as_ref();
-
     loop {
         select! {
             message = sync_to_api_rx.select_next_some() => {
@@ -194,14 +239,17 @@ async fn handle_events<P: Preset>(
 
             message = validator_to_api_rx.select_next_some() => {
                 let receivers = match message {
+                    ValidatorToApi::BalanceDeltas(balance_deltas_event) => {
+                        event_channels.send(Topic::BalanceDeltas, balance_deltas_event)?
+                    }
                     ValidatorToApi::ContributionAndProof(signed_contribution_and_proof) => {
-                        let event =
-                            Topic::ContributionAndProof.build(signed_contribution_and_proof)?;
-                        contribution_and_proofs.send(event).unwrap_or_default()
+                        event_channels.send(
+                            Topic::ContributionAndProof,
+                            signed_contribution_and_proof,
+                        )?
                     }
                     ValidatorToApi::VoluntaryExit(signed_voluntary_exit) => {
-                        let event = Topic::VoluntaryExit.build(signed_voluntary_exit)?;
-                        voluntary_exits.send(event).unwrap_or_default()
+                        event_channels.send(Topic::VoluntaryExit, signed_voluntary_exit)?
                     }
                 };
 
@@ -211,24 +259,32 @@ async fn handle_events<P: Preset>(
             message = fc_to_api_rx.select_next_some() => {
                 let receivers = match message {
                     ApiMessage::AttestationEvent(attestation) => {
-                        let event = Topic::Attestation.build(attestation)?;
-                        attestations.send(event).unwrap_or_default()
+                        let attributes = EventAttributes {
+                            committee_index: Some(attestation.data.index),
+                            ..EventAttributes::default()
+                        };
+
+                        event_channels.send_with_attributes(Topic::Attestation, attestation, attributes)?
                     }
                     ApiMessage::BlockEvent(block_event) => {
-                        let event = Topic::Block.build(block_event)?;
-                        blocks.send(event).unwrap_or_default()
+                        let attributes = EventAttributes {
+                            proposer_index: Some(block_event.proposer_index),
+                            ..EventAttributes::default()
+                        };
+
+                        event_channels.send_with_attributes(Topic::Block, block_event, attributes)?
                     }
                     ApiMessage::ChainReorgEvent(chain_reorg_event) => {
-                        let event = Topic::ChainReorg.build(chain_reorg_event)?;
-                        chain_reorgs.send(event).unwrap_or_default()
+                        event_channels.send(Topic::ChainReorg, chain_reorg_event)?
                     }
                     ApiMessage::FinalizedCheckpoint(finalized_checkpoint_event) => {
-                        let event = Topic::FinalizedCheckpoint.build(finalized_checkpoint_event)?;
-                        finalized_checkpoints.send(event).unwrap_or_default()
+                        event_channels.send(Topic::FinalizedCheckpoint, finalized_checkpoint_event)?
                     }
                     ApiMessage::Head(head_event) => {
-                        let event = Topic::Head.build(head_event)?;
-                        heads.send(event).unwrap_or_default()
+                        event_channels.send(Topic::Head, head_event)?
+                    }
+                    ApiMessage::InvalidatedBlocks(invalidated_blocks_event) => {
+                        event_channels.send(Topic::InvalidatedBlocks, invalidated_blocks_event)?
                     }
                 };
 
@@ -238,8 +294,7 @@ async fn handle_events<P: Preset>(
             message = pool_to_api_rx.select_next_some() => {
                 let receivers = match message {
                     PoolToApiMessage::SignedBlsToExecutionChange(signed_bls_to_execution_change) => {
-                        let event = Topic::BlsToExecutionChange.build(signed_bls_to_execution_change)?;
-                        bls_to_execution_changes.send(event).unwrap_or_default()
+                        event_channels.send(Topic::BlsToExecutionChange, signed_bls_to_execution_change)?
                     }
                 };
 
@@ -0,0 +1,120 @@
+//! TLS termination for the HTTP API.
+//!
+//! The certificate and private key are loaded again whenever either file is modified.
+//! This allows certificates to be renewed without restarting the node.
+
+use core::pin::Pin;
+use std::{net::SocketAddr, time::SystemTime};
+
+use anyhow::Result;
+use axum::{extract::connect_info::IntoMakeServiceWithConnectInfo, Router};
+use futures::future::poll_fn;
+use hyper::{
+    server::{
+        accept::Accept as _,
+        conn::{AddrIncoming, Http},
+    },
+    service::Service as _,
+};
+use log::{debug, info, warn};
+use native_tls::Identity;
+use parking_lot::Mutex;
+use tokio_native_tls::TlsAcceptor;
+
+use crate::http_api_config::TlsConfig;
+
+struct LoadedAcceptor {
+    modified: (SystemTime, SystemTime),
+    acceptor: TlsAcceptor,
+}
+
+struct ReloadingAcceptor {
+    config: TlsConfig,
+    loaded: Mutex<LoadedAcceptor>,
+}
+
+impl ReloadingAcceptor {
+    fn new(config: TlsConfig) -> Result<Self> {
+        let loaded = LoadedAcceptor {
+            modified: modification_times(&config)?,
+            acceptor: load_acceptor(&config)?,
+        };
+
+        Ok(Self {
+            config,
+            loaded: Mutex::new(loaded),
+        })
+    }
+
+    // Checking modification times on every connection is cheap enough to not need a separate task.
+    fn acceptor(&self) -> TlsAcceptor {
+        let mut loaded = self.loaded.lock();
+
+        match modification_times(&self.config) {
+            Ok(modified) if modified != loaded.modified => match load_acceptor(&self.config) {
+                Ok(acceptor) => {
+                    info!("HTTP API TLS certificate reloaded");
+                    *loaded = LoadedAcceptor { modified, acceptor };
+                }
+                // The files may be in the middle of being replaced.
+                // Keep using the old certificate and try again on the next connection.
+                Err(error) => warn!("failed to reload HTTP API TLS certificate: {error:?}"),
+            },
+            Ok(_) => {}
+            Err(error) => warn!("failed to check HTTP API TLS certificate for changes: {error:?}"),
+        }
+
+        loaded.acceptor.clone()
+    }
+}
+
+pub async fn serve(
+    mut incoming: AddrIncoming,
+    mut make_service: IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+    config: TlsConfig,
+) -> Result<()> {
+    let acceptor = ReloadingAcceptor::new(config)?;
+
+    while let Some(stream) = poll_fn(|context| Pin::new(&mut incoming).poll_accept(context)).await {
+        let stream = stream?;
+
+        // Connection info has to be extracted before the handshake.
+        // `ConnectInfo<SocketAddr>` is only implemented for unencrypted streams.
+        let service = make_service.call(&stream).await?;
+        let acceptor = acceptor.acceptor();
+
+        // Perform handshakes in separate tasks to prevent slow clients from blocking others.
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(error) => {
+                    debug!("HTTP API TLS handshake failed: {error}");
+                    return;
+                }
+            };
+
+            let connection = Http::new()
+                .serve_connection(stream, service)
+                .with_upgrades();
+
+            if let Err(error) = connection.await {
+                debug!("HTTP API connection closed with an error: {error}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn modification_times(config: &TlsConfig) -> Result<(SystemTime, SystemTime)> {
+    let certificate_modified = fs_err::metadata(&config.certificate_file)?.modified()?;
+    let private_key_modified = fs_err::metadata(&config.private_key_file)?.modified()?;
+    Ok((certificate_modified, private_key_modified))
+}
+
+fn load_acceptor(config: &TlsConfig) -> Result<TlsAcceptor> {
+    let certificate = fs_err::read(&config.certificate_file)?;
+    let private_key = fs_err::read(&config.private_key_file)?;
+    let identity = Identity::from_pkcs8(&certificate, &private_key)?;
+    Ok(native_tls::TlsAcceptor::new(identity)?.into())
+}
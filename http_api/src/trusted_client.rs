@@ -0,0 +1,136 @@
+//! Authentication of validator clients running on the same machine as the beacon node.
+//!
+//! Requests from trusted clients may skip verification that the client has already performed.
+//! A client is trusted if it connects over a loopback interface and presents the token loaded
+//! from the file passed to the beacon node, the same way a JWT secret is shared with an execution
+//! client.
+
+use std::{net::SocketAddr, path::Path, sync::Arc};
+
+use anyhow::{ensure, Result};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRef, FromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use log::info;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+pub struct TrustedClientToken {
+    token: Zeroizing<String>,
+}
+
+impl TrustedClientToken {
+    pub fn load(token_file: &Path) -> Result<Self> {
+        let token = Zeroizing::new(fs_err::read_to_string(token_file)?.trim().to_owned());
+
+        ensure!(!token.is_empty(), Error::EmptyToken);
+
+        info!("HTTP API will skip redundant verification of attestations from trusted clients");
+
+        Ok(Self { token })
+    }
+
+    fn matches(&self, candidate: &str) -> bool {
+        let expected = self.token.as_bytes();
+        let candidate = candidate.as_bytes();
+
+        // Compare all bytes to avoid leaking the length of the matching prefix through timing.
+        expected.len() == candidate.len()
+            && expected
+                .iter()
+                .zip(candidate)
+                .fold(0, |difference, (left, right)| difference | (left ^ right))
+                == 0
+    }
+}
+
+/// Whether the request came from a trusted validator client.
+///
+/// Requests that fail authentication are not rejected.
+/// They are processed like requests from any other client.
+pub struct TrustedClient(pub bool);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for TrustedClient
+where
+    S: Sync,
+    Option<Arc<TrustedClientToken>>: FromRef<S>,
+{
+    type Rejection = core::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(token) = Option::<Arc<TrustedClientToken>>::from_ref(state) else {
+            return Ok(Self(false));
+        };
+
+        let is_loopback = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .is_some_and(|ConnectInfo(address)| address.ip().is_loopback());
+
+        let is_authenticated = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|candidate| token.matches(candidate));
+
+        Ok(Self(is_loopback && is_authenticated))
+    }
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("trusted client token file is empty")]
+    EmptyToken,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+
+    use super::*;
+
+    fn parts(address: SocketAddr, authorization: Option<&str>) -> Parts {
+        let mut builder = Request::builder().extension(ConnectInfo(address));
+
+        if let Some(authorization) = authorization {
+            builder = builder.header(AUTHORIZATION, authorization);
+        }
+
+        builder
+            .body(())
+            .expect("request should be valid")
+            .into_parts()
+            .0
+    }
+
+    async fn is_trusted(address: &str, authorization: Option<&str>) -> bool {
+        let token = Some(Arc::new(TrustedClientToken {
+            token: Zeroizing::new("secret".to_owned()),
+        }));
+
+        let address = address.parse().expect("address should be valid");
+        let mut parts = parts(address, authorization);
+
+        let TrustedClient(trusted) = TrustedClient::from_request_parts(&mut parts, &token)
+            .await
+            .unwrap_or_else(|never| match never {});
+
+        trusted
+    }
+
+    #[tokio::test]
+    async fn only_loopback_clients_with_matching_token_are_trusted() {
+        assert!(is_trusted("127.0.0.1:5000", Some("Bearer secret")).await);
+        assert!(is_trusted("[::1]:5000", Some("Bearer secret")).await);
+
+        assert!(!is_trusted("127.0.0.1:5000", None).await);
+        assert!(!is_trusted("127.0.0.1:5000", Some("Bearer secre")).await);
+        assert!(!is_trusted("127.0.0.1:5000", Some("Bearer secret2")).await);
+        assert!(!is_trusted("127.0.0.1:5000", Some("secret")).await);
+        assert!(!is_trusted("192.168.0.2:5000", Some("Bearer secret")).await);
+    }
+}
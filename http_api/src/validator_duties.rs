@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use axum::extract::State;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use serde::Serialize;
+use types::{
+    nonstandard::{RelativeEpoch, WithStatus},
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{Epoch, ValidatorIndex, H256},
+    },
+    preset::Preset,
+};
+
+use crate::{
+    error::Error,
+    extractors::{EthJson, EthPath},
+    response::EthResponse,
+    standard::{
+        self, ValidatorAttesterDutyResponse, ValidatorProposerDutyResponse,
+        ValidatorSyncDutyResponse,
+    },
+};
+
+#[derive(Serialize)]
+pub struct ValidatorAllDutiesResponse {
+    attester_dependent_root: H256,
+    proposer_dependent_root: H256,
+    attester_duties: Vec<ValidatorAttesterDutyResponse>,
+    proposer_duties: Vec<ValidatorProposerDutyResponse>,
+    sync_duties: Vec<ValidatorSyncDutyResponse>,
+}
+
+/// `POST /grandine/v1/validator/duties/all/{epoch}`
+///
+/// Combines the responses of the standard attester, proposer and sync committee duty endpoints
+/// for the posted validator indices. All of them are computed from the same state in `epoch`.
+/// Dependent roots are included in the data because attester and proposer duties depend on
+/// different blocks.
+pub async fn validator_all_duties<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    EthPath(epoch): EthPath<Epoch>,
+    EthJson(validator_indices): EthJson<Vec<ValidatorIndex>>,
+) -> Result<EthResponse<ValidatorAllDutiesResponse>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        // `duties` responses are not supposed to contain a `finalized` field.
+        finalized: _,
+    } = controller.preprocessed_state_at_epoch(epoch)?;
+
+    let previous_epoch = epoch.saturating_sub(1).max(GENESIS_EPOCH);
+    let attester_dependent_root = controller.dependent_root(&state, previous_epoch)?;
+    let proposer_dependent_root = controller.dependent_root(&state, epoch)?;
+
+    let indices = validator_indices
+        .iter()
+        .copied()
+        .collect::<HashSet<ValidatorIndex>>();
+
+    let attester_duties =
+        standard::attester_duties(&state, epoch, RelativeEpoch::Current, &indices)
+            .map_err(Error::Internal)?;
+
+    let proposer_duties =
+        standard::proposer_duties(&state, epoch, &indices).map_err(Error::Internal)?;
+
+    let sync_duties = match state.post_altair() {
+        Some(state) => standard::sync_committee_duties(state, epoch, validator_indices)?,
+        None => vec![],
+    };
+
+    let response = ValidatorAllDutiesResponse {
+        attester_dependent_root,
+        proposer_dependent_root,
+        attester_duties,
+        proposer_duties,
+        sync_duties,
+    };
+
+    Ok(EthResponse::json(response).execution_optimistic(optimistic))
+}
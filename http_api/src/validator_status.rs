@@ -1,5 +1,6 @@
 use bls::PublicKeyBytes;
-use helper_functions::{accessors, misc};
+use fork_choice_store::ValidatorRegistry;
+use helper_functions::misc;
 use parse_display::{Display, FromStr};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use types::{
@@ -19,10 +20,14 @@ pub enum ValidatorId {
 }
 
 impl ValidatorId {
-    pub fn validator_index<P: Preset>(self, state: &BeaconState<P>) -> Option<ValidatorIndex> {
+    pub fn validator_index<P: Preset>(
+        self,
+        validator_registry: &ValidatorRegistry,
+        state: &BeaconState<P>,
+    ) -> Option<ValidatorIndex> {
         match self {
             Self::ValidatorIndex(validator_index) => Some(validator_index),
-            Self::PublicKey(pubkey) => accessors::index_of_public_key(state, pubkey),
+            Self::PublicKey(pubkey) => validator_registry.index_of_public_key(state, pubkey),
         }
     }
 }
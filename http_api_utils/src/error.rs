@@ -19,6 +19,8 @@ pub enum Error {
         uri: Uri,
         source: AnyhowError,
     },
+    #[error("invalid X-Correlation-Id header")]
+    InvalidCorrelationId,
 }
 
 impl IntoResponse for Error {
@@ -49,7 +51,7 @@ impl Error {
 
     const fn status_code(&self) -> StatusCode {
         match self {
-            Self::InvalidBody { .. } => StatusCode::BAD_REQUEST,
+            Self::InvalidBody { .. } | Self::InvalidCorrelationId => StatusCode::BAD_REQUEST,
         }
     }
 }
@@ -17,6 +17,7 @@ pub fn extend_router_with_middleware(
     timeout: Option<Duration>,
     allowed_origins: AllowOrigin,
     metrics: Option<Arc<Metrics>>,
+    slow_request_threshold: Option<Duration>,
 ) -> Router {
     if let Some(timeout) = timeout {
         router = router.layer(
@@ -28,6 +29,13 @@ pub fn extend_router_with_middleware(
         );
     }
 
+    // Layers added later wrap earlier ones.
+    // Adding this after the timeout makes it log span trees of requests that time out.
+    router = router.layer(axum::middleware::from_fn_with_state(
+        slow_request_threshold,
+        middleware::trace_request,
+    ));
+
     router = router.layer(CorsLayer::new().allow_origin(allowed_origins).vary([]));
 
     if Feature::LogHttpRequests.is_enabled() || metrics.is_some() {
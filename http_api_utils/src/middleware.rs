@@ -1,24 +1,30 @@
+use core::time::Duration;
 use std::{error::Error as StdError, net::SocketAddr};
 
 use axum::{
     body::{Body, Bytes, HttpBody},
-    extract::{ConnectInfo, MatchedPath, OriginalUri},
+    extract::{ConnectInfo, MatchedPath, OriginalUri, State},
     http::{
-        header::{HeaderValue, CONTENT_TYPE},
+        header::{HeaderName, HeaderValue, CONTENT_TYPE},
         Request, Uri,
     },
     middleware::Next,
     response::{IntoResponse as _, Response},
     Error as AxumError, Extension,
 };
-use log::info;
+use log::{info, warn};
 use mime::{APPLICATION_JSON, TEXT_EVENT_STREAM};
+use request_tracing::{CorrelationId, TraceContext};
 
 use crate::{error::Error, misc::Direction};
 
 // Don't log states when `Feature::LogHttpBodies` is enabled.
 const ENDPOINTS_WITH_IGNORED_BODIES: &[&str] = &["/eth/v2/debug/beacon/states/"];
 
+// Clients may set this to correlate their own logs with ours.
+// Requests without it are assigned a random ID. Requests with an invalid one are rejected.
+static CORRELATION_ID: HeaderName = HeaderName::from_static("x-correlation-id");
+
 async fn buffer_and_log<B>(direction: Direction, uri: &Uri, body: B) -> Result<Bytes, Error>
 where
     B: HttpBody<Data = Bytes> + Send,
@@ -68,6 +74,44 @@ pub async fn insert_response_extensions(request: Request<Body>, next: Next<Body>
         .into_response()
 }
 
+pub async fn trace_request(
+    State(slow_request_threshold): State<Option<Duration>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, Error> {
+    let correlation_id = match request.headers().get(&CORRELATION_ID) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|string| string.parse().ok())
+            .ok_or(Error::InvalidCorrelationId)?,
+        None => CorrelationId::random(),
+    };
+
+    let header_value = HeaderValue::from_str(correlation_id.as_str())
+        .expect("correlation ID should only contain printable ASCII");
+
+    let context = TraceContext::new(correlation_id);
+    let span_name = format!("{} {}", request.method(), request.uri().path());
+
+    let mut response = context
+        .clone()
+        .scope(request_tracing::in_span(span_name, next.run(request)))
+        .await;
+
+    response
+        .headers_mut()
+        .insert(CORRELATION_ID.clone(), header_value);
+
+    let latency = context.elapsed();
+
+    if slow_request_threshold.is_some_and(|threshold| latency >= threshold) {
+        warn!("slow request took {latency:?}: {}", context.render());
+    }
+
+    Ok(response)
+}
+
 pub async fn log_request_and_response_bodies(
     request: Request<Body>,
     next: Next<Body>,
@@ -129,7 +129,7 @@ impl KeystoreManager {
                             deleted_keys.push(pubkey);
                             Status::Deleted.into()
                         }
-                        KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer => {
+                        KeyOrigin::Interop | KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer => {
                             Error::ReadOnly.into()
                         }
                     },
@@ -253,7 +253,7 @@ impl KeystoreManager {
                 url: None,
                 readonly: match origin {
                     KeyOrigin::KeymanagerAPI => false,
-                    KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer => true,
+                    KeyOrigin::Interop | KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer => true,
                 },
             })
             .collect()
@@ -43,7 +43,9 @@ impl RemoteKeyManager {
             .copied()
             .map(|pubkey| match signer_keys.get(&pubkey) {
                 Some(origin) => match origin {
-                    KeyOrigin::KeymanagerAPI | KeyOrigin::LocalFileSystem => Error::ReadOnly.into(),
+                    KeyOrigin::Interop | KeyOrigin::KeymanagerAPI | KeyOrigin::LocalFileSystem => {
+                        Error::ReadOnly.into()
+                    }
                     KeyOrigin::Web3Signer => {
                         signer.delete_key(pubkey);
                         Status::Deleted.into()
@@ -141,6 +141,7 @@ pub async fn run_metrics_server<P: Preset, W: Wait>(
         Some(Duration::from_millis(config.timeout)),
         AllowOrigin::any(),
         None,
+        None,
     );
 
     Server::bind(&addr)
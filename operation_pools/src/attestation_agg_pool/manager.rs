@@ -7,6 +7,7 @@ use dedicated_executor::DedicatedExecutor;
 use eth1_api::ApiController;
 use features::Feature;
 use fork_choice_control::Wait;
+use log::warn;
 use prometheus_metrics::Metrics;
 use ssz::ContiguousList;
 use std_ext::ArcExt as _;
@@ -15,7 +16,7 @@ use types::{
     config::Config,
     phase0::{
         containers::{Attestation, AttestationData},
-        primitives::{Epoch, H256},
+        primitives::{CommitteeIndex, Epoch, H256},
     },
     preset::Preset,
 };
@@ -23,9 +24,10 @@ use types::{
 use crate::{
     attestation_agg_pool::{
         pool::Pool,
+        pool_attestation::PoolAttestation,
         tasks::{
-            BestProposableAttestationsTask, ComputeProposerIndicesTask, InsertAttestationTask,
-            PackProposableAttestationsTask, SetRegisteredValidatorsTask,
+            BestProposableAttestationsTask, CheckConsistencyTask, ComputeProposerIndicesTask,
+            InsertAttestationTask, PackProposableAttestationsTask, SetRegisteredValidatorsTask,
         },
     },
     misc::PoolTask,
@@ -67,6 +69,10 @@ impl<P: Preset, W: Wait> Manager<P, W> {
             }
             TickKind::Attest => {
                 self.pool.clear_best_proposable_attestations().await;
+
+                if Feature::DebugAttestationPool.is_enabled() {
+                    self.check_consistency();
+                }
             }
             TickKind::AggregateFourth => {
                 let next_slot = slot + 1;
@@ -95,6 +101,16 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         self.pool.best_aggregate_attestation(data).await
     }
 
+    pub async fn best_aggregate_attestation_for_committee(
+        &self,
+        data: AttestationData,
+        committee_index: CommitteeIndex,
+    ) -> Option<Attestation<P>> {
+        self.pool
+            .best_aggregate_attestation_for_committee(data, committee_index)
+            .await
+    }
+
     pub async fn best_aggregate_attestation_by_data_root(
         &self,
         attestation_data_root: H256,
@@ -117,6 +133,13 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         .await
     }
 
+    pub fn check_consistency(&self) {
+        self.spawn_detached(CheckConsistencyTask {
+            pool: self.pool.clone_arc(),
+            controller: self.controller.clone_arc(),
+        });
+    }
+
     pub fn compute_proposer_indices(&self, beacon_state: Arc<BeaconState<P>>) {
         self.spawn_detached(ComputeProposerIndicesTask {
             pool: self.pool.clone_arc(),
@@ -124,10 +147,19 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         });
     }
 
-    pub fn insert_attestation(&self, wait_group: W, attestation: Arc<Attestation<P>>) {
+    /// Inserts an attestation in any of the formats accepted by the pool.
+    ///
+    /// Attestations covering multiple committees are ignored. See [`PoolAttestation`].
+    pub fn insert_attestation(&self, wait_group: W, attestation: impl PoolAttestation<P>) {
+        let Some((committee_index, attestation)) = attestation.into_pool_attestation() else {
+            warn!("attestation covering multiple committees cannot be pooled");
+            return;
+        };
+
         self.spawn_detached(InsertAttestationTask {
             wait_group,
             pool: self.pool.clone_arc(),
+            committee_index,
             attestation,
             metrics: self.metrics.clone(),
         });
@@ -16,7 +16,7 @@ use types::{
     phase0::{
         consts::GENESIS_EPOCH,
         containers::{Attestation, AttestationData},
-        primitives::{Epoch, Slot, ValidatorIndex, H256},
+        primitives::{CommitteeIndex, Epoch, Slot, ValidatorIndex, H256},
     },
     preset::Preset,
     traits::BeaconState,
@@ -24,7 +24,7 @@ use types::{
 
 use crate::attestation_agg_pool::{
     max_clique::MaxClique,
-    types::{Aggregate, AggregateMap, AttestationMap, AttestationSet},
+    types::{Aggregate, AggregateMap, AggregationKey, AttestationMap, AttestationSet},
 };
 
 #[allow(type_alias_bounds)]
@@ -73,7 +73,8 @@ impl<P: Preset> Pool<P> {
             .insert(root, data);
     }
 
-    pub async fn aggregates(&self, data: AttestationData) -> Arc<Mutex<Vec<Aggregate<P>>>> {
+    pub async fn aggregates(&self, key: AggregationKey) -> Arc<Mutex<Vec<Aggregate<P>>>> {
+        let (data, _) = key;
         let epoch = data.target.epoch;
 
         if let Some(aggregates) = self
@@ -81,7 +82,7 @@ impl<P: Preset> Pool<P> {
             .read()
             .await
             .get(&epoch)
-            .and_then(|epoch_aggregates| epoch_aggregates.get(&data))
+            .and_then(|epoch_aggregates| epoch_aggregates.get(&key))
         {
             return aggregates.clone_arc();
         }
@@ -91,7 +92,7 @@ impl<P: Preset> Pool<P> {
             .await
             .entry(epoch)
             .or_default()
-            .entry(data)
+            .entry(key)
             .or_default()
             .clone_arc()
     }
@@ -131,7 +132,8 @@ impl<P: Preset> Pool<P> {
             .get(&epoch)
             .into_iter()
             .flatten()
-            .map(|(data, aggregates)| async {
+            .filter(|(key, _)| is_phase0_key(**key))
+            .map(|((data, _), aggregates)| async {
                 aggregates
                     .lock()
                     .await
@@ -162,6 +164,18 @@ impl<P: Preset> Pool<P> {
     pub async fn best_aggregate_attestation(
         &self,
         data: AttestationData,
+    ) -> Option<Attestation<P>> {
+        self.best_aggregate_attestation_for_committee(data, data.index)
+            .await
+    }
+
+    /// Returns the best aggregate of attestations with the given data for the given committee.
+    ///
+    /// Attestations in the format of the next fork have `data.index` set to 0 for all committees.
+    pub async fn best_aggregate_attestation_for_committee(
+        &self,
+        data: AttestationData,
+        committee_index: CommitteeIndex,
     ) -> Option<Attestation<P>> {
         let epoch = data.target.epoch;
 
@@ -170,7 +184,7 @@ impl<P: Preset> Pool<P> {
             .read()
             .await
             .get(&epoch)
-            .and_then(|epoch_aggregates| epoch_aggregates.get(&data))
+            .and_then(|epoch_aggregates| epoch_aggregates.get(&(data, committee_index)))
         {
             return aggregates
                 .lock()
@@ -296,8 +310,9 @@ impl<P: Preset> Pool<P> {
 
     pub async fn singular_attestations(
         &self,
-        data: AttestationData,
+        key: AggregationKey,
     ) -> Arc<RwLock<AttestationSet<P>>> {
+        let (data, _) = key;
         let epoch = data.target.epoch;
 
         if let Some(attestations) = self
@@ -305,7 +320,7 @@ impl<P: Preset> Pool<P> {
             .read()
             .await
             .get(&epoch)
-            .and_then(|epoch_attestations| epoch_attestations.get(&data))
+            .and_then(|epoch_attestations| epoch_attestations.get(&key))
         {
             return attestations.clone_arc();
         }
@@ -315,7 +330,7 @@ impl<P: Preset> Pool<P> {
             .await
             .entry(epoch)
             .or_default()
-            .entry(data)
+            .entry(key)
             .or_default()
             .clone_arc()
     }
@@ -327,6 +342,7 @@ impl<P: Preset> Pool<P> {
             .get(&epoch)
             .into_iter()
             .flatten()
+            .filter(|(key, _)| is_phase0_key(**key))
             .map(|(_, attestations)| async { attestations.read().await.clone() })
             .collect::<FuturesUnordered<_>>()
             .collect::<Vec<_>>()
@@ -348,3 +364,10 @@ impl<P: Preset> Pool<P> {
             .is_some()
     }
 }
+
+// Aggregates of attestations in the format of the next fork cannot be represented as Phase 0
+// attestations unless they are for committee 0. Methods used for packing and by the HTTP API
+// must skip the rest.
+const fn is_phase0_key((data, committee_index): AggregationKey) -> bool {
+    data.index == committee_index
+}
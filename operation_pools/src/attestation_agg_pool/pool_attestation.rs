@@ -0,0 +1,258 @@
+use std::{borrow::Cow, sync::Arc};
+
+use bls::AggregateSignatureBytes;
+use ssz::BitList;
+use types::{
+    phase0::{
+        containers::{Attestation, AttestationData},
+        primitives::CommitteeIndex,
+    },
+    preset::Preset,
+};
+
+/// An attestation in one of the formats accepted by the attestation aggregation pool.
+///
+/// Attestations in the next fork leave `AttestationData.index` at 0 and select committees with
+/// `committee_bits` instead, which lets proposers aggregate attestations from several committees.
+/// The pool stores attestations in either format as a Phase 0 [`Attestation`] with their original
+/// data and signature and aggregates them by data and committee index. Signatures are only
+/// combined if they were made over the same data, so attestations in different formats only share
+/// aggregates for committee 0.
+pub trait PoolAttestation<P: Preset> {
+    fn data(&self) -> AttestationData;
+
+    fn signature(&self) -> AggregateSignatureBytes;
+
+    /// Returns the index of the committee the attestation is for along with the bits of the
+    /// members of that committee that attested.
+    ///
+    /// Returns `None` if the attestation covers multiple committees.
+    fn single_committee(
+        &self,
+    ) -> Option<(CommitteeIndex, Cow<BitList<P::MaxValidatorsPerCommittee>>)>;
+
+    /// Returns `None` if the attestation covers multiple committees.
+    /// Those can only come from blocks and cannot be split without the committees themselves.
+    fn into_pool_attestation(self) -> Option<(CommitteeIndex, Arc<Attestation<P>>)>
+    where
+        Self: Sized,
+    {
+        let (committee_index, aggregation_bits) = self.single_committee()?;
+
+        let attestation = Attestation {
+            aggregation_bits: aggregation_bits.into_owned(),
+            data: self.data(),
+            signature: self.signature(),
+        };
+
+        Some((committee_index, Arc::new(attestation)))
+    }
+}
+
+// Phase 0 attestations are already in the format used by the pool.
+// Taking them in an `Arc` avoids copying the ones shared with other components.
+impl<P: Preset> PoolAttestation<P> for Arc<Attestation<P>> {
+    fn data(&self) -> AttestationData {
+        self.data
+    }
+
+    fn signature(&self) -> AggregateSignatureBytes {
+        self.signature
+    }
+
+    fn single_committee(
+        &self,
+    ) -> Option<(CommitteeIndex, Cow<BitList<P::MaxValidatorsPerCommittee>>)> {
+        Some((self.data.index, Cow::Borrowed(&self.aggregation_bits)))
+    }
+
+    fn into_pool_attestation(self) -> Option<(CommitteeIndex, Arc<Attestation<P>>)> {
+        Some((self.data.index, self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bls::{AggregateSignature, SecretKey, SecretKeyBytes};
+    use itertools::Itertools as _;
+    use ssz::SszHash as _;
+    use std_ext::ArcExt as _;
+    use types::preset::Minimal;
+
+    use crate::{
+        attestation_agg_pool::{pool::Pool, tasks::InsertAttestationTask},
+        misc::PoolTask as _,
+    };
+
+    use super::*;
+
+    // Stands in for the container of the next fork until it is added to `types`.
+    struct CommitteeBitsAttestation {
+        aggregation_bits: BitList<<Minimal as Preset>::MaxValidatorsPerCommittee>,
+        data: AttestationData,
+        signature: AggregateSignatureBytes,
+        committee_indices: Vec<CommitteeIndex>,
+    }
+
+    impl PoolAttestation<Minimal> for CommitteeBitsAttestation {
+        fn data(&self) -> AttestationData {
+            self.data
+        }
+
+        fn signature(&self) -> AggregateSignatureBytes {
+            self.signature
+        }
+
+        fn single_committee(
+            &self,
+        ) -> Option<(
+            CommitteeIndex,
+            Cow<BitList<<Minimal as Preset>::MaxValidatorsPerCommittee>>,
+        )> {
+            match self.committee_indices.as_slice() {
+                [index] => Some((*index, Cow::Borrowed(&self.aggregation_bits))),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn conversion_keeps_original_data_and_signature() {
+        let mut aggregation_bits = BitList::with_length(4);
+        aggregation_bits.set(1, true);
+
+        let data = AttestationData {
+            slot: 9,
+            index: 0,
+            ..AttestationData::default()
+        };
+
+        let committee_bits_attestation = CommitteeBitsAttestation {
+            aggregation_bits: aggregation_bits.clone(),
+            data,
+            signature: AggregateSignatureBytes::default(),
+            committee_indices: vec![2],
+        };
+
+        let expected_attestation = Arc::new(Attestation::<Minimal> {
+            aggregation_bits,
+            data,
+            signature: AggregateSignatureBytes::default(),
+        });
+
+        assert_eq!(
+            committee_bits_attestation.into_pool_attestation(),
+            Some((2, expected_attestation.clone_arc())),
+        );
+
+        let (committee_index, pool_attestation) = expected_attestation
+            .clone_arc()
+            .into_pool_attestation()
+            .expect("Phase 0 attestations are always for a single committee");
+
+        assert_eq!(committee_index, 0);
+        assert!(Arc::ptr_eq(&pool_attestation, &expected_attestation));
+    }
+
+    #[tokio::test]
+    async fn only_signatures_over_same_data_are_aggregated() -> Result<()> {
+        let pool = Arc::new(Pool::<Minimal>::default());
+        let secret_keys = (1..=4).map(secret_key).collect::<Result<Vec<_>>>()?;
+
+        let committee_bits_data = AttestationData {
+            slot: 9,
+            index: 0,
+            ..AttestationData::default()
+        };
+
+        let phase0_data_2 = AttestationData {
+            index: 2,
+            ..committee_bits_data
+        };
+
+        // Each attestation is by the validator at its position in the committee.
+        // Attestations for committee 0 have the same data in both formats.
+        // Attestations for committee 2 do not.
+        let attestations = [
+            (committee_bits_data, 0),
+            (committee_bits_data, 0),
+            (phase0_data_2, 2),
+            (committee_bits_data, 2),
+        ];
+
+        for (validator, (data, committee_index)) in attestations.into_iter().enumerate() {
+            let mut aggregation_bits = BitList::with_length(4);
+            aggregation_bits.set(validator, true);
+
+            let attestation = Arc::new(Attestation {
+                aggregation_bits,
+                data,
+                signature: secret_keys[validator].sign(data.hash_tree_root()).into(),
+            });
+
+            InsertAttestationTask {
+                wait_group: (),
+                pool: pool.clone_arc(),
+                committee_index,
+                attestation,
+                metrics: None,
+            }
+            .run()
+            .await?;
+        }
+
+        let assert_verifies = |attestation: Option<Attestation<Minimal>>, validators: &[usize]| {
+            let attestation = attestation.expect("pool should contain an aggregate");
+
+            let attesting_positions = attestation.aggregation_bits.iter_ones().collect_vec();
+
+            assert_eq!(attesting_positions, validators);
+
+            let public_keys = validators
+                .iter()
+                .map(|validator| secret_keys[*validator].to_public_key())
+                .collect_vec();
+
+            let signature = AggregateSignature::try_from(attestation.signature)
+                .expect("aggregate signature should be valid");
+
+            assert!(
+                signature.fast_aggregate_verify(attestation.data.hash_tree_root(), &public_keys),
+            );
+        };
+
+        assert_verifies(
+            pool.best_aggregate_attestation(committee_bits_data).await,
+            &[0, 1],
+        );
+        assert_verifies(pool.best_aggregate_attestation(phase0_data_2).await, &[2]);
+
+        assert_verifies(
+            pool.best_aggregate_attestation_for_committee(committee_bits_data, 2)
+                .await,
+            &[3],
+        );
+
+        // The aggregate for committee 2 in the format of the next fork cannot be packed.
+        assert_eq!(pool.aggregate_attestations_by_epoch(0).await.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn attestations_covering_multiple_committees_are_not_accepted() {
+        let attestation = CommitteeBitsAttestation {
+            aggregation_bits: BitList::with_length(8),
+            data: AttestationData::default(),
+            signature: AggregateSignatureBytes::default(),
+            committee_indices: vec![0, 1],
+        };
+
+        assert_eq!(attestation.into_pool_attestation(), None);
+    }
+
+    fn secret_key(byte: u8) -> Result<SecretKey> {
+        SecretKey::try_from(SecretKeyBytes::from([byte; 32])).map_err(Into::into)
+    }
+}
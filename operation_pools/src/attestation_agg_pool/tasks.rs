@@ -1,16 +1,23 @@
 use core::time::Duration;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Result;
 use bls::PublicKeyBytes;
 use eth1_api::ApiController;
 use fork_choice_control::Wait;
-use helper_functions::accessors;
+use helper_functions::{accessors, misc};
+use itertools::Itertools as _;
+use log::warn;
 use prometheus_metrics::Metrics;
 use ssz::ContiguousList;
 use std_ext::ArcExt as _;
 use types::{
-    combined::BeaconState, phase0::containers::Attestation, preset::Preset,
+    combined::BeaconState,
+    phase0::{
+        containers::Attestation,
+        primitives::{CommitteeIndex, Epoch, ValidatorIndex},
+    },
+    preset::Preset,
     traits::BeaconState as _,
 };
 
@@ -60,6 +67,86 @@ impl<P: Preset, W: Wait> PoolTask for BestProposableAttestationsTask<P, W> {
     }
 }
 
+/// Checks that votes in aggregates in the pool have been counted by fork choice.
+///
+/// Attestations from the last slot are skipped because fork choice may not have applied them yet.
+/// So are attestations for other branches, since they may have been rejected for valid reasons.
+pub struct CheckConsistencyTask<P: Preset, W: Wait> {
+    pub pool: Arc<Pool<P>>,
+    pub controller: ApiController<P, W>,
+}
+
+impl<P: Preset, W: Wait> PoolTask for CheckConsistencyTask<P, W> {
+    type Output = ();
+
+    async fn run(self) -> Result<Self::Output> {
+        let Self { pool, controller } = self;
+
+        let beacon_state = controller.preprocessed_state_at_current_slot()?;
+        let current_slot = controller.slot();
+        let current_epoch = misc::compute_epoch_at_slot::<P>(current_slot);
+        let mut target_epochs = HashMap::<ValidatorIndex, Epoch>::new();
+
+        for epoch in current_epoch.saturating_sub(1)..=current_epoch {
+            for attestation in pool.aggregate_attestations_by_epoch(epoch).await {
+                let data = attestation.data;
+
+                if data.slot + 1 >= current_slot {
+                    continue;
+                }
+
+                let on_head_chain = accessors::attestation_epoch(&beacon_state, data.target.epoch)
+                    .and_then(|epoch| accessors::get_block_root(&beacon_state, epoch))
+                    .is_ok_and(|root| root == data.target.root);
+
+                if !on_head_chain {
+                    continue;
+                }
+
+                let attesting_indices = accessors::get_attesting_indices(
+                    &beacon_state,
+                    data,
+                    &attestation.aggregation_bits,
+                )?;
+
+                for validator_index in attesting_indices {
+                    let target_epoch = target_epochs.entry(validator_index).or_default();
+                    *target_epoch = data.target.epoch.max(*target_epoch);
+                }
+            }
+        }
+
+        let latest_message_epochs = controller.latest_message_epochs(target_epochs.keys().copied());
+        let missing_votes = missing_votes(&target_epochs, latest_message_epochs);
+
+        if !missing_votes.is_empty() {
+            warn!(
+                "votes in attestation pool are missing from fork choice latest messages \
+                 (slot: {current_slot}, validators: [{}])",
+                missing_votes.iter().format(", "),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn missing_votes(
+    target_epochs: &HashMap<ValidatorIndex, Epoch>,
+    latest_message_epochs: HashMap<ValidatorIndex, Option<Epoch>>,
+) -> Vec<ValidatorIndex> {
+    latest_message_epochs
+        .into_iter()
+        .filter(|(validator_index, latest_epoch)| {
+            latest_epoch.map_or(true, |latest_epoch| {
+                latest_epoch < target_epochs[validator_index]
+            })
+        })
+        .map(|(validator_index, _)| validator_index)
+        .sorted()
+        .collect_vec()
+}
+
 pub struct ComputeProposerIndicesTask<P: Preset> {
     pub pool: Arc<Pool<P>>,
     pub beacon_state: Arc<BeaconState<P>>,
@@ -151,6 +238,7 @@ impl<P: Preset, W: Wait> PoolTask for PackProposableAttestationsTask<P, W> {
 pub struct InsertAttestationTask<P: Preset, W> {
     pub wait_group: W,
     pub pool: Arc<Pool<P>>,
+    pub committee_index: CommitteeIndex,
     pub attestation: Arc<Attestation<P>>,
     pub metrics: Option<Arc<Metrics>>,
 }
@@ -162,6 +250,7 @@ impl<P: Preset, W: Send + 'static> PoolTask for InsertAttestationTask<P, W> {
         let Self {
             wait_group,
             pool,
+            committee_index,
             attestation,
             metrics,
         } = self;
@@ -176,8 +265,9 @@ impl<P: Preset, W: Send + 'static> PoolTask for InsertAttestationTask<P, W> {
             signature,
         } = *attestation;
 
-        let singular_attestations = pool.singular_attestations(data).await;
-        let aggregates = pool.aggregates(data).await;
+        let key = (data, committee_index);
+        let singular_attestations = pool.singular_attestations(key).await;
+        let aggregates = pool.aggregates(key).await;
         let mut aggregates = aggregates.lock().await;
 
         if aggregation_bits.count_ones() > 1 || aggregates.is_empty() {
@@ -283,3 +373,26 @@ async fn pack_attestations_greedily<P: Preset>(
         &pool.aggregate_attestations_by_epoch(current_epoch).await,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_votes_include_validators_whose_latest_messages_are_older_or_absent() {
+        let target_epochs = HashMap::from([(0, 3), (1, 3), (2, 3), (3, 3)]);
+
+        let latest_message_epochs =
+            HashMap::from([(0, Some(3)), (1, Some(4)), (2, Some(2)), (3, None)]);
+
+        assert_eq!(missing_votes(&target_epochs, latest_message_epochs), [2, 3]);
+    }
+
+    #[test]
+    fn missing_votes_omit_validators_left_out_by_fork_choice() {
+        let target_epochs = HashMap::from([(0, 3), (1, 3)]);
+        let latest_message_epochs = HashMap::from([(0, Some(3))]);
+
+        assert!(missing_votes(&target_epochs, latest_message_epochs).is_empty());
+    }
+}
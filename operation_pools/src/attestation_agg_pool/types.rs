@@ -7,16 +7,25 @@ use bls::AggregateSignature;
 use ssz::BitList;
 use tokio::sync::{Mutex, RwLock};
 use types::{
-    phase0::containers::{Attestation, AttestationData},
+    phase0::{
+        containers::{Attestation, AttestationData},
+        primitives::CommitteeIndex,
+    },
     preset::Preset,
 };
 
+// Attestations are aggregated by their data and the committee they are for. The committee index is
+// not redundant because attestations in the next fork leave `AttestationData.index` at 0.
+// Only signatures over the same data may be aggregated, so attestations in that format share
+// aggregates with Phase 0 ones only for committee 0. See `PoolAttestation`.
+pub type AggregationKey = (AttestationData, CommitteeIndex);
+
 // Use `Mutex` instead of `RwLock` to avoid race conditions in `InsertAttestationTask`.
 // Don't let this comment fool you into thinking the locking is well thought out.
 // There may be other bugs.
-pub type AggregateMap<P> = HashMap<AttestationData, Arc<Mutex<Vec<Aggregate<P>>>>>;
+pub type AggregateMap<P> = HashMap<AggregationKey, Arc<Mutex<Vec<Aggregate<P>>>>>;
 
-pub type AttestationMap<P> = HashMap<AttestationData, Arc<RwLock<AttestationSet<P>>>>;
+pub type AttestationMap<P> = HashMap<AggregationKey, Arc<RwLock<AttestationSet<P>>>>;
 
 // Use `BTreeSet` to make attestation packing deterministic for snapshot testing.
 // This does not affect performance in our benchmarks.
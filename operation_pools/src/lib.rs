@@ -1,5 +1,5 @@
 pub use crate::{
-    attestation_agg_pool::{AttestationPacker, Manager as AttestationAggPool},
+    attestation_agg_pool::{AttestationPacker, Manager as AttestationAggPool, PoolAttestation},
     bls_to_execution_change_pool::{
         BlsToExecutionChangePool, Service as BlsToExecutionChangePoolService,
     },
@@ -11,11 +11,13 @@ pub use crate::{
 mod attestation_agg_pool {
     pub use attestation_packer::AttestationPacker;
     pub use manager::Manager;
+    pub use pool_attestation::PoolAttestation;
 
     mod attestation_packer;
     mod manager;
     mod max_clique;
     mod pool;
+    mod pool_attestation;
     mod tasks;
     mod types;
 }
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use anyhow::Result;
 use dedicated_executor::DedicatedExecutor;
 use eth1_api::RealController;
 use eth2_libp2p::GossipId;
-use fork_choice_control::{VerifyAggregateAndProofResult, VerifyAttestationResult};
+use fork_choice_control::{P2pMessage, VerifyAggregateAndProofResult, VerifyAttestationResult};
 use fork_choice_store::{AggregateAndProofAction, AttestationAction};
 use futures::{
     channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -27,24 +27,31 @@ use types::{
     config::Config,
     phase0::{
         containers::{AggregateAndProof, Attestation, SignedAggregateAndProof},
-        primitives::SubnetId,
+        primitives::{Slot, SubnetId},
     },
     preset::Preset,
 };
 
-use crate::messages::P2pToAttestationVerifier;
+use crate::messages::{P2pToAttestationVerifier, SubnetServiceToAttestationVerifier};
 
 const MAX_BATCH_SIZE: usize = 64;
 
+// Number of queued attestations past which only the ones we have a use for are verified.
+// The rest are ignored rather than rejected because there is nothing wrong with them.
+const OVERLOAD_THRESHOLD: usize = 16 * MAX_BATCH_SIZE;
+
 pub struct AttestationVerifier<P: Preset> {
     attestations: Vec<AttestationWithOrigin<P>>,
     aggregates: Vec<AggregateWithOrigin<P>>,
+    aggregation_duties: BTreeSet<(Slot, SubnetId)>,
     controller: RealController<P>,
     dedicated_executor: DedicatedExecutor,
     active_task_count: usize,
     max_active_tasks: usize,
     metrics: Option<Arc<Metrics>>,
+    p2p_tx: UnboundedSender<P2pMessage<P>>,
     p2p_to_verifier_rx: UnboundedReceiver<P2pToAttestationVerifier<P>>,
+    subnet_service_to_verifier_rx: UnboundedReceiver<SubnetServiceToAttestationVerifier>,
     task_to_verifier_rx: UnboundedReceiver<TaskMessage>,
     task_to_verifier_tx: UnboundedSender<TaskMessage>,
 }
@@ -55,19 +62,24 @@ impl<P: Preset> AttestationVerifier<P> {
         controller: RealController<P>,
         dedicated_executor: DedicatedExecutor,
         metrics: Option<Arc<Metrics>>,
+        p2p_tx: UnboundedSender<P2pMessage<P>>,
         p2p_to_verifier_rx: UnboundedReceiver<P2pToAttestationVerifier<P>>,
+        subnet_service_to_verifier_rx: UnboundedReceiver<SubnetServiceToAttestationVerifier>,
     ) -> Self {
         let (task_to_verifier_tx, task_to_verifier_rx) = mpsc::unbounded();
 
         Self {
             attestations: vec![],
             aggregates: vec![],
+            aggregation_duties: BTreeSet::new(),
             controller,
             dedicated_executor,
             active_task_count: 0,
             max_active_tasks: num_cpus::get(),
             metrics,
+            p2p_tx,
             p2p_to_verifier_rx,
+            subnet_service_to_verifier_rx,
             task_to_verifier_rx,
             task_to_verifier_tx,
         }
@@ -98,12 +110,23 @@ impl<P: Preset> AttestationVerifier<P> {
                             self.spawn_verify_batch_tasks();
                         }
                         P2pToAttestationVerifier::GossipAttestation(attestation, subnet_id, gossip_id) => {
-                            self.attestations.push(AttestationWithOrigin {
-                                attestation,
-                                subnet_id,
-                                gossip_id,
-                            });
-                            self.spawn_verify_batch_tasks();
+                            if self.should_shed_attestation(&attestation, subnet_id) {
+                                self.ignore_attestation(gossip_id);
+                            } else {
+                                self.attestations.push(AttestationWithOrigin {
+                                    attestation,
+                                    subnet_id,
+                                    gossip_id,
+                                });
+                                self.spawn_verify_batch_tasks();
+                            }
+                        }
+                    }
+                }
+                message = self.subnet_service_to_verifier_rx.select_next_some() => {
+                    match message {
+                        SubnetServiceToAttestationVerifier::AggregationDuties(aggregation_duties) => {
+                            self.aggregation_duties = aggregation_duties;
                         }
                     }
                 }
@@ -111,6 +134,37 @@ impl<P: Preset> AttestationVerifier<P> {
         }
     }
 
+    // Attestations that we need to aggregate or that vote for our head are always verified.
+    // The former are needed to perform validator duties. The latter are the most likely to
+    // affect fork choice and are cheap to verify because the target state is already cached.
+    fn should_shed_attestation(&self, attestation: &Attestation<P>, subnet_id: SubnetId) -> bool {
+        if self.attestations.len() < OVERLOAD_THRESHOLD {
+            return false;
+        }
+
+        let data = attestation.data;
+
+        if self.aggregation_duties.contains(&(data.slot, subnet_id)) {
+            return false;
+        }
+
+        data.beacon_block_root != self.controller.head_block_root().value
+    }
+
+    fn ignore_attestation(&self, gossip_id: GossipId) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_attestation_verifier_ignored_attestation();
+        }
+
+        if self
+            .p2p_tx
+            .unbounded_send(P2pMessage::Ignore(gossip_id))
+            .is_err()
+        {
+            debug!("send to p2p failed because the receiver was dropped");
+        }
+    }
+
     fn spawn_verify_batch_tasks(&mut self) {
         self.spawn_verify_attestation_batch_task();
         self.spawn_verify_aggregate_batch_task();
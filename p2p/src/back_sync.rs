@@ -4,6 +4,7 @@ use anyhow::{ensure, Result};
 use database::Database;
 use derive_more::Display;
 use eth1_api::RealController;
+use fork_choice_control::BackSyncStatus;
 use futures::channel::mpsc::UnboundedSender;
 use genesis::GenesisProvider;
 use log::{info, warn};
@@ -28,12 +29,16 @@ pub struct BackSync<P: Preset> {
 }
 
 impl<P: Preset> BackSync<P> {
-    pub fn load(database: &Database) -> Result<Option<Self>> {
-        let data = Data::find(database)?;
+    pub fn load(database: &Database, controller: &RealController<P>) -> Result<Option<Self>> {
+        let Some(mut data) = Data::find(database)? else {
+            return Ok(None);
+        };
+
+        data.resume_from_stored_status(controller)?;
 
         features::log!(DebugP2p, "loaded back sync: {data:?}");
 
-        Ok(data.map(Self::new))
+        Ok(Some(Self::new(data)))
     }
 
     pub fn new(data: Data) -> Self {
@@ -64,6 +69,10 @@ impl<P: Preset> BackSync<P> {
         self.data.is_finished()
     }
 
+    pub const fn status(&self) -> BackSyncStatus {
+        self.data.status()
+    }
+
     pub fn finish(&self, database: &Database) -> Result<()> {
         self.data.remove(database)
     }
@@ -151,11 +160,16 @@ impl<P: Preset> BackSync<P> {
                     );
                 }
 
-                // Store back synced blocks in fork choice store.
-                controller.store_back_sync_blocks(blocks)?;
+                let data = Data {
+                    current: checkpoint,
+                    ..self.data
+                };
+
+                // Store back synced blocks in fork choice store along with the progress made.
+                controller.store_back_sync_blocks(blocks, data.status())?;
 
                 // Update back sync progress in sync database.
-                self.data.current = checkpoint;
+                self.data = data;
                 self.save(database)?;
 
                 features::log!(DebugP2p, "back sync batch saved {checkpoint:?}");
@@ -235,6 +249,49 @@ impl Data {
         self.current == self.low
     }
 
+    const fn status(&self) -> BackSyncStatus {
+        BackSyncStatus {
+            earliest_available_slot: self.current.slot,
+            target_slot: self.low.slot,
+            verified_up_to_root: self.current.block_root,
+        }
+    }
+
+    // Blocks are stored before the sync database is updated, so the status in `Storage` may be
+    // ahead of `self` if the node was stopped in between.
+    fn resume_from_stored_status<P: Preset>(
+        &mut self,
+        controller: &RealController<P>,
+    ) -> Result<()> {
+        let Some(status) = controller.back_sync_status()? else {
+            return Ok(());
+        };
+
+        let BackSyncStatus {
+            earliest_available_slot,
+            target_slot,
+            verified_up_to_root,
+        } = status;
+
+        if target_slot != self.low.slot
+            || earliest_available_slot >= self.current.slot
+            || earliest_available_slot < self.low.slot
+        {
+            return Ok(());
+        }
+
+        if let Some(block) = controller.block_by_root(verified_up_to_root)? {
+            self.current = block.value.as_ref().into();
+
+            features::log!(
+                DebugP2p,
+                "resuming back sync from stored status: {status:?}"
+            );
+        }
+
+        Ok(())
+    }
+
     fn save(&self, database: &Database) -> Result<()> {
         database.put(self.db_key(), self.to_ssz()?)
     }
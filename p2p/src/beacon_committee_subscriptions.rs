@@ -1,8 +1,9 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
+use anyhow::Result;
 use helper_functions::misc;
 use types::{
-    phase0::primitives::{CommitteeIndex, Epoch, ValidatorIndex},
+    phase0::primitives::{CommitteeIndex, Epoch, Slot, SubnetId, ValidatorIndex},
     preset::Preset,
 };
 
@@ -28,6 +29,29 @@ impl BeaconCommitteeSubscriptions {
             .copied()
     }
 
+    /// Returns the slots and subnets in which own validators have to aggregate attestations.
+    pub fn aggregation_duties<P: Preset>(&self) -> Result<BTreeSet<(Slot, SubnetId)>> {
+        self.all()
+            .filter(|subscription| subscription.is_aggregator)
+            .map(|subscription| {
+                let BeaconCommitteeSubscription {
+                    committee_index,
+                    committees_at_slot,
+                    slot,
+                    ..
+                } = subscription;
+
+                let subnet_id = misc::compute_subnet_for_attestation::<P>(
+                    committees_at_slot,
+                    slot,
+                    committee_index,
+                )?;
+
+                Ok((slot, subnet_id))
+            })
+            .collect()
+    }
+
     pub fn update<P: Preset>(
         &mut self,
         subscriptions: impl IntoIterator<Item = BeaconCommitteeSubscription>,
@@ -44,3 +68,39 @@ impl BeaconCommitteeSubscriptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn aggregation_duties_only_include_aggregator_subscriptions() -> Result<()> {
+        let mut subscriptions = BeaconCommitteeSubscriptions::default();
+
+        subscriptions.update::<Minimal>([
+            BeaconCommitteeSubscription {
+                validator_index: 0,
+                committee_index: 1,
+                committees_at_slot: 2,
+                slot: 10,
+                is_aggregator: true,
+            },
+            BeaconCommitteeSubscription {
+                validator_index: 1,
+                committee_index: 0,
+                committees_at_slot: 2,
+                slot: 11,
+                is_aggregator: false,
+            },
+        ]);
+
+        assert_eq!(
+            subscriptions.aggregation_duties::<Minimal>()?,
+            BTreeSet::from([(10, 5)]),
+        );
+
+        Ok(())
+    }
+}
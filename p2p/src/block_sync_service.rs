@@ -1,5 +1,5 @@
 use core::{convert::Infallible as Never, fmt::Debug, time::Duration};
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::Arc, time::Instant};
 
 use anyhow::Result;
 use database::Database;
@@ -14,17 +14,16 @@ use futures::{
 };
 use genesis::GenesisProvider;
 use helper_functions::misc;
-use log::{error, info};
+use log::{debug, error, info, warn};
 use prometheus_metrics::Metrics;
 use ssz::{SszReadDefault, SszWrite as _};
 use std_ext::ArcExt as _;
+use strum::IntoStaticStr;
 use thiserror::Error;
 use tokio::select;
 use tokio_stream::wrappers::IntervalStream;
 use types::{
-    combined::SignedBeaconBlock,
     deneb::containers::BlobIdentifier,
-    nonstandard::Phase,
     phase0::primitives::{Slot, H256},
     preset::Preset,
 };
@@ -32,9 +31,10 @@ use types::{
 use crate::{
     back_sync::{BackSync, Data as BackSyncData, Error as BackSyncError, SyncCheckpoint},
     block_verification_pool::BlockVerificationPool,
-    messages::{ArchiverToSync, P2pToSync, SyncToApi, SyncToMetrics, SyncToP2p},
-    misc::RequestId,
+    messages::{ArchiverToSync, P2pToSync, SyncToApi, SyncToMetrics, SyncToP2p, WatchdogToSync},
+    misc::{RequestId, SyncDiagnostics},
     sync_manager::{SyncBatch, SyncManager, SyncTarget},
+    sync_progress::{self, SyncProgress},
 };
 
 const LATEST_FINALIZED_BACK_SYNC_CHECKPOINT_KEY: &str = "latest_finalized_back_sync_checkpoint";
@@ -44,7 +44,8 @@ const NETWORK_EVENT_INTERVAL: Duration = Duration::from_secs(1);
 #[error("ran out of request IDs")]
 struct Error;
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
 pub enum SyncDirection {
     Forward,
     Back,
@@ -56,6 +57,7 @@ pub struct Channels<P: Preset> {
     pub sync_to_p2p_tx: UnboundedSender<SyncToP2p>,
     pub sync_to_api_tx: UnboundedSender<SyncToApi>,
     pub sync_to_metrics_tx: Option<UnboundedSender<SyncToMetrics>>,
+    pub watchdog_to_sync_rx: Option<UnboundedReceiver<WatchdogToSync>>,
 }
 
 pub struct BlockSyncService<P: Preset> {
@@ -66,6 +68,8 @@ pub struct BlockSyncService<P: Preset> {
     block_verification_pool: BlockVerificationPool<P>,
     controller: RealController<P>,
     sync_manager: SyncManager,
+    forward_sync_progress: SyncProgress,
+    back_sync_progress: SyncProgress,
     metrics: Option<Arc<Metrics>>,
     next_request_id: usize,
     slot: Slot,
@@ -76,6 +80,7 @@ pub struct BlockSyncService<P: Preset> {
     sync_to_p2p_tx: UnboundedSender<SyncToP2p>,
     sync_to_api_tx: UnboundedSender<SyncToApi>,
     sync_to_metrics_tx: Option<UnboundedSender<SyncToMetrics>>,
+    watchdog_to_sync_rx: Option<UnboundedReceiver<WatchdogToSync>>,
     archiver_to_sync_tx: Option<UnboundedSender<ArchiverToSync>>,
     archiver_to_sync_rx: Option<UnboundedReceiver<ArchiverToSync>>,
 }
@@ -113,10 +118,13 @@ impl<P: Preset> BlockSyncService<P> {
 
                 if !back_sync_process.is_finished() {
                     back_sync_process.save(&db)?;
+
+                    controller
+                        .store_back_sync_blocks(core::iter::empty(), back_sync_process.status())?;
                 }
             }
 
-            back_sync = BackSync::load(&db)?;
+            back_sync = BackSync::load(&db, &controller)?;
 
             let (sync_tx, sync_rx) = futures::channel::mpsc::unbounded();
 
@@ -138,6 +146,7 @@ impl<P: Preset> BlockSyncService<P> {
             sync_to_p2p_tx,
             sync_to_api_tx,
             sync_to_metrics_tx,
+            watchdog_to_sync_rx,
         } = channels;
 
         // `is_back_synced` is set correctly only when back sync is enabled. Otherwise it is set
@@ -153,6 +162,8 @@ impl<P: Preset> BlockSyncService<P> {
             block_verification_pool: BlockVerificationPool::new(controller.clone_arc())?,
             controller,
             sync_manager: SyncManager::default(),
+            forward_sync_progress: SyncProgress::default(),
+            back_sync_progress: SyncProgress::default(),
             metrics,
             next_request_id: 0,
             slot,
@@ -165,6 +176,7 @@ impl<P: Preset> BlockSyncService<P> {
             sync_to_p2p_tx,
             sync_to_api_tx,
             sync_to_metrics_tx,
+            watchdog_to_sync_rx,
             archiver_to_sync_tx,
             archiver_to_sync_rx,
         };
@@ -203,7 +215,7 @@ impl<P: Preset> BlockSyncService<P> {
                                     back_sync.data(),
                                 );
 
-                                if let Some(sync) = BackSync::load(database)? {
+                                if let Some(sync) = BackSync::load(database, &self.controller)? {
                                     self.back_sync = Some(sync);
                                     self.request_blobs_and_blocks_if_ready()?;
                                 } else {
@@ -230,6 +242,18 @@ impl<P: Preset> BlockSyncService<P> {
                     }
                 },
 
+                message = match self.watchdog_to_sync_rx.as_mut() {
+                    Some(receiver) => Either::Left(receiver.select_next_some()),
+                    None => Either::Right(futures::future::pending()),
+                }, if self.watchdog_to_sync_rx.is_some() => match message {
+                    WatchdogToSync::RequestDiagnostics(sender) => {
+                        if sender.send(self.diagnostics()).is_err() {
+                            debug!("send to watchdog failed because the receiver was dropped");
+                        }
+                    }
+                    WatchdogToSync::Reset => self.reset()?,
+                },
+
                 message = self.p2p_to_sync_rx.select_next_some() => {
                     match message {
                         P2pToSync::FinalizedEpoch(epoch) => {
@@ -239,10 +263,7 @@ impl<P: Preset> BlockSyncService<P> {
                         }
                         P2pToSync::HeadState(state) => {
                             if !Feature::DisableBlockVerificationPool.is_enabled() {
-                                tokio::task::block_in_place(|| {
-                                    self.block_verification_pool
-                                        .verify_and_process_blocks(&state)
-                                });
+                                self.block_verification_pool.verify_and_process_blocks(&state);
                             }
                         }
                         P2pToSync::Slot(slot) => {
@@ -251,6 +272,9 @@ impl<P: Preset> BlockSyncService<P> {
                             if let Some(metrics) = self.metrics.as_ref() {
                                 self.sync_manager.track_collection_metrics(metrics);
                             }
+
+                            self.report_slow_peers();
+                            self.report_sync_progress();
                         }
                         P2pToSync::AddPeer(peer_id, status) => {
                             self.sync_manager.add_peer(peer_id, status);
@@ -259,9 +283,12 @@ impl<P: Preset> BlockSyncService<P> {
                         }
                         P2pToSync::RemovePeer(peer_id) => {
                             let batches_to_retry = self.sync_manager.remove_peer(&peer_id);
+                            self.sync_manager.remove_peer_request_stats(&peer_id);
                             self.retry_sync_batches(batches_to_retry)?;
                         }
                         P2pToSync::RequestFailed(peer_id) => {
+                            self.sync_manager.request_failed(peer_id);
+
                             if !self.is_forward_synced {
                                 let batches_to_retry = self.sync_manager.remove_peer(&peer_id);
                                 self.retry_sync_batches(batches_to_retry)?;
@@ -280,19 +307,24 @@ impl<P: Preset> BlockSyncService<P> {
                             self.controller.on_requested_blob_sidecar(blob_sidecar, block_seen, peer_id);
                         }
                         P2pToSync::RequestedBlock((block, peer_id, request_id)) => {
+                            self.sync_manager.block_received(request_id);
+
                             match self
                                 .sync_manager
                                 .request_direction(request_id)
                                 .unwrap_or(self.sync_direction)
                             {
                                 SyncDirection::Forward => {
-                                    if should_push_block_in_verification_pool(&block) {
-                                        self.block_verification_pool.push(block);
+                                    if !Feature::DisableBlockVerificationPool.is_enabled() {
+                                        self.block_verification_pool.push(block, Some(peer_id));
                                     } else {
                                         self.controller.on_requested_block(block, Some(peer_id));
                                     }
                                 }
                                 SyncDirection::Back => {
+                                    self.sync_manager
+                                        .back_sync_block_received(request_id, block.message().slot());
+
                                     if let Some(back_sync) = self.back_sync.as_mut() {
                                         back_sync.push_block(block);
                                     }
@@ -309,8 +341,19 @@ impl<P: Preset> BlockSyncService<P> {
                         }
                         P2pToSync::BlocksByRangeRequestFinished(request_id) => {
                             let request_direction = self.sync_manager.request_direction(request_id);
+                            let started_at = self.sync_manager.block_request_started_at(request_id);
+
+                            if let Some((direction, started_at)) = request_direction.zip(started_at) {
+                                self.sync_progress(direction)
+                                    .record_batch_latency(started_at.elapsed());
+                            }
 
-                            self.sync_manager.blocks_by_range_request_finished(request_id);
+                            let missing_batch = self
+                                .sync_manager
+                                .blocks_by_range_request_finished::<P>(request_id);
+
+                            // Request blocks the peer did not have from peers that may have them.
+                            self.retry_sync_batches(missing_batch.into_iter().collect())?;
 
                             if request_direction == Some(SyncDirection::Back) {
                                 // aka batch finished
@@ -330,7 +373,8 @@ impl<P: Preset> BlockSyncService<P> {
                                                     BackSyncError::FinalCheckpointMismatch { .. }
                                                 ) = error.downcast_ref() {
                                                     back_sync.finish(database)?;
-                                                    self.back_sync = BackSync::load(database)?;
+                                                    self.back_sync =
+                                                        BackSync::load(database, &self.controller)?;
                                                 }
                                             }
                                         }
@@ -518,7 +562,9 @@ impl<P: Preset> BlockSyncService<P> {
 
         let request_id = self.request_id()?;
 
-        let Some(peer_id) = peer_id.or_else(|| self.sync_manager.random_peer()) else {
+        let Some(peer_id) =
+            peer_id.or_else(|| self.sync_manager.random_peer_for_blobs(&identifiers))
+        else {
             return Ok(());
         };
 
@@ -562,6 +608,97 @@ impl<P: Preset> BlockSyncService<P> {
         Ok(())
     }
 
+    fn diagnostics(&mut self) -> SyncDiagnostics {
+        SyncDiagnostics {
+            unverified_blocks: self.block_verification_pool.unverified_block_count(),
+            is_forward_synced: self.is_forward_synced,
+            ..self.sync_manager.diagnostics()
+        }
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        warn!("resetting block sync");
+
+        for peer_id in self.sync_manager.reset() {
+            self.request_peer_status(peer_id)?;
+        }
+
+        self.request_blobs_and_blocks_if_ready()
+    }
+
+    fn report_slow_peers(&mut self) {
+        for peer_id in self.sync_manager.take_slow_peers() {
+            SyncToP2p::ReportSlowPeer(peer_id).send(&self.sync_to_p2p_tx);
+        }
+
+        SyncToP2p::PeerRequestStats(self.sync_manager.peer_request_stats())
+            .send(&self.sync_to_p2p_tx);
+    }
+
+    fn report_sync_progress(&mut self) {
+        let (direction, slot, target_slot) = match self.sync_direction {
+            SyncDirection::Forward if !self.is_forward_synced => (
+                SyncDirection::Forward,
+                self.controller.head_slot(),
+                self.slot,
+            ),
+            SyncDirection::Back => match self.back_sync.as_ref() {
+                Some(back_sync) if !back_sync.is_finished() => (
+                    SyncDirection::Back,
+                    back_sync.current_slot(),
+                    back_sync.low_slot(),
+                ),
+                _ => return,
+            },
+            SyncDirection::Forward => return,
+        };
+
+        let remaining_slots = slot.abs_diff(target_slot);
+        let progress = self.sync_progress(direction);
+
+        progress.record_slot(slot, Instant::now());
+
+        let milli_slots_per_second = progress.milli_slots_per_second();
+        let time_to_sync = progress.time_to_sync(remaining_slots);
+        let average_batch_latency = progress.average_batch_latency();
+
+        let direction_name: &str = direction.into();
+
+        info!(
+            "{direction_name} sync: {remaining_slots} slots left (slot {slot}, target {target_slot}), \
+             {} slots/s, ETA {}, average batch latency {}",
+            milli_slots_per_second
+                .map_or_else(|| "unknown".to_owned(), sync_progress::format_milli_rate),
+            time_to_sync.map_or_else(|| "unknown".to_owned(), sync_progress::format_duration),
+            average_batch_latency
+                .map_or_else(|| "unknown".to_owned(), |latency| format!("{latency:.1?}")),
+        );
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_sync_progress(
+                direction_name,
+                milli_slots_per_second,
+                time_to_sync,
+                average_batch_latency,
+            );
+        }
+    }
+
+    fn finish_sync_progress(&mut self, direction: SyncDirection) {
+        self.sync_progress(direction).reset();
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_sync_progress(direction.into(), None, None, None);
+        }
+    }
+
+    fn sync_progress(&mut self, direction: SyncDirection) -> &mut SyncProgress {
+        match direction {
+            SyncDirection::Forward => &mut self.forward_sync_progress,
+            SyncDirection::Back => &mut self.back_sync_progress,
+        }
+    }
+
     fn request_peer_status(&mut self, peer_id: PeerId) -> Result<()> {
         SyncToP2p::RequestPeerStatus(self.request_id()?, peer_id).send(&self.sync_to_p2p_tx);
         Ok(())
@@ -589,6 +726,8 @@ impl<P: Preset> BlockSyncService<P> {
         if was_back_synced != is_back_synced && is_back_synced {
             info!("back sync completed");
 
+            self.finish_sync_progress(SyncDirection::Back);
+
             self.sync_manager.cache_clear();
             self.sync_direction = SyncDirection::Forward;
         }
@@ -612,6 +751,8 @@ impl<P: Preset> BlockSyncService<P> {
         }
 
         if !was_forward_synced && is_forward_synced {
+            self.finish_sync_progress(SyncDirection::Forward);
+
             SyncToP2p::SubscribeToCoreTopics.send(&self.sync_to_p2p_tx);
 
             if self.back_sync.is_some() {
@@ -634,10 +775,6 @@ impl<P: Preset> BlockSyncService<P> {
     }
 }
 
-fn should_push_block_in_verification_pool<P: Preset>(block: &SignedBeaconBlock<P>) -> bool {
-    block.phase() == Phase::Phase0 && !Feature::DisableBlockVerificationPool.is_enabled()
-}
-
 fn get<V: SszReadDefault>(database: &Database, key: impl AsRef<[u8]>) -> Result<Option<V>> {
     database
         .get(key)?
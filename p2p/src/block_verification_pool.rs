@@ -1,8 +1,12 @@
 use core::ops::Range;
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
 use anyhow::Result;
 use eth1_api::Eth1ExecutionEngine;
+use eth2_libp2p::PeerId;
 use execution_engine::ExecutionEngine;
 use fork_choice_control::{Controller, Wait};
 use helper_functions::{
@@ -11,6 +15,7 @@ use helper_functions::{
 };
 use itertools::Itertools as _;
 use log::{debug, warn};
+use parking_lot::{Condvar, Mutex};
 use rayon::{
     iter::{IndexedParallelIterator as _, IntoParallelIterator as _, ParallelIterator as _},
     ThreadPool, ThreadPoolBuilder,
@@ -19,15 +24,21 @@ use std_ext::ArcExt as _;
 use transition_functions::combined::{self, PhaseError};
 use types::{
     combined::{BeaconState, SignedBeaconBlock},
-    phase0::primitives::{Epoch, Slot},
+    phase0::primitives::{Epoch, Slot, H256},
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
+// Signatures of blocks are verified on a separate thread pool while fork choice runs state
+// transitions for blocks submitted earlier. Verification jobs may finish in any order, but blocks
+// are submitted to fork choice in the order the jobs were started. Submitting blocks out of order
+// would make fork choice delay them until their parents are processed.
 pub struct BlockVerificationPool<P: Preset, E = Arc<Eth1ExecutionEngine<P>>, W: Wait = ()> {
     controller: Arc<Controller<P, E, W>>,
     thread_pool: ThreadPool,
-    unverified_blocks: BTreeMap<Slot, Vec<Arc<SignedBeaconBlock<P>>>>,
+    unverified_blocks: BTreeMap<Slot, Vec<(Arc<SignedBeaconBlock<P>>, Option<PeerId>)>>,
+    commit_stage: Arc<(Mutex<CommitStage<VerifiedBlock<P>>>, Condvar)>,
+    next_job: u64,
 }
 
 impl<P, E, W> BlockVerificationPool<P, E, W>
@@ -45,14 +56,20 @@ where
             controller,
             thread_pool,
             unverified_blocks: BTreeMap::new(),
+            commit_stage: Arc::default(),
+            next_job: 0,
         })
     }
 
-    pub fn push(&mut self, block: Arc<SignedBeaconBlock<P>>) {
+    pub fn push(&mut self, block: Arc<SignedBeaconBlock<P>>, peer_id: Option<PeerId>) {
         self.unverified_blocks
             .entry(block.message().slot())
             .or_default()
-            .push(block);
+            .push((block, peer_id));
+    }
+
+    pub fn unverified_block_count(&self) -> usize {
+        self.unverified_blocks.values().map(Vec::len).sum()
     }
 
     pub fn prune_outdated_blocks(&mut self, finalized_epoch: Epoch) {
@@ -67,12 +84,13 @@ where
     // - This will stall if two epochs' worth of blocks are not enough to update the head and no
     //   other mechanism is used to assist syncing. This may happen if the fork choice anchor is a
     //   non-genesis block or if the chain has many consecutive empty slots.
-    // - Only up to two epochs' worth of blocks can be verified before pausing to wait for the state
-    //   transition, which reduces performance. It's most noticeable when the number of logical CPU
-    //   cores does not divide 32.
+    // - Shufflings are only known one epoch ahead, so blocks can be verified at most one epoch
+    //   ahead of the head state. Verification of later blocks waits for the state transition.
     // The correct approach would be to verify blocks in the fork choice store, either all of them
     // with a single call to `multi_verify` or in parallel. We have tried doing so and achieved a
     // speedup in benchmarks, but it made the fork choice store significantly more complicated.
+    //
+    // Verification is done in the background. Use `wait_for_verification` to wait for it.
     pub fn verify_and_process_blocks(&mut self, head_state: &Arc<BeaconState<P>>) {
         let head_state_epoch = misc::compute_epoch_at_slot::<P>(head_state.slot());
         let next_state_epoch = head_state_epoch + 1;
@@ -86,58 +104,112 @@ where
             return;
         }
 
-        let mut verifiable_blocks_by_epoch = vec![(head_state.clone_arc(), head_epoch_blocks)];
+        let job = self.next_job;
+        let controller = self.controller.clone_arc();
+        let commit_stage = self.commit_stage.clone_arc();
+        let head_state = head_state.clone_arc();
 
-        if !next_epoch_blocks.is_empty() {
-            // We only need beacon committees from the next epoch to validate signatures.
-            let mut state = head_state.clone_arc();
+        self.next_job += 1;
 
-            *state.slot_mut() = misc::compute_start_slot_at_epoch::<P>(next_state_epoch);
-            state.cache_mut().advance_epoch();
+        self.thread_pool.spawn(move || {
+            let mut verifiable_blocks_by_epoch = vec![(head_state.clone_arc(), head_epoch_blocks)];
 
-            verifiable_blocks_by_epoch.push((state, next_epoch_blocks));
-        }
+            if !next_epoch_blocks.is_empty() {
+                // We only need beacon committees from the next epoch to validate signatures.
+                let mut state = head_state;
 
-        let config = self.controller.chain_config();
+                *state.slot_mut() = misc::compute_start_slot_at_epoch::<P>(next_state_epoch);
+                state.cache_mut().advance_epoch();
 
-        self.thread_pool.install(|| {
-            verifiable_blocks_by_epoch
+                verifiable_blocks_by_epoch.push((state, next_epoch_blocks));
+            }
+
+            let config = controller.chain_config();
+
+            // `collect` preserves the order of blocks, so they remain sorted by slot.
+            let verified_blocks = verifiable_blocks_by_epoch
                 .into_par_iter()
                 .flat_map(|(state, blocks)| rayon::iter::repeatn(state, blocks.len()).zip(blocks))
-                .for_each(|(state, block)| {
+                .map(|(state, (block, peer_id))| {
                     let verifier =
                         MultiVerifier::new([VerifierOption::SkipBlockSyncAggregateSignature]);
 
                     match combined::verify_signatures(config, &state, &block, verifier) {
-                        Ok(()) => self.controller.on_semi_verified_block(block),
+                        Ok(()) => VerifiedBlock::SemiVerified(block),
                         Err(error) if error.is::<PhaseError>() => {
-                            // If phases of the block and state do not match (this can happen around
-                            // a phase boundary), fall back to `Controller::on_requested_block`.
+                            // If phases of the block and state do not match (this can happen
+                            // around a phase boundary), fall back to
+                            // `Controller::on_requested_block`.
                             debug!("{error}");
-                            self.controller.on_requested_block(block, None);
+                            VerifiedBlock::Unverified(block, peer_id)
                         }
                         Err(error) => {
                             warn!(
                                 "block signature verification failed \
                                  (block: {block:?}, error: {error:?})",
                             );
-                            self.controller.on_requested_block(block, None);
+                            VerifiedBlock::Unverified(block, peer_id)
                         }
                     }
-                });
+                })
+                .collect::<Vec<_>>();
+
+            let (stage, condvar) = &*commit_stage;
+
+            // Blocks are submitted while holding the lock to prevent jobs from interleaving them.
+            let mut stage = stage.lock();
+
+            for verified_block in stage.complete(job, verified_blocks) {
+                match verified_block {
+                    VerifiedBlock::SemiVerified(block) => controller.on_semi_verified_block(block),
+                    VerifiedBlock::Unverified(block, peer_id) => {
+                        controller.on_requested_block(block, peer_id)
+                    }
+                }
+            }
+
+            drop(stage);
+
+            condvar.notify_all();
         });
     }
 
+    /// Blocks until all blocks passed to [`Self::verify_and_process_blocks`] have been verified
+    /// and submitted to fork choice.
+    ///
+    /// This does not wait for fork choice to process the blocks.
+    pub fn wait_for_verification(&self) {
+        let (stage, condvar) = &*self.commit_stage;
+        let mut stage = stage.lock();
+
+        while stage.next_job < self.next_job {
+            condvar.wait(&mut stage);
+        }
+    }
+
     // Send older blocks to fork choice to be verified and processed there
     fn process_older_blocks(&mut self, head_epoch: Epoch) {
         let end_slot = misc::compute_start_slot_at_epoch::<P>(head_epoch) + 1;
 
-        for block in self.take_blocks_by_slot_range(0..end_slot) {
-            self.controller.on_requested_block(block, None);
+        let blocks = self
+            .take_blocks_by_slot_range(0..end_slot)
+            .map(|(block, peer_id)| {
+                let message = block.message();
+                let slot = message.slot();
+                let block_root = message.hash_tree_root();
+                let parent_root = message.parent_root();
+                (slot, block_root, parent_root, (block, peer_id))
+            });
+
+        for (block, peer_id) in order_by_parent_linkage(blocks) {
+            self.controller.on_requested_block(block, peer_id);
         }
     }
 
-    fn take_blocks_by_epoch(&mut self, epoch: Epoch) -> Vec<Arc<SignedBeaconBlock<P>>> {
+    fn take_blocks_by_epoch(
+        &mut self,
+        epoch: Epoch,
+    ) -> Vec<(Arc<SignedBeaconBlock<P>>, Option<PeerId>)> {
         let slot_range = Self::verifiable_slot_range(epoch);
 
         let blocks = self
@@ -157,7 +229,7 @@ where
     fn take_blocks_by_slot_range(
         &mut self,
         slot_range: Range<Slot>,
-    ) -> impl Iterator<Item = Arc<SignedBeaconBlock<P>>> {
+    ) -> impl Iterator<Item = (Arc<SignedBeaconBlock<P>>, Option<PeerId>)> {
         let mut taken = self.unverified_blocks.split_off(&slot_range.start);
 
         self.unverified_blocks
@@ -172,3 +244,135 @@ where
         start + 1..end + 1
     }
 }
+
+// Orders items by slot, except that items whose parents are among them are moved after their
+// parents. Fork choice delays blocks submitted before their parents, so this avoids a round trip
+// through its delayed block queue even if peers send blocks that are not sorted or are invalid.
+fn order_by_parent_linkage<T>(items: impl IntoIterator<Item = (Slot, H256, H256, T)>) -> Vec<T> {
+    let mut items = items.into_iter().collect_vec();
+
+    items.sort_by_key(|(slot, _, _, _)| *slot);
+
+    let roots = items
+        .iter()
+        .map(|(_, root, _, _)| *root)
+        .collect::<HashSet<_>>();
+
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut emitted_roots = HashSet::new();
+    let mut waiting_for_parent = HashMap::<_, Vec<_>>::new();
+
+    for item in items {
+        let (_, _, parent_root, _) = item;
+
+        if roots.contains(&parent_root) && !emitted_roots.contains(&parent_root) {
+            waiting_for_parent
+                .entry(parent_root)
+                .or_default()
+                .push(item);
+            continue;
+        }
+
+        let mut stack = vec![item];
+
+        while let Some((_, root, _, value)) = stack.pop() {
+            ordered.push(value);
+            emitted_roots.insert(root);
+
+            if let Some(children) = waiting_for_parent.remove(&root) {
+                stack.extend(children.into_iter().rev());
+            }
+        }
+    }
+
+    // Items can only be left over if their ancestry contains a cycle, which would require a hash
+    // collision. Keep them anyway so that fork choice gets to reject them.
+    let mut left_over = waiting_for_parent.into_values().flatten().collect_vec();
+    left_over.sort_by_key(|(slot, _, _, _)| *slot);
+    ordered.extend(left_over.into_iter().map(|(_, _, _, value)| value));
+
+    ordered
+}
+
+enum VerifiedBlock<P: Preset> {
+    SemiVerified(Arc<SignedBeaconBlock<P>>),
+    // The peer is kept so that it can be reported if the block turns out to be invalid.
+    Unverified(Arc<SignedBeaconBlock<P>>, Option<PeerId>),
+}
+
+// Reorders the output of verification jobs to match the order in which the jobs were started.
+struct CommitStage<T> {
+    next_job: u64,
+    completed: BTreeMap<u64, Vec<T>>,
+}
+
+impl<T> Default for CommitStage<T> {
+    fn default() -> Self {
+        Self {
+            next_job: 0,
+            completed: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> CommitStage<T> {
+    // Returns items that can be committed now, including ones left over by jobs finished earlier.
+    fn complete(&mut self, job: u64, items: Vec<T>) -> Vec<T> {
+        self.completed.insert(job, items);
+
+        let mut ready = vec![];
+
+        while let Some(items) = self.completed.remove(&self.next_job) {
+            ready.extend(items);
+            self.next_job += 1;
+        }
+
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_stage_releases_items_in_job_order() {
+        let mut stage = CommitStage::default();
+
+        assert!(stage.complete(1, vec![3, 4]).is_empty());
+        assert!(stage.complete(2, vec![5]).is_empty());
+        assert_eq!(stage.complete(0, vec![1, 2]), [1, 2, 3, 4, 5]);
+        assert!(stage.complete(4, vec![7]).is_empty());
+        assert_eq!(stage.complete(3, vec![6]), [6, 7]);
+        assert_eq!(stage.next_job, 5);
+    }
+
+    #[test]
+    fn order_by_parent_linkage_sorts_out_of_order_blocks() {
+        let root = H256::repeat_byte;
+
+        // Block `b` has a lower slot than its parent `a`. That makes it invalid, but it should
+        // still be submitted after `a` so that fork choice rejects it right away.
+        let blocks = [
+            (5, root(4), root(2), 'd'),
+            (1, root(2), root(1), 'b'),
+            (2, root(3), root(9), 'c'),
+            (3, root(1), root(0), 'a'),
+        ];
+
+        assert_eq!(order_by_parent_linkage(blocks), ['c', 'a', 'b', 'd']);
+    }
+
+    #[test]
+    fn order_by_parent_linkage_keeps_blocks_with_unknown_parents_in_slot_order() {
+        let root = H256::repeat_byte;
+
+        let blocks = [
+            (3, root(3), root(13), 'c'),
+            (1, root(1), root(11), 'a'),
+            (2, root(2), root(12), 'b'),
+        ];
+
+        assert_eq!(order_by_parent_linkage(blocks), ['a', 'b', 'c']);
+    }
+}
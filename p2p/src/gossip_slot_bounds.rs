@@ -0,0 +1,169 @@
+// Bounds on the slots of gossip objects relative to the current slot.
+//
+// Objects slightly ahead of the current slot are normal because clocks of nodes are never
+// perfectly synchronized. Fork choice already delays blocks, blob sidecars and attestations until
+// their slot. Sync committee messages and contributions are only valid in their own slot,
+// so they are held in a `GossipQuarantine` until it arrives.
+//
+// Objects further away than the configured tolerance cannot become useful soon enough to be worth
+// the memory and verification time. They are ignored before any other processing.
+
+use std::collections::BTreeMap;
+
+use strum::AsRefStr;
+use types::phase0::primitives::Slot;
+
+// Enough to hold several slots of sync committee messages for every subnet on mainnet.
+const MAX_QUARANTINED_OBJECTS: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GossipSlotTolerance {
+    pub future_slots: u64,
+    pub past_slots: u64,
+}
+
+impl GossipSlotTolerance {
+    #[must_use]
+    pub fn check(self, kind: GossipObjectKind, slot: Slot, current_slot: Slot) -> SlotCheck {
+        if current_slot < slot {
+            if slot - current_slot > self.future_slots {
+                return SlotCheck::TooFarInFuture;
+            }
+
+            return SlotCheck::Early;
+        }
+
+        if kind.has_past_bound() && current_slot - slot > self.past_slots {
+            return SlotCheck::TooFarInPast;
+        }
+
+        SlotCheck::Timely
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum GossipObjectKind {
+    BeaconBlock,
+    BlobSidecar,
+    AggregateAndProof,
+    Attestation,
+    SyncCommitteeMessage,
+    SyncCommitteeContribution,
+}
+
+impl GossipObjectKind {
+    // Blocks and blob sidecars remain relevant until they are finalized.
+    // Fork choice ignores them after that, so they need no other lower bound.
+    const fn has_past_bound(self) -> bool {
+        !matches!(self, Self::BeaconBlock | Self::BlobSidecar)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum SlotCheck {
+    Timely,
+    Early,
+    TooFarInFuture,
+    TooFarInPast,
+}
+
+impl SlotCheck {
+    #[must_use]
+    pub const fn is_in_bounds(self) -> bool {
+        matches!(self, Self::Timely | Self::Early)
+    }
+}
+
+pub struct GossipQuarantine<T> {
+    objects: BTreeMap<Slot, Vec<T>>,
+    len: usize,
+}
+
+impl<T> Default for GossipQuarantine<T> {
+    fn default() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> GossipQuarantine<T> {
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Holds `object` until `slot`. Returns it back if the quarantine is full.
+    pub fn insert(&mut self, slot: Slot, object: T) -> Result<(), T> {
+        if self.len >= MAX_QUARANTINED_OBJECTS {
+            return Err(object);
+        }
+
+        self.objects.entry(slot).or_default().push(object);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes objects for slots up to and including `slot`.
+    pub fn take_due(&mut self, slot: Slot) -> Vec<T> {
+        let later = self.objects.split_off(&(slot + 1));
+        let due = core::mem::replace(&mut self.objects, later);
+        let due = due.into_values().flatten().collect::<Vec<_>>();
+
+        self.len -= due.len();
+
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    const TOLERANCE: GossipSlotTolerance = GossipSlotTolerance {
+        future_slots: 2,
+        past_slots: 32,
+    };
+
+    #[test_case(GossipObjectKind::Attestation, 100 => SlotCheck::Timely)]
+    #[test_case(GossipObjectKind::Attestation, 102 => SlotCheck::Early)]
+    #[test_case(GossipObjectKind::BeaconBlock, 103 => SlotCheck::TooFarInFuture)]
+    #[test_case(GossipObjectKind::SyncCommitteeMessage, 68 => SlotCheck::Timely)]
+    #[test_case(GossipObjectKind::SyncCommitteeMessage, 67 => SlotCheck::TooFarInPast)]
+    #[test_case(GossipObjectKind::BlobSidecar, 0 => SlotCheck::Timely)]
+    fn check_compares_slot_with_tolerance(kind: GossipObjectKind, slot: Slot) -> SlotCheck {
+        TOLERANCE.check(kind, slot, 100)
+    }
+
+    #[test]
+    fn quarantine_releases_objects_when_their_slot_arrives() {
+        let mut quarantine = GossipQuarantine::default();
+
+        assert_eq!(quarantine.insert(11, 'b'), Ok(()));
+        assert_eq!(quarantine.insert(12, 'c'), Ok(()));
+        assert_eq!(quarantine.insert(11, 'a'), Ok(()));
+
+        assert!(quarantine.take_due(10).is_empty());
+        assert_eq!(quarantine.take_due(11), ['b', 'a']);
+        assert_eq!(quarantine.len(), 1);
+        assert_eq!(quarantine.take_due(20), ['c']);
+        assert_eq!(quarantine.len(), 0);
+    }
+
+    #[test]
+    fn quarantine_rejects_objects_when_full() {
+        let mut quarantine = GossipQuarantine::default();
+
+        for index in 0..MAX_QUARANTINED_OBJECTS {
+            assert_eq!(quarantine.insert(1, index), Ok(()));
+        }
+
+        assert_eq!(quarantine.insert(1, 0), Err(0));
+    }
+}
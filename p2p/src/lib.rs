@@ -4,13 +4,15 @@ pub use crate::{
     attestation_verifier::AttestationVerifier,
     block_sync_service::{BlockSyncService, Channels as BlockSyncServiceChannels},
     block_verification_pool::BlockVerificationPool,
+    gossip_slot_bounds::GossipSlotTolerance,
     messages::{
         ApiToP2p, P2pToSlasher, P2pToValidator, SubnetServiceToP2p, SyncToApi, SyncToMetrics,
-        ToSubnetService, ValidatorToP2p,
+        ToSubnetService, ValidatorToP2p, WatchdogToSync,
     },
-    misc::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
+    misc::{BeaconCommitteeSubscription, SyncCommitteeSubscription, SyncDiagnostics},
     network::{Channels, Network},
     network_api::{NodeIdentity, NodePeer, NodePeerCount, NodePeersQuery},
+    reachability::NodeReachability,
     subnet_service::SubnetService,
 };
 
@@ -20,12 +22,18 @@ mod back_sync;
 mod beacon_committee_subscriptions;
 mod block_sync_service;
 mod block_verification_pool;
+mod gossip_slot_bounds;
 mod messages;
 mod misc;
 mod network;
 mod network_api;
+mod peer_request_stats;
+mod protocol_versions;
 mod range_and_root_requests;
+mod reachability;
+mod seen_gossip_digests;
 mod subnet_service;
 mod sync_committee_subnets;
 mod sync_manager;
+mod sync_progress;
 mod upnp;
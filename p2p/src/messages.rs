@@ -1,9 +1,12 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 
 use anyhow::Result;
 use bls::PublicKeyBytes;
 use eth2_libp2p::{
-    rpc::{GoodbyeReason, StatusMessage},
+    rpc::{GoodbyeReason, RPCResponseErrorCode, StatusMessage},
     types::{EnrForkId, GossipKind},
     GossipId, GossipTopic, MessageAcceptance, NetworkEvent, PeerAction, PeerId, PeerRequestId,
     PubsubMessage, ReportSource, Request, Response, Subnet, SubnetDiscovery,
@@ -30,9 +33,11 @@ use types::{
 use crate::{
     misc::{
         AttestationSubnetActions, BeaconCommitteeSubscription, RequestId,
-        SyncCommitteeSubnetAction, SyncCommitteeSubscription,
+        SyncCommitteeSubnetAction, SyncCommitteeSubscription, SyncDiagnostics,
     },
     network_api::{NodeIdentity, NodePeer, NodePeerCount, NodePeersQuery},
+    peer_request_stats::PeerRequestStats,
+    reachability::NodeReachability,
 };
 
 pub enum P2pToAttestationVerifier<P: Preset> {
@@ -86,6 +91,9 @@ pub enum ApiToP2p<P: Preset> {
     RequestPeer(PeerId, #[serde(skip)] Sender<Option<NodePeer>>),
     RequestPeerCount(#[serde(skip)] Sender<NodePeerCount>),
     RequestPeers(NodePeersQuery, #[serde(skip)] Sender<Vec<NodePeer>>),
+    RequestPeersWithRequestStats(NodePeersQuery, #[serde(skip)] Sender<Vec<NodePeer>>),
+    RequestPeerClients(#[serde(skip)] Sender<BTreeMap<String, u64>>),
+    RequestReachability(#[serde(skip)] Sender<NodeReachability>),
 }
 
 impl<P: Preset> ApiToP2p<P> {
@@ -121,8 +129,23 @@ impl SyncToMetrics {
     }
 }
 
+pub enum WatchdogToSync {
+    RequestDiagnostics(Sender<SyncDiagnostics>),
+    Reset,
+}
+
+impl WatchdogToSync {
+    pub fn send(self, tx: &UnboundedSender<Self>) {
+        if tx.unbounded_send(self).is_err() {
+            debug!("send to block sync service failed because the receiver was dropped");
+        }
+    }
+}
+
 pub enum SyncToP2p {
+    PeerRequestStats(HashMap<PeerId, PeerRequestStats>),
     PruneReceivedBlocks,
+    ReportSlowPeer(PeerId),
     RequestBlobsByRange(RequestId, PeerId, Slot, u64),
     RequestBlobsByRoot(RequestId, PeerId, Vec<BlobIdentifier>),
     RequestBlocksByRange(RequestId, PeerId, Slot, u64),
@@ -209,6 +232,7 @@ pub enum ServiceInboundMessage<P: Preset> {
     Publish(PubsubMessage<P>),
     ReportPeer(PeerId, PeerAction, ReportSource, &'static str),
     ReportMessageValidationResult(GossipId, MessageAcceptance),
+    SendErrorResponse(PeerId, PeerRequestId, RPCResponseErrorCode, String),
     SendRequest(PeerId, RequestId, Request),
     SendResponse(PeerId, PeerRequestId, Box<Response<P>>),
     Subscribe(GossipTopic),
@@ -256,6 +280,18 @@ impl SubnetServiceToP2p {
     }
 }
 
+pub enum SubnetServiceToAttestationVerifier {
+    AggregationDuties(BTreeSet<(Slot, SubnetId)>),
+}
+
+impl SubnetServiceToAttestationVerifier {
+    pub fn send(self, tx: &UnboundedSender<Self>) {
+        if tx.unbounded_send(self).is_err() {
+            debug!("send to attestation verifier failed because the receiver was dropped");
+        }
+    }
+}
+
 pub enum ToSubnetService {
     SetRegisteredValidators(Vec<PublicKeyBytes>),
     UpdateBeaconCommitteeSubscriptions(Slot, Vec<BeaconCommitteeSubscription>, Sender<Result<()>>),
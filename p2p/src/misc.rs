@@ -62,3 +62,15 @@ pub struct SyncCommitteeSubscription {
     #[serde(with = "serde_utils::string_or_native")]
     pub until_epoch: Epoch,
 }
+
+/// State of block sync reported to the chain head watchdog.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SyncDiagnostics {
+    pub peer_count: usize,
+    pub max_peer_head_slot: Option<Slot>,
+    pub block_requests_by_range: usize,
+    pub blob_requests_by_range: usize,
+    pub sequential_redownloads: usize,
+    pub unverified_blocks: usize,
+    pub is_forward_synced: bool,
+}
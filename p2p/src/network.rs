@@ -6,6 +6,7 @@ use std::{
 };
 
 use anyhow::{bail, Result};
+use database::Database;
 use dedicated_executor::DedicatedExecutor;
 use enum_iterator::Sequence as _;
 use eth1_api::RealController;
@@ -14,26 +15,26 @@ use eth2_libp2p::{
         methods::{
             BlobsByRangeRequest, BlobsByRootRequest, BlocksByRangeRequest, BlocksByRootRequest,
         },
-        GoodbyeReason, StatusMessage,
+        GoodbyeReason, RPCResponseErrorCode, StatusMessage,
     },
     service::Network as Service,
-    types::{core_topics_to_subscribe, EnrForkId, ForkContext, GossipEncoding},
-    Context, GossipId, GossipTopic, MessageAcceptance, MessageId, NetworkConfig, NetworkEvent,
-    NetworkGlobals, PeerAction, PeerId, PeerRequestId, PubsubMessage, ReportSource, Request,
-    Response, ShutdownReason, Subnet, SubnetDiscovery, SyncInfo, SyncStatus, TaskExecutor,
+    types::{core_topics_to_subscribe, EnrForkId, ForkContext, GossipEncoding, GossipKind},
+    Context, GossipId, GossipTopic, IdentTopic, MessageAcceptance, MessageId, NetworkConfig,
+    NetworkEvent, NetworkGlobals, PeerAction, PeerId, PeerRequestId, PubsubMessage, ReportSource,
+    Request, Response, ShutdownReason, Subnet, SubnetDiscovery, SyncInfo, SyncStatus, TaskExecutor,
 };
-use fork_choice_control::P2pMessage;
+use fork_choice_control::{P2pMessage, StorageError};
 use futures::{
     channel::mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
-    future::FutureExt as _,
+    future::{BoxFuture, FutureExt as _},
     select,
-    stream::StreamExt as _,
+    stream::{FuturesUnordered, StreamExt as _},
 };
 use helper_functions::misc;
-use log::{debug, error, log, warn, Level};
+use log::{debug, error, info, log, warn, Level};
 use operation_pools::{BlsToExecutionChangePool, Origin, PoolToP2pMessage, SyncCommitteeAggPool};
 use prometheus_client::registry::Registry;
-use prometheus_metrics::Metrics;
+use prometheus_metrics::{Metrics, OperationalCounter};
 use slog::{o, Drain as _, Logger};
 use slog_stdlog::StdLog;
 use std_ext::ArcExt as _;
@@ -42,6 +43,7 @@ use types::{
     altair::containers::{SignedContributionAndProof, SyncCommitteeMessage},
     capella::containers::SignedBlsToExecutionChange,
     combined::SignedBeaconBlock,
+    config::Config as ChainConfig,
     deneb::containers::{BlobIdentifier, BlobSidecar},
     nonstandard::{Phase, WithStatus},
     phase0::{
@@ -57,12 +59,19 @@ use types::{
 };
 
 use crate::{
+    gossip_slot_bounds::{GossipObjectKind, GossipQuarantine, GossipSlotTolerance, SlotCheck},
     messages::{
         ApiToP2p, P2pToAttestationVerifier, P2pToSlasher, P2pToSync, P2pToValidator,
         ServiceInboundMessage, ServiceOutboundMessage, SubnetServiceToP2p, SyncToP2p,
         ValidatorToP2p,
     },
     misc::{AttestationSubnetActions, RequestId, SubnetPeerDiscovery, SyncCommitteeSubnetAction},
+    peer_request_stats::PeerRequestStats,
+    protocol_versions::{
+        DowngradeSummary, ProtocolVersion, ProtocolVersionTracker, VersionedProtocol,
+    },
+    reachability::ReachabilityTracker,
+    seen_gossip_digests::{self, SeenGossipDigests},
     upnp::PortMappings,
 };
 
@@ -85,6 +94,20 @@ const NEW_PHASE_TOPICS_ADVANCE_SLOTS: u64 = 5;
 /// <https://github.com/ethereum/consensus-specs/blob/9839ed49346a85f95af4f8b0cb9c4d98b2308af8/specs/altair/p2p-interface.md#transitioning-the-gossip>
 const OLD_PHASE_TOPICS_REMAIN_EPOCHS: u64 = 2;
 
+/// Minimum number of mesh peers on a topic for our own messages to be published without delay.
+///
+/// Publishing right after subscribing to a topic may happen before the mesh is formed.
+/// `gossipsub` drops such messages with only a log entry.
+/// Only applies to subscribed topics. Messages on other topics are sent to fanout peers,
+/// which `gossipsub` selects when publishing, so waiting for mesh peers would only delay them.
+const PUBLISH_MIN_MESH_PEERS: usize = 1;
+
+/// Number of times publishing our own message is delayed while waiting for mesh peers.
+/// The message is published anyway after the last retry in case it reaches fanout peers.
+const PUBLISH_MAX_RETRIES: u8 = 3;
+
+const PUBLISH_RETRY_DELAY: Duration = Duration::from_millis(500);
+
 pub struct Channels<P: Preset> {
     pub api_to_p2p_rx: UnboundedReceiver<ApiToP2p<P>>,
     pub fork_choice_to_p2p_rx: UnboundedReceiver<P2pMessage<P>>,
@@ -101,8 +124,18 @@ pub struct Channels<P: Preset> {
 #[allow(clippy::struct_field_names)]
 pub struct Network<P: Preset> {
     network_globals: Arc<NetworkGlobals>,
+    // Peers known to be banned as of the last slot. Used to count new bans.
+    banned_peers: HashSet<PeerId>,
     received_blob_sidecars: HashMap<BlobIdentifier, Slot>,
     received_block_roots: HashMap<H256, Slot>,
+    // Req/resp statistics are collected by `SyncManager` and sent here to be served by the HTTP API.
+    peer_request_stats: HashMap<PeerId, PeerRequestStats>,
+    protocol_versions: ProtocolVersionTracker,
+    reachability: ReachabilityTracker,
+    seen_gossip_digests: SeenGossipDigests,
+    gossip_slot_tolerance: GossipSlotTolerance,
+    // Sync committee messages and contributions that arrived before their slot.
+    gossip_quarantine: GossipQuarantine<QuarantinedObject<P>>,
     controller: RealController<P>,
     channels: Channels<P>,
     dedicated_executor: Arc<DedicatedExecutor>,
@@ -128,6 +161,14 @@ impl<P: Preset> Network<P> {
         &self.network_globals
     }
 
+    pub(crate) const fn peer_request_stats(&self) -> &HashMap<PeerId, PeerRequestStats> {
+        &self.peer_request_stats
+    }
+
+    pub(crate) const fn reachability(&self) -> &ReachabilityTracker {
+        &self.reachability
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
         network_config: &NetworkConfig,
@@ -139,6 +180,8 @@ impl<P: Preset> Network<P> {
         bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
         metrics: Option<Arc<Metrics>>,
         libp2p_registry: Option<&mut Registry>,
+        gossip_digests_database: Database,
+        gossip_slot_tolerance: GossipSlotTolerance,
     ) -> Result<Self> {
         let chain_config = controller.chain_config().as_ref();
         let head_state = controller.head_state().value;
@@ -174,15 +217,30 @@ impl<P: Preset> Network<P> {
             }
         }
 
+        let seen_gossip_digests = SeenGossipDigests::load(gossip_digests_database, slot)?;
+
         let (network_to_service_tx, network_to_service_rx) = mpsc::unbounded();
         let (service_to_network_tx, service_to_network_rx) = mpsc::unbounded();
 
-        run_network_service(service, network_to_service_rx, service_to_network_tx);
+        run_network_service(
+            service,
+            fork_context.clone_arc(),
+            metrics.clone(),
+            network_to_service_rx,
+            service_to_network_tx,
+        );
 
         let network = Self {
             network_globals,
+            banned_peers: HashSet::new(),
             received_blob_sidecars: HashMap::new(),
             received_block_roots: HashMap::new(),
+            peer_request_stats: HashMap::new(),
+            protocol_versions: ProtocolVersionTracker::default(),
+            reachability: ReachabilityTracker::new(network_config, Instant::now()),
+            seen_gossip_digests,
+            gossip_slot_tolerance,
+            gossip_quarantine: GossipQuarantine::default(),
             controller,
             channels,
             dedicated_executor,
@@ -246,6 +304,15 @@ impl<P: Preset> Network<P> {
                         ApiToP2p::RequestPeers(query, receiver) => {
                             receiver.send(self.node_peers(&query)).is_ok()
                         },
+                        ApiToP2p::RequestPeersWithRequestStats(query, receiver) => {
+                            receiver.send(self.node_peers_with_request_stats(&query)).is_ok()
+                        },
+                        ApiToP2p::RequestPeerClients(receiver) => {
+                            receiver.send(self.node_peer_clients()).is_ok()
+                        },
+                        ApiToP2p::RequestReachability(receiver) => {
+                            receiver.send(self.node_reachability()).is_ok()
+                        },
                     };
 
                     if !success {
@@ -280,7 +347,15 @@ impl<P: Preset> Network<P> {
                     match message {
                         P2pMessage::Slot(slot) => {
                             self.on_slot(slot);
+                            self.release_quarantined_objects(slot);
+                            self.track_banned_peers();
+                            self.track_protocol_versions();
+                            self.track_reachability(slot);
                             self.track_collection_metrics();
+
+                            if let Err(error) = self.seen_gossip_digests.on_slot(slot) {
+                                warn!("failed to save digests of gossip messages: {error:?}");
+                            }
                         }
                         P2pMessage::Accept(gossip_id) => {
                             self.report_outcome(gossip_id, MessageAcceptance::Accept);
@@ -419,6 +494,17 @@ impl<P: Preset> Network<P> {
                         SyncToP2p::PruneReceivedBlocks => {
                             self.received_block_roots = HashMap::new();
                         }
+                        SyncToP2p::PeerRequestStats(peer_request_stats) => {
+                            self.peer_request_stats = peer_request_stats;
+                        }
+                        SyncToP2p::ReportSlowPeer(peer_id) => {
+                            self.report_peer(
+                                peer_id,
+                                PeerAction::MidToleranceError,
+                                ReportSource::SyncService,
+                                "slow_sync_peer",
+                            );
+                        }
                     }
                 },
 
@@ -478,6 +564,25 @@ impl<P: Preset> Network<P> {
 
                     ServiceInboundMessage::SubscribeNewForkTopics(next_phase, fork_digest)
                         .send(&self.network_to_service_tx);
+
+                    // `Network::subscribe_new_fork_topics` subscribes to blob sidecar topics
+                    // using the number of subnets from Deneb. Correct that using the chain config.
+                    let libp2p_topics = core_topics_to_subscribe(next_phase);
+                    let topics = core_topics(chain_config, next_phase);
+
+                    for kind in topics.iter().filter(|kind| !libp2p_topics.contains(kind)) {
+                        let topic =
+                            GossipTopic::new(kind.clone(), GossipEncoding::default(), fork_digest);
+
+                        ServiceInboundMessage::Subscribe(topic).send(&self.network_to_service_tx);
+                    }
+
+                    for kind in libp2p_topics.iter().filter(|kind| !topics.contains(kind)) {
+                        let topic =
+                            GossipTopic::new(kind.clone(), GossipEncoding::default(), fork_digest);
+
+                        ServiceInboundMessage::Unsubscribe(topic).send(&self.network_to_service_tx);
+                    }
                 }
             }
         }
@@ -546,7 +651,8 @@ impl<P: Preset> Network<P> {
     }
 
     fn publish_blob_sidecar(&self, blob_sidecar: Arc<BlobSidecar<P>>) {
-        let subnet_id = misc::compute_subnet_for_blob_sidecar(blob_sidecar.index);
+        let subnet_id =
+            misc::compute_subnet_for_blob_sidecar(self.controller.chain_config(), &blob_sidecar);
         let blob_identifier: BlobIdentifier = blob_sidecar.as_ref().into();
 
         self.log(
@@ -846,8 +952,9 @@ impl<P: Preset> Network<P> {
 
     fn handle_network_event(&mut self, network_event: NetworkEvent<RequestId, P>) {
         match network_event {
-            NetworkEvent::PeerConnectedIncoming(peer_id) => {
+            NetworkEvent::PeerConnectedIncoming(peer_id, endpoint) => {
                 self.log_with_feature(format_args!("peer {peer_id} connected incoming"));
+                self.reachability.record_inbound(&endpoint, Instant::now());
             }
             NetworkEvent::PeerConnectedOutgoing(peer_id) => {
                 self.log_with_feature(format_args!("peer {peer_id} connected outgoing"));
@@ -855,6 +962,7 @@ impl<P: Preset> Network<P> {
             }
             NetworkEvent::PeerDisconnected(peer_id) => {
                 self.log_with_feature(format_args!("peer {peer_id} disconnected"));
+                self.protocol_versions.remove_peer(&peer_id);
                 P2pToSync::RemovePeer(peer_id).send(&self.channels.p2p_to_sync_tx);
             }
             NetworkEvent::RPCFailed { peer_id, id, error } => {
@@ -881,9 +989,19 @@ impl<P: Preset> Network<P> {
             NetworkEvent::PubsubMessage {
                 id,
                 source,
+                topic,
                 message,
                 ..
-            } => self.handle_pubsub_message(id, source, message),
+            } => {
+                if let Some(metrics) = self.metrics.as_ref() {
+                    // Topics are formatted as `/eth2/{fork_digest}/{name}/{encoding}`.
+                    if let Some(encoding) = topic.as_str().rsplit('/').next() {
+                        metrics.register_gossip_topic_encoding(&[encoding]);
+                    }
+                }
+
+                self.handle_pubsub_message(id, source, message);
+            }
             NetworkEvent::StatusPeer(peer_id) => self.init_status_peer_request(peer_id),
             NetworkEvent::NewListenAddr(multiaddr) => {
                 // These come from `libp2p`. We don't use them anywhere. `eth2_libp2p` outputs them
@@ -906,9 +1024,21 @@ impl<P: Preset> Network<P> {
                 Ok(())
             }
             Request::BlocksByRange(request) => {
+                self.record_protocol_version(
+                    peer_id,
+                    VersionedProtocol::BlocksByRange,
+                    (&request).into(),
+                );
+
                 self.handle_blocks_by_range_request(peer_id, peer_request_id, request)
             }
             Request::BlocksByRoot(request) => {
+                self.record_protocol_version(
+                    peer_id,
+                    VersionedProtocol::BlocksByRoot,
+                    (&request).into(),
+                );
+
                 self.handle_blocks_by_root_request(peer_id, peer_request_id, request);
                 Ok(())
             }
@@ -983,7 +1113,18 @@ impl<P: Preset> Network<P> {
 
         self.dedicated_executor
             .spawn(async move {
-                let blocks = controller.blocks_by_range(start_slot..end_slot)?;
+                let blocks = match controller.blocks_by_range(start_slot..end_slot) {
+                    Ok(blocks) => blocks,
+                    Err(error) => {
+                        send_block_lookup_error(
+                            peer_id,
+                            peer_request_id,
+                            &error,
+                            &network_to_service_tx,
+                        );
+                        return Err(error);
+                    }
+                };
 
                 for block_with_root in blocks {
                     let block = block_with_root.block;
@@ -1204,7 +1345,18 @@ impl<P: Preset> Network<P> {
                     .into_iter()
                     .take(MAX_FOR_DOS_PREVENTION.try_into()?);
 
-                let blocks = controller.blocks_by_root(block_roots)?;
+                let blocks = match controller.blocks_by_root(block_roots) {
+                    Ok(blocks) => blocks,
+                    Err(error) => {
+                        send_block_lookup_error(
+                            peer_id,
+                            peer_request_id,
+                            &error,
+                            &network_to_service_tx,
+                        );
+                        return Err(error);
+                    }
+                };
 
                 for block in blocks.into_iter().map(WithStatus::value) {
                     log(
@@ -1416,6 +1568,15 @@ impl<P: Preset> Network<P> {
                 let block_root = beacon_block.message().hash_tree_root();
                 let block_slot = beacon_block.message().slot();
 
+                let kind = GossipObjectKind::BeaconBlock;
+
+                if self
+                    .check_gossip_slot(kind, block_slot, source, &message_id)
+                    .is_none()
+                {
+                    return;
+                }
+
                 if !self.register_new_received_block(block_root, block_slot) {
                     return;
                 }
@@ -1451,6 +1612,16 @@ impl<P: Preset> Network<P> {
 
                 let (subnet_id, blob_sidecar) = *data;
                 let blob_identifier: BlobIdentifier = blob_sidecar.as_ref().into();
+                let slot = blob_sidecar.signed_block_header.message.slot;
+
+                let kind = GossipObjectKind::BlobSidecar;
+
+                if self
+                    .check_gossip_slot(kind, slot, source, &message_id)
+                    .is_none()
+                {
+                    return;
+                }
 
                 self.log(
                     Level::Info,
@@ -1476,6 +1647,29 @@ impl<P: Preset> Network<P> {
                     metrics.register_gossip_object(&["aggregate_and_proof_attestation"]);
                 }
 
+                let slot = aggregate_and_proof.message.aggregate.data.slot;
+
+                let kind = GossipObjectKind::AggregateAndProof;
+
+                if self
+                    .check_gossip_slot(kind, slot, source, &message_id)
+                    .is_none()
+                {
+                    return;
+                }
+
+                let digest = seen_gossip_digests::gossip_digest(&*aggregate_and_proof);
+
+                if !self.seen_gossip_digests.insert(slot, digest) {
+                    self.log(
+                        Level::Debug,
+                        format_args!("ignoring already seen aggregate and proof from {source}"),
+                    );
+
+                    self.report_outcome(GossipId { source, message_id }, MessageAcceptance::Ignore);
+                    return;
+                }
+
                 self.log(
                     Level::Debug,
                     format_args!(
@@ -1498,6 +1692,30 @@ impl<P: Preset> Network<P> {
                     metrics.register_gossip_object(&["attestation"]);
                 }
 
+                let slot = attestation.data.slot;
+
+                if self
+                    .check_gossip_slot(GossipObjectKind::Attestation, slot, source, &message_id)
+                    .is_none()
+                {
+                    return;
+                }
+
+                let digest = seen_gossip_digests::gossip_digest(&*attestation);
+
+                if !self.seen_gossip_digests.insert(slot, digest) {
+                    self.log(
+                        Level::Debug,
+                        format_args!(
+                            "ignoring already seen singular attestation in subnet {subnet_id} \
+                             from {source}",
+                        ),
+                    );
+
+                    self.report_outcome(GossipId { source, message_id }, MessageAcceptance::Ignore);
+                    return;
+                }
+
                 self.log(
                     Level::Debug,
                     format_args!(
@@ -1575,6 +1793,14 @@ impl<P: Preset> Network<P> {
                     metrics.register_gossip_object(&["signed_contribution_and_proof"]);
                 }
 
+                let slot = proof.message.contribution.slot;
+                let kind = GossipObjectKind::SyncCommitteeContribution;
+
+                let Some(slot_check) = self.check_gossip_slot(kind, slot, source, &message_id)
+                else {
+                    return;
+                };
+
                 let gossip_id = GossipId { source, message_id };
 
                 self.log(
@@ -1584,6 +1810,12 @@ impl<P: Preset> Network<P> {
                     ),
                 );
 
+                if slot_check == SlotCheck::Early {
+                    let object = QuarantinedObject::SyncCommitteeContribution(proof, gossip_id);
+                    self.quarantine_gossip_object(kind, slot, object);
+                    return;
+                }
+
                 // Handle it asynchronously to not block the event loop.
                 self.sync_committee_agg_pool
                     .handle_external_contribution_and_proof_detached(
@@ -1597,6 +1829,14 @@ impl<P: Preset> Network<P> {
                 }
 
                 let (subnet_id, sync_committee_message) = *message;
+                let slot = sync_committee_message.slot;
+                let kind = GossipObjectKind::SyncCommitteeMessage;
+
+                let Some(slot_check) = self.check_gossip_slot(kind, slot, source, &message_id)
+                else {
+                    return;
+                };
+
                 let gossip_id = GossipId { source, message_id };
 
                 self.log(
@@ -1607,6 +1847,17 @@ impl<P: Preset> Network<P> {
                     ),
                 );
 
+                if slot_check == SlotCheck::Early {
+                    let object = QuarantinedObject::SyncCommitteeMessage(
+                        sync_committee_message,
+                        subnet_id,
+                        gossip_id,
+                    );
+
+                    self.quarantine_gossip_object(kind, slot, object);
+                    return;
+                }
+
                 // Handle it asynchronously to not block the event loop.
                 self.sync_committee_agg_pool
                     .handle_external_message_detached(
@@ -1648,6 +1899,83 @@ impl<P: Preset> Network<P> {
         }
     }
 
+    /// Ignores gossip objects too far from the current slot.
+    /// Returns `None` if the object should not be processed any further.
+    fn check_gossip_slot(
+        &self,
+        kind: GossipObjectKind,
+        slot: Slot,
+        source: PeerId,
+        message_id: &MessageId,
+    ) -> Option<SlotCheck> {
+        let current_slot = self.controller.slot();
+        let slot_check = self.gossip_slot_tolerance.check(kind, slot, current_slot);
+
+        if slot_check.is_in_bounds() {
+            return Some(slot_check);
+        }
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_gossip_clamped_object(&[kind.as_ref(), slot_check.as_ref()]);
+        }
+
+        self.log(
+            Level::Debug,
+            format_args!(
+                "ignoring {} as gossip from {source} because its slot is {} \
+                 (slot: {slot}, current slot: {current_slot})",
+                kind.as_ref(),
+                slot_check.as_ref(),
+            ),
+        );
+
+        let gossip_id = GossipId {
+            source,
+            message_id: message_id.clone(),
+        };
+
+        self.report_outcome(gossip_id, MessageAcceptance::Ignore);
+
+        None
+    }
+
+    fn quarantine_gossip_object(
+        &mut self,
+        kind: GossipObjectKind,
+        slot: Slot,
+        object: QuarantinedObject<P>,
+    ) {
+        if let Err(object) = self.gossip_quarantine.insert(slot, object) {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_gossip_clamped_object(&[kind.as_ref(), "quarantine_full"]);
+            }
+
+            self.report_outcome(object.into_gossip_id(), MessageAcceptance::Ignore);
+        }
+    }
+
+    fn release_quarantined_objects(&mut self, slot: Slot) {
+        for object in self.gossip_quarantine.take_due(slot) {
+            match object {
+                QuarantinedObject::SyncCommitteeMessage(message, subnet_id, gossip_id) => {
+                    self.sync_committee_agg_pool
+                        .handle_external_message_detached(
+                            message,
+                            subnet_id,
+                            Origin::Gossip(gossip_id),
+                        );
+                }
+                QuarantinedObject::SyncCommitteeContribution(proof, gossip_id) => {
+                    self.sync_committee_agg_pool
+                        .handle_external_contribution_and_proof_detached(
+                            *proof,
+                            Origin::Gossip(gossip_id),
+                        );
+                }
+            }
+        }
+    }
+
     fn init_status_peer_request(&self, peer_id: PeerId) {
         P2pToSync::StatusPeer(peer_id).send(&self.channels.p2p_to_sync_tx);
     }
@@ -1868,10 +2196,9 @@ impl<P: Preset> Network<P> {
 
         let current_phase = self.fork_context.current_fork();
 
-        for kind in core_topics_to_subscribe(current_phase)
-            .iter()
+        for kind in core_topics(self.controller.chain_config(), current_phase)
+            .into_iter()
             .filter(|kind| !subscribed_topics.contains(kind))
-            .cloned()
         {
             ServiceInboundMessage::SubscribeKind(kind).send(&self.network_to_service_tx);
         }
@@ -1976,6 +2303,76 @@ impl<P: Preset> Network<P> {
             })
     }
 
+    fn track_banned_peers(&mut self) {
+        let Some(metrics) = self.metrics.as_ref() else {
+            return;
+        };
+
+        let banned_peers = self
+            .network_globals
+            .peers
+            .read()
+            .peers()
+            .filter(|(_, peer_info)| peer_info.is_banned())
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<HashSet<_>>();
+
+        for _ in banned_peers.difference(&self.banned_peers) {
+            metrics.register_operational_counter(OperationalCounter::BannedPeers, 1);
+        }
+
+        self.banned_peers = banned_peers;
+    }
+
+    fn record_protocol_version(
+        &mut self,
+        peer_id: PeerId,
+        protocol: VersionedProtocol,
+        version: ProtocolVersion,
+    ) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_req_resp_protocol_version(&[protocol.as_ref(), version.as_ref()]);
+        }
+
+        self.protocol_versions.record(peer_id, protocol, version);
+    }
+
+    fn track_protocol_versions(&mut self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics
+                .set_downgraded_protocol_peers(self.protocol_versions.summary().downgraded_peers);
+        }
+
+        if let Some(summary) = self.protocol_versions.take_widespread_downgrade() {
+            let DowngradeSummary {
+                downgraded_peers,
+                judged_peers,
+            } = summary;
+
+            warn!(
+                "{downgraded_peers} out of {judged_peers} peers negotiated outdated req/resp \
+                 protocol versions; peers may not have upgraded for the current fork",
+            );
+        }
+    }
+
+    fn track_reachability(&mut self, slot: Slot) {
+        if !misc::is_epoch_start::<P>(slot) {
+            return;
+        }
+
+        let reachability = self.node_reachability();
+        let (new_diagnostics, resolved) = self.reachability.take_new_diagnostics(&reachability);
+
+        for diagnostic in new_diagnostics {
+            warn!("node may not be reachable by peers: {diagnostic}");
+        }
+
+        if resolved {
+            info!("node is now receiving inbound connections from peers");
+        }
+    }
+
     fn track_collection_metrics(&self) {
         if let Some(metrics) = self.metrics.as_ref() {
             let type_name = tynm::type_name::<Self>();
@@ -1989,6 +2386,11 @@ impl<P: Preset> Network<P> {
                 &[&type_name, "received_block_roots"],
                 self.received_block_roots.len(),
             );
+
+            metrics.set_collection_length(
+                &[&type_name, "gossip_quarantine"],
+                self.gossip_quarantine.len(),
+            );
         }
     }
 
@@ -1997,12 +2399,45 @@ impl<P: Preset> Network<P> {
     }
 }
 
+enum QuarantinedObject<P: Preset> {
+    SyncCommitteeMessage(SyncCommitteeMessage, SubnetId, GossipId),
+    SyncCommitteeContribution(Box<SignedContributionAndProof<P>>, GossipId),
+}
+
+impl<P: Preset> QuarantinedObject<P> {
+    fn into_gossip_id(self) -> GossipId {
+        match self {
+            Self::SyncCommitteeMessage(_, _, gossip_id)
+            | Self::SyncCommitteeContribution(_, gossip_id) => gossip_id,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 enum Error {
     #[error("end slot overflowed ({start_slot} + {difference})")]
     EndSlotOverflow { start_slot: u64, difference: u64 },
 }
 
+// `core_topics_to_subscribe` always uses the number of blob sidecar subnets from Deneb.
+// Use the number from the chain config instead in case a later phase changes it.
+fn core_topics(chain_config: &ChainConfig, phase: Phase) -> Vec<GossipKind> {
+    let blob_sidecar_subnet_count = if phase >= Phase::Deneb {
+        chain_config.blob_sidecar_subnet_count(phase).get()
+    } else {
+        0
+    };
+
+    let blob_sidecar_topics = (0..blob_sidecar_subnet_count).map(GossipKind::BlobSidecar);
+
+    core_topics_to_subscribe(phase)
+        .iter()
+        .filter(|kind| !matches!(kind, GossipKind::BlobSidecar(_)))
+        .cloned()
+        .chain(blob_sidecar_topics)
+        .collect()
+}
+
 fn fork_digest(fork_context: &ForkContext) -> ForkDigest {
     fork_context
         .to_context_bytes(fork_context.current_fork())
@@ -2011,16 +2446,31 @@ fn fork_digest(fork_context: &ForkContext) -> ForkDigest {
 
 fn run_network_service<P: Preset>(
     mut service: Service<RequestId, P>,
+    fork_context: Arc<ForkContext>,
+    metrics: Option<Arc<Metrics>>,
     mut network_to_service_rx: UnboundedReceiver<ServiceInboundMessage<P>>,
     service_to_network_tx: UnboundedSender<ServiceOutboundMessage<P>>,
 ) {
     tokio::spawn(async move {
+        let mut publish_retries = FuturesUnordered::new();
+
         loop {
             select! {
                 network_event = service.next_event().fuse() => {
                     ServiceOutboundMessage::NetworkEvent(network_event).send(&service_to_network_tx);
                 }
 
+                (message, retries) = publish_retries.select_next_some() => {
+                    publish_own_message(
+                        &mut service,
+                        &fork_context,
+                        metrics.as_deref(),
+                        &mut publish_retries,
+                        message,
+                        retries,
+                    );
+                }
+
                 message = network_to_service_rx.select_next_some() => {
                     match message {
                         ServiceInboundMessage::DiscoverSubnetPeers(subnet_discoveries) => {
@@ -2030,7 +2480,14 @@ fn run_network_service<P: Preset>(
                             service.goodbye_peer(&peer_id, goodbye_reason, report_source);
                         }
                         ServiceInboundMessage::Publish(message) => {
-                            service.publish(message);
+                            publish_own_message(
+                                &mut service,
+                                &fork_context,
+                                metrics.as_deref(),
+                                &mut publish_retries,
+                                message,
+                                0,
+                            );
                         }
                         ServiceInboundMessage::ReportPeer(peer_id, action, source, msg) => {
                             service.report_peer(&peer_id, action, source, msg);
@@ -2042,6 +2499,9 @@ fn run_network_service<P: Preset>(
                                 message_acceptance,
                             );
                         }
+                        ServiceInboundMessage::SendErrorResponse(peer_id, peer_request_id, error_code, reason) => {
+                            service.send_error_response(peer_id, peer_request_id, error_code, reason);
+                        }
                         ServiceInboundMessage::SendRequest(peer_id, request_id, request) => {
                             service.send_request(peer_id, request_id, request);
                         }
@@ -2076,6 +2536,106 @@ fn run_network_service<P: Preset>(
     });
 }
 
+fn publish_own_message<P: Preset>(
+    service: &mut Service<RequestId, P>,
+    fork_context: &ForkContext,
+    metrics: Option<&Metrics>,
+    publish_retries: &mut FuturesUnordered<BoxFuture<'static, (PubsubMessage<P>, u8)>>,
+    message: PubsubMessage<P>,
+    retries: u8,
+) {
+    let message_type = message_type(&message);
+
+    let topic = GossipTopic::new(
+        message.kind(),
+        GossipEncoding::default(),
+        fork_digest(fork_context),
+    );
+
+    let topic_hash = IdentTopic::from(topic).hash();
+    let gossipsub = service.gossipsub();
+    let subscribed = gossipsub
+        .topics()
+        .any(|subscribed| *subscribed == topic_hash);
+    let mesh_peers = gossipsub.mesh_peers(&topic_hash).count();
+
+    if subscribed && mesh_peers < PUBLISH_MIN_MESH_PEERS {
+        if retries < PUBLISH_MAX_RETRIES {
+            debug!(
+                "delaying publishing {message_type} because topic has {mesh_peers} mesh peers \
+                 (retry {} of {PUBLISH_MAX_RETRIES})",
+                retries + 1,
+            );
+
+            if let Some(metrics) = metrics {
+                metrics.register_own_gossip_publish_retry(&[message_type]);
+            }
+
+            publish_retries.push(
+                async move {
+                    tokio::time::sleep(PUBLISH_RETRY_DELAY).await;
+                    (message, retries + 1)
+                }
+                .boxed(),
+            );
+
+            return;
+        }
+
+        warn!(
+            "publishing {message_type} with {mesh_peers} mesh peers \
+             after {PUBLISH_MAX_RETRIES} retries; it may not reach the network",
+        );
+
+        if let Some(metrics) = metrics {
+            metrics.register_own_gossip_publish_failure(&[message_type]);
+        }
+    }
+
+    service.publish(message);
+}
+
+const fn message_type<P: Preset>(message: &PubsubMessage<P>) -> &'static str {
+    match message {
+        PubsubMessage::BeaconBlock(_) => "beacon_block",
+        PubsubMessage::BlobSidecar(_) => "blob_sidecar",
+        PubsubMessage::AggregateAndProofAttestation(_) => "aggregate_and_proof_attestation",
+        PubsubMessage::Attestation(_, _) => "attestation",
+        PubsubMessage::VoluntaryExit(_) => "voluntary_exit",
+        PubsubMessage::ProposerSlashing(_) => "proposer_slashing",
+        PubsubMessage::AttesterSlashing(_) => "attester_slashing",
+        PubsubMessage::SignedContributionAndProof(_) => "signed_contribution_and_proof",
+        PubsubMessage::SyncCommitteeMessage(_) => "sync_committee_message",
+        PubsubMessage::BlsToExecutionChange(_) => "bls_to_execution_change",
+        PubsubMessage::LightClientFinalityUpdate(_) => "light_client_finality_update",
+        PubsubMessage::LightClientOptimisticUpdate(_) => "light_client_optimistic_update",
+    }
+}
+
+// Terminate the response stream with an error rather than silently omitting blocks.
+// Blocks whose execution payloads have been pruned cannot be served whole.
+fn send_block_lookup_error<P: Preset>(
+    peer_id: PeerId,
+    peer_request_id: PeerRequestId,
+    error: &anyhow::Error,
+    network_to_service_tx: &UnboundedSender<ServiceInboundMessage<P>>,
+) {
+    let error_code = match error.downcast_ref() {
+        Some(StorageError::ExecutionPayloadPruned { .. }) => {
+            RPCResponseErrorCode::ResourceUnavailable
+        }
+        _ => RPCResponseErrorCode::ServerError,
+    };
+
+    ServiceInboundMessage::SendErrorResponse(
+        peer_id,
+        peer_request_id,
+        error_code,
+        error.to_string(),
+    )
+    .send(network_to_service_tx);
+}
+
 fn log(level: Level, connected_peers: usize, target_peers: usize, message: impl Display) {
     log!(
         level,
@@ -2088,6 +2648,8 @@ fn log(level: Level, connected_peers: usize, target_peers: usize, message: impl
 
 #[cfg(test)]
 mod tests {
+    use core::num::NonZeroU64;
+
     use super::*;
 
     const MAX_REQUEST_BLOCKS: u64 = 1024;
@@ -2096,4 +2658,28 @@ mod tests {
     fn ensure_constant_sanity() {
         assert!(MAX_FOR_DOS_PREVENTION < MAX_REQUEST_BLOCKS);
     }
+
+    #[test]
+    fn core_topics_include_blob_sidecar_subnets_from_chain_config() {
+        let chain_config = ChainConfig {
+            blob_sidecar_subnet_count: NonZeroU64::new(9).expect("9 is nonzero"),
+            ..ChainConfig::mainnet()
+        };
+
+        let blob_sidecar_subnets = |phase| {
+            core_topics(&chain_config, phase)
+                .into_iter()
+                .filter_map(|kind| match kind {
+                    GossipKind::BlobSidecar(subnet_id) => Some(subnet_id),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(blob_sidecar_subnets(Phase::Capella), []);
+        assert_eq!(
+            blob_sidecar_subnets(Phase::Deneb),
+            (0..9).collect::<Vec<_>>()
+        );
+    }
 }
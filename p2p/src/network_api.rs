@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, time::Instant};
+
 use eth2_libp2p::{
     types::EnrAttestationBitfield, ConnectionDirection, Enr, EnrExt as _, EnrSyncCommitteeBitfield,
     Multiaddr, PeerConnectionStatus, PeerId, PeerInfo,
@@ -5,7 +7,7 @@ use eth2_libp2p::{
 use serde::{Deserialize, Serialize};
 use types::preset::Preset;
 
-use crate::Network;
+use crate::{peer_request_stats::PeerRequestStats, reachability::NodeReachability, Network};
 
 #[derive(Deserialize, Serialize)]
 pub struct NodePeersQuery {
@@ -29,10 +31,17 @@ pub struct NodePeer {
     last_seen_p2p_address: Multiaddr,
     state: PeerState,
     direction: PeerDirection,
+    // Not part of the standard API. Only included in `/grandine/v1/node/peers`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    req_resp: Option<PeerRequestStats>,
 }
 
 impl NodePeer {
-    fn from_peer_info(peer_info: &PeerInfo, peer_id: &PeerId) -> Option<Self> {
+    fn from_peer_info(
+        peer_info: &PeerInfo,
+        peer_id: &PeerId,
+        req_resp: Option<PeerRequestStats>,
+    ) -> Option<Self> {
         let addr = peer_info
             .listening_addresses()
             .first()
@@ -49,6 +58,7 @@ impl NodePeer {
             last_seen_p2p_address: addr,
             state,
             direction,
+            req_resp,
         })
     }
 }
@@ -155,8 +165,48 @@ impl<P: Preset> Network<P> {
         }
     }
 
+    /// Counts connected peers by the client they identify as in their agent string.
+    #[must_use]
+    pub fn node_peer_clients(&self) -> BTreeMap<String, u64> {
+        let mut clients = BTreeMap::<_, u64>::new();
+
+        self.network_globals()
+            .peers
+            .read()
+            .connected_peers()
+            .for_each(|(_, peer_info)| {
+                *clients
+                    .entry(peer_info.client().kind.to_string())
+                    .or_default() += 1;
+            });
+
+        clients
+    }
+
+    #[must_use]
+    pub fn node_reachability(&self) -> NodeReachability {
+        self.reachability().report(
+            Instant::now(),
+            self.outbound_peer_count(),
+            self.network_globals().local_enr().ip4(),
+        )
+    }
+
     #[must_use]
     pub fn node_peers(&self, query: &NodePeersQuery) -> Vec<NodePeer> {
+        self.filtered_node_peers(query, false)
+    }
+
+    #[must_use]
+    pub fn node_peers_with_request_stats(&self, query: &NodePeersQuery) -> Vec<NodePeer> {
+        self.filtered_node_peers(query, true)
+    }
+
+    fn filtered_node_peers(
+        &self,
+        query: &NodePeersQuery,
+        with_request_stats: bool,
+    ) -> Vec<NodePeer> {
         self.network_globals()
             .peers
             .read()
@@ -178,19 +228,41 @@ impl<P: Preset> Network<P> {
                     .map(|states| states.contains(&state))
                     .unwrap_or(true);
 
+                // Peers that have not served any sync requests yet get empty statistics.
+                let req_resp = with_request_stats.then(|| {
+                    self.peer_request_stats()
+                        .get(peer_id)
+                        .copied()
+                        .unwrap_or_default()
+                });
+
                 (allowed_by_direction && allowed_by_state)
-                    .then(|| NodePeer::from_peer_info(peer_info, peer_id))
+                    .then(|| NodePeer::from_peer_info(peer_info, peer_id, req_resp))
                     .flatten()
             })
             .collect()
     }
 
+    fn outbound_peer_count(&self) -> usize {
+        self.network_globals()
+            .peers
+            .read()
+            .connected_peers()
+            .filter(|(_, peer_info)| {
+                matches!(
+                    peer_info.connection_direction(),
+                    Some(ConnectionDirection::Outgoing),
+                )
+            })
+            .count()
+    }
+
     #[must_use]
     pub fn node_peer(&self, peer_id: &PeerId) -> Option<NodePeer> {
         self.network_globals()
             .peers
             .read()
             .peer_info(peer_id)
-            .and_then(|peer_info| NodePeer::from_peer_info(peer_info, peer_id))
+            .and_then(|peer_info| NodePeer::from_peer_info(peer_info, peer_id, None))
     }
 }
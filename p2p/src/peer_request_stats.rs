@@ -0,0 +1,284 @@
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use cached::{Cached as _, SizedCache};
+use eth2_libp2p::PeerId;
+use itertools::Itertools as _;
+use prometheus_metrics::Metrics;
+use serde::Serialize;
+
+use crate::{misc::RequestId, range_and_root_requests::REQUEST_BY_RANGE_TIMEOUT};
+
+// Peers are only judged after serving this many requests to avoid downscoring them for bad luck.
+const MIN_REQUESTS_TO_JUDGE: u64 = 8;
+const MIN_PEERS_TO_COMPARE: usize = 3;
+// A peer is slow if its score is less than the median score divided by this.
+const SLOW_PEER_SCORE_DIVISOR: u64 = 4;
+
+/// Req/resp statistics of a peer collected from blocks by range requests made during sync.
+///
+/// Statistics are reset when the peer is downscored for being slow,
+/// so it is only judged again after serving enough new requests.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize)]
+pub struct PeerRequestStats {
+    pub completed_requests: u64,
+    pub failed_requests: u64,
+    pub timed_out_requests: u64,
+    pub empty_responses: u64,
+    pub blocks_received: u64,
+    pub response_time_ms: u64,
+    pub downscores: u64,
+}
+
+impl PeerRequestStats {
+    const fn total_requests(self) -> u64 {
+        self.completed_requests + self.failed_requests + self.timed_out_requests
+    }
+
+    // Blocks per minute of waiting for responses scaled by the fraction of successful requests.
+    // Integers are precise enough for ranking peers.
+    fn score(self) -> Option<u64> {
+        let total_requests = self.total_requests();
+
+        if total_requests == 0 {
+            return None;
+        }
+
+        let blocks_per_minute =
+            self.blocks_received.saturating_mul(60_000) / self.response_time_ms.max(1);
+
+        Some(blocks_per_minute.saturating_mul(self.completed_requests) / total_requests)
+    }
+}
+
+struct RequestInFlight {
+    peer_id: PeerId,
+    started_at: Instant,
+    blocks_received: u64,
+}
+
+pub struct PeerRequestStatsTracker {
+    peers: HashMap<PeerId, PeerRequestStats>,
+    requests_in_flight: SizedCache<RequestId, RequestInFlight>,
+}
+
+impl Default for PeerRequestStatsTracker {
+    fn default() -> Self {
+        Self {
+            peers: HashMap::new(),
+            requests_in_flight: SizedCache::with_size(1000),
+        }
+    }
+}
+
+impl PeerRequestStatsTracker {
+    pub fn request_started(&mut self, request_id: RequestId, peer_id: PeerId) {
+        self.requests_in_flight.cache_set(
+            request_id,
+            RequestInFlight {
+                peer_id,
+                started_at: Instant::now(),
+                blocks_received: 0,
+            },
+        );
+    }
+
+    pub fn block_received(&mut self, request_id: RequestId) {
+        if let Some(request) = self.requests_in_flight.cache_get_mut(&request_id) {
+            request.blocks_received += 1;
+        }
+    }
+
+    pub fn request_finished(&mut self, request_id: RequestId) {
+        let Some(request) = self.requests_in_flight.cache_remove(&request_id) else {
+            return;
+        };
+
+        let response_time_ms = request
+            .started_at
+            .elapsed()
+            .as_millis()
+            .try_into()
+            .unwrap_or(u64::MAX);
+
+        let stats = self.peers.entry(request.peer_id).or_default();
+
+        stats.completed_requests += 1;
+        stats.blocks_received += request.blocks_received;
+        stats.response_time_ms = stats.response_time_ms.saturating_add(response_time_ms);
+
+        if request.blocks_received == 0 {
+            stats.empty_responses += 1;
+        }
+    }
+
+    pub fn request_failed(&mut self, peer_id: PeerId) {
+        self.peers.entry(peer_id).or_default().failed_requests += 1;
+    }
+
+    pub fn expire_requests(&mut self) {
+        let expired_ids = self.requests_in_flight.key_order().copied().collect_vec();
+
+        let expired_ids = expired_ids
+            .into_iter()
+            .filter(|request_id| {
+                self.requests_in_flight
+                    .cache_get(request_id)
+                    .is_some_and(|request| request.started_at.elapsed() > REQUEST_BY_RANGE_TIMEOUT)
+            })
+            .collect_vec();
+
+        for request_id in expired_ids {
+            if let Some(request) = self.requests_in_flight.cache_remove(&request_id) {
+                self.peers
+                    .entry(request.peer_id)
+                    .or_default()
+                    .timed_out_requests += 1;
+            }
+        }
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+    }
+
+    /// Orders peers from the highest throughput to the lowest.
+    ///
+    /// Peers without statistics come first so that they get a chance to be measured.
+    /// The sort is stable, so peers with equal scores keep their previous order.
+    pub fn sort_by_throughput(&self, peers: &mut [PeerId]) {
+        peers.sort_by_cached_key(|peer_id| {
+            let score = self
+                .peers
+                .get(peer_id)
+                .copied()
+                .and_then(PeerRequestStats::score);
+            core::cmp::Reverse(score.unwrap_or(u64::MAX))
+        });
+    }
+
+    /// Returns peers that are consistently slower than the others and resets their statistics.
+    pub fn take_slow_peers(&mut self) -> Vec<PeerId> {
+        let judged_scores = self
+            .peers
+            .iter()
+            .filter(|(_, stats)| stats.total_requests() >= MIN_REQUESTS_TO_JUDGE)
+            .filter_map(|(peer_id, stats)| Some((*peer_id, stats.score()?)))
+            .collect_vec();
+
+        if judged_scores.len() < MIN_PEERS_TO_COMPARE {
+            return vec![];
+        }
+
+        let median_score = judged_scores
+            .iter()
+            .map(|(_, score)| *score)
+            .sorted_unstable()
+            .nth(judged_scores.len() / 2)
+            .unwrap_or_default();
+
+        let slow_peers = judged_scores
+            .into_iter()
+            .filter(|(_, score)| score.saturating_mul(SLOW_PEER_SCORE_DIVISOR) < median_score)
+            .map(|(peer_id, _)| peer_id)
+            .collect_vec();
+
+        for peer_id in &slow_peers {
+            if let Some(stats) = self.peers.get_mut(peer_id) {
+                *stats = PeerRequestStats {
+                    downscores: stats.downscores + 1,
+                    ..PeerRequestStats::default()
+                };
+            }
+        }
+
+        slow_peers
+    }
+
+    pub fn cache_clear(&mut self) {
+        self.requests_in_flight.cache_clear();
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> HashMap<PeerId, PeerRequestStats> {
+        self.peers.clone()
+    }
+
+    pub fn track_collection_metrics(&self, metrics: &Arc<Metrics>) {
+        let type_name = tynm::type_name::<Self>();
+
+        metrics.set_collection_length(&[&type_name, "peers"], self.peers.len());
+        metrics.set_collection_length(
+            &[&type_name, "requests_in_flight"],
+            self.requests_in_flight.cache_size(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consistently_slow_peers_are_sorted_last_and_reported_once() {
+        let fast_peers = [PeerId::random(), PeerId::random(), PeerId::random()];
+        let slow_peer = PeerId::random();
+        let new_peer = PeerId::random();
+
+        let mut tracker = PeerRequestStatsTracker::default();
+
+        for peer_id in fast_peers {
+            tracker.peers.insert(
+                peer_id,
+                PeerRequestStats {
+                    completed_requests: 10,
+                    blocks_received: 640,
+                    response_time_ms: 10_000,
+                    ..PeerRequestStats::default()
+                },
+            );
+        }
+
+        tracker.peers.insert(
+            slow_peer,
+            PeerRequestStats {
+                completed_requests: 4,
+                timed_out_requests: 6,
+                empty_responses: 2,
+                blocks_received: 128,
+                response_time_ms: 40_000,
+                ..PeerRequestStats::default()
+            },
+        );
+
+        let mut peers = [slow_peer, fast_peers[0], new_peer];
+
+        tracker.sort_by_throughput(&mut peers);
+
+        assert_eq!(peers, [new_peer, fast_peers[0], slow_peer]);
+        assert_eq!(tracker.take_slow_peers(), [slow_peer]);
+        assert!(tracker.take_slow_peers().is_empty());
+        assert_eq!(tracker.stats()[&slow_peer].downscores, 1);
+    }
+
+    #[test]
+    fn requests_track_blocks_and_empty_responses() {
+        let peer_id = PeerId::random();
+
+        let mut tracker = PeerRequestStatsTracker::default();
+
+        tracker.request_started(0, peer_id);
+        tracker.request_started(1, peer_id);
+        tracker.block_received(0);
+        tracker.block_received(0);
+        tracker.request_finished(0);
+        tracker.request_finished(1);
+        tracker.request_failed(peer_id);
+
+        let stats = tracker.stats()[&peer_id];
+
+        assert_eq!(stats.completed_requests, 2);
+        assert_eq!(stats.failed_requests, 1);
+        assert_eq!(stats.empty_responses, 1);
+        assert_eq!(stats.blocks_received, 2);
+    }
+}
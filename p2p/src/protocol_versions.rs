@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use eth2_libp2p::{
+    rpc::methods::{BlocksByRangeRequest, BlocksByRootRequest},
+    PeerId,
+};
+use strum::AsRefStr;
+
+// Downgrades are only reported when enough peers have been seen to make the fraction meaningful.
+const MIN_PEERS_TO_JUDGE: usize = 8;
+// Downgrades are reported when at least 1 in this many peers negotiated an outdated version.
+const DOWNGRADED_PEER_FRACTION_DIVISOR: usize = 4;
+
+/// Req/resp protocols that have been superseded by newer versions.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum VersionedProtocol {
+    BlocksByRange,
+    BlocksByRoot,
+}
+
+impl VersionedProtocol {
+    const fn latest_version(self) -> ProtocolVersion {
+        match self {
+            Self::BlocksByRange | Self::BlocksByRoot => ProtocolVersion::V2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProtocolVersion {
+    V1,
+    V2,
+}
+
+impl From<&BlocksByRangeRequest> for ProtocolVersion {
+    fn from(request: &BlocksByRangeRequest) -> Self {
+        match request {
+            BlocksByRangeRequest::V1(_) => Self::V1,
+            BlocksByRangeRequest::V2(_) => Self::V2,
+        }
+    }
+}
+
+impl From<&BlocksByRootRequest> for ProtocolVersion {
+    fn from(request: &BlocksByRootRequest) -> Self {
+        match request {
+            BlocksByRootRequest::V1(_) => Self::V1,
+            BlocksByRootRequest::V2(_) => Self::V2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DowngradeSummary {
+    pub downgraded_peers: usize,
+    pub judged_peers: usize,
+}
+
+/// Tracks req/resp protocol versions negotiated by connected peers.
+///
+/// Versions are taken from requests made by peers because `eth2_libp2p` does not report the
+/// outcome of negotiation for requests made by us. A peer that only sends outdated requests most
+/// likely does not support newer versions, so we have to downgrade when responding to it.
+#[derive(Default)]
+pub struct ProtocolVersionTracker {
+    // Latest version of each protocol negotiated by each peer.
+    peers: HashMap<PeerId, HashMap<VersionedProtocol, ProtocolVersion>>,
+    downgrade_reported: bool,
+}
+
+impl ProtocolVersionTracker {
+    pub fn record(
+        &mut self,
+        peer_id: PeerId,
+        protocol: VersionedProtocol,
+        version: ProtocolVersion,
+    ) {
+        self.peers
+            .entry(peer_id)
+            .or_default()
+            .insert(protocol, version);
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+    }
+
+    #[must_use]
+    pub fn summary(&self) -> DowngradeSummary {
+        let downgraded_peers = self
+            .peers
+            .values()
+            .filter(|protocols| {
+                protocols
+                    .iter()
+                    .any(|(protocol, version)| *version < protocol.latest_version())
+            })
+            .count();
+
+        DowngradeSummary {
+            downgraded_peers,
+            judged_peers: self.peers.len(),
+        }
+    }
+
+    /// Returns a summary if downgrades have become widespread since the last call.
+    ///
+    /// The summary is returned once until the fraction of downgraded peers falls below the
+    /// threshold again to avoid repeating the same warning every slot.
+    pub fn take_widespread_downgrade(&mut self) -> Option<DowngradeSummary> {
+        let summary = self.summary();
+
+        let DowngradeSummary {
+            downgraded_peers,
+            judged_peers,
+        } = summary;
+
+        let widespread = judged_peers >= MIN_PEERS_TO_JUDGE
+            && downgraded_peers.saturating_mul(DOWNGRADED_PEER_FRACTION_DIVISOR) >= judged_peers;
+
+        let newly_widespread = widespread && !self.downgrade_reported;
+
+        self.downgrade_reported = widespread;
+
+        newly_widespread.then_some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widespread_downgrade_is_reported_once() {
+        let mut tracker = ProtocolVersionTracker::default();
+
+        let peers = core::iter::repeat_with(PeerId::random)
+            .take(MIN_PEERS_TO_JUDGE)
+            .collect::<Vec<_>>();
+
+        for peer_id in &peers {
+            tracker.record(
+                *peer_id,
+                VersionedProtocol::BlocksByRange,
+                ProtocolVersion::V2,
+            );
+        }
+
+        assert_eq!(tracker.take_widespread_downgrade(), None);
+
+        for peer_id in &peers[..2] {
+            tracker.record(
+                *peer_id,
+                VersionedProtocol::BlocksByRoot,
+                ProtocolVersion::V1,
+            );
+        }
+
+        let expected = DowngradeSummary {
+            downgraded_peers: 2,
+            judged_peers: MIN_PEERS_TO_JUDGE,
+        };
+
+        assert_eq!(tracker.take_widespread_downgrade(), Some(expected));
+        assert_eq!(tracker.take_widespread_downgrade(), None);
+
+        tracker.remove_peer(&peers[0]);
+
+        assert_eq!(tracker.summary().downgraded_peers, 1);
+        assert_eq!(tracker.take_widespread_downgrade(), None);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{block_sync_service::SyncDirection, misc::RequestId, sync_manager::Sy
 type RequestKey = usize;
 
 const MAX_ROOT_REQUESTS_PER_KEY: usize = 3;
-const REQUEST_BY_RANGE_TIMEOUT: Duration = Duration::from_secs(15);
+pub const REQUEST_BY_RANGE_TIMEOUT: Duration = Duration::from_secs(15);
 const REQUEST_BY_ROOT_TIMEOUT_IN_SECONDS: u64 = 5;
 
 pub struct RangeAndRootRequests<K> {
@@ -43,6 +43,14 @@ impl<K: Hash + Eq + Clone> RangeAndRootRequests<K> {
             .insert(peer_id)
     }
 
+    pub fn requested_peers(&mut self, key: &K) -> impl Iterator<Item = PeerId> + '_ {
+        self.requests_by_root
+            .cache_get(key)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
     pub fn cache_clear(&mut self) {
         self.requests_by_range.cache_clear();
         self.requests_by_root.cache_clear();
@@ -109,6 +117,12 @@ impl<K: Hash + Eq + Clone> RangeAndRootRequests<K> {
             .map(|(batch, _)| batch.direction)
     }
 
+    pub fn request_by_range_started_at(&mut self, request_id: RequestId) -> Option<Instant> {
+        self.requests_by_range
+            .cache_get(&request_id)
+            .map(|(_, started_at)| *started_at)
+    }
+
     pub fn request_by_range_count(&mut self) -> usize {
         self.requests_by_range_keys()
             .into_iter()
@@ -120,8 +134,10 @@ impl<K: Hash + Eq + Clone> RangeAndRootRequests<K> {
             .count()
     }
 
-    pub fn request_by_range_finished(&mut self, request_id: RequestId) {
-        self.requests_by_range.cache_remove(&request_id);
+    pub fn request_by_range_finished(&mut self, request_id: RequestId) -> Option<SyncBatch> {
+        self.requests_by_range
+            .cache_remove(&request_id)
+            .map(|(batch, _)| batch)
     }
 
     pub fn chunk_by_root_received(&mut self, k: &K, peer_id: &PeerId) {
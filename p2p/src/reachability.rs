@@ -0,0 +1,394 @@
+use core::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+use std::{net::Ipv4Addr, time::Instant};
+
+use eth2_libp2p::{ConnectedPoint, Multiaddr, NetworkConfig};
+use serde::Serialize;
+
+// Peers normally find and dial a new node through discovery within minutes.
+const GRACE_PERIOD: Duration = Duration::from_secs(15 * 60);
+// Outbound connections show that the node itself is online and discoverable peers exist.
+// Without them, a lack of inbound connections says nothing about the node's ports.
+const MIN_OUTBOUND_PEERS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    Disabled,
+    Unknown,
+    Reachable,
+    Unreachable,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Transport {
+    Tcp,
+    Quic,
+}
+
+impl Transport {
+    // QUIC addresses also contain `/udp/`, so they have to be checked for first.
+    fn of(address: &Multiaddr) -> Option<Self> {
+        let address = address.to_string();
+
+        if address.contains("/quic") {
+            Some(Self::Quic)
+        } else if address.contains("/tcp/") {
+            Some(Self::Tcp)
+        } else {
+            None
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Tcp => "TCP",
+            Self::Quic => "QUIC",
+        }
+    }
+
+    const fn port_protocol(self) -> &'static str {
+        match self {
+            Self::Tcp => "TCP",
+            Self::Quic => "UDP",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum DiagnosticKind {
+    NoInboundConnections,
+    LocalEnrAddress,
+    MissingEnrAddress,
+}
+
+/// A problem that may prevent peers from dialing the node.
+///
+/// Messages contain details like the time the node has been running for that change between
+/// reports, so diagnostics are identified by their transport and kind instead.
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct Diagnostic {
+    #[serde(skip)]
+    transport: Transport,
+    #[serde(skip)]
+    kind: DiagnosticKind,
+    message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl Diagnostic {
+    const fn key(&self) -> (Transport, DiagnosticKind) {
+        (self.transport, self.kind)
+    }
+}
+
+#[derive(Default)]
+struct InboundConnections {
+    count: u64,
+    last_at: Option<Instant>,
+}
+
+#[derive(Serialize)]
+pub struct TransportReachability {
+    status: Reachability,
+    port: Option<u16>,
+    inbound_connections: u64,
+    seconds_since_last_inbound: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct NodeReachability {
+    tcp: TransportReachability,
+    quic: TransportReachability,
+    enr_address: Option<Ipv4Addr>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Infers whether peers can dial the node from the inbound connections it has received.
+///
+/// `eth2_libp2p` does not implement a dial-back protocol, so a port is only considered unreachable
+/// once the node has been running long enough and has enough outbound peers that some of them
+/// would have been expected to connect to it.
+pub struct ReachabilityTracker {
+    started_at: Instant,
+    tcp_port: u16,
+    quic_port: Option<u16>,
+    tcp: InboundConnections,
+    quic: InboundConnections,
+    // Diagnostics logged by the last call to `take_new_diagnostics`.
+    reported_diagnostics: Vec<(Transport, DiagnosticKind)>,
+}
+
+impl ReachabilityTracker {
+    #[must_use]
+    pub fn new(network_config: &NetworkConfig, started_at: Instant) -> Self {
+        let listen_addrs = network_config.listen_addrs();
+
+        Self {
+            started_at,
+            tcp_port: listen_addrs.tcp_port(),
+            quic_port: (!network_config.disable_quic_support).then(|| listen_addrs.quic_port()),
+            tcp: InboundConnections::default(),
+            quic: InboundConnections::default(),
+            reported_diagnostics: vec![],
+        }
+    }
+
+    /// Records an inbound connection accepted on the local address in `endpoint`.
+    ///
+    /// Peers may have connected to the node over several transports before, so the transport is
+    /// determined from the connection itself rather than from addresses seen for the peer.
+    pub fn record_inbound(&mut self, endpoint: &ConnectedPoint, now: Instant) {
+        let ConnectedPoint::Listener { local_addr, .. } = endpoint else {
+            return;
+        };
+
+        let Some(transport) = Transport::of(local_addr) else {
+            return;
+        };
+
+        let connections = match transport {
+            Transport::Tcp => &mut self.tcp,
+            Transport::Quic => &mut self.quic,
+        };
+
+        connections.count += 1;
+        connections.last_at = Some(now);
+    }
+
+    #[must_use]
+    pub fn report(
+        &self,
+        now: Instant,
+        outbound_peers: usize,
+        enr_address: Option<Ipv4Addr>,
+    ) -> NodeReachability {
+        let tcp = self.transport_reachability(Some(self.tcp_port), &self.tcp, now, outbound_peers);
+        let quic = self.transport_reachability(self.quic_port, &self.quic, now, outbound_peers);
+
+        let mut diagnostics = vec![];
+
+        for (transport, reachability) in [(Transport::Tcp, &tcp), (Transport::Quic, &quic)] {
+            if let (Reachability::Unreachable, Some(port)) =
+                (reachability.status, reachability.port)
+            {
+                diagnostics.push(Diagnostic {
+                    transport,
+                    kind: DiagnosticKind::NoInboundConnections,
+                    message: format!(
+                        "no inbound {} connections in {} minutes despite {outbound_peers} \
+                         outbound peers; {} port {port} is likely blocked by a firewall or not \
+                         forwarded by a NAT router",
+                        transport.name(),
+                        now.saturating_duration_since(self.started_at).as_secs() / 60,
+                        transport.port_protocol(),
+                    ),
+                });
+            }
+        }
+
+        if tcp.status != Reachability::Reachable {
+            match enr_address {
+                Some(address) if is_local(address) => diagnostics.push(Diagnostic {
+                    transport: Transport::Tcp,
+                    kind: DiagnosticKind::LocalEnrAddress,
+                    message: format!(
+                        "ENR advertises local address {address} that peers outside the local \
+                         network cannot dial; set --enr-address to the public address of the node",
+                    ),
+                }),
+                None if tcp.status == Reachability::Unreachable => diagnostics.push(Diagnostic {
+                    transport: Transport::Tcp,
+                    kind: DiagnosticKind::MissingEnrAddress,
+                    message: "ENR does not contain an IPv4 address; set --enr-address to the \
+                              public address of the node"
+                        .to_owned(),
+                }),
+                _ => {}
+            }
+        }
+
+        NodeReachability {
+            tcp,
+            quic,
+            enr_address,
+            diagnostics,
+        }
+    }
+
+    /// Returns diagnostics that were not returned by the previous call, along with whether all
+    /// previously returned ones have been resolved.
+    pub fn take_new_diagnostics(
+        &mut self,
+        reachability: &NodeReachability,
+    ) -> (Vec<Diagnostic>, bool) {
+        let new_diagnostics = reachability
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| !self.reported_diagnostics.contains(&diagnostic.key()))
+            .cloned()
+            .collect();
+
+        let resolved = !self.reported_diagnostics.is_empty() && reachability.diagnostics.is_empty();
+
+        self.reported_diagnostics = reachability
+            .diagnostics
+            .iter()
+            .map(Diagnostic::key)
+            .collect();
+
+        (new_diagnostics, resolved)
+    }
+
+    fn transport_reachability(
+        &self,
+        port: Option<u16>,
+        connections: &InboundConnections,
+        now: Instant,
+        outbound_peers: usize,
+    ) -> TransportReachability {
+        let running_for = now.saturating_duration_since(self.started_at);
+
+        let status = if port.is_none() {
+            Reachability::Disabled
+        } else if connections.count > 0 {
+            Reachability::Reachable
+        } else if running_for < GRACE_PERIOD || outbound_peers < MIN_OUTBOUND_PEERS {
+            Reachability::Unknown
+        } else {
+            Reachability::Unreachable
+        };
+
+        TransportReachability {
+            status,
+            port,
+            inbound_connections: connections.count,
+            seconds_since_last_inbound: connections
+                .last_at
+                .map(|last_at| now.saturating_duration_since(last_at).as_secs()),
+        }
+    }
+}
+
+const fn is_local(address: Ipv4Addr) -> bool {
+    address.is_private() || address.is_loopback() || address.is_link_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(started_at: Instant) -> ReachabilityTracker {
+        ReachabilityTracker {
+            started_at,
+            tcp_port: 9000,
+            quic_port: Some(9001),
+            tcp: InboundConnections::default(),
+            quic: InboundConnections::default(),
+            reported_diagnostics: vec![],
+        }
+    }
+
+    fn inbound(local_address: &str) -> ConnectedPoint {
+        ConnectedPoint::Listener {
+            local_addr: local_address.parse().expect("address is valid"),
+            send_back_addr: "/ip4/198.51.100.7/tcp/30000"
+                .parse()
+                .expect("address is valid"),
+        }
+    }
+
+    #[test]
+    fn ports_are_not_judged_during_grace_period_or_without_outbound_peers() {
+        let started_at = Instant::now();
+        let tracker = tracker(started_at);
+        let public_address = Some(Ipv4Addr::new(203, 0, 113, 1));
+
+        let report = tracker.report(started_at + GRACE_PERIOD / 2, 50, public_address);
+
+        assert_eq!(report.tcp.status, Reachability::Unknown);
+        assert!(report.diagnostics.is_empty());
+
+        let report = tracker.report(started_at + GRACE_PERIOD, 2, public_address);
+
+        assert_eq!(report.tcp.status, Reachability::Unknown);
+        assert!(report.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn transports_without_inbound_connections_are_reported_unreachable() {
+        let started_at = Instant::now();
+        let mut tracker = tracker(started_at);
+        let now = started_at + GRACE_PERIOD;
+
+        tracker.record_inbound(&inbound("/ip4/0.0.0.0/tcp/9000"), now);
+
+        let report = tracker.report(now, 10, Some(Ipv4Addr::new(203, 0, 113, 1)));
+
+        assert_eq!(report.tcp.status, Reachability::Reachable);
+        assert_eq!(report.tcp.inbound_connections, 1);
+        assert_eq!(report.quic.status, Reachability::Unreachable);
+        assert_eq!(report.diagnostics.len(), 1);
+        assert!(report.diagnostics[0].message.contains("UDP port 9001"));
+
+        tracker.record_inbound(&inbound("/ip4/0.0.0.0/udp/9001/quic-v1"), now);
+
+        let report = tracker.report(now, 10, Some(Ipv4Addr::new(203, 0, 113, 1)));
+
+        assert_eq!(report.quic.status, Reachability::Reachable);
+        assert!(report.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn local_enr_address_is_reported() {
+        let started_at = Instant::now();
+        let tracker = tracker(started_at);
+
+        let report = tracker.report(started_at, 0, Some(Ipv4Addr::new(192, 168, 1, 10)));
+
+        assert_eq!(report.diagnostics.len(), 1);
+        assert!(report.diagnostics[0].message.contains("192.168.1.10"));
+    }
+
+    #[test]
+    fn diagnostics_are_only_taken_once_until_resolved() {
+        let started_at = Instant::now();
+        let mut tracker = tracker(started_at);
+        let now = started_at + GRACE_PERIOD;
+
+        let report = tracker.report(now, 10, None);
+        let (new_diagnostics, resolved) = tracker.take_new_diagnostics(&report);
+
+        assert_eq!(new_diagnostics.len(), 3);
+        assert!(!resolved);
+
+        let (new_diagnostics, resolved) = tracker.take_new_diagnostics(&report);
+
+        assert!(new_diagnostics.is_empty());
+        assert!(!resolved);
+
+        // Messages change as time passes, but the same problems are not reported again.
+        let report = tracker.report(now + GRACE_PERIOD, 10, None);
+        let (new_diagnostics, resolved) = tracker.take_new_diagnostics(&report);
+
+        assert!(new_diagnostics.is_empty());
+        assert!(!resolved);
+
+        tracker.record_inbound(&inbound("/ip4/0.0.0.0/tcp/9000"), now);
+        tracker.record_inbound(&inbound("/ip4/0.0.0.0/udp/9001/quic-v1"), now);
+
+        let report = tracker.report(now, 10, None);
+        let (new_diagnostics, resolved) = tracker.take_new_diagnostics(&report);
+
+        assert!(new_diagnostics.is_empty());
+        assert!(resolved);
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use anyhow::{ensure, Result};
+use database::Database;
+use derive_more::Display;
+use log::info;
+use ssz::SszHash;
+use thiserror::Error;
+use types::phase0::primitives::Slot;
+
+/// Number of slots before the current one for which digests of gossip messages are retained.
+///
+/// Peers only replay messages still present in their `gossipsub` message caches.
+/// Those span a few heartbeats, which is well under a slot on all networks.
+const RETAINED_SLOTS: u64 = 4;
+
+/// Number of slots after the current one for which digests are recorded.
+/// Messages for later slots are rare enough that they are not worth tracking.
+const MAXIMUM_CLOCK_DISPARITY_SLOTS: u64 = 1;
+
+const DIGEST_SIZE: usize = core::mem::size_of::<GossipDigest>();
+
+// The first 8 bytes of the hash tree root of a message.
+// Collisions are unlikely enough to be ignored at the number of messages received per slot.
+pub type GossipDigest = u64;
+
+pub fn gossip_digest(message: &impl SszHash) -> GossipDigest {
+    let mut bytes = [0; DIGEST_SIZE];
+    bytes.copy_from_slice(&message.hash_tree_root()[..DIGEST_SIZE]);
+    GossipDigest::from_le_bytes(bytes)
+}
+
+// `gossipsub` drops duplicate messages by itself, but the set of messages it has seen does not
+// survive restarts. Peers replay recent messages to a restarted node, which then has to verify
+// tens of thousands of attestations it already processed before the restart.
+pub struct SeenGossipDigests {
+    database: Database,
+    current_slot: Slot,
+    digests: BTreeMap<Slot, HashSet<GossipDigest>>,
+    modified_slots: BTreeSet<Slot>,
+}
+
+impl SeenGossipDigests {
+    pub fn load(database: Database, current_slot: Slot) -> Result<Self> {
+        let mut digests = BTreeMap::new();
+
+        for slot in
+            oldest_retained_slot(current_slot)..=current_slot + MAXIMUM_CLOCK_DISPARITY_SLOTS
+        {
+            if let Some(bytes) = database.get(SeenGossipDigestsBySlot(slot).to_string())? {
+                digests.insert(slot, decode(&bytes)?);
+            }
+        }
+
+        let seen_gossip_digests = Self {
+            database,
+            current_slot,
+            digests,
+            modified_slots: BTreeSet::new(),
+        };
+
+        let digest_count = seen_gossip_digests.digest_count();
+
+        if digest_count > 0 {
+            info!("loaded {digest_count} digests of gossip messages received before restart");
+        }
+
+        Ok(seen_gossip_digests)
+    }
+
+    /// Returns `true` if a message with the same digest has not been seen before.
+    ///
+    /// Messages for slots outside the retained range are not tracked and are always new.
+    pub fn insert(&mut self, slot: Slot, digest: GossipDigest) -> bool {
+        let retained_slots = oldest_retained_slot(self.current_slot)
+            ..=self.current_slot + MAXIMUM_CLOCK_DISPARITY_SLOTS;
+
+        if !retained_slots.contains(&slot) {
+            return true;
+        }
+
+        let new = self.digests.entry(slot).or_default().insert(digest);
+
+        if new {
+            self.modified_slots.insert(slot);
+        }
+
+        new
+    }
+
+    /// Prunes digests for old slots and saves the ones received since the last call.
+    pub fn on_slot(&mut self, slot: Slot) -> Result<()> {
+        let oldest_slot = oldest_retained_slot(slot);
+
+        self.current_slot = slot;
+        self.digests = self.digests.split_off(&oldest_slot);
+        self.modified_slots = self.modified_slots.split_off(&oldest_slot);
+
+        let pairs = core::mem::take(&mut self.modified_slots)
+            .into_iter()
+            .filter_map(|slot| {
+                let digests = self.digests.get(&slot)?;
+                Some((SeenGossipDigestsBySlot(slot).to_string(), encode(digests)))
+            });
+
+        self.database.put_batch(pairs)?;
+
+        self.database.delete_range(
+            SeenGossipDigestsBySlot(0).to_string()
+                ..SeenGossipDigestsBySlot(oldest_slot).to_string(),
+        )
+    }
+
+    #[must_use]
+    pub fn digest_count(&self) -> usize {
+        self.digests.values().map(HashSet::len).sum()
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "{}{_0:020}", Self::PREFIX)]
+struct SeenGossipDigestsBySlot(Slot);
+
+impl SeenGossipDigestsBySlot {
+    const PREFIX: &'static str = "g";
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("stored gossip digests have invalid length: {length}")]
+    InvalidLength { length: usize },
+}
+
+const fn oldest_retained_slot(current_slot: Slot) -> Slot {
+    current_slot.saturating_sub(RETAINED_SLOTS)
+}
+
+// Digests are stored as concatenated little-endian integers. SSZ would require a length limit.
+fn encode(digests: &HashSet<GossipDigest>) -> Vec<u8> {
+    digests.iter().copied().flat_map(u64::to_le_bytes).collect()
+}
+
+fn decode(bytes: &[u8]) -> Result<HashSet<GossipDigest>> {
+    let length = bytes.len();
+
+    ensure!(length % DIGEST_SIZE == 0, Error::InvalidLength { length });
+
+    let digests = bytes
+        .chunks_exact(DIGEST_SIZE)
+        .map(|chunk| {
+            let mut digest = [0; DIGEST_SIZE];
+            digest.copy_from_slice(chunk);
+            GossipDigest::from_le_bytes(digest)
+        })
+        .collect();
+
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seen_gossip_digests_survive_reload() -> Result<()> {
+        let database = Database::in_memory();
+        let mut seen = SeenGossipDigests::load(database, 10)?;
+
+        assert!(seen.insert(10, 1));
+        assert!(seen.insert(10, 2));
+        assert!(!seen.insert(10, 1));
+        assert!(seen.insert(9, 1));
+
+        seen.on_slot(11)?;
+
+        let mut reloaded = SeenGossipDigests::load(seen.database, 11)?;
+
+        assert_eq!(reloaded.digest_count(), 3);
+        assert!(!reloaded.insert(10, 2));
+        assert!(!reloaded.insert(9, 1));
+        assert!(reloaded.insert(11, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn seen_gossip_digests_are_pruned_after_retained_slots() -> Result<()> {
+        let database = Database::in_memory();
+        let mut seen = SeenGossipDigests::load(database, 10)?;
+
+        assert!(seen.insert(10, 1));
+
+        seen.on_slot(10 + RETAINED_SLOTS)?;
+
+        assert!(!seen.insert(10, 1));
+
+        seen.on_slot(11 + RETAINED_SLOTS)?;
+
+        assert_eq!(seen.digest_count(), 0);
+
+        let reloaded = SeenGossipDigests::load(seen.database, 11 + RETAINED_SLOTS)?;
+
+        assert_eq!(reloaded.digest_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn seen_gossip_digests_do_not_track_slots_outside_retained_range() -> Result<()> {
+        let mut seen = SeenGossipDigests::load(Database::in_memory(), 10)?;
+        let oldest_slot = 10 - RETAINED_SLOTS;
+
+        assert!(seen.insert(oldest_slot - 1, 1));
+        assert!(seen.insert(oldest_slot - 1, 1));
+        assert!(seen.insert(12, 1));
+        assert!(seen.insert(12, 1));
+        assert!(seen.insert(oldest_slot, 1));
+        assert!(!seen.insert(oldest_slot, 1));
+        assert!(seen.insert(11, 1));
+        assert!(!seen.insert(11, 1));
+
+        Ok(())
+    }
+}
@@ -19,7 +19,7 @@ use types::{
 use crate::{
     attestation_subnets::AttestationSubnets,
     beacon_committee_subscriptions::BeaconCommitteeSubscriptions,
-    messages::{SubnetServiceToP2p, ToSubnetService},
+    messages::{SubnetServiceToAttestationVerifier, SubnetServiceToP2p, ToSubnetService},
     misc::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
     sync_committee_subnets::SyncCommitteeSubnets,
 };
@@ -30,6 +30,7 @@ pub struct SubnetService<P: Preset, W: Wait> {
     sync_committee_subnets: SyncCommitteeSubnets<P>,
     beacon_committee_subscriptions: BeaconCommitteeSubscriptions,
     p2p_tx: UnboundedSender<SubnetServiceToP2p>,
+    attestation_verifier_tx: UnboundedSender<SubnetServiceToAttestationVerifier>,
     fork_choice_rx: UnboundedReceiver<SubnetMessage<W>>,
     rx: UnboundedReceiver<ToSubnetService>,
 }
@@ -40,6 +41,7 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
         attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
         node_id: NodeId,
         p2p_tx: UnboundedSender<SubnetServiceToP2p>,
+        attestation_verifier_tx: UnboundedSender<SubnetServiceToAttestationVerifier>,
         fork_choice_rx: UnboundedReceiver<SubnetMessage<W>>,
         rx: UnboundedReceiver<ToSubnetService>,
     ) -> Self {
@@ -49,6 +51,7 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
             sync_committee_subnets: SyncCommitteeSubnets::default(),
             beacon_committee_subscriptions: BeaconCommitteeSubscriptions::default(),
             p2p_tx,
+            attestation_verifier_tx,
             fork_choice_rx,
             rx,
         }
@@ -121,6 +124,8 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
             SubnetServiceToP2p::UpdateAttestationSubnets(actions).send(&self.p2p_tx);
         }
 
+        self.send_aggregation_duties()?;
+
         let actions = self.sync_committee_subnets.on_slot(slot);
 
         if !actions.is_empty() {
@@ -148,6 +153,17 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
 
         SubnetServiceToP2p::UpdateAttestationSubnets(actions).send(&self.p2p_tx);
 
+        self.send_aggregation_duties()
+    }
+
+    fn send_aggregation_duties(&self) -> Result<()> {
+        let duties = self
+            .beacon_committee_subscriptions
+            .aggregation_duties::<P>()?;
+
+        SubnetServiceToAttestationVerifier::AggregationDuties(duties)
+            .send(&self.attestation_verifier_tx);
+
         Ok(())
     }
 
@@ -1,5 +1,9 @@
 use core::{fmt::Display, hash::Hash, ops::Range, time::Duration};
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::Result;
 use arithmetic::NonZeroExt as _;
@@ -19,7 +23,9 @@ use types::{
 };
 
 use crate::{
-    block_sync_service::SyncDirection, misc::RequestId,
+    block_sync_service::SyncDirection,
+    misc::{RequestId, SyncDiagnostics},
+    peer_request_stats::{PeerRequestStats, PeerRequestStatsTracker},
     range_and_root_requests::RangeAndRootRequests,
 };
 
@@ -65,8 +71,13 @@ pub struct SyncBatch {
 
 pub struct SyncManager {
     peers: HashMap<PeerId, StatusMessage>,
+    // Slots of the earliest blocks peers served in response to back sync requests.
+    // Peers are assumed to have all blocks until they are seen to be missing some.
+    peer_earliest_available_slots: HashMap<PeerId, Slot>,
+    lowest_back_sync_slots: HashMap<RequestId, Slot>,
     blob_requests: RangeAndRootRequests<BlobIdentifier>,
     block_requests: RangeAndRootRequests<H256>,
+    peer_request_stats: PeerRequestStatsTracker,
     last_sync_head: Slot,
     last_sync_range: Range<Slot>,
     sequential_redownloads: usize,
@@ -78,8 +89,11 @@ impl Default for SyncManager {
     fn default() -> Self {
         Self {
             peers: HashMap::new(),
+            peer_earliest_available_slots: HashMap::new(),
+            lowest_back_sync_slots: HashMap::new(),
             blob_requests: RangeAndRootRequests::<BlobIdentifier>::default(),
             block_requests: RangeAndRootRequests::<H256>::default(),
+            peer_request_stats: PeerRequestStatsTracker::default(),
             last_sync_range: 0..0,
             last_sync_head: 0,
             sequential_redownloads: 0,
@@ -97,6 +111,10 @@ impl SyncManager {
         self.block_requests.request_direction(request_id)
     }
 
+    pub fn block_request_started_at(&mut self, request_id: RequestId) -> Option<Instant> {
+        self.block_requests.request_by_range_started_at(request_id)
+    }
+
     pub fn add_peer(&mut self, peer_id: PeerId, status: StatusMessage) {
         self.log_with_feature(format_args!(
             "add peer (peer_id: {peer_id}, status: {status:?})",
@@ -108,6 +126,7 @@ impl SyncManager {
     pub fn remove_peer(&mut self, peer_id: &PeerId) -> Vec<SyncBatch> {
         self.log_with_feature(format_args!("remove peer (peer_id: {peer_id})"));
         self.peers.remove(peer_id);
+        self.peer_earliest_available_slots.remove(peer_id);
 
         self.block_requests
             .remove_peer(peer_id)
@@ -116,7 +135,11 @@ impl SyncManager {
     }
 
     pub fn retry_batch(&mut self, request_id: RequestId, batch: &SyncBatch) -> Option<PeerId> {
-        let peer = self.random_peer();
+        let peer = match batch.direction {
+            SyncDirection::Back => self.random_peer_with_blocks_from(batch.start_slot),
+            SyncDirection::Forward => None,
+        }
+        .or_else(|| self.random_peer());
 
         self.log_with_feature(format_args!(
             "retrying batch {batch:?}, new peer: {peer:?}, request_id: {request_id}",
@@ -166,9 +189,10 @@ impl SyncManager {
 
         let slots_per_request = P::SlotsPerEpoch::non_zero().get() * EPOCHS_PER_REQUEST;
 
+        let mut unassigned_peers = Self::peer_sync_batch_assignments(&peers_to_sync).collect_vec();
         let mut sync_batches = vec![];
 
-        for (peer_id, index) in Self::peer_sync_batch_assignments(&peers_to_sync).zip(0..) {
+        for index in 0.. {
             let start_slot = state_slot
                 .saturating_sub(slots_per_request * (index + 1))
                 .max(low_slot);
@@ -181,6 +205,20 @@ impl SyncManager {
                 slots_per_request
             };
 
+            // Fall back to all peers if none of them are known to have the blocks.
+            // It is better to retry requests than to stop back sync entirely.
+            let any_peer_has_blocks = peers_to_sync
+                .iter()
+                .any(|peer_id| self.may_have_blocks_from(peer_id, start_slot));
+
+            let Some(position) = unassigned_peers.iter().position(|peer_id| {
+                !any_peer_has_blocks || self.may_have_blocks_from(peer_id, start_slot)
+            }) else {
+                break;
+            };
+
+            let peer_id = unassigned_peers.remove(position);
+
             let batch = SyncBatch {
                 target: SyncTarget::Block,
                 direction: SyncDirection::Back,
@@ -379,6 +417,8 @@ impl SyncManager {
             (batch.start_slot..(batch.start_slot + batch.count)),
         ));
 
+        self.peer_request_stats
+            .request_started(request_id, batch.peer_id);
         self.block_requests.add_request_by_range(request_id, batch)
     }
 
@@ -402,12 +442,33 @@ impl SyncManager {
             .choose(&mut thread_rng())
     }
 
+    // Prefer peers that have not been asked for the blobs yet.
+    // Blobs are only requested by root when they did not arrive through gossip in time.
+    pub fn random_peer_for_blobs(&mut self, blob_ids: &[BlobIdentifier]) -> Option<PeerId> {
+        let chain_id = self.chain_with_max_peer_count()?;
+
+        let requested_peers = blob_ids
+            .iter()
+            .flat_map(|blob_id| self.blob_requests.requested_peers(blob_id).collect_vec())
+            .collect::<HashSet<_>>();
+
+        let (fresh_peers, requested_peers) = self
+            .chain_peers(&chain_id)
+            .into_iter()
+            .partition::<Vec<_>, _>(|peer_id| !requested_peers.contains(peer_id));
+
+        fresh_peers
+            .into_iter()
+            .choose(&mut thread_rng())
+            .or_else(|| requested_peers.into_iter().choose(&mut thread_rng()))
+    }
+
     pub fn blobs_by_range_request_finished(&mut self, request_id: RequestId) {
         self.log_with_feature(format_args!(
             "request blob sidecars by range finished (request_id: {request_id})",
         ));
 
-        self.blob_requests.request_by_range_finished(request_id)
+        self.blob_requests.request_by_range_finished(request_id);
     }
 
     pub fn received_blob_sidecar_chunk(
@@ -424,12 +485,93 @@ impl SyncManager {
             .chunk_by_root_received(&blob_identifier, &peer_id)
     }
 
-    pub fn blocks_by_range_request_finished(&mut self, request_id: RequestId) {
+    pub fn block_received(&mut self, request_id: RequestId) {
+        self.peer_request_stats.block_received(request_id);
+    }
+
+    pub fn request_failed(&mut self, peer_id: PeerId) {
+        self.peer_request_stats.request_failed(peer_id);
+    }
+
+    pub fn remove_peer_request_stats(&mut self, peer_id: &PeerId) {
+        self.peer_request_stats.remove_peer(peer_id);
+    }
+
+    /// Returns peers that should be downscored for serving sync requests consistently slower
+    /// than other peers.
+    pub fn take_slow_peers(&mut self) -> Vec<PeerId> {
+        let slow_peers = self.peer_request_stats.take_slow_peers();
+
+        for peer_id in &slow_peers {
+            self.log(
+                Level::Info,
+                format_args!("peer {peer_id} is consistently slow to serve sync requests"),
+            );
+        }
+
+        slow_peers
+    }
+
+    #[must_use]
+    pub fn peer_request_stats(&self) -> HashMap<PeerId, PeerRequestStats> {
+        self.peer_request_stats.stats()
+    }
+
+    pub fn back_sync_block_received(&mut self, request_id: RequestId, slot: Slot) {
+        self.lowest_back_sync_slots
+            .entry(request_id)
+            .and_modify(|lowest_slot| *lowest_slot = (*lowest_slot).min(slot))
+            .or_insert(slot);
+    }
+
+    /// Returns the part of a back sync batch that has to be requested from another peer.
+    pub fn blocks_by_range_request_finished<P: Preset>(
+        &mut self,
+        request_id: RequestId,
+    ) -> Option<SyncBatch> {
         self.log_with_feature(format_args!(
             "request blocks by range finished (request_id: {request_id})",
         ));
 
-        self.block_requests.request_by_range_finished(request_id)
+        self.peer_request_stats.request_finished(request_id);
+
+        let lowest_slot = self.lowest_back_sync_slots.remove(&request_id);
+        let batch = self.block_requests.request_by_range_finished(request_id)?;
+
+        if batch.direction != SyncDirection::Back {
+            return None;
+        }
+
+        let end_slot = batch.start_slot + batch.count;
+        let available_from = lowest_slot.unwrap_or(end_slot).min(end_slot);
+
+        // Empty slots are common, but entire empty epochs are not.
+        // A gap that long most likely means the peer has pruned the blocks.
+        if available_from <= batch.start_slot + P::SlotsPerEpoch::U64 {
+            if let Some(earliest_slot) = self.peer_earliest_available_slots.get_mut(&batch.peer_id)
+            {
+                *earliest_slot = (*earliest_slot).min(batch.start_slot);
+            }
+
+            return None;
+        }
+
+        self.log_with_feature(format_args!(
+            "peer {} only served blocks from slot {available_from} in back sync batch {batch:?}",
+            batch.peer_id,
+        ));
+
+        self.peer_earliest_available_slots
+            .insert(batch.peer_id, available_from);
+
+        let missing_batch = SyncBatch {
+            count: available_from - batch.start_slot,
+            ..batch
+        };
+
+        self.random_peer_with_blocks_from(missing_batch.start_slot)
+            .is_some()
+            .then_some(missing_batch)
     }
 
     pub fn block_by_root_request_finished(&mut self, block_root: H256) {
@@ -461,7 +603,7 @@ impl SyncManager {
 
     fn find_peers_to_sync(&mut self) -> Option<Vec<PeerId>> {
         self.find_chain_to_sync().map(|chain_id| {
-            let peers_to_sync = self.chain_peers_shuffled(&chain_id);
+            let peers_to_sync = self.chain_peers_by_throughput(&chain_id);
 
             self.log_with_feature(format_args!("peers to sync count: {}", peers_to_sync.len()));
 
@@ -494,6 +636,21 @@ impl SyncManager {
         }
     }
 
+    fn random_peer_with_blocks_from(&self, slot: Slot) -> Option<PeerId> {
+        let chain_id = self.chain_with_max_peer_count()?;
+
+        self.chain_peers(&chain_id)
+            .into_iter()
+            .filter(|peer_id| self.may_have_blocks_from(peer_id, slot))
+            .choose(&mut thread_rng())
+    }
+
+    fn may_have_blocks_from(&self, peer_id: &PeerId, slot: Slot) -> bool {
+        self.peer_earliest_available_slots
+            .get(peer_id)
+            .map_or(true, |earliest_slot| *earliest_slot <= slot)
+    }
+
     fn chain_peers(&self, chain_id: &ChainId) -> Vec<PeerId> {
         self.peers
             .iter()
@@ -502,9 +659,11 @@ impl SyncManager {
             .collect()
     }
 
-    fn chain_peers_shuffled(&self, chain_id: &ChainId) -> Vec<PeerId> {
+    // Peers are shuffled before sorting to spread batches among peers with equal throughput.
+    fn chain_peers_by_throughput(&self, chain_id: &ChainId) -> Vec<PeerId> {
         let mut peers = self.chain_peers(chain_id);
         peers.shuffle(&mut thread_rng());
+        self.peer_request_stats.sort_by_throughput(&mut peers);
         peers
     }
 
@@ -561,6 +720,7 @@ impl SyncManager {
     pub fn expired_block_range_batches(
         &mut self,
     ) -> impl Iterator<Item = (SyncBatch, Instant)> + '_ {
+        self.peer_request_stats.expire_requests();
         self.block_requests.expired_range_batches()
     }
 
@@ -592,14 +752,46 @@ impl SyncManager {
     }
 
     pub fn cache_clear(&mut self) {
+        self.lowest_back_sync_slots.clear();
         self.blob_requests.cache_clear();
         self.block_requests.cache_clear();
+        self.peer_request_stats.cache_clear();
+    }
+
+    /// Forgets requests in flight and progress of forward sync.
+    ///
+    /// Returns all known peers. Their statuses may be outdated and should be requested again.
+    pub fn reset(&mut self) -> Vec<PeerId> {
+        self.cache_clear();
+        self.last_sync_head = 0;
+        self.last_sync_range = 0..0;
+        self.sequential_redownloads = 0;
+        self.peers.keys().copied().collect()
+    }
+
+    pub fn diagnostics(&mut self) -> SyncDiagnostics {
+        SyncDiagnostics {
+            peer_count: self.total_peers(),
+            max_peer_head_slot: self.peers.values().map(|status| status.head_slot).max(),
+            block_requests_by_range: self.block_requests.request_by_range_count(),
+            blob_requests_by_range: self.blob_requests.request_by_range_count(),
+            sequential_redownloads: self.sequential_redownloads,
+            ..SyncDiagnostics::default()
+        }
     }
 
     pub fn track_collection_metrics(&self, metrics: &Arc<Metrics>) {
         let type_name = tynm::type_name::<Self>();
 
         metrics.set_collection_length(&[&type_name, "peers"], self.peers.len());
+        metrics.set_collection_length(
+            &[&type_name, "peer_earliest_available_slots"],
+            self.peer_earliest_available_slots.len(),
+        );
+        metrics.set_collection_length(
+            &[&type_name, "lowest_back_sync_slots"],
+            self.lowest_back_sync_slots.len(),
+        );
         metrics.set_collection_length(
             &[&type_name, "status_updates_cache"],
             self.status_updates_cache.cache_size(),
@@ -608,6 +800,7 @@ impl SyncManager {
         // TODO: Differentiate
         self.blob_requests.track_collection_metrics(metrics);
         self.block_requests.track_collection_metrics(metrics);
+        self.peer_request_stats.track_collection_metrics(metrics);
     }
 }
 
@@ -677,4 +870,129 @@ mod tests {
             resulting_batches,
         );
     }
+
+    #[test]
+    fn back_sync_avoids_peers_that_are_missing_blocks() {
+        let peer_status = StatusMessage {
+            fork_digest: H32::default(),
+            finalized_root: H256::default(),
+            finalized_epoch: 6,
+            head_root: H256::default(),
+            head_slot: 8 * 32,
+        };
+
+        let peer_with_blocks = PeerId::random();
+        let peer_without_blocks = PeerId::random();
+
+        let mut sync_manager = SyncManager::default();
+
+        sync_manager.add_peer(peer_with_blocks, peer_status);
+        sync_manager.add_peer(peer_without_blocks, peer_status);
+
+        let request_id = 0;
+
+        let batch = SyncBatch {
+            target: SyncTarget::Block,
+            direction: SyncDirection::Back,
+            peer_id: peer_without_blocks,
+            start_slot: 112,
+            count: 16,
+        };
+
+        sync_manager.add_block_request_by_range(request_id, batch);
+        sync_manager.back_sync_block_received(request_id, 127);
+
+        let missing_batch = sync_manager.blocks_by_range_request_finished::<Minimal>(request_id);
+
+        assert_eq!(
+            missing_batch.map(|batch| (batch.peer_id, batch.start_slot, batch.count)),
+            Some((peer_without_blocks, 112, 15)),
+        );
+
+        let batches = sync_manager.build_back_sync_batches::<Minimal>(128, 0);
+
+        itertools::assert_equal(
+            batches
+                .into_iter()
+                .map(|batch| (batch.peer_id, batch.start_slot)),
+            [
+                (peer_with_blocks, 112),
+                (peer_with_blocks, 96),
+                (peer_with_blocks, 80),
+            ],
+        );
+    }
+
+    #[test]
+    fn reset_forgets_requests_in_flight() {
+        let peer_status = StatusMessage {
+            fork_digest: H32::default(),
+            finalized_root: H256::default(),
+            finalized_epoch: 6,
+            head_root: H256::default(),
+            head_slot: 8 * 32,
+        };
+
+        let peer_id = PeerId::random();
+
+        let mut sync_manager = SyncManager::default();
+
+        sync_manager.add_peer(peer_id, peer_status);
+
+        let batch = SyncBatch {
+            target: SyncTarget::Block,
+            direction: SyncDirection::Forward,
+            peer_id,
+            start_slot: 112,
+            count: 16,
+        };
+
+        sync_manager.add_block_request_by_range(0, batch);
+
+        let diagnostics = sync_manager.diagnostics();
+
+        assert_eq!(diagnostics.peer_count, 1);
+        assert_eq!(diagnostics.max_peer_head_slot, Some(8 * 32));
+        assert_eq!(diagnostics.block_requests_by_range, 1);
+
+        assert_eq!(sync_manager.reset(), [peer_id]);
+        assert_eq!(sync_manager.diagnostics().block_requests_by_range, 0);
+        assert!(sync_manager.ready_to_request_blocks_by_range());
+    }
+
+    #[test]
+    fn blobs_are_requested_from_peers_not_asked_yet() {
+        let peer_status = StatusMessage {
+            fork_digest: H32::default(),
+            finalized_root: H256::default(),
+            finalized_epoch: 6,
+            head_root: H256::default(),
+            head_slot: 8 * 32,
+        };
+
+        let asked_peer = PeerId::random();
+        let fresh_peer = PeerId::random();
+
+        let blob_ids = [BlobIdentifier {
+            block_root: H256::repeat_byte(1),
+            index: 0,
+        }];
+
+        let mut sync_manager = SyncManager::default();
+
+        sync_manager.add_peer(asked_peer, peer_status);
+        sync_manager.add_peer(fresh_peer, peer_status);
+
+        assert_eq!(
+            sync_manager.add_blobs_request_by_root(blob_ids.to_vec(), asked_peer),
+            blob_ids,
+        );
+
+        for _ in 0..10 {
+            assert_eq!(
+                sync_manager.random_peer_for_blobs(&blob_ids),
+                Some(fresh_peer),
+            );
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use core::time::Duration;
+use std::{collections::VecDeque, time::Instant};
+
+use types::phase0::primitives::Slot;
+
+// Long enough to smooth out stalls between batches, short enough to reflect changes in peers.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(300);
+const BATCH_LATENCIES_TO_AVERAGE: usize = 16;
+
+/// Throughput of forward or back sync measured from slots reached over a recent time window.
+#[derive(Default)]
+pub struct SyncProgress {
+    samples: VecDeque<(Instant, Slot)>,
+    batch_latencies: VecDeque<Duration>,
+}
+
+impl SyncProgress {
+    pub fn record_slot(&mut self, slot: Slot, now: Instant) {
+        self.samples.push_back((now, slot));
+
+        // Keep the latest sample outside the window so throughput covers the whole window.
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(time, _)| now.duration_since(*time) >= THROUGHPUT_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn record_batch_latency(&mut self, latency: Duration) {
+        if self.batch_latencies.len() == BATCH_LATENCIES_TO_AVERAGE {
+            self.batch_latencies.pop_front();
+        }
+
+        self.batch_latencies.push_back(latency);
+    }
+
+    /// Returns throughput in thousandths of a slot per second to avoid floating point arithmetic.
+    pub fn milli_slots_per_second(&self) -> Option<u64> {
+        let (elapsed, slots) = self.window()?;
+        let rate = u128::from(slots) * 1_000_000 / elapsed.as_millis().max(1);
+        u64::try_from(rate).ok()
+    }
+
+    pub fn time_to_sync(&self, remaining_slots: u64) -> Option<Duration> {
+        let (elapsed, slots) = self.window()?;
+
+        if slots == 0 {
+            return None;
+        }
+
+        let millis = elapsed.as_millis() * u128::from(remaining_slots) / u128::from(slots);
+
+        u64::try_from(millis).ok().map(Duration::from_millis)
+    }
+
+    pub fn average_batch_latency(&self) -> Option<Duration> {
+        let count = u32::try_from(self.batch_latencies.len()).ok()?;
+
+        if count == 0 {
+            return None;
+        }
+
+        Some(self.batch_latencies.iter().sum::<Duration>() / count)
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.batch_latencies.clear();
+    }
+
+    // Slots are counted in either direction, so this works for back sync too.
+    fn window(&self) -> Option<(Duration, u64)> {
+        let (first_time, first_slot) = self.samples.front()?;
+        let (last_time, last_slot) = self.samples.back()?;
+        let elapsed = last_time.duration_since(*first_time);
+
+        (!elapsed.is_zero()).then(|| (elapsed, last_slot.abs_diff(*first_slot)))
+    }
+}
+
+/// Formats rates returned by [`SyncProgress::milli_slots_per_second`] with one decimal place.
+pub fn format_milli_rate(milli_rate: u64) -> String {
+    format!("{}.{}", milli_rate / 1000, milli_rate % 1000 / 100)
+}
+
+/// Formats durations with the two most significant units, e.g. `1h 23m` or `4m 05s`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn throughput_and_time_to_sync_are_computed_over_window() {
+        let start = Instant::now();
+        let mut progress = SyncProgress::default();
+
+        assert_eq!(progress.milli_slots_per_second(), None);
+
+        progress.record_slot(100, start);
+
+        assert_eq!(progress.milli_slots_per_second(), None);
+
+        progress.record_slot(400, start + Duration::from_secs(200));
+        progress.record_slot(1000, start + Duration::from_secs(400));
+        progress.record_slot(1600, start + Duration::from_secs(600));
+
+        // The first sample is dropped once a newer one is old enough to cover the window.
+        assert_eq!(progress.milli_slots_per_second(), Some(3000));
+        assert_eq!(progress.time_to_sync(900), Some(Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn back_sync_throughput_is_positive() {
+        let start = Instant::now();
+        let mut progress = SyncProgress::default();
+
+        progress.record_slot(1000, start);
+        progress.record_slot(900, start + Duration::from_secs(10));
+
+        assert_eq!(progress.milli_slots_per_second(), Some(10_000));
+    }
+
+    #[test]
+    fn stalled_sync_has_no_time_to_sync() {
+        let start = Instant::now();
+        let mut progress = SyncProgress::default();
+
+        progress.record_slot(100, start);
+        progress.record_slot(100, start + Duration::from_secs(10));
+
+        assert_eq!(progress.time_to_sync(100), None);
+    }
+
+    #[test]
+    fn average_batch_latency_only_includes_recent_batches() {
+        let mut progress = SyncProgress::default();
+
+        assert_eq!(progress.average_batch_latency(), None);
+
+        progress.record_batch_latency(Duration::from_secs(100));
+
+        for _ in 0..BATCH_LATENCIES_TO_AVERAGE {
+            progress.record_batch_latency(Duration::from_secs(2));
+        }
+
+        assert_eq!(
+            progress.average_batch_latency(),
+            Some(Duration::from_secs(2)),
+        );
+    }
+
+    #[test_case(0 => "0.0")]
+    #[test_case(2_560 => "2.5")]
+    #[test_case(12_999 => "12.9")]
+    fn format_milli_rate_truncates_to_one_decimal_place(milli_rate: u64) -> String {
+        format_milli_rate(milli_rate)
+    }
+
+    #[test_case(Duration::from_secs(42) => "42s")]
+    #[test_case(Duration::from_secs(245) => "4m 05s")]
+    #[test_case(Duration::from_secs(4980) => "1h 23m")]
+    fn format_duration_uses_two_most_significant_units(duration: Duration) -> String {
+        format_duration(duration)
+    }
+}
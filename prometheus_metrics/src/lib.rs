@@ -1,7 +1,9 @@
 pub use crate::{
     helpers::{duration_from_now_to, start_timer_vec, stop_and_discard, stop_and_record},
     metrics::Metrics,
+    operational_counters::{OperationalCounter, OperationalCounterValues},
 };
 
 mod helpers;
 mod metrics;
+mod operational_counters;
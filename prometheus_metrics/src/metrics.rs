@@ -3,12 +3,15 @@ use core::time::Duration;
 
 use log::warn;
 use prometheus::{
-    histogram_opts, opts, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
-    IntGaugeVec,
+    histogram_opts, opts, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec,
 };
 use types::phase0::primitives::{Epoch, Gwei, Slot, UnixSeconds};
 
-use crate::helpers;
+use crate::{
+    helpers,
+    operational_counters::{OperationalCounter, OperationalCounterValues},
+};
 
 #[derive(Debug)]
 pub struct Metrics {
@@ -29,8 +32,12 @@ pub struct Metrics {
     // Collection Lengths
     collection_lengths: IntGaugeVec,
 
+    // Logging
+    log_warning_occurrences: IntCounterVec,
+
     // HTTP API metrics
     http_api_response_times: HistogramVec,
+    http_api_dropped_events: IntCounterVec,
 
     // Dedicated Executor
     pub dedicated_executor_task_times: Histogram,
@@ -39,21 +46,36 @@ pub struct Metrics {
 
     // Network / Gossip stats
     gossip_objects: IntCounterVec,
+    gossip_clamped_objects: IntCounterVec,
+    own_gossip_publish_retries: IntCounterVec,
+    own_gossip_publish_failures: IntCounterVec,
+    gossip_topic_encodings: IntCounterVec,
+    req_resp_protocol_versions: IntCounterVec,
+    downgraded_protocol_peers: IntGauge,
     pub received_sync_contribution_subsets: IntCounter,
     pub received_aggregated_attestation_subsets: IntCounter,
 
     // Extra Network stats
     gossip_block_slot_start_delay_time: Histogram,
 
+    // Sync progress
+    sync_milli_slots_per_second: IntGaugeVec,
+    sync_time_to_sync_seconds: GaugeVec,
+    sync_batch_latency_seconds: GaugeVec,
+
     // Mutator
     mutator_attestations: IntCounterVec,
     mutator_aggregate_and_proofs: IntCounterVec,
+    mutator_invalidated_blocks: IntCounter,
+    mutator_blob_recovery_attempts: IntCounterVec,
+    mutator_blob_recovery_successes: IntCounterVec,
 
     pub block_processing_times: Histogram,
     pub block_post_processing_times: Histogram,
 
     // Attestation Verifier
     attestation_verifier_active_task_count: IntGauge,
+    attestation_verifier_ignored_attestations: IntCounter,
 
     pub attestation_verifier_process_attestation_batch_times: Histogram,
     pub attestation_verifier_processs_aggregate_batch_times: Histogram,
@@ -78,12 +100,17 @@ pub struct Metrics {
     pub validator_propose_successes: IntCounter,
     pub validator_proposal_slashing_protector_times: Histogram,
 
+    // Sync committees
+    validator_own_sync_committee_messages: IntCounterVec,
+    validator_own_sync_committee_deltas: IntCounterVec,
+
     // Build beacon block times
     pub build_beacon_block_times: Histogram,
     pub local_execution_payload_times: Histogram,
     pub process_sync_committee_contribution_times: Histogram,
     pub prepare_bls_to_execution_changes_times: Histogram,
     pub eth1_vote_times: Histogram,
+    eth1_vote_strategies: IntCounterVec,
     pub eth1_pending_deposits_times: Histogram,
     pub prepare_attester_slashings_times: Histogram,
     pub prepare_proposer_slashings_times: Histogram,
@@ -135,10 +162,17 @@ pub struct Metrics {
     beacon_participation_prev_epoch_target_attesting_gwei_total: IntGauge,
     validator_count: IntGauge,
 
+    // Operational counters
+    operational_counters: IntCounterVec,
+    operational_counters_lifetime: IntCounterVec,
+
     // Builder API
     pub builder_register_validator_times: Histogram,
     pub builder_post_blinded_block_times: Histogram,
     pub builder_get_execution_payload_header_times: Histogram,
+    builder_relay_header_requests: IntCounterVec,
+    builder_relay_header_latencies: HistogramVec,
+    builder_relay_scores: IntGaugeVec,
 
     // WebSigner
     pub web3signer_load_keys_times: Histogram,
@@ -148,6 +182,8 @@ pub struct Metrics {
     pub eth1_api_request_times: HistogramVec,
     pub eth1_api_errors_count: IntCounter,
     pub eth1_api_reset_count: IntCounter,
+    eth1_api_catch_up_mode: IntGauge,
+    eth1_api_catch_up_transitions: IntCounterVec,
 
     // Jemalloc stats
     pub jemalloc_bytes_allocated: IntGauge,
@@ -190,6 +226,15 @@ impl Metrics {
                 &["type", "name"],
             )?,
 
+            // Logging
+            log_warning_occurrences: IntCounterVec::new(
+                opts!(
+                    "LOG_WARNING_OCCURRENCES",
+                    "Number of warnings and errors by call site, including ones left out of logs",
+                ),
+                &["level", "call_site"],
+            )?,
+
             // HTTP API metrics
             http_api_response_times: HistogramVec::new(
                 histogram_opts!(
@@ -199,6 +244,14 @@ impl Metrics {
                 &["request_path"],
             )?,
 
+            http_api_dropped_events: IntCounterVec::new(
+                opts!(
+                    "HTTP_API_DROPPED_EVENTS",
+                    "Number of events dropped because event stream clients fell behind",
+                ),
+                &["topic"],
+            )?,
+
             // Dedicated Executor
             dedicated_executor_task_times: Histogram::with_opts(histogram_opts!(
                 "DEDICATED_EXECUTOR_TASK_TIMES",
@@ -224,6 +277,51 @@ impl Metrics {
                 &["type"],
             )?,
 
+            gossip_clamped_objects: IntCounterVec::new(
+                opts!(
+                    "GOSSIP_CLAMPED_OBJECTS",
+                    "Counter for objects received via gossip that were ignored because of their slot",
+                ),
+                &["type", "reason"],
+            )?,
+
+            own_gossip_publish_retries: IntCounterVec::new(
+                opts!(
+                    "OWN_GOSSIP_PUBLISH_RETRIES",
+                    "Number of times publishing own objects was delayed due to too few mesh peers",
+                ),
+                &["type"],
+            )?,
+
+            own_gossip_publish_failures: IntCounterVec::new(
+                opts!(
+                    "OWN_GOSSIP_PUBLISH_FAILURES",
+                    "Number of own objects published with too few mesh peers after all retries",
+                ),
+                &["type"],
+            )?,
+
+            gossip_topic_encodings: IntCounterVec::new(
+                opts!(
+                    "GOSSIP_TOPIC_ENCODINGS",
+                    "Counter for objects received via gossip by encoding of their topic",
+                ),
+                &["encoding"],
+            )?,
+
+            req_resp_protocol_versions: IntCounterVec::new(
+                opts!(
+                    "REQ_RESP_PROTOCOL_VERSIONS",
+                    "Counter for requests received from peers by protocol and negotiated version",
+                ),
+                &["protocol", "version"],
+            )?,
+
+            downgraded_protocol_peers: IntGauge::new(
+                "DOWNGRADED_PROTOCOL_PEERS",
+                "Number of connected peers that negotiated outdated req/resp protocol versions",
+            )?,
+
             received_sync_contribution_subsets: IntCounter::new(
                 "RECEIVED_SYNC_CONTRIBUTION_SUBSETS",
                 "Number of received sync contributions that are subsets of already known aggregates"
@@ -240,6 +338,31 @@ impl Metrics {
                 "Duration between when the block is received and the start of the slot it belongs to.",
             ))?,
 
+            // Sync progress
+            sync_milli_slots_per_second: IntGaugeVec::new(
+                opts!(
+                    "SYNC_MILLI_SLOTS_PER_SECOND",
+                    "Thousandths of a slot synced per second over the last few minutes by sync direction",
+                ),
+                &["direction"],
+            )?,
+
+            sync_time_to_sync_seconds: GaugeVec::new(
+                opts!(
+                    "SYNC_TIME_TO_SYNC_SECONDS",
+                    "Estimated time until sync completes by sync direction (0 if synced or unknown)",
+                ),
+                &["direction"],
+            )?,
+
+            sync_batch_latency_seconds: GaugeVec::new(
+                opts!(
+                    "SYNC_BATCH_LATENCY_SECONDS",
+                    "Average latency of recent blocks by range requests by sync direction",
+                ),
+                &["direction"],
+            )?,
+
             // Mutator
             mutator_attestations: IntCounterVec::new(
                 opts!(
@@ -257,6 +380,27 @@ impl Metrics {
                 &["type"],
             )?,
 
+            mutator_invalidated_blocks: IntCounter::new(
+                "MUTATOR_INVALIDATED_BLOCKS",
+                "Number of optimistically imported blocks invalidated by the execution engine",
+            )?,
+
+            mutator_blob_recovery_attempts: IntCounterVec::new(
+                opts!(
+                    "MUTATOR_BLOB_RECOVERY_ATTEMPTS",
+                    "Number of attempts to recover blobs missing after the availability timeout by source",
+                ),
+                &["source"],
+            )?,
+
+            mutator_blob_recovery_successes: IntCounterVec::new(
+                opts!(
+                    "MUTATOR_BLOB_RECOVERY_SUCCESSES",
+                    "Number of blocks that became available after recovering blobs by source",
+                ),
+                &["source"],
+            )?,
+
             block_processing_times: Histogram::with_opts(histogram_opts!(
                 "MUTATOR_BLOCK_PROCESSING_TIMES",
                 "Mutator Block processing times",
@@ -273,6 +417,11 @@ impl Metrics {
                 "Attestation verifier active task count",
             )?,
 
+            attestation_verifier_ignored_attestations: IntCounter::new(
+                "ATTESTATION_VERIFIER_IGNORED_ATTESTATIONS",
+                "Number of gossip attestations ignored without verification because of overload",
+            )?,
+
             attestation_verifier_process_attestation_batch_times: Histogram::with_opts(
                 histogram_opts!(
                     "ATTESTATION_VERIFIER_PROCESS_ATTESTATION_BATCH_TIMES",
@@ -355,6 +504,23 @@ impl Metrics {
                 )
             )?,
 
+            // Sync committees
+            validator_own_sync_committee_messages: IntCounterVec::new(
+                opts!(
+                    "VALIDATOR_OWN_SYNC_COMMITTEE_MESSAGES",
+                    "Number of own sync committee duties by whether they were included in a block",
+                ),
+                &["outcome"],
+            )?,
+
+            validator_own_sync_committee_deltas: IntCounterVec::new(
+                opts!(
+                    "VALIDATOR_OWN_SYNC_COMMITTEE_DELTAS",
+                    "Sync committee rewards and penalties of own validators in Gwei",
+                ),
+                &["type"],
+            )?,
+
             // Build beacon block times
             build_beacon_block_times: Histogram::with_opts(histogram_opts!(
                 "BUILD_BEACON_BLOCK_TIMES",
@@ -381,6 +547,14 @@ impl Metrics {
                 "Eth1 vote times",
             ))?,
 
+            eth1_vote_strategies: IntCounterVec::new(
+                opts!(
+                    "ETH1_VOTE_STRATEGIES",
+                    "Number of Eth1 votes in proposed blocks by strategy used to choose them",
+                ),
+                &["strategy"],
+            )?,
+
             eth1_pending_deposits_times: Histogram::with_opts(histogram_opts!(
                 "ETH1_PENDING_DEPOSITS_TIMES",
                 "Eth1 pending deposits times",
@@ -579,6 +753,23 @@ impl Metrics {
                 "Number of total validators",
             )?,
 
+            // Operational counters
+            operational_counters: IntCounterVec::new(
+                opts!(
+                    "OPERATIONAL_COUNTERS",
+                    "Number of notable node events since start",
+                ),
+                &["counter"],
+            )?,
+
+            operational_counters_lifetime: IntCounterVec::new(
+                opts!(
+                    "OPERATIONAL_COUNTERS_LIFETIME",
+                    "Number of notable node events including ones before previous restarts",
+                ),
+                &["counter"],
+            )?,
+
             // Builder API
             builder_register_validator_times: Histogram::with_opts(histogram_opts!(
                 "BUILDER_REGISTER_VALIDATORS_TIMES",
@@ -595,6 +786,30 @@ impl Metrics {
                 "Builder get execution payload header times",
             ))?,
 
+            builder_relay_header_requests: IntCounterVec::new(
+                opts!(
+                    "BUILDER_RELAY_HEADER_REQUESTS",
+                    "Number of execution payload header requests to each relay by outcome",
+                ),
+                &["relay", "outcome"],
+            )?,
+
+            builder_relay_header_latencies: HistogramVec::new(
+                histogram_opts!(
+                    "BUILDER_RELAY_HEADER_LATENCIES",
+                    "Execution payload header response times of each relay",
+                ),
+                &["relay"],
+            )?,
+
+            builder_relay_scores: IntGaugeVec::new(
+                opts!(
+                    "BUILDER_RELAY_SCORES",
+                    "Reliability score of each relay in basis points",
+                ),
+                &["relay"],
+            )?,
+
             // WebSigner
             web3signer_load_keys_times: Histogram::with_opts(histogram_opts!(
                 "WEB3SIGNER_LOAD_KEYS_TIMES",
@@ -625,6 +840,19 @@ impl Metrics {
                 "Number of ETH1 API errors",
             )?,
 
+            eth1_api_catch_up_mode: IntGauge::new(
+                "ETH1_API_CATCH_UP_MODE",
+                "Whether payloads are withheld from a syncing execution engine (1) or not (0)",
+            )?,
+
+            eth1_api_catch_up_transitions: IntCounterVec::new(
+                opts!(
+                    "ETH1_API_CATCH_UP_TRANSITIONS",
+                    "Number of times catch-up mode was entered or exited",
+                ),
+                &["transition"],
+            )?,
+
             // Jemalloc stats
             jemalloc_bytes_allocated: IntGauge::new(
                 "JEMALLOC_BYTES_ALLOCATED",
@@ -672,22 +900,39 @@ impl Metrics {
         default_registry.register(Box::new(self.system_total_memory.clone()))?;
         default_registry.register(Box::new(self.total_cpu_percentage.clone()))?;
         default_registry.register(Box::new(self.collection_lengths.clone()))?;
+        default_registry.register(Box::new(self.log_warning_occurrences.clone()))?;
         default_registry.register(Box::new(self.http_api_response_times.clone()))?;
+        default_registry.register(Box::new(self.http_api_dropped_events.clone()))?;
         default_registry.register(Box::new(self.dedicated_executor_task_count.clone()))?;
         default_registry.register(Box::new(self.dedicated_executor_thread_count.clone()))?;
         default_registry.register(Box::new(self.gossip_objects.clone()))?;
+        default_registry.register(Box::new(self.gossip_clamped_objects.clone()))?;
+        default_registry.register(Box::new(self.own_gossip_publish_retries.clone()))?;
+        default_registry.register(Box::new(self.own_gossip_publish_failures.clone()))?;
+        default_registry.register(Box::new(self.gossip_topic_encodings.clone()))?;
+        default_registry.register(Box::new(self.req_resp_protocol_versions.clone()))?;
+        default_registry.register(Box::new(self.downgraded_protocol_peers.clone()))?;
         default_registry.register(Box::new(self.received_sync_contribution_subsets.clone()))?;
         default_registry.register(Box::new(
             self.received_aggregated_attestation_subsets.clone(),
         ))?;
         default_registry.register(Box::new(self.gossip_block_slot_start_delay_time.clone()))?;
+        default_registry.register(Box::new(self.sync_milli_slots_per_second.clone()))?;
+        default_registry.register(Box::new(self.sync_time_to_sync_seconds.clone()))?;
+        default_registry.register(Box::new(self.sync_batch_latency_seconds.clone()))?;
         default_registry.register(Box::new(self.mutator_attestations.clone()))?;
         default_registry.register(Box::new(self.mutator_aggregate_and_proofs.clone()))?;
+        default_registry.register(Box::new(self.mutator_invalidated_blocks.clone()))?;
+        default_registry.register(Box::new(self.mutator_blob_recovery_attempts.clone()))?;
+        default_registry.register(Box::new(self.mutator_blob_recovery_successes.clone()))?;
         default_registry.register(Box::new(self.block_processing_times.clone()))?;
         default_registry.register(Box::new(self.block_post_processing_times.clone()))?;
         default_registry.register(Box::new(
             self.attestation_verifier_active_task_count.clone(),
         ))?;
+        default_registry.register(Box::new(
+            self.attestation_verifier_ignored_attestations.clone(),
+        ))?;
         default_registry.register(Box::new(
             self.attestation_verifier_process_attestation_batch_times
                 .clone(),
@@ -715,6 +960,8 @@ impl Metrics {
         default_registry.register(Box::new(
             self.validator_proposal_slashing_protector_times.clone(),
         ))?;
+        default_registry.register(Box::new(self.validator_own_sync_committee_messages.clone()))?;
+        default_registry.register(Box::new(self.validator_own_sync_committee_deltas.clone()))?;
         default_registry.register(Box::new(self.build_beacon_block_times.clone()))?;
         default_registry.register(Box::new(self.local_execution_payload_times.clone()))?;
         default_registry.register(Box::new(
@@ -724,6 +971,7 @@ impl Metrics {
             self.prepare_bls_to_execution_changes_times.clone(),
         ))?;
         default_registry.register(Box::new(self.eth1_vote_times.clone()))?;
+        default_registry.register(Box::new(self.eth1_vote_strategies.clone()))?;
         default_registry.register(Box::new(self.eth1_pending_deposits_times.clone()))?;
         default_registry.register(Box::new(self.prepare_attester_slashings_times.clone()))?;
         default_registry.register(Box::new(self.prepare_proposer_slashings_times.clone()))?;
@@ -781,16 +1029,23 @@ impl Metrics {
                 .clone(),
         ))?;
         default_registry.register(Box::new(self.validator_count.clone()))?;
+        default_registry.register(Box::new(self.operational_counters.clone()))?;
+        default_registry.register(Box::new(self.operational_counters_lifetime.clone()))?;
         default_registry.register(Box::new(self.builder_register_validator_times.clone()))?;
         default_registry.register(Box::new(self.builder_post_blinded_block_times.clone()))?;
         default_registry.register(Box::new(
             self.builder_get_execution_payload_header_times.clone(),
         ))?;
+        default_registry.register(Box::new(self.builder_relay_header_requests.clone()))?;
+        default_registry.register(Box::new(self.builder_relay_header_latencies.clone()))?;
+        default_registry.register(Box::new(self.builder_relay_scores.clone()))?;
         default_registry.register(Box::new(self.web3signer_load_keys_times.clone()))?;
         default_registry.register(Box::new(self.web3signer_sign_times.clone()))?;
         default_registry.register(Box::new(self.eth1_api_request_times.clone()))?;
         default_registry.register(Box::new(self.eth1_api_errors_count.clone()))?;
         default_registry.register(Box::new(self.eth1_api_reset_count.clone()))?;
+        default_registry.register(Box::new(self.eth1_api_catch_up_mode.clone()))?;
+        default_registry.register(Box::new(self.eth1_api_catch_up_transitions.clone()))?;
         default_registry.register(Box::new(self.jemalloc_bytes_allocated.clone()))?;
         default_registry.register(Box::new(self.jemalloc_bytes_active.clone()))?;
         default_registry.register(Box::new(self.jemalloc_bytes_metadata.clone()))?;
@@ -851,6 +1106,17 @@ impl Metrics {
         }
     }
 
+    // Logging
+    pub fn register_log_warning(&self, level: &str, call_site: &str) {
+        // Do not log errors here. This is called by the logger itself.
+        if let Ok(counter) = self
+            .log_warning_occurrences
+            .get_metric_with_label_values(&[level, call_site])
+        {
+            counter.inc();
+        }
+    }
+
     // HTTP API metrics
     pub fn set_http_response_time(&self, labels: &[&str], response_duration: Duration) {
         match self
@@ -862,6 +1128,16 @@ impl Metrics {
         }
     }
 
+    pub fn register_dropped_events(&self, topic: &str, count: u64) {
+        match self
+            .http_api_dropped_events
+            .get_metric_with_label_values(&[topic])
+        {
+            Ok(counter) => counter.inc_by(count),
+            Err(error) => warn!("unable to register dropped events for topic {topic}: {error:?}"),
+        }
+    }
+
     // Dedicated Executor
     pub fn set_dedicated_exutor_task_count(&self, task_count: usize) {
         self.dedicated_executor_task_count.set(task_count as i64)
@@ -882,6 +1158,70 @@ impl Metrics {
         }
     }
 
+    pub fn register_gossip_clamped_object(&self, labels: &[&str]) {
+        match self
+            .gossip_clamped_objects
+            .get_metric_with_label_values(labels)
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register clamped gossip object for {labels:?}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_gossip_topic_encoding(&self, labels: &[&str]) {
+        match self
+            .gossip_topic_encodings
+            .get_metric_with_label_values(labels)
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register gossip topic encoding for {labels:?}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_req_resp_protocol_version(&self, labels: &[&str]) {
+        match self
+            .req_resp_protocol_versions
+            .get_metric_with_label_values(labels)
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register req/resp protocol version for {labels:?}: {error:?}")
+            }
+        }
+    }
+
+    pub fn set_downgraded_protocol_peers(&self, peer_count: usize) {
+        self.downgraded_protocol_peers.set(peer_count as i64)
+    }
+
+    pub fn register_own_gossip_publish_retry(&self, labels: &[&str]) {
+        match self
+            .own_gossip_publish_retries
+            .get_metric_with_label_values(labels)
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register own gossip publish retry for {labels:?}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_own_gossip_publish_failure(&self, labels: &[&str]) {
+        match self
+            .own_gossip_publish_failures
+            .get_metric_with_label_values(labels)
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register own gossip publish failure for {labels:?}: {error:?}")
+            }
+        }
+    }
+
     // Extra Network stats
     pub fn observe_block_duration_to_slot(&self, block_slot_timestamp: UnixSeconds) {
         match helpers::duration_from_now_to(block_slot_timestamp) {
@@ -892,6 +1232,45 @@ impl Metrics {
         }
     }
 
+    // Sync progress
+    pub fn set_sync_progress(
+        &self,
+        direction: &str,
+        milli_slots_per_second: Option<u64>,
+        time_to_sync: Option<Duration>,
+        average_batch_latency: Option<Duration>,
+    ) {
+        match self
+            .sync_milli_slots_per_second
+            .get_metric_with_label_values(&[direction])
+        {
+            Ok(gauge) => gauge.set(
+                milli_slots_per_second
+                    .and_then(|rate| i64::try_from(rate).ok())
+                    .unwrap_or_default(),
+            ),
+            Err(error) => warn!("unable to track {direction} sync progress: {error:?}"),
+        }
+
+        let values = [
+            (
+                &self.sync_time_to_sync_seconds,
+                time_to_sync.as_ref().map(Duration::as_secs_f64),
+            ),
+            (
+                &self.sync_batch_latency_seconds,
+                average_batch_latency.as_ref().map(Duration::as_secs_f64),
+            ),
+        ];
+
+        for (gauges, value) in values {
+            match gauges.get_metric_with_label_values(&[direction]) {
+                Ok(gauge) => gauge.set(value.unwrap_or_default()),
+                Err(error) => warn!("unable to track {direction} sync progress: {error:?}"),
+            }
+        }
+    }
+
     // Mutator
     pub fn register_mutator_attestation(&self, labels: &[&str]) {
         match self
@@ -917,12 +1296,72 @@ impl Metrics {
         }
     }
 
+    pub fn register_mutator_invalidated_blocks(&self, block_count: usize) {
+        self.mutator_invalidated_blocks.inc_by(block_count as u64)
+    }
+
+    pub fn register_mutator_blob_recovery_attempt(&self, source: &str) {
+        match self
+            .mutator_blob_recovery_attempts
+            .get_metric_with_label_values(&[source])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register blob recovery attempt for {source}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_mutator_blob_recovery_success(&self, source: &str) {
+        match self
+            .mutator_blob_recovery_successes
+            .get_metric_with_label_values(&[source])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register blob recovery success for {source}: {error:?}")
+            }
+        }
+    }
+
     // Attestation Verifier
     pub fn set_attestation_verifier_active_task_count(&self, task_count: usize) {
         self.attestation_verifier_active_task_count
             .set(task_count as i64)
     }
 
+    pub fn register_attestation_verifier_ignored_attestation(&self) {
+        self.attestation_verifier_ignored_attestations.inc()
+    }
+
+    // Build beacon block times
+    pub fn register_eth1_vote_strategy(&self, strategy: &str) {
+        match self
+            .eth1_vote_strategies
+            .get_metric_with_label_values(&[strategy])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register Eth1 vote strategy {strategy}: {error:?}")
+            }
+        }
+    }
+
+    // Eth1 API
+    pub fn register_eth1_api_catch_up_transition(&self, transition: &str, active: bool) {
+        self.eth1_api_catch_up_mode.set(active.into());
+
+        match self
+            .eth1_api_catch_up_transitions
+            .get_metric_with_label_values(&[transition])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register catch-up transition {transition}: {error:?}")
+            }
+        }
+    }
+
     // EF interop metrics
     pub fn set_active_validators(&self, validator_count: usize) {
         self.beacon_current_active_validators
@@ -964,6 +1403,112 @@ impl Metrics {
         self.validator_count.set(validator_count as i64);
     }
 
+    // Sync committees
+    pub fn register_own_sync_committee_outcome(&self, included: bool, delta: u64) {
+        let (outcome, delta_type) = if included {
+            ("included", "reward")
+        } else {
+            ("missed", "penalty")
+        };
+
+        match self
+            .validator_own_sync_committee_messages
+            .get_metric_with_label_values(&[outcome])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register own sync committee outcome {outcome}: {error:?}")
+            }
+        }
+
+        match self
+            .validator_own_sync_committee_deltas
+            .get_metric_with_label_values(&[delta_type])
+        {
+            Ok(counter) => counter.inc_by(delta),
+            Err(error) => {
+                warn!("unable to register own sync committee {delta_type}: {error:?}")
+            }
+        }
+    }
+
+    // Operational counters
+    pub fn register_operational_counter(&self, counter: OperationalCounter, count: u64) {
+        let label = counter.as_str();
+
+        for counter_vec in [
+            &self.operational_counters,
+            &self.operational_counters_lifetime,
+        ] {
+            match counter_vec.get_metric_with_label_values(&[label]) {
+                Ok(metric) => metric.inc_by(count),
+                Err(error) => warn!("unable to register operational counter {label}: {error:?}"),
+            }
+        }
+    }
+
+    // Used to carry over lifetime values persisted before a restart.
+    pub fn restore_operational_counter(&self, counter: OperationalCounter, lifetime: u64) {
+        let label = counter.as_str();
+
+        match self
+            .operational_counters_lifetime
+            .get_metric_with_label_values(&[label])
+        {
+            Ok(metric) => metric.inc_by(lifetime),
+            Err(error) => warn!("unable to restore operational counter {label}: {error:?}"),
+        }
+    }
+
+    pub fn operational_counter(
+        &self,
+        counter: OperationalCounter,
+    ) -> Result<OperationalCounterValues> {
+        let label = counter.as_str();
+
+        Ok(OperationalCounterValues {
+            since_start: self
+                .operational_counters
+                .get_metric_with_label_values(&[label])?
+                .get(),
+            lifetime: self
+                .operational_counters_lifetime
+                .get_metric_with_label_values(&[label])?
+                .get(),
+        })
+    }
+
+    // Builder API
+    pub fn register_builder_relay_header(&self, relay: &str, outcome: &str, latency: Duration) {
+        match self
+            .builder_relay_header_requests
+            .get_metric_with_label_values(&[relay, outcome])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register header request for relay {relay}: {error:?}")
+            }
+        }
+
+        match self
+            .builder_relay_header_latencies
+            .get_metric_with_label_values(&[relay])
+        {
+            Ok(histogram) => histogram.observe(latency.as_secs_f64()),
+            Err(error) => warn!("unable to track header latency for relay {relay}: {error:?}"),
+        }
+    }
+
+    pub fn set_builder_relay_score(&self, relay: &str, score: u64) {
+        match self
+            .builder_relay_scores
+            .get_metric_with_label_values(&[relay])
+        {
+            Ok(gauge) => gauge.set(score as i64),
+            Err(error) => warn!("unable to track score for relay {relay}: {error:?}"),
+        }
+    }
+
     // Jemalloc stats
     pub fn set_jemalloc_bytes_allocated(&self, bytes: usize) {
         self.jemalloc_bytes_allocated.set(bytes as i64)
@@ -0,0 +1,34 @@
+use enum_iterator::Sequence;
+use serde::Serialize;
+
+/// Counters of notable node events that operators want to see across restarts.
+///
+/// Lifetime values are persisted periodically, so events since the last snapshot
+/// before an unclean shutdown are lost.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Sequence)]
+pub enum OperationalCounter {
+    ProcessedBlocks,
+    ProducedAttestations,
+    MissedDuties,
+    Reorgs,
+    BannedPeers,
+}
+
+impl OperationalCounter {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ProcessedBlocks => "processed_blocks",
+            Self::ProducedAttestations => "produced_attestations",
+            Self::MissedDuties => "missed_duties",
+            Self::Reorgs => "reorgs",
+            Self::BannedPeers => "banned_peers",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize)]
+pub struct OperationalCounterValues {
+    pub since_start: u64,
+    pub lifetime: u64,
+}
@@ -0,0 +1,352 @@
+//! Span trees for individual HTTP API requests.
+//!
+//! The HTTP layer creates a [`TraceContext`] with a [`CorrelationId`] for every request.
+//! Code running on behalf of the request records nested spans using [`in_span`] and
+//! [`in_span_sync`]. The current context is stored in a task-local variable, so it follows the
+//! request across `.await` points. It does not follow work sent to other tasks or threads.
+//! That has to be done explicitly by passing the value of [`current`] along with the work and
+//! entering it again with [`TraceContext::scope`] or [`TraceContext::sync_scope`].
+//!
+//! Outside of a request both [`in_span`] and [`in_span_sync`] do nothing.
+
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    future::Future,
+    str::FromStr,
+    time::Duration,
+};
+use std::{borrow::Cow, sync::Arc, time::Instant};
+
+use parking_lot::Mutex;
+use std_ext::ArcExt as _;
+use thiserror::Error;
+
+// Long enough for UUIDs and most other ID formats clients already use.
+const MAX_CORRELATION_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// Identifier of a request chosen by the client or generated randomly.
+///
+/// Client-provided IDs are kept as they are so that clients can find them in our logs.
+/// They are limited to printable ASCII, which is safe to log and to echo back in a header.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CorrelationId(Arc<str>);
+
+impl Display for CorrelationId {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl FromStr for CorrelationId {
+    type Err = InvalidCorrelationId;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let is_valid = !string.is_empty()
+            && string.len() <= MAX_CORRELATION_ID_LENGTH
+            && string
+                .bytes()
+                .all(|byte| byte == b' ' || byte.is_ascii_graphic());
+
+        if is_valid {
+            Ok(Self(string.into()))
+        } else {
+            Err(InvalidCorrelationId)
+        }
+    }
+}
+
+impl CorrelationId {
+    #[must_use]
+    pub fn random() -> Self {
+        Self(format!("{:016x}", rand::random::<u64>()).into())
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Error)]
+#[error(
+    "correlation ID must consist of 1 to {MAX_CORRELATION_ID_LENGTH} printable ASCII characters"
+)]
+pub struct InvalidCorrelationId;
+
+#[derive(Clone)]
+pub struct TraceContext {
+    trace: Arc<Trace>,
+    parent: Option<usize>,
+}
+
+impl TraceContext {
+    #[must_use]
+    pub fn new(correlation_id: CorrelationId) -> Self {
+        let trace = Trace {
+            correlation_id,
+            started_at: Instant::now(),
+            spans: Mutex::default(),
+        };
+
+        Self {
+            trace: Arc::new(trace),
+            parent: None,
+        }
+    }
+
+    #[must_use]
+    pub fn correlation_id(&self) -> CorrelationId {
+        self.trace.correlation_id.clone()
+    }
+
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.trace.started_at.elapsed()
+    }
+
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    pub fn sync_scope<T>(self, function: impl FnOnce() -> T) -> T {
+        CURRENT.sync_scope(self, function)
+    }
+
+    /// Renders all spans recorded so far as an indented tree.
+    ///
+    /// Spans that have not finished yet (including ones whose futures were dropped) are marked as
+    /// such instead of having a duration.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let spans = self.trace.spans.lock();
+        let mut lines = vec![format!("request {}", self.trace.correlation_id)];
+
+        render_children(&spans, None, 1, &mut lines);
+
+        lines.join("\n")
+    }
+
+    fn open_span(&self, name: Cow<'static, str>) -> Self {
+        let mut spans = self.trace.spans.lock();
+
+        spans.push(Span {
+            name,
+            parent: self.parent,
+            start: self.trace.started_at.elapsed(),
+            duration: None,
+        });
+
+        Self {
+            trace: self.trace.clone_arc(),
+            parent: Some(spans.len() - 1),
+        }
+    }
+
+    fn close_span(&self) {
+        let Some(index) = self.parent else {
+            return;
+        };
+
+        let mut spans = self.trace.spans.lock();
+
+        if let Some(span) = spans.get_mut(index) {
+            span.duration = Some(self.trace.started_at.elapsed().saturating_sub(span.start));
+        }
+    }
+}
+
+/// Ends the span it was created for when dropped.
+pub struct SpanGuard(TraceContext);
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        self.0.close_span();
+    }
+}
+
+struct Trace {
+    correlation_id: CorrelationId,
+    started_at: Instant,
+    spans: Mutex<Vec<Span>>,
+}
+
+struct Span {
+    name: Cow<'static, str>,
+    parent: Option<usize>,
+    start: Duration,
+    duration: Option<Duration>,
+}
+
+#[must_use]
+pub fn current() -> Option<TraceContext> {
+    CURRENT.try_with(TraceContext::clone).ok()
+}
+
+/// Starts a span that ends when the returned guard is dropped.
+///
+/// Spans started this way cannot have children. Use [`in_span`] or [`in_span_sync`] for that.
+#[must_use]
+pub fn start_span(name: impl Into<Cow<'static, str>>) -> Option<SpanGuard> {
+    current().map(|context| SpanGuard(context.open_span(name.into())))
+}
+
+/// Runs `future` in `context` if there is one.
+///
+/// Meant for work received from another task along with the value [`current`] had there.
+pub async fn resume<F: Future>(context: Option<TraceContext>, future: F) -> F::Output {
+    match context {
+        Some(context) => context.scope(future).await,
+        None => future.await,
+    }
+}
+
+/// Like [`resume`], but for synchronous code.
+pub fn resume_sync<T>(context: Option<TraceContext>, function: impl FnOnce() -> T) -> T {
+    match context {
+        Some(context) => context.sync_scope(function),
+        None => function(),
+    }
+}
+
+pub async fn in_span<F: Future>(name: impl Into<Cow<'static, str>>, future: F) -> F::Output {
+    let Some(context) = current() else {
+        return future.await;
+    };
+
+    let child = context.open_span(name.into());
+    let output = child.clone().scope(future).await;
+    child.close_span();
+    output
+}
+
+pub fn in_span_sync<T>(name: impl Into<Cow<'static, str>>, function: impl FnOnce() -> T) -> T {
+    let Some(context) = current() else {
+        return function();
+    };
+
+    let child = context.open_span(name.into());
+    let output = child.clone().sync_scope(function);
+    child.close_span();
+    output
+}
+
+fn render_children(spans: &[Span], parent: Option<usize>, depth: usize, lines: &mut Vec<String>) {
+    for (index, span) in spans.iter().enumerate() {
+        if span.parent != parent {
+            continue;
+        }
+
+        let indentation = "  ".repeat(depth);
+
+        let Span {
+            name,
+            start,
+            duration,
+            ..
+        } = span;
+
+        let line = match duration {
+            Some(duration) => format!("{indentation}{name}: {duration:?} (at {start:?})"),
+            None => format!("{indentation}{name}: unfinished (at {start:?})"),
+        };
+
+        lines.push(line);
+
+        render_children(spans, Some(index), depth + 1, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correlation_id_round_trips_through_string() {
+        let correlation_id = CorrelationId::random();
+
+        assert_eq!(
+            correlation_id.to_string().parse::<CorrelationId>(),
+            Ok(correlation_id),
+        );
+    }
+
+    #[test]
+    fn client_correlation_ids_are_kept_as_they_are() {
+        for string in ["6ba7b810-9dad-11d1-80b4-00c04fd430c8", "job 42/retry"] {
+            assert_eq!(
+                string.parse::<CorrelationId>().map(|id| id.to_string()),
+                Ok(string.to_owned()),
+            );
+        }
+    }
+
+    #[test]
+    fn correlation_ids_must_be_printable_ascii() {
+        let too_long = "a".repeat(MAX_CORRELATION_ID_LENGTH + 1);
+
+        for string in ["", "tab\there", "über", too_long.as_str()] {
+            assert_eq!(string.parse::<CorrelationId>(), Err(InvalidCorrelationId),);
+        }
+    }
+
+    #[test]
+    fn spans_are_ignored_outside_of_requests() {
+        assert!(current().is_none());
+        assert_eq!(in_span_sync("span", || 1), 1);
+    }
+
+    #[tokio::test]
+    async fn spans_are_nested_across_await_points() {
+        let context = TraceContext::new(CorrelationId("abc".into()));
+
+        context
+            .clone()
+            .scope(in_span("outer", async {
+                in_span("inner", tokio::task::yield_now()).await;
+                in_span_sync("sync", || {});
+            }))
+            .await;
+
+        let rendered = context.render();
+        let lines = rendered.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "request abc");
+        assert!(lines[1].starts_with("  outer: "));
+        assert!(lines[2].starts_with("    inner: "));
+        assert!(lines[3].starts_with("    sync: "));
+    }
+
+    #[test]
+    fn guard_ends_span_when_dropped() {
+        let context = TraceContext::new(CorrelationId::random());
+
+        context.clone().sync_scope(|| {
+            let _span = start_span("guarded");
+            assert!(context.render().contains("guarded: unfinished"));
+        });
+
+        assert!(!context.render().contains("unfinished"));
+    }
+
+    #[tokio::test]
+    async fn context_can_be_passed_to_other_tasks() {
+        let context = TraceContext::new(CorrelationId::random());
+
+        let captured = context
+            .clone()
+            .scope(async { current().expect("context should be set inside scope") })
+            .await;
+
+        tokio::spawn(captured.scope(in_span("spawned", async {})))
+            .await
+            .expect("spawned task should not panic");
+
+        assert!(context.render().contains("spawned: "));
+        assert!(!context.render().contains("unfinished"));
+    }
+}
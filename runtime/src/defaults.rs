@@ -1,4 +1,7 @@
-use core::{num::NonZeroU16, time::Duration};
+use core::{
+    num::{NonZeroU16, NonZeroU64},
+    time::Duration,
+};
 
 use bytesize::ByteSize;
 use nonzero_ext::nonzero;
@@ -6,12 +9,15 @@ use p2p::NetworkConfig;
 
 pub const DEFAULT_ETH1_DB_SIZE: ByteSize = ByteSize::gib(16);
 pub const DEFAULT_ETH2_DB_SIZE: ByteSize = ByteSize::gib(256);
+pub const DEFAULT_GOSSIP_FUTURE_SLOT_TOLERANCE: u64 = 2;
+pub const DEFAULT_GOSSIP_PAST_SLOT_TOLERANCE: u64 = 64;
 pub const DEFAULT_METRICS_PORT: u16 = 5054;
 pub const DEFAULT_LIBP2P_IPV4_PORT: NonZeroU16 = nonzero!(9000_u16);
 pub const DEFAULT_LIBP2P_IPV6_PORT: NonZeroU16 = nonzero!(9050_u16);
 pub const DEFAULT_LIBP2P_QUIC_IPV4_PORT: NonZeroU16 = nonzero!(9001_u16);
 pub const DEFAULT_LIBP2P_QUIC_IPV6_PORT: NonZeroU16 = nonzero!(9051_u16);
-pub const DEFAULT_REQUEST_TIMEOUT: u64 = 30000;
+pub const DEFAULT_STORAGE_READ_CACHE_SIZE: ByteSize = ByteSize::b(0);
+pub const DEFAULT_REQUEST_TIMEOUT: NonZeroU64 = nonzero!(30000_u64);
 pub const DEFAULT_TARGET_PEERS: usize = 100;
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
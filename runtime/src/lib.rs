@@ -1,16 +1,20 @@
 pub use crate::{
     defaults::{
         default_network_config, DEFAULT_ETH1_DB_SIZE, DEFAULT_ETH2_DB_SIZE,
+        DEFAULT_GOSSIP_FUTURE_SLOT_TOLERANCE, DEFAULT_GOSSIP_PAST_SLOT_TOLERANCE,
         DEFAULT_LIBP2P_IPV4_PORT, DEFAULT_LIBP2P_IPV6_PORT, DEFAULT_LIBP2P_QUIC_IPV4_PORT,
         DEFAULT_LIBP2P_QUIC_IPV6_PORT, DEFAULT_METRICS_PORT, DEFAULT_REQUEST_TIMEOUT,
-        DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
+        DEFAULT_STORAGE_READ_CACHE_SIZE, DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
     },
     misc::{MetricsConfig, StorageConfig},
     runtime::run_after_genesis,
-    schema::initialize as initialize_schema,
+    schema::{initialize as initialize_schema, read_version as read_schema_version},
+    watchdog::{ChainHeadStalled, StallReport},
 };
 
 mod defaults;
 mod misc;
+mod operational_counters;
 mod runtime;
 mod schema;
+mod watchdog;
@@ -1,10 +1,14 @@
 use core::num::NonZeroU64;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
+use anyhow::Result;
 use bytesize::ByteSize;
+use database::{Codec, Database, DatabaseBackend, DurabilityLevel};
 use directories::Directories;
+use http_api::StorageSettings;
 use metrics::{MetricsServerConfig, MetricsServiceConfig};
 use prometheus_metrics::Metrics;
+use semver::Version;
 
 #[derive(Clone, Debug)]
 pub struct MetricsConfig {
@@ -21,4 +25,133 @@ pub struct StorageConfig {
     pub eth1_db_size: ByteSize,
     pub archival_epoch_interval: NonZeroU64,
     pub prune_storage: bool,
+    pub prune_history_epochs: Option<u64>,
+    pub archival_snapshot_interval: Option<NonZeroU64>,
+    pub blob_retention_epochs: Option<u64>,
+    pub hot_dir: Option<PathBuf>,
+    pub cold_dir: Option<PathBuf>,
+    pub era_directory: Option<PathBuf>,
+    pub database_backend: DatabaseBackend,
+    pub durability: DurabilityLevel,
+    pub compression: Codec,
+    pub read_cache_size: ByteSize,
+    pub split_execution_payloads: bool,
+    pub execution_payload_retention_epochs: Option<u64>,
+    /// Set from the result of [`crate::initialize_schema`] before the runtime is started.
+    pub previous_schema_version: Option<Version>,
+}
+
+impl StorageConfig {
+    /// Opens the main database used by `Storage`.
+    pub fn storage_database(&self) -> Result<Database> {
+        if self.in_memory {
+            return Ok(Database::in_memory().with_codec(self.compression));
+        }
+
+        Database::persistent_with_backend(
+            self.database_backend,
+            self.durability,
+            "beacon_fork_choice",
+            self.hot_directory().join("beacon_fork_choice"),
+            self.db_size,
+        )
+        .map(|database| database.with_codec(self.compression))
+    }
+
+    /// Opens the database for finalized blocks and archival states if a separate directory is
+    /// configured for them.
+    ///
+    /// All other data is stored in the main database in [`Self::hot_directory`].
+    pub fn archive_database(&self) -> Result<Option<Database>> {
+        if self.in_memory {
+            return Ok(None);
+        }
+
+        self.cold_dir
+            .as_ref()
+            .map(|directory| {
+                Database::persistent_with_backend(
+                    self.database_backend,
+                    self.durability,
+                    "beacon_archive",
+                    directory.join("beacon_archive"),
+                    self.db_size,
+                )
+                .map(|database| database.with_codec(self.compression))
+            })
+            .transpose()
+    }
+
+    /// Opens the databases used by `Storage` for inspection without modifying them.
+    ///
+    /// Backends are detected from existing files, so `database_backend` is ignored.
+    pub fn read_only_databases(&self) -> Result<(Database, Option<Database>)> {
+        let database = Database::persistent_read_only(
+            "beacon_fork_choice",
+            self.hot_directory().join("beacon_fork_choice"),
+        )?;
+
+        let archive_database = self
+            .cold_dir
+            .as_ref()
+            .map(|directory| {
+                Database::persistent_read_only("beacon_archive", directory.join("beacon_archive"))
+            })
+            .transpose()?;
+
+        Ok((database, archive_database))
+    }
+
+    /// Compacts the databases used by `Storage` and returns the number of bytes reclaimed on disk.
+    ///
+    /// The databases must not be open, so this cannot be done while the beacon node is running.
+    pub fn compact_databases(&self) -> Result<u64> {
+        if self.in_memory {
+            return Ok(0);
+        }
+
+        let mut reclaimed_bytes = Database::compact_offline(
+            "beacon_fork_choice",
+            self.hot_directory().join("beacon_fork_choice"),
+            self.db_size,
+        )?;
+
+        if let Some(directory) = &self.cold_dir {
+            reclaimed_bytes += Database::compact_offline(
+                "beacon_archive",
+                directory.join("beacon_archive"),
+                self.db_size,
+            )?;
+        }
+
+        Ok(reclaimed_bytes)
+    }
+
+    /// Returns the directory of the main database, which holds unfinalized blocks, indices and
+    /// checkpoints. Defaults to `directories.store_directory`.
+    #[must_use]
+    pub fn hot_directory(&self) -> PathBuf {
+        self.hot_dir
+            .clone()
+            .or_else(|| self.directories.store_directory.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the settings included in configuration fingerprints.
+    #[must_use]
+    pub fn settings(&self) -> StorageSettings {
+        StorageSettings {
+            in_memory: self.in_memory,
+            db_size: self.db_size.as_u64(),
+            eth1_db_size: self.eth1_db_size.as_u64(),
+            archival_epoch_interval: self.archival_epoch_interval.get(),
+            prune_storage: self.prune_storage,
+            prune_history_epochs: self.prune_history_epochs,
+            archival_snapshot_interval: self.archival_snapshot_interval.map(NonZeroU64::get),
+            blob_retention_epochs: self.blob_retention_epochs,
+            separate_archive: self.cold_dir.is_some(),
+            database_backend: self.database_backend.into(),
+            compression: self.compression.into(),
+        }
+    }
 }
@@ -0,0 +1,90 @@
+use core::{convert::Infallible as Never, time::Duration};
+use std::sync::Arc;
+
+use anyhow::Result;
+use database::Database;
+use log::warn;
+use prometheus_metrics::{Metrics, OperationalCounter};
+use ssz::{SszReadDefault as _, SszWrite as _};
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically persists lifetime values of [`OperationalCounter`]s.
+///
+/// Persisted values are added to the lifetime counters on startup,
+/// so statistics shown to operators are not reset by restarts.
+pub struct OperationalCountersSnapshotter {
+    database: Database,
+    metrics: Arc<Metrics>,
+}
+
+impl OperationalCountersSnapshotter {
+    pub fn new(database: Database, metrics: Arc<Metrics>) -> Result<Self> {
+        for counter in enum_iterator::all::<OperationalCounter>() {
+            if let Some(bytes) = database.get(counter.as_str())? {
+                metrics.restore_operational_counter(counter, u64::from_ssz_default(bytes)?);
+            }
+        }
+
+        Ok(Self { database, metrics })
+    }
+
+    pub async fn run(self: Arc<Self>) -> Result<Never> {
+        let mut interval = tokio::time::interval(SNAPSHOT_INTERVAL);
+
+        // The first tick completes immediately. There is nothing new to save at that point.
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = self.save() {
+                warn!("failed to save operational counters: {error:?}");
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let pairs = enum_iterator::all::<OperationalCounter>()
+            .map(|counter| {
+                let lifetime = self.metrics.operational_counter(counter)?.lifetime;
+                Ok((counter.as_str(), lifetime.to_ssz()?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.database.put_batch(pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifetime_values_survive_restarts() -> Result<()> {
+        let metrics = Arc::new(Metrics::new()?);
+        let snapshotter = OperationalCountersSnapshotter::new(Database::in_memory(), metrics)?;
+
+        snapshotter
+            .metrics
+            .register_operational_counter(OperationalCounter::Reorgs, 2);
+
+        snapshotter.save()?;
+
+        let metrics = Arc::new(Metrics::new()?);
+        let restarted = OperationalCountersSnapshotter::new(snapshotter.database, metrics)?;
+
+        restarted
+            .metrics
+            .register_operational_counter(OperationalCounter::Reorgs, 1);
+
+        let values = restarted
+            .metrics
+            .operational_counter(OperationalCounter::Reorgs)?;
+
+        assert_eq!(values.since_start, 1);
+        assert_eq!(values.lifetime, 3);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
-use core::{convert::Infallible as Never, future::Future};
+use core::{convert::Infallible as Never, future::Future, num::NonZeroU64};
 use std::{collections::HashSet, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use builder_api::{BuilderApi, BuilderConfig};
 use bytesize::ByteSize;
 use clock::Tick;
@@ -10,9 +10,9 @@ use dedicated_executor::DedicatedExecutor;
 use eth1::{Eth1Chain, Eth1Config};
 use eth1_api::{
     Eth1Api, Eth1ApiToMetrics, Eth1ConnectionData, Eth1ExecutionEngine, Eth1Metrics,
-    ExecutionService, RealController,
+    ExecutionService, RealController, Recorder,
 };
-use fork_choice_control::{Controller, StateLoadStrategy, Storage};
+use fork_choice_control::{Controller, EraStore, StateLoadStrategy, Storage};
 use fork_choice_store::StoreConfig;
 use futures::{
     channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -21,29 +21,50 @@ use futures::{
     stream::TryStreamExt as _,
 };
 use genesis::GenesisProvider;
+use helper_functions::misc;
 use http_api::{Channels as HttpApiChannels, HttpApi, HttpApiConfig};
 use keymanager::KeyManager;
 use liveness_tracker::LivenessTracker;
-use log::info;
+use log::{info, warn};
 use metrics::{run_metrics_server, MetricsChannels, MetricsService};
 use operation_pools::{AttestationAggPool, BlsToExecutionChangePool, SyncCommitteeAggPool};
 use p2p::{
-    AttestationVerifier, BlockSyncService, BlockSyncServiceChannels, Channels, Network,
-    NetworkConfig, SubnetService,
+    AttestationVerifier, BlockSyncService, BlockSyncServiceChannels, Channels, GossipSlotTolerance,
+    Network, NetworkConfig, SubnetService,
 };
 use signer::Signer;
 use slasher::{Databases, Slasher, SlasherConfig};
 use slashing_protection::SlashingProtector;
 use std_ext::ArcExt as _;
+use thiserror::Error;
 use tokio::{select, sync::RwLock};
-use types::{config::Config as ChainConfig, preset::Preset, traits::BeaconState as _};
+use types::{
+    config::Config as ChainConfig,
+    phase0::primitives::{Epoch, Slot},
+    preset::Preset,
+    traits::BeaconState as _,
+};
 use validator::{Validator, ValidatorChannels, ValidatorConfig};
 
-use crate::misc::{MetricsConfig, StorageConfig};
+use crate::{
+    misc::{MetricsConfig, StorageConfig},
+    operational_counters::OperationalCountersSnapshotter,
+    watchdog::HeadWatchdog,
+};
 
 #[cfg(unix)]
 use tokio::signal::unix::SignalKind;
 
+#[derive(Debug, Error)]
+enum Error {
+    #[error(
+        "{count} validator key(s) signed messages in epochs later than the chain head \
+         (head epoch: {head_epoch}) and may be active on another node; \
+         pass --skip-slashing-protection-check to start anyway"
+    )]
+    ValidatorsActiveElsewhere { count: usize, head_epoch: Epoch },
+}
+
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::too_many_lines)]
 pub async fn run_after_genesis<P: Preset>(
@@ -63,6 +84,8 @@ pub async fn run_after_genesis<P: Preset>(
     back_sync_enabled: bool,
     metrics_config: MetricsConfig,
     track_liveness: bool,
+    head_watchdog_slots: Option<NonZeroU64>,
+    gossip_slot_tolerance: GossipSlotTolerance,
     eth1_api_to_metrics_tx: Option<UnboundedSender<Eth1ApiToMetrics>>,
     eth1_api_to_metrics_rx: Option<UnboundedReceiver<Eth1ApiToMetrics>>,
     slashing_protection_history_limit: u64,
@@ -73,12 +96,25 @@ pub async fn run_after_genesis<P: Preset>(
         metrics_service_config,
     } = metrics_config;
 
+    let storage_database = storage_config.storage_database()?;
+    let archive_database = storage_config.archive_database()?;
+    let storage_settings = storage_config.settings();
+    let hot_directory = storage_config.hot_directory();
+
     let StorageConfig {
         in_memory,
         db_size,
         directories,
         archival_epoch_interval,
         prune_storage,
+        prune_history_epochs,
+        archival_snapshot_interval,
+        blob_retention_epochs,
+        era_directory,
+        read_cache_size,
+        split_execution_payloads,
+        execution_payload_retention_epochs,
+        previous_schema_version,
         ..
     } = storage_config;
 
@@ -95,6 +131,8 @@ pub async fn run_after_genesis<P: Preset>(
     let (fork_choice_to_subnet_tx, fork_choice_to_subnet_rx) = mpsc::unbounded();
     let (fork_choice_to_validator_tx, fork_choice_to_validator_rx) = mpsc::unbounded();
     let (p2p_to_attestation_verifier_tx, p2p_to_attestation_verifier_rx) = mpsc::unbounded();
+    let (subnet_service_to_attestation_verifier_tx, subnet_service_to_attestation_verifier_rx) =
+        mpsc::unbounded();
     let (p2p_to_sync_tx, p2p_to_sync_rx) = mpsc::unbounded();
     let (p2p_to_validator_tx, p2p_to_validator_rx) = mpsc::unbounded();
     let (sync_to_p2p_tx, sync_to_p2p_rx) = mpsc::unbounded();
@@ -108,6 +146,7 @@ pub async fn run_after_genesis<P: Preset>(
     let (pool_to_p2p_tx, pool_to_p2p_rx) = mpsc::unbounded();
     let (subnet_service_to_p2p_tx, subnet_service_to_p2p_rx) = mpsc::unbounded();
     let (subnet_service_tx, subnet_service_rx) = mpsc::unbounded();
+    let (watchdog_to_sync_tx, watchdog_to_sync_rx) = mpsc::unbounded();
 
     let (fork_choice_to_sync_tx, fork_choice_to_sync_rx) =
         back_sync_enabled.then(mpsc::unbounded).unzip();
@@ -122,14 +161,24 @@ pub async fn run_after_genesis<P: Preset>(
     let mut validator_to_slasher_tx = None;
     let mut validator_to_liveness_tx = None;
 
-    let eth1_api = Arc::new(Eth1Api::new(
-        chain_config.clone_arc(),
-        signer.client().clone(),
-        eth1_config.eth1_auth.clone_arc(),
-        eth1_config.eth1_rpc_urls.clone(),
-        eth1_api_to_metrics_tx,
-        metrics.clone(),
-    ));
+    let eth1_api_recorder = eth1_config
+        .eth1_api_recording_file
+        .as_ref()
+        .map(Recorder::create)
+        .transpose()?
+        .map(Arc::new);
+
+    let eth1_api = Arc::new(
+        Eth1Api::new(
+            chain_config.clone_arc(),
+            signer.client().clone(),
+            eth1_config.eth1_auth.clone_arc(),
+            eth1_config.eth1_rpc_urls.clone(),
+            eth1_api_to_metrics_tx,
+            metrics.clone(),
+        )
+        .with_recorder(eth1_api_recorder),
+    );
 
     let execution_engine = Arc::new(Eth1ExecutionEngine::new(
         chain_config.clone_arc(),
@@ -137,26 +186,25 @@ pub async fn run_after_genesis<P: Preset>(
         execution_service_tx,
     ));
 
-    let storage_database = if in_memory {
-        Database::in_memory()
-    } else {
-        Database::persistent(
-            "beacon_fork_choice",
-            directories
-                .store_directory
-                .clone()
-                .unwrap_or_default()
-                .join("beacon_fork_choice"),
-            db_size,
-        )?
-    };
+    let era_store = era_directory.map(EraStore::new).transpose()?;
 
-    let storage = Arc::new(Storage::new(
-        chain_config.clone_arc(),
-        storage_database,
-        archival_epoch_interval,
-        prune_storage,
-    ));
+    let storage = Arc::new(
+        Storage::new(
+            chain_config.clone_arc(),
+            storage_database,
+            archive_database,
+            archival_epoch_interval,
+            prune_storage,
+        )
+        .with_prune_history_epochs(prune_history_epochs)
+        .with_archival_snapshot_interval(archival_snapshot_interval)
+        .with_blob_retention_epochs(blob_retention_epochs)
+        .with_era_store(era_store)
+        .with_read_cache_size(read_cache_size)
+        .with_split_execution_payloads(split_execution_payloads)
+        .with_execution_payload_retention_epochs(execution_payload_retention_epochs)
+        .with_previous_schema_version(previous_schema_version),
+    );
 
     let ((anchor_state, anchor_block, unfinalized_blocks), loaded_from_remote) =
         storage.load(signer.client(), state_load_strategy).await?;
@@ -177,10 +225,6 @@ pub async fn run_after_genesis<P: Preset>(
         )?
     };
 
-    slashing_protector.register_validators(signer.keys().copied())?;
-
-    let slashing_protector = Arc::new(Mutex::new(slashing_protector));
-
     let current_tick = Tick::current(&chain_config, anchor_state.genesis_time())?;
 
     let (controller, mutator_handle) = Controller::new(
@@ -192,7 +236,7 @@ pub async fn run_after_genesis<P: Preset>(
         execution_engine.clone_arc(),
         metrics.clone(),
         fc_to_api_tx,
-        fork_choice_to_p2p_tx,
+        fork_choice_to_p2p_tx.clone(),
         fork_choice_to_subnet_tx,
         fork_choice_to_sync_tx,
         fork_choice_to_validator_tx,
@@ -200,8 +244,26 @@ pub async fn run_after_genesis<P: Preset>(
         unfinalized_blocks,
     )?;
 
-    let execution_service =
-        ExecutionService::new(eth1_api, controller.clone_arc(), execution_service_rx);
+    // A fresh in-memory database has no history to check against.
+    if !in_memory {
+        check_slashing_protection_history::<P>(
+            &mut slashing_protector,
+            &signer,
+            controller.head_slot(),
+            validator_config.standby || validator_config.skip_slashing_protection_check,
+        )?;
+    }
+
+    slashing_protector.register_validators(signer.keys().copied())?;
+
+    let slashing_protector = Arc::new(Mutex::new(slashing_protector));
+
+    let execution_service = ExecutionService::new(
+        eth1_api.clone_arc(),
+        controller.clone_arc(),
+        metrics.clone(),
+        execution_service_rx,
+    );
 
     let validator_keys = Arc::new(signer.keys().copied().collect::<HashSet<_>>());
 
@@ -225,7 +287,9 @@ pub async fn run_after_genesis<P: Preset>(
         controller.clone_arc(),
         dedicated_executor_low_priority,
         metrics.clone(),
+        fork_choice_to_p2p_tx,
         p2p_to_attestation_verifier_rx,
+        subnet_service_to_attestation_verifier_rx,
     );
 
     let metrics_service = metrics_service_config.map(|metrics_config| {
@@ -282,12 +346,22 @@ pub async fn run_after_genesis<P: Preset>(
         )
     });
 
+    let head_watchdog = head_watchdog_slots.map(|stall_slots| {
+        HeadWatchdog::new(
+            controller.clone_arc(),
+            eth1_api,
+            stall_slots,
+            watchdog_to_sync_tx,
+        )
+    });
+
     let block_sync_service_channels = BlockSyncServiceChannels {
         fork_choice_to_sync_rx,
         p2p_to_sync_rx,
         sync_to_p2p_tx,
         sync_to_api_tx,
         sync_to_metrics_tx,
+        watchdog_to_sync_rx: head_watchdog.is_some().then_some(watchdog_to_sync_rx),
     };
 
     let block_sync_database = Database::persistent(
@@ -313,13 +387,28 @@ pub async fn run_after_genesis<P: Preset>(
 
     block_sync_service.try_to_spawn_back_sync_states_archiver()?;
 
-    let builder_api = builder_config.map(|builder_config| {
-        Arc::new(BuilderApi::new(
-            builder_config,
-            signer.client().clone(),
-            metrics.clone(),
-        ))
-    });
+    let builder_api = builder_config
+        .map(|builder_config| -> Result<_> {
+            let database = if in_memory {
+                Database::in_memory()
+            } else {
+                Database::persistent(
+                    "builder_relays",
+                    hot_directory.join("builder_relays"),
+                    ByteSize::mib(16),
+                )?
+            };
+
+            let builder_api = BuilderApi::new(
+                builder_config,
+                signer.client().clone(),
+                metrics.clone(),
+                database,
+            )?;
+
+            Ok(Arc::new(builder_api))
+        })
+        .transpose()?;
 
     let slasher = slasher_config
         .map(|slasher_config| -> Result<_> {
@@ -375,12 +464,23 @@ pub async fn run_after_genesis<P: Preset>(
                 ByteSize::gib(128),
             )?;
 
+            let backfill_db = Database::persistent(
+                "SLASHER_BACKFILL",
+                directories
+                    .store_directory
+                    .clone()
+                    .unwrap_or_default()
+                    .join(format!("slasher_backfill_{fork_version:?}_db")),
+                ByteSize::mib(16),
+            )?;
+
             let databases = Databases {
                 votes_db,
                 attestations_db,
                 min_targets_db,
                 max_targets_db,
                 blocks_db,
+                backfill_db,
             };
 
             let (network_tx, network_to_slasher_rx) = mpsc::unbounded();
@@ -474,7 +574,7 @@ pub async fn run_after_genesis<P: Preset>(
         builder_api,
         keymanager.proposer_configs().clone_arc(),
         signer,
-        slashing_protector,
+        slashing_protector.clone_arc(),
         sync_committee_agg_pool.clone_arc(),
         bls_to_execution_change_pool.clone_arc(),
         metrics.clone(),
@@ -500,6 +600,16 @@ pub async fn run_after_genesis<P: Preset>(
     let gossip_registry = prometheus_client::registry::Registry::default();
     let mut registry = network_config.metrics_enabled.then_some(gossip_registry);
 
+    let gossip_digests_database = if in_memory {
+        Database::in_memory()
+    } else {
+        Database::persistent(
+            "gossip_digests",
+            hot_directory.join("gossip_digests"),
+            ByteSize::mib(16),
+        )?
+    };
+
     let network = Network::new(
         &network_config,
         controller.clone_arc(),
@@ -510,6 +620,8 @@ pub async fn run_after_genesis<P: Preset>(
         bls_to_execution_change_pool.clone_arc(),
         metrics.clone(),
         registry.as_mut(),
+        gossip_digests_database,
+        gossip_slot_tolerance,
     )
     .await?;
 
@@ -517,6 +629,7 @@ pub async fn run_after_genesis<P: Preset>(
         attestation_agg_pool.clone_arc(),
         network.node_id(),
         subnet_service_to_p2p_tx,
+        subnet_service_to_attestation_verifier_tx,
         fork_choice_to_subnet_rx,
         subnet_service_rx,
     );
@@ -541,6 +654,8 @@ pub async fn run_after_genesis<P: Preset>(
         validator_config,
         network_config: Arc::new(network_config),
         http_api_config,
+        storage_settings,
+        slashing_protector,
         attestation_agg_pool,
         sync_committee_agg_pool,
         bls_to_execution_change_pool,
@@ -548,6 +663,25 @@ pub async fn run_after_genesis<P: Preset>(
         metrics: metrics.clone(),
     };
 
+    let operational_counters_snapshotter = metrics
+        .clone()
+        .map(|metrics| -> Result<_> {
+            let database = if in_memory {
+                Database::in_memory()
+            } else {
+                Database::persistent(
+                    "operational_counters",
+                    hot_directory.join("operational_counters"),
+                    ByteSize::mib(1),
+                )?
+            };
+
+            let snapshotter = OperationalCountersSnapshotter::new(database, metrics)?;
+
+            Ok(Arc::new(snapshotter))
+        })
+        .transpose()?;
+
     let join_mutator = async { tokio::task::spawn_blocking(|| mutator_handle.join()).await? };
     let run_clock = run_clock(controller.clone_arc());
 
@@ -578,6 +712,16 @@ pub async fn run_after_genesis<P: Preset>(
         None => Either::Right(core::future::pending()),
     };
 
+    let run_head_watchdog = match head_watchdog {
+        Some(watchdog) => Either::Left(watchdog.run()),
+        None => Either::Right(core::future::pending()),
+    };
+
+    let run_operational_counters_snapshotter = match operational_counters_snapshotter.as_ref() {
+        Some(snapshotter) => Either::Left(snapshotter.clone_arc().run()),
+        None => Either::Right(core::future::pending()),
+    };
+
     select! {
         result = join_mutator => result,
         result = spawn_fallible(execution_service.run()) => result,
@@ -593,11 +737,63 @@ pub async fn run_after_genesis<P: Preset>(
         result = spawn_fallible(run_metrics_service) => result,
         result = spawn_fallible(run_liveness_tracker) => result,
         result = spawn_fallible(subnet_service.run()) => result,
+        result = spawn_fallible(run_head_watchdog) => result,
+        result = spawn_fallible(run_operational_counters_snapshotter) => result.map(from_never),
         result = wait_for_signal() => result,
     }?;
 
     info!("saving current chain before exit…");
 
+    if let Some(snapshotter) = operational_counters_snapshotter {
+        snapshotter.save()?;
+    }
+
+    Ok(())
+}
+
+// Keys whose latest signed epoch is past the head were most likely used by another node
+// while this one was offline. Signing with them again risks slashing.
+// Attestations made right before a shutdown may target the epoch after the head block
+// if the last slots of the epoch were empty, so that epoch is not considered suspicious.
+fn check_slashing_protection_history<P: Preset>(
+    slashing_protector: &mut SlashingProtector,
+    signer: &Signer,
+    head_slot: Slot,
+    allow_conflicts: bool,
+) -> Result<()> {
+    let head_epoch = misc::compute_epoch_at_slot::<P>(head_slot);
+    let latest_signed_epochs =
+        slashing_protector.latest_signed_epochs::<P>(signer.keys().copied())?;
+
+    let mut active_elsewhere = 0;
+
+    for pubkey in signer.keys() {
+        match latest_signed_epochs.get(pubkey) {
+            Some(epoch) if *epoch > head_epoch + 1 => {
+                warn!(
+                    "validator key {pubkey:?} signed messages up to epoch {epoch} \
+                     but chain head is in epoch {head_epoch}; \
+                     the key may be active on another node",
+                );
+
+                active_elsewhere += 1;
+            }
+            Some(_) => {}
+            None => warn!(
+                "validator key {pubkey:?} has no slashing protection history; \
+                 if it was used on another node, import its slashing protection data \
+                 before the validator performs any duties",
+            ),
+        }
+    }
+
+    if active_elsewhere > 0 && !allow_conflicts {
+        bail!(Error::ValidatorsActiveElsewhere {
+            count: active_elsewhere,
+            head_epoch,
+        });
+    }
+
     Ok(())
 }
 
@@ -54,7 +54,22 @@ const META_FILE_NAME: &str = "meta.json";
 // ## 0.2.3
 //
 // Added state_root to slot indexing to storage to enable loading archived states by state root.
-const SCHEMA_VERSION: &str = "0.2.3";
+//
+// ## 0.2.4
+//
+// Values in the Eth2 database may be compressed with Zstandard instead of raw Snappy.
+// Versions before 0.2.4 cannot read them.
+//
+// ## 0.2.5
+//
+// Finalized blocks may be stored as blinded blocks with their execution payloads in separate keys.
+// Versions before 0.2.5 cannot read them.
+//
+// ## 0.2.6
+//
+// Finalized blocks and execution payloads are stored in the archive database if one is configured.
+// Ones stored in the main database before are moved there. Versions before 0.2.6 cannot find them.
+const SCHEMA_VERSION: &str = "0.2.6";
 
 // Semantic Versioning by itself only achieves forward compatibility.
 // Backward compatibility is achieved using a version requirement separate from the schema version.
@@ -72,9 +87,19 @@ enum Error {
     ApplicationNameMismatch { actual: String },
     #[error("expected schema version compatible with {VERSION_REQUIREMENT}, found {version}")]
     IncompatibleVersion { version: Version },
+    #[error(
+        "schema version {version} of the data directory is newer than the latest version \
+         supported by this version of Grandine ({SCHEMA_VERSION}); upgrade Grandine to use it"
+    )]
+    SchemaVersionTooNew { version: Version },
 }
 
-pub fn initialize(data_directory: impl AsRef<Path>) -> Result<()> {
+/// Records the current schema version in `data_directory`.
+///
+/// Returns the schema version recorded previously if it is older than the current one.
+/// Data stored with it may need to be migrated.
+/// Fails without modifying anything if the recorded schema version is newer than the current one.
+pub fn initialize(data_directory: impl AsRef<Path>) -> Result<Option<Version>> {
     let meta_file_path = data_directory.as_ref().join(META_FILE_NAME);
 
     match fs_err::read(meta_file_path.as_path()) {
@@ -91,7 +116,7 @@ pub fn initialize(data_directory: impl AsRef<Path>) -> Result<()> {
                 },
             );
 
-            let version = schema_version.parse()?;
+            let version: Version = schema_version.parse()?;
 
             // 0.2.0 version requirement does not strictly require any actions from users.
             // And as this version requirement was introduced separately from rocks_db -> libmdbx migration,
@@ -106,23 +131,49 @@ pub fn initialize(data_directory: impl AsRef<Path>) -> Result<()> {
                 );
             };
 
-            // Set the schema version to the current one even if it's older.
+            let current_version = SCHEMA_VERSION
+                .parse::<Version>()
+                .expect("constant contains valid Semantic Versioning version");
+
+            // Older versions of the application cannot read data written by newer ones.
+            // Overwriting the schema version would hide that until the data is misread.
+            ensure!(version <= current_version, Error::SchemaVersionTooNew { version });
+
+            // Set the schema version to the current one if it's older.
             // The application can only write data conforming to the current schema.
-            if schema_version != SCHEMA_VERSION {
+            // Databases record their own progress through migrations, so this is safe to do
+            // before they are migrated.
+            if version < current_version {
                 write_meta(data_directory)?;
 
                 info!("using schema version {SCHEMA_VERSION} for new data");
             }
+
+            Ok((version < current_version).then_some(version))
         }
         Err(error) if error.kind() == ErrorKind::NotFound => {
             write_meta(data_directory)?;
 
             info!("initialized data directory with schema version {SCHEMA_VERSION}");
+
+            Ok(None)
         }
         Err(error) => bail!(error),
     }
+}
 
-    Ok(())
+/// Returns the schema version recorded in `data_directory` without updating it.
+pub fn read_version(data_directory: impl AsRef<Path>) -> Result<Option<String>> {
+    let meta_file_path = data_directory.as_ref().join(META_FILE_NAME);
+
+    match fs_err::read(meta_file_path.as_path()) {
+        Ok(bytes) => {
+            let Meta { schema_version, .. } = serde_json::from_slice(bytes.as_slice())?;
+            Ok(Some(schema_version.to_owned()))
+        }
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => bail!(error),
+    }
 }
 
 fn write_meta(data_directory: impl AsRef<Path>) -> Result<()> {
@@ -0,0 +1,165 @@
+use core::num::NonZeroU64;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use eth1_api::{Eth1Api, RealController};
+use futures::{
+    channel::{mpsc::UnboundedSender, oneshot},
+    stream::TryStreamExt as _,
+};
+use log::{info, warn};
+use p2p::{SyncDiagnostics, WatchdogToSync};
+use thiserror::Error;
+use types::{
+    phase0::primitives::{Epoch, ExecutionBlockNumber, Slot},
+    preset::Preset,
+};
+
+const MAX_RECOVERY_ATTEMPTS: usize = 3;
+
+/// Detects when the chain head stops advancing even though peers report newer heads.
+///
+/// Each stall is first handled by switching back to the primary execution endpoint and resetting
+/// block sync. If the head still does not advance after [`MAX_RECOVERY_ATTEMPTS`] attempts,
+/// the watchdog fails with a report of the node's state, which stops the application.
+pub struct HeadWatchdog<P: Preset> {
+    controller: RealController<P>,
+    eth1_api: Arc<Eth1Api>,
+    stall_slots: NonZeroU64,
+    watchdog_to_sync_tx: UnboundedSender<WatchdogToSync>,
+}
+
+impl<P: Preset> HeadWatchdog<P> {
+    pub const fn new(
+        controller: RealController<P>,
+        eth1_api: Arc<Eth1Api>,
+        stall_slots: NonZeroU64,
+        watchdog_to_sync_tx: UnboundedSender<WatchdogToSync>,
+    ) -> Self {
+        Self {
+            controller,
+            eth1_api,
+            stall_slots,
+            watchdog_to_sync_tx,
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let mut ticks = clock::ticks(
+            self.controller.chain_config(),
+            self.controller.genesis_time(),
+        )?;
+        let mut last_head_slot = self.controller.head_slot();
+        let mut last_progress_slot = self.controller.slot();
+        let mut attempts = 0;
+
+        while let Some(tick) = ticks.try_next().await? {
+            if !tick.is_start_of_slot() {
+                continue;
+            }
+
+            let head_slot = self.controller.head_slot();
+
+            if head_slot > last_head_slot {
+                if attempts > 0 {
+                    info!("chain head advanced to slot {head_slot} after recovery");
+                }
+
+                last_head_slot = head_slot;
+                last_progress_slot = tick.slot;
+                attempts = 0;
+                continue;
+            }
+
+            if tick.slot < last_progress_slot + self.stall_slots.get() {
+                continue;
+            }
+
+            let sync = self.sync_diagnostics().await?;
+
+            // The head is not expected to advance if no peer has anything newer either.
+            if !sync
+                .max_peer_head_slot
+                .is_some_and(|peer_head_slot| peer_head_slot > head_slot)
+            {
+                continue;
+            }
+
+            let report = self.report(tick.slot, sync).await;
+
+            if attempts == MAX_RECOVERY_ATTEMPTS {
+                bail!(ChainHeadStalled { report });
+            }
+
+            attempts += 1;
+
+            warn!(
+                "chain head has not advanced for {} slots while peers report newer heads; \
+                 attempting recovery ({attempts}/{MAX_RECOVERY_ATTEMPTS}): {report:#?}",
+                tick.slot - last_progress_slot,
+            );
+
+            self.eth1_api.reconnect().await;
+
+            WatchdogToSync::Reset.send(&self.watchdog_to_sync_tx);
+
+            last_progress_slot = tick.slot;
+        }
+
+        Ok(())
+    }
+
+    async fn sync_diagnostics(&self) -> Result<SyncDiagnostics> {
+        let (sender, receiver) = oneshot::channel();
+        WatchdogToSync::RequestDiagnostics(sender).send(&self.watchdog_to_sync_tx);
+        Ok(receiver.await?)
+    }
+
+    async fn report(&self, current_slot: Slot, sync: SyncDiagnostics) -> StallReport {
+        let head = self.controller.head();
+        let execution_endpoint = self.eth1_api.current_endpoint().await;
+        let execution_last_error = self.eth1_api.last_error().await;
+
+        let execution_head_number = self
+            .eth1_api
+            .current_head_number()
+            .await
+            .map_err(|error| error.to_string());
+
+        StallReport {
+            current_slot,
+            head_slot: head.value.slot(),
+            head_optimistic: head.optimistic,
+            finalized_epoch: self.controller.finalized_epoch(),
+            fork_count_viable: self.controller.fork_count_viable(),
+            unfinalized_block_count: self.controller.unfinalized_block_count_total(),
+            sync,
+            execution_endpoint: execution_endpoint.map(String::from),
+            execution_head_number,
+            execution_last_error,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StallReport {
+    pub current_slot: Slot,
+    pub head_slot: Slot,
+    pub head_optimistic: bool,
+    pub finalized_epoch: Epoch,
+    pub fork_count_viable: usize,
+    pub unfinalized_block_count: usize,
+    pub sync: SyncDiagnostics,
+    pub execution_endpoint: Option<String>,
+    pub execution_head_number: Result<ExecutionBlockNumber, String>,
+    pub execution_last_error: Option<String>,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "chain head could not be recovered after {} attempts: {report:#?}",
+    MAX_RECOVERY_ATTEMPTS
+)]
+pub struct ChainHeadStalled {
+    pub report: StallReport,
+}
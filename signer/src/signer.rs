@@ -31,6 +31,8 @@ enum Error {
 
 #[derive(Clone, Copy)]
 pub enum KeyOrigin {
+    /// Derived from validator indices as described in the interop specification.
+    Interop,
     KeymanagerAPI,
     LocalFileSystem,
     Web3Signer,
@@ -0,0 +1,116 @@
+use core::ops::Range;
+
+use anyhow::Result;
+use database::Database;
+use derive_more::Constructor;
+use serde::{Deserialize, Serialize};
+use types::phase0::primitives::Epoch;
+
+const BACKFILL_PROGRESS_KEY: &str = "backfill_progress";
+
+// Progress is logged every time this many epochs have been backfilled.
+pub const BACKFILL_PROGRESS_INTERVAL: u64 = 100;
+
+// Epochs in `next_epoch..end_epoch` still have to be backfilled.
+// `end_epoch` is fixed when the backfill starts so that restarts do not extend it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct BackfillProgress {
+    pub start_epoch: Epoch,
+    pub next_epoch: Epoch,
+    pub end_epoch: Epoch,
+}
+
+impl BackfillProgress {
+    #[must_use]
+    pub const fn new(epochs: Range<Epoch>) -> Self {
+        Self {
+            start_epoch: epochs.start,
+            next_epoch: epochs.start,
+            end_epoch: epochs.end,
+        }
+    }
+
+    #[must_use]
+    pub const fn remaining_epochs(self) -> Range<Epoch> {
+        self.next_epoch..self.end_epoch
+    }
+
+    #[must_use]
+    pub const fn is_complete(self) -> bool {
+        self.next_epoch >= self.end_epoch
+    }
+
+    #[must_use]
+    pub const fn completed_epochs(self) -> u64 {
+        self.next_epoch.saturating_sub(self.start_epoch)
+    }
+
+    #[must_use]
+    pub const fn total_epochs(self) -> u64 {
+        self.end_epoch.saturating_sub(self.start_epoch)
+    }
+
+    // The window starts `slashing_history_limit` epochs before the head.
+    // It stops being covered if the node was offline for longer than that or the limit was raised.
+    #[must_use]
+    pub const fn covers_window_starting_at(self, window_start: Epoch) -> bool {
+        self.start_epoch <= window_start && window_start < self.end_epoch
+    }
+}
+
+#[derive(Constructor)]
+pub struct Backfill {
+    db: Database,
+}
+
+impl Backfill {
+    pub fn progress(&self) -> Result<Option<BackfillProgress>> {
+        self.db
+            .get(BACKFILL_PROGRESS_KEY)?
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    pub fn save_progress(&self, progress: BackfillProgress) -> Result<()> {
+        self.db
+            .put(BACKFILL_PROGRESS_KEY, bincode::serialize(&progress)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_is_persisted() -> Result<()> {
+        let backfill = Backfill::new(Database::in_memory());
+
+        assert_eq!(backfill.progress()?, None);
+
+        let mut progress = BackfillProgress::new(10..20);
+        progress.next_epoch = 15;
+
+        backfill.save_progress(progress)?;
+
+        assert_eq!(backfill.progress()?, Some(progress));
+        assert_eq!(progress.remaining_epochs(), 15..20);
+        assert_eq!(progress.completed_epochs(), 5);
+        assert_eq!(progress.total_epochs(), 10);
+        assert!(!progress.is_complete());
+
+        Ok(())
+    }
+
+    #[test]
+    fn progress_only_covers_windows_starting_within_its_range() {
+        let progress = BackfillProgress::new(10..20);
+
+        assert!(!progress.covers_window_starting_at(9));
+        assert!(progress.covers_window_starting_at(10));
+        assert!(progress.covers_window_starting_at(19));
+        assert!(!progress.covers_window_starting_at(20));
+    }
+}
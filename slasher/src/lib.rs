@@ -6,6 +6,7 @@ pub use crate::{
 
 mod attestation_votes;
 mod attestations;
+mod backfill;
 mod blocks;
 mod indexed_attestations;
 mod messages;
@@ -8,7 +8,7 @@ use features::Feature;
 use futures::{
     channel::mpsc::{UnboundedReceiver, UnboundedSender},
     select,
-    stream::StreamExt,
+    stream::{self, StreamExt},
 };
 use helper_functions::{accessors, misc};
 use log::{debug, info, warn};
@@ -26,6 +26,7 @@ use types::{
 
 use crate::{
     attestations::Attestations,
+    backfill::{Backfill, BackfillProgress, BACKFILL_PROGRESS_INTERVAL},
     blocks::Blocks,
     status::{ExplainedAttesterSlashing, ExplainedProposerSlashing},
     SlasherConfig, SlasherToValidator, ValidatorToSlasher,
@@ -53,14 +54,17 @@ pub struct Databases {
     pub min_targets_db: Database,
     pub max_targets_db: Database,
     pub blocks_db: Database,
+    pub backfill_db: Database,
 }
 
 #[allow(clippy::struct_field_names)]
 pub struct Slasher<P: Preset> {
+    config: SlasherConfig,
     controller: RealController<P>,
     fork_version: Version,
     attestations: Attestations<P>,
     blocks: Blocks,
+    backfill: Backfill,
     slasher_to_validator_tx: UnboundedSender<SlasherToValidator<P>>,
     network_to_slasher_rx: UnboundedReceiver<P2pToSlasher<P>>,
     validator_to_slasher_rx: UnboundedReceiver<ValidatorToSlasher>,
@@ -83,9 +87,11 @@ impl<P: Preset> Slasher<P> {
             min_targets_db,
             max_targets_db,
             blocks_db,
+            backfill_db,
         } = databases;
 
         Self {
+            config,
             controller,
             fork_version,
             attestations: Attestations::new(
@@ -96,6 +102,7 @@ impl<P: Preset> Slasher<P> {
                 max_targets_db,
             ),
             blocks: Blocks::new(config, blocks_db),
+            backfill: Backfill::new(backfill_db),
             slasher_to_validator_tx,
             network_to_slasher_rx,
             validator_to_slasher_rx,
@@ -103,6 +110,9 @@ impl<P: Preset> Slasher<P> {
     }
 
     pub async fn run(mut self) -> Result<Never> {
+        let mut backfill_progress = self.start_backfill()?;
+        let mut backfill_epochs = stream::iter(backfill_progress.remaining_epochs()).fuse();
+
         loop {
             select! {
                 network_message = self.network_to_slasher_rx.select_next_some() => {
@@ -125,8 +135,125 @@ impl<P: Preset> Slasher<P> {
                         ValidatorToSlasher::Epoch(epoch) => self.cleanup(epoch)?,
                     }
                 },
+
+                epoch = backfill_epochs.select_next_some() => {
+                    if let Err(error) = self.backfill_epoch(epoch) {
+                        // Skipping the epoch would leave a gap in slasher history.
+                        // Stop and leave `epoch` recorded as the next one instead.
+                        warn!(
+                            "failed to backfill slasher with epoch {epoch}; \
+                             backfill stopped and will resume from it after a restart: {error:?}",
+                        );
+
+                        backfill_epochs = stream::iter(epoch..epoch).fuse();
+                    } else {
+                        backfill_progress.next_epoch = epoch + 1;
+
+                        self.report_backfill_progress(backfill_progress)?;
+                    }
+
+                    // Backfilled epochs are always ready.
+                    // Yield to avoid starving other tasks while there are no messages to process.
+                    tokio::task::yield_now().await;
+                },
+            }
+        }
+    }
+
+    // Live gossip is only processed from the moment the slasher is enabled.
+    // Stored chain history up to `slashing_history_limit` epochs back is processed in the
+    // background one epoch at a time. Progress is persisted so that restarts resume the backfill.
+    // The backfill starts over if the recorded progress no longer covers the history window.
+    fn start_backfill(&self) -> Result<BackfillProgress> {
+        let head_epoch = misc::compute_epoch_at_slot::<P>(self.controller.head_slot());
+        let end_epoch = head_epoch + 1;
+        let start_epoch = end_epoch.saturating_sub(self.config.slashing_history_limit);
+
+        let progress = match self.backfill.progress()? {
+            Some(progress) if progress.covers_window_starting_at(start_epoch) => progress,
+            stored_progress => {
+                if let Some(stored_progress) = stored_progress {
+                    info!(
+                        "recorded slasher backfill (epochs: {}..{}) does not cover \
+                         history window starting at epoch {start_epoch}; starting over",
+                        stored_progress.start_epoch, stored_progress.end_epoch,
+                    );
+                }
+
+                let progress = BackfillProgress::new(start_epoch..end_epoch);
+
+                self.backfill.save_progress(progress)?;
+
+                progress
+            }
+        };
+
+        if !progress.is_complete() {
+            info!(
+                "slasher backfill from stored history started \
+                 (epochs: {}..{}, completed: {}/{})",
+                progress.next_epoch,
+                progress.end_epoch,
+                progress.completed_epochs(),
+                progress.total_epochs(),
+            );
+        }
+
+        Ok(progress)
+    }
+
+    fn report_backfill_progress(&self, progress: BackfillProgress) -> Result<()> {
+        self.backfill.save_progress(progress)?;
+
+        if progress.is_complete() {
+            info!(
+                "slasher backfill from stored history completed ({} epochs)",
+                progress.total_epochs(),
+            );
+        } else if progress.completed_epochs() % BACKFILL_PROGRESS_INTERVAL == 0 {
+            info!(
+                "slasher backfill progress: {}/{} epochs (next epoch: {})",
+                progress.completed_epochs(),
+                progress.total_epochs(),
+                progress.next_epoch,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn backfill_epoch(&self, epoch: Epoch) -> Result<()> {
+        let start_slot = misc::compute_start_slot_at_epoch::<P>(epoch);
+        let end_slot = misc::compute_start_slot_at_epoch::<P>(epoch + 1);
+        let blocks = self.controller.blocks_by_range(start_slot..end_slot)?;
+
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        for block_with_root in &blocks {
+            self.process_block(&block_with_root.block)?;
+        }
+
+        // Blocks in `epoch` can only include attestations from `epoch` and the one before it.
+        // Committees for both can be computed from the state at the start of `epoch`.
+        let Some(state) = self.controller.state_at_slot(start_slot)? else {
+            debug!("state needed to backfill slasher with epoch {epoch} is not available");
+            return Ok(());
+        };
+
+        let current_epoch = self.controller.finalized_epoch();
+
+        for block_with_root in blocks {
+            for attestation in block_with_root.block.message().body().attestations() {
+                let indexed_attestation =
+                    accessors::get_indexed_attestation(&state.value, attestation)?;
+
+                self.process_indexed_attestation(&indexed_attestation, current_epoch)?;
             }
         }
+
+        Ok(())
     }
 
     fn process_block(&self, block: &SignedBeaconBlock<P>) -> Result<()> {
@@ -163,23 +290,33 @@ impl<P: Preset> Slasher<P> {
             let indexed_attestation =
                 accessors::get_indexed_attestation(&target_state, attestation)?;
 
-            debug!(
-                "processing attestation record \
-                 (attesters: {:?}, slot: {}, source: {}, target: {}, fork_version: {:?})",
-                indexed_attestation.attesting_indices,
-                indexed_attestation.data.slot,
-                indexed_attestation.data.source.epoch,
-                indexed_attestation.data.target.epoch,
-                self.fork_version,
-            );
+            self.process_indexed_attestation(&indexed_attestation, current_epoch)?;
+        }
 
-            for explained_attester_slashing in
-                self.check_attestation(&indexed_attestation, current_epoch)?
-            {
-                info!("attester slashing constructed: {explained_attester_slashing:?}");
+        Ok(())
+    }
 
-                self.process_attester_slashing(explained_attester_slashing.slashing);
-            }
+    fn process_indexed_attestation(
+        &self,
+        indexed_attestation: &IndexedAttestation<P>,
+        current_epoch: Epoch,
+    ) -> Result<()> {
+        debug!(
+            "processing attestation record \
+             (attesters: {:?}, slot: {}, source: {}, target: {}, fork_version: {:?})",
+            indexed_attestation.attesting_indices,
+            indexed_attestation.data.slot,
+            indexed_attestation.data.source.epoch,
+            indexed_attestation.data.target.epoch,
+            self.fork_version,
+        );
+
+        for explained_attester_slashing in
+            self.check_attestation(indexed_attestation, current_epoch)?
+        {
+            info!("attester slashing constructed: {explained_attester_slashing:?}");
+
+            self.process_attester_slashing(explained_attester_slashing.slashing);
         }
 
         Ok(())
@@ -7,7 +7,7 @@ use fs_err::File;
 use helper_functions::{accessors, misc, signing::SignForSingleFork};
 use itertools::Itertools as _;
 use log::{debug, info, warn};
-use rusqlite::{Connection, OptionalExtension, Rows, Transaction, TransactionBehavior};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Rows, Transaction, TransactionBehavior};
 use ssz::{SszReadDefault as _, SszWrite as _};
 use thiserror::Error;
 use types::{
@@ -73,6 +73,22 @@ pub struct DbError {
     pubkey: PublicKeyBytes,
 }
 
+#[derive(Debug, Error)]
+#[error("in-memory slashing protection database cannot be backed up on a separate connection")]
+pub struct InMemoryBackupError;
+
+/// A read-only connection used to back up the database without blocking signing.
+pub struct SlashingProtectionBackup {
+    connection: Connection,
+}
+
+impl SlashingProtectionBackup {
+    /// Writes a consistent copy of the database to `directory`. See [`SlashingProtector::backup_to`].
+    pub fn write_to(self, directory: impl AsRef<Path>) -> Result<()> {
+        vacuum_into(&self.connection, directory.as_ref())
+    }
+}
+
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
 pub enum SlashingValidationOutcome {
     Accept,
@@ -342,6 +358,34 @@ impl SlashingProtector {
         Ok(())
     }
 
+    /// Writes a consistent copy of the database to `directory`.
+    ///
+    /// `VACUUM INTO` reads the database in a single transaction,
+    /// so the copy cannot contain partially stored signing records.
+    pub fn backup_to(&self, directory: impl AsRef<Path>) -> Result<()> {
+        vacuum_into(&self.connection, directory.as_ref())
+    }
+
+    /// Opens a separate connection to the database for [`SlashingProtectionBackup::write_to`].
+    ///
+    /// Copying a large database takes a while. Doing it on a separate connection lets callers
+    /// release `self` so that signing can continue in the meantime.
+    pub fn prepare_backup(&self) -> Result<SlashingProtectionBackup> {
+        // `Connection::path` returns an empty string for in-memory databases.
+        let path = self
+            .connection
+            .path()
+            .filter(|path| !path.is_empty())
+            .ok_or(InMemoryBackupError)?;
+
+        let connection = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+
+        Ok(SlashingProtectionBackup { connection })
+    }
+
     pub fn build_interchange_data(
         &mut self,
         genesis_validators_root: H256,
@@ -813,6 +857,67 @@ impl SlashingProtector {
         Ok(())
     }
 
+    /// Returns the latest epoch of any stored block proposal or attestation target.
+    ///
+    /// Used to make sure a standby validator never signs anything that could conflict
+    /// with messages imported from the node it is taking over from.
+    pub fn latest_signed_epoch<P: Preset>(&mut self) -> Result<Option<Epoch>> {
+        let transaction = self.transaction()?;
+
+        let max_slot: Option<Slot> =
+            transaction.query_row("SELECT MAX(slot) FROM block_proposals", [], |row| {
+                row.get(0)
+            })?;
+
+        let max_target_epoch: Option<Epoch> = transaction.query_row(
+            "SELECT MAX(target_epoch) FROM attestation_proposals",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(max_slot
+            .map(misc::compute_epoch_at_slot::<P>)
+            .max(max_target_epoch))
+    }
+
+    /// Returns the latest signed epoch of every validator in `pubkeys` that has signing history.
+    ///
+    /// Validators without any stored block proposals or attestations are omitted.
+    pub fn latest_signed_epochs<P: Preset>(
+        &mut self,
+        pubkeys: impl IntoIterator<Item = PublicKeyBytes>,
+    ) -> Result<HashMap<PublicKeyBytes, Epoch>> {
+        let transaction = self.transaction()?;
+        let mut latest_signed_epochs = HashMap::new();
+
+        for pubkey in pubkeys {
+            let Some(validator_id) = Self::find_validator_record(&transaction, pubkey)? else {
+                continue;
+            };
+
+            let max_slot: Option<Slot> = transaction.query_row(
+                "SELECT MAX(slot) FROM block_proposals WHERE validator_id = ?1",
+                [validator_id],
+                |row| row.get(0),
+            )?;
+
+            let max_target_epoch: Option<Epoch> = transaction.query_row(
+                "SELECT MAX(target_epoch) FROM attestation_proposals WHERE validator_id = ?1",
+                [validator_id],
+                |row| row.get(0),
+            )?;
+
+            if let Some(epoch) = max_slot
+                .map(misc::compute_epoch_at_slot::<P>)
+                .max(max_target_epoch)
+            {
+                latest_signed_epochs.insert(pubkey, epoch);
+            }
+        }
+
+        Ok(latest_signed_epochs)
+    }
+
     fn prune_attestations(&mut self, epoch: Epoch) -> Result<()> {
         let transaction = self.transaction()?;
 
@@ -987,6 +1092,18 @@ fn remove_fork_version_from_validators_if_needed(
     Ok(())
 }
 
+fn vacuum_into(connection: &Connection, directory: &Path) -> Result<()> {
+    let path = directory.join(DB_PATH);
+
+    fs_err::create_dir_all(directory)?;
+
+    connection.execute("VACUUM INTO ?1", [path.to_string_lossy().into_owned()])?;
+
+    info!("Slashing protection database backed up to {path:?}");
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use bls::Signature;
@@ -1117,6 +1234,75 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(build_persistent_slashing_protector)]
+    #[test_case(build_in_memory_slashing_protector)]
+    fn test_slashing_protection_backup(constructor: Constructor) -> Result<()> {
+        let (mut slashing_protector, _dir) = constructor()?;
+        let backup_dir = Builder::new().prefix("backup").rand_bytes(10).tempdir()?;
+
+        slashing_protector.register_validators([PUBKEY])?;
+        slashing_protector.backup_to(backup_dir.path())?;
+
+        let mut backup = SlashingProtector::persistent(
+            backup_dir.path(),
+            DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT,
+            H256::default(),
+        )?;
+
+        let interchange = backup.build_interchange_data(H256::default())?;
+
+        assert_eq!(
+            interchange
+                .data
+                .iter()
+                .map(|data| data.pubkey)
+                .collect::<Vec<_>>(),
+            [PUBKEY],
+        );
+
+        slashing_protector
+            .backup_to(backup_dir.path())
+            .expect_err("VACUUM INTO should not overwrite existing databases");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_slashing_protection_backup_on_separate_connection() -> Result<()> {
+        let (mut slashing_protector, _dir) = build_persistent_slashing_protector()?;
+        let backup_dir = Builder::new().prefix("backup").rand_bytes(10).tempdir()?;
+
+        slashing_protector.register_validators([PUBKEY])?;
+
+        let backup = slashing_protector.prepare_backup()?;
+
+        // Signing records can still be written while the backup is in progress.
+        slashing_protector.register_validators([PublicKeyBytes::default()])?;
+
+        backup.write_to(backup_dir.path())?;
+
+        let mut backup = SlashingProtector::persistent(
+            backup_dir.path(),
+            DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT,
+            H256::default(),
+        )?;
+
+        let interchange = backup.build_interchange_data(H256::default())?;
+
+        assert!(interchange.data.iter().any(|data| data.pubkey == PUBKEY));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_slashing_protection_backup_on_separate_connection_fails_in_memory() -> Result<()> {
+        let (slashing_protector, _dir) = build_in_memory_slashing_protector()?;
+
+        assert!(slashing_protector.prepare_backup().is_err());
+
+        Ok(())
+    }
+
     #[test_case(build_persistent_slashing_protector)]
     #[test_case(build_in_memory_slashing_protector)]
     fn test_slashing_protection_on_empty_db_block(constructor: Constructor) -> Result<()> {
@@ -1316,6 +1502,74 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(build_persistent_slashing_protector)]
+    #[test_case(build_in_memory_slashing_protector)]
+    fn test_slashing_protection_latest_signed_epoch(constructor: Constructor) -> Result<()> {
+        let (mut slashing_protector, _dir) = constructor()?;
+
+        assert_eq!(slashing_protector.latest_signed_epoch::<Minimal>()?, None);
+
+        slashing_protector.register_validators(core::iter::once(PUBKEY))?;
+
+        let attestation = AttestationProposal {
+            source_epoch: 6,
+            target_epoch: 7,
+            signing_root: Some(ATTESTATION_SIGNING_ROOT),
+        };
+
+        let transaction = slashing_protector.transaction()?;
+        SlashingProtector::validate_attestation_proposal(attestation, PUBKEY, &transaction)?;
+        transaction.commit()?;
+
+        assert_eq!(
+            slashing_protector.latest_signed_epoch::<Minimal>()?,
+            Some(7)
+        );
+
+        let proposal = BlockProposal {
+            slot: misc::compute_start_slot_at_epoch::<Minimal>(9),
+            signing_root: Some(BLOCK_SIGNING_ROOT),
+        };
+
+        slashing_protector.validate_and_store_proposal(proposal, PUBKEY, 9)?;
+
+        assert_eq!(
+            slashing_protector.latest_signed_epoch::<Minimal>()?,
+            Some(9)
+        );
+
+        Ok(())
+    }
+
+    #[test_case(build_persistent_slashing_protector)]
+    #[test_case(build_in_memory_slashing_protector)]
+    fn test_slashing_protection_latest_signed_epochs(constructor: Constructor) -> Result<()> {
+        let (mut slashing_protector, _dir) = constructor()?;
+
+        slashing_protector.register_validators(core::iter::once(PUBKEY))?;
+
+        assert_eq!(
+            slashing_protector.latest_signed_epochs::<Minimal>([PUBKEY])?,
+            HashMap::new(),
+        );
+
+        let proposal = BlockProposal {
+            slot: misc::compute_start_slot_at_epoch::<Minimal>(9),
+            signing_root: Some(BLOCK_SIGNING_ROOT),
+        };
+
+        slashing_protector.validate_and_store_proposal(proposal, PUBKEY, 9)?;
+
+        let unknown_pubkey = PublicKeyBytes::default();
+
+        assert_eq!(
+            slashing_protector.latest_signed_epochs::<Minimal>([PUBKEY, unknown_pubkey])?,
+            HashMap::from([(PUBKEY, 9)]),
+        );
+
+        Ok(())
+    }
+
     #[duplicate_item(
         glob                                                             function_name                     constructor;
         ["slashing-protection-interchange-tests/tests/generated/*.json"] [run_interchange_test_in_memory]  [build_in_memory_slashing_protector];
@@ -0,0 +1,200 @@
+use core::str::FromStr as _;
+
+use ethereum_types::H256;
+use ssz_derive::Ssz;
+use typenum::U4;
+
+use crate::{
+    contiguous_list::ContiguousList,
+    error::{ReadError, WriteError},
+    merkle_tree,
+    porcelain::{SszHash, SszReadDefault as _, SszWrite},
+};
+
+#[derive(PartialEq, Eq, Debug, Ssz)]
+#[ssz(internal)]
+enum TestUnion {
+    None,
+    Byte(u8),
+    Pair(u16),
+    List(ContiguousList<u16, U4>),
+}
+
+// The example from [EIP-7495].
+//
+// [EIP-7495]: https://eips.ethereum.org/EIPS/eip-7495#stablecontainern
+#[derive(PartialEq, Eq, Debug, Ssz)]
+#[ssz(internal, stable_container = 4)]
+struct Shape {
+    side: Option<u16>,
+    color: Option<u8>,
+    radius: Option<u16>,
+}
+
+#[derive(PartialEq, Eq, Debug, Ssz)]
+#[ssz(internal, stable_container = 8)]
+struct VariableStableContainer {
+    a: Option<u8>,
+    b: Option<ContiguousList<u16, U4>>,
+    c: Option<u16>,
+}
+
+#[test]
+fn union_variants_are_serialized_with_selectors() -> Result<(), WriteError> {
+    let list = ContiguousList::try_from(vec![1, 2]).expect("list fits in maximum length");
+
+    assert_eq!(TestUnion::None.to_ssz()?, [0x00]);
+    assert_eq!(TestUnion::Byte(0xaa).to_ssz()?, [0x01, 0xaa]);
+    assert_eq!(TestUnion::Pair(0x1234).to_ssz()?, [0x02, 0x34, 0x12]);
+    assert_eq!(TestUnion::List(list).to_ssz()?, [0x03, 1, 0, 2, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn union_round_trips() -> Result<(), WriteError> {
+    let list = ContiguousList::try_from(vec![3, 4, 5]).expect("list fits in maximum length");
+
+    for value in [
+        TestUnion::None,
+        TestUnion::Byte(0xaa),
+        TestUnion::Pair(0x1234),
+        TestUnion::List(list),
+    ] {
+        assert_eq!(TestUnion::from_ssz_default(value.to_ssz()?), Ok(value));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_unions_are_rejected() {
+    assert_eq!(
+        TestUnion::from_ssz_default([]),
+        Err(ReadError::UnionEmptySlice),
+    );
+
+    assert_eq!(
+        TestUnion::from_ssz_default([0x04, 0x00]),
+        Err(ReadError::UnionSelectorInvalid { selector: 4 }),
+    );
+
+    assert_eq!(
+        TestUnion::from_ssz_default([0x00, 0x00]),
+        Err(ReadError::UnionNoneFollowedByValue { length: 1 }),
+    );
+
+    assert_eq!(
+        TestUnion::from_ssz_default([0x02, 0x34]),
+        Err(ReadError::FixedSizeMismatch {
+            expected: 2,
+            actual: 1,
+        }),
+    );
+}
+
+#[test]
+fn union_hash_tree_root_mixes_in_selector() {
+    assert_eq!(
+        TestUnion::None.hash_tree_root(),
+        merkle_tree::mix_in_selector(H256::zero(), 0),
+    );
+
+    assert_eq!(
+        TestUnion::Pair(0x1234).hash_tree_root(),
+        merkle_tree::mix_in_selector(0x1234_u16.hash_tree_root(), 2),
+    );
+}
+
+#[test]
+fn stable_container_matches_eip_7495_examples() -> Result<(), WriteError> {
+    let cases = [
+        (
+            Shape {
+                side: Some(0x42),
+                color: Some(1),
+                radius: Some(0x42),
+            },
+            [0x07, 0x42, 0x00, 0x01, 0x42, 0x00].as_slice(),
+            "37b28eab19bc3e246e55d2e2b2027479454c27ee006d92d4847c84893a162e6d",
+        ),
+        (
+            Shape {
+                side: Some(0x42),
+                color: Some(1),
+                radius: None,
+            },
+            [0x03, 0x42, 0x00, 0x01].as_slice(),
+            "bfdb6fda9d02805e640c0f5767b8d1bb9ff4211498a5e2d7c0f36e1b88ce57ff",
+        ),
+        (
+            Shape {
+                side: None,
+                color: Some(1),
+                radius: None,
+            },
+            [0x02, 0x01].as_slice(),
+            "522edd7309c0041b8eb6a218d756af558e9cf4c816441ec7e6eef42dfa47bb98",
+        ),
+        (
+            Shape {
+                side: None,
+                color: Some(1),
+                radius: Some(0x42),
+            },
+            [0x06, 0x01, 0x42, 0x00].as_slice(),
+            "f66d2c38c8d2afbd409e86c529dff728e9a4208215ca20ee44e49c3d11e145d8",
+        ),
+    ];
+
+    for (shape, expected_bytes, expected_root) in cases {
+        let expected_root = H256::from_str(expected_root).expect("root in test is valid");
+
+        assert_eq!(shape.to_ssz()?, expected_bytes);
+        assert_eq!(shape.hash_tree_root(), expected_root);
+        assert_eq!(Shape::from_ssz_default(expected_bytes), Ok(shape));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stable_container_with_variable_fields_round_trips() -> Result<(), WriteError> {
+    let list = ContiguousList::try_from(vec![1, 2]).expect("list fits in maximum length");
+
+    let value = VariableStableContainer {
+        a: Some(0xaa),
+        b: Some(list),
+        c: Some(0x1234),
+    };
+
+    let bytes = value.to_ssz()?;
+
+    assert_eq!(
+        bytes,
+        [0x07, 0xaa, 0x07, 0x00, 0x00, 0x00, 0x34, 0x12, 1, 0, 2, 0],
+    );
+
+    assert_eq!(VariableStableContainer::from_ssz_default(bytes), Ok(value));
+
+    let value = VariableStableContainer {
+        a: None,
+        b: Some(ContiguousList::default()),
+        c: None,
+    };
+
+    let bytes = value.to_ssz()?;
+
+    assert_eq!(bytes, [0x02, 0x04, 0x00, 0x00, 0x00]);
+    assert_eq!(VariableStableContainer::from_ssz_default(bytes), Ok(value));
+
+    Ok(())
+}
+
+#[test]
+fn stable_container_with_unknown_fields_is_rejected() {
+    assert_eq!(
+        Shape::from_ssz_default([0x0a, 0x42, 0x00]),
+        Err(ReadError::StableContainerUnknownField { index: 3 }),
+    );
+}
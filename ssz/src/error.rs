@@ -44,6 +44,16 @@ pub enum ReadError {
     BitListTooLong { maximum: usize, actual: usize },
     #[error("expected container to have {expected} as the first offset, found {actual}")]
     ContainerFirstOffsetMismatch { expected: usize, actual: usize },
+    #[error("empty slice is not a valid union")]
+    UnionEmptySlice,
+    #[error("union selector {selector} does not correspond to any variant")]
+    UnionSelectorInvalid { selector: u8 },
+    #[error("union selector 0 denotes None but is followed by {length} bytes")]
+    UnionNoneFollowedByValue { length: usize },
+    #[error("expected optional value to be empty or start with 1, found {selector}")]
+    OptionalSelectorInvalid { selector: u8 },
+    #[error("stable container has unknown field {index} marked as active")]
+    StableContainerUnknownField { index: usize },
     // TODO(Grandine Team): Try replacing `ReadError::Custom` with something that can carry arbitrary
     //                      runtime data. An `Error` associated type in `SszRead` would be the most
     //                      flexible, but harder to use and would require a lot of changes. Using
@@ -78,3 +88,17 @@ pub enum ConversionError {
     #[error("Uint256 {value} does not fit in u64")]
     Uint256DoesNotFitInU64 { value: Uint256 },
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Error)]
+pub enum MultiproofError {
+    #[error("generalized index 0 does not refer to any node")]
+    ZeroGeneralizedIndex,
+    #[error("node at generalized index {gindex} is not available")]
+    NodeMissing { gindex: u64 },
+    #[error("expected {expected} leaves, found {actual}")]
+    LeafCountMismatch { expected: usize, actual: usize },
+    #[error("expected {expected} proof nodes, found {actual}")]
+    ProofLengthMismatch { expected: usize, actual: usize },
+    #[error("multiproof does not reach the root")]
+    RootNotReached,
+}
@@ -13,13 +13,16 @@ pub use crate::{
     consts::{Endianness, Offset, BYTES_PER_LENGTH_OFFSET},
     contiguous_list::ContiguousList,
     contiguous_vector::ContiguousVector,
-    error::{IndexError, PushError, ReadError, WriteError},
+    error::{IndexError, MultiproofError, PushError, ReadError, WriteError},
     hc::Hc,
-    merkle_tree::{mix_in_length, MerkleTree, ProofWithLength},
+    merkle_tree::{mix_in_aux, mix_in_length, mix_in_selector, MerkleTree, ProofWithLength},
+    multiproof::{calculate_multi_merkle_root, helper_indices, MerkleNodes, Multiproof},
     persistent_list::PersistentList,
     persistent_vector::PersistentVector,
     porcelain::{SszHash, SszRead, SszReadDefault, SszSize, SszWrite},
-    shared::{read_offset_unchecked, subslice, write_offset},
+    shared::{
+        read_offset_unchecked, read_union_selector, subslice, write_offset, write_with_selector,
+    },
     size::Size,
     type_level::{
         ArrayLengthCopy, BitVectorBits, ByteVectorBytes, BytesToDepth, ContiguousVectorElements,
@@ -44,7 +47,9 @@ mod error;
 mod hc;
 mod iter;
 mod merkle_tree;
+mod multiproof;
 mod negative;
+mod optional;
 mod persistent_list;
 mod persistent_vector;
 mod pointers;
@@ -55,5 +60,7 @@ mod type_level;
 mod uint256;
 mod zero_default;
 
+#[cfg(test)]
+mod derive_tests;
 #[cfg(test)]
 mod spec_tests;
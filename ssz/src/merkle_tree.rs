@@ -129,6 +129,14 @@ impl<D: ArrayLength<H256>> MerkleTree<D> {
         (sibling_to_update, hash)
     }
 
+    /// Returns the hash of the last complete left node at `height`.
+    ///
+    /// The value is only meaningful if bit `height` of the number of chunks pushed is set.
+    #[must_use]
+    pub fn sibling_hash(&self, height: usize) -> H256 {
+        self.sibling_hashes[height]
+    }
+
     pub fn push_and_compute_root(&mut self, index: usize, chunk: H256) -> H256 {
         let (updated_sibling, mut hash) = self.push(index, chunk);
 
@@ -289,6 +297,20 @@ pub fn mix_in_length(root: H256, length: usize) -> H256 {
     hashing::hash_256_256(root, hash_of_length(length))
 }
 
+/// [`mix_in_selector`](https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/simple-serialize.md#merkleization)
+#[must_use]
+pub fn mix_in_selector(root: H256, selector: u8) -> H256 {
+    let mut hash_of_selector = H256::zero();
+    hash_of_selector[..1].copy_from_slice(&[selector]);
+    hashing::hash_256_256(root, hash_of_selector)
+}
+
+/// [`mix_in_aux`](https://eips.ethereum.org/EIPS/eip-7495#merkleization)
+#[must_use]
+pub fn mix_in_aux(root: H256, aux: H256) -> H256 {
+    hashing::hash_256_256(root, aux)
+}
+
 fn hash_of_length(length: usize) -> H256 {
     assert_type_eq_all!(Endianness, LittleEndian);
 
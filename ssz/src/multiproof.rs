@@ -0,0 +1,269 @@
+// Merkle multiproofs as described in the [SSZ proof specification].
+//
+// A multiproof proves several nodes of a tree at once. Siblings that can be computed from the
+// proven nodes (or from other siblings) are omitted, so the proof is never larger than the
+// individual branches combined and is usually much smaller.
+//
+// Nodes are identified by generalized indices. The root has index 1 and the children of node `i`
+// are `2 * i` and `2 * i + 1`.
+//
+// [SSZ proof specification]: https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/merkle-proofs.md#merkle-multiproofs
+
+use std::collections::{BTreeSet, HashMap};
+
+use ethereum_types::H256;
+use itertools::Itertools as _;
+
+use crate::error::MultiproofError;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Multiproof {
+    pub gindices: Vec<u64>,
+    /// Values of the nodes at `gindices`, in the same order.
+    pub leaves: Vec<H256>,
+    /// Values of the nodes at [`helper_indices`] of `gindices`, in the same order.
+    pub proof: Vec<H256>,
+}
+
+impl Multiproof {
+    /// [`verify_merkle_multiproof`](https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/merkle-proofs.md#merkle-multiproofs)
+    #[must_use]
+    pub fn verify(&self, root: H256) -> bool {
+        calculate_multi_merkle_root(&self.leaves, &self.proof, &self.gindices) == Ok(root)
+    }
+}
+
+/// Nodes of one or more Merkle trees nested in each other.
+///
+/// Every node of a subtree is stored, so this is only suitable for small trees like the ones
+/// formed by fields of containers.
+#[derive(Default)]
+pub struct MerkleNodes {
+    nodes: HashMap<u64, H256>,
+}
+
+impl MerkleNodes {
+    /// Stores the tree formed by `chunks` with its root at generalized index `root_gindex`.
+    ///
+    /// Missing chunks up to the next power of 2 are treated as zero hashes.
+    /// Returns the root of the tree.
+    pub fn insert_subtree(
+        &mut self,
+        root_gindex: u64,
+        chunks: impl IntoIterator<Item = H256>,
+    ) -> H256 {
+        let mut level = chunks.into_iter().collect_vec();
+        let width = level.len().next_power_of_two();
+        let mut first_gindex = root_gindex << width.ilog2();
+
+        level.resize(width, H256::zero());
+
+        loop {
+            self.nodes
+                .extend((first_gindex..).zip(level.iter().copied()));
+
+            if let [root] = level.as_slice() {
+                return *root;
+            }
+
+            level = level
+                .into_iter()
+                .tuples()
+                .map(|(left, right)| hashing::hash_256_256(left, right))
+                .collect();
+
+            first_gindex /= 2;
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, gindex: u64) -> Option<H256> {
+        self.nodes.get(&gindex).copied()
+    }
+
+    pub fn multiproof(&self, gindices: &[u64]) -> Result<Multiproof, MultiproofError> {
+        let node = |gindex| {
+            self.get(gindex)
+                .ok_or(MultiproofError::NodeMissing { gindex })
+        };
+
+        let leaves = gindices.iter().copied().map(node).try_collect()?;
+
+        let proof = helper_indices(gindices)?
+            .into_iter()
+            .map(node)
+            .try_collect()?;
+
+        Ok(Multiproof {
+            gindices: gindices.to_vec(),
+            leaves,
+            proof,
+        })
+    }
+}
+
+/// [`get_helper_indices`](https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/merkle-proofs.md#merkle-multiproofs)
+///
+/// Returns the indices of nodes needed to compute the root from nodes at `gindices`,
+/// sorted in descending order.
+pub fn helper_indices(gindices: &[u64]) -> Result<Vec<u64>, MultiproofError> {
+    let mut branch_indices = BTreeSet::new();
+    let mut path_indices = BTreeSet::new();
+
+    for gindex in gindices.iter().copied() {
+        if gindex == 0 {
+            return Err(MultiproofError::ZeroGeneralizedIndex);
+        }
+
+        let mut node = gindex;
+
+        while node > 1 {
+            path_indices.insert(node);
+            branch_indices.insert(node ^ 1);
+            node /= 2;
+        }
+    }
+
+    Ok(branch_indices
+        .into_iter()
+        .rev()
+        .filter(|gindex| !path_indices.contains(gindex))
+        .collect())
+}
+
+/// [`calculate_multi_merkle_root`](https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/merkle-proofs.md#merkle-multiproofs)
+pub fn calculate_multi_merkle_root(
+    leaves: &[H256],
+    proof: &[H256],
+    gindices: &[u64],
+) -> Result<H256, MultiproofError> {
+    let helper_indices = helper_indices(gindices)?;
+
+    if leaves.len() != gindices.len() {
+        return Err(MultiproofError::LeafCountMismatch {
+            expected: gindices.len(),
+            actual: leaves.len(),
+        });
+    }
+
+    if proof.len() != helper_indices.len() {
+        return Err(MultiproofError::ProofLengthMismatch {
+            expected: helper_indices.len(),
+            actual: proof.len(),
+        });
+    }
+
+    let mut objects = gindices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .chain(helper_indices.iter().copied().zip(proof.iter().copied()))
+        .collect::<HashMap<_, _>>();
+
+    let mut keys = objects.keys().copied().sorted().rev().collect_vec();
+    let mut position = 0;
+
+    while let Some(key) = keys.get(position).copied() {
+        let parent = key / 2;
+
+        if key > 1 && !objects.contains_key(&parent) {
+            if let (Some(left), Some(right)) = (objects.get(&(key & !1)), objects.get(&(key | 1))) {
+                objects.insert(parent, hashing::hash_256_256(*left, *right));
+                keys.push(parent);
+            }
+        }
+
+        position += 1;
+    }
+
+    objects
+        .get(&1)
+        .copied()
+        .ok_or(MultiproofError::RootNotReached)
+}
+
+#[cfg(test)]
+mod tests {
+    use hashing::ZERO_HASHES;
+
+    use super::*;
+
+    fn chunk(byte: u8) -> H256 {
+        H256::repeat_byte(byte)
+    }
+
+    fn nodes() -> (MerkleNodes, H256) {
+        let mut nodes = MerkleNodes::default();
+
+        // A container with 3 fields, the last of which is a container with 5 fields.
+        let inner_root = nodes.insert_subtree(6, (1..=5).map(chunk));
+        let root = nodes.insert_subtree(1, [chunk(6), chunk(7), inner_root]);
+
+        (nodes, root)
+    }
+
+    #[test]
+    fn helper_indices_match_example_in_specification() -> Result<(), MultiproofError> {
+        assert_eq!(helper_indices(&[8, 9, 14])?, [15, 6, 5]);
+        assert!(helper_indices(&[4, 5, 6, 7])?.is_empty());
+        assert!(helper_indices(&[1])?.is_empty());
+        assert_eq!(
+            helper_indices(&[0]),
+            Err(MultiproofError::ZeroGeneralizedIndex)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn subtrees_are_padded_with_zero_hashes() {
+        let (nodes, root) = nodes();
+        let inner_root = nodes.get(6).unwrap_or_default();
+
+        assert_eq!(nodes.get(1), Some(root));
+        assert_eq!(
+            nodes.get(3),
+            Some(hashing::hash_256_256(inner_root, H256::zero()))
+        );
+        assert_eq!(nodes.get(6 << 3 | 4), Some(chunk(5)));
+        assert_eq!(nodes.get(6 << 3 | 5), Some(H256::zero()));
+        assert_eq!(nodes.get(6 << 2 | 3), Some(ZERO_HASHES[1]));
+        assert_eq!(nodes.get(6 << 4), None);
+    }
+
+    #[test]
+    fn multiproofs_are_verified_against_root() -> Result<(), MultiproofError> {
+        let (nodes, root) = nodes();
+
+        let multiproof = nodes.multiproof(&[4, 6 << 3 | 1, 6 << 3 | 4])?;
+
+        // Siblings of the requested nodes and their ancestors,
+        // excluding the ones that can be computed from the requested nodes.
+        assert_eq!(multiproof.proof.len(), 6);
+        assert_eq!(multiproof.leaves, [chunk(6), chunk(2), chunk(5)]);
+        assert!(multiproof.verify(root));
+        assert!(!multiproof.verify(chunk(0)));
+
+        let mut tampered = multiproof.clone();
+        tampered.leaves[1] = chunk(3);
+
+        assert!(!tampered.verify(root));
+
+        let mut truncated = multiproof;
+        truncated.proof.pop();
+
+        assert!(!truncated.verify(root));
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiproof_fails_for_nodes_not_stored() {
+        let (nodes, _) = nodes();
+
+        assert_eq!(
+            nodes.multiproof(&[6 << 4]),
+            Err(MultiproofError::NodeMissing { gindex: 6 << 4 }),
+        );
+    }
+}
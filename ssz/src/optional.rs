@@ -0,0 +1,120 @@
+// `Option` is serialized as the provisional `Optional` type from [EIP-6475].
+// It is equivalent to `Union[None, T]` except that `None` is serialized as an empty slice.
+//
+// Fields of stable containers are also represented by `Option`, but they are serialized
+// differently. See the `stable_container` attribute of `ssz_derive::Ssz`.
+//
+// [EIP-6475]: https://eips.ethereum.org/EIPS/eip-6475
+
+use ethereum_types::H256;
+use typenum::U1;
+
+use crate::{
+    error::{ReadError, WriteError},
+    merkle_tree,
+    porcelain::{SszHash, SszRead, SszSize, SszWrite},
+    shared,
+    size::Size,
+};
+
+const SOME_SELECTOR: u8 = 1;
+
+impl<T> SszSize for Option<T> {
+    const SIZE: Size = Size::Variable { minimum_size: 0 };
+}
+
+impl<C, T: SszRead<C>> SszRead<C> for Option<T> {
+    fn from_ssz_unchecked(context: &C, bytes: &[u8]) -> Result<Self, ReadError> {
+        let Some((selector, value_bytes)) = bytes.split_first() else {
+            return Ok(None);
+        };
+
+        if *selector != SOME_SELECTOR {
+            return Err(ReadError::OptionalSelectorInvalid {
+                selector: *selector,
+            });
+        }
+
+        T::from_ssz(context, value_bytes).map(Some)
+    }
+}
+
+impl<T: SszWrite> SszWrite for Option<T> {
+    fn write_variable(&self, bytes: &mut Vec<u8>) -> Result<(), WriteError> {
+        match self {
+            Some(value) => shared::write_with_selector(bytes, SOME_SELECTOR, value),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: SszHash> SszHash for Option<T> {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        match self {
+            Some(value) => merkle_tree::mix_in_selector(value.hash_tree_root(), SOME_SELECTOR),
+            None => merkle_tree::mix_in_selector(H256::zero(), 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashing::ZERO_HASHES;
+
+    use crate::porcelain::SszReadDefault as _;
+
+    use super::*;
+
+    #[test]
+    fn none_is_serialized_as_empty_slice() -> Result<(), WriteError> {
+        assert!(None::<u16>.to_ssz()?.is_empty());
+        assert_eq!(Option::<u16>::from_ssz_default([]), Ok(None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn some_is_serialized_with_selector() -> Result<(), WriteError> {
+        assert_eq!(Some(0x1234_u16).to_ssz()?, [0x01, 0x34, 0x12]);
+        assert_eq!(
+            Option::<u16>::from_ssz_default([0x01, 0x34, 0x12]),
+            Ok(Some(0x1234)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_selector_is_rejected() {
+        assert_eq!(
+            Option::<u16>::from_ssz_default([0x02, 0x34, 0x12]),
+            Err(ReadError::OptionalSelectorInvalid { selector: 2 }),
+        );
+    }
+
+    #[test]
+    fn value_of_wrong_size_is_rejected() {
+        assert_eq!(
+            Option::<u16>::from_ssz_default([0x01, 0x34]),
+            Err(ReadError::FixedSizeMismatch {
+                expected: 2,
+                actual: 1,
+            }),
+        );
+    }
+
+    #[test]
+    fn hash_tree_root_mixes_in_selector() {
+        let mut hash_of_selector = H256::zero();
+        hash_of_selector[..1].copy_from_slice(&[SOME_SELECTOR]);
+
+        assert_eq!(None::<u16>.hash_tree_root(), ZERO_HASHES[1]);
+
+        assert_eq!(
+            Some(0x1234_u16).hash_tree_root(),
+            hashing::hash_256_256(0x1234_u16.hash_tree_root(), hash_of_selector),
+        );
+    }
+}
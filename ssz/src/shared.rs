@@ -150,6 +150,35 @@ pub fn write_list<T: SszWrite>(
     Ok(())
 }
 
+#[inline]
+pub fn read_union_selector(bytes: &[u8]) -> Result<(u8, &[u8]), ReadError> {
+    bytes
+        .split_first()
+        .map(|(selector, value_bytes)| (*selector, value_bytes))
+        .ok_or(ReadError::UnionEmptySlice)
+}
+
+// Used for unions and optional values, which are serialized as a selector followed by the value.
+#[inline]
+pub fn write_with_selector<T: SszWrite>(
+    bytes: &mut Vec<u8>,
+    selector: u8,
+    value: &T,
+) -> Result<(), WriteError> {
+    bytes.push(selector);
+
+    match T::SIZE {
+        Size::Fixed { size } => {
+            let length_before = bytes.len();
+            bytes.resize(length_before + size, 0);
+            value.write_fixed(&mut bytes[length_before..]);
+        }
+        Size::Variable { .. } => value.write_variable(bytes)?,
+    }
+
+    Ok(())
+}
+
 pub fn validate_index(length: usize, index: u64) -> Result<usize, IndexError> {
     // Converting `index` to `usize` is safe, but it makes elements past `u32::MAX` inaccessible on
     // 32 bit machines. Persistent collections may have more than that due to structural sharing.
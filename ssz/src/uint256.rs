@@ -266,6 +266,11 @@ impl Uint256 {
         ]))
     }
 
+    #[must_use]
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Self)
+    }
+
     const fn into_raw(self) -> RawUint256 {
         self.0
     }
@@ -18,6 +18,7 @@ use crate::ssz_type::SszType;
 mod crate_path;
 mod ssz_field;
 mod ssz_type;
+mod ssz_variant;
 
 #[proc_macro_derive(Ssz, attributes(ssz))]
 pub fn derive(input: TokenStream) -> TokenStream {
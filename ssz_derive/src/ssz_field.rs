@@ -1,5 +1,5 @@
 use darling::FromField;
-use syn::{parse_quote, ExprPath, Ident, Path, Type};
+use syn::{parse_quote, ExprPath, GenericArgument, Ident, Path, PathArguments, Type};
 
 #[derive(FromField)]
 #[darling(attributes(ssz))]
@@ -16,4 +16,27 @@ impl SszField {
         let ty = &self.ty;
         parse_quote! { <#ty as #ssz::SszSize>::SIZE }
     }
+
+    // Fields of stable containers are declared as `Option<T>`. This returns `T`.
+    // Type aliases and fully qualified paths to `Option` are not recognized.
+    pub fn optional_type(&self) -> Option<&Type> {
+        let Type::Path(type_path) = &self.ty else {
+            return None;
+        };
+
+        let segment = type_path.path.segments.last()?;
+
+        if segment.ident != "Option" {
+            return None;
+        }
+
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+
+        match arguments.args.first()? {
+            GenericArgument::Type(ty) if arguments.args.len() == 1 => Some(ty),
+            _ => None,
+        }
+    }
 }
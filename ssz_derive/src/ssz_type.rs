@@ -7,11 +7,20 @@ use syn::{
     parse_quote,
     punctuated::Punctuated,
     token::{Comma, Where},
-    Error, Expr, Generics, Ident, ImplGenerics, ImplItemFn, ImplItemType, Member, Path,
+    Error, Expr, Generics, Ident, ImplGenerics, ImplItemFn, ImplItemType, Member, Path, Type,
     TypeGenerics, TypeParam, WhereClause, WherePredicate,
 };
 
-use crate::{crate_path, ssz_field::SszField};
+use crate::{crate_path, ssz_field::SszField, ssz_variant::SszVariant};
+
+// See <https://github.com/ethereum/consensus-specs/blob/4c54bddb6cd144ca8a0a01b7155f43b295c70458/ssz/simple-serialize.md#illegal-types>.
+const MAX_UNION_VARIANTS: usize = 128;
+
+const BITS_PER_BYTE: usize = 8;
+const BYTES_PER_CHUNK: usize = 32;
+
+// Selector, name, and field of a union variant.
+type UnionVariant<'a> = (u8, &'a Ident, Option<&'a SszField>);
 
 // False positive. The `bool`s are independent and using enums would be too verbose.
 #[allow(clippy::struct_excessive_bools)]
@@ -23,7 +32,7 @@ use crate::{crate_path, ssz_field::SszField};
 pub struct SszType {
     ident: Ident,
     generics: Generics,
-    data: Data<(), SszField>,
+    data: Data<SszVariant, SszField>,
 
     // This is named `bound` to mimic other derive macros.
     //
@@ -48,6 +57,13 @@ pub struct SszType {
     // By default, newtype structs that wrap a variable-size type add an additional offset.
     #[darling(default)]
     transparent: bool,
+    // Structs with this attribute are serialized as [EIP-7495] stable containers with the
+    // specified capacity. All unskipped fields must be of type `Option<T>`.
+    //
+    // Enums are always serialized as SSZ unions. The position of a variant is used as its selector.
+    //
+    // [EIP-7495]: https://eips.ethereum.org/EIPS/eip-7495
+    stable_container: Option<usize>,
 }
 
 impl SszType {
@@ -85,6 +101,7 @@ impl SszType {
         }
 
         if self.derive_write {
+            // Unions and stable containers are always variable-size.
             let write_fixed_fn_impl = self.write_fixed_fn_impl(&ssz)?;
             let write_variable_fn_impl = self.write_variable_fn_impl(&ssz)?;
 
@@ -119,6 +136,8 @@ impl SszType {
             derive_read,
             derive_size,
             derive_write,
+            transparent,
+            stable_container,
             ..
         } = *self;
 
@@ -129,6 +148,27 @@ impl SszType {
             ));
         }
 
+        if self.is_union() && (transparent || stable_container.is_some()) {
+            return Err(Error::new(
+                Span::call_site(),
+                "transparent and stable_container attributes cannot be used with unions",
+            ));
+        }
+
+        if transparent && stable_container.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "transparent and stable_container attributes cannot be used together",
+            ));
+        }
+
+        if stable_container == Some(0) {
+            return Err(Error::new(
+                Span::call_site(),
+                "stable containers must have a nonzero capacity",
+            ));
+        }
+
         Ok(())
     }
 
@@ -178,6 +218,20 @@ impl SszType {
     }
 
     fn size_expr(&self, ssz: &Path) -> Result<Expr, Error> {
+        if self.is_union() {
+            self.union_variants()?;
+
+            return Ok(parse_quote! { #ssz::Size::Variable { minimum_size: 1 } });
+        }
+
+        if let Some(capacity) = self.stable_container {
+            self.stable_container_fields()?;
+
+            let minimum_size = active_fields_length(capacity);
+
+            return Ok(parse_quote! { #ssz::Size::Variable { minimum_size: #minimum_size } });
+        }
+
         if self.transparent {
             self.single_unskipped_field()?;
         }
@@ -201,6 +255,14 @@ impl SszType {
     // False positive. The name refers to the function whose implementation this generates.
     #[allow(clippy::wrong_self_convention)]
     fn from_ssz_unchecked_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        if self.is_union() {
+            return self.union_from_ssz_unchecked_fn_impl(ssz);
+        }
+
+        if let Some(capacity) = self.stable_container {
+            return self.stable_container_from_ssz_unchecked_fn_impl(ssz, capacity);
+        }
+
         if self.transparent {
             let (member, _) = self.single_unskipped_field()?;
 
@@ -308,16 +370,20 @@ impl SszType {
         })
     }
 
-    fn write_fixed_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+    fn write_fixed_fn_impl(&self, ssz: &Path) -> Result<Option<ImplItemFn>, Error> {
+        if self.is_union() || self.stable_container.is_some() {
+            return Ok(None);
+        }
+
         if self.transparent {
             let (member, _) = self.single_unskipped_field()?;
 
-            return Ok(parse_quote! {
+            return Ok(Some(parse_quote! {
                 #[inline]
                 fn write_fixed(&self, bytes: &mut [u8]) {
                     #ssz::SszWrite::write_fixed(&self.#member, bytes)
                 }
-            });
+            }));
         }
 
         let stmts = self.unskipped_fields()?.map(|(member, ssz_field)| {
@@ -330,14 +396,22 @@ impl SszType {
             }
         });
 
-        Ok(parse_quote! {
+        Ok(Some(parse_quote! {
             fn write_fixed(&self, bytes: &mut [u8]) {
                 #(#stmts)*
             }
-        })
+        }))
     }
 
     fn write_variable_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        if self.is_union() {
+            return self.union_write_variable_fn_impl(ssz);
+        }
+
+        if let Some(capacity) = self.stable_container {
+            return self.stable_container_write_variable_fn_impl(ssz, capacity);
+        }
+
         if self.transparent {
             let (member, _) = self.single_unskipped_field()?;
 
@@ -415,6 +489,14 @@ impl SszType {
     }
 
     fn hash_tree_root_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        if self.is_union() {
+            return self.union_hash_tree_root_fn_impl(ssz);
+        }
+
+        if let Some(capacity) = self.stable_container {
+            return self.stable_container_hash_tree_root_fn_impl(ssz, capacity);
+        }
+
         if self.transparent {
             let (member, _) = self.single_unskipped_field()?;
 
@@ -426,49 +508,471 @@ impl SszType {
             });
         }
 
-        let mut nodes = self
+        let nodes = self
             .unskipped_fields()?
             .map(|(member, _)| quote! { #ssz::SszHash::hash_tree_root(&self.#member) })
             .collect_vec();
 
-        let mut height = 0_usize;
+        if nodes.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "struct has no unskipped fields",
+            ));
+        }
 
-        let root = loop {
-            match nodes.len() {
-                0 => {
-                    return Err(Error::new(
-                        Span::call_site(),
-                        "struct has no unskipped fields",
-                    ));
+        let root = merkleize(ssz, nodes, 0);
+
+        Ok(parse_quote! {
+            fn hash_tree_root(&self) -> #ssz::H256 {
+                #root
+            }
+        })
+    }
+
+    // False positive. The name refers to the function whose implementation this generates.
+    #[allow(clippy::wrong_self_convention)]
+    fn union_from_ssz_unchecked_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        let arms = self
+            .union_variants()?
+            .into_iter()
+            .map(|(selector, ident, ssz_field)| {
+                if ssz_field.is_some() {
+                    quote! {
+                        #selector => ::core::result::Result::Ok(Self::#ident(
+                            #ssz::SszRead::from_ssz(context, bytes)?,
+                        )),
+                    }
+                } else {
+                    quote! {
+                        #selector => {
+                            if !bytes.is_empty() {
+                                let error = #ssz::ReadError::UnionNoneFollowedByValue {
+                                    length: bytes.len(),
+                                };
+
+                                return ::core::result::Result::Err(error);
+                            }
+
+                            ::core::result::Result::Ok(Self::#ident)
+                        }
+                    }
+                }
+            });
+
+        Ok(parse_quote! {
+            fn from_ssz_unchecked(context: &C, bytes: &[u8]) -> Result<Self, #ssz::ReadError> {
+                let (selector, bytes) = #ssz::read_union_selector(bytes)?;
+
+                match selector {
+                    #(#arms)*
+                    selector => ::core::result::Result::Err(
+                        #ssz::ReadError::UnionSelectorInvalid { selector },
+                    ),
+                }
+            }
+        })
+    }
+
+    fn union_write_variable_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        let arms = self
+            .union_variants()?
+            .into_iter()
+            .map(|(selector, ident, ssz_field)| {
+                if ssz_field.is_some() {
+                    quote! {
+                        Self::#ident(value) => #ssz::write_with_selector(bytes, #selector, value),
+                    }
+                } else {
+                    quote! {
+                        Self::#ident => {
+                            bytes.push(#selector);
+                            ::core::result::Result::Ok(())
+                        }
+                    }
                 }
-                1 => {
-                    break nodes
-                        .into_iter()
-                        .exactly_one()
-                        .expect("this branch is executed when nodes contains exactly 1 element");
+            });
+
+        Ok(parse_quote! {
+            fn write_variable(
+                &self,
+                bytes: &mut ::std::vec::Vec<u8>,
+            ) -> ::core::result::Result<(), #ssz::WriteError> {
+                match self {
+                    #(#arms)*
                 }
-                _ => {
-                    let zero_hash_expr = quote! { #ssz::hashing::ZERO_HASHES[#height] };
+            }
+        })
+    }
 
-                    nodes = nodes
-                        .into_iter()
-                        .chain(core::iter::once(zero_hash_expr))
-                        .tuples()
-                        .map(|(left, right)| quote! { #ssz::hashing::hash_256_256(#left, #right) })
-                        .collect();
+    fn union_hash_tree_root_fn_impl(&self, ssz: &Path) -> Result<ImplItemFn, Error> {
+        let arms = self
+            .union_variants()?
+            .into_iter()
+            .map(|(selector, ident, ssz_field)| {
+                if ssz_field.is_some() {
+                    quote! {
+                        Self::#ident(value) => #ssz::mix_in_selector(
+                            #ssz::SszHash::hash_tree_root(value),
+                            #selector,
+                        ),
+                    }
+                } else {
+                    quote! {
+                        Self::#ident => #ssz::mix_in_selector(#ssz::H256::zero(), #selector),
+                    }
+                }
+            });
 
-                    height += 1;
+        Ok(parse_quote! {
+            fn hash_tree_root(&self) -> #ssz::H256 {
+                match self {
+                    #(#arms)*
                 }
             }
+        })
+    }
+
+    // False positive. The name refers to the function whose implementation this generates.
+    #[allow(clippy::wrong_self_convention)]
+    fn stable_container_from_ssz_unchecked_fn_impl(
+        &self,
+        ssz: &Path,
+        capacity: usize,
+    ) -> Result<ImplItemFn, Error> {
+        let fields = self.stable_container_fields()?;
+        let active_fields_length = active_fields_length(capacity);
+        let field_count = fields.len();
+
+        let unknown_fields_validation_stmt = (field_count < capacity).then(|| {
+            quote! {
+                let unknown_field = (#field_count..#capacity).find(|index| {
+                    active_fields[index / #BITS_PER_BYTE] & (1 << (index % #BITS_PER_BYTE)) != 0
+                });
+
+                if let ::core::option::Option::Some(index) = unknown_field {
+                    let error = #ssz::ReadError::StableContainerUnknownField { index };
+                    return ::core::result::Result::Err(error);
+                }
+            }
+        });
+
+        let init_stmts = quote! {
+            let active_fields = #ssz::subslice(bytes, 0..#active_fields_length)?;
+            let bytes = &bytes[#active_fields_length..];
+
+            #unknown_fields_validation_stmt
+
+            let current_position_in_fixed = 0;
+            let lowest_used_offset = bytes.len();
         };
 
+        let fixed_part_stmts = fields.iter().map(|(index, member, ty)| {
+            let size_expr = optional_size_expr(ssz, ty);
+            let offset_ident = member.offset_ident();
+            let presence_ident = member.presence_ident();
+            let (byte_index, bit_mask) = active_field_position(*index);
+
+            quote! {
+                let #presence_ident = active_fields[#byte_index] & #bit_mask != 0;
+
+                let #offset_ident = if #presence_ident {
+                    match #size_expr {
+                        #ssz::Size::Fixed { size } => current_position_in_fixed,
+                        #ssz::Size::Variable { .. } => {
+                            let end = current_position_in_fixed + #ssz::BYTES_PER_LENGTH_OFFSET;
+                            let subslice = #ssz::subslice(bytes, current_position_in_fixed..end)?;
+
+                            #ssz::read_offset_unchecked(subslice)?
+                        }
+                    }
+                } else {
+                    0
+                };
+
+                let current_position_in_fixed = if #presence_ident {
+                    current_position_in_fixed + #size_expr.fixed_part()
+                } else {
+                    current_position_in_fixed
+                };
+            }
+        });
+
+        // Deserialize fields in reverse like in containers.
+        let variable_part_stmts = fields.iter().rev().map(|(_, member, ty)| {
+            let size_expr = optional_size_expr(ssz, ty);
+            let offset_ident = member.offset_ident();
+            let presence_ident = member.presence_ident();
+            let value_ident = member.value_ident();
+
+            quote! {
+                let (lowest_used_offset, #value_ident) = if #presence_ident {
+                    let (lowest_used_offset, end) = match #size_expr {
+                        #ssz::Size::Fixed { size } => (lowest_used_offset, #offset_ident + size),
+                        #ssz::Size::Variable { .. } => (#offset_ident, lowest_used_offset),
+                    };
+
+                    let subslice = #ssz::subslice(bytes, #offset_ident..end)?;
+                    let value = #ssz::SszRead::from_ssz_unchecked(context, subslice)?;
+
+                    (lowest_used_offset, ::core::option::Option::Some(value))
+                } else {
+                    (lowest_used_offset, ::core::option::Option::None)
+                };
+            }
+        });
+
+        let members = self.all_fields()?.map(|(member, ssz_field)| {
+            if ssz_field.skip {
+                let ty = &ssz_field.ty;
+                quote! { #member: <#ty as ::core::default::Default>::default(), }
+            } else {
+                let value_ident = member.value_ident();
+                quote! { #member: #value_ident, }
+            }
+        });
+
+        Ok(parse_quote! {
+            fn from_ssz_unchecked(context: &C, bytes: &[u8]) -> Result<Self, #ssz::ReadError> {
+                #init_stmts
+                #(#fixed_part_stmts)*
+                #(#variable_part_stmts)*
+
+                let expected = current_position_in_fixed;
+                let actual = lowest_used_offset;
+
+                if actual != expected {
+                    let error = #ssz::ReadError::ContainerFirstOffsetMismatch {
+                        expected,
+                        actual,
+                    };
+
+                    return ::core::result::Result::Err(error);
+                }
+
+                ::core::result::Result::Ok(Self { #(#members)* })
+            }
+        })
+    }
+
+    fn stable_container_write_variable_fn_impl(
+        &self,
+        ssz: &Path,
+        capacity: usize,
+    ) -> Result<ImplItemFn, Error> {
+        let active_fields_stmts =
+            self.stable_container_active_fields_stmts(active_fields_length(capacity))?;
+
+        let fields = self.stable_container_fields()?;
+
+        let fixed_part_stmts = fields.iter().map(|(_, member, ty)| {
+            let size_expr = optional_size_expr(ssz, ty);
+            let offset_ident = member.offset_ident();
+
+            quote! {
+                let #offset_ident = bytes.len();
+
+                if let ::core::option::Option::Some(value) = &self.#member {
+                    let length_with_fixed_part = #offset_ident + #size_expr.fixed_part();
+
+                    bytes.resize(length_with_fixed_part, 0);
+
+                    if let #ssz::Size::Fixed { size } = #size_expr {
+                        let subslice = &mut bytes[#offset_ident..length_with_fixed_part];
+                        #ssz::SszWrite::write_fixed(value, subslice);
+                    }
+                }
+            }
+        });
+
+        let variable_part_stmts = fields.iter().map(|(_, member, ty)| {
+            let size_expr = optional_size_expr(ssz, ty);
+            let offset_ident = member.offset_ident();
+
+            quote! {
+                if let (::core::option::Option::Some(value), #ssz::Size::Variable { .. }) =
+                    (&self.#member, #size_expr)
+                {
+                    let offset = bytes.len() - length_before;
+                    #ssz::write_offset(bytes, #offset_ident, offset)?;
+                    #ssz::SszWrite::write_variable(value, bytes)?;
+                }
+            }
+        });
+
+        Ok(parse_quote! {
+            fn write_variable(
+                &self,
+                bytes: &mut ::std::vec::Vec<u8>,
+            ) -> ::core::result::Result<(), #ssz::WriteError> {
+                #active_fields_stmts
+
+                bytes.extend_from_slice(&active_fields);
+
+                let length_before = bytes.len();
+
+                #(#fixed_part_stmts)*
+                #(#variable_part_stmts)*
+                ::core::result::Result::Ok(())
+            }
+        })
+    }
+
+    fn stable_container_hash_tree_root_fn_impl(
+        &self,
+        ssz: &Path,
+        capacity: usize,
+    ) -> Result<ImplItemFn, Error> {
+        // `active_fields` is padded to a whole number of chunks to simplify merkleization.
+        let chunk_count = active_fields_length(capacity).div_ceil(BYTES_PER_CHUNK);
+        let active_fields_stmts =
+            self.stable_container_active_fields_stmts(chunk_count * BYTES_PER_CHUNK)?;
+
+        let field_nodes = self
+            .stable_container_fields()?
+            .into_iter()
+            .map(|(_, member, _)| {
+                quote! {
+                    self.#member
+                        .as_ref()
+                        .map_or_else(#ssz::H256::zero, #ssz::SszHash::hash_tree_root)
+                }
+            })
+            .collect();
+
+        let active_fields_nodes = (0..chunk_count)
+            .map(|index| {
+                let start = index * BYTES_PER_CHUNK;
+                let end = start + BYTES_PER_CHUNK;
+                quote! { #ssz::H256::from_slice(&active_fields[#start..#end]) }
+            })
+            .collect();
+
+        let fields_root = merkleize(ssz, field_nodes, depth(capacity));
+        let active_fields_root = merkleize(ssz, active_fields_nodes, depth(chunk_count));
+
         Ok(parse_quote! {
             fn hash_tree_root(&self) -> #ssz::H256 {
-                #root
+                #active_fields_stmts
+                #ssz::mix_in_aux(#fields_root, #active_fields_root)
             }
         })
     }
 
+    fn stable_container_active_fields_stmts(&self, length: usize) -> Result<TokenStream, Error> {
+        let stmts = self
+            .stable_container_fields()?
+            .into_iter()
+            .map(|(index, member, _)| {
+                let (byte_index, bit_mask) = active_field_position(index);
+
+                quote! {
+                    if self.#member.is_some() {
+                        active_fields[#byte_index] |= #bit_mask;
+                    }
+                }
+            });
+
+        Ok(quote! {
+            let mut active_fields = [0_u8; #length];
+            #(#stmts)*
+        })
+    }
+
+    const fn is_union(&self) -> bool {
+        matches!(self.data, Data::Enum(_))
+    }
+
+    // Variants are returned with their selectors and fields.
+    // `None` is represented by a unit variant, which is only allowed in the first position.
+    fn union_variants(&self) -> Result<Vec<UnionVariant<'_>>, Error> {
+        let Data::Enum(variants) = &self.data else {
+            return Err(Error::new(Span::call_site(), "expected enum"));
+        };
+
+        if variants.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "SSZ unions with no variants are illegal",
+            ));
+        }
+
+        if variants.len() > MAX_UNION_VARIANTS {
+            return Err(Error::new(
+                Span::call_site(),
+                format!("SSZ unions cannot have more than {MAX_UNION_VARIANTS} variants"),
+            ));
+        }
+
+        variants
+            .iter()
+            .enumerate()
+            .map(|(position, variant)| {
+                let SszVariant { ident, fields } = variant;
+
+                let selector = u8::try_from(position).map_err(|error| {
+                    Error::new(ident.span(), error)
+                })?;
+
+                if fields.is_unit() {
+                    if position != 0 || variants.len() == 1 {
+                        return Err(Error::new(
+                            ident.span(),
+                            "only the first variant of an SSZ union with other variants can be None",
+                        ));
+                    }
+
+                    return Ok((selector, ident, None));
+                }
+
+                match fields.fields.as_slice() {
+                    [ssz_field] if fields.is_tuple() && !ssz_field.skip => {
+                        Ok((selector, ident, Some(ssz_field)))
+                    }
+                    _ => Err(Error::new(
+                        ident.span(),
+                        "SSZ union variants must have no fields or exactly one unnamed field",
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    // Fields are returned with their indices in the active fields bitvector and their inner types.
+    fn stable_container_fields(&self) -> Result<Vec<(usize, Member, &Type)>, Error> {
+        let fields = self
+            .unskipped_fields()?
+            .enumerate()
+            .map(|(index, (member, ssz_field))| {
+                let ty = ssz_field.optional_type().ok_or_else(|| {
+                    Error::new(
+                        Span::call_site(),
+                        format!("field {member:?} of stable container must be of type Option<T>"),
+                    )
+                })?;
+
+                Ok((index, member, ty))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let capacity = self.stable_container.unwrap_or_default();
+
+        if fields.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "struct has no unskipped fields",
+            ));
+        }
+
+        if fields.len() > capacity {
+            return Err(Error::new(
+                Span::call_site(),
+                format!("stable container has more than {capacity} fields"),
+            ));
+        }
+
+        Ok(fields)
+    }
+
     fn single_unskipped_field(&self) -> Result<(Member, &SszField), Error> {
         self.unskipped_fields()?.exactly_one().map_err(|_| {
             Error::new(
@@ -485,11 +989,10 @@ impl SszType {
 
     fn all_fields(&self) -> Result<impl Iterator<Item = (Member, &SszField)>, Error> {
         match &self.data {
-            // Enums could be used to represent SSZ unions,
-            // but SSZ unions are not used in the currently stable parts of `consensus-specs`.
+            // Unions are handled separately. This should only be reached through a bug in the macro.
             Data::Enum(_) => Err(Error::new(
                 Span::call_site(),
-                "SSZ unions are not implemented",
+                "enums are serialized as SSZ unions and have no fields",
             )),
             Data::Struct(fields) if fields.is_empty() => Err(Error::new(
                 Span::call_site(),
@@ -517,6 +1020,56 @@ impl Member {
     fn value_ident(&self) -> Ident {
         format_ident!("value_of_{}", self)
     }
+
+    fn presence_ident(&self) -> Ident {
+        format_ident!("is_present_{}", self)
+    }
+}
+
+// Builds a Merkle tree out of `nodes` with at least the specified depth.
+// Missing nodes are filled in with zero hashes. The tree is built when the macro is expanded.
+fn merkleize(ssz: &Path, mut nodes: Vec<TokenStream>, depth: usize) -> TokenStream {
+    let mut height = 0_usize;
+
+    loop {
+        if height >= depth && nodes.len() == 1 {
+            break nodes
+                .into_iter()
+                .exactly_one()
+                .expect("nodes should contain exactly 1 element");
+        }
+
+        let zero_hash_expr = quote! { #ssz::hashing::ZERO_HASHES[#height] };
+
+        nodes = nodes
+            .into_iter()
+            .chain(core::iter::once(zero_hash_expr))
+            .tuples()
+            .map(|(left, right)| quote! { #ssz::hashing::hash_256_256(#left, #right) })
+            .collect();
+
+        height += 1;
+    }
+}
+
+fn optional_size_expr(ssz: &Path, ty: &Type) -> TokenStream {
+    quote! { <#ty as #ssz::SszSize>::SIZE }
+}
+
+const fn active_fields_length(capacity: usize) -> usize {
+    capacity.div_ceil(BITS_PER_BYTE)
+}
+
+const fn active_field_position(index: usize) -> (usize, u8) {
+    (index / BITS_PER_BYTE, 1 << (index % BITS_PER_BYTE))
+}
+
+fn depth(leaf_count: usize) -> usize {
+    leaf_count
+        .next_power_of_two()
+        .trailing_zeros()
+        .try_into()
+        .expect("depth of Merkle tree should fit in usize")
 }
 
 fn context_type() -> TypeParam {
@@ -0,0 +1,11 @@
+use darling::{ast::Fields, FromVariant};
+use syn::Ident;
+
+use crate::ssz_field::SszField;
+
+#[derive(FromVariant)]
+#[darling(attributes(ssz))]
+pub struct SszVariant {
+    pub ident: Ident,
+    pub fields: Fields<SszField>,
+}
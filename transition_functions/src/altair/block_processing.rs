@@ -5,8 +5,8 @@ use helper_functions::{
     accessors::{
         attestation_epoch, get_attestation_participation_flags, get_attesting_indices,
         get_base_reward, get_base_reward_per_increment, get_beacon_proposer_index,
-        get_block_root_at_slot, index_of_public_key, initialize_shuffled_indices,
-        total_active_balance,
+        get_block_root_at_slot, get_sync_committee_participant_reward, index_of_public_key,
+        initialize_shuffled_indices,
     },
     altair::slash_validator,
     error::SignatureKind,
@@ -21,9 +21,7 @@ use typenum::Unsigned as _;
 use types::{
     altair::{
         beacon_state::BeaconState,
-        consts::{
-            PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, SYNC_REWARD_WEIGHT, WEIGHT_DENOMINATOR,
-        },
+        consts::{PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, WEIGHT_DENOMINATOR},
         containers::{BeaconBlock as AltairBeaconBlock, BeaconBlockBody, SyncAggregate},
     },
     config::Config,
@@ -440,11 +438,7 @@ pub fn process_sync_aggregate<P: Preset>(
     verify_sync_aggregate_signature(config, state, sync_aggregate, verifier)?;
 
     // > Compute participant and proposer rewards
-    let total_active_increments = total_active_balance(state) / P::EFFECTIVE_BALANCE_INCREMENT;
-    let total_base_rewards = get_base_reward_per_increment(state) * total_active_increments;
-    let max_participant_rewards = (total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR)
-        .div_typenum::<P::SlotsPerEpoch>();
-    let participant_reward = max_participant_rewards.div_typenum::<P::SyncCommitteeSize>();
+    let participant_reward = get_sync_committee_participant_reward(state);
     let proposer_reward =
         participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR.get() - PROPOSER_WEIGHT);
 
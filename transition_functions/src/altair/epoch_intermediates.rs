@@ -7,6 +7,7 @@ use helper_functions::{
     predicates::{is_active_validator, is_eligible_for_penalties, is_in_inactivity_leak},
 };
 use itertools::izip;
+use num_integer::Roots as _;
 use serde::Serialize;
 use static_assertions::assert_eq_size;
 use types::{
@@ -316,6 +317,52 @@ pub fn epoch_deltas<P: Preset, D: AltairEpochDeltas>(
         .collect()
 }
 
+/// Returns the deltas an eligible validator with `effective_balance` would receive for timely
+/// source, target and head votes.
+///
+/// `total_active_balance` must be the one from before epoch processing.
+/// `statistics` and `in_inactivity_leak` must be the ones `epoch_deltas` was called with.
+#[must_use]
+pub fn ideal_epoch_deltas<P: Preset>(
+    statistics: Statistics,
+    total_active_balance: Gwei,
+    in_inactivity_leak: bool,
+    effective_balance: Gwei,
+) -> EpochDeltasForReport {
+    // > Rewards are not given for participation during an inactivity leak
+    if in_inactivity_leak {
+        return EpochDeltasForReport::default();
+    }
+
+    let increment = P::EFFECTIVE_BALANCE_INCREMENT;
+    let base_reward_per_increment =
+        increment.get() * P::BASE_REWARD_FACTOR / total_active_balance.sqrt();
+    let base_reward = compute_base_reward::<P>(effective_balance, base_reward_per_increment);
+    let active_increments = total_active_balance / increment;
+
+    let participation_component_reward = |weight, participating_balance: Gwei| {
+        let reward_numerator = base_reward * weight * (participating_balance / increment);
+        let reward_denominator = active_increments * WEIGHT_DENOMINATOR.get();
+        reward_numerator / reward_denominator
+    };
+
+    EpochDeltasForReport {
+        source_reward: participation_component_reward(
+            TIMELY_SOURCE_WEIGHT,
+            statistics.previous_epoch_source_participating_balance,
+        ),
+        target_reward: participation_component_reward(
+            TIMELY_TARGET_WEIGHT,
+            statistics.previous_epoch_target_participating_balance,
+        ),
+        head_reward: participation_component_reward(
+            TIMELY_HEAD_WEIGHT,
+            statistics.previous_epoch_head_participating_balance,
+        ),
+        ..EpochDeltasForReport::default()
+    }
+}
+
 #[cfg(test)]
 mod spec_tests {
     use spec_test_utils::Case;
@@ -377,3 +424,68 @@ mod spec_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Mainnet;
+
+    use super::*;
+
+    // The square root of the total active balance is a power of 2 to keep expected values exact.
+    const TOTAL_ACTIVE_BALANCE: Gwei = 1 << 50;
+    const EFFECTIVE_BALANCE: Gwei = 32_000_000_000;
+
+    const fn statistics(participating_balance: Gwei) -> Statistics {
+        Statistics {
+            previous_epoch_source_participating_balance: participating_balance,
+            previous_epoch_target_participating_balance: participating_balance,
+            previous_epoch_head_participating_balance: participating_balance,
+            current_epoch_target_participating_balance: participating_balance,
+        }
+    }
+
+    #[test]
+    fn ideal_epoch_deltas_scale_with_participation() {
+        let full = ideal_epoch_deltas::<Mainnet>(
+            statistics(TOTAL_ACTIVE_BALANCE),
+            TOTAL_ACTIVE_BALANCE,
+            false,
+            EFFECTIVE_BALANCE,
+        );
+
+        let half = ideal_epoch_deltas::<Mainnet>(
+            statistics(TOTAL_ACTIVE_BALANCE / 2),
+            TOTAL_ACTIVE_BALANCE,
+            false,
+            EFFECTIVE_BALANCE,
+        );
+
+        assert_eq!(full.source_reward, 13349);
+        assert_eq!(full.target_reward, 24791);
+        assert_eq!(full.head_reward, 13349);
+
+        assert_eq!(half.source_reward, 6674);
+        assert_eq!(half.target_reward, 12395);
+        assert_eq!(half.head_reward, 6674);
+
+        for deltas in [full, half] {
+            assert_eq!(deltas.source_penalty, 0);
+            assert_eq!(deltas.target_penalty, 0);
+            assert_eq!(deltas.inactivity_penalty, 0);
+        }
+    }
+
+    #[test]
+    fn ideal_epoch_deltas_during_inactivity_leak_are_zero() {
+        let deltas = ideal_epoch_deltas::<Mainnet>(
+            statistics(TOTAL_ACTIVE_BALANCE),
+            TOTAL_ACTIVE_BALANCE,
+            true,
+            EFFECTIVE_BALANCE,
+        );
+
+        assert_eq!(deltas.source_reward, 0);
+        assert_eq!(deltas.target_reward, 0);
+        assert_eq!(deltas.head_reward, 0);
+    }
+}
@@ -78,7 +78,7 @@ pub mod unphased {
 
 pub mod phase0 {
     pub use epoch_intermediates::{
-        EpochDeltasForReport, Performance, PerformanceForReport,
+        ideal_epoch_deltas, EpochDeltasForReport, Performance, PerformanceForReport,
         Phase0ValidatorSummary as ValidatorSummary, Statistics, StatisticsForReport,
     };
     pub use epoch_processing::EpochReport;
@@ -102,7 +102,8 @@ pub mod phase0 {
 
 pub mod altair {
     pub use epoch_intermediates::{
-        AltairValidatorSummary as ValidatorSummary, EpochDeltasForReport, Statistics,
+        ideal_epoch_deltas, AltairValidatorSummary as ValidatorSummary, EpochDeltasForReport,
+        Statistics,
     };
     pub use epoch_processing::EpochReport;
 
@@ -474,16 +474,16 @@ impl Phase0EpochDeltas for EpochDeltasForTransition {
 
 #[derive(Clone, Copy, Default, Debug, Serialize)]
 pub struct EpochDeltasForReport {
-    source_reward: Gwei,
-    source_penalty: Gwei,
-    target_reward: Gwei,
-    target_penalty: Gwei,
-    head_reward: Gwei,
-    head_penalty: Gwei,
-    proposer_reward: Gwei,
-    inclusion_delay_reward: Gwei,
-    canceling_penalty: Gwei,
-    inactivity_penalty: Gwei,
+    pub source_reward: Gwei,
+    pub source_penalty: Gwei,
+    pub target_reward: Gwei,
+    pub target_penalty: Gwei,
+    pub head_reward: Gwei,
+    pub head_penalty: Gwei,
+    pub proposer_reward: Gwei,
+    pub inclusion_delay_reward: Gwei,
+    pub canceling_penalty: Gwei,
+    pub inactivity_penalty: Gwei,
 }
 
 impl EpochDeltas for EpochDeltasForReport {
@@ -765,6 +765,55 @@ pub fn epoch_deltas<P: Preset, S: Statistics, D: Phase0EpochDeltas>(
     Ok(deltas)
 }
 
+/// Returns the deltas an eligible validator with `effective_balance` would receive for an
+/// attestation matching the source, target and head that is included with the minimum delay.
+///
+/// `statistics` and `in_inactivity_leak` must be the ones `epoch_deltas` was called with.
+#[must_use]
+pub fn ideal_epoch_deltas<P: Preset>(
+    statistics: impl Statistics,
+    in_inactivity_leak: bool,
+    effective_balance: Gwei,
+) -> EpochDeltasForReport {
+    let increment = P::EFFECTIVE_BALANCE_INCREMENT;
+
+    let base_reward = effective_balance * P::BASE_REWARD_FACTOR
+        / statistics.current_epoch_active_balance().sqrt()
+        / BASE_REWARDS_PER_EPOCH;
+
+    let attestation_component_reward = |attesting_balance: Gwei| {
+        if in_inactivity_leak {
+            base_reward
+        } else {
+            base_reward * (attesting_balance / increment)
+                / (statistics.current_epoch_active_balance() / increment)
+        }
+    };
+
+    let proposer_reward = base_reward / P::PROPOSER_REWARD_QUOTIENT;
+
+    let canceling_penalty = if in_inactivity_leak {
+        BASE_REWARDS_PER_EPOCH.get() * base_reward - proposer_reward
+    } else {
+        0
+    };
+
+    EpochDeltasForReport {
+        source_reward: attestation_component_reward(
+            statistics.previous_epoch_source_attesting_balance(),
+        ),
+        target_reward: attestation_component_reward(
+            statistics.previous_epoch_target_attesting_balance(),
+        ),
+        head_reward: attestation_component_reward(
+            statistics.previous_epoch_head_attesting_balance(),
+        ),
+        inclusion_delay_reward: base_reward - proposer_reward,
+        canceling_penalty,
+        ..EpochDeltasForReport::default()
+    }
+}
+
 #[cfg(test)]
 mod spec_tests {
     use spec_test_utils::Case;
@@ -830,3 +879,58 @@ mod spec_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Mainnet;
+
+    use super::*;
+
+    // The square root of the active balance is a power of 2 to keep expected values exact.
+    const ACTIVE_BALANCE: Gwei = 1 << 50;
+    const EFFECTIVE_BALANCE: Gwei = 32_000_000_000;
+    const BASE_REWARD: Gwei = 15258;
+    const PROPOSER_REWARD: Gwei = BASE_REWARD / 8;
+
+    const fn statistics(attesting_balance: Gwei) -> StatisticsForReport {
+        StatisticsForReport {
+            previous_epoch_source_attesting_balance: attesting_balance,
+            previous_epoch_target_attesting_balance: attesting_balance,
+            previous_epoch_head_attesting_balance: attesting_balance,
+            current_epoch_active_balance: ACTIVE_BALANCE,
+            current_epoch_target_attesting_balance: attesting_balance,
+        }
+    }
+
+    #[test]
+    fn ideal_epoch_deltas_scale_with_participation() {
+        let full =
+            ideal_epoch_deltas::<Mainnet>(statistics(ACTIVE_BALANCE), false, EFFECTIVE_BALANCE);
+        let half =
+            ideal_epoch_deltas::<Mainnet>(statistics(ACTIVE_BALANCE / 2), false, EFFECTIVE_BALANCE);
+
+        assert_eq!(full.source_reward, BASE_REWARD);
+        assert_eq!(full.target_reward, BASE_REWARD);
+        assert_eq!(full.head_reward, BASE_REWARD);
+        assert_eq!(full.inclusion_delay_reward, BASE_REWARD - PROPOSER_REWARD);
+        assert_eq!(full.canceling_penalty, 0);
+
+        assert_eq!(half.source_reward, 7628);
+        assert_eq!(half.target_reward, 7628);
+        assert_eq!(half.head_reward, 7628);
+        assert_eq!(half.inclusion_delay_reward, BASE_REWARD - PROPOSER_REWARD);
+        assert_eq!(half.canceling_penalty, 0);
+    }
+
+    #[test]
+    fn ideal_epoch_deltas_during_inactivity_leak_are_canceled_out() {
+        let deltas =
+            ideal_epoch_deltas::<Mainnet>(statistics(ACTIVE_BALANCE / 2), true, EFFECTIVE_BALANCE);
+
+        assert_eq!(deltas.source_reward, BASE_REWARD);
+        assert_eq!(deltas.target_reward, BASE_REWARD);
+        assert_eq!(deltas.head_reward, BASE_REWARD);
+        assert_eq!(deltas.inclusion_delay_reward, BASE_REWARD - PROPOSER_REWARD);
+        assert_eq!(deltas.canceling_penalty, 4 * BASE_REWARD - PROPOSER_REWARD);
+    }
+}
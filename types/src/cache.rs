@@ -29,6 +29,7 @@ pub struct Cache {
     pub proposer_index: OnceCell<ValidatorIndex>,
     pub active_validator_indices_ordered: EnumMap<RelativeEpoch, OnceCell<PackedIndices>>,
     pub active_validator_indices_shuffled: EnumMap<RelativeEpoch, OnceCell<PackedIndices>>,
+    pub committee_boundaries: EnumMap<RelativeEpoch, OnceCell<CommitteeBoundaries>>,
     pub total_active_balance: EnumMap<RelativeEpoch, OnceCell<NonZeroGwei>>,
     pub validator_indices: OnceCell<HashMap<PublicKeyBytes, ValidatorIndex>>,
 }
@@ -41,18 +42,57 @@ impl Cache {
     pub fn advance_epoch(&mut self) {
         let ordered = &mut self.active_validator_indices_ordered;
         let shuffled = &mut self.active_validator_indices_shuffled;
+        let boundaries = &mut self.committee_boundaries;
         let balance = &mut self.total_active_balance;
 
         ordered[RelativeEpoch::Previous] = core::mem::take(&mut ordered[RelativeEpoch::Current]);
         shuffled[RelativeEpoch::Previous] = core::mem::take(&mut shuffled[RelativeEpoch::Current]);
+        boundaries[RelativeEpoch::Previous] =
+            core::mem::take(&mut boundaries[RelativeEpoch::Current]);
         balance[RelativeEpoch::Previous] = core::mem::take(&mut balance[RelativeEpoch::Current]);
 
         ordered[RelativeEpoch::Current] = core::mem::take(&mut ordered[RelativeEpoch::Next]);
         shuffled[RelativeEpoch::Current] = core::mem::take(&mut shuffled[RelativeEpoch::Next]);
+        boundaries[RelativeEpoch::Current] = core::mem::take(&mut boundaries[RelativeEpoch::Next]);
         balance[RelativeEpoch::Current] = core::mem::take(&mut balance[RelativeEpoch::Next]);
     }
 }
 
+/// Positions of all beacon committees in an epoch.
+///
+/// The committee with index `i` counting from the start of the epoch consists of the validators at
+/// positions `boundaries[i]..boundaries[i + 1]` in `Cache.active_validator_indices_shuffled`.
+#[derive(Clone, Debug)]
+pub struct CommitteeBoundaries {
+    committees_per_slot: u64,
+    boundaries: Arc<[usize]>,
+}
+
+impl CommitteeBoundaries {
+    #[must_use]
+    pub const fn new(committees_per_slot: u64, boundaries: Arc<[usize]>) -> Self {
+        Self {
+            committees_per_slot,
+            boundaries,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn committees_per_slot(&self) -> u64 {
+        self.committees_per_slot
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn committee(&self, index_in_epoch: u64) -> Option<Range<usize>> {
+        let index = usize::try_from(index_in_epoch).ok()?;
+        let start = *self.boundaries.get(index)?;
+        let end = *self.boundaries.get(index + 1)?;
+        Some(start..end)
+    }
+}
+
 // Possible optimization: store the discriminant in alignment bits.
 // `triomphe` provides an `ArcUnion` type, but it only supports 2 variants.
 // `elysees` (a fork of `triomphe`) used to support 4 variants, but it does not as of version 0.3.0.
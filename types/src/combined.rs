@@ -663,6 +663,16 @@ impl<P: Preset> SszRead<Config> for SignedBlindedBeaconBlock<P> {
     }
 }
 
+impl<P: Preset> SszWrite for SignedBlindedBeaconBlock<P> {
+    fn write_variable(&self, bytes: &mut Vec<u8>) -> Result<(), WriteError> {
+        match self {
+            Self::Bellatrix(block) => block.write_variable(bytes),
+            Self::Capella(block) => block.write_variable(bytes),
+            Self::Deneb(block) => block.write_variable(bytes),
+        }
+    }
+}
+
 impl<P: Preset> SignedBlindedBeaconBlock<P> {
     pub fn split(self) -> (BlindedBeaconBlock<P>, SignatureBytes) {
         match self {
@@ -830,6 +840,26 @@ pub enum ExecutionPayload<P: Preset> {
     Deneb(DenebExecutionPayload<P>),
 }
 
+impl<P: Preset> SszSize for ExecutionPayload<P> {
+    // The const parameter should be `Self::VARIANT_COUNT`, but `Self` refers to a generic type.
+    // Type parameters cannot be used in `const` contexts until `generic_const_exprs` is stable.
+    const SIZE: Size = Size::for_untagged_union::<{ Phase::CARDINALITY - 2 }>([
+        BellatrixExecutionPayload::<P>::SIZE,
+        CapellaExecutionPayload::<P>::SIZE,
+        DenebExecutionPayload::<P>::SIZE,
+    ]);
+}
+
+impl<P: Preset> SszWrite for ExecutionPayload<P> {
+    fn write_variable(&self, bytes: &mut Vec<u8>) -> Result<(), WriteError> {
+        match self {
+            Self::Bellatrix(payload) => payload.write_variable(bytes),
+            Self::Capella(payload) => payload.write_variable(bytes),
+            Self::Deneb(payload) => payload.write_variable(bytes),
+        }
+    }
+}
+
 impl<P: Preset> SszHash for ExecutionPayload<P> {
     type PackingFactor = U1;
 
@@ -1050,6 +1080,42 @@ mod spec_tests {
         assert_eq!(value.phase(), Phase::test_phase);
     }
 
+    #[duplicate_item(
+        glob                                                                              function_name                                preset    test_phase;
+        ["consensus-spec-tests/tests/mainnet/phase0/ssz_static/SignedBeaconBlock/*/*"]    [phase0_mainnet_signed_beacon_block_json]    [Mainnet] [Phase0];
+        ["consensus-spec-tests/tests/minimal/phase0/ssz_static/SignedBeaconBlock/*/*"]    [phase0_minimal_signed_beacon_block_json]    [Minimal] [Phase0];
+        ["consensus-spec-tests/tests/mainnet/altair/ssz_static/SignedBeaconBlock/*/*"]    [altair_mainnet_signed_beacon_block_json]    [Mainnet] [Altair];
+        ["consensus-spec-tests/tests/minimal/altair/ssz_static/SignedBeaconBlock/*/*"]    [altair_minimal_signed_beacon_block_json]    [Minimal] [Altair];
+        ["consensus-spec-tests/tests/mainnet/bellatrix/ssz_static/SignedBeaconBlock/*/*"] [bellatrix_mainnet_signed_beacon_block_json] [Mainnet] [Bellatrix];
+        ["consensus-spec-tests/tests/minimal/bellatrix/ssz_static/SignedBeaconBlock/*/*"] [bellatrix_minimal_signed_beacon_block_json] [Minimal] [Bellatrix];
+        ["consensus-spec-tests/tests/mainnet/capella/ssz_static/SignedBeaconBlock/*/*"]   [capella_mainnet_signed_beacon_block_json]   [Mainnet] [Capella];
+        ["consensus-spec-tests/tests/minimal/capella/ssz_static/SignedBeaconBlock/*/*"]   [capella_minimal_signed_beacon_block_json]   [Minimal] [Capella];
+        ["consensus-spec-tests/tests/mainnet/deneb/ssz_static/SignedBeaconBlock/*/*"]     [deneb_mainnet_signed_beacon_block_json]     [Mainnet] [Deneb];
+        ["consensus-spec-tests/tests/minimal/deneb/ssz_static/SignedBeaconBlock/*/*"]     [deneb_minimal_signed_beacon_block_json]     [Minimal] [Deneb];
+    )]
+    #[test_resources(glob)]
+    fn function_name(case: Case) {
+        let config = preset::default_config().start_and_stay_in(Phase::test_phase);
+        let ssz_bytes = case.bytes("serialized.ssz_snappy");
+
+        let block = SignedBeaconBlock::<preset>::from_ssz(&config, ssz_bytes.as_slice())
+            .expect("SSZ decoding should succeed");
+
+        let json_bytes = serde_json::to_vec(&block).expect("serialization to JSON should succeed");
+
+        // `SignedBeaconBlock` is untagged. Blocks from different phases must not be confused.
+        let json_block = serde_json::from_slice::<SignedBeaconBlock<preset>>(json_bytes.as_slice())
+            .expect("deserialization from JSON should succeed");
+
+        assert_eq!(json_block, block);
+        assert_eq!(json_block.phase(), Phase::test_phase);
+
+        assert_eq!(
+            json_block.to_ssz().expect("SSZ encoding should succeed"),
+            ssz_bytes,
+        );
+    }
+
     #[duplicate_item(
         glob                                                                                        function_name                         combined_type                 preset    phase;
         ["consensus-spec-tests/tests/mainnet/altair/ssz_static/LightClientFinalityUpdate/*/*"]      [altair_mainnet_finality_update]      [LightClientFinalityUpdate]   [Mainnet] [Altair];
@@ -127,7 +127,7 @@ pub struct Config {
     #[serde(with = "serde_utils::string_or_native")]
     pub min_epochs_for_blob_sidecars_requests: u64,
     #[serde(with = "serde_utils::string_or_native")]
-    pub blob_sidecar_subnet_count: u64,
+    pub blob_sidecar_subnet_count: NonZeroU64,
 
     // Transition
     pub terminal_block_hash: ExecutionBlockHash,
@@ -210,7 +210,7 @@ impl Default for Config {
             max_request_blocks_deneb: 128,
             max_request_blob_sidecars: 768,
             min_epochs_for_blob_sidecars_requests: 4096,
-            blob_sidecar_subnet_count: 6,
+            blob_sidecar_subnet_count: nonzero!(6_u64),
 
             // Transition
             terminal_block_hash: ExecutionBlockHash::zero(),
@@ -753,6 +753,31 @@ impl Config {
         }
     }
 
+    /// Number of blob sidecar subnets in `phase`.
+    ///
+    /// Phases before Deneb have no blob sidecars. The Deneb value is returned for them anyway.
+    /// Phases that change the maximum number of blobs per block are expected to add their own
+    /// configuration variables, as is done in the consensus specs.
+    #[inline]
+    #[must_use]
+    pub const fn blob_sidecar_subnet_count(&self, phase: Phase) -> NonZeroU64 {
+        match phase {
+            Phase::Phase0 | Phase::Altair | Phase::Bellatrix | Phase::Capella | Phase::Deneb => {
+                self.blob_sidecar_subnet_count
+            }
+        }
+    }
+
+    /// [`MIN_EPOCHS_FOR_BLOCK_REQUESTS`](https://github.com/ethereum/consensus-specs/blob/v1.4.0/specs/phase0/p2p-interface.md#configuration)
+    ///
+    /// Blocks in this many epochs before the current one must be served to peers.
+    /// The consensus specs derive it from other configuration variables.
+    #[inline]
+    #[must_use]
+    pub const fn min_epochs_for_block_requests(&self) -> u64 {
+        self.min_validator_withdrawability_delay + self.churn_limit_quotient.get() / 2
+    }
+
     #[must_use]
     pub fn fork_slot<P: Preset>(&self, phase: Phase) -> Toption<Slot> {
         self.fork_epoch(phase)
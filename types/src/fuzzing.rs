@@ -0,0 +1,310 @@
+//! Entry points for fuzzing decoding of gossip and Req/Resp messages.
+//!
+//! This module is only compiled with `--cfg fuzzing`, which `cargo fuzz` passes automatically.
+//! The fuzz targets that call it are in the `fuzz` directory at the root of the repository.
+//!
+//! A fuzzer input consists of a byte selecting the message type followed by the message in one of
+//! the encodings used on the wire:
+//! - [`decode_gossip_message`] takes plain SSZ.
+//! - [`decode_gossip_payload`] takes SSZ compressed with raw Snappy, as in gossip messages.
+//! - [`decode_req_resp_payload`] takes the length of the SSZ encoding as an unsigned varint
+//!   followed by the SSZ encoding compressed with framed Snappy, as in Req/Resp messages.
+//!   Result and context bytes of response chunks are not included.
+//!
+//! The Snappy and varint decoding mirrors the codecs in `eth2_libp2p`, including their size limits.
+//! [`seed`], [`seed_gossip_payload`] and [`seed_req_resp_payload`] turn captured messages into
+//! inputs in the corresponding formats.
+
+use std::io::{Read as _, Write as _};
+
+use enum_iterator::Sequence;
+use snap::{read::FrameDecoder, write::FrameEncoder};
+use ssz::{SszRead, SszWrite};
+
+use crate::{
+    altair::containers::{
+        SignedBeaconBlock as AltairSignedBeaconBlock, SignedContributionAndProof,
+        SyncCommitteeMessage,
+    },
+    bellatrix::containers::SignedBeaconBlock as BellatrixSignedBeaconBlock,
+    capella::containers::{
+        SignedBeaconBlock as CapellaSignedBeaconBlock, SignedBlsToExecutionChange,
+    },
+    combined::SignedBeaconBlock,
+    config::Config,
+    deneb::containers::{BlobSidecar, SignedBeaconBlock as DenebSignedBeaconBlock},
+    nonstandard::Phase,
+    phase0::containers::{
+        Attestation, AttesterSlashing, ProposerSlashing, SignedAggregateAndProof,
+        SignedBeaconBlock as Phase0SignedBeaconBlock, SignedVoluntaryExit,
+    },
+    preset::Preset,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Sequence)]
+pub enum GossipMessageKind {
+    // Decoded the way blocks from storage are, with the phase determined by the slot.
+    SignedBeaconBlock,
+    Phase0SignedBeaconBlock,
+    AltairSignedBeaconBlock,
+    BellatrixSignedBeaconBlock,
+    CapellaSignedBeaconBlock,
+    DenebSignedBeaconBlock,
+    BlobSidecar,
+    SignedAggregateAndProof,
+    Attestation,
+    SignedVoluntaryExit,
+    ProposerSlashing,
+    AttesterSlashing,
+    SignedContributionAndProof,
+    SyncCommitteeMessage,
+    SignedBlsToExecutionChange,
+}
+
+impl GossipMessageKind {
+    /// Returns the kind of messages in the `ssz_static` test cases for `container` in `phase`.
+    #[must_use]
+    pub fn of_spec_test(phase: Phase, container: &str) -> Option<Self> {
+        let kind = match (phase, container) {
+            (Phase::Phase0, "SignedBeaconBlock") => Self::Phase0SignedBeaconBlock,
+            (Phase::Altair, "SignedBeaconBlock") => Self::AltairSignedBeaconBlock,
+            (Phase::Bellatrix, "SignedBeaconBlock") => Self::BellatrixSignedBeaconBlock,
+            (Phase::Capella, "SignedBeaconBlock") => Self::CapellaSignedBeaconBlock,
+            (Phase::Deneb, "SignedBeaconBlock") => Self::DenebSignedBeaconBlock,
+            (_, "BlobSidecar") => Self::BlobSidecar,
+            (_, "SignedAggregateAndProof") => Self::SignedAggregateAndProof,
+            (_, "Attestation") => Self::Attestation,
+            (_, "SignedVoluntaryExit") => Self::SignedVoluntaryExit,
+            (_, "ProposerSlashing") => Self::ProposerSlashing,
+            (_, "AttesterSlashing") => Self::AttesterSlashing,
+            (_, "SignedContributionAndProof") => Self::SignedContributionAndProof,
+            (_, "SyncCommitteeMessage") => Self::SyncCommitteeMessage,
+            (_, "SignedBlsToExecutionChange") => Self::SignedBlsToExecutionChange,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    #[must_use]
+    pub fn from_selector(selector: u8) -> Self {
+        let index = usize::from(selector) % enum_iterator::cardinality::<Self>();
+
+        enum_iterator::all::<Self>()
+            .nth(index)
+            .expect("index should be less than the number of variants")
+    }
+
+    #[must_use]
+    pub fn selector(self) -> u8 {
+        enum_iterator::all::<Self>()
+            .position(|kind| kind == self)
+            .and_then(|index| index.try_into().ok())
+            .expect("number of variants should fit in u8")
+    }
+}
+
+/// Decodes `data` as the message selected by its first byte.
+///
+/// # Panics
+///
+/// Panics if a message that decodes successfully is not encoded back into the same bytes.
+pub fn decode_gossip_message<P: Preset>(config: &Config, data: &[u8]) {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+
+    decode_ssz::<P>(config, GossipMessageKind::from_selector(*selector), bytes);
+}
+
+/// Decompresses `data` the way gossip messages are and decodes the result like
+/// [`decode_gossip_message`].
+///
+/// # Panics
+///
+/// Panics if a message that decodes successfully is not encoded back into the same bytes.
+pub fn decode_gossip_payload<P: Preset>(config: &Config, data: &[u8]) {
+    let Some((selector, compressed)) = data.split_first() else {
+        return;
+    };
+
+    // Check the length before decompressing to avoid allocating for oversized messages.
+    let Ok(length) = snap::raw::decompress_len(compressed) else {
+        return;
+    };
+
+    if length > config.gossip_max_size {
+        return;
+    }
+
+    let Ok(bytes) = snap::raw::Decoder::new().decompress_vec(compressed) else {
+        return;
+    };
+
+    decode_ssz::<P>(config, GossipMessageKind::from_selector(*selector), &bytes);
+}
+
+/// Decodes the length prefix and decompresses `data` the way Req/Resp messages are and decodes
+/// the result like [`decode_gossip_message`].
+///
+/// # Panics
+///
+/// Panics if a message that decodes successfully is not encoded back into the same bytes.
+pub fn decode_req_resp_payload<P: Preset>(config: &Config, data: &[u8]) {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+
+    let Some((length, frames)) = read_varint(bytes) else {
+        return;
+    };
+
+    if length > config.max_chunk_size {
+        return;
+    }
+
+    let Ok(limit) = u64::try_from(length + 1) else {
+        return;
+    };
+
+    // Read one byte more than the prefix allows to detect payloads longer than it.
+    let mut bytes = vec![];
+
+    if FrameDecoder::new(frames)
+        .take(limit)
+        .read_to_end(&mut bytes)
+        .is_err()
+        || bytes.len() != length
+    {
+        return;
+    }
+
+    decode_ssz::<P>(config, GossipMessageKind::from_selector(*selector), &bytes);
+}
+
+fn decode_ssz<P: Preset>(config: &Config, kind: GossipMessageKind, bytes: &[u8]) {
+    match kind {
+        GossipMessageKind::SignedBeaconBlock => {
+            assert_survives_round_trip::<_, SignedBeaconBlock<P>>(config, bytes)
+        }
+        GossipMessageKind::Phase0SignedBeaconBlock => {
+            assert_survives_round_trip::<_, Phase0SignedBeaconBlock<P>>(&(), bytes)
+        }
+        GossipMessageKind::AltairSignedBeaconBlock => {
+            assert_survives_round_trip::<_, AltairSignedBeaconBlock<P>>(&(), bytes)
+        }
+        GossipMessageKind::BellatrixSignedBeaconBlock => {
+            assert_survives_round_trip::<_, BellatrixSignedBeaconBlock<P>>(&(), bytes)
+        }
+        GossipMessageKind::CapellaSignedBeaconBlock => {
+            assert_survives_round_trip::<_, CapellaSignedBeaconBlock<P>>(&(), bytes)
+        }
+        GossipMessageKind::DenebSignedBeaconBlock => {
+            assert_survives_round_trip::<_, DenebSignedBeaconBlock<P>>(&(), bytes)
+        }
+        GossipMessageKind::BlobSidecar => {
+            assert_survives_round_trip::<_, BlobSidecar<P>>(&(), bytes)
+        }
+        GossipMessageKind::SignedAggregateAndProof => {
+            assert_survives_round_trip::<_, SignedAggregateAndProof<P>>(&(), bytes)
+        }
+        GossipMessageKind::Attestation => {
+            assert_survives_round_trip::<_, Attestation<P>>(&(), bytes)
+        }
+        GossipMessageKind::SignedVoluntaryExit => {
+            assert_survives_round_trip::<_, SignedVoluntaryExit>(&(), bytes)
+        }
+        GossipMessageKind::ProposerSlashing => {
+            assert_survives_round_trip::<_, ProposerSlashing>(&(), bytes)
+        }
+        GossipMessageKind::AttesterSlashing => {
+            assert_survives_round_trip::<_, AttesterSlashing<P>>(&(), bytes)
+        }
+        GossipMessageKind::SignedContributionAndProof => {
+            assert_survives_round_trip::<_, SignedContributionAndProof<P>>(&(), bytes)
+        }
+        GossipMessageKind::SyncCommitteeMessage => {
+            assert_survives_round_trip::<_, SyncCommitteeMessage>(&(), bytes)
+        }
+        GossipMessageKind::SignedBlsToExecutionChange => {
+            assert_survives_round_trip::<_, SignedBlsToExecutionChange>(&(), bytes)
+        }
+    }
+}
+
+/// Prepends the selector of `kind` to `ssz_bytes` to make an input for [`decode_gossip_message`].
+#[must_use]
+pub fn seed(kind: GossipMessageKind, ssz_bytes: &[u8]) -> Vec<u8> {
+    core::iter::once(kind.selector())
+        .chain(ssz_bytes.iter().copied())
+        .collect()
+}
+
+/// Makes an input for [`decode_gossip_payload`] out of the SSZ encoding of a message.
+///
+/// # Errors
+///
+/// Returns an error if compression fails.
+pub fn seed_gossip_payload(kind: GossipMessageKind, ssz_bytes: &[u8]) -> snap::Result<Vec<u8>> {
+    let compressed = snap::raw::Encoder::new().compress_vec(ssz_bytes)?;
+    Ok(seed(kind, &compressed))
+}
+
+/// Makes an input for [`decode_req_resp_payload`] out of the SSZ encoding of a message.
+///
+/// # Errors
+///
+/// Returns an error if compression fails.
+pub fn seed_req_resp_payload(
+    kind: GossipMessageKind,
+    ssz_bytes: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    let mut data = vec![kind.selector()];
+
+    write_varint(&mut data, ssz_bytes.len());
+
+    let mut encoder = FrameEncoder::new(data);
+    encoder.write_all(ssz_bytes)?;
+    encoder.into_inner().map_err(|error| error.into_error())
+}
+
+// Unsigned LEB128 as used by `unsigned-varint`. Encodings longer than 10 bytes are rejected.
+fn read_varint(bytes: &[u8]) -> Option<(usize, &[u8])> {
+    let mut value = 0_u64;
+
+    for (index, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+
+        if byte & 0x80 == 0 {
+            return Some((value.try_into().ok()?, &bytes[index + 1..]));
+        }
+    }
+
+    None
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push(u8::try_from(value & 0x7f).expect("value is masked to 7 bits") | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(u8::try_from(value).expect("loop ends when value fits in 7 bits"));
+}
+
+// Decoding is allowed to fail, but anything that decodes must be encoded back into the same bytes.
+// Accepting multiple encodings of the same message could let nodes disagree on its validity.
+fn assert_survives_round_trip<C, T>(context: &C, bytes: &[u8])
+where
+    T: SszRead<C> + SszWrite,
+{
+    let Ok(value) = T::from_ssz(context, bytes) else {
+        return;
+    };
+
+    let encoded = value
+        .to_ssz()
+        .expect("value decoded from SSZ should be encodable");
+
+    assert_eq!(encoded, bytes);
+}
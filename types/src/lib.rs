@@ -64,7 +64,10 @@ pub mod deneb {
     mod spec_tests;
 }
 
-mod collections;
+pub mod collections;
+
+#[cfg(fuzzing)]
+pub mod fuzzing;
 
 mod unphased {
     pub mod consts;
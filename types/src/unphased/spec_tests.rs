@@ -1,11 +1,16 @@
-use core::fmt::Debug;
+use core::fmt::{Debug, Write as _};
 
+use quickcheck::{Arbitrary as _, Gen};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 use spec_test_utils::Case;
 use ssz::{SszHash, SszReadDefault, SszWrite};
 
 use crate::phase0::primitives::H256;
 
+// Each randomized value is serialized several times. `BeaconState`s make this slow.
+const RANDOMIZED_VALUES_PER_CASE: usize = 4;
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 struct Roots {
@@ -13,6 +18,10 @@ struct Roots {
 }
 
 // This is nearly the same as `ssz::spec_tests::run_valid_case`, but reuse isn't worth the trouble.
+//
+// `ssz_static` tests contain randomized values of every container in every phase,
+// so they also serve as round-trip tests for the JSON representation used by the Beacon API.
+// Values in them are used as templates for more random values of the same shape.
 pub fn run_spec_test_case<T>(case: Case)
 where
     T: SszReadDefault + SszWrite + SszHash + DeserializeOwned + Serialize + PartialEq + Debug,
@@ -23,7 +32,9 @@ where
 
     assert_matches_consensus_specs(expected_ssz_bytes.as_slice(), &yaml_value, root);
     serde_utils::assert_json_contains_no_numbers(&yaml_value);
+    assert_survives_json_round_trip(&yaml_value);
     assert_survives_bincode_round_trip(&yaml_value);
+    assert_randomized_values_survive_round_trips(&yaml_value);
 }
 
 fn assert_matches_consensus_specs<T>(expected_ssz_bytes: &[u8], yaml_value: &T, root: H256)
@@ -38,6 +49,112 @@ where
     assert_eq!(yaml_value.hash_tree_root(), root);
 }
 
+// This catches asymmetric `serde` attributes, such as a field renamed only when serializing.
+fn assert_survives_json_round_trip<T>(input: &T)
+where
+    T: DeserializeOwned + Serialize + PartialEq + Debug,
+{
+    let json_bytes = serde_json::to_vec(input).expect("serialization to JSON should succeed");
+    let output = serde_json::from_slice::<T>(json_bytes.as_slice())
+        .expect("deserialization from JSON should succeed");
+
+    assert_eq!(&output, input);
+}
+
+// Checks that values survive SSZ → JSON → SSZ round trips.
+// This catches JSON representations that lose information, such as fields left out when empty.
+fn assert_randomized_values_survive_round_trips<T>(template: &T)
+where
+    T: SszReadDefault + SszWrite + DeserializeOwned + Serialize + PartialEq + Debug,
+{
+    let template = serde_json::to_value(template).expect("serialization to JSON should succeed");
+    // The size of a `Gen` only affects collections. None are generated here.
+    let mut gen = Gen::new(0);
+
+    for _ in 0..RANDOMIZED_VALUES_PER_CASE {
+        let mut json_value = template.clone();
+
+        randomize_leaves(&mut json_value, &mut gen);
+
+        let randomized_value = serde_json::from_value::<T>(json_value.clone())
+            .unwrap_or_else(|error| panic!("randomized value {json_value} is invalid: {error}"));
+
+        let ssz_bytes = randomized_value
+            .to_ssz()
+            .expect("SSZ encoding should succeed");
+
+        let ssz_value =
+            T::from_ssz_default(ssz_bytes.as_slice()).expect("SSZ decoding should succeed");
+
+        let json_bytes =
+            serde_json::to_vec(&ssz_value).expect("serialization to JSON should succeed");
+
+        let json_value_after_round_trip = serde_json::from_slice::<Value>(json_bytes.as_slice())
+            .expect("JSON produced by serde_json should be valid");
+
+        let json_ssz_value = serde_json::from_slice::<T>(json_bytes.as_slice())
+            .expect("deserialization from JSON should succeed");
+
+        assert_eq!(ssz_value, randomized_value);
+        assert_eq!(json_value_after_round_trip, json_value);
+        assert_eq!(json_ssz_value, randomized_value);
+
+        assert_eq!(
+            json_ssz_value
+                .to_ssz()
+                .expect("SSZ encoding should succeed"),
+            ssz_bytes,
+        );
+    }
+}
+
+// Randomization preserves the length of every string and array.
+// Values of every container remain valid without generators written for each of them:
+// - Integers are replaced with integers of the same bit length or less.
+//   This keeps them within the range of types like `ParticipationFlags`.
+// - Hexadecimal strings keep their last byte.
+//   It contains the length bit of bitlists and the padding of bitvectors.
+fn randomize_leaves(value: &mut Value, gen: &mut Gen) {
+    match value {
+        Value::Bool(boolean) => *boolean = bool::arbitrary(gen),
+        Value::String(string) => {
+            if let Some(digits) = string.strip_prefix("0x") {
+                let randomized_bytes = (digits.len() / 2).saturating_sub(1);
+                let last_digits = digits
+                    .chars()
+                    .skip(randomized_bytes * 2)
+                    .collect::<String>();
+                let mut randomized = String::from("0x");
+
+                for _ in 0..randomized_bytes {
+                    write!(randomized, "{:02x}", u8::arbitrary(gen))
+                        .expect("writing to a String should succeed");
+                }
+
+                randomized.push_str(last_digits.as_str());
+
+                *string = randomized;
+            } else if let Ok(integer) = string.parse::<u64>() {
+                let mask = u64::MAX
+                    .checked_shr(integer.leading_zeros())
+                    .unwrap_or_default();
+                *string = (u64::arbitrary(gen) & mask).to_string();
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                randomize_leaves(value, gen);
+            }
+        }
+        Value::Object(fields) => {
+            for value in fields.values_mut() {
+                randomize_leaves(value, gen);
+            }
+        }
+        Value::Null | Value::Number(_) => {}
+    }
+}
+
 fn assert_survives_bincode_round_trip<T>(input: &T)
 where
     T: DeserializeOwned + Serialize + PartialEq + Debug,
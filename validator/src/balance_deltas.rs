@@ -0,0 +1,178 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use bls::PublicKeyBytes;
+use helper_functions::accessors;
+use itertools::izip;
+use serde::Serialize;
+use ssz::Ssz;
+use std_ext::ArcExt as _;
+use transition_functions::{
+    altair::EpochReport as AltairEpochReport,
+    combined::{self, EpochReport},
+    phase0::EpochReport as Phase0EpochReport,
+    unphased::EpochDeltas,
+};
+use types::{
+    combined::BeaconState,
+    config::Config,
+    phase0::primitives::{Epoch, Gwei, ValidatorIndex},
+    preset::Preset,
+};
+
+use crate::sync_committee_performance::SyncCommitteeDeltas;
+
+/// Balance changes of an own validator in a single epoch, split by their cause.
+///
+/// Attestation deltas include inactivity penalties. They are the ones applied during processing
+/// of `epoch`, which reward attestations from the epoch before it in Phase 0. Sync committee
+/// deltas are taken from sync aggregates in canonical blocks of `epoch`.
+#[derive(PartialEq, Eq, Debug, Serialize, Ssz)]
+#[ssz(derive_hash = false)]
+pub struct BalanceDeltasEvent {
+    #[serde(with = "serde_utils::string_or_native")]
+    pub epoch: Epoch,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub attestation_rewards: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub attestation_penalties: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub sync_committee_rewards: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub sync_committee_penalties: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub slashing_penalty: Gwei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub post_balance: Gwei,
+}
+
+/// Runs epoch processing on `state` to compute balance deltas of own validators.
+///
+/// `state` must be in the last slot of the epoch to report on.
+pub fn balance_deltas<P: Preset>(
+    config: &Config,
+    mut state: Arc<BeaconState<P>>,
+    own_public_keys: &HashSet<PublicKeyBytes>,
+    sync_committee_deltas: &HashMap<ValidatorIndex, SyncCommitteeDeltas>,
+) -> Result<Vec<BalanceDeltasEvent>> {
+    let epoch = accessors::get_current_epoch(&*state);
+
+    let own_validator_indices = own_public_keys
+        .iter()
+        .filter_map(|pubkey| accessors::index_of_public_key(&*state, *pubkey))
+        .collect::<HashSet<_>>();
+
+    if own_validator_indices.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let events = match combined::epoch_report(config, state.make_mut())? {
+        EpochReport::Phase0(Phase0EpochReport {
+            epoch_deltas,
+            slashing_penalties,
+            post_balances,
+            ..
+        }) => events(
+            epoch,
+            &own_validator_indices,
+            epoch_deltas,
+            &slashing_penalties,
+            post_balances,
+            sync_committee_deltas,
+        ),
+        EpochReport::PostAltair(AltairEpochReport {
+            epoch_deltas,
+            slashing_penalties,
+            post_balances,
+            ..
+        }) => events(
+            epoch,
+            &own_validator_indices,
+            epoch_deltas,
+            &slashing_penalties,
+            post_balances,
+            sync_committee_deltas,
+        ),
+    };
+
+    Ok(events)
+}
+
+fn events(
+    epoch: Epoch,
+    own_validator_indices: &HashSet<ValidatorIndex>,
+    epoch_deltas: Vec<impl EpochDeltas>,
+    slashing_penalties: &HashMap<ValidatorIndex, Gwei>,
+    post_balances: Vec<Gwei>,
+    sync_committee_deltas: &HashMap<ValidatorIndex, SyncCommitteeDeltas>,
+) -> Vec<BalanceDeltasEvent> {
+    izip!(0.., epoch_deltas, post_balances)
+        .filter(|(validator_index, _, _)| own_validator_indices.contains(validator_index))
+        .map(|(validator_index, epoch_deltas, post_balance)| {
+            let sync_committee_deltas = sync_committee_deltas
+                .get(&validator_index)
+                .copied()
+                .unwrap_or_default();
+
+            BalanceDeltasEvent {
+                epoch,
+                validator_index,
+                attestation_rewards: epoch_deltas.combined_reward(),
+                attestation_penalties: epoch_deltas.combined_penalty(),
+                sync_committee_rewards: sync_committee_deltas.rewards,
+                sync_committee_penalties: sync_committee_deltas.penalties,
+                slashing_penalty: slashing_penalties
+                    .get(&validator_index)
+                    .copied()
+                    .unwrap_or_default(),
+                post_balance,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use helper_functions::misc;
+    use types::{preset::Minimal, traits::BeaconState as _};
+
+    use super::*;
+
+    #[test]
+    fn missed_attestations_are_reported_as_penalties() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let last_slot = misc::compute_start_slot_at_epoch::<Minimal>(2) - 1;
+
+        combined::process_slots(&config, state.make_mut(), last_slot)?;
+
+        let own_public_keys = HashSet::from([state.validators().get(3)?.pubkey.to_bytes()]);
+        let sync_committee_deltas = HashMap::from([(
+            3,
+            SyncCommitteeDeltas {
+                rewards: 5,
+                penalties: 0,
+            },
+        )]);
+
+        let events = balance_deltas(&config, state, &own_public_keys, &sync_committee_deltas)?;
+
+        assert_eq!(events.len(), 1);
+
+        let event = &events[0];
+
+        assert_eq!(event.epoch, 1);
+        assert_eq!(event.validator_index, 3);
+        assert_eq!(event.attestation_rewards, 0);
+        assert!(event.attestation_penalties > 0);
+        assert_eq!(event.sync_committee_rewards, 5);
+        assert_eq!(event.slashing_penalty, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+// These tests compute duties the same way `Validator` does and check them against captured chains.
+// Nothing is signed, so the parts of the pipeline that depend on signatures are not covered.
+
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use eth2_cache_utils::{holesky, mainnet};
+use fork_choice_store::ValidatorRegistry;
+use helper_functions::{accessors, misc};
+use std_ext::ArcExt as _;
+use transition_functions::combined;
+use typenum::Unsigned as _;
+use types::{
+    config::Config,
+    nonstandard::RelativeEpoch,
+    phase0::consts::{AttestationSubnetCount, GENESIS_SLOT},
+    preset::Mainnet,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
+};
+
+use crate::{own_sync_committee_subscriptions::OwnSyncCommitteeSubscriptions, slot_head::SlotHead};
+
+#[test]
+fn proposer_and_attester_duties_match_mainnet_blocks() -> Result<()> {
+    let config = Arc::new(Config::mainnet());
+    let mut state = mainnet::GENESIS_BEACON_STATE.force().clone_arc();
+    let mut head_block_root = accessors::latest_block_root(state.as_ref());
+    let mut checked_attestations = 0;
+
+    for block in mainnet::BEACON_BLOCKS_UP_TO_SLOT_128.force() {
+        let block_slot = block.message().slot();
+
+        if block_slot == GENESIS_SLOT {
+            continue;
+        }
+
+        // Attestations are usually included in the block right after the slot they are for.
+        // `Validator` would produce them using the head as of that slot.
+        let attested_slot = block_slot - 1;
+
+        if state.slot() < attested_slot {
+            combined::process_slots(&config, state.make_mut(), attested_slot)?;
+        }
+
+        let slot_head = SlotHead {
+            config: config.clone_arc(),
+            beacon_block_root: head_block_root,
+            beacon_state: state.clone_arc(),
+            optimistic: false,
+        };
+
+        for attestation in block.message().body().attestations() {
+            let data = attestation.data;
+
+            // Attestations for other slots or voting for other heads cannot be reproduced here.
+            if data.slot != attested_slot || data.beacon_block_root != head_block_root {
+                continue;
+            }
+
+            let committee = slot_head.beacon_committee(data.index)?;
+
+            assert_eq!(attestation.aggregation_bits.len(), committee.len());
+            assert_eq!(slot_head.attestation_data(data.index), data);
+
+            checked_attestations += 1;
+        }
+
+        combined::process_slots(&config, state.make_mut(), block_slot)?;
+
+        let proposer_slot_head = SlotHead {
+            config: config.clone_arc(),
+            beacon_block_root: head_block_root,
+            beacon_state: state.clone_arc(),
+            optimistic: false,
+        };
+
+        assert_eq!(
+            proposer_slot_head.proposer_index()?,
+            block.message().proposer_index(),
+        );
+
+        combined::state_transition_for_report(&config, state.make_mut(), block)?;
+
+        head_block_root = block.message().hash_tree_root();
+    }
+
+    assert!(checked_attestations > 0);
+
+    Ok(())
+}
+
+#[test]
+fn attestation_subnets_match_mainnet_committee_count() -> Result<()> {
+    let genesis_state = mainnet::GENESIS_BEACON_STATE.force().clone_arc();
+
+    let slot_head = SlotHead {
+        config: Arc::new(Config::mainnet()),
+        beacon_block_root: accessors::latest_block_root(genesis_state.as_ref()),
+        beacon_state: genesis_state,
+        optimistic: false,
+    };
+
+    // Mainnet launched with 21063 validators, which is enough for 5 committees per slot.
+    let committees_per_slot =
+        accessors::get_committee_count_per_slot(&slot_head.beacon_state, RelativeEpoch::Current);
+
+    assert_eq!(committees_per_slot, 5);
+
+    for slot in misc::slots_in_epoch::<Mainnet>(0) {
+        assert_eq!(
+            slot_head.beacon_committees(slot)?.count(),
+            usize::try_from(committees_per_slot)?,
+        );
+
+        for (committee_index, _) in slot_head.beacon_committees(slot)? {
+            assert_eq!(
+                slot_head.subnet_id(slot, committee_index)?,
+                (committees_per_slot * slot + committee_index) % AttestationSubnetCount::U64,
+            );
+        }
+    }
+
+    assert_eq!(slot_head.subnet_id(1, 0)?, 5);
+    assert_eq!(slot_head.subnet_id(12, 4)?, 0);
+    assert_eq!(slot_head.subnet_id(31, 4)?, 31);
+
+    Ok(())
+}
+
+#[test]
+fn sync_committee_duties_match_holesky_sync_committee() -> Result<()> {
+    let state = holesky::CAPELLA_BEACON_STATE.force();
+    let state = state
+        .post_altair()
+        .expect("Capella state should be post-Altair");
+
+    let current_epoch = accessors::get_current_epoch(state);
+    let current_period = misc::sync_committee_period::<Mainnet>(current_epoch);
+    let next_period_start = misc::start_of_sync_committee_period::<Mainnet>(current_period + 1);
+    let sync_committee = state.current_sync_committee();
+
+    let own_public_keys = sync_committee
+        .pubkeys
+        .iter()
+        .take(4)
+        .map(|public_key| public_key.to_bytes())
+        .collect::<HashSet<_>>();
+
+    let validator_registry = ValidatorRegistry::new(state);
+    let mut own_subscriptions = OwnSyncCommitteeSubscriptions::<Mainnet>::default();
+
+    own_subscriptions.build(state, &validator_registry, &own_public_keys);
+
+    let subscriptions = own_subscriptions
+        .take_epoch_subscriptions(current_epoch)
+        .expect("subscriptions for the current period should start in the current epoch");
+
+    assert_eq!(subscriptions.len(), own_public_keys.len());
+
+    for subscription in subscriptions {
+        let public_key = accessors::public_key(state, subscription.validator_index)?.to_bytes();
+
+        assert!(own_public_keys.contains(&public_key));
+        assert_eq!(subscription.until_epoch, next_period_start);
+
+        for position in subscription.sync_committee_indices {
+            assert_eq!(sync_committee.pubkeys[position].to_bytes(), public_key);
+        }
+    }
+
+    Ok(())
+}
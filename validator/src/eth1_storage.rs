@@ -7,15 +7,16 @@ use std::{
     sync::{Arc, RwLockReadGuard, RwLockWriteGuard},
 };
 
-use anyhow::{Context as _, Result};
+use anyhow::{ensure, Context as _, Result};
 use arithmetic::{NonZeroExt as _, U64Ext as _};
-use deposit_tree::DepositTree;
+use deposit_tree::IncrementalDepositTree;
 use eth1::{DepositEvent, Eth1Block, Eth1Chain};
 use helper_functions::misc::compute_timestamp_at_slot;
 use itertools::Itertools as _;
 use log::{error, warn};
 use prometheus_metrics::Metrics;
-use ssz::ContiguousList;
+use ssz::{ContiguousList, H256};
+use strum::AsRefStr;
 use thiserror::Error;
 use typenum::Unsigned as _;
 use types::{
@@ -38,7 +39,14 @@ pub trait Eth1Storage {
     where
         Self: 'a;
 
-    fn finalized_deposit_tree(&self) -> Result<DepositTree>;
+    type DepositTreeRef<'a>: Deref<Target = IncrementalDepositTree>
+    where
+        Self: 'a;
+
+    /// Returns a deposit tree containing all deposits in [`Eth1Storage::unfinalized_blocks`].
+    ///
+    /// Must be called after [`Eth1Storage::unfinalized_blocks`] if both are held at once.
+    fn deposit_tree(&self) -> Result<Self::DepositTreeRef<'_>>;
 
     fn unfinalized_blocks(&self) -> Self::UnfinalizedBlocks<'_>;
 
@@ -51,19 +59,22 @@ pub trait Eth1Storage {
     ) -> Result<()>;
 
     /// [`get_eth1_vote`](https://github.com/ethereum/consensus-specs/blob/v1.3.0/specs/phase0/validator.md#eth1-data)
+    ///
+    /// Deviates from the specification when the Eth1 chain is lagging behind the voting period.
+    /// See [`Eth1VoteStrategy::MajorityOfAllVotes`].
     fn eth1_vote<P: Preset>(
         &self,
         config: &Config,
         metrics: Option<&Arc<Metrics>>,
         state_at_slot: &impl BeaconState<P>,
-    ) -> Result<Eth1Data> {
+    ) -> Result<(Eth1Data, Eth1VoteStrategy)> {
         let _timer = metrics.map(|metrics| metrics.eth1_vote_times.start_timer());
 
         let eth1_data = state_at_slot.eth1_data();
         let period_start = voting_period_start_time(config, state_at_slot);
 
-        let mut finalized_deposit_tree = self.finalized_deposit_tree()?;
-        let mut valid_votes = HashMap::new();
+        let finalized_deposit_tree = *self.deposit_tree()?.finalized();
+        let mut valid_votes = vec![];
 
         features::log!(DebugEth1, "Eth1 Vote Eth1 Data: {eth1_data:?}");
         features::log!(
@@ -73,7 +84,7 @@ pub trait Eth1Storage {
             finalized_deposit_tree.last_added_block_number,
         );
 
-        for (position, vote) in state_at_slot.eth1_data_votes().into_iter().enumerate() {
+        for vote in state_at_slot.eth1_data_votes() {
             let unfinalized_blocks = self.unfinalized_blocks();
 
             let Some(block_position) = unfinalized_blocks
@@ -109,17 +120,12 @@ pub trait Eth1Storage {
                 continue;
             }
 
-            let (count, _) = valid_votes.entry(vote).or_insert((0, position));
-            *count += 1;
+            valid_votes.push(vote);
         }
 
-        if let Some(vote) = valid_votes
-            .into_iter()
-            .max_by_key(|(_, (count, position))| (*count, Reverse(*position)))
-            .map(|(vote, _)| vote)
-        {
+        if let Some(vote) = majority_vote(valid_votes) {
             features::log!(DebugEth1, "Eth1 Vote: {vote:?}");
-            return Ok(*vote);
+            return Ok((vote, Eth1VoteStrategy::MajorityOfValidVotes));
         }
 
         let unfinalized_blocks = self.unfinalized_blocks();
@@ -159,22 +165,42 @@ pub trait Eth1Storage {
                 ..eth1_data
             };
 
-            for block in eth1_blocks_to_catch_up {
-                for DepositEvent { data, index } in block.deposit_events.iter().copied() {
-                    // It's possible for download manager to add deposits to finalized deposit tree on grandine restart
-                    // while deposits are not yet finalized in eth2
-                    if finalized_deposit_tree.deposit_count <= index {
-                        eth1_data.deposit_root =
-                            finalized_deposit_tree.push_and_compute_root(index, data)?;
-                        eth1_data.deposit_count = finalized_deposit_tree.deposit_count;
-                    }
-                }
+            let deposit_tree = self.deposit_tree()?;
+
+            // It's possible for download manager to add deposits to finalized deposit tree on grandine restart
+            // while deposits are not yet finalized in eth2
+            let last_deposit_index = eth1_blocks_to_catch_up
+                .iter()
+                .rev()
+                .find_map(|block| block.deposit_events.last())
+                .map(|deposit_event| deposit_event.index)
+                .filter(|index| deposit_tree.finalized().deposit_count <= *index);
+
+            if let Some(index) = last_deposit_index {
+                eth1_data.deposit_count = index + 1;
+                eth1_data.deposit_root = deposit_tree.root(eth1_data.deposit_count)?;
             }
 
-            return Ok(eth1_data);
+            return Ok((eth1_data, Eth1VoteStrategy::LatestCandidateBlock));
         }
 
-        Ok(eth1_data)
+        let latest_timestamp = unfinalized_blocks.last().map(|block| block.timestamp);
+
+        if is_eth1_chain_lagging(config, latest_timestamp, period_start) {
+            features::log!(
+                DebugEth1,
+                "Eth1 chain is lagging (latest block timestamp: {latest_timestamp:?}, \
+                 voting period start: {period_start})",
+            );
+
+            if let Some(vote) = majority_vote(state_at_slot.eth1_data_votes())
+                .filter(|vote| vote.deposit_count >= eth1_data.deposit_count)
+            {
+                return Ok((vote, Eth1VoteStrategy::MajorityOfAllVotes));
+            }
+        }
+
+        Ok((eth1_data, Eth1VoteStrategy::StateEth1Data))
     }
 
     fn pending_deposits<P: Preset>(
@@ -214,20 +240,8 @@ pub trait Eth1Storage {
             return Ok(ContiguousList::default());
         }
 
-        let finalized_deposit_tree = self.finalized_deposit_tree()?;
-
-        features::log!(
-            DebugEth1,
-            "finalized_deposit_tree.deposit_count: {}",
-            finalized_deposit_tree.deposit_count,
-        );
-        features::log!(
-            DebugEth1,
-            "finalized_deposit_tree.last_added_block_number: {}",
-            finalized_deposit_tree.last_added_block_number,
-        );
-
         let unfinalized_blocks = self.unfinalized_blocks();
+        let deposit_tree = self.deposit_tree()?;
 
         features::log!(
             DebugEth1,
@@ -236,43 +250,50 @@ pub trait Eth1Storage {
             unfinalized_blocks.first().map(|block| block.number),
             unfinalized_blocks.last().map(|block| block.number),
         );
-
-        let mut deposit_events = unfinalized_blocks
-            .iter()
-            .flat_map(|block| block.deposit_events.iter())
-            .take_while(|event| event.index < eth1_data.deposit_count)
-            .peekable();
-
-        let mut deposit_tree = finalized_deposit_tree;
-
-        // This loop is an optimization.
-        // Deposits before the ones that needs proofs can be processed using `DepositTree::push`.
-        // Passing them to `DepositTree::extend_and_construct_proofs` with the rest would also work,
-        // but it would likely be slower and would require a different value of `deposit_indices`.
-        while let Some(DepositEvent { data, index }) = deposit_events
-            .next_if(|event| event.index < eth1_deposit_index)
-            .copied()
-        {
-            deposit_tree.push(index, data)?;
-        }
-
         features::log!(
             DebugEth1,
-            "deposit_tree.deposit_count after catching up: {}",
-            deposit_tree.deposit_count,
+            "deposit_tree.finalized().deposit_count: {}, deposit_tree.deposit_count(): {}",
+            deposit_tree.finalized().deposit_count,
+            deposit_tree.deposit_count(),
+        );
+
+        let deposit_root = deposit_tree
+            .root(eth1_data.deposit_count)
+            .context(Error::NotEnoughDeposits)?;
+
+        // Proofs for deposits from a different deposit contract or Eth1 chain would be rejected
+        // by the state transition function. Checking here gives a more useful error.
+        ensure!(
+            deposit_root == eth1_data.deposit_root,
+            Error::DepositRootMismatch {
+                computed: deposit_root,
+                in_eth1_data: eth1_data.deposit_root,
+            },
         );
 
-        let deposit_data = deposit_events.map(|event| &event.data).collect_vec();
-        let deposit_indices = eth1_deposit_index..eth1_data.deposit_count;
         let proof_indices = eth1_deposit_index..eth1_deposit_index + expected_number_of_deposits;
 
-        features::log!(DebugEth1, "deposit indices: {deposit_indices:?}");
         features::log!(DebugEth1, "proof indices: {proof_indices:?}");
 
-        let deposits = deposit_tree
-            .extend_and_construct_proofs(deposit_data.as_slice(), deposit_indices, proof_indices)
+        let proofs = deposit_tree
+            .construct_proofs(proof_indices.clone(), eth1_data.deposit_count)
             .context(Error::NotEnoughDeposits)?;
 
+        let deposit_data = unfinalized_blocks
+            .iter()
+            .flat_map(|block| block.deposit_events.iter())
+            .filter(|event| proof_indices.contains(&event.index))
+            .map(|event| event.data)
+            .collect_vec();
+
+        ensure!(deposit_data.len() == proofs.len(), Error::NotEnoughDeposits);
+
+        let deposits = proofs
+            .into_iter()
+            .zip(deposit_data)
+            .map(|(proof, data)| Deposit { proof, data })
+            .collect_vec();
+
         features::log!(DebugEth1, "deposits len: {}", deposits.len());
 
         deposits.try_into().map_err(Into::into)
@@ -337,9 +358,13 @@ pub trait Eth1Storage {
 impl Eth1Storage for Eth1Chain {
     type UnfinalizedBlocks<'a> = RwLockReadGuard<'a, Vec<Eth1Block>>;
     type UnfinalizedBlocksMut<'a> = RwLockWriteGuard<'a, Vec<Eth1Block>>;
+    type DepositTreeRef<'a> = RwLockReadGuard<'a, IncrementalDepositTree>;
 
-    fn finalized_deposit_tree(&self) -> Result<DepositTree> {
-        self.load_deposit_tree()
+    fn deposit_tree(&self) -> Result<Self::DepositTreeRef<'_>> {
+        Ok(self
+            .deposit_tree()
+            .read()
+            .expect("deposit tree lock is poisoned"))
     }
 
     fn unfinalized_blocks(&self) -> Self::UnfinalizedBlocks<'_> {
@@ -363,10 +388,32 @@ impl Eth1Storage for Eth1Chain {
     }
 }
 
+/// The way a vote returned by [`Eth1Storage::eth1_vote`] was chosen.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Eth1VoteStrategy {
+    /// The most common vote for a candidate block, as in the specification.
+    MajorityOfValidVotes,
+    /// The latest candidate block, as in the specification.
+    LatestCandidateBlock,
+    /// The most common vote in the state regardless of whether its block is known.
+    ///
+    /// Used when the Eth1 chain has not caught up to the voting period. Validators with a
+    /// synced Eth1 chain are likely to be the ones that cast the existing votes.
+    MajorityOfAllVotes,
+    /// `state.eth1_data`, as in the specification.
+    StateEth1Data,
+}
+
 #[derive(Debug, Error)]
 enum Error {
     #[error("not enough deposits")]
     NotEnoughDeposits,
+    #[error(
+        "computed deposit root {computed:?} does not match \
+         deposit root in Eth1 data {in_eth1_data:?}"
+    )]
+    DepositRootMismatch { computed: H256, in_eth1_data: H256 },
 }
 
 /// [`is_candidate_block`](https://github.com/ethereum/consensus-specs/blob/v1.3.0/specs/phase0/validator.md#eth1-data)
@@ -377,6 +424,33 @@ fn is_candidate_block(config: &Config, eth1_block: &Eth1Block, period_start: Uni
     (low..=high).contains(&period_start)
 }
 
+// Candidate blocks are between `range_width` and `2 * range_width` seconds older than the start of
+// the voting period. If even the latest known block is older than that, the Eth1 chain is behind.
+fn is_eth1_chain_lagging(
+    config: &Config,
+    latest_timestamp: Option<UnixSeconds>,
+    period_start: UnixSeconds,
+) -> bool {
+    let range_width = config.seconds_per_eth1_block * config.eth1_follow_distance;
+
+    latest_timestamp.map_or(true, |timestamp| timestamp + range_width * 2 < period_start)
+}
+
+// Ties are broken in favor of the vote that was cast first, like in `get_eth1_vote`.
+fn majority_vote<'votes>(votes: impl IntoIterator<Item = &'votes Eth1Data>) -> Option<Eth1Data> {
+    let mut counts = HashMap::new();
+
+    for (position, vote) in votes.into_iter().enumerate() {
+        let (count, _) = counts.entry(vote).or_insert((0, position));
+        *count += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(_, (count, position))| (*count, Reverse(*position)))
+        .map(|(vote, _)| *vote)
+}
+
 /// [`voting_period_start_time`](https://github.com/ethereum/consensus-specs/blob/v1.3.0/specs/phase0/validator.md#eth1-data)
 fn voting_period_start_time<P: Preset>(
     config: &Config,
@@ -391,6 +465,7 @@ fn voting_period_start_time<P: Preset>(
 
 #[cfg(test)]
 mod tests {
+    use deposit_tree::DepositTree;
     use std_ext::ArcExt as _;
     use tap::Pipe as _;
     use try_from_iterator::TryFromIterator as _;
@@ -410,9 +485,18 @@ mod tests {
     impl Eth1Storage for TestEth1Storage {
         type UnfinalizedBlocks<'a> = &'a Vec<Eth1Block>;
         type UnfinalizedBlocksMut<'a> = &'a mut Vec<Eth1Block>;
+        type DepositTreeRef<'a> = Box<IncrementalDepositTree>;
+
+        fn deposit_tree(&self) -> Result<Self::DepositTreeRef<'_>> {
+            let mut deposit_tree = IncrementalDepositTree::new(self.finalized_deposit_tree);
 
-        fn finalized_deposit_tree(&self) -> Result<DepositTree> {
-            Ok(self.finalized_deposit_tree)
+            for block in &self.unfinalized_blocks {
+                for DepositEvent { data, index } in block.deposit_events.iter().copied() {
+                    deposit_tree.push(index, data)?;
+                }
+            }
+
+            Ok(Box::new(deposit_tree))
         }
 
         fn unfinalized_blocks(&self) -> Self::UnfinalizedBlocks<'_> {
@@ -685,7 +769,7 @@ mod tests {
         assert_eq!(last_slot, 17);
 
         let state_16 = (first_slot..last_slot).try_fold(state_0, |state, slot| -> Result<_> {
-            let eth1_vote = eth1_storage.eth1_vote(&config, None, &state)?;
+            let (eth1_vote, _) = eth1_storage.eth1_vote(&config, None, &state)?;
             let deposits = eth1_storage.pending_deposits(&state, eth1_vote, None)?;
 
             assert!(deposits.is_empty());
@@ -699,10 +783,12 @@ mod tests {
             Ok(new_state)
         })?;
 
-        let block_17_eth1_vote = eth1_storage.eth1_vote(&config, None, &state_16)?;
+        let (block_17_eth1_vote, block_17_strategy) =
+            eth1_storage.eth1_vote(&config, None, &state_16)?;
         let block_17_deposits =
             eth1_storage.pending_deposits(&state_16, block_17_eth1_vote, None)?;
 
+        assert_eq!(block_17_strategy, Eth1VoteStrategy::MajorityOfValidVotes);
         assert_eq!(block_17_deposits.len(), 1);
 
         let (_, state_17) = factory::block_with_eth1_vote_and_deposits(
@@ -720,4 +806,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn eth1_vote_copies_majority_of_all_votes_if_eth1_chain_is_lagging() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let eth1_storage = TestEth1Storage::default();
+        let [vote_a, vote_b] = votes_after(state.eth1_data());
+
+        *state.make_mut().eth1_data_votes_mut() = [vote_a, vote_b, vote_b].try_into()?;
+
+        assert_eq!(
+            eth1_storage.eth1_vote(&config, None, &state)?,
+            (vote_b, Eth1VoteStrategy::MajorityOfAllVotes),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn eth1_vote_does_not_copy_votes_that_move_deposit_count_back() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let eth1_storage = TestEth1Storage::default();
+        let eth1_data = state.eth1_data();
+
+        let old_vote = Eth1Data {
+            deposit_count: eth1_data.deposit_count - 1,
+            block_hash: ExecutionBlockHash::repeat_byte(1),
+            ..eth1_data
+        };
+
+        *state.make_mut().eth1_data_votes_mut() = [old_vote].try_into()?;
+
+        assert_eq!(
+            eth1_storage.eth1_vote(&config, None, &state)?,
+            (eth1_data, Eth1VoteStrategy::StateEth1Data),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn eth1_vote_ignores_unknown_votes_if_eth1_chain_is_synced() -> Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let [vote_a, vote_b] = votes_after(state.eth1_data());
+
+        // The block is too recent to be a candidate, but it shows the Eth1 chain is synced.
+        let eth1_storage = TestEth1Storage {
+            unfinalized_blocks: vec![Eth1Block {
+                timestamp: state.genesis_time(),
+                ..Eth1Block::default()
+            }],
+            ..TestEth1Storage::default()
+        };
+
+        *state.make_mut().eth1_data_votes_mut() = [vote_a, vote_b].try_into()?;
+
+        assert_eq!(
+            eth1_storage.eth1_vote(&config, None, &state)?,
+            (state.eth1_data(), Eth1VoteStrategy::StateEth1Data),
+        );
+
+        Ok(())
+    }
+
+    fn votes_after(eth1_data: Eth1Data) -> [Eth1Data; 2] {
+        [1, 2].map(|byte| Eth1Data {
+            deposit_count: eth1_data.deposit_count + u64::from(byte),
+            block_hash: ExecutionBlockHash::repeat_byte(byte),
+            ..eth1_data
+        })
+    }
 }
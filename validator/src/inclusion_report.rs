@@ -0,0 +1,109 @@
+use serde::Serialize;
+use types::phase0::primitives::{Slot, ValidatorIndex};
+
+/// What would happen to the contents of operation pools if a block were built right now.
+///
+/// Produced by running the same checks as block production without removing anything from pools.
+#[derive(Debug, Serialize)]
+pub struct BlockInclusionReport {
+    pub slot: Slot,
+    pub attestations: AttestationInclusion,
+    pub proposer_slashings: Vec<OperationInclusion>,
+    pub attester_slashings: Vec<OperationInclusion>,
+    pub voluntary_exits: Vec<OperationInclusion>,
+    pub bls_to_execution_changes: Vec<OperationInclusion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttestationInclusion {
+    /// Aggregates in the pool for the current and previous epochs.
+    pub in_pool: usize,
+    /// Attestations chosen by the packer. They may combine several aggregates from the pool.
+    pub included: usize,
+    /// Aggregates left out because they add fewer new votes than the chosen ones.
+    pub unprofitable: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OperationInclusion {
+    pub validator_indices: Vec<ValidatorIndex>,
+    #[serde(flatten)]
+    pub outcome: InclusionOutcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum InclusionOutcome {
+    Included,
+    /// The operation has already been applied to the state, most likely by an earlier block.
+    AlreadyIncluded,
+    /// The operation conflicts with the node's own validators or other operations in the block.
+    Conflicting {
+        reason: String,
+    },
+    Invalid {
+        reason: String,
+    },
+    /// The operation is valid, but the block already contains the maximum number of them.
+    BlockFull,
+}
+
+impl InclusionOutcome {
+    pub const fn is_included(&self) -> bool {
+        matches!(self, Self::Included)
+    }
+}
+
+/// Applies the per-block limit to outcomes computed for operations in pool order.
+pub fn limit_inclusions(
+    outcomes: impl IntoIterator<Item = OperationInclusion>,
+    max_per_block: usize,
+) -> Vec<OperationInclusion> {
+    let mut included = 0;
+
+    outcomes
+        .into_iter()
+        .map(|mut inclusion| {
+            if inclusion.outcome.is_included() {
+                if included < max_per_block {
+                    included += 1;
+                } else {
+                    inclusion.outcome = InclusionOutcome::BlockFull;
+                }
+            }
+
+            inclusion
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_inclusions_marks_excess_operations_as_block_full() {
+        let outcomes = [
+            InclusionOutcome::Included,
+            InclusionOutcome::AlreadyIncluded,
+            InclusionOutcome::Included,
+            InclusionOutcome::Included,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, outcome)| OperationInclusion {
+            validator_indices: vec![index as ValidatorIndex],
+            outcome,
+        });
+
+        let inclusions = limit_inclusions(outcomes, 2);
+
+        assert!(inclusions[0].outcome.is_included());
+        assert!(matches!(
+            inclusions[1].outcome,
+            InclusionOutcome::AlreadyIncluded,
+        ));
+        assert!(inclusions[2].outcome.is_included());
+        assert!(matches!(inclusions[3].outcome, InclusionOutcome::BlockFull));
+    }
+}
@@ -1,15 +1,33 @@
 pub use crate::{
+    balance_deltas::BalanceDeltasEvent,
+    inclusion_report::{
+        AttestationInclusion, BlockInclusionReport, InclusionOutcome, OperationInclusion,
+    },
     messages::{ApiToValidator, ValidatorToApi, ValidatorToLiveness},
     misc::{ProposerData as ValidatorProposerData, ValidatorBlindedBlock},
+    standby::StandbyStatus,
+    sync_committee_performance::SyncCommitteeTotals,
     validator::{Channels as ValidatorChannels, Validator},
     validator_config::ValidatorConfig,
 };
 
+mod balance_deltas;
 mod eth1_storage;
+mod inclusion_report;
 mod messages;
 mod misc;
 mod own_beacon_committee_subscriptions;
 mod own_sync_committee_subscriptions;
 mod slot_head;
+mod standby;
+mod sync_committee_performance;
 mod validator;
 mod validator_config;
+mod withdrawal_verification;
+
+#[cfg(test)]
+mod duty_tests;
+#[cfg(test)]
+mod simulation;
+#[cfg(test)]
+mod simulation_tests;
@@ -1,33 +1,63 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 
 use anyhow::{Error, Result};
 use bls::{PublicKeyBytes, SignatureBytes};
-use builder_api::unphased::containers::SignedValidatorRegistrationV1;
+use builder_api::{unphased::containers::SignedValidatorRegistrationV1, RelayReport};
 use futures::channel::{mpsc::UnboundedSender, oneshot::Sender};
 use log::warn;
+use request_tracing::TraceContext;
 use types::{
-    altair::containers::SignedContributionAndProof,
+    altair::{containers::SignedContributionAndProof, primitives::SyncCommitteePeriod},
     combined::{
         BeaconBlock, BeaconState, ExecutionPayload, SignedBeaconBlock, SignedBlindedBeaconBlock,
     },
     nonstandard::WithBlobsAndMev,
     phase0::{
         containers::{Attestation, AttesterSlashing, ProposerSlashing, SignedVoluntaryExit},
-        primitives::{Epoch, Slot, H256},
+        primitives::{Epoch, Slot, ValidatorIndex, H256},
     },
     preset::Preset,
 };
 
-use crate::misc::{ProposerData, ValidatorBlindedBlock};
+use crate::{
+    balance_deltas::BalanceDeltasEvent,
+    inclusion_report::BlockInclusionReport,
+    misc::{ProposerData, ValidatorBlindedBlock},
+    standby::StandbyStatus,
+    sync_committee_performance::SyncCommitteeTotals,
+};
 
 pub type BeaconBlockSender<P> = Sender<Result<Option<WithBlobsAndMev<BeaconBlock<P>, P>>>>;
 pub type BlindedBlockSender<P> =
     Sender<Result<Option<WithBlobsAndMev<ValidatorBlindedBlock<P>, P>>>>;
+pub type SyncCommitteePerformanceSender =
+    Sender<BTreeMap<SyncCommitteePeriod, BTreeMap<ValidatorIndex, SyncCommitteeTotals>>>;
 
 pub enum ApiToValidator<P: Preset> {
-    ProduceBeaconBlock(BeaconBlockSender<P>, H256, SignatureBytes, Slot, bool),
-    ProduceBlindedBeaconBlock(BlindedBlockSender<P>, H256, SignatureBytes, Slot, bool),
+    ProduceBeaconBlock(
+        BeaconBlockSender<P>,
+        H256,
+        SignatureBytes,
+        Slot,
+        bool,
+        Option<TraceContext>,
+    ),
+    ProduceBlindedBeaconBlock(
+        BlindedBlockSender<P>,
+        H256,
+        SignatureBytes,
+        Slot,
+        bool,
+        Option<u64>,
+        Option<TraceContext>,
+    ),
+    ActivateFromStandby(Sender<Result<StandbyStatus>>),
     AttesterSlashing(Box<AttesterSlashing<P>>),
+    BlockInclusionReport(Sender<Result<Option<BlockInclusionReport>>>),
+    BuilderRelayReports(Sender<Vec<RelayReport>>),
     ProposerSlashing(Box<ProposerSlashing>),
     PublishSignedBlindedBlock(
         Sender<Option<WithBlobsAndMev<ExecutionPayload<P>, P>>>,
@@ -38,6 +68,8 @@ pub enum ApiToValidator<P: Preset> {
     RequestProposerSlashings(Sender<Vec<ProposerSlashing>>),
     RequestSignedVoluntaryExits(Sender<Vec<SignedVoluntaryExit>>),
     SignedVoluntaryExit(Box<SignedVoluntaryExit>),
+    StandbyStatus(Sender<StandbyStatus>),
+    SyncCommitteePerformance(SyncCommitteePerformanceSender),
     SignedValidatorRegistrations(
         Sender<Vec<(usize, Error)>>,
         Vec<SignedValidatorRegistrationV1>,
@@ -58,6 +90,7 @@ impl<P: Preset> ApiToValidator<P> {
 }
 
 pub enum ValidatorToApi<P: Preset> {
+    BalanceDeltas(BalanceDeltasEvent),
     ContributionAndProof(Box<SignedContributionAndProof<P>>),
     VoluntaryExit(Box<SignedVoluntaryExit>),
 }
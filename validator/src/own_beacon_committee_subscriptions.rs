@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
+use fork_choice_store::ValidatorRegistry;
 use helper_functions::{accessors, misc, predicates, signing::SignForSingleFork as _};
 use log::warn;
 use p2p::BeaconCommitteeSubscription;
@@ -19,6 +20,7 @@ impl OwnBeaconCommitteeSubscriptions {
         config: &Config,
         epoch: Epoch,
         state: &impl BeaconState<P>,
+        validator_registry: &ValidatorRegistry,
         signer: &RwLock<Signer>,
     ) -> Result<Vec<BeaconCommitteeSubscription>> {
         if self
@@ -35,7 +37,7 @@ impl OwnBeaconCommitteeSubscriptions {
             .keys()
             .copied()
             .filter_map(|public_key| {
-                let validator_index = accessors::index_of_public_key(state, public_key)?;
+                let validator_index = validator_registry.index_of_public_key(state, public_key)?;
                 Some((validator_index, public_key))
             })
             .collect::<HashMap<_, _>>();
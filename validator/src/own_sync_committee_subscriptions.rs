@@ -2,6 +2,7 @@ use core::marker::PhantomData;
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use bls::PublicKeyBytes;
+use fork_choice_store::ValidatorRegistry;
 use helper_functions::{accessors, misc};
 use itertools::Itertools as _;
 use p2p::SyncCommitteeSubscription;
@@ -24,6 +25,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
     pub fn build(
         &mut self,
         state: &(impl PostAltairBeaconState<P> + ?Sized),
+        validator_registry: &ValidatorRegistry,
         own_public_keys: &HashSet<PublicKeyBytes>,
     ) {
         let current_epoch = accessors::get_current_epoch(state);
@@ -35,6 +37,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
             let subscriptions = core::iter::repeat(current_epoch)
                 .zip(sync_committee_subscriptions(
                     state,
+                    validator_registry,
                     own_public_keys,
                     state.current_sync_committee(),
                     next_period_start,
@@ -51,6 +54,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
 
             let subscriptions = sync_committee_subscriptions(
                 state,
+                validator_registry,
                 own_public_keys,
                 state.next_sync_committee(),
                 next_period_expiration,
@@ -97,6 +101,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
 
 fn sync_committee_subscriptions<P: Preset>(
     state: &(impl PostAltairBeaconState<P> + ?Sized),
+    validator_registry: &ValidatorRegistry,
     own_public_keys: &HashSet<PublicKeyBytes>,
     sync_committee: &SyncCommittee<P>,
     until_epoch: Epoch,
@@ -108,7 +113,7 @@ fn sync_committee_subscriptions<P: Preset>(
         .filter(|(_, public_key)| own_public_keys.contains(&public_key.to_bytes()))
         .filter_map(|(position, public_key)| {
             Some((
-                accessors::index_of_public_key(state, public_key.to_bytes())?,
+                validator_registry.index_of_public_key(state, public_key.to_bytes())?,
                 position,
             ))
         })
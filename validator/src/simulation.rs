@@ -0,0 +1,467 @@
+use core::ops::Range;
+use std::{collections::VecDeque, sync::Arc};
+
+use anyhow::Result;
+use bls::PublicKeyBytes;
+use clock::{Tick, TickKind};
+use crossbeam_utils::sync::WaitGroup;
+use database::Database;
+use dedicated_executor::DedicatedExecutor;
+use deposit_tree::DepositTree;
+use eth1::{Eth1Chain, Eth1Config};
+use eth1_api::{ApiController, Eth1Api, Eth1ExecutionEngine};
+use eth2_libp2p::GossipId;
+use fork_choice_control::{Controller, MutatorHandle, Storage};
+use fork_choice_store::StoreConfig;
+use futures::{channel::mpsc::UnboundedReceiver, lock::Mutex};
+use itertools::Itertools as _;
+use keymanager::ProposerConfigs;
+use operation_pools::{AttestationAggPool, BlsToExecutionChangePool, SyncCommitteeAggPool};
+use p2p::ValidatorToP2p;
+use reqwest::Client;
+use signer::{KeyOrigin, Signer, Web3SignerConfig};
+use slashing_protection::{SlashingProtector, DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT};
+use std_ext::ArcExt as _;
+use tokio::{sync::RwLock, task::JoinHandle};
+use types::{
+    combined::{BeaconState, SignedBeaconBlock},
+    config::Config,
+    phase0::{
+        consts::GENESIS_SLOT,
+        containers::{Attestation, SignedAggregateAndProof},
+        primitives::{Epoch, Slot, SubnetId, ValidatorIndex, H256},
+    },
+    preset::{Minimal, Preset},
+};
+
+use crate::{
+    validator::{Channels, Validator},
+    validator_config::ValidatorConfig,
+};
+
+pub type NodeIndex = usize;
+
+#[derive(Clone)]
+enum NetworkMessage<P: Preset> {
+    Block(Arc<SignedBeaconBlock<P>>),
+    SingularAttestation(Arc<Attestation<P>>, SubnetId),
+    AggregateAndProof(Box<SignedAggregateAndProof<P>>),
+}
+
+impl<P: Preset> NetworkMessage<P> {
+    fn published(message: ValidatorToP2p<P>) -> Option<Self> {
+        match message {
+            ValidatorToP2p::PublishBeaconBlock(block) => Some(Self::Block(block)),
+            ValidatorToP2p::PublishSingularAttestation(attestation, subnet_id) => {
+                Some(Self::SingularAttestation(attestation, subnet_id))
+            }
+            ValidatorToP2p::PublishAggregateAndProof(aggregate_and_proof) => {
+                Some(Self::AggregateAndProof(aggregate_and_proof))
+            }
+            // Honest validators only publish the messages above before the Altair fork.
+            // Gossip verdicts only affect peer scoring, which the virtual network does not do.
+            _ => None,
+        }
+    }
+}
+
+struct Envelope<P: Preset> {
+    sender: NodeIndex,
+    recipient: NodeIndex,
+    message: NetworkMessage<P>,
+}
+
+// Messages are delivered in the order they were sent.
+// This keeps simulations deterministic and ensures blocks are delivered after their parents.
+//
+// Messages between nodes in different partitions are held until the partition is healed rather
+// than dropped. This stands in for the sync a node would perform after reconnecting.
+struct VirtualNetwork<P: Preset> {
+    in_flight: VecDeque<Envelope<P>>,
+    // The partition each node is in. All nodes are in the same one when the network is healed.
+    partitions: Vec<usize>,
+}
+
+impl<P: Preset> VirtualNetwork<P> {
+    fn can_reach(&self, sender: NodeIndex, recipient: NodeIndex) -> bool {
+        self.partitions[sender] == self.partitions[recipient]
+    }
+
+    fn broadcast(&mut self, sender: NodeIndex, message: &NetworkMessage<P>) {
+        for recipient in (0..self.partitions.len()).filter(|recipient| *recipient != sender) {
+            self.in_flight.push_back(Envelope {
+                sender,
+                recipient,
+                message: message.clone(),
+            });
+        }
+    }
+
+    fn take_deliverable(&mut self) -> Vec<Envelope<P>> {
+        let (deliverable, held) = core::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition::<Vec<_>, _>(|envelope| self.can_reach(envelope.sender, envelope.recipient));
+
+        self.in_flight = held.into();
+
+        deliverable
+    }
+}
+
+// A node with everything needed to perform validator duties.
+// Networking is replaced by `Simulation`, which routes what `Validator` publishes to other nodes.
+struct Node<P: Preset> {
+    controller: ApiController<P, WaitGroup>,
+    validator_to_p2p_rx: UnboundedReceiver<ValidatorToP2p<P>>,
+    validator: JoinHandle<Result<()>>,
+    bls_to_execution_change_pool_service: JoinHandle<Result<()>>,
+    // Keep the `MutatorHandle` around to avoid joining the mutator thread prematurely.
+    #[allow(dead_code)]
+    mutator_handle: MutatorHandle<P, WaitGroup>,
+}
+
+impl<P: Preset> Drop for Node<P> {
+    fn drop(&mut self) {
+        self.validator.abort();
+        self.bls_to_execution_change_pool_service.abort();
+    }
+}
+
+impl<P: Preset> Node<P> {
+    #[allow(clippy::too_many_lines)]
+    fn new(
+        config: &Arc<Config>,
+        genesis_block: &Arc<SignedBeaconBlock<P>>,
+        genesis_state: &Arc<BeaconState<P>>,
+        deposit_tree: DepositTree,
+        validator_indices: Range<ValidatorIndex>,
+    ) -> Result<Self> {
+        let (_, api_to_validator_rx) = futures::channel::mpsc::unbounded();
+        let (execution_service_tx, _) = futures::channel::mpsc::unbounded();
+        let (fc_to_validator_tx, fc_to_validator_rx) = futures::channel::mpsc::unbounded();
+        let (_, p2p_to_validator_rx) = futures::channel::mpsc::unbounded();
+        let (pool_to_api_tx, _) = futures::channel::mpsc::unbounded();
+        let (pool_to_p2p_tx, _) = futures::channel::mpsc::unbounded();
+        let (subnet_service_tx, _) = futures::channel::mpsc::unbounded();
+        let (validator_to_api_tx, _) = futures::channel::mpsc::unbounded();
+        let (validator_to_p2p_tx, validator_to_p2p_rx) = futures::channel::mpsc::unbounded();
+
+        let eth1_config = Arc::new(Eth1Config {
+            default_deposit_tree: Some(deposit_tree),
+            ..Eth1Config::default()
+        });
+
+        let client = Client::new();
+
+        let eth1_chain = Eth1Chain::new(
+            config.clone_arc(),
+            eth1_config.clone_arc(),
+            client.clone(),
+            Database::in_memory(),
+            None,
+            None,
+        )?;
+
+        let eth1_api = Arc::new(Eth1Api::new(
+            config.clone_arc(),
+            client.clone(),
+            eth1_config.eth1_auth.clone_arc(),
+            eth1_config.eth1_rpc_urls.clone(),
+            None,
+            None,
+        ));
+
+        let execution_engine = Arc::new(Eth1ExecutionEngine::new(
+            config.clone_arc(),
+            eth1_api,
+            execution_service_tx,
+        ));
+
+        let (controller, mutator_handle) = Controller::new(
+            config.clone_arc(),
+            StoreConfig::minimal(config),
+            genesis_block.clone_arc(),
+            genesis_state.clone_arc(),
+            Tick::block_proposal(genesis_block),
+            execution_engine.clone_arc(),
+            None,
+            futures::sink::drain(),
+            futures::sink::drain(),
+            futures::sink::drain(),
+            futures::sink::drain(),
+            fc_to_validator_tx,
+            Arc::new(Storage::in_memory(config.clone_arc())),
+            core::iter::empty(),
+        )?;
+
+        let validator_keys = validator_indices
+            .map(interop::secret_key)
+            .map(|secret_key| {
+                let secret_key = Arc::new(secret_key);
+                let public_key = PublicKeyBytes::from(secret_key.to_public_key());
+                (public_key, secret_key, KeyOrigin::Interop)
+            });
+
+        let signer = Signer::new(validator_keys, client, Web3SignerConfig::default(), None);
+
+        let mut slashing_protector =
+            SlashingProtector::in_memory(DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT)?;
+
+        slashing_protector.register_validators(signer.keys().copied())?;
+
+        let validator_config = Arc::new(ValidatorConfig::default());
+
+        let proposer_configs = Arc::new(ProposerConfigs::new_in_memory(
+            validator_config.suggested_fee_recipient,
+            H256::default(),
+        ));
+
+        let dedicated_executor =
+            Arc::new(DedicatedExecutor::new("dedicated-executor", 1, None, None));
+
+        let attestation_agg_pool =
+            AttestationAggPool::new(controller.clone_arc(), dedicated_executor.clone_arc(), None);
+
+        let sync_committee_agg_pool = SyncCommitteeAggPool::new(
+            dedicated_executor,
+            controller.clone_arc(),
+            None,
+            pool_to_p2p_tx.clone(),
+            None,
+        );
+
+        let (bls_to_execution_change_pool, bls_to_execution_change_pool_service) =
+            BlsToExecutionChangePool::new(
+                controller.clone_arc(),
+                pool_to_api_tx,
+                pool_to_p2p_tx,
+                None,
+            );
+
+        let channels = Channels {
+            api_to_validator_rx,
+            fork_choice_rx: fc_to_validator_rx,
+            p2p_tx: validator_to_p2p_tx,
+            p2p_to_validator_rx,
+            slasher_to_validator_rx: None,
+            subnet_service_tx,
+            validator_to_api_tx,
+            validator_to_liveness_tx: None,
+            validator_to_slasher_tx: None,
+        };
+
+        let validator = Validator::new(
+            eth1_chain,
+            validator_config,
+            controller.clone_arc(),
+            execution_engine,
+            attestation_agg_pool,
+            None,
+            proposer_configs,
+            Arc::new(RwLock::new(signer)),
+            Arc::new(Mutex::new(slashing_protector)),
+            sync_committee_agg_pool,
+            bls_to_execution_change_pool,
+            None,
+            channels,
+        );
+
+        Ok(Self {
+            controller,
+            validator_to_p2p_rx,
+            validator: tokio::spawn(validator.run()),
+            bls_to_execution_change_pool_service: tokio::spawn(
+                bls_to_execution_change_pool_service.run(),
+            ),
+            mutator_handle,
+        })
+    }
+
+    fn wait_for_tasks(&self) {
+        assert!(
+            !self.validator.is_finished(),
+            "validator should keep running until the node is dropped",
+        );
+
+        tokio::task::block_in_place(|| self.controller.wait_for_tasks());
+    }
+
+    fn take_published_messages(&mut self) -> Vec<ValidatorToP2p<P>> {
+        core::iter::from_fn(|| self.validator_to_p2p_rx.try_next().ok().flatten()).collect()
+    }
+}
+
+/// Several nodes running in one process, connected by a virtual network and driven by a virtual
+/// clock. Used to test how nodes handle forks and reorgs caused by network conditions.
+///
+/// Each node runs fork choice along with a [`Validator`] and the operation pools it uses.
+/// Blocks, attestations and aggregates are produced by the validators and gossiped through the
+/// virtual network. Nodes are allowed to finish their work after every tick and every round of
+/// message delivery, so the outcome of a simulation depends only on the calls made to it.
+///
+/// The simulation must be run in a multi-threaded Tokio runtime.
+pub struct Simulation<P: Preset> {
+    nodes: Vec<Node<P>>,
+    network: VirtualNetwork<P>,
+    slot: Slot,
+}
+
+impl<P: Preset> Simulation<P> {
+    /// Creates a node for each element of `validator_counts`.
+    /// Validators are assigned to nodes in order of their indices.
+    pub fn new(config: Arc<Config>, validator_counts: &[u64]) -> Result<Self> {
+        let (genesis_state, deposit_tree) = factory::min_genesis_state(&config)?;
+        let genesis_block = Arc::new(genesis::beacon_block(&genesis_state));
+
+        let mut next_validator_index = 0;
+
+        let nodes = validator_counts
+            .iter()
+            .map(|validator_count| {
+                let validator_indices =
+                    next_validator_index..next_validator_index + validator_count;
+
+                next_validator_index = validator_indices.end;
+
+                Node::new(
+                    &config,
+                    &genesis_block,
+                    &genesis_state,
+                    deposit_tree,
+                    validator_indices,
+                )
+            })
+            .collect::<Result<_>>()?;
+
+        let network = VirtualNetwork {
+            in_flight: VecDeque::new(),
+            partitions: vec![0; validator_counts.len()],
+        };
+
+        Ok(Self {
+            nodes,
+            network,
+            slot: GENESIS_SLOT,
+        })
+    }
+
+    #[must_use]
+    pub fn head_block_roots(&self) -> Vec<H256> {
+        self.nodes
+            .iter()
+            .map(|node| node.controller.head_block_root().value)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn finalized_epochs(&self) -> Vec<Epoch> {
+        self.nodes
+            .iter()
+            .map(|node| node.controller.finalized_epoch())
+            .collect()
+    }
+
+    /// Asserts that all nodes have the same head and returns its root.
+    pub fn assert_converged(&self) -> H256 {
+        let head_block_roots = self.head_block_roots();
+
+        head_block_roots
+            .iter()
+            .copied()
+            .all_equal_value()
+            .unwrap_or_else(|_| panic!("nodes should have the same head: {head_block_roots:?}"))
+    }
+
+    /// Splits the network so that nodes can only reach nodes in the same group.
+    /// Nodes not in any of `groups` form a group of their own.
+    pub fn partition(&mut self, groups: &[&[NodeIndex]]) {
+        self.network.partitions.fill(groups.len());
+
+        for (partition, group) in groups.iter().enumerate() {
+            for node_index in group.iter().copied() {
+                self.network.partitions[node_index] = partition;
+            }
+        }
+    }
+
+    /// Reconnects all nodes and delivers the messages held by partitions.
+    pub fn heal(&mut self) {
+        self.network.partitions.fill(0);
+        self.exchange_messages();
+    }
+
+    /// Advances the clocks of all nodes through every tick of each slot up to and including
+    /// `slot`. Validators perform their duties as the ticks come.
+    pub fn advance_to_slot(&mut self, slot: Slot) {
+        while self.slot < slot {
+            self.slot += 1;
+
+            for kind in enum_iterator::all::<TickKind>() {
+                let tick = Tick {
+                    slot: self.slot,
+                    kind,
+                };
+
+                for node in &self.nodes {
+                    node.controller.on_tick(tick);
+                }
+
+                self.exchange_messages();
+            }
+        }
+    }
+
+    // Delivering messages may cause nodes to publish more of them,
+    // e.g., validators attest as soon as they receive a block for the current slot.
+    fn exchange_messages(&mut self) {
+        loop {
+            for node in &self.nodes {
+                node.wait_for_tasks();
+            }
+
+            for (sender, node) in self.nodes.iter_mut().enumerate() {
+                for message in node.take_published_messages() {
+                    if let Some(message) = NetworkMessage::published(message) {
+                        self.network.broadcast(sender, &message);
+                    }
+                }
+            }
+
+            let deliverable = self.network.take_deliverable();
+
+            if deliverable.is_empty() {
+                break;
+            }
+
+            for envelope in deliverable {
+                let controller = &self.nodes[envelope.recipient].controller;
+
+                match envelope.message {
+                    NetworkMessage::Block(block) => {
+                        controller.on_gossip_block(block, GossipId::default());
+                    }
+                    NetworkMessage::SingularAttestation(attestation, subnet_id) => {
+                        controller.on_gossip_singular_attestation(
+                            attestation,
+                            subnet_id,
+                            GossipId::default(),
+                        );
+                    }
+                    NetworkMessage::AggregateAndProof(aggregate_and_proof) => {
+                        controller.on_gossip_aggregate_and_proof(
+                            aggregate_and_proof,
+                            GossipId::default(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Simulation<Minimal> {
+    pub fn minimal(validator_counts: &[u64]) -> Self {
+        Self::new(Arc::new(Config::minimal()), validator_counts)
+            .expect("minimal configuration is valid")
+    }
+}
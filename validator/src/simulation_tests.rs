@@ -0,0 +1,70 @@
+use helper_functions::misc;
+use types::{
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{Epoch, Slot},
+    },
+    preset::Minimal,
+};
+
+use crate::simulation::Simulation;
+
+// The multi-threaded runtime is needed because `Validator` uses `tokio::task::block_in_place`.
+// `tokio::task::block_in_place` panics when called from a `current_thread` runtime.
+#[tokio::test(flavor = "multi_thread")]
+async fn nodes_converge_and_finalize_without_partitions() {
+    let mut simulation = Simulation::minimal(&[16, 24, 24]);
+
+    simulation.advance_to_slot(start_of_epoch(5));
+    simulation.assert_converged();
+
+    // Finality requires attestations to be aggregated and packed into blocks by the pools.
+    for finalized_epoch in simulation.finalized_epochs() {
+        assert!(finalized_epoch > GENESIS_EPOCH);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn minority_partition_reorgs_to_majority_fork_after_healing() {
+    let mut simulation = Simulation::minimal(&[16, 24, 24]);
+
+    simulation.advance_to_slot(1);
+    simulation.assert_converged();
+
+    simulation.partition(&[&[0], &[1, 2]]);
+    simulation.advance_to_slot(start_of_epoch(2));
+
+    let [minority_root, majority_root, other_majority_root] = simulation
+        .head_block_roots()
+        .try_into()
+        .expect("simulation has 3 nodes");
+
+    assert_ne!(minority_root, majority_root);
+    assert_eq!(majority_root, other_majority_root);
+
+    simulation.heal();
+
+    assert_eq!(simulation.assert_converged(), majority_root);
+
+    simulation.advance_to_slot(start_of_epoch(2) + 1);
+    simulation.assert_converged();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulations_are_deterministic() {
+    let run = || {
+        let mut simulation = Simulation::minimal(&[16, 24, 24]);
+
+        simulation.partition(&[&[0], &[1, 2]]);
+        simulation.advance_to_slot(start_of_epoch(1));
+        simulation.heal();
+        simulation.advance_to_slot(start_of_epoch(2));
+        simulation.head_block_roots()
+    };
+
+    assert_eq!(run(), run());
+}
+
+fn start_of_epoch(epoch: Epoch) -> Slot {
+    misc::compute_start_slot_at_epoch::<Minimal>(epoch)
+}
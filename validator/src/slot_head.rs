@@ -19,7 +19,10 @@ use types::{
     combined::BeaconState,
     config::Config,
     nonstandard::{Phase, RelativeEpoch},
-    phase0::primitives::{CommitteeIndex, Epoch, Slot, SubnetId, ValidatorIndex, H256},
+    phase0::{
+        containers::{AttestationData, Checkpoint},
+        primitives::{CommitteeIndex, Epoch, Slot, SubnetId, ValidatorIndex, H256},
+    },
     preset::Preset,
     traits::BeaconState as _,
 };
@@ -85,6 +88,22 @@ impl<P: Preset> SlotHead<P> {
         self.beacon_state.phase() >= Phase::Altair
     }
 
+    #[must_use]
+    pub fn attestation_data(&self, committee_index: CommitteeIndex) -> AttestationData {
+        let target = Checkpoint {
+            epoch: self.current_epoch(),
+            root: accessors::epoch_boundary_block_root(&self.beacon_state, self.beacon_block_root),
+        };
+
+        AttestationData {
+            slot: self.slot(),
+            index: committee_index,
+            beacon_block_root: self.beacon_block_root,
+            source: self.beacon_state.current_justified_checkpoint(),
+            target,
+        }
+    }
+
     pub fn subnet_id(&self, slot: Slot, committee_index: CommitteeIndex) -> Result<SubnetId> {
         let committees_per_slot =
             accessors::get_committee_count_per_slot(&self.beacon_state, RelativeEpoch::Current);
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use types::phase0::primitives::Epoch;
+
+// A standby node taking over duties does not know what the primary signed before it stopped
+// responding. Waiting this many epochs ensures any attestations from the primary target earlier
+// epochs than the ones signed after activation.
+pub const ACTIVATION_DELAY_EPOCHS: u64 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StandbyStatus {
+    Active,
+    Standby { missed_heartbeats: u64 },
+    Activating { signing_from_epoch: Epoch },
+}
+
+/// Tracks whether the validator is allowed to sign in an active/passive redundancy setup.
+///
+/// Duties are tracked regardless of the status so that a standby node can take over immediately.
+#[derive(Clone, Copy)]
+pub struct Standby {
+    status: StandbyStatus,
+    missed_heartbeat_limit: u64,
+}
+
+impl Standby {
+    #[must_use]
+    pub const fn new(standby: bool, missed_heartbeat_limit: u64) -> Self {
+        let status = if standby {
+            StandbyStatus::Standby {
+                missed_heartbeats: 0,
+            }
+        } else {
+            StandbyStatus::Active
+        };
+
+        Self {
+            status,
+            missed_heartbeat_limit,
+        }
+    }
+
+    #[must_use]
+    pub const fn status(self) -> StandbyStatus {
+        self.status
+    }
+
+    #[must_use]
+    pub const fn is_standby(self) -> bool {
+        matches!(self.status, StandbyStatus::Standby { .. })
+    }
+
+    #[must_use]
+    pub const fn may_sign(self, current_epoch: Epoch) -> bool {
+        match self.status {
+            StandbyStatus::Active => true,
+            StandbyStatus::Standby { .. } => false,
+            StandbyStatus::Activating { signing_from_epoch } => signing_from_epoch <= current_epoch,
+        }
+    }
+
+    /// Returns `true` if the validator switched from `Activating` to `Active`.
+    pub fn complete_activation(&mut self, current_epoch: Epoch) -> bool {
+        if let StandbyStatus::Activating { signing_from_epoch } = self.status {
+            if signing_from_epoch <= current_epoch {
+                self.status = StandbyStatus::Active;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns `true` if the primary has missed enough heartbeats for the standby to take over.
+    pub fn record_heartbeat(&mut self, primary_healthy: bool) -> bool {
+        let StandbyStatus::Standby { missed_heartbeats } = &mut self.status else {
+            return false;
+        };
+
+        if primary_healthy {
+            *missed_heartbeats = 0;
+        } else {
+            *missed_heartbeats += 1;
+        }
+
+        *missed_heartbeats >= self.missed_heartbeat_limit
+    }
+
+    /// Does nothing if the validator is already active or activating.
+    pub fn activate(&mut self, signing_from_epoch: Epoch) -> StandbyStatus {
+        if self.is_standby() {
+            self.status = StandbyStatus::Activating { signing_from_epoch };
+        }
+
+        self.status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standby_activates_after_missed_heartbeats() {
+        let mut standby = Standby::new(true, 2);
+
+        assert!(!standby.may_sign(10));
+        assert!(!standby.record_heartbeat(false));
+        assert!(!standby.record_heartbeat(true));
+        assert!(!standby.record_heartbeat(false));
+        assert!(standby.record_heartbeat(false));
+
+        assert_eq!(
+            standby.activate(12),
+            StandbyStatus::Activating {
+                signing_from_epoch: 12,
+            },
+        );
+
+        // Activating again must not move the epoch signing starts from.
+        standby.activate(20);
+
+        assert!(!standby.may_sign(11));
+        assert!(!standby.complete_activation(11));
+        assert!(standby.may_sign(12));
+        assert!(standby.complete_activation(12));
+        assert_eq!(standby.status(), StandbyStatus::Active);
+        assert!(!standby.record_heartbeat(false));
+    }
+
+    #[test]
+    fn active_validator_ignores_heartbeats() {
+        let mut standby = Standby::new(false, 1);
+
+        assert!(standby.may_sign(0));
+        assert!(!standby.record_heartbeat(false));
+        assert_eq!(standby.activate(5), StandbyStatus::Active);
+    }
+}
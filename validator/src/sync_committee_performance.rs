@@ -0,0 +1,275 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bls::PublicKeyBytes;
+use helper_functions::{accessors, misc};
+use itertools::Itertools as _;
+use prometheus_metrics::{Metrics, OperationalCounter};
+use serde::Serialize;
+use types::{
+    altair::primitives::SyncCommitteePeriod,
+    combined::{BeaconState, SignedBeaconBlock},
+    phase0::primitives::{Epoch, Gwei, Slot, ValidatorIndex},
+    preset::Preset,
+    traits::SignedBeaconBlock as _,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct SyncCommitteeOutcome {
+    validator_index: ValidatorIndex,
+    included: bool,
+    delta: Gwei,
+}
+
+#[derive(Default, PartialEq, Eq, Debug, Serialize)]
+pub struct SyncCommitteeTotals {
+    pub included: usize,
+    pub missed: usize,
+    pub rewards: Gwei,
+    pub penalties: Gwei,
+    pub missed_slots: Vec<Slot>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct SyncCommitteeDeltas {
+    pub rewards: Gwei,
+    pub penalties: Gwei,
+}
+
+/// Sync committee participation of own validators as recorded in the sync aggregates of head blocks.
+///
+/// Sync committee messages that are not included in a block leave no other trace on chain,
+/// so this is the only way to notice missed sync committee duties.
+/// Outcomes are kept for the current and previous sync committee periods.
+#[derive(Default)]
+pub struct SyncCommitteePerformance {
+    // Outcomes are keyed by the slot of the block containing the sync aggregate.
+    // A block replacing another one after a reorg overwrites its outcomes instead of adding to them.
+    outcomes: BTreeMap<Slot, Vec<SyncCommitteeOutcome>>,
+}
+
+impl SyncCommitteePerformance {
+    pub fn track_block<P: Preset>(
+        &mut self,
+        block: &SignedBeaconBlock<P>,
+        state: &BeaconState<P>,
+        own_public_keys: &HashSet<PublicKeyBytes>,
+        metrics: Option<&Metrics>,
+    ) {
+        let Some(body) = block.message().body().post_altair() else {
+            return;
+        };
+
+        let Some(post_altair_state) = state.post_altair() else {
+            return;
+        };
+
+        let slot = block.message().slot();
+
+        // Outcomes from blocks in later slots are no longer canonical if they are not descendants
+        // of the new head. Outcomes from descendants will be tracked again when they become head.
+        let already_tracked = self.outcomes.split_off(&slot).contains_key(&slot);
+
+        let participant_reward = accessors::get_sync_committee_participant_reward(state);
+
+        let outcomes = post_altair_state
+            .current_sync_committee()
+            .pubkeys
+            .iter()
+            .zip(body.sync_aggregate().sync_committee_bits)
+            .filter(|(pubkey, _)| own_public_keys.contains(&pubkey.to_bytes()))
+            .filter_map(|(pubkey, included)| {
+                let validator_index =
+                    accessors::index_of_public_key(post_altair_state, pubkey.to_bytes())?;
+
+                Some(SyncCommitteeOutcome {
+                    validator_index,
+                    included,
+                    delta: participant_reward,
+                })
+            })
+            .collect_vec();
+
+        if !already_tracked {
+            if let Some(metrics) = metrics {
+                for outcome in &outcomes {
+                    metrics.register_own_sync_committee_outcome(outcome.included, outcome.delta);
+
+                    if !outcome.included {
+                        metrics.register_operational_counter(OperationalCounter::MissedDuties, 1);
+                    }
+                }
+            }
+        }
+
+        if !outcomes.is_empty() {
+            self.outcomes.insert(slot, outcomes);
+        }
+
+        let period = misc::sync_committee_period::<P>(misc::compute_epoch_at_slot::<P>(slot));
+        let previous_period = period.saturating_sub(1);
+        let oldest_epoch = misc::start_of_sync_committee_period::<P>(previous_period);
+        let oldest_slot = misc::compute_start_slot_at_epoch::<P>(oldest_epoch);
+
+        self.outcomes = self.outcomes.split_off(&oldest_slot);
+    }
+
+    #[must_use]
+    pub fn deltas_in_epoch<P: Preset>(
+        &self,
+        epoch: Epoch,
+    ) -> HashMap<ValidatorIndex, SyncCommitteeDeltas> {
+        let mut deltas = HashMap::<_, SyncCommitteeDeltas>::new();
+
+        for outcome in self
+            .outcomes
+            .range(misc::slots_in_epoch::<P>(epoch))
+            .flat_map(|(_, outcomes)| outcomes)
+        {
+            let validator_deltas = deltas.entry(outcome.validator_index).or_default();
+
+            if outcome.included {
+                validator_deltas.rewards += outcome.delta;
+            } else {
+                validator_deltas.penalties += outcome.delta;
+            }
+        }
+
+        deltas
+    }
+
+    #[must_use]
+    pub fn totals<P: Preset>(
+        &self,
+    ) -> BTreeMap<SyncCommitteePeriod, BTreeMap<ValidatorIndex, SyncCommitteeTotals>> {
+        let mut totals = BTreeMap::<_, BTreeMap<_, SyncCommitteeTotals>>::new();
+
+        for (slot, outcomes) in &self.outcomes {
+            let period = misc::sync_committee_period::<P>(misc::compute_epoch_at_slot::<P>(*slot));
+
+            for outcome in outcomes {
+                let validator_totals = totals
+                    .entry(period)
+                    .or_default()
+                    .entry(outcome.validator_index)
+                    .or_default();
+
+                if outcome.included {
+                    validator_totals.included += 1;
+                    validator_totals.rewards += outcome.delta;
+                } else {
+                    validator_totals.missed += 1;
+                    validator_totals.penalties += outcome.delta;
+
+                    // Validators may occupy multiple positions in the sync committee.
+                    if validator_totals.missed_slots.last() != Some(slot) {
+                        validator_totals.missed_slots.push(*slot);
+                    }
+                }
+            }
+        }
+
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bls::CachedPublicKey;
+    use types::{
+        altair::containers::{
+            BeaconBlock as AltairBeaconBlock, BeaconBlockBody as AltairBeaconBlockBody,
+            SignedBeaconBlock as AltairSignedBeaconBlock, SyncAggregate,
+        },
+        config::Config,
+        nonstandard::Phase,
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn sync_committee_performance_counts_own_validators_once_per_slot() -> Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Altair);
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let committee = state
+            .post_altair()
+            .expect("state should be post-Altair")
+            .current_sync_committee()
+            .pubkeys
+            .iter()
+            .map(CachedPublicKey::to_bytes)
+            .collect_vec();
+
+        let included_pubkey = committee[0];
+        let missed_pubkey = committee
+            .iter()
+            .copied()
+            .find(|pubkey| *pubkey != included_pubkey)
+            .expect("sync committee should contain more than one validator");
+        let own_public_keys = HashSet::from([included_pubkey, missed_pubkey]);
+        let included_index = accessors::index_of_public_key(&*state, included_pubkey)
+            .expect("committee members should be in the registry");
+        let missed_index = accessors::index_of_public_key(&*state, missed_pubkey)
+            .expect("committee members should be in the registry");
+
+        // Validators may appear in the committee more than once.
+        let included_positions = committee
+            .iter()
+            .filter(|pubkey| **pubkey == included_pubkey)
+            .count();
+        let missed_positions = committee
+            .iter()
+            .filter(|pubkey| **pubkey == missed_pubkey)
+            .count();
+
+        let block_at_slot = |slot| {
+            let mut sync_aggregate = SyncAggregate::<Minimal>::default();
+
+            for (position, pubkey) in committee.iter().enumerate() {
+                sync_aggregate
+                    .sync_committee_bits
+                    .set(position, *pubkey != missed_pubkey);
+            }
+
+            SignedBeaconBlock::from(AltairSignedBeaconBlock {
+                message: AltairBeaconBlock {
+                    slot,
+                    body: AltairBeaconBlockBody {
+                        sync_aggregate,
+                        ..AltairBeaconBlockBody::default()
+                    },
+                    ..AltairBeaconBlock::default()
+                },
+                ..AltairSignedBeaconBlock::default()
+            })
+        };
+
+        let mut performance = SyncCommitteePerformance::default();
+
+        performance.track_block(&block_at_slot(1), &state, &own_public_keys, None);
+        performance.track_block(&block_at_slot(2), &state, &own_public_keys, None);
+        // A block replacing the one in slot 2 must not be counted twice.
+        performance.track_block(&block_at_slot(2), &state, &own_public_keys, None);
+
+        let totals = performance.totals::<Minimal>();
+        let period_totals = &totals[&0];
+
+        assert_eq!(
+            period_totals[&included_index].included,
+            2 * included_positions
+        );
+        assert_eq!(period_totals[&included_index].missed, 0);
+        assert_eq!(period_totals[&missed_index].included, 0);
+        assert_eq!(period_totals[&missed_index].missed, 2 * missed_positions);
+        assert_eq!(
+            period_totals[&missed_index].penalties,
+            u64::try_from(2 * missed_positions)?
+                * accessors::get_sync_committee_participant_reward(&*state),
+        );
+        assert_eq!(period_totals[&missed_index].missed_slots, [1, 2]);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 //! <https://github.com/ethereum/consensus-specs/blob/b2f42bf4d79432ee21e2f2b3912ff4bbf7898ada/specs/phase0/validator.md>
 
-use core::ops::ControlFlow;
+use core::{ops::ControlFlow, time::Duration};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error as StdError,
@@ -48,7 +48,7 @@ use operation_pools::{
     SyncCommitteeAggPool,
 };
 use p2p::{P2pToValidator, ToSubnetService, ValidatorToP2p};
-use prometheus_metrics::Metrics;
+use prometheus_metrics::{Metrics, OperationalCounter};
 use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use signer::{Signer, SigningMessage, SigningTriple};
 use slasher::{SlasherToValidator, ValidatorToSlasher};
@@ -74,9 +74,12 @@ use types::{
         },
         primitives::SubcommitteeIndex,
     },
-    bellatrix::containers::{
-        BeaconBlock as BellatrixBeaconBlock, BeaconBlockBody as BellatrixBeaconBlockBody,
-        ExecutionPayload as BellatrixExecutionPayload,
+    bellatrix::{
+        containers::{
+            BeaconBlock as BellatrixBeaconBlock, BeaconBlockBody as BellatrixBeaconBlockBody,
+            ExecutionPayload as BellatrixExecutionPayload,
+        },
+        primitives::Wei,
     },
     capella::containers::{
         BeaconBlock as CapellaBeaconBlock, BeaconBlockBody as CapellaBeaconBlockBody,
@@ -99,7 +102,7 @@ use types::{
         consts::{FAR_FUTURE_EPOCH, GENESIS_EPOCH, GENESIS_SLOT},
         containers::{
             AggregateAndProof, Attestation, AttestationData, AttesterSlashing,
-            BeaconBlock as Phase0BeaconBlock, BeaconBlockBody as Phase0BeaconBlockBody, Checkpoint,
+            BeaconBlock as Phase0BeaconBlock, BeaconBlockBody as Phase0BeaconBlockBody,
             ProposerSlashing, SignedAggregateAndProof, SignedVoluntaryExit,
         },
         primitives::{Epoch, ExecutionAddress, ExecutionBlockHash, Slot, ValidatorIndex, H256},
@@ -111,7 +114,11 @@ use types::{
 };
 
 use crate::{
-    eth1_storage::Eth1Storage as _,
+    balance_deltas,
+    eth1_storage::{Eth1Storage as _, Eth1VoteStrategy},
+    inclusion_report::{
+        self, AttestationInclusion, BlockInclusionReport, InclusionOutcome, OperationInclusion,
+    },
     messages::{
         ApiToValidator, BeaconBlockSender, BlindedBlockSender, ValidatorToApi, ValidatorToLiveness,
     },
@@ -119,22 +126,20 @@ use crate::{
     own_beacon_committee_subscriptions::OwnBeaconCommitteeSubscriptions,
     own_sync_committee_subscriptions::OwnSyncCommitteeSubscriptions,
     slot_head::SlotHead,
+    standby::{Standby, StandbyStatus, ACTIVATION_DELAY_EPOCHS},
+    sync_committee_performance::SyncCommitteePerformance,
     validator_config::ValidatorConfig,
+    withdrawal_verification,
 };
 
 const EPOCHS_TO_KEEP_REGISTERED_VALIDATORS: u64 = 2;
 
-// Some relays reject requests whose bodies are too long.
-// We have 50000 validators in Holesky. Their registrations add up to over 20 MiB.
-//
-// We originally set `MAX_VALIDATORS_PER_REGISTRATION` to 1000.
-// That didn't work because processing registrations for 1000 validators takes around 3 seconds,
-// which happens to be the default timeout for validator registration requests in `mev-boost`.
-const MAX_VALIDATORS_PER_REGISTRATION: usize = 500;
-
 const PAYLOAD_CACHE_SIZE: usize = 20;
 const PAYLOAD_ID_CACHE_SIZE: usize = 10;
 
+// Heartbeats are checked in the validator task. Waiting longer would delay duties.
+const PRIMARY_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Error)]
 enum Error<P: Preset> {
     #[error("self-incriminating attester slashing: {attester_slashing:?}")]
@@ -192,9 +197,14 @@ pub struct Validator<P: Preset, W: Wait> {
     validator_votes: HashMap<Epoch, Vec<ValidatorVote>>,
     builder_api: Option<Arc<BuilderApi>>,
     last_registration_epoch: Option<Epoch>,
+    // Registrations of own validators are only signed again when their contents change.
+    // Relays accept the same signed registration in every submission.
+    own_registrations: Arc<Mutex<HashMap<PublicKeyBytes, (ValidatorRegistrationV1, Signature)>>>,
     proposer_configs: Arc<ProposerConfigs>,
     signer: Arc<RwLock<Signer>>,
     slashing_protector: Arc<Mutex<SlashingProtector>>,
+    standby: Standby,
+    sync_committee_performance: SyncCommitteePerformance,
     slasher_to_validator_rx: Option<UnboundedReceiver<SlasherToValidator<P>>>,
     subnet_service_tx: UnboundedSender<ToSubnetService>,
     prepared_proposers: HashMap<ValidatorIndex, ExecutionAddress>,
@@ -243,6 +253,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             validator_to_slasher_tx,
         } = channels;
 
+        let standby = Standby::new(
+            validator_config.standby,
+            validator_config.primary_missed_heartbeat_limit.get(),
+        );
+
         Self {
             chain_config: controller.chain_config().clone_arc(),
             eth1_chain,
@@ -265,9 +280,12 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             validator_votes: HashMap::new(),
             builder_api,
             last_registration_epoch: None,
+            own_registrations: Arc::default(),
             proposer_configs,
             signer,
             slashing_protector,
+            standby,
+            sync_committee_performance: SyncCommitteePerformance::default(),
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
             slasher_to_validator_rx,
@@ -304,8 +322,17 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         self.eth1_chain.finalize_deposits(finalized_eth1_deposit_index)?;
                     },
                     ValidatorMessage::Head(wait_group, head) => {
+                        let state = self.controller.state_by_chain_link(&head);
+                        let own_public_keys = self.own_public_keys().await;
+
+                        self.sync_committee_performance.track_block(
+                            &head.block,
+                            &state,
+                            &own_public_keys,
+                            self.metrics.as_deref(),
+                        );
+
                         if let Some(validator_to_liveness_tx) = &self.validator_to_liveness_tx {
-                            let state = self.controller.state_by_chain_link(&head);
                             ValidatorToLiveness::Head(head.block.clone_arc(), state).send(validator_to_liveness_tx);
                         }
 
@@ -382,6 +409,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
                 api_message = self.api_to_validator_rx.select_next_some() => {
                     let success = match api_message {
+                        ApiToValidator::ActivateFromStandby(sender) => {
+                            let current_epoch = misc::compute_epoch_at_slot::<P>(self.controller.slot());
+                            let result = self.activate_from_standby(current_epoch).await;
+                            sender.send(result).is_ok()
+                        },
                         ApiToValidator::AttesterSlashing(attester_slashing) => {
                             if self.handle_external_attester_slashing(*attester_slashing.clone())?.is_publishable() {
                                 ValidatorToP2p::PublishAttesterSlashing(attester_slashing).send(&self.p2p_tx);
@@ -389,6 +421,15 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
                             true
                         },
+                        ApiToValidator::BuilderRelayReports(sender) => {
+                            let reports = self
+                                .builder_api
+                                .as_ref()
+                                .map(|builder_api| builder_api.relay_reports())
+                                .unwrap_or_default();
+
+                            sender.send(reports).is_ok()
+                        },
                         ApiToValidator::PublishSignedBlindedBlock(sender, signed_blinded_block) => {
                             let result = self.publish_signed_blinded_block(&signed_blinded_block).await;
                             sender.send(result).is_ok()
@@ -399,14 +440,17 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                             randao_reveal,
                             slot,
                             skip_randao_verification,
+                            trace,
                         ) => {
-                            self.produce_beacon_block(
+                            let produce_beacon_block = self.produce_beacon_block(
                                 sender,
                                 graffiti,
                                 randao_reveal,
                                 slot,
                                 skip_randao_verification,
-                            ).await
+                            );
+
+                            request_tracing::resume(trace, produce_beacon_block).await
                         },
                         ApiToValidator::ProduceBlindedBeaconBlock(
                             sender,
@@ -414,14 +458,19 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                             randao_reveal,
                             slot,
                             skip_randao_verification,
+                            builder_boost_factor,
+                            trace,
                         ) => {
-                            self.produce_blinded_beacon_block(
+                            let produce_blinded_beacon_block = self.produce_blinded_beacon_block(
                                 sender,
                                 graffiti,
                                 randao_reveal,
                                 slot,
                                 skip_randao_verification,
-                            ).await
+                                builder_boost_factor,
+                            );
+
+                            request_tracing::resume(trace, produce_blinded_beacon_block).await
                         },
                         ApiToValidator::ProposerSlashing(proposer_slashing) => {
                             if self.handle_external_proposer_slashing(*proposer_slashing)?.is_publishable() {
@@ -449,6 +498,19 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         ApiToValidator::RequestSignedVoluntaryExits(sender) => {
                             sender.send(self.voluntary_exits.clone()).is_ok()
                         }
+                        ApiToValidator::BlockInclusionReport(sender) => {
+                            let current_slot = self.controller.slot();
+
+                            let result = match self.safe_slot_head(current_slot).await {
+                                Some(slot_head) => self
+                                    .block_inclusion_report(&slot_head)
+                                    .await
+                                    .map(Some),
+                                None => Ok(None),
+                            };
+
+                            sender.send(result).is_ok()
+                        }
                         ApiToValidator::SignedValidatorRegistrations(sender, registrations) => {
                             let (registered_validators, errors): (Vec<_>, Vec<_>) = registrations
                                 .into_iter()
@@ -491,6 +553,12 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
                             true
                         }
+                        ApiToValidator::StandbyStatus(sender) => {
+                            sender.send(self.standby.status()).is_ok()
+                        }
+                        ApiToValidator::SyncCommitteePerformance(sender) => {
+                            sender.send(self.sync_committee_performance.totals::<P>()).is_ok()
+                        }
                         ApiToValidator::SignedContributionsAndProofs(sender, contributions_and_proofs) => {
                             let current_slot = self.controller.slot();
 
@@ -659,6 +727,17 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         let current_epoch = misc::compute_epoch_at_slot::<P>(slot);
 
+        if self.standby.complete_activation(current_epoch) {
+            info!("validator activated from standby; signing from epoch {current_epoch}");
+
+            // Builder registrations are skipped in standby mode.
+            self.last_registration_epoch = None;
+        }
+
+        if kind == TickKind::Propose && self.standby.is_standby() {
+            self.check_primary_heartbeat(current_epoch).await;
+        }
+
         if tick.is_start_of_epoch::<P>() {
             let _timer = self
                 .metrics
@@ -675,6 +754,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 ValidatorToLiveness::Epoch(current_epoch).send(validator_to_liveness_tx);
             }
 
+            if self.validator_config.balance_delta_events {
+                self.spawn_balance_deltas_reporting(current_epoch).await;
+            }
+
             self.process_validator_votes(current_epoch)?;
             self.discard_old_proposer_slashings(current_epoch);
             self.discard_old_registered_validators(current_epoch);
@@ -729,6 +812,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 .await?;
         }
 
+        // Duties are still tracked in standby mode so that signing can start right after activation.
+        let may_sign = self.standby.may_sign(current_epoch);
+
         match kind {
             TickKind::Propose => {
                 let _timer = self
@@ -737,7 +823,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     .map(|metrics| metrics.validator_propose_tick_times.start_timer());
 
                 self.discard_previous_slot_attestations();
-                self.propose(wait_group, &slot_head).await?;
+
+                if may_sign {
+                    self.propose(wait_group, &slot_head).await?;
+                }
+
                 // Sync committee messages and contributions for the previous slot are sometimes
                 // constructed while proposing a block. They must be discarded before the time to
                 // publish new ones comes.
@@ -750,11 +840,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     .as_ref()
                     .map(|metrics| metrics.validator_attest_tick_times.start_timer());
 
-                self.attest_and_start_aggregating(&wait_group, &slot_head)
-                    .await?;
+                if may_sign {
+                    self.attest_and_start_aggregating(&wait_group, &slot_head)
+                        .await?;
 
-                self.publish_sync_committee_messages(&wait_group, &slot_head)
-                    .await?;
+                    self.publish_sync_committee_messages(&wait_group, &slot_head)
+                        .await?;
+                }
             }
             TickKind::Aggregate => {
                 let _timer = self
@@ -762,10 +854,12 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     .as_ref()
                     .map(|metrics| metrics.validator_aggregate_tick_times.start_timer());
 
-                self.publish_aggregates_and_proofs(&wait_group, &slot_head)
-                    .await;
+                if may_sign {
+                    self.publish_aggregates_and_proofs(&wait_group, &slot_head)
+                        .await;
 
-                self.publish_contributions_and_proofs(&slot_head).await;
+                    self.publish_contributions_and_proofs(&slot_head).await;
+                }
 
                 if misc::is_epoch_start::<P>(slot) {
                     let current_epoch = misc::compute_epoch_at_slot::<P>(slot);
@@ -945,6 +1039,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .pipe(Some)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn build_blinded_beacon_block(
         &mut self,
         slot_head: &SlotHead<P>,
@@ -953,6 +1048,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         graffiti: H256,
         execution_payload_header_handle: Option<JoinHandle<Result<Option<SignedBuilderBid<P>>>>>,
         skip_randao_verification: bool,
+        builder_boost_factor: Option<u64>,
     ) -> Result<Option<WithBlobsAndMev<ValidatorBlindedBlock<P>, P>>> {
         let Some(beacon_block) = self
             .build_beacon_block(
@@ -969,15 +1065,40 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         if beacon_block.value.phase() >= Phase::Bellatrix {
             if let Some(header_handle) = execution_payload_header_handle {
-                match header_handle.await? {
+                match request_tracing::in_span("wait_for_builder_bid", header_handle).await? {
                     Ok(Some(response)) => {
                         let blob_kzg_commitments = response.blob_kzg_commitments().cloned();
                         let mev = response.mev();
 
+                        if !prefer_builder_payload(mev, beacon_block.mev, builder_boost_factor) {
+                            info!(
+                                "using local execution payload instead of builder payload \
+                                 (builder value: {mev}, local value: {:?}, \
+                                 builder_boost_factor: {builder_boost_factor:?})",
+                                beacon_block.mev,
+                            );
+
+                            return Ok(Some(beacon_block.map(ValidatorBlindedBlock::BeaconBlock)));
+                        }
+
+                        let execution_payload_header = response.execution_payload_header();
+
+                        if let Err(error) = withdrawal_verification::validate_withdrawals_root(
+                            &slot_head.beacon_state,
+                            &execution_payload_header,
+                        ) {
+                            warn!(
+                                "using local execution payload because builder payload \
+                                 has unexpected withdrawals: {error}",
+                            );
+
+                            return Ok(Some(beacon_block.map(ValidatorBlindedBlock::BeaconBlock)));
+                        }
+
                         if let Some(blinded_block) = self.blinded_block_from_beacon_block(
                             slot_head,
                             beacon_block.value.clone(),
-                            response.execution_payload_header(),
+                            execution_payload_header,
                             blob_kzg_commitments,
                             skip_randao_verification,
                         ) {
@@ -1027,36 +1148,65 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             proofs,
             blobs,
             mev,
-        } = self
-            .local_execution_payload_option(slot_head, proposer_index)
-            .await
-            .map(|value| value.map(Some))
-            .unwrap_or_else(|| WithBlobsAndMev::with_default(None));
+        } = request_tracing::in_span(
+            "local_execution_payload",
+            self.local_execution_payload_option(slot_head, proposer_index),
+        )
+        .await
+        .map(|value| value.map(Some))
+        .unwrap_or_else(|| WithBlobsAndMev::with_default(None));
+
+        if let Some(execution_payload) = execution_payload.as_ref() {
+            if let Err(error) = withdrawal_verification::validate_withdrawals(
+                &slot_head.beacon_state,
+                execution_payload,
+            ) {
+                warn!(
+                    "refusing to build block with execution payload \
+                     that has unexpected withdrawals: {error}",
+                );
+
+                return Ok(None);
+            }
+        }
 
         let blob_kzg_commitments = commitments.unwrap_or_default();
-        let sync_aggregate = self.process_sync_committee_contributions(slot_head).await?;
+
+        let sync_aggregate = request_tracing::in_span(
+            "sync_aggregate",
+            self.process_sync_committee_contributions(slot_head),
+        )
+        .await?;
 
         let bls_to_execution_changes = self
             .prepare_bls_to_execution_changes_for_proposal(slot_head)
             .await;
 
-        let attestations = self
-            .attestation_agg_pool
-            .best_proposable_attestations(slot_head.beacon_state.clone_arc())
-            .await?;
+        let attestations = request_tracing::in_span(
+            "attestations",
+            self.attestation_agg_pool
+                .best_proposable_attestations(slot_head.beacon_state.clone_arc()),
+        )
+        .await?;
 
         let own_public_keys = self.own_public_keys().await;
 
         tokio::task::block_in_place(|| -> Result<_> {
-            let eth1_data = match self.eth1_chain.eth1_vote(
+            let _span = request_tracing::start_span("assemble_block");
+
+            let (mut eth1_data, mut eth1_vote_strategy) = match self.eth1_chain.eth1_vote(
                 &self.chain_config,
                 self.metrics.as_ref(),
                 &slot_head.beacon_state,
             ) {
-                Ok(eth1_data) => eth1_data,
+                Ok(eth1_vote) => eth1_vote,
                 Err(error) => {
                     warn!("{error:?}");
-                    slot_head.beacon_state.eth1_data()
+
+                    (
+                        slot_head.beacon_state.eth1_data(),
+                        Eth1VoteStrategy::StateEth1Data,
+                    )
                 }
             };
 
@@ -1066,12 +1216,46 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 self.metrics.as_ref(),
             ) {
                 Ok(deposits) => deposits,
+                // A vote chosen regardless of whether its block is known may require deposits
+                // that have not been downloaded yet. Vote for `state.eth1_data` instead of
+                // giving up on the proposal.
+                Err(error) if eth1_vote_strategy == Eth1VoteStrategy::MajorityOfAllVotes => {
+                    warn!(
+                        "falling back to state Eth1 data because deposits for \
+                         majority vote {eth1_data:?} are unavailable: {error:?}",
+                    );
+
+                    eth1_data = slot_head.beacon_state.eth1_data();
+                    eth1_vote_strategy = Eth1VoteStrategy::StateEth1Data;
+
+                    match self.eth1_chain.pending_deposits(
+                        &slot_head.beacon_state,
+                        eth1_data,
+                        self.metrics.as_ref(),
+                    ) {
+                        Ok(deposits) => deposits,
+                        Err(error) => {
+                            warn!("{error:?}");
+                            return Ok(None);
+                        }
+                    }
+                }
                 Err(error) => {
                     warn!("{error:?}");
                     return Ok(None);
                 }
             };
 
+            debug!(
+                "chose Eth1 vote for block at slot {} using strategy {}: {eth1_data:?}",
+                slot_head.slot(),
+                eth1_vote_strategy.as_ref(),
+            );
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_eth1_vote_strategy(eth1_vote_strategy.as_ref());
+            }
+
             let slot = slot_head.slot();
             let parent_root = slot_head.beacon_block_root;
 
@@ -1234,7 +1418,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         slot: Slot,
         skip_randao_verification: bool,
     ) -> bool {
-        let Some(slot_head) = self.safe_slot_head(slot).await else {
+        let Some(slot_head) =
+            request_tracing::in_span("safe_slot_head", self.safe_slot_head(slot)).await
+        else {
             return sender.send(Ok(None)).is_ok();
         };
 
@@ -1258,8 +1444,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         randao_reveal: SignatureBytes,
         slot: Slot,
         skip_randao_verification: bool,
+        builder_boost_factor: Option<u64>,
     ) -> bool {
-        let Some(slot_head) = self.safe_slot_head(slot).await else {
+        let Some(slot_head) =
+            request_tracing::in_span("safe_slot_head", self.safe_slot_head(slot)).await
+        else {
             return sender.send(Ok(None)).is_ok();
         };
 
@@ -1269,9 +1458,14 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             return sender.send(Ok(None)).is_ok();
         };
 
-        let public_key = slot_head.public_key(proposer_index);
-        let execution_payload_header_handle =
-            self.get_execution_payload_header(&slot_head, public_key.to_bytes());
+        // A `builder_boost_factor` of 0 means the caller always wants a locally built payload.
+        // There is no point in requesting a header from the builder in that case.
+        let execution_payload_header_handle = if builder_boost_factor == Some(0) {
+            None
+        } else {
+            let public_key = slot_head.public_key(proposer_index);
+            self.get_execution_payload_header(&slot_head, public_key.to_bytes())
+        };
 
         let result = self
             .build_blinded_beacon_block(
@@ -1281,6 +1475,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 graffiti,
                 execution_payload_header_handle,
                 skip_randao_verification,
+                builder_boost_factor,
             )
             .await;
 
@@ -1342,6 +1537,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     public_key.to_bytes(),
                     error,
                 );
+
+                self.register_missed_duty();
+
                 return Ok(());
             }
         };
@@ -1374,6 +1572,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 proposer_index,
                 slot_head.slot(),
             );
+
+            self.register_missed_duty();
+
             return Ok(());
         };
 
@@ -1591,6 +1792,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
             self.attestation_agg_pool
                 .insert_attestation(wait_group.clone(), attestation);
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_operational_counter(OperationalCounter::ProducedAttestations, 1);
+            }
         }
 
         prometheus_metrics::stop_and_record(timer);
@@ -1895,6 +2100,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             return Ok(());
         }
 
+        if !self
+            .standby
+            .may_sign(misc::compute_epoch_at_slot::<P>(last_tick.slot))
+        {
+            return Ok(());
+        }
+
         let slot_head = SlotHead {
             config: self.chain_config.clone_arc(),
             beacon_block_root: head.block_root,
@@ -1935,6 +2147,12 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         self.validator_config.graffiti[index]
     }
 
+    fn register_missed_duty(&self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_operational_counter(OperationalCounter::MissedDuties, 1);
+        }
+    }
+
     async fn own_public_keys(&self) -> HashSet<PublicKeyBytes> {
         self.signer
             .read()
@@ -1955,14 +2173,6 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         let own_public_keys = self.own_public_keys().await;
 
         let (triples, other_data) = tokio::task::block_in_place(|| {
-            let target = Checkpoint {
-                epoch: slot_head.current_epoch(),
-                root: accessors::epoch_boundary_block_root(
-                    &slot_head.beacon_state,
-                    slot_head.beacon_block_root,
-                ),
-            };
-
             let (triples, other_data): (Vec<_>, Vec<_>) = slot_head
                 .beacon_committees(slot_head.slot())?
                 .map(|(committee_index, committee)| {
@@ -1980,13 +2190,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     (committee_index, committee.len(), members)
                 })
                 .flat_map(|(index, size, members)| {
-                    let data = AttestationData {
-                        slot: slot_head.slot(),
-                        index,
-                        beacon_block_root: slot_head.beacon_block_root,
-                        source: slot_head.beacon_state.current_justified_checkpoint(),
-                        target,
-                    };
+                    let data = slot_head.attestation_data(index);
 
                     members.into_iter().map(
                         move |(member_position, validator_index, public_key)| {
@@ -2058,6 +2262,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         state: &(impl PostAltairBeaconState<P> + ?Sized),
     ) -> Result<Vec<SyncCommitteeMember>> {
         let own_public_keys = self.own_public_keys().await;
+        let validator_registry = self.controller.validator_registry();
 
         tokio::task::block_in_place(|| {
             let sync_committee = match relative_epoch {
@@ -2083,7 +2288,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         return None;
                     }
 
-                    let validator_index = accessors::index_of_public_key(state, public_key)?;
+                    let validator_index =
+                        validator_registry.index_of_public_key(state, public_key)?;
+
                     Some((validator_index, public_key))
                 })
                 .sorted_by_key(|(validator_index, _)| *validator_index)
@@ -2477,6 +2684,118 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         Ok(())
     }
 
+    async fn check_primary_heartbeat(&mut self, current_epoch: Epoch) {
+        let Some(primary_url) = self.validator_config.primary_beacon_node_url.clone() else {
+            return;
+        };
+
+        let primary_healthy = match primary_url.join("/eth/v1/node/health") {
+            Ok(url) => {
+                let client = self.signer.read().await.client().clone();
+
+                client
+                    .get(url)
+                    .timeout(PRIMARY_HEARTBEAT_TIMEOUT)
+                    .send()
+                    .await
+                    .is_ok_and(|response| response.status().is_success())
+            }
+            Err(error) => {
+                warn!("invalid primary beacon node URL {primary_url}: {error}");
+                return;
+            }
+        };
+
+        if !primary_healthy {
+            warn!("primary beacon node {primary_url} missed a heartbeat");
+        }
+
+        if self.standby.record_heartbeat(primary_healthy) {
+            warn!("primary beacon node {primary_url} is unresponsive; activating from standby");
+
+            if let Err(error) = self.activate_from_standby(current_epoch).await {
+                error!("failed to activate from standby: {error:?}");
+            }
+        }
+    }
+
+    // The primary node may have signed messages up to the moment it stopped responding.
+    // Signing is delayed until those messages can no longer conflict with new ones.
+    // Messages imported into the slashing protection database delay it further.
+    async fn activate_from_standby(&mut self, current_epoch: Epoch) -> Result<StandbyStatus> {
+        if !self.standby.is_standby() {
+            return Ok(self.standby.status());
+        }
+
+        let latest_signed_epoch = self
+            .slashing_protector
+            .lock()
+            .await
+            .latest_signed_epoch::<P>()?;
+
+        let delayed_epoch = current_epoch + ACTIVATION_DELAY_EPOCHS;
+        let signing_from_epoch =
+            latest_signed_epoch.map_or(delayed_epoch, |epoch| delayed_epoch.max(epoch + 1));
+
+        info!(
+            "activating validator from standby (current epoch: {current_epoch}, \
+             latest signed epoch: {latest_signed_epoch:?}, \
+             signing from epoch: {signing_from_epoch})",
+        );
+
+        Ok(self.standby.activate(signing_from_epoch))
+    }
+
+    // Reporting runs epoch processing on a copy of the state, so it is done off the validator task.
+    async fn spawn_balance_deltas_reporting(&self, current_epoch: Epoch) {
+        if current_epoch == GENESIS_EPOCH {
+            return;
+        }
+
+        let previous_epoch = current_epoch - 1;
+        let own_public_keys = self.own_public_keys().await;
+
+        if own_public_keys.is_empty() {
+            return;
+        }
+
+        let sync_committee_deltas = self
+            .sync_committee_performance
+            .deltas_in_epoch::<P>(previous_epoch);
+
+        let chain_config = self.chain_config.clone_arc();
+        let controller = self.controller.clone_arc();
+        let validator_to_api_tx = self.validator_to_api_tx.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let last_slot = misc::compute_start_slot_at_epoch::<P>(current_epoch) - 1;
+
+            let result = controller.state_at_slot(last_slot).and_then(|state| {
+                let Some(WithStatus { value: state, .. }) = state else {
+                    return Ok(vec![]);
+                };
+
+                balance_deltas::balance_deltas(
+                    &chain_config,
+                    state,
+                    &own_public_keys,
+                    &sync_committee_deltas,
+                )
+            });
+
+            match result {
+                Ok(events) => {
+                    for event in events {
+                        ValidatorToApi::BalanceDeltas(event).send(&validator_to_api_tx);
+                    }
+                }
+                Err(error) => {
+                    warn!("failed to compute balance deltas for epoch {previous_epoch}: {error:?}");
+                }
+            }
+        });
+    }
+
     fn spawn_slashing_protection_pruning(&self, current_epoch: Epoch) {
         let slashing_protector = self.slashing_protector.clone_arc();
         tokio::spawn(async move { slashing_protector.lock().await.prune::<P>(current_epoch) });
@@ -2487,9 +2806,17 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         epoch: Epoch,
         beacon_state: &BeaconState<P>,
     ) {
+        let validator_registry = self.controller.validator_registry();
+
         let subscriptions = match self
             .own_beacon_committee_subscriptions
-            .compute_for_epoch(&self.chain_config, epoch, beacon_state, &self.signer)
+            .compute_for_epoch(
+                &self.chain_config,
+                epoch,
+                beacon_state,
+                &validator_registry,
+                &self.signer,
+            )
             .await
         {
             Ok(subscriptions) => subscriptions,
@@ -2516,9 +2843,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     async fn update_sync_committee_subscriptions(&mut self, beacon_state: &BeaconState<P>) {
         if let Some(post_altair_state) = beacon_state.post_altair() {
             let own_public_keys = self.own_public_keys().await;
+            let validator_registry = self.controller.validator_registry();
 
-            self.own_sync_committee_subscriptions
-                .build(post_altair_state, &own_public_keys);
+            self.own_sync_committee_subscriptions.build(
+                post_altair_state,
+                &validator_registry,
+                &own_public_keys,
+            );
 
             let current_epoch = accessors::get_current_epoch(beacon_state);
 
@@ -2887,6 +3218,192 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             )
     }
 
+    /// Runs the checks done when preparing operations for a block built on `slot_head`
+    /// without removing anything from pools.
+    #[allow(clippy::too_many_lines)]
+    async fn block_inclusion_report(
+        &self,
+        slot_head: &SlotHead<P>,
+    ) -> Result<BlockInclusionReport> {
+        let state = &slot_head.beacon_state;
+        let own_public_keys = self.own_public_keys().await;
+
+        let is_slashed = |validator_index: ValidatorIndex| {
+            state
+                .validators()
+                .get(validator_index)
+                .is_ok_and(|validator| validator.slashed)
+        };
+
+        let outcome = |result: Result<()>, already_included: bool| match result {
+            Ok(()) => InclusionOutcome::Included,
+            Err(_) if already_included => InclusionOutcome::AlreadyIncluded,
+            Err(error) if error.downcast_ref::<Error<P>>().is_some() => {
+                InclusionOutcome::Conflicting {
+                    reason: error.to_string(),
+                }
+            }
+            Err(error) => InclusionOutcome::Invalid {
+                reason: error.to_string(),
+            },
+        };
+
+        let proposer_slashings = self.proposer_slashings.iter().map(|proposer_slashing| {
+            let validator_index = proposer_slashing.signed_header_1.message.proposer_index;
+
+            let result = Self::validate_proposer_slashing_for_block(
+                proposer_slashing,
+                slot_head,
+                &own_public_keys,
+            );
+
+            OperationInclusion {
+                validator_indices: vec![validator_index],
+                outcome: outcome(result, is_slashed(validator_index)),
+            }
+        });
+
+        let proposer_slashings =
+            inclusion_report::limit_inclusions(proposer_slashings, P::MaxProposerSlashings::USIZE);
+
+        let attester_slashings = self.attester_slashings.iter().map(|attester_slashing| {
+            let indices_2 = attester_slashing
+                .attestation_2
+                .attesting_indices
+                .iter()
+                .collect::<HashSet<_>>();
+
+            let validator_indices = attester_slashing
+                .attestation_1
+                .attesting_indices
+                .iter()
+                .filter(|validator_index| indices_2.contains(validator_index))
+                .copied()
+                .collect_vec();
+
+            let already_included =
+                !validator_indices.is_empty() && validator_indices.iter().copied().all(is_slashed);
+
+            let result = Self::validate_attester_slashing_for_block(
+                attester_slashing,
+                slot_head,
+                &own_public_keys,
+            );
+
+            OperationInclusion {
+                validator_indices,
+                outcome: outcome(result, already_included),
+            }
+        });
+
+        let attester_slashings =
+            inclusion_report::limit_inclusions(attester_slashings, P::MaxAttesterSlashings::USIZE);
+
+        // Slashing a validator initiates its exit, so a block cannot also contain an exit for it.
+        let slashed_in_block = proposer_slashings
+            .iter()
+            .chain(&attester_slashings)
+            .filter(|inclusion| inclusion.outcome.is_included())
+            .flat_map(|inclusion| inclusion.validator_indices.iter().copied())
+            .collect::<HashSet<_>>();
+
+        let voluntary_exits = self.voluntary_exits.iter().map(|voluntary_exit| {
+            let validator_index = voluntary_exit.message.validator_index;
+
+            let outcome = if slashed_in_block.contains(&validator_index) {
+                InclusionOutcome::Conflicting {
+                    reason: "validator is slashed in the same block".to_owned(),
+                }
+            } else {
+                let already_exited = state
+                    .validators()
+                    .get(validator_index)
+                    .is_ok_and(|validator| validator.exit_epoch != FAR_FUTURE_EPOCH);
+
+                let result =
+                    unphased::validate_voluntary_exit(&self.chain_config, state, *voluntary_exit);
+
+                outcome(result, already_exited)
+            };
+
+            OperationInclusion {
+                validator_indices: vec![validator_index],
+                outcome,
+            }
+        });
+
+        let voluntary_exits =
+            inclusion_report::limit_inclusions(voluntary_exits, P::MaxVoluntaryExits::USIZE);
+
+        let bls_to_execution_changes = self
+            .bls_to_execution_change_pool
+            .signed_bls_to_execution_changes()
+            .await?
+            .into_iter()
+            .map(|bls_to_execution_change| {
+                let validator_index = bls_to_execution_change.message.validator_index;
+
+                let already_changed = state
+                    .validators()
+                    .get(validator_index)
+                    .is_ok_and(predicates::has_eth1_withdrawal_credential);
+
+                let result = state.post_capella().map_or_else(
+                    || Err(AnyhowError::msg("state is not post-Capella")),
+                    |state| {
+                        capella::validate_bls_to_execution_change(
+                            &self.chain_config,
+                            state,
+                            bls_to_execution_change,
+                        )
+                    },
+                );
+
+                OperationInclusion {
+                    validator_indices: vec![validator_index],
+                    outcome: outcome(result, already_changed),
+                }
+            });
+
+        let bls_to_execution_changes = inclusion_report::limit_inclusions(
+            bls_to_execution_changes,
+            P::MaxBlsToExecutionChanges::USIZE,
+        );
+
+        let current_epoch = slot_head.current_epoch();
+        let mut in_pool = 0;
+
+        for epoch in [current_epoch.saturating_sub(1), current_epoch]
+            .into_iter()
+            .dedup()
+        {
+            in_pool += self
+                .attestation_agg_pool
+                .aggregate_attestations_by_epoch(epoch)
+                .await
+                .len();
+        }
+
+        let included = self
+            .attestation_agg_pool
+            .best_proposable_attestations(state.clone_arc())
+            .await?
+            .len();
+
+        Ok(BlockInclusionReport {
+            slot: slot_head.slot(),
+            attestations: AttestationInclusion {
+                in_pool,
+                included,
+                unprofitable: in_pool.saturating_sub(included),
+            },
+            proposer_slashings,
+            attester_slashings,
+            voluntary_exits,
+            bls_to_execution_changes,
+        })
+    }
+
     async fn process_sync_committee_contributions(
         &self,
         slot_head: &SlotHead<P>,
@@ -2968,7 +3485,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 let slot = slot_head.slot();
                 let parent_hash = state.latest_execution_payload_header().block_hash();
 
-                let handle = tokio::spawn(async move {
+                let get_execution_payload_header = async move {
                     builder_api
                         .get_execution_payload_header::<P>(
                             &chain_config,
@@ -2977,7 +3494,15 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                             public_key,
                         )
                         .await
-                });
+                };
+
+                let handle = tokio::spawn(request_tracing::resume(
+                    request_tracing::current(),
+                    request_tracing::in_span(
+                        "get_execution_payload_header",
+                        get_execution_payload_header,
+                    ),
+                ));
 
                 return Some(handle);
             }
@@ -2987,6 +3512,8 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     }
 
     fn register_validators(&mut self, current_epoch: Epoch) {
+        let sign_registrations = self.standby.may_sign(current_epoch);
+
         if let Some(last_registration_epoch) = self.last_registration_epoch {
             let next_registration_epoch =
                 last_registration_epoch + EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION;
@@ -2998,6 +3525,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         let builder_api = self.builder_api.clone();
         let chain_config = self.chain_config.clone_arc();
+        let own_registrations = self.own_registrations.clone_arc();
         let proposer_configs = self.proposer_configs.clone_arc();
         let signer = self.signer.clone_arc();
         let registered_validators = self.registered_validators.clone();
@@ -3016,6 +3544,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             )
             .send(&subnet_service_tx);
 
+            if !sign_registrations {
+                return Ok(());
+            }
+
             let Some(builder_api) = builder_api.clone() else {
                 return Ok(());
             };
@@ -3034,6 +3566,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 })
                 .collect::<Result<Vec<_>>>()?;
 
+            let mut own_registrations = own_registrations.lock().await;
+
+            let (reused_registrations, registrations) =
+                partition_registrations(&own_registrations, registrations);
+
             let triples = registrations
                 .iter()
                 .map(|registration| SigningTriple {
@@ -3045,9 +3582,22 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
             let signatures = signer.read().await.sign_triples(triples, None).await?;
 
-            let signed_registrations = registrations
+            debug!(
+                "signed {} validator registrations and reused {} signed earlier",
+                registrations.len(),
+                reused_registrations.len(),
+            );
+
+            // Rebuilding the map also discards registrations of validators that were removed.
+            *own_registrations = reused_registrations
                 .into_iter()
-                .zip(signatures)
+                .chain(registrations.into_iter().zip(signatures))
+                .map(|(registration, signature)| (registration.pubkey, (registration, signature)))
+                .collect();
+
+            let signed_registrations = own_registrations
+                .values()
+                .copied()
                 .chain(
                     registered_validators
                         .into_values()
@@ -3059,11 +3609,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 })
                 .collect_vec();
 
-            // Do not submit requests in parallel. Doing so causes all of them to be timed out.
-            for registration in signed_registrations.chunks(MAX_VALIDATORS_PER_REGISTRATION) {
-                if let Err(error) = builder_api.register_validators(registration).await {
-                    warn!("failed to register validator batch: {error}");
-                }
+            drop(own_registrations);
+
+            if let Err(error) = builder_api.register_validators(&signed_registrations).await {
+                warn!("failed to register validators: {error}");
             }
 
             Ok::<_, AnyhowError>(())
@@ -3220,3 +3769,137 @@ fn post_merge_state<P: Preset>(state: &BeaconState<P>) -> Option<&dyn PostBellat
         .post_bellatrix()
         .filter(|state| predicates::is_merge_transition_complete(*state))
 }
+
+// See `builder_boost_factor` in the Beacon Node API specification:
+// <https://ethereum.github.io/beacon-APIs/#/Validator/produceBlockV3>
+//
+// The builder payload is preferred when the boost factor is absent to preserve the behavior of
+// the blinded block endpoint. The local payload value is unknown for pre-Shanghai payloads.
+fn prefer_builder_payload(
+    builder_value: Wei,
+    local_value: Option<Wei>,
+    builder_boost_factor: Option<u64>,
+) -> bool {
+    let Some(builder_boost_factor) = builder_boost_factor else {
+        return true;
+    };
+
+    let Some(local_value) = local_value else {
+        return builder_boost_factor > 0;
+    };
+
+    // A boosted value too large to represent is larger than any local value.
+    let Some(boosted_builder_value) =
+        builder_value.checked_mul(Wei::from_u64(builder_boost_factor))
+    else {
+        return true;
+    };
+
+    boosted_builder_value / Wei::from_u64(100) > local_value
+}
+
+// Signed registrations can be reused as long as the preferences they contain stay the same.
+// Reusing them keeps the old timestamps, which relays accept as long as they do not decrease.
+fn partition_registrations(
+    own_registrations: &HashMap<PublicKeyBytes, (ValidatorRegistrationV1, Signature)>,
+    registrations: impl IntoIterator<Item = ValidatorRegistrationV1>,
+) -> (
+    Vec<(ValidatorRegistrationV1, Signature)>,
+    Vec<ValidatorRegistrationV1>,
+) {
+    registrations.into_iter().partition_map(|registration| {
+        match own_registrations.get(&registration.pubkey) {
+            Some((signed, signature))
+                if signed.fee_recipient == registration.fee_recipient
+                    && signed.gas_limit == registration.gas_limit =>
+            {
+                Either::Left((*signed, *signature))
+            }
+            _ => Either::Right(registration),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn partition_registrations_only_reuses_registrations_with_same_preferences() {
+        let registration =
+            |pubkey_byte, fee_recipient_byte, gas_limit, timestamp| ValidatorRegistrationV1 {
+                fee_recipient: ExecutionAddress::repeat_byte(fee_recipient_byte),
+                gas_limit,
+                timestamp,
+                pubkey: PublicKeyBytes::repeat_byte(pubkey_byte),
+            };
+
+        let own_registrations = [
+            registration(1, 1, 30_000_000, 10),
+            registration(2, 2, 30_000_000, 10),
+            registration(3, 3, 30_000_000, 10),
+        ]
+        .into_iter()
+        .map(|registration| (registration.pubkey, (registration, Signature::default())))
+        .collect();
+
+        let (reused, unsigned) = partition_registrations(
+            &own_registrations,
+            [
+                registration(1, 1, 30_000_000, 20),
+                registration(2, 9, 30_000_000, 20),
+                registration(3, 3, 36_000_000, 20),
+                registration(4, 4, 30_000_000, 20),
+            ],
+        );
+
+        let reused = reused
+            .into_iter()
+            .map(|(registration, _)| (registration.pubkey, registration.timestamp))
+            .collect_vec();
+
+        let unsigned = unsigned
+            .into_iter()
+            .map(|registration| (registration.pubkey, registration.timestamp))
+            .collect_vec();
+
+        assert_eq!(reused, [(PublicKeyBytes::repeat_byte(1), 10)]);
+        assert_eq!(
+            unsigned,
+            [
+                (PublicKeyBytes::repeat_byte(2), 20),
+                (PublicKeyBytes::repeat_byte(3), 20),
+                (PublicKeyBytes::repeat_byte(4), 20),
+            ],
+        );
+    }
+
+    #[test_case(100, None, None => true)]
+    #[test_case(100, Some(200), None => true)]
+    #[test_case(100, Some(200), Some(0) => false)]
+    #[test_case(100, None, Some(0) => false)]
+    #[test_case(100, None, Some(100) => true)]
+    #[test_case(100, Some(99), Some(100) => true)]
+    #[test_case(100, Some(100), Some(100) => false)]
+    #[test_case(100, Some(150), Some(200) => true)]
+    #[test_case(100, Some(50), Some(40) => false)]
+    #[test_case(100, Some(0), Some(u64::MAX) => true)]
+    fn prefer_builder_payload_compares_boosted_builder_value(
+        builder_value: u64,
+        local_value: Option<u64>,
+        builder_boost_factor: Option<u64>,
+    ) -> bool {
+        prefer_builder_payload(
+            Wei::from_u64(builder_value),
+            local_value.map(Wei::from_u64),
+            builder_boost_factor,
+        )
+    }
+
+    #[test]
+    fn prefer_builder_payload_when_boosted_builder_value_overflows() {
+        assert!(prefer_builder_payload(Wei::MAX, Some(Wei::MAX), Some(200)));
+    }
+}
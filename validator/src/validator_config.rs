@@ -1,6 +1,9 @@
+use core::num::NonZeroU64;
 use std::path::PathBuf;
 
 use educe::Educe;
+use nonzero_ext::nonzero;
+use reqwest::Url;
 use types::phase0::primitives::{ExecutionAddress, H256};
 
 #[derive(Clone, Debug, Educe)]
@@ -11,4 +14,10 @@ pub struct ValidatorConfig {
     pub max_empty_slots: u64,
     pub suggested_fee_recipient: ExecutionAddress,
     pub keystore_storage_password_file: Option<PathBuf>,
+    pub standby: bool,
+    pub primary_beacon_node_url: Option<Url>,
+    #[educe(Default(expression = "nonzero!(3_u64)"))]
+    pub primary_missed_heartbeat_limit: NonZeroU64,
+    pub skip_slashing_protection_check: bool,
+    pub balance_delta_events: bool,
 }
@@ -0,0 +1,174 @@
+use anyhow::{ensure, Result};
+use ssz::{ContiguousList, SszHash as _};
+use tap::TryConv as _;
+use thiserror::Error;
+use transition_functions::capella;
+use types::{
+    capella::containers::Withdrawal,
+    combined::{BeaconState, ExecutionPayload, ExecutionPayloadHeader},
+    phase0::primitives::H256,
+    preset::Preset,
+};
+
+/// Checks that withdrawals in `execution_payload` are the ones expected in the next block.
+///
+/// `state` must be the state the block will be built on, advanced to the slot of the block.
+/// The same check is done when processing the block, but a payload from the execution layer
+/// should never be signed if it disagrees with the consensus layer about withdrawals.
+pub fn validate_withdrawals<P: Preset>(
+    state: &BeaconState<P>,
+    execution_payload: &ExecutionPayload<P>,
+) -> Result<()> {
+    let in_payload = match execution_payload {
+        ExecutionPayload::Bellatrix(_) => return Ok(()),
+        ExecutionPayload::Capella(payload) => &payload.withdrawals,
+        ExecutionPayload::Deneb(payload) => &payload.withdrawals,
+    };
+
+    let Some(state) = state.post_capella() else {
+        return Ok(());
+    };
+
+    let expected = capella::get_expected_withdrawals(state)?;
+
+    ensure!(
+        expected.len() == in_payload.len(),
+        Error::CountMismatch {
+            expected: expected.len(),
+            in_payload: in_payload.len(),
+        },
+    );
+
+    for (position, (expected, in_payload)) in expected
+        .into_iter()
+        .zip(in_payload.iter().copied())
+        .enumerate()
+    {
+        ensure!(
+            expected == in_payload,
+            Error::WithdrawalMismatch {
+                position,
+                expected,
+                in_payload,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Like [`validate_withdrawals`], but for payload headers from builders.
+///
+/// Headers only contain the root of the withdrawals list, so the divergence cannot be narrowed
+/// down to individual withdrawals.
+pub fn validate_withdrawals_root<P: Preset>(
+    state: &BeaconState<P>,
+    execution_payload_header: &ExecutionPayloadHeader<P>,
+) -> Result<()> {
+    let in_header = match execution_payload_header {
+        ExecutionPayloadHeader::Bellatrix(_) => return Ok(()),
+        ExecutionPayloadHeader::Capella(header) => header.withdrawals_root,
+        ExecutionPayloadHeader::Deneb(header) => header.withdrawals_root,
+    };
+
+    let Some(state) = state.post_capella() else {
+        return Ok(());
+    };
+
+    let expected = capella::get_expected_withdrawals(state)?
+        .try_conv::<ContiguousList<Withdrawal, P::MaxWithdrawalsPerPayload>>()?
+        .hash_tree_root();
+
+    ensure!(
+        expected == in_header,
+        Error::RootMismatch {
+            expected,
+            in_header,
+        },
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("expected {expected} withdrawals, payload contains {in_payload}")]
+    CountMismatch { expected: usize, in_payload: usize },
+    #[error(
+        "withdrawal at position {position} in payload differs from expected \
+         (expected: {expected:?}, in payload: {in_payload:?})"
+    )]
+    WithdrawalMismatch {
+        position: usize,
+        expected: Withdrawal,
+        in_payload: Withdrawal,
+    },
+    #[error("expected withdrawals root {expected:?}, payload header contains {in_header:?}")]
+    RootMismatch { expected: H256, in_header: H256 },
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        capella::containers::{
+            ExecutionPayload as CapellaExecutionPayload,
+            ExecutionPayloadHeader as CapellaExecutionPayloadHeader,
+        },
+        config::Config,
+        nonstandard::Phase,
+        phase0::primitives::ExecutionAddress,
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn validate_withdrawals_rejects_unexpected_withdrawals() -> Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Capella);
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let payload = ExecutionPayload::from(CapellaExecutionPayload::default());
+
+        validate_withdrawals(&state, &payload)?;
+
+        let payload = ExecutionPayload::from(CapellaExecutionPayload {
+            withdrawals: [Withdrawal {
+                index: 0,
+                validator_index: 0,
+                address: ExecutionAddress::zero(),
+                amount: 1,
+            }]
+            .try_into()?,
+            ..CapellaExecutionPayload::default()
+        });
+
+        validate_withdrawals(&state, &payload)
+            .expect_err("genesis state should not have any withdrawals");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_withdrawals_root_rejects_wrong_root() -> Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Capella);
+        let (state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let withdrawals_root =
+            ContiguousList::<Withdrawal, <Minimal as Preset>::MaxWithdrawalsPerPayload>::default()
+                .hash_tree_root();
+
+        let header = ExecutionPayloadHeader::from(CapellaExecutionPayloadHeader {
+            withdrawals_root,
+            ..CapellaExecutionPayloadHeader::default()
+        });
+
+        validate_withdrawals_root(&state, &header)?;
+
+        let header = ExecutionPayloadHeader::from(CapellaExecutionPayloadHeader::default());
+
+        validate_withdrawals_root(&state, &header)
+            .expect_err("withdrawals root in header should not match");
+
+        Ok(())
+    }
+}